@@ -0,0 +1,308 @@
+use crate::error::ContractError;
+use crate::state::{Config, Stage, CLAIMED, CONFIG, OWNERSHIP_PROPOSAL, STAGES, STAGE_COUNT};
+
+use astroport::asset::token_asset;
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use sha2::Digest;
+use spectrum::helper::assert_valid_addr;
+use spectrum::adapters::asset::AssetEx;
+use spectrum::airdrop::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, IsClaimedResponse, MigrateMsg, QueryMsg, StageResponse,
+};
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            spec_token: deps.api.addr_validate(&msg.spec_token)?,
+        },
+    )?;
+    STAGE_COUNT.save(deps.storage, &0u64)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Claim { stage, amount, proof } => claim(deps, env, info, stage, amount, proof),
+        ExecuteMsg::Clawback { stage } => clawback(deps, env, info, stage),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// If the template is not found in the received message, then a [`ContractError`] is returned,
+/// otherwise returns a [`Response`] with the specified attributes if the operation was successful
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::RegisterStage { merkle_root, expiration } => {
+            // only the owner can airdrop SPEC
+            if info.sender != config.spec_token || cw20_msg.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            register_stage(deps, env, merkle_root, expiration, cw20_msg.amount)
+        }
+    }
+}
+
+/// ## Description
+/// Registers a stage airdropping the SPEC to the leaves of the merkle root.
+fn register_stage(
+    deps: DepsMut,
+    env: Env,
+    merkle_root: String,
+    expiration: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let mut root_buf = [0u8; 32];
+    hex::decode_to_slice(&merkle_root, &mut root_buf)
+        .map_err(|_| ContractError::InvalidMerkleRoot {})?;
+    if expiration <= env.block.time.seconds() {
+        return Err(ContractError::StageExpired { expiration });
+    }
+
+    let stage = STAGE_COUNT.load(deps.storage)? + 1;
+    STAGE_COUNT.save(deps.storage, &stage)?;
+    STAGES.save(
+        deps.storage,
+        stage,
+        &Stage {
+            merkle_root: merkle_root.clone(),
+            expiration,
+            total_amount: amount,
+            claimed_amount: Uint128::zero(),
+            clawed_back: false,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_stage"),
+        attr("stage", stage.to_string()),
+        attr("merkle_root", merkle_root),
+        attr("amount", amount),
+    ]))
+}
+
+/// ## Description
+/// Verifies the leaf of the address and the amount against the merkle root.
+fn verify_proof(
+    merkle_root: &str,
+    address: &str,
+    amount: Uint128,
+    proof: &[String],
+) -> Result<(), ContractError> {
+    let leaf = format!("{}{}", address, amount);
+    let mut hash: [u8; 32] = sha2::Sha256::digest(leaf.as_bytes()).into();
+    for sibling in proof {
+        let mut sibling_buf = [0u8; 32];
+        hex::decode_to_slice(sibling, &mut sibling_buf)
+            .map_err(|_| ContractError::InvalidProof {})?;
+        let mut pair = [hash, sibling_buf];
+        pair.sort_unstable();
+        hash = sha2::Sha256::digest(&pair.concat()).into();
+    }
+
+    let mut root_buf = [0u8; 32];
+    hex::decode_to_slice(merkle_root, &mut root_buf)
+        .map_err(|_| ContractError::InvalidMerkleRoot {})?;
+    if hash != root_buf {
+        return Err(ContractError::InvalidProof {});
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Claims the SPEC airdropped to the sender in the stage with the merkle proof.
+fn claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage_id: u64,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut stage = STAGES.load(deps.storage, stage_id)?;
+    if env.block.time.seconds() >= stage.expiration {
+        return Err(ContractError::StageExpired { expiration: stage.expiration });
+    }
+    if CLAIMED.has(deps.storage, (stage_id, &info.sender)) {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+    verify_proof(&stage.merkle_root, info.sender.as_str(), amount, &proof)?;
+
+    stage.claimed_amount += amount;
+    if stage.claimed_amount > stage.total_amount {
+        return Err(ContractError::InvalidProof {});
+    }
+    STAGES.save(deps.storage, stage_id, &stage)?;
+    CLAIMED.save(deps.storage, (stage_id, &info.sender), &true)?;
+
+    Ok(Response::new()
+        .add_message(token_asset(config.spec_token, amount).transfer_msg(&info.sender)?)
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("stage", stage_id.to_string()),
+            attr("address", info.sender),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Sends the SPEC not claimed before the stage expiration to the owner. Only the owner can
+/// execute this.
+fn clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stage = STAGES.load(deps.storage, stage_id)?;
+    if env.block.time.seconds() < stage.expiration {
+        return Err(ContractError::StageNotExpired { expiration: stage.expiration });
+    }
+    if stage.clawed_back {
+        return Err(ContractError::AlreadyClawedBack {});
+    }
+    stage.clawed_back = true;
+    STAGES.save(deps.storage, stage_id, &stage)?;
+
+    let amount = stage.total_amount.checked_sub(stage.claimed_amount)?;
+    let mut response = Response::new();
+    if !amount.is_zero() {
+        response = response
+            .add_message(token_asset(config.spec_token, amount).transfer_msg(&config.owner)?);
+    }
+
+    Ok(response.add_attributes(vec![
+        attr("action", "clawback"),
+        attr("stage", stage_id.to_string()),
+        attr("amount", amount),
+    ]))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Stage { stage } => to_binary(&query_stage(deps, stage)?),
+        QueryMsg::IsClaimed { stage, address } => to_binary(&query_is_claimed(deps, stage, address)?),
+    }
+}
+
+/// ## Description
+/// Returns the stage.
+fn query_stage(deps: Deps, stage_id: u64) -> StdResult<StageResponse> {
+    let stage = STAGES.load(deps.storage, stage_id)?;
+
+    Ok(StageResponse {
+        stage: stage_id,
+        merkle_root: stage.merkle_root,
+        expiration: stage.expiration,
+        total_amount: stage.total_amount,
+        claimed_amount: stage.claimed_amount,
+        clawed_back: stage.clawed_back,
+    })
+}
+
+/// ## Description
+/// Returns whether the address has claimed in the stage.
+fn query_is_claimed(deps: Deps, stage_id: u64, address: String) -> StdResult<IsClaimedResponse> {
+    let is_claimed = CLAIMED.has(deps.storage, (stage_id, &deps.api.addr_validate(&address)?));
+
+    Ok(IsClaimedResponse { is_claimed })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored owner and SPEC token addresses.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.spec_token, "spec_token")?;
+
+    Ok(())
+}
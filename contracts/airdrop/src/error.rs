@@ -0,0 +1,40 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes airdrop contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Invalid merkle root")]
+    InvalidMerkleRoot {},
+
+    #[error("Invalid merkle proof")]
+    InvalidProof {},
+
+    #[error("Already claimed")]
+    AlreadyClaimed {},
+
+    #[error("Stage expired at {expiration}")]
+    StageExpired { expiration: u64 },
+
+    #[error("Stage does not expire until {expiration}")]
+    StageNotExpired { expiration: u64 },
+
+    #[error("Stage is already clawed back")]
+    AlreadyClawedBack {},
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
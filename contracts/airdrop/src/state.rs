@@ -0,0 +1,44 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores the main parameter for the airdrop contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to register stages and claw back unclaimed SPEC
+    pub owner: Addr,
+    /// The SPEC token contract address
+    pub spec_token: Addr,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// This structure stores an airdrop stage.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Stage {
+    /// The hex encoded merkle root
+    pub merkle_root: String,
+    /// The block time in seconds the stage expires
+    pub expiration: u64,
+    /// The SPEC airdropped in the stage
+    pub total_amount: Uint128,
+    /// The SPEC claimed in the stage
+    pub claimed_amount: Uint128,
+    /// Whether the unclaimed SPEC was sent back to the owner
+    pub clawed_back: bool,
+}
+
+/// Stores the number of stages registered, used as the id of the latest stage
+pub const STAGE_COUNT: Item<u64> = Item::new("stage_count");
+
+/// Stores the stages by id
+pub const STAGES: Map<u64, Stage> = Map::new("stages");
+
+/// Stores the addresses claimed in each stage
+pub const CLAIMED: Map<(u64, &Addr), bool> = Map::new("claimed");
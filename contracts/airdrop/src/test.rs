@@ -0,0 +1,250 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, CosmosMsg, Env, OwnedDeps, Response, StdError, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use sha2::Digest;
+use spectrum::airdrop::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, IsClaimedResponse, QueryMsg, StageResponse,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::Config;
+
+const OWNER: &str = "owner";
+const SPEC_TOKEN: &str = "spec_token";
+const USER_1: &str = "user_1";
+const USER_2: &str = "user_2";
+const USER_3: &str = "user_3";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    register_stage(&mut deps)?;
+    claim(&mut deps)?;
+    clawback(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn leaf(address: &str, amount: u128) -> [u8; 32] {
+    sha2::Sha256::digest(format!("{}{}", address, amount).as_bytes()).into()
+}
+
+fn hash_pair(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let mut pair = [a, b];
+    pair.sort_unstable();
+    sha2::Sha256::digest(&pair.concat()).into()
+}
+
+/// Returns the merkle root of USER_1 100, USER_2 200 and USER_3 300
+fn merkle_root() -> String {
+    hex::encode(hash_pair(hash_pair(leaf(USER_1, 100), leaf(USER_2, 200)), leaf(USER_3, 300)))
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> Result<CosmosMsg, ContractError> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: SPEC_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn register_msg(sender: &str, amount: u128, merkle_root: String) -> Result<ExecuteMsg, ContractError> {
+    Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::RegisterStage {
+            merkle_root,
+            expiration: 10000,
+        })?,
+    }))
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(OWNER, &[]);
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        spec_token: SPEC_TOKEN.to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(OWNER),
+            spec_token: Addr::unchecked(SPEC_TOKEN),
+        }
+    );
+
+    Ok(())
+}
+
+fn register_stage(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // only the owner can airdrop SPEC
+    let info = mock_info("token", &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 600, merkle_root())?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(USER_1, 600, merkle_root())?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 600, "root".to_string())?);
+    assert_error(res, "Invalid merkle root");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(10000), info, register_msg(OWNER, 600, merkle_root())?);
+    assert_error(res, "Stage expired at 10000");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 600, merkle_root())?)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_stage"),
+            attr("stage", "1"),
+            attr("merkle_root", merkle_root()),
+            attr("amount", "600"),
+        ]
+    );
+
+    Ok(())
+}
+
+fn claim(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let proof = vec![hex::encode(leaf(USER_2, 200)), hex::encode(leaf(USER_3, 300))];
+
+    // the amount must match the leaf
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Claim { stage: 1, amount: Uint128::from(101u128), proof: proof.clone() };
+    let res = execute(deps.as_mut(), env_at(0), info, msg);
+    assert_error(res, "Invalid merkle proof");
+
+    // the proof is bound to the sender
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::Claim { stage: 1, amount: Uint128::from(100u128), proof: proof.clone() };
+    let res = execute(deps.as_mut(), env_at(0), info, msg);
+    assert_error(res, "Invalid merkle proof");
+
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Claim { stage: 1, amount: Uint128::from(100u128), proof: proof.clone() };
+    let res = execute(deps.as_mut(), env_at(0), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [transfer_msg(USER_1, 100)?]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim"),
+            attr("stage", "1"),
+            attr("address", USER_1),
+            attr("amount", "100"),
+        ]
+    );
+
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Claim { stage: 1, amount: Uint128::from(100u128), proof };
+    let res = execute(deps.as_mut(), env_at(0), info, msg);
+    assert_error(res, "Already claimed");
+
+    let info = mock_info(USER_3, &[]);
+    let proof = vec![hex::encode(hash_pair(leaf(USER_1, 100), leaf(USER_2, 200)))];
+    let msg = ExecuteMsg::Claim { stage: 1, amount: Uint128::from(300u128), proof };
+    execute(deps.as_mut(), env_at(0), info, msg)?;
+
+    let res: IsClaimedResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::IsClaimed { stage: 1, address: USER_3.to_string() },
+    )?)?;
+    assert!(res.is_claimed);
+    let res: IsClaimedResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::IsClaimed { stage: 1, address: USER_2.to_string() },
+    )?)?;
+    assert!(!res.is_claimed);
+
+    // cannot claim after the expiration
+    let info = mock_info(USER_2, &[]);
+    let proof = vec![hex::encode(leaf(USER_1, 100)), hex::encode(leaf(USER_3, 300))];
+    let msg = ExecuteMsg::Claim { stage: 1, amount: Uint128::from(200u128), proof };
+    let res = execute(deps.as_mut(), env_at(10000), info, msg);
+    assert_error(res, "Stage expired at 10000");
+
+    Ok(())
+}
+
+fn clawback(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(10000), info, ExecuteMsg::Clawback { stage: 1 });
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env_at(9999), info, ExecuteMsg::Clawback { stage: 1 });
+    assert_error(res, "Stage does not expire until 10000");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env_at(10000), info, ExecuteMsg::Clawback { stage: 1 })?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [transfer_msg(OWNER, 200)?]
+    );
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env_at(10000), info, ExecuteMsg::Clawback { stage: 1 });
+    assert_error(res, "Stage is already clawed back");
+
+    let res: StageResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Stage { stage: 1 })?)?;
+    assert_eq!(
+        res,
+        StageResponse {
+            stage: 1,
+            merkle_root: merkle_root(),
+            expiration: 10000,
+            total_amount: Uint128::from(600u128),
+            claimed_amount: Uint128::from(400u128),
+            clawed_back: true,
+        }
+    );
+
+    Ok(())
+}
@@ -3,8 +3,12 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use spectrum::astroport_farm::{
-    InstantiateMsg, CallbackMsg, Cw20HookMsg, ExecuteMsg, QueryMsg, RewardInfoResponse,
+    InstantiateMsg, MigrateMsg, CallbackMsg, ClaimsResponse, Cw20HookMsg, ExecuteMsg, QueryMsg,
+    ReferralInfoResponse, RewardInfoResponse, SharePriceHistoryResponse, StakeInfoResponse, StateResponse,
+    UserInfoResponse,
 };
+use IDC_astroport_farm::state::Config;
+use spectrum::vault::ExchangeRateResponse;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,4 +22,13 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(RewardInfoResponse), &out_dir);
+    export_schema(&schema_for!(UserInfoResponse), &out_dir);
+    export_schema(&schema_for!(ClaimsResponse), &out_dir);
+    export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(ExchangeRateResponse), &out_dir);
+    export_schema(&schema_for!(SharePriceHistoryResponse), &out_dir);
+    export_schema(&schema_for!(StakeInfoResponse), &out_dir);
+    export_schema(&schema_for!(ReferralInfoResponse), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
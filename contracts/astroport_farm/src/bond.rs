@@ -1,29 +1,50 @@
-use astroport::asset::{Asset, token_asset};
-use astroport::querier::query_token_balance;
-use cosmwasm_std::{attr, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Coin, Decimal};
+use astroport::asset::{determine_asset_info, is_token_factory_denom, native_asset, Asset, AssetInfo, AssetInfoExt, token_asset};
+use cosmwasm_std::{attr, Addr, CosmosMsg, CustomQuery, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, Coin, Decimal};
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
-use crate::state::{ScalingOperation, CONFIG, REWARD, STATE, Config};
+use crate::cw20::{DEFAULT_LIMIT, MAX_LIMIT};
+use crate::state::{ScalingOperation, CLAIMS, CONFIG, REFERRAL_FEES, REWARD, SHARE_PRICE_CHECKPOINTS, STATE, Config};
 
 use cw20::{Expiration};
 
 use spectrum::adapters::asset::AssetEx;
-use spectrum::astroport_farm::{RewardInfoResponse, RewardInfoResponseItem, CallbackMsg};
+use spectrum::adapters::pair::PairAdapter;
+use spectrum::pause::assert_not_paused;
+use spectrum::astroport_farm::{
+    CallbackMsg, ClaimInfo, ClaimsResponse, ReferralInfoResponse, RewardInfoResponse, RewardInfoResponseItem, SharePriceCheckpoint,
+    SharePriceHistoryResponse, StakeInfoResponse, UserInfoResponse,
+};
 use spectrum::helper::{ScalingUint128};
+use spectrum::querier::query_asset_balance;
+
+/// The shares of the first bond locked in the farm forever
+const MINIMUM_INITIAL_BOND: Uint128 = Uint128::new(1000);
 
 /// ## Description
-/// Send assets to compound proxy to create LP token and bond received LP token on behalf of sender.
-pub fn bond_assets(
-    deps: DepsMut,
+/// Send assets to compound proxy to create LP token and bond received LP token on behalf of the
+/// staker, or the sender if not set.
+#[allow(clippy::too_many_arguments)]
+pub fn bond_assets<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     assets: Vec<Asset>,
     minimum_receive: Option<Uint128>,
     no_swap: Option<bool>,
     slippage_tolerance: Option<Decimal>,
+    staker_addr: Option<String>,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    let staker_addr = staker_addr
+        .map(|staker_addr| deps.api.addr_validate(&staker_addr))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+
     let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut funds: Vec<Coin> = vec![];
@@ -45,37 +66,131 @@ pub fn bond_assets(
         }
     }
 
-    let compound = config.compound_proxy.compound_msg(assets, funds, no_swap, slippage_tolerance)?;
-    messages.push(compound);
+    messages.extend(compound_and_bond_msgs(
+        deps.as_ref(),
+        &env,
+        &config,
+        assets,
+        funds,
+        info.sender,
+        staker_addr.clone(),
+        referrer,
+        minimum_receive,
+        no_swap,
+        slippage_tolerance,
+    )?);
 
-    let prev_balance = query_token_balance(&deps.querier, staking_token, &env.contract.address)?;
-    messages.push(
-        CallbackMsg::BondTo {
-            to: info.sender,
-            prev_balance,
-            minimum_receive,
-        }
-        .into_cosmos_msg(&env.contract.address)?,
-    );
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "bond_assets"),
+            attr("staker_addr", staker_addr),
+        ]))
+}
+
+/// ## Description
+/// Send a single cw20 asset received by the contract to compound proxy and bond received LP token on behalf of the staker.
+#[allow(clippy::too_many_arguments)]
+pub fn bond_asset<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    sender_addr: String,
+    staker_addr: Option<String>,
+    amount: Uint128,
+    minimum_receive: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    let sender_addr = deps.api.addr_validate(&sender_addr)?;
+    let staker_addr = staker_addr
+        .map(|staker_addr| deps.api.addr_validate(&staker_addr))
+        .transpose()?
+        .unwrap_or_else(|| sender_addr.clone());
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // LP token is bonded directly
+    if config.liquidity_token == info.sender {
+        return Err(ContractError::InvalidMessage {});
+    }
+
+    let asset = token_asset(info.sender, amount);
+    let mut messages: Vec<CosmosMsg> = vec![asset.increase_allowance_msg(
+        config.compound_proxy.0.to_string(),
+        Some(Expiration::AtHeight(env.block.height + 1)),
+    )?];
+    messages.extend(compound_and_bond_msgs(
+        deps.as_ref(),
+        &env,
+        &config,
+        vec![asset],
+        vec![],
+        sender_addr,
+        staker_addr.clone(),
+        referrer,
+        minimum_receive,
+        None,
+        slippage_tolerance,
+    )?);
 
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("action", "bond_assets"))
+        .add_attributes(vec![
+            attr("action", "bond_asset"),
+            attr("staker_addr", staker_addr),
+        ]))
+}
+
+/// Creates the messages sending assets to compound proxy and bonding the LP token received
+#[allow(clippy::too_many_arguments)]
+fn compound_and_bond_msgs<C: CustomQuery>(
+    deps: Deps<C>,
+    env: &Env,
+    config: &Config,
+    assets: Vec<Asset>,
+    funds: Vec<Coin>,
+    sender: Addr,
+    to: Addr,
+    referrer: Option<Addr>,
+    minimum_receive: Option<Uint128>,
+    no_swap: Option<bool>,
+    slippage_tolerance: Option<Decimal>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let compound = config.compound_proxy.compound_msg(assets, funds, no_swap, slippage_tolerance, None)?;
+
+    let prev_balance = query_asset_balance(&deps.querier, &determine_asset_info(&config.liquidity_token), &env.contract.address)?;
+    let bond_to = CallbackMsg::BondTo {
+        to,
+        sender,
+        prev_balance,
+        minimum_receive,
+        referrer,
+    }
+    .into_cosmos_msg(&env.contract.address)?;
+
+    Ok(vec![compound, bond_to])
 }
 
 /// ## Description
 /// Bond available LP token on the contract on behalf of the user.
-pub fn bond_to(
-    deps: DepsMut,
+#[allow(clippy::too_many_arguments)]
+pub fn bond_to<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     _info: MessageInfo,
     to: Addr,
+    sender: Addr,
     prev_balance: Uint128,
-    minimum_receive: Option<Uint128>
+    minimum_receive: Option<Uint128>,
+    referrer: Option<Addr>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    let balance = query_token_balance(&deps.querier, &config.liquidity_token, &env.contract.address)?;
+    let balance = query_asset_balance(&deps.querier, &determine_asset_info(&config.liquidity_token), &env.contract.address)?;
     let amount = balance - prev_balance;
 
     if let Some(minimum_receive) = minimum_receive {
@@ -91,21 +206,32 @@ pub fn bond_to(
         deps,
         env,
         config,
+        &sender,
         to,
         amount,
+        referrer,
     )
 }
 
 /// ## Description
-/// Bond received LP token on behalf of the user.
-pub fn bond(
-    deps: DepsMut,
+/// Bond received LP token on behalf of the staker, or the sender if not set.
+pub fn bond<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     sender_addr: String,
+    staker_addr: Option<String>,
     amount: Uint128,
+    referrer: Option<String>,
 ) -> Result<Response, ContractError> {
-    let staker_addr = deps.api.addr_validate(&sender_addr)?;
+    assert_not_paused(deps.storage)?;
+
+    let sender_addr = deps.api.addr_validate(&sender_addr)?;
+    let staker_addr = staker_addr
+        .map(|staker_addr| deps.api.addr_validate(&staker_addr))
+        .transpose()?
+        .unwrap_or_else(|| sender_addr.clone());
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
 
     let config = CONFIG.load(deps.storage)?;
 
@@ -118,26 +244,165 @@ pub fn bond(
         deps,
         env,
         config,
+        &sender_addr,
+        staker_addr,
+        amount,
+        referrer,
+    )
+}
+
+/// ## Description
+/// Bond x/tokenfactory LP token sent as funds on behalf of the staker, or the sender if not set.
+pub fn bond_native<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: Option<String>,
+    referrer: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    let staker_addr = staker_addr
+        .map(|staker_addr| deps.api.addr_validate(&staker_addr))
+        .transpose()?
+        .unwrap_or_else(|| info.sender.clone());
+    let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // a cw20 LP token is bonded with the Bond hook
+    if !is_token_factory_denom(config.liquidity_token.as_str()) {
+        return Err(ContractError::InvalidMessage {});
+    }
+
+    let amount = match &info.funds[..] {
+        [coin] if coin.denom == config.liquidity_token.as_str() => coin.amount,
+        _ => return Err(ContractError::InvalidMessage {}),
+    };
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    bond_internal(
+        deps,
+        env,
+        config,
+        &info.sender,
         staker_addr,
         amount,
+        referrer,
     )
 }
 
-/// Internal bond function used by bond and bond_to
-fn bond_internal(
-    deps: DepsMut,
+/// ## Description
+/// Bond the coin transferred over IBC on behalf of the receiver named in the ibc-hooks memo. The
+/// sender is the intermediate account of the hook, so the receiver must always be set.
+pub fn ibc_hook_deposit<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    farm: String,
+    min_lp: Option<Uint128>,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    if farm != env.contract.address.as_str() {
+        return Err(ContractError::InvalidHookTarget(farm));
+    }
+
+    let coin = match &info.funds[..] {
+        [coin] => coin.clone(),
+        _ => return Err(ContractError::InvalidMessage {}),
+    };
+    if coin.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let res = if coin.denom == config.liquidity_token.as_str() {
+        if let Some(minimum_receive) = min_lp {
+            if coin.amount < minimum_receive {
+                return Err(ContractError::AssertionMinimumReceive {
+                    minimum_receive,
+                    amount: coin.amount,
+                });
+            }
+        }
+        bond_native(deps, env, info, Some(receiver), None)?
+    } else {
+        let asset = native_asset(coin.denom, coin.amount);
+        bond_assets(deps, env, info, vec![asset], min_lp, None, None, Some(receiver), None)?
+    };
+
+    Ok(res.add_attribute("ibc_hook_farm", farm))
+}
+
+/// ## Description
+/// Bond LP token unbonded from the legacy farm on behalf of the staker.
+pub fn migrate_stake<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    sender_addr: String,
+    staker_addr: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
+
+    let sender_addr = deps.api.addr_validate(&sender_addr)?;
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    // only staking token sent by the legacy farm can execute this message
+    if config.liquidity_token != info.sender
+        || config.legacy_farm.as_ref().map(|it| it.as_str()) != Some(sender_addr.as_str()) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let res = bond_internal(
+        deps,
+        env,
+        config,
+        &sender_addr,
+        staker_addr,
+        amount,
+        None,
+    )?;
+
+    Ok(res.add_attribute("migrate_from", sender_addr))
+}
+
+/// Internal bond function used by bond and bond_to, the referrer is only set by the staker and only
+/// if they have none
+fn bond_internal<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     config: Config,
+    sender_addr: &Addr,
     staker_addr: Addr,
     amount: Uint128,
+    referrer: Option<Addr>,
 ) -> Result<Response, ContractError>{
+    if referrer.as_ref() == Some(&staker_addr) {
+        return Err(ContractError::SelfReferral {});
+    }
+    if referrer.is_some() && *sender_addr != staker_addr {
+        return Err(ContractError::ThirdPartyReferral {});
+    }
 
-    let lp_balance = config.staking_contract.query_deposit(
+    let lp_balance = config.staking().query_deposit(
         &deps.querier,
         &config.liquidity_token,
         &env.contract.address,
     )?;
 
+    if !config.deposit_cap.is_zero() && lp_balance + amount > config.deposit_cap {
+        return Err(ContractError::DepositCapExceeded {
+            deposit_cap: config.deposit_cap,
+        });
+    }
+
     let mut messages: Vec<CosmosMsg> = vec![];
 
     let mut state = STATE.load(deps.storage)?;
@@ -146,38 +411,80 @@ fn bond_internal(
     let mut reward_info = REWARD
         .may_load(deps.storage, &staker_addr)?
         .unwrap_or_default();
+    reward_info.settle_boost(&state);
+    reward_info.settle_stake_reward(&state)?;
+    // only a bond by the staker restarts the cooldown, so others cannot lock the stake
+    if *sender_addr == staker_addr {
+        reward_info.last_bond_time = env.block.time.seconds();
+    }
+    if reward_info.referrer.is_none() {
+        reward_info.referrer = referrer;
+    }
 
-    // convert amount to share & update
-    let bond_share = state.calc_bond_share(amount, lp_balance, ScalingOperation::Truncate);
-    state.total_bond_share += bond_share;
+    // split amount between the auto-stake bucket and the compounding shares
+    let stake_amount = amount * reward_info.auto_stake_ratio;
+    let bond_amount = amount.checked_sub(stake_amount)?;
+    let compound_balance = state.calc_compound_balance(lp_balance);
+    reward_info.stake_bond_amount += stake_amount;
+    state.total_stake_bond_amount += stake_amount;
 
-    let deposit_amount = state.calc_bond_amount(
-        lp_balance + amount,
-        bond_share,
-    );
+    if !bond_amount.is_zero() {
+        // convert amount to share & update
+        let mut bond_share = state.calc_bond_share(bond_amount, compound_balance, ScalingOperation::Truncate);
+        if state.total_bond_share.is_zero() {
+            // lock the first shares, so LP donated to an empty farm cannot raise the share price
+            // enough to round the next bond down to zero shares
+            if bond_share <= MINIMUM_INITIAL_BOND {
+                return Err(ContractError::MinimumInitialBond(MINIMUM_INITIAL_BOND));
+            }
+            state.total_bond_share += MINIMUM_INITIAL_BOND;
+            bond_share -= MINIMUM_INITIAL_BOND;
+        }
+        state.total_bond_share += bond_share;
 
-    let pool_info = config.pair.query_pool_info(&deps.querier)?;
-    reward_info.ensure_deposit_costs(deps.storage)?;
-    reward_info.bond(bond_share, deposit_amount, env.block.time.seconds(), &pool_info)?;
+        let deposit_amount = state.calc_bond_amount(
+            compound_balance + bond_amount,
+            bond_share,
+        );
+
+        // value the deposit at reserves anchored to the TWAP, a skewed pool would inflate its cost
+        let mut pool_info = config.pair.query_pool_info(&deps.querier)?;
+        if let Some(price_guard) = &config.price_guard {
+            let pair_info = config.pair.query_pair_info(&deps.querier)?;
+            pool_info = price_guard.anchored_pool(&deps.querier, &pair_info, pool_info)?;
+        }
+        reward_info.ensure_deposit_costs(deps.storage)?;
+        reward_info.bond(bond_share, deposit_amount, env.block.time.seconds(), &pool_info)?;
+        reward_info.update_working_share(&deps.querier, &config, &mut state, &staker_addr)?;
+    }
+    reward_info.settle_referral(deps.storage, &mut state)?;
 
     REWARD.save(deps.storage, &staker_addr, &reward_info)?;
     STATE.save(deps.storage, &state)?;
 
-    messages.push(config.staking_contract.deposit_msg(config.liquidity_token.to_string(), amount)?);
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
+    messages.push(config.staking().deposit_msg(config.liquidity_token.to_string(), amount)?);
+    let mut attributes = vec![
         attr("action", "bond"),
+        attr("staker_addr", staker_addr),
         attr("amount", amount),
-        attr("bond_amount", amount),
-    ]))
+        attr("bond_amount", bond_amount),
+    ];
+    if !stake_amount.is_zero() {
+        attributes.push(attr("stake_amount", stake_amount));
+    }
+    Ok(Response::new().add_messages(messages).add_attributes(attributes))
 }
 
 /// ## Description
-/// Unbond LP token of sender
-pub fn unbond(
-    deps: DepsMut,
+/// Unbond LP token of sender, or a single pool asset when `as_asset` is set
+pub fn unbond<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     amount: Uint128,
+    as_asset: Option<AssetInfo>,
+    minimum_receive: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
 ) -> Result<Response, ContractError> {
 
     if amount.is_zero() {
@@ -187,51 +494,526 @@ pub fn unbond(
     let staker_addr = info.sender;
 
     let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
+    let staking_token = &config.liquidity_token;
 
-    let lp_balance = config.staking_contract.query_deposit(
+    let lp_balance = config.staking().query_deposit(
         &deps.querier,
-        &staking_token,
+        staking_token,
         &env.contract.address,
     )?;
 
     let mut state = STATE.load(deps.storage)?;
     let mut reward_info = REWARD.load(deps.storage, &staker_addr)?;
+    reward_info.assert_bond_cooldown(&config, env.block.time.seconds())?;
+    reward_info.settle_boost(&state);
+    reward_info.settle_stake_reward(&state)?;
 
     let user_balance = reward_info.calc_user_balance(
         &state,
-        lp_balance,
+        state.calc_compound_balance(lp_balance),
         env.block.time.seconds(),
     );
+    let total_balance = user_balance + reward_info.stake_bond_amount;
 
-    if user_balance < amount {
+    if total_balance < amount {
         return Err(ContractError::UnbondExceedBalance {});
     }
 
-    let bond_share = reward_info.bond_share.multiply_ratio_and_ceil(amount, user_balance);
+    // unbond from the auto-stake bucket and the compounding shares pro rata
+    let stake_amount = amount.multiply_ratio(reward_info.stake_bond_amount, total_balance);
+    reward_info.stake_bond_amount = reward_info.stake_bond_amount.checked_sub(stake_amount)?;
+    state.total_stake_bond_amount = state.total_stake_bond_amount.checked_sub(stake_amount)?;
+
+    let bond_amount = amount.checked_sub(stake_amount)?;
+    if !bond_amount.is_zero() {
+        let bond_share = reward_info.bond_share.multiply_ratio_and_ceil(bond_amount, user_balance);
+        state.total_bond_share = state.total_bond_share.checked_sub(bond_share)?;
+        reward_info.unbond(bond_share)?;
+        reward_info.update_working_share(&deps.querier, &config, &mut state, &staker_addr)?;
+    }
+    reward_info.settle_referral(deps.storage, &mut state)?;
+
+    // update state
+    STATE.save(deps.storage, &state)?;
+    REWARD.save(deps.storage, &staker_addr, &reward_info)?;
+
+    let messages = match as_asset {
+        Some(ask_asset_info) => {
+            zap_out_msgs(deps.as_ref(), &env, &config, staker_addr.clone(), ask_asset_info, amount, minimum_receive, slippage_tolerance)?
+        }
+        None => withdraw_lp_msgs(deps.storage, &env, &config, &staker_addr, amount)?,
+    };
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "unbond"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+        ]))
+}
+
+/// Creates the messages withdrawing LP token from the staking contract and the pair, and swapping
+/// the pool assets into the asset to receive
+#[allow(clippy::too_many_arguments)]
+fn zap_out_msgs<C: CustomQuery>(
+    deps: Deps<C>,
+    env: &Env,
+    config: &Config,
+    to: Addr,
+    ask_asset_info: AssetInfo,
+    amount: Uint128,
+    minimum_receive: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    if config.unbonding_period != 0 {
+        return Err(ContractError::UnbondAsAssetWithUnbondingPeriod {});
+    }
+
+    let pool_info = config.pair.query_pool_info(&deps.querier)?;
+    if !pool_info.assets.iter().any(|it| it.info.equal(&ask_asset_info)) {
+        return Err(ContractError::InvalidMessage {});
+    }
+
+    let prev_balances = pool_info.assets.into_iter()
+        .map(|it| {
+            let balance = query_asset_balance(&deps.querier, &it.info, &env.contract.address)?;
+            Ok(it.info.with_balance(balance))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(vec![
+        config.staking().withdraw_msg(config.liquidity_token.to_string(), amount)?,
+        config.pair.withdraw_liquidity_msg(config.liquidity_token.to_string(), amount)?,
+        CallbackMsg::ZapOut {
+            to,
+            ask_asset_info,
+            prev_balances,
+            minimum_receive,
+            slippage_tolerance,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    ])
+}
+
+/// ## Description
+/// Sends the pool assets withdrawn from the pair to compound proxy, which swaps them into the
+/// asset to receive and sends it to the staker.
+#[allow(clippy::too_many_arguments)]
+pub fn zap_out<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    _info: MessageInfo,
+    to: Addr,
+    ask_asset_info: AssetInfo,
+    prev_balances: Vec<Asset>,
+    minimum_receive: Option<Uint128>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut assets: Vec<Asset> = vec![];
+    let mut funds: Vec<Coin> = vec![];
+    for prev_balance in prev_balances {
+        let balance = query_asset_balance(&deps.querier, &prev_balance.info, &env.contract.address)?;
+        let asset = prev_balance.info.with_balance(balance.checked_sub(prev_balance.amount)?);
+        if asset.amount.is_zero() {
+            continue;
+        }
+        if asset.is_native_token() {
+            funds.push(Coin {
+                denom: asset.info.to_string(),
+                amount: asset.amount,
+            });
+        } else {
+            messages.push(asset.increase_allowance_msg(
+                config.compound_proxy.0.to_string(),
+                Some(Expiration::AtHeight(env.block.height + 1)),
+            )?);
+        }
+        assets.push(asset);
+    }
+
+    messages.push(config.compound_proxy.swap_to_asset_msg(
+        assets,
+        funds,
+        ask_asset_info,
+        minimum_receive,
+        slippage_tolerance,
+        Some(to.to_string()),
+    )?);
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "zap_out"))
+}
+
+/// ## Description
+/// Unbond all LP token of sender. Only withdraws from the staking contract and transfers the LP token,
+/// so it stays available when the compound path is broken. The unbonding period and the bond
+/// cooldown are bypassed.
+pub fn emergency_unbond<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let config = CONFIG.load(deps.storage)?;
+    let staking_token = &config.liquidity_token;
+
+    let lp_balance = config.staking().query_deposit(
+        &deps.querier,
+        staking_token,
+        &env.contract.address,
+    )?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut reward_info = REWARD.load(deps.storage, &staker_addr)?;
+    reward_info.settle_boost(&state);
+    reward_info.settle_stake_reward(&state)?;
+
+    let bond_amount = reward_info.calc_user_balance(
+        &state,
+        state.calc_compound_balance(lp_balance),
+        env.block.time.seconds(),
+    );
+    let stake_amount = reward_info.stake_bond_amount;
+    let amount = bond_amount + stake_amount;
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    reward_info.stake_bond_amount = Uint128::zero();
+    state.total_stake_bond_amount = state.total_stake_bond_amount.checked_sub(stake_amount)?;
+
+    let bond_share = reward_info.bond_share;
     state.total_bond_share = state.total_bond_share.checked_sub(bond_share)?;
     reward_info.unbond(bond_share)?;
+    // no share is left, so the working share is cleared without querying the governance
+    reward_info.update_working_share(&deps.querier, &config, &mut state, &staker_addr)?;
+    reward_info.settle_referral(deps.storage, &mut state)?;
 
     // update state
     STATE.save(deps.storage, &state)?;
     REWARD.save(deps.storage, &staker_addr, &reward_info)?;
 
+    Ok(Response::new()
+        .add_message(config.staking().withdraw_msg(config.liquidity_token.to_string(), amount)?)
+        .add_message(determine_asset_info(&config.liquidity_token).with_balance(amount).transfer_msg(&staker_addr)?)
+        .add_attributes(vec![
+            attr("action", "emergency_unbond"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Creates the messages withdrawing LP token from the staking contract. The LP token is transferred
+/// to the staker, or kept in the contract as a claim until the unbonding period has elapsed.
+pub fn withdraw_lp_msgs(
+    storage: &mut dyn Storage,
+    env: &Env,
+    config: &Config,
+    staker_addr: &Addr,
+    amount: Uint128,
+) -> StdResult<Vec<CosmosMsg>> {
+    let withdraw = config.staking().withdraw_msg(config.liquidity_token.to_string(), amount)?;
+    if config.unbonding_period == 0 {
+        return Ok(vec![
+            withdraw,
+            determine_asset_info(&config.liquidity_token).with_balance(amount).transfer_msg(staker_addr)?,
+        ]);
+    }
+
+    let release_at = env.block.time.seconds() + config.unbonding_period;
+    CLAIMS.update(storage, (staker_addr, release_at), |claim| -> StdResult<_> {
+        Ok(claim.unwrap_or_default().checked_add(amount)?)
+    })?;
+
+    Ok(vec![withdraw])
+}
+
+/// ## Description
+/// Withdraw LP token of sender whose unbonding period has elapsed.
+pub fn claim<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let config = CONFIG.load(deps.storage)?;
+
+    let claims = CLAIMS
+        .prefix(&staker_addr)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(env.block.time.seconds())),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut amount = Uint128::zero();
+    for (release_at, claim) in claims {
+        amount += claim;
+        CLAIMS.remove(deps.storage, (&staker_addr, release_at));
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_message(determine_asset_info(&config.liquidity_token).with_balance(amount).transfer_msg(&staker_addr)?)
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Sets the share of the future deposits of the sender bonded in the auto-stake bucket.
+pub fn update_auto_stake_ratio<C: CustomQuery>(
+    deps: DepsMut<C>,
+    info: MessageInfo,
+    auto_stake_ratio: Decimal,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let state = STATE.load(deps.storage)?;
+    let mut reward_info = REWARD
+        .may_load(deps.storage, &staker_addr)?
+        .unwrap_or_default();
+
+    reward_info.settle_stake_reward(&state)?;
+    reward_info.auto_stake_ratio = auto_stake_ratio;
+
+    REWARD.save(deps.storage, &staker_addr, &reward_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_auto_stake_ratio"),
+        attr("staker_addr", staker_addr),
+        attr("auto_stake_ratio", auto_stake_ratio.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Withdraw the rewards earned by the auto-stake bucket of sender.
+pub fn claim_stake_reward<C: CustomQuery>(
+    deps: DepsMut<C>,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut reward_info = REWARD.may_load(deps.storage, &staker_addr)?
+        .unwrap_or_default();
+
+    reward_info.settle_stake_reward(&state)?;
+    let rewards = std::mem::take(&mut reward_info.pending_stake_rewards);
+    if rewards.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    for reward in rewards.iter() {
+        let balance = state.stake_reward_balances.iter_mut()
+            .find(|it| it.info.equal(&reward.info))
+            .ok_or_else(|| StdError::generic_err(format!("No stake reward balance of {}", reward.info)))?;
+        balance.amount = balance.amount.checked_sub(reward.amount)?;
+    }
+
+    STATE.save(deps.storage, &state)?;
+    REWARD.save(deps.storage, &staker_addr, &reward_info)?;
+
+    let messages = rewards.iter()
+        .map(|reward| reward.transfer_msg(&staker_addr))
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "claim_stake_reward"),
+            attr("staker_addr", staker_addr),
+            attr("rewards", rewards.iter().map(|it| it.to_string()).collect::<Vec<_>>().join(",")),
+        ]))
+}
+
+/// ## Description
+/// Withdraw the referral fees credited to sender as referrer, after settling the given referred
+/// stakers.
+pub fn claim_referral<C: CustomQuery>(
+    deps: DepsMut<C>,
+    info: MessageInfo,
+    stakers: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let referrer = info.sender;
+
+    let mut state = STATE.load(deps.storage)?;
+    for staker in stakers.unwrap_or_default() {
+        let staker_addr = deps.api.addr_validate(&staker)?;
+        let mut reward_info = REWARD.load(deps.storage, &staker_addr)?;
+        if reward_info.referrer.as_ref() != Some(&referrer) {
+            return Err(ContractError::Unauthorized {});
+        }
+        reward_info.settle_boost(&state);
+        reward_info.settle_referral(deps.storage, &mut state)?;
+        REWARD.save(deps.storage, &staker_addr, &reward_info)?;
+    }
+
+    let fees = REFERRAL_FEES.may_load(deps.storage, &referrer)?.unwrap_or_default();
+    if fees.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    REFERRAL_FEES.remove(deps.storage, &referrer);
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![
+        attr("action", "claim_referral"),
+        attr("referrer", referrer.to_string()),
+    ];
+    for fee in fees {
+        let balance = state.referral_balances.iter_mut()
+            .find(|it| it.info.equal(&fee.info))
+            .ok_or_else(|| StdError::generic_err(format!("No referral balance of {}", fee.info)))?;
+        balance.amount = balance.amount.checked_sub(fee.amount)?;
+        attributes.push(attr("token", fee.info.to_string()));
+        attributes.push(attr("amount", fee.amount));
+        messages.push(fee.transfer_msg(&referrer)?);
+    }
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(attributes))
+}
+
+/// ## Description
+/// Withdraws LP token from the reserve. Only the owner can execute this.
+pub fn withdraw_reserve<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let recipient = recipient
+        .map(|recipient| deps.api.addr_validate(&recipient))
+        .transpose()?
+        .unwrap_or(info.sender);
+
+    let mut state = STATE.load(deps.storage)?;
+    let lp_balance = state.calc_compound_balance(config.staking().query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?);
+
+    let share = state.calc_bond_share(amount, lp_balance, ScalingOperation::Ceil);
+    if share > state.reserve_share {
+        return Err(ContractError::UnbondExceedBalance {});
+    }
+
+    state.total_bond_share = state.total_bond_share.checked_sub(share)?;
+    state.reserve_share = state.reserve_share.checked_sub(share)?;
+
+    STATE.save(deps.storage, &state)?;
+
     Ok(Response::new()
         .add_messages(vec![
-            config.staking_contract.withdraw_msg(staking_token.to_string(), amount)?,
-            token_asset(staking_token, amount).transfer_msg(&staker_addr)?,
+            config.staking().withdraw_msg(config.liquidity_token.to_string(), amount)?,
+            determine_asset_info(&config.liquidity_token).with_balance(amount).transfer_msg(&recipient)?,
         ])
         .add_attributes(vec![
-            attr("action", "unbond"),
-            attr("staker_addr", staker_addr),
+            attr("action", "withdraw_reserve"),
+            attr("recipient", recipient),
             attr("amount", amount),
+            attr("share", share),
         ]))
 }
 
+/// ## Description
+/// Refreshes the working share of the staker from their staked SPEC weight.
+pub fn update_boost<C: CustomQuery>(
+    deps: DepsMut<C>,
+    staker_addr: String,
+) -> Result<Response, ContractError> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    let mut reward_info = REWARD.load(deps.storage, &staker_addr)?;
+
+    reward_info.settle_boost(&state);
+    reward_info.update_working_share(&deps.querier, &config, &mut state, &staker_addr)?;
+    reward_info.settle_referral(deps.storage, &mut state)?;
+
+    STATE.save(deps.storage, &state)?;
+    REWARD.save(deps.storage, &staker_addr, &reward_info)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update_boost"),
+        attr("staker_addr", staker_addr),
+        attr("working_share", reward_info.working_share),
+    ]))
+}
+
+/// ## Description
+/// Returns the unbonded LP token of the staker waiting for the unbonding period.
+pub fn query_claims<C: CustomQuery>(
+    deps: Deps<C>,
+    staker_addr: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ClaimsResponse> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let claims = CLAIMS
+        .prefix(&staker_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(release_at, amount)| ClaimInfo { amount, release_at }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(ClaimsResponse { claims })
+}
+
+/// ## Description
+/// Returns the share price recorded at each compound.
+pub fn query_share_price_history<C: CustomQuery>(
+    deps: Deps<C>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SharePriceHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let checkpoints = SHARE_PRICE_CHECKPOINTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(time, share_price)| SharePriceCheckpoint { time, share_price }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(SharePriceHistoryResponse { checkpoints })
+}
+
 /// ## Description
 /// Returns reward info for the staker.
-pub fn query_reward_info(
-    deps: Deps,
+pub fn query_reward_info<C: CustomQuery>(
+    deps: Deps<C>,
     env: Env,
     staker_addr: String,
 ) -> StdResult<RewardInfoResponse> {
@@ -244,20 +1026,86 @@ pub fn query_reward_info(
     })
 }
 
+/// ## Description
+/// Returns the auto-stake bucket of the staker and its pending rewards.
+pub fn query_stake_info<C: CustomQuery>(
+    deps: Deps<C>,
+    staker_addr: String,
+) -> StdResult<StakeInfoResponse> {
+    let staker_addr_validated = deps.api.addr_validate(&staker_addr)?;
+    let mut reward_info = REWARD
+        .may_load(deps.storage, &staker_addr_validated)?
+        .unwrap_or_default();
+    let state = STATE.load(deps.storage)?;
+    reward_info.settle_stake_reward(&state)?;
+
+    Ok(StakeInfoResponse {
+        staker_addr,
+        auto_stake_ratio: reward_info.auto_stake_ratio,
+        stake_bond_amount: reward_info.stake_bond_amount,
+        pending_rewards: reward_info.pending_stake_rewards,
+    })
+}
+
+/// ## Description
+/// Returns the referrer of the address and the referral fees credited to it.
+pub fn query_referral_info<C: CustomQuery>(
+    deps: Deps<C>,
+    address: String,
+) -> StdResult<ReferralInfoResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let reward_info = REWARD.may_load(deps.storage, &address)?.unwrap_or_default();
+
+    Ok(ReferralInfoResponse {
+        referrer: reward_info.referrer.map(|referrer| referrer.to_string()),
+        pending_fees: REFERRAL_FEES.may_load(deps.storage, &address)?.unwrap_or_default(),
+    })
+}
+
+/// ## Description
+/// Returns the shares of the staker and the LP token amount backing them.
+pub fn query_user_info<C: CustomQuery>(
+    deps: Deps<C>,
+    env: Env,
+    staker_addr: String,
+) -> StdResult<UserInfoResponse> {
+    let staker_addr_validated = deps.api.addr_validate(&staker_addr)?;
+    let mut reward_info = REWARD
+        .may_load(deps.storage, &staker_addr_validated)?
+        .unwrap_or_default();
+    let state = STATE.load(deps.storage)?;
+    reward_info.settle_boost(&state);
+    let config = CONFIG.load(deps.storage)?;
+
+    let lp_balance = state.calc_compound_balance(config.staking().query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?);
+
+    Ok(UserInfoResponse {
+        staker_addr,
+        shares: reward_info.bond_share,
+        lp_amount: state.calc_bond_amount(lp_balance, reward_info.bond_share),
+        share_price: state.calc_share_price(lp_balance),
+    })
+}
+
 /// Loads reward info from the storage
-fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<RewardInfoResponseItem> {
+fn read_reward_info<C: CustomQuery>(deps: Deps<C>, env: Env, staker_addr: &Addr) -> StdResult<RewardInfoResponseItem> {
     let mut reward_info = REWARD
         .may_load(deps.storage, staker_addr)?
         .unwrap_or_default();
     let state = STATE.load(deps.storage)?;
+    reward_info.settle_boost(&state);
     let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
+    let staking_token = config.liquidity_token.clone();
 
-    let lp_balance = config.staking_contract.query_deposit(
+    let lp_balance = state.calc_compound_balance(config.staking().query_deposit(
         &deps.querier,
         &staking_token,
         &env.contract.address,
-    )?;
+    )?);
 
     let bond_amount = reward_info.calc_user_balance(
         &state,
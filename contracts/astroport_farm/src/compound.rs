@@ -1,47 +1,135 @@
 use astroport::{
     asset::{Asset},
 };
-use cosmwasm_std::{attr, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128, Decimal};
+use cosmwasm_std::{attr, Attribute, Coin, CosmosMsg, CustomQuery, DepsMut, Env, Event, MessageInfo, Response, StdResult, SubMsg, Uint128, Decimal};
 
 use crate::{
     error::ContractError,
-    state::CONFIG,
+    state::{
+        CompoundSnapshot, ScalingOperation, COMPOUND_SNAPSHOT, CONFIG, HARVESTED, KEEPERS, SHARE_PRICE_CHECKPOINTS,
+        STATE,
+    },
 };
 
 use cw20::{Expiration};
-use astroport::asset::{AssetInfo, AssetInfoExt, token_asset};
+use astroport::asset::{determine_asset_info, AssetInfo, AssetInfoExt};
 
-use astroport::querier::query_token_balance;
 use spectrum::adapters::asset::AssetEx;
+use spectrum::events::{CompoundReport, STAGE_HARVEST, STAGE_STAKE};
+use spectrum::math::bps_amount;
+use spectrum::querier::query_asset_balance;
+use spectrum::pause::assert_not_paused;
 
 use spectrum::astroport_farm::CallbackMsg;
 
+/// The reply id of the generator claim sent by compound
+pub const CLAIM_REWARDS_REPLY_ID: u64 = 1;
+
+/// The reply id of the generator claim sent by harvest
+pub const HARVEST_REPLY_ID: u64 = 2;
+
 /// ## Description
-/// Performs compound by sending LP rewards to compound proxy and reinvest received LP token
-pub fn compound(
-    deps: DepsMut,
+/// Performs compound by claiming LP rewards from the staking contract, the received rewards are
+/// sent to compound proxy in the claim reply and the received LP token is reinvested.
+pub fn compound<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     minimum_receive: Option<Uint128>,
     slippage_tolerance: Option<Decimal>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage)?;
 
     let config = CONFIG.load(deps.storage)?;
 
-    // Only controller can call this function
-    if info.sender != config.controller {
+    // Only controller and keepers can call this function
+    if info.sender != config.controller && !KEEPERS.has(deps.storage, &info.sender) {
         return Err(ContractError::Unauthorized {});
     }
 
+    // Abort while the pool is manipulated away from its time weighted average price
+    if let Some(price_guard) = &config.price_guard {
+        let pair_info = config.pair.query_pair_info(&deps.querier)?;
+        price_guard.assert_price(&deps.querier, &pair_info)?;
+    }
+
+    let staking = config.staking();
     let staking_token = config.liquidity_token;
 
-    let pending_token = config.staking_contract.query_pending_token(
+    let base_reward_token = determine_asset_info(&config.base_reward_token);
+    let rewards = staking.query_pending_rewards(
         &deps.querier,
         &staking_token,
         &env.contract.address,
+        &base_reward_token,
+    )?;
+    let rewards = aggregate_rewards(rewards)?;
+
+    // Skip until rewards are worth compounding, they keep accruing in the staking contract
+    if !config.min_reward_value.is_zero() {
+        let harvested = HARVESTED.may_load(deps.storage)?.unwrap_or_default();
+        let simulate_rewards: Vec<Asset> = aggregate_rewards(rewards.iter().cloned().chain(harvested).collect())?
+            .into_iter()
+            .filter(|it| !it.amount.is_zero())
+            .collect();
+        let reward_value = if simulate_rewards.is_empty() {
+            Uint128::zero()
+        } else {
+            config.compound_proxy
+                .query_compound_simulation(&deps.querier, simulate_rewards)?
+                .lp_amount
+        };
+        if reward_value < config.min_reward_value {
+            return Ok(Response::new()
+                .add_attribute("action", "skip_compound")
+                .add_attribute("reward_value", reward_value)
+                .add_attribute("min_reward_value", config.min_reward_value));
+        }
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    state.last_compound_time = env.block.time.seconds();
+    state.last_compound_height = env.block.height;
+    STATE.save(deps.storage, &state)?;
+
+    // the pending rewards can be stale within the block, only the reward tokens are trusted
+    let balances = rewards
+        .into_iter()
+        .map(|asset| {
+            let balance = query_asset_balance(&deps.querier, &asset.info, &env.contract.address)?;
+            Ok(asset.info.with_balance(balance))
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+    COMPOUND_SNAPSHOT.save(deps.storage, &CompoundSnapshot {
+        balances,
+        minimum_receive,
+        slippage_tolerance,
+    })?;
+
+    let claim_rewards = staking.claim_rewards_msg(
+        vec![staking_token.to_string()],
     )?;
 
-    let lp_balance = config.staking_contract.query_deposit(
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(claim_rewards, CLAIM_REWARDS_REPLY_ID))
+        .add_attribute("action", "claim_rewards"))
+}
+
+/// ## Description
+/// Compounds the rewards received by the generator claim, measured as the increase of the reward
+/// balances since the snapshot. The performance fee is taken from the claimed rewards only and
+/// sent to the fee collector.
+pub fn compound_rewards<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let snapshot = COMPOUND_SNAPSHOT.load(deps.storage)?;
+    COMPOUND_SNAPSHOT.remove(deps.storage);
+
+    let staking_token = config.liquidity_token.clone();
+
+    let lp_balance = config.staking().query_deposit(
         &deps.querier,
         &staking_token,
         &env.contract.address,
@@ -49,22 +137,28 @@ pub fn compound(
 
     let total_fee = config.fee;
 
+    let mut state = STATE.load(deps.storage)?;
+
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut attributes: Vec<Attribute> = vec![];
+    let mut events: Vec<Event> = vec![];
 
-    let mut rewards: Vec<Asset> = vec![];
     let mut compound_rewards: Vec<Asset> = vec![];
 
-    let claim_rewards = config.staking_contract.claim_rewards_msg(
-        vec![staking_token.to_string()],
-    )?;
-    messages.push(claim_rewards);
+    let mut rewards = snapshot.balances
+        .into_iter()
+        .map(|prev_balance| {
+            let balance = query_asset_balance(&deps.querier, &prev_balance.info, &env.contract.address)?;
+            Ok(prev_balance.info.with_balance(balance.checked_sub(prev_balance.amount)?))
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
 
-    rewards.push(
-        token_asset(config.base_reward_token, pending_token.pending),
-    );
-    if let Some(pending_on_proxy) = pending_token.pending_on_proxy {
-        rewards.extend(pending_on_proxy);
+    // the rewards claimed by harvest are compounded along with the claimed rewards
+    if !lp_balance.is_zero() {
+        if let Some(harvested) = HARVESTED.may_load(deps.storage)? {
+            rewards = aggregate_rewards(rewards.into_iter().chain(harvested).collect())?;
+            HARVESTED.remove(deps.storage);
+        }
     }
 
     let mut compound_funds: Vec<Coin> = vec![];
@@ -72,11 +166,20 @@ pub fn compound(
         let reward_amount = asset.amount;
         if !reward_amount.is_zero() && !lp_balance.is_zero() {
             let commission_amount = reward_amount * total_fee;
-            let compound_amount = reward_amount.checked_sub(commission_amount)?;
+            let mut compound_amount = reward_amount.checked_sub(commission_amount)?;
+
+            // keep the reward of the auto-stake bucket for its stakers to claim
+            let mut stake_amount = Uint128::zero();
+            if !state.total_stake_bond_amount.is_zero() {
+                stake_amount = compound_amount.multiply_ratio(state.total_stake_bond_amount, lp_balance);
+                compound_amount = compound_amount.checked_sub(stake_amount)?;
+                state.distribute_stake_reward(asset.info.with_balance(stake_amount))?;
+            }
+
             if !compound_amount.is_zero() {
                 let compound_asset = asset.info.with_balance(compound_amount);
                 if let AssetInfo::NativeToken { denom } = &asset.info {
-                    compound_funds.push(Coin { denom: denom.clone(), amount: asset.amount });
+                    compound_funds.push(Coin { denom: denom.clone(), amount: compound_amount });
                 } else {
                     let increase_allowance = compound_asset.increase_allowance_msg(
                         config.compound_proxy.0.to_string(),
@@ -87,8 +190,17 @@ pub fn compound(
                 compound_rewards.push(compound_asset);
             }
 
-            if !commission_amount.is_zero() {
-                let commission_asset = asset.info.with_balance(commission_amount);
+            // keep the referral share of the fee charged on the referred shares for the referrers
+            let mut referral_amount = Uint128::zero();
+            if config.referral_bps != 0 && !state.total_referred_share.is_zero() {
+                let referred_amount = state.calc_bond_amount(state.calc_compound_balance(lp_balance), state.total_referred_share);
+                referral_amount = bps_amount(commission_amount.multiply_ratio(referred_amount, lp_balance), config.referral_bps)?;
+                state.distribute_referral_fee(asset.info.with_balance(referral_amount))?;
+            }
+
+            let fee_amount = commission_amount.checked_sub(referral_amount)?;
+            if !fee_amount.is_zero() {
+                let commission_asset = asset.info.with_balance(fee_amount);
                 let transfer_fee = commission_asset.transfer_msg(&config.fee_collector)?;
                 messages.push(transfer_fee);
             }
@@ -96,18 +208,32 @@ pub fn compound(
             attributes.push(attr("token", asset.info.to_string()));
             attributes.push(attr("compound_amount", compound_amount));
             attributes.push(attr("commission_amount", commission_amount));
+            if !stake_amount.is_zero() {
+                attributes.push(attr("stake_amount", stake_amount));
+            }
+            if !referral_amount.is_zero() {
+                attributes.push(attr("referral_amount", referral_amount));
+            }
+            events.push(
+                CompoundReport::new(STAGE_HARVEST)
+                    .reward(&asset)
+                    .fee(&asset.info, commission_amount)
+                    .into_event(),
+            );
         }
     }
 
+    STATE.save(deps.storage, &state)?;
+
     if !compound_rewards.is_empty() {
-        let compound = config.compound_proxy.compound_msg(compound_rewards, compound_funds, None, slippage_tolerance)?;
+        let compound = config.compound_proxy.compound_msg(compound_rewards, compound_funds, None, snapshot.slippage_tolerance, None)?;
         messages.push(compound);
 
-        let prev_balance = query_token_balance(&deps.querier, staking_token, &env.contract.address)?;
+        let prev_balance = query_asset_balance(&deps.querier, &determine_asset_info(&staking_token), &env.contract.address)?;
         messages.push(
             CallbackMsg::Stake {
                 prev_balance,
-                minimum_receive,
+                minimum_receive: snapshot.minimum_receive,
             }
             .into_cosmos_msg(&env.contract.address)?,
         );
@@ -116,13 +242,169 @@ pub fn compound(
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("action", "compound")
+        .add_attribute("fee", config.fee.to_string())
+        .add_attribute("fee_collector", config.fee_collector)
+        .add_attributes(attributes)
+        .add_events(events))
+}
+
+/// ## Description
+/// Claims the pending rewards without compounding, so rewards are secured while the compound route
+/// is unhealthy. The received rewards are buffered in the claim reply until the next compound.
+pub fn harvest<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner
+        && info.sender != config.controller
+        && Some(&info.sender) != config.pauser.as_ref()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let staking = config.staking();
+    let staking_token = config.liquidity_token;
+
+    let base_reward_token = determine_asset_info(&config.base_reward_token);
+    let rewards = staking.query_pending_rewards(
+        &deps.querier,
+        &staking_token,
+        &env.contract.address,
+        &base_reward_token,
+    )?;
+
+    let balances = aggregate_rewards(rewards)?
+        .into_iter()
+        .map(|asset| {
+            let balance = query_asset_balance(&deps.querier, &asset.info, &env.contract.address)?;
+            Ok(asset.info.with_balance(balance))
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+    COMPOUND_SNAPSHOT.save(deps.storage, &CompoundSnapshot {
+        balances,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    })?;
+
+    let claim_rewards = staking.claim_rewards_msg(
+        vec![staking_token.to_string()],
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(claim_rewards, HARVEST_REPLY_ID))
+        .add_attribute("action", "harvest"))
+}
+
+/// ## Description
+/// Adds the rewards received by the harvest claim, measured as the increase of the reward balances
+/// since the snapshot, to the buffer compounded by the next compound.
+pub fn harvest_rewards<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+) -> Result<Response, ContractError> {
+    let snapshot = COMPOUND_SNAPSHOT.load(deps.storage)?;
+    COMPOUND_SNAPSHOT.remove(deps.storage);
+
+    let mut harvested = HARVESTED.may_load(deps.storage)?.unwrap_or_default();
+    let mut attributes: Vec<Attribute> = vec![];
+    for prev_balance in snapshot.balances {
+        let balance = query_asset_balance(&deps.querier, &prev_balance.info, &env.contract.address)?;
+        let amount = balance.checked_sub(prev_balance.amount)?;
+        if amount.is_zero() {
+            continue;
+        }
+        attributes.push(attr("token", prev_balance.info.to_string()));
+        attributes.push(attr("harvest_amount", amount));
+        harvested.push(prev_balance.info.with_balance(amount));
+    }
+    HARVESTED.save(deps.storage, &aggregate_rewards(harvested)?)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "harvest_rewards")
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Adds the balances of proxy reward tokens paid directly to the farm to the buffer compounded by
+/// the next compound, excluding the amounts already buffered or kept for the referrers and the
+/// auto-stake bucket. The base reward token is not swept, it is only paid by the claim.
+pub fn sweep_rewards<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner && info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let base_reward_token = determine_asset_info(&config.base_reward_token);
+    let liquidity_token = determine_asset_info(&config.liquidity_token);
+    let reward_tokens = config.staking().query_pending_rewards(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+        &base_reward_token,
+    )?;
+
+    let mut harvested = HARVESTED.may_load(deps.storage)?.unwrap_or_default();
+    let state = STATE.load(deps.storage)?;
+    let mut attributes: Vec<Attribute> = vec![];
+    for asset_info in assets {
+        if asset_info.equal(&base_reward_token)
+            || asset_info.equal(&liquidity_token)
+            || !reward_tokens.iter().any(|reward| reward.info.equal(&asset_info))
+        {
+            return Err(ContractError::UnrecognizedReward(asset_info.to_string()));
+        }
+
+        let balance = query_asset_balance(&deps.querier, &asset_info, &env.contract.address)?;
+        let buffered = harvested
+            .iter()
+            .chain(state.referral_balances.iter())
+            .chain(state.stake_reward_balances.iter())
+            .filter(|it| it.info.equal(&asset_info))
+            .map(|it| it.amount)
+            .sum::<Uint128>();
+        let amount = balance.saturating_sub(buffered);
+        if amount.is_zero() {
+            continue;
+        }
+        attributes.push(attr("token", asset_info.to_string()));
+        attributes.push(attr("sweep_amount", amount));
+        harvested.push(asset_info.with_balance(amount));
+    }
+    HARVESTED.save(deps.storage, &aggregate_rewards(harvested)?)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "sweep_rewards")
         .add_attributes(attributes))
 }
 
+/// ## Description
+/// Merges the base reward and proxy rewards of the same token, so a proxy reward equal to the
+/// base reward or to a pool asset is charged and sent to the compound proxy only once.
+fn aggregate_rewards(rewards: Vec<Asset>) -> StdResult<Vec<Asset>> {
+    let mut aggregated: Vec<Asset> = vec![];
+    for reward in rewards {
+        if let Some(existing) = aggregated.iter_mut().find(|a| a.info.equal(&reward.info)) {
+            existing.amount = existing.amount.checked_add(reward.amount)?;
+        } else {
+            aggregated.push(reward);
+        }
+    }
+    Ok(aggregated)
+}
+
 /// ## Description
 /// Stakes received LP token to the staking contract.
-pub fn stake(
-    deps: DepsMut,
+pub fn stake<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     _info: MessageInfo,
     prev_balance: Uint128,
@@ -130,9 +412,10 @@ pub fn stake(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
+    let staking = config.staking();
     let staking_token = config.liquidity_token;
 
-    let balance = query_token_balance(&deps.querier, &staking_token, &env.contract.address)?;
+    let balance = query_asset_balance(&deps.querier, &determine_asset_info(&staking_token), &env.contract.address)?;
     let amount = balance - prev_balance;
 
     if let Some(minimum_receive) = minimum_receive {
@@ -144,13 +427,57 @@ pub fn stake(
         }
     }
 
+    let mut state = STATE.load(deps.storage)?;
+    state.total_compounded += amount;
+
+    let mut attributes = vec![
+        attr("action", "stake"),
+        attr("staking_token", &staking_token),
+        attr("amount", amount),
+    ];
+
+    let lp_balance = state.calc_compound_balance(staking.query_deposit(
+        &deps.querier,
+        &staking_token,
+        &env.contract.address,
+    )?);
+
+    if config.boost.is_some() || config.reserve_bps != 0 {
+        // skim the reserve share of the compounded LP
+        let reserve_amount = bps_amount(amount, config.reserve_bps)?;
+        let distribute_amount = amount.checked_sub(reserve_amount)?;
+
+        // share the compounded LP by working share when the boost is enabled
+        if config.boost.is_some() {
+            let boost_share = state.distribute_boost(distribute_amount, lp_balance);
+            if !boost_share.is_zero() {
+                attributes.push(attr("boost_share", boost_share));
+            }
+        }
+
+        // mint the reserve shares after the distributed LP is accounted in the share price
+        let reserve_share = state.calc_bond_share(
+            reserve_amount,
+            lp_balance + distribute_amount,
+            ScalingOperation::Truncate,
+        );
+        if !reserve_share.is_zero() {
+            state.total_bond_share += reserve_share;
+            state.reserve_share += reserve_share;
+            attributes.push(attr("reserve_share", reserve_share));
+        }
+    }
+
+    STATE.save(deps.storage, &state)?;
+
+    // record the share price including the staked LP token for yield history
+    let share_price = state.calc_share_price(lp_balance + amount);
+    SHARE_PRICE_CHECKPOINTS.save(deps.storage, env.block.time.seconds(), &share_price)?;
+
     Ok(Response::new()
         .add_message(
-            config.staking_contract.deposit_msg(staking_token.to_string(), amount)?
+            staking.deposit_msg(staking_token.to_string(), amount)?
         )
-        .add_attributes(vec![
-            attr("action", "stake"),
-            attr("staking_token", staking_token),
-            attr("amount", amount),
-        ]))
+        .add_attributes(attributes)
+        .add_event(CompoundReport::new(STAGE_STAKE).lp_minted(amount).into_event()))
 }
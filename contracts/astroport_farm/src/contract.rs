@@ -1,28 +1,43 @@
 use cosmwasm_std::{
-    attr, entry_point, from_binary, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128,
+    attr, from_binary, to_binary, Binary, CustomQuery, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Reply, Response, StdError, StdResult, Uint128,
 };
 
 use crate::{
-    bond::{bond, bond_assets, bond_to},
-    compound::{compound, stake},
+    bond::{bond, bond_asset, bond_assets, bond_native, bond_to, ibc_hook_deposit, migrate_stake},
+    compound::{compound, compound_rewards, harvest, harvest_rewards, stake, sweep_rewards, CLAIM_REWARDS_REPLY_ID, HARVEST_REPLY_ID},
     error::ContractError,
     ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner},
-    state::{Config, State, CONFIG, OWNERSHIP_PROPOSAL},
+    receipt::{create_receipt_token, mint_receipt, redeem_receipt, register_receipt_token, RECEIPT_TOKEN_REPLY_ID},
+    state::{BoostConfig, Config, State, CONFIG, OWNERSHIP_PROPOSAL},
 };
 
+use astroport::asset::{determine_asset_info, validate_token};
 use cw20::{Cw20ReceiveMsg, MarketingInfoResponse, MinterResponse};
 use spectrum::adapters::generator::Generator;
+use spectrum::adapters::gov::Gov;
+use spectrum::adapters::oracle::PriceGuardConfig;
 use spectrum::adapters::pair::Pair;
 
-use crate::bond::{query_reward_info, unbond};
-use crate::state::{POOL_INFO, STATE};
+use crate::bond::{
+    claim, claim_referral, claim_stake_reward, emergency_unbond, query_claims, query_referral_info, query_reward_info,
+    query_share_price_history, query_stake_info, query_user_info,
+    unbond, update_auto_stake_ratio, update_boost, withdraw_reserve, zap_out,
+};
+use crate::state::{HARVESTED, KEEPERS, POOL_INFO, STATE};
 use spectrum::astroport_farm::{
-    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    Boost, CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, StateResponse,
 };
 use spectrum::compound_proxy::Compounder;
+use spectrum::helper::{assert_bps, assert_ratio, assert_valid_addr, invalid_state};
+use spectrum::oracle::PriceGuard;
+use spectrum::pause;
+use spectrum::vault::ExchangeRateResponse;
 use crate::cw20::{execute_burn, execute_burn_from, execute_decrease_allowance, execute_increase_allowance, execute_send, execute_send_from, execute_transfer, execute_transfer_from, query_all_accounts, query_all_allowances, query_allowance, query_balance, query_token_info};
 
+/// The max share of compounded LP token kept in the reserve, in bps
+const MAX_RESERVE_BPS: u64 = 1000;
+
 /// ## Description
 /// Validates that decimal value is in the range 0 to 1
 fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
@@ -36,9 +51,8 @@ fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
 /// ## Description
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 /// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
+pub fn instantiate<C: CustomQuery>(
+    deps: DepsMut<C>,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
@@ -51,13 +65,27 @@ pub fn instantiate(
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
             staking_contract: Generator(deps.api.addr_validate(&msg.staking_contract)?),
+            staking_interface: msg.staking_interface.unwrap_or_default(),
             compound_proxy: Compounder(deps.api.addr_validate(&msg.compound_proxy)?),
             controller: deps.api.addr_validate(&msg.controller)?,
             fee: msg.fee,
             fee_collector: deps.api.addr_validate(&msg.fee_collector)?,
-            liquidity_token: deps.api.addr_validate(&msg.liquidity_token)?,
-            base_reward_token: deps.api.addr_validate(&msg.base_reward_token)?,
+            liquidity_token: validate_token(deps.api, &msg.liquidity_token)?,
+            base_reward_token: validate_token(deps.api, &msg.base_reward_token)?,
             pair: Pair(deps.api.addr_validate(&msg.pair)?),
+            pauser: msg.pauser
+                .map(|pauser| deps.api.addr_validate(&pauser))
+                .transpose()?,
+            deposit_cap: msg.deposit_cap.unwrap_or_default(),
+            unbonding_period: msg.unbonding_period.unwrap_or_default(),
+            min_reward_value: msg.min_reward_value.unwrap_or_default(),
+            legacy_farm: None,
+            boost: None,
+            reserve_bps: 0,
+            price_guard: None,
+            bond_cooldown: 0,
+            referral_bps: 0,
+            receipt_token: None,
             name: msg.name,
             symbol: msg.symbol,
         },
@@ -67,6 +95,21 @@ pub fn instantiate(
         deps.storage,
         &State {
             total_bond_share: Uint128::zero(),
+            last_compound_time: 0,
+            last_compound_height: 0,
+            total_compounded: Uint128::zero(),
+            total_working_share: Uint128::zero(),
+            boost_index: Decimal::zero(),
+            boost_epoch: 0,
+            boost_start_index: Decimal::zero(),
+            boost_start_ratio: Decimal::zero(),
+            total_stake_bond_amount: Uint128::zero(),
+            stake_reward_indexes: vec![],
+            stake_reward_balances: vec![],
+            reserve_share: Uint128::zero(),
+            total_referred_share: Uint128::zero(),
+            referral_indexes: vec![],
+            referral_balances: vec![],
         },
     )?;
 
@@ -75,27 +118,84 @@ pub fn instantiate(
 
 /// ## Description
 /// Exposes execute functions available in the contract.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
+pub fn execute<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Bond { staker_addr, referrer } => bond_native(deps, env, info, staker_addr, referrer),
         ExecuteMsg::UpdateConfig {
             compound_proxy,
             controller,
             fee,
             fee_collector,
-        } => update_config(deps, info, compound_proxy, controller, fee, fee_collector),
-        ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
+            pauser,
+            deposit_cap,
+            unbonding_period,
+            min_reward_value,
+            legacy_farm,
+            boost,
+            reserve_bps,
+            price_guard,
+            bond_cooldown,
+            referral_bps,
+        } => update_config(
+            deps,
+            info,
+            compound_proxy,
+            controller,
+            fee,
+            fee_collector,
+            pauser,
+            deposit_cap,
+            unbonding_period,
+            min_reward_value,
+            legacy_farm,
+            boost,
+            reserve_bps,
+            price_guard,
+            bond_cooldown,
+            referral_bps,
+        ),
+        ExecuteMsg::Pause {} => set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => set_paused(deps, info, false),
+        ExecuteMsg::Unbond {
+            amount,
+            as_asset,
+            minimum_receive,
+            slippage_tolerance,
+        } => unbond(deps, env, info, amount, as_asset, minimum_receive, slippage_tolerance),
+        ExecuteMsg::EmergencyUnbond {} => emergency_unbond(deps, env, info),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::UpdateAutoStakeRatio { auto_stake_ratio } => {
+            validate_percentage(auto_stake_ratio, "auto_stake_ratio")?;
+            update_auto_stake_ratio(deps, info, auto_stake_ratio)
+        }
+        ExecuteMsg::ClaimStakeReward {} => claim_stake_reward(deps, info),
+        ExecuteMsg::ClaimReferral { stakers } => claim_referral(deps, info, stakers),
+        ExecuteMsg::WithdrawReserve { amount, recipient } => {
+            withdraw_reserve(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::UpdateBoost { staker_addr } => update_boost(deps, staker_addr),
+        ExecuteMsg::IbcHookDeposit {
+            farm,
+            min_lp,
+            receiver,
+        } => ibc_hook_deposit(deps, env, info, farm, min_lp, receiver),
+        ExecuteMsg::CreateReceiptToken { code_id, name, symbol } => {
+            create_receipt_token(deps, env, info, code_id, name, symbol)
+        }
+        ExecuteMsg::MintReceipt { amount } => mint_receipt(deps, env, info, amount),
         ExecuteMsg::BondAssets {
             assets,
             minimum_receive,
             no_swap,
             slippage_tolerance,
+            staker_addr,
+            referrer,
         } => bond_assets(
             deps,
             env,
@@ -104,11 +204,17 @@ pub fn execute(
             minimum_receive,
             no_swap,
             slippage_tolerance,
+            staker_addr,
+            referrer,
         ),
         ExecuteMsg::Compound {
             minimum_receive,
             slippage_tolerance,
         } => compound(deps, env, info, minimum_receive, slippage_tolerance),
+        ExecuteMsg::Harvest {} => harvest(deps, env, info),
+        ExecuteMsg::SweepRewards { assets } => sweep_rewards(deps, env, info, assets),
+        ExecuteMsg::AddKeeper { keeper } => add_keeper(deps, info, keeper),
+        ExecuteMsg::RemoveKeeper { keeper } => remove_keeper(deps, info, keeper),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -161,18 +267,51 @@ pub fn execute(
 /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
 /// If the template is not found in the received message, then a [`ContractError`] is returned,
 /// otherwise returns a [`Response`] with the specified attributes if the operation was successful
-fn receive_cw20(
-    deps: DepsMut,
+fn receive_cw20<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Bond { staker_addr }) => bond(
+        Ok(Cw20HookMsg::Bond { staker_addr, referrer }) => bond(
             deps,
             env,
             info,
-            staker_addr.unwrap_or(cw20_msg.sender),
+            cw20_msg.sender,
+            staker_addr,
+            cw20_msg.amount,
+            referrer,
+        ),
+        Ok(Cw20HookMsg::MigrateStake { staker_addr }) => migrate_stake(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
+            staker_addr,
+            cw20_msg.amount,
+        ),
+        Ok(Cw20HookMsg::BondAsset {
+            minimum_receive,
+            slippage_tolerance,
+            staker_addr,
+            referrer,
+        }) => bond_asset(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
+            staker_addr,
+            cw20_msg.amount,
+            minimum_receive,
+            slippage_tolerance,
+            referrer,
+        ),
+        Ok(Cw20HookMsg::RedeemReceipt {}) => redeem_receipt(
+            deps,
+            env,
+            info,
+            cw20_msg.sender,
             cw20_msg.amount,
         ),
         Err(_) => Err(ContractError::InvalidMessage {}),
@@ -182,13 +321,23 @@ fn receive_cw20(
 /// ## Description
 /// Updates contract config. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
 #[allow(clippy::too_many_arguments)]
-pub fn update_config(
-    deps: DepsMut,
+pub fn update_config<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     compound_proxy: Option<String>,
     controller: Option<String>,
     fee: Option<Decimal>,
     fee_collector: Option<String>,
+    pauser: Option<String>,
+    deposit_cap: Option<Uint128>,
+    unbonding_period: Option<u64>,
+    min_reward_value: Option<Uint128>,
+    legacy_farm: Option<String>,
+    boost: Option<Boost>,
+    reserve_bps: Option<u64>,
+    price_guard: Option<PriceGuard>,
+    bond_cooldown: Option<u64>,
+    referral_bps: Option<u64>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -204,24 +353,137 @@ pub fn update_config(
         config.controller = deps.api.addr_validate(&controller)?;
     }
 
+    let mut attributes = vec![attr("action", "update_config")];
+
     if let Some(fee) = fee {
         validate_percentage(fee, "fee")?;
         config.fee = fee;
+        attributes.push(attr("fee", fee.to_string()));
     }
 
     if let Some(fee_collector) = fee_collector {
         config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+        attributes.push(attr("fee_collector", fee_collector));
+    }
+
+    if let Some(pauser) = pauser {
+        config.pauser = Some(deps.api.addr_validate(&pauser)?);
+    }
+
+    if let Some(deposit_cap) = deposit_cap {
+        config.deposit_cap = deposit_cap;
+    }
+
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+
+    if let Some(min_reward_value) = min_reward_value {
+        config.min_reward_value = min_reward_value;
+    }
+
+    if let Some(legacy_farm) = legacy_farm {
+        config.legacy_farm = Some(deps.api.addr_validate(&legacy_farm)?);
+    }
+
+    if let Some(boost) = boost {
+        validate_percentage(boost.base_ratio, "base_ratio")?;
+        let boost = if boost.base_ratio == Decimal::one() {
+            None
+        } else {
+            Some(BoostConfig {
+                gov: Gov(deps.api.addr_validate(&boost.gov)?),
+                base_ratio: boost.base_ratio,
+            })
+        };
+        // the stakers hold the base working share until they are updated, so the boost is shared
+        // by all of them from the start
+        if let (None, Some(boost)) = (&config.boost, &boost) {
+            let mut state = STATE.load(deps.storage)?;
+            state.start_boost(boost.base_ratio)?;
+            STATE.save(deps.storage, &state)?;
+        }
+        config.boost = boost;
+    }
+
+    if let Some(reserve_bps) = reserve_bps {
+        if reserve_bps > MAX_RESERVE_BPS {
+            return Err(ContractError::InvalidReserveShare(MAX_RESERVE_BPS));
+        }
+        config.reserve_bps = reserve_bps;
+        attributes.push(attr("reserve_bps", reserve_bps.to_string()));
+    }
+
+    if let Some(price_guard) = price_guard {
+        config.price_guard = PriceGuardConfig::from_msg(deps.api, price_guard)?;
+    }
+
+    if let Some(bond_cooldown) = bond_cooldown {
+        config.bond_cooldown = bond_cooldown;
+        attributes.push(attr("bond_cooldown", bond_cooldown.to_string()));
+    }
+
+    if let Some(referral_bps) = referral_bps {
+        if referral_bps > 10000 {
+            return Err(StdError::generic_err("referral_bps must be 0 to 10000").into());
+        }
+        config.referral_bps = referral_bps;
+        attributes.push(attr("referral_bps", referral_bps.to_string()));
     }
 
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// ## Description
+/// Pauses or unpauses bond and compound. Only the owner and pauser can execute this.
+fn set_paused<C: CustomQuery>(deps: DepsMut<C>, info: MessageInfo, paused: bool) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    Ok(pause::set_paused(deps.storage, &info.sender, &config.owner, config.pauser.as_ref(), paused)?)
+}
+
+/// ## Description
+/// Allows an address to compound in addition to the controller. Only the owner can execute this.
+fn add_keeper<C: CustomQuery>(deps: DepsMut<C>, info: MessageInfo, keeper: String) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let keeper = deps.api.addr_validate(&keeper)?;
+    KEEPERS.save(deps.storage, &keeper, &true)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_keeper"),
+        attr("keeper", keeper),
+    ]))
+}
+
+/// ## Description
+/// Removes an address from the keepers. Only the owner can execute this.
+fn remove_keeper<C: CustomQuery>(deps: DepsMut<C>, info: MessageInfo, keeper: String) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let keeper = deps.api.addr_validate(&keeper)?;
+    KEEPERS.remove(deps.storage, &keeper);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_keeper"),
+        attr("keeper", keeper),
+    ]))
 }
 
 /// # Description
 /// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
-pub fn handle_callback(
-    deps: DepsMut,
+pub fn handle_callback<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     msg: CallbackMsg,
@@ -237,22 +499,57 @@ pub fn handle_callback(
         } => stake(deps, env, info, prev_balance, minimum_receive),
         CallbackMsg::BondTo {
             to,
+            sender,
             prev_balance,
             minimum_receive,
-        } => bond_to(deps, env, info, to, prev_balance, minimum_receive),
+            referrer,
+        } => bond_to(deps, env, info, to, sender, prev_balance, minimum_receive, referrer),
+        CallbackMsg::ZapOut {
+            to,
+            ask_asset_info,
+            prev_balances,
+            minimum_receive,
+            slippage_tolerance,
+        } => zap_out(deps, env, info, to, ask_asset_info, prev_balances, minimum_receive, slippage_tolerance),
+    }
+}
+
+/// ## Description
+/// Compounds the rewards received by the generator claim, or buffers them after a harvest, or stores
+/// the instantiated receipt token.
+pub fn reply<C: CustomQuery>(deps: DepsMut<C>, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        CLAIM_REWARDS_REPLY_ID => compound_rewards(deps, env),
+        HARVEST_REPLY_ID => harvest_rewards(deps, env),
+        RECEIPT_TOKEN_REPLY_ID => register_receipt_token(deps, msg),
+        _ => Err(StdError::generic_err("Unknown reply id").into()),
     }
 }
 
 /// ## Description
 /// Exposes all the queries available in the contract.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query<C: CustomQuery>(deps: Deps<C>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::RewardInfo { staker_addr } => {
             to_binary(&query_reward_info(deps, env, staker_addr)?)
         }
-        QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::State {} => to_binary(&query_state(deps, env)?),
+        QueryMsg::Paused {} => to_binary(&pause::is_paused(deps.storage)?),
+        QueryMsg::Keepers {} => to_binary(&query_keepers(deps)?),
+        QueryMsg::Claims { staker_addr, start_after, limit } => {
+            to_binary(&query_claims(deps, staker_addr, start_after, limit)?)
+        }
+        QueryMsg::Harvested {} => to_binary(&HARVESTED.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
+        QueryMsg::SharePriceHistory { start_after, limit } => {
+            to_binary(&query_share_price_history(deps, start_after, limit)?)
+        }
+        QueryMsg::UserInfo { staker_addr } => {
+            to_binary(&query_user_info(deps, env, staker_addr)?)
+        }
+        QueryMsg::StakeInfo { staker_addr } => to_binary(&query_stake_info(deps, staker_addr)?),
+        QueryMsg::ReferralInfo { address } => to_binary(&query_referral_info(deps, address)?),
 
         // cw20
         QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
@@ -268,22 +565,72 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 
 /// ## Description
 /// Returns contract config
-fn query_config(deps: Deps) -> StdResult<Config> {
+fn query_config<C: CustomQuery>(deps: Deps<C>) -> StdResult<Config> {
     let config = CONFIG.load(deps.storage)?;
     Ok(config)
 }
 
+/// ## Description
+/// Returns the keepers allowed to compound in addition to the controller
+fn query_keepers<C: CustomQuery>(deps: Deps<C>) -> StdResult<Vec<String>> {
+    KEEPERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.to_string()))
+        .collect()
+}
+
 /// ## Description
 /// Returns contract state
-fn query_state(deps: Deps) -> StdResult<State> {
+fn query_state<C: CustomQuery>(deps: Deps<C>, env: Env) -> StdResult<StateResponse> {
     let state = STATE.load(deps.storage)?;
-    Ok(state)
+    let config = CONFIG.load(deps.storage)?;
+
+    let lp_balance = config.staking().query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+
+    let compound_balance = state.calc_compound_balance(lp_balance);
+
+    Ok(StateResponse {
+        total_bond_amount: lp_balance,
+        total_bond_share: state.total_bond_share,
+        share_price: state.calc_share_price(compound_balance),
+        total_stake_bond_amount: state.total_stake_bond_amount,
+        reserve_share: state.reserve_share,
+        reserve_amount: state.calc_bond_amount(compound_balance, state.reserve_share),
+        last_compound_time: state.last_compound_time,
+        last_compound_height: state.last_compound_height,
+        total_compounded: state.total_compounded,
+    })
+}
+
+/// ## Description
+/// Returns the LP token per share and the LP token backing the shares, the auto-stake bucket is
+/// excluded like the share price.
+fn query_exchange_rate<C: CustomQuery>(deps: Deps<C>, env: Env) -> StdResult<ExchangeRateResponse> {
+    let state = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let lp_balance = config.staking().query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+    let compound_balance = state.calc_compound_balance(lp_balance);
+
+    Ok(ExchangeRateResponse {
+        asset: determine_asset_info(&config.liquidity_token),
+        exchange_rate: state.calc_share_price(compound_balance),
+        total_shares: state.total_bond_share,
+        total_assets: compound_balance,
+    })
 }
 
 /// ## Description
 /// Used for contract migration. Returns a default object of type [`Response`].
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate<C: CustomQuery>(deps: DepsMut<C>, env: Env, msg: MigrateMsg) -> StdResult<Response> {
     msg.validate()?;
 
     let mut config = CONFIG.load(deps.storage)?;
@@ -295,5 +642,108 @@ pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response>
     let pool_info = config.pair.query_pool_info(&deps.querier)?;
     POOL_INFO.save(deps.storage, &pool_info)?;
 
+    assert_state(deps.as_ref(), &env, &config)?;
+
     Ok(Response::default())
 }
+
+/// ## Description
+/// Checks the migrated config and that the shares are backed by the staking deposit, so the
+/// migration aborts on inconsistent state instead of carrying it over.
+fn assert_state<C: CustomQuery>(deps: Deps<C>, env: &Env, config: &Config) -> StdResult<()> {
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.staking_contract.0, "staking_contract")?;
+    assert_valid_addr(deps.api, &config.compound_proxy.0, "compound_proxy")?;
+    assert_valid_addr(deps.api, &config.controller, "controller")?;
+    assert_valid_addr(deps.api, &config.fee_collector, "fee_collector")?;
+    for (token, field) in [(&config.liquidity_token, "liquidity_token"), (&config.base_reward_token, "base_reward_token")] {
+        validate_token(deps.api, token.as_str())
+            .map_err(|_| invalid_state(&format!("{} is not a valid token", field)))?;
+    }
+    if let Some(pauser) = &config.pauser {
+        assert_valid_addr(deps.api, pauser, "pauser")?;
+    }
+    if let Some(legacy_farm) = &config.legacy_farm {
+        assert_valid_addr(deps.api, legacy_farm, "legacy_farm")?;
+    }
+    if let Some(receipt_token) = &config.receipt_token {
+        assert_valid_addr(deps.api, receipt_token, "receipt_token")?;
+    }
+    if let Some(boost) = &config.boost {
+        assert_valid_addr(deps.api, &boost.gov.0, "boost gov")?;
+        assert_ratio(boost.base_ratio, "base_ratio")?;
+    }
+    if let Some(price_guard) = &config.price_guard {
+        assert_valid_addr(deps.api, &price_guard.oracle.0, "price_guard oracle")?;
+        assert_bps(price_guard.max_deviation_bps, 10000, "max_deviation_bps")?;
+    }
+    assert_ratio(config.fee, "fee")?;
+    assert_bps(config.reserve_bps, MAX_RESERVE_BPS, "reserve_bps")?;
+    assert_bps(config.referral_bps, 10000, "referral_bps")?;
+
+    let state = STATE.load(deps.storage)?;
+    if state.reserve_share > state.total_bond_share {
+        return Err(invalid_state("reserve shares exceed the total shares"));
+    }
+    if state.total_referred_share > state.total_bond_share {
+        return Err(invalid_state("referred shares exceed the total shares"));
+    }
+    let lp_balance = config.staking().query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+    if lp_balance < state.total_stake_bond_amount {
+        return Err(invalid_state("auto-stake bucket exceeds the staking deposit"));
+    }
+    if !state.total_bond_share.is_zero() && state.calc_compound_balance(lp_balance).is_zero() {
+        return Err(invalid_state("shares are not backed by the staking deposit"));
+    }
+
+    Ok(())
+}
+
+/// The entry points of the contract on chains without custom queries. A chain-specific build
+/// enables the `library` feature and exports the generic entry points with its own query type.
+#[cfg(not(feature = "library"))]
+pub mod entry {
+    use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response, StdResult};
+    use spectrum::astroport_farm::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+    use crate::error::ContractError;
+
+    #[entry_point]
+    pub fn instantiate(
+        deps: DepsMut<Empty>,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        super::instantiate(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn execute(
+        deps: DepsMut<Empty>,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        super::execute(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn reply(deps: DepsMut<Empty>, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        super::reply(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn query(deps: Deps<Empty>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        super::query(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut<Empty>, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+        super::migrate(deps, env, msg)
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Addr, attr, Binary, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, attr, Binary, BlockInfo, CustomQuery, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128};
 use cw20::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo, AllowanceResponse, BalanceResponse, Cw20ReceiveMsg, Expiration, TokenInfoResponse};
 use cw_storage_plus::Bound;
 use astroport::asset::{Asset, AssetInfo};
@@ -6,9 +6,9 @@ use astroport::pair::PoolResponse;
 use crate::error::ContractError;
 use crate::state::{ALLOWANCES, CONFIG, REWARD, STATE};
 
-fn transfer_internal(
-    deps: DepsMut,
-    _env: Env,
+pub(crate) fn transfer_internal<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
     sender_addr: &Addr,
     recipient: &str,
     share: Uint128,
@@ -18,13 +18,20 @@ fn transfer_internal(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
     let mut sender = REWARD.load(deps.storage, sender_addr)?;
+    // shares in the cooldown cannot move to an account free to unbond them
+    sender.assert_bond_cooldown(&config, env.block.time.seconds())?;
+    sender.settle_boost(&state);
     sender.bond_share = sender.bond_share.checked_sub(share)?;
     sender.transfer_share += share;
 
     let rcpt_addr = deps.api.addr_validate(recipient)?;
     let mut receiver = REWARD.may_load(deps.storage, &rcpt_addr)?
         .unwrap_or_default();
+    receiver.settle_boost(&state);
     if receiver.transfer_share < share {
         let new_share = share - receiver.transfer_share;
         receiver.bond_share += receiver.transfer_share;
@@ -48,14 +55,20 @@ fn transfer_internal(
         receiver.transfer_share -= share;
     }
 
+    sender.update_working_share(&deps.querier, &config, &mut state, sender_addr)?;
+    receiver.update_working_share(&deps.querier, &config, &mut state, &rcpt_addr)?;
+    sender.settle_referral(deps.storage, &mut state)?;
+    receiver.settle_referral(deps.storage, &mut state)?;
+
+    STATE.save(deps.storage, &state)?;
     REWARD.save(deps.storage, sender_addr, &sender)?;
     REWARD.save(deps.storage, &rcpt_addr, &receiver)?;
 
     Ok(())
 }
 
-pub fn execute_transfer(
-    deps: DepsMut,
+pub fn execute_transfer<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     recipient: String,
@@ -72,8 +85,9 @@ pub fn execute_transfer(
     Ok(res)
 }
 
-fn burn_internal(
-    deps: DepsMut,
+fn burn_internal<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
     sender: &Addr,
     share: Uint128,
 ) -> Result<(), ContractError> {
@@ -81,10 +95,15 @@ fn burn_internal(
         return Err(ContractError::InvalidZeroAmount {});
     }
 
+    let config = CONFIG.load(deps.storage)?;
     let mut state = STATE.load(deps.storage)?;
     let mut reward_info = REWARD.load(deps.storage, sender)?;
+    reward_info.assert_bond_cooldown(&config, env.block.time.seconds())?;
+    reward_info.settle_boost(&state);
     state.total_bond_share = state.total_bond_share.checked_sub(share)?;
     reward_info.unbond(share)?;
+    reward_info.update_working_share(&deps.querier, &config, &mut state, sender)?;
+    reward_info.settle_referral(deps.storage, &mut state)?;
 
     STATE.save(deps.storage, &state)?;
     REWARD.save(deps.storage, sender, &reward_info)?;
@@ -92,14 +111,14 @@ fn burn_internal(
     Ok(())
 }
 
-pub fn execute_burn(
-    deps: DepsMut,
-    _env: Env,
+pub fn execute_burn<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
 
-    burn_internal(deps, &info.sender, amount)?;
+    burn_internal(deps, env, &info.sender, amount)?;
 
     let res = Response::new()
         .add_attribute("action", "burn")
@@ -108,8 +127,8 @@ pub fn execute_burn(
     Ok(res)
 }
 
-pub fn execute_send(
-    deps: DepsMut,
+pub fn execute_send<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     contract: String,
@@ -134,8 +153,8 @@ pub fn execute_send(
     Ok(res)
 }
 
-pub fn execute_increase_allowance(
-    deps: DepsMut,
+pub fn execute_increase_allowance<C: CustomQuery>(
+    deps: DepsMut<C>,
     _env: Env,
     info: MessageInfo,
     spender: String,
@@ -169,8 +188,8 @@ pub fn execute_increase_allowance(
     Ok(res)
 }
 
-pub fn execute_decrease_allowance(
-    deps: DepsMut,
+pub fn execute_decrease_allowance<C: CustomQuery>(
+    deps: DepsMut<C>,
     _env: Env,
     info: MessageInfo,
     spender: String,
@@ -235,8 +254,8 @@ pub fn deduct_allowance(
     })
 }
 
-pub fn execute_transfer_from(
-    deps: DepsMut,
+pub fn execute_transfer_from<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     owner: String,
@@ -259,8 +278,8 @@ pub fn execute_transfer_from(
     Ok(res)
 }
 
-pub fn execute_burn_from(
-    deps: DepsMut,
+pub fn execute_burn_from<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     owner: String,
@@ -270,7 +289,7 @@ pub fn execute_burn_from(
 
     // deduct allowance before doing anything else have enough allowance
     deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
-    burn_internal(deps, &owner_addr, amount)?;
+    burn_internal(deps, env, &owner_addr, amount)?;
 
     let res = Response::new().add_attributes(vec![
         attr("action", "burn_from"),
@@ -281,8 +300,8 @@ pub fn execute_burn_from(
     Ok(res)
 }
 
-pub fn execute_send_from(
-    deps: DepsMut,
+pub fn execute_send_from<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     owner: String,
@@ -315,18 +334,19 @@ pub fn execute_send_from(
     Ok(res)
 }
 
-pub fn query_balance(
-    deps: Deps,
+pub fn query_balance<C: CustomQuery>(
+    deps: Deps<C>,
     address: String
 ) -> StdResult<BalanceResponse> {
     let address = deps.api.addr_validate(&address)?;
     let reward_info = REWARD
         .may_load(deps.storage, &address)?
         .unwrap_or_default();
-    Ok(BalanceResponse { balance: reward_info.bond_share })
+    let state = STATE.load(deps.storage)?;
+    Ok(BalanceResponse { balance: reward_info.bond_share + reward_info.calc_pending_boost_share(&state) })
 }
 
-pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
+pub fn query_token_info<C: CustomQuery>(deps: Deps<C>) -> StdResult<TokenInfoResponse> {
     let config = CONFIG.load(deps.storage)?;
     let state = STATE.load(deps.storage)?;
     let res = TokenInfoResponse {
@@ -338,7 +358,7 @@ pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
     Ok(res)
 }
 
-pub fn query_allowance(deps: Deps, owner: String, spender: String) -> StdResult<AllowanceResponse> {
+pub fn query_allowance<C: CustomQuery>(deps: Deps<C>, owner: String, spender: String) -> StdResult<AllowanceResponse> {
     let owner_addr = deps.api.addr_validate(&owner)?;
     let spender_addr = deps.api.addr_validate(&spender)?;
     let allowance = ALLOWANCES
@@ -348,11 +368,11 @@ pub fn query_allowance(deps: Deps, owner: String, spender: String) -> StdResult<
 }
 
 // settings for pagination
-const MAX_LIMIT: u32 = 30;
-const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
 
-pub fn query_all_allowances(
-    deps: Deps,
+pub fn query_all_allowances<C: CustomQuery>(
+    deps: Deps<C>,
     owner: String,
     start_after: Option<String>,
     limit: Option<u32>,
@@ -376,8 +396,8 @@ pub fn query_all_allowances(
     Ok(AllAllowancesResponse { allowances })
 }
 
-pub fn query_all_accounts(
-    deps: Deps,
+pub fn query_all_accounts<C: CustomQuery>(
+    deps: Deps<C>,
     start_after: Option<String>,
     limit: Option<u32>,
 ) -> StdResult<AllAccountsResponse> {
@@ -31,6 +31,42 @@ pub enum ContractError {
 
     #[error("Cannot set to own account")]
     CannotSetOwnAccount {},
+
+    #[error("Deposit cap exceeded; deposit cap: {deposit_cap}")]
+    DepositCapExceeded { deposit_cap: Uint128 },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Receipt token is not created")]
+    ReceiptTokenNotCreated {},
+
+    #[error("Receipt token is already created")]
+    ReceiptTokenAlreadyCreated {},
+
+    #[error("Cannot unbond as asset while the unbonding period is set")]
+    UnbondAsAssetWithUnbondingPeriod {},
+
+    #[error("Reserve share must be at most {0} bps")]
+    InvalidReserveShare(u64),
+
+    #[error("IBC hook deposit is intended for another farm: {0}")]
+    InvalidHookTarget(String),
+
+    #[error("Initial bond must be greater than {0}")]
+    MinimumInitialBond(Uint128),
+
+    #[error("Cannot unbond before {0}, the bond cooldown has not elapsed")]
+    BondCooldown(u64),
+
+    #[error("Cannot refer yourself")]
+    SelfReferral {},
+
+    #[error("Only the staker can set their referrer")]
+    ThirdPartyReferral {},
+
+    #[error("Not a proxy reward of the staking contract: {0}")]
+    UnrecognizedReward(String),
 }
 
 impl From<OverflowError> for ContractError {
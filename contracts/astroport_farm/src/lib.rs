@@ -3,6 +3,8 @@ pub mod compound;
 pub mod contract;
 pub mod error;
 pub mod ownership;
+pub mod receipt;
+pub mod staking;
 pub mod state;
 pub mod cw20;
 
@@ -1,12 +1,16 @@
 use std::collections::HashMap;
-use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Decimal256, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
 use cosmwasm_std::testing::{MockApi, MockStorage};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use astroport::asset::{native_asset, token_asset};
+use astroport::asset::{native_asset, token_asset, Asset, AssetInfo, PairInfo};
+use astroport::factory::PairType;
 use astroport::generator::{PendingTokenResponse};
-use astroport::pair::PoolResponse;
+use astroport::pair::{PoolResponse, SimulationResponse};
+use spectrum::adapters::gov::BoostWeightResponse;
+use spectrum::compound_proxy::CompoundSimulationResponse;
+use spectrum::oracle::PriceResponse;
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -21,10 +25,14 @@ pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
 
 const ASTRO_TOKEN: &str = "astro";
 const REWARD_TOKEN: &str = "reward";
+const LP_TOKEN: &str = "lp_token";
+pub const TOTAL_WEIGHT: &str = "total_weight";
 
 pub struct WasmMockQuerier {
     balances: HashMap<(String, String), Uint128>,
     raw: HashMap<(String, Binary), Binary>,
+    proxy_rewards: Option<Vec<Asset>>,
+    oracle_price: Decimal256,
 }
 
 impl WasmMockQuerier {
@@ -32,6 +40,8 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             balances: HashMap::new(),
             raw: HashMap::new(),
+            proxy_rewards: None,
+            oracle_price: Decimal256::zero(),
         }
     }
 
@@ -39,6 +49,30 @@ impl WasmMockQuerier {
         self.balances.insert((token, addr), amount);
     }
 
+    pub fn set_proxy_rewards(&mut self, proxy_rewards: Option<Vec<Asset>>) {
+        self.proxy_rewards = proxy_rewards;
+    }
+
+    pub fn set_oracle_price(&mut self, price: Decimal256) {
+        self.oracle_price = price;
+    }
+
+    /// Credits the pending rewards of the generator to the user, as ClaimRewards does
+    pub fn claim_rewards(&mut self, generator: &str, user: &str) {
+        let mut rewards = vec![token_asset(
+            Addr::unchecked(ASTRO_TOKEN),
+            self.get_balance(generator.to_string(), ASTRO_TOKEN.to_string()),
+        )];
+        rewards.extend(self.proxy_rewards.clone().unwrap_or_else(|| vec![token_asset(
+            Addr::unchecked(REWARD_TOKEN),
+            self.get_balance(generator.to_string(), REWARD_TOKEN.to_string()),
+        )]));
+        for reward in rewards {
+            let balance = self.get_balance(reward.info.to_string(), user.to_string());
+            self.set_balance(reward.info.to_string(), user.to_string(), balance + reward.amount);
+        }
+    }
+
     fn get_balance(&self, token: String, addr: String) -> Uint128 {
         *self.balances.get(&(token, addr)).unwrap_or(&Uint128::zero())
     }
@@ -99,11 +133,27 @@ impl WasmMockQuerier {
                 let reward = self.get_balance(contract_addr.clone(), REWARD_TOKEN.to_string());
                 to_binary(&PendingTokenResponse {
                     pending,
-                    pending_on_proxy: Some(vec![
+                    pending_on_proxy: Some(self.proxy_rewards.clone().unwrap_or_else(|| vec![
                         token_asset(Addr::unchecked(REWARD_TOKEN), reward),
-                    ]),
+                    ])),
                 })
             },
+            MockQueryMsg::StakedBalance { .. } => {
+                let balance = self.get_balance(contract_addr.clone(), LP_TOKEN.to_string());
+                to_binary(&balance)
+            },
+            // the incentives contract and staking adapters return the same rewards as a single list
+            MockQueryMsg::PendingRewards {} => {
+                let mut rewards = vec![token_asset(
+                    Addr::unchecked(ASTRO_TOKEN),
+                    self.get_balance(contract_addr.clone(), ASTRO_TOKEN.to_string()),
+                )];
+                rewards.extend(self.proxy_rewards.clone().unwrap_or_else(|| vec![token_asset(
+                    Addr::unchecked(REWARD_TOKEN),
+                    self.get_balance(contract_addr.clone(), REWARD_TOKEN.to_string()),
+                )]));
+                to_binary(&rewards)
+            },
             MockQueryMsg::Pool {} => {
                 to_binary(&PoolResponse {
                     total_share: Uint128::from(1_000_000u128),
@@ -112,6 +162,48 @@ impl WasmMockQuerier {
                         native_asset("denom2".to_string(), Uint128::from(1_000_000u128)),
                     ]
                 })
+            },
+            MockQueryMsg::Pair {} => {
+                to_binary(&PairInfo {
+                    asset_infos: vec![
+                        AssetInfo::NativeToken { denom: "denom1".to_string() },
+                        AssetInfo::NativeToken { denom: "denom2".to_string() },
+                    ],
+                    contract_addr: Addr::unchecked(contract_addr),
+                    liquidity_token: Addr::unchecked("lp_token"),
+                    pair_type: PairType::Xyk {},
+                })
+            },
+            // the pool is 1:1, every simulation returns the offer amount
+            MockQueryMsg::Simulation { offer_asset, .. } => {
+                to_binary(&SimulationResponse {
+                    return_amount: offer_asset.amount,
+                    spread_amount: Uint128::zero(),
+                    commission_amount: Uint128::zero(),
+                })
+            },
+            MockQueryMsg::Price { .. } => {
+                to_binary(&PriceResponse {
+                    price: self.oracle_price,
+                    start_time: 0,
+                    sources: 1,
+                    max_deviation: Decimal256::zero(),
+                })
+            },
+            MockQueryMsg::CompoundSimulation { rewards } => {
+                to_binary(&CompoundSimulationResponse {
+                    lp_amount: rewards.iter().map(|it| it.amount).sum(),
+                    swap_asset_a_amount: Uint128::zero(),
+                    swap_asset_b_amount: Uint128::zero(),
+                    return_a_amount: Uint128::zero(),
+                    return_b_amount: Uint128::zero(),
+                })
+            },
+            MockQueryMsg::BoostWeight { address } => {
+                to_binary(&BoostWeightResponse {
+                    weight: self.get_balance(contract_addr.clone(), address),
+                    total_weight: self.get_balance(contract_addr.clone(), TOTAL_WEIGHT.to_string()),
+                })
             }
         }
     }
@@ -131,7 +223,28 @@ enum MockQueryMsg {
         lp_token: String,
         user: String
     },
+    StakedBalance {
+        staker: String,
+    },
+    // incentives query `lp_token` and `user`, staking adapters query `staker`
+    PendingRewards {},
     Pool {},
+    Pair {},
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        window: u64,
+    },
+    CompoundSimulation {
+        rewards: Vec<Asset>,
+    },
+    BoostWeight {
+        address: String,
+    },
 }
 
 impl Querier for WasmMockQuerier {
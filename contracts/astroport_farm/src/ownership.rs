@@ -1,4 +1,4 @@
-use cosmwasm_std::{attr, Addr, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cosmwasm_std::{attr, Addr, CustomQuery, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -31,8 +31,8 @@ pub struct OwnershipProposal {
 /// `owner` is the current owner.
 ///
 /// `proposal` is the object of type [`OwnershipProposal`].
-pub fn propose_new_owner(
-    deps: DepsMut,
+pub fn propose_new_owner<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     env: Env,
     new_owner: String,
@@ -78,8 +78,8 @@ pub fn propose_new_owner(
 /// `owner` is the current owner.
 ///
 /// `proposal` is the object of type [`OwnershipProposal`].
-pub fn drop_ownership_proposal(
-    deps: DepsMut,
+pub fn drop_ownership_proposal<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     owner: Addr,
     proposal: Item<OwnershipProposal>,
@@ -108,12 +108,12 @@ pub fn drop_ownership_proposal(
 /// `proposal` is the object of type [`OwnershipProposal`].
 ///
 /// `cb` is a type of callback function that takes two parameters of type [`DepsMut`] and [`Addr`].
-pub fn claim_ownership(
-    deps: DepsMut,
+pub fn claim_ownership<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     env: Env,
     proposal: Item<OwnershipProposal>,
-    cb: fn(DepsMut, Addr) -> StdResult<()>,
+    cb: fn(DepsMut<C>, Addr) -> StdResult<()>,
 ) -> StdResult<Response> {
     let p: OwnershipProposal = proposal
         .load(deps.storage)
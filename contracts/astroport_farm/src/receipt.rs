@@ -0,0 +1,168 @@
+use astroport::token::InstantiateMsg as TokenInstantiateMsg;
+use cosmwasm_std::{
+    attr, to_binary, CosmosMsg, CustomQuery, DepsMut, Env, MessageInfo, Reply, Response, StdError,
+    SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, MinterResponse};
+use cw_utils::parse_reply_instantiate_data;
+
+use crate::bond::withdraw_lp_msgs;
+use crate::cw20::transfer_internal;
+use crate::error::ContractError;
+use crate::state::{CONFIG, REWARD, STATE};
+
+/// The reply id of the receipt token instantiation
+pub const RECEIPT_TOKEN_REPLY_ID: u64 = 3;
+
+/// ## Description
+/// Instantiates the cw20 receipt token of the farm shares with the farm as minter. Only the owner
+/// can execute this, once.
+pub fn create_receipt_token<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    name: String,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if config.receipt_token.is_some() {
+        return Err(ContractError::ReceiptTokenAlreadyCreated {});
+    }
+
+    let instantiate = WasmMsg::Instantiate {
+        admin: Some(config.owner.to_string()),
+        code_id,
+        msg: to_binary(&TokenInstantiateMsg {
+            name,
+            symbol,
+            decimals: 6,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: env.contract.address.to_string(),
+                cap: None,
+            }),
+            marketing: None,
+        })?,
+        funds: vec![],
+        label: "Spectrum farm receipt token".to_string(),
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(instantiate, RECEIPT_TOKEN_REPLY_ID))
+        .add_attribute("action", "create_receipt_token"))
+}
+
+/// ## Description
+/// Stores the receipt token instantiated by [`create_receipt_token`].
+pub fn register_receipt_token<C: CustomQuery>(deps: DepsMut<C>, msg: Reply) -> Result<Response, ContractError> {
+    let res = parse_reply_instantiate_data(msg)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let receipt_token = deps.api.addr_validate(&res.contract_address)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.receipt_token = Some(receipt_token.clone());
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("receipt_token", receipt_token))
+}
+
+/// ## Description
+/// Escrows the shares of the sender under the farm address and mints the same amount of receipt
+/// token to the sender. The bond cooldown of the sender must have elapsed, so the receipt token
+/// cannot be redeemed earlier than the shares.
+pub fn mint_receipt<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let receipt_token = config.receipt_token.clone().ok_or(ContractError::ReceiptTokenNotCreated {})?;
+
+    let escrow = env.contract.address.to_string();
+    transfer_internal(deps, env, &info.sender, &escrow, amount)?;
+
+    let mint = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: receipt_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Mint {
+            recipient: info.sender.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new()
+        .add_message(mint)
+        .add_attributes(vec![
+            attr("action", "mint_receipt"),
+            attr("staker_addr", info.sender),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Burns the receipt token received and unbonds the same amount of escrowed shares at the share
+/// price, the LP token is withdrawn to the sender of the receipt token.
+pub fn redeem_receipt<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    sender_addr: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the receipt token can execute this message
+    if config.receipt_token.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let staker_addr = deps.api.addr_validate(&sender_addr)?;
+    let escrow = env.contract.address.clone();
+
+    let lp_balance = config.staking().query_deposit(
+        &deps.querier,
+        &config.liquidity_token,
+        &env.contract.address,
+    )?;
+
+    let mut state = STATE.load(deps.storage)?;
+    let mut reward_info = REWARD.load(deps.storage, &escrow)?;
+    reward_info.settle_boost(&state);
+
+    // the escrow pools the shares of every minter, so its deposit smoothing does not apply
+    let lp_amount = state.calc_bond_amount(state.calc_compound_balance(lp_balance), amount);
+    state.total_bond_share = state.total_bond_share.checked_sub(amount)?;
+    reward_info.unbond(amount)?;
+    reward_info.update_working_share(&deps.querier, &config, &mut state, &escrow)?;
+    reward_info.settle_referral(deps.storage, &mut state)?;
+
+    STATE.save(deps.storage, &state)?;
+    REWARD.save(deps.storage, &escrow, &reward_info)?;
+
+    let mut messages = vec![CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: info.sender.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+        funds: vec![],
+    })];
+    if !lp_amount.is_zero() {
+        messages.extend(withdraw_lp_msgs(deps.storage, &env, &config, &staker_addr, lp_amount)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "redeem_receipt"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+            attr("lp_amount", lp_amount),
+        ]))
+}
@@ -0,0 +1,87 @@
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use cosmwasm_std::{Addr, CosmosMsg, CustomQuery, QuerierWrapper, StdResult, Uint128};
+use spectrum::adapters::generator::Generator;
+use spectrum::adapters::incentives::Incentives;
+use spectrum::adapters::staking::StakingAdapter;
+use spectrum::astroport_farm::StakingInterface;
+
+use crate::state::Config;
+
+/// The LP staking contract, called with the interface selected in the config.
+pub enum Staking {
+    Generator(Generator),
+    Incentives(Incentives),
+    Adapter(StakingAdapter),
+}
+
+impl Config {
+    /// Returns the staking contract with the configured interface
+    pub fn staking(&self) -> Staking {
+        match self.staking_interface {
+            StakingInterface::Generator => Staking::Generator(self.staking_contract.clone()),
+            StakingInterface::Incentives => Staking::Incentives(Incentives(self.staking_contract.0.clone())),
+            StakingInterface::Adapter => Staking::Adapter(StakingAdapter(self.staking_contract.0.clone())),
+        }
+    }
+}
+
+impl Staking {
+    /// Returns the pending rewards of the staker, the generator base reward comes first
+    pub fn query_pending_rewards<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        lp_token: &Addr,
+        staker: &Addr,
+        base_reward_token: &AssetInfo,
+    ) -> StdResult<Vec<Asset>> {
+        match self {
+            Staking::Generator(generator) => {
+                let pending_token = generator.query_pending_token(querier, lp_token, staker)?;
+                let mut rewards = vec![base_reward_token.with_balance(pending_token.pending)];
+                if let Some(pending_on_proxy) = pending_token.pending_on_proxy {
+                    rewards.extend(pending_on_proxy);
+                }
+                Ok(rewards)
+            }
+            Staking::Incentives(incentives) => incentives.query_pending_rewards(querier, lp_token, staker),
+            Staking::Adapter(adapter) => adapter.query_pending_rewards(querier, staker),
+        }
+    }
+
+    pub fn query_deposit<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        lp_token: &Addr,
+        staker: &Addr,
+    ) -> StdResult<Uint128> {
+        match self {
+            Staking::Generator(generator) => generator.query_deposit(querier, lp_token, staker),
+            Staking::Incentives(incentives) => incentives.query_deposit(querier, lp_token, staker),
+            Staking::Adapter(adapter) => adapter.query_staked_balance(querier, staker),
+        }
+    }
+
+    pub fn deposit_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        match self {
+            Staking::Generator(generator) => generator.deposit_msg(lp_token, amount),
+            Staking::Incentives(incentives) => incentives.deposit_msg(lp_token, amount),
+            Staking::Adapter(adapter) => adapter.stake_msg(lp_token, amount),
+        }
+    }
+
+    pub fn withdraw_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        match self {
+            Staking::Generator(generator) => generator.withdraw_msg(lp_token, amount),
+            Staking::Incentives(incentives) => incentives.withdraw_msg(lp_token, amount),
+            Staking::Adapter(adapter) => adapter.unstake_msg(amount),
+        }
+    }
+
+    pub fn claim_rewards_msg(&self, lp_tokens: Vec<String>) -> StdResult<CosmosMsg> {
+        match self {
+            Staking::Generator(generator) => generator.claim_rewards_msg(lp_tokens),
+            Staking::Incentives(incentives) => incentives.claim_rewards_msg(lp_tokens),
+            Staking::Adapter(adapter) => adapter.claim_rewards_msg(),
+        }
+    }
+}
@@ -2,14 +2,19 @@ use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, CustomQuery, Decimal, QuerierWrapper, StdResult, Storage, Uint128};
 use cw20::AllowanceResponse;
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
 use astroport::pair::PoolResponse;
 use spectrum::adapters::generator::Generator;
+use spectrum::adapters::gov::Gov;
+use spectrum::adapters::oracle::PriceGuardConfig;
 use spectrum::adapters::pair::Pair;
+use spectrum::astroport_farm::StakingInterface;
 use spectrum::compound_proxy::Compounder;
 use spectrum::helper::{compute_deposit_time, ScalingUint128};
 
+use crate::error::ContractError;
 use crate::ownership::OwnershipProposal;
 
 pub fn default_pair() -> Pair {
@@ -31,6 +36,27 @@ pub struct Config {
     #[serde(default)] pub name: String,
     #[serde(default)] pub symbol: String,
     #[serde(default = "default_pair")] pub pair: Pair,
+    #[serde(default)] pub pauser: Option<Addr>,
+    #[serde(default)] pub deposit_cap: Uint128,
+    #[serde(default)] pub unbonding_period: u64,
+    #[serde(default)] pub min_reward_value: Uint128,
+    #[serde(default)] pub legacy_farm: Option<Addr>,
+    #[serde(default)] pub boost: Option<BoostConfig>,
+    #[serde(default)] pub reserve_bps: u64,
+    #[serde(default)] pub price_guard: Option<PriceGuardConfig>,
+    #[serde(default)] pub staking_interface: StakingInterface,
+    #[serde(default)] pub bond_cooldown: u64,
+    #[serde(default)] pub referral_bps: u64,
+    #[serde(default)] pub receipt_token: Option<Addr>,
+}
+
+/// This structure stores the boost of compounded yield for SPEC stakers.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoostConfig {
+    /// The SPEC governance staking contract
+    pub gov: Gov,
+    /// The share of the working share given without staked SPEC
+    pub base_ratio: Decimal,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -38,11 +64,98 @@ pub const CONFIG: Item<Config> = Item::new("config");
 #[derive(Serialize, Deserialize, Clone,Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub total_bond_share: Uint128,
+
+    /// The block time in seconds of the last compound
+    #[serde(default)] pub last_compound_time: u64,
+    /// The block height of the last compound
+    #[serde(default)] pub last_compound_height: u64,
+    /// The cumulative LP token amount bonded from compound
+    #[serde(default)] pub total_compounded: Uint128,
+    /// The sum of the working shares of the stakers
+    #[serde(default)] pub total_working_share: Uint128,
+    /// The cumulative boosted share minted per working share
+    #[serde(default)] pub boost_index: Decimal,
+    /// The number of times the boost was enabled, stakers not settled since then earn by the base
+    /// working share
+    #[serde(default)] pub boost_epoch: u64,
+    /// The boost index when the boost was last enabled
+    #[serde(default)] pub boost_start_index: Decimal,
+    /// The base ratio when the boost was last enabled
+    #[serde(default)] pub boost_start_ratio: Decimal,
+    /// The LP token amount bonded in the auto-stake bucket, which earns the base reward token
+    /// instead of compounding
+    #[serde(default)] pub total_stake_bond_amount: Uint128,
+    /// The cumulative amount of each reward token per LP token bonded in the auto-stake bucket
+    #[serde(default)] pub stake_reward_indexes: Vec<RewardIndex>,
+    /// The rewards of the auto-stake bucket kept in the contract and not claimed by its stakers
+    #[serde(default)] pub stake_reward_balances: Vec<Asset>,
+    /// The shares held by the reserve, withdrawable only by the owner
+    #[serde(default)] pub reserve_share: Uint128,
+    /// The sum of the shares of stakers with a referrer
+    #[serde(default)] pub total_referred_share: Uint128,
+    /// The cumulative referral fee of each reward token per referred share
+    #[serde(default)] pub referral_indexes: Vec<RewardIndex>,
+    /// The referral fees kept in the contract and not claimed by the referrers
+    #[serde(default)] pub referral_balances: Vec<Asset>,
+}
+
+/// This structure stores the cumulative amount of a reward token per unit, such as a referred
+/// share or an LP token in the auto-stake bucket.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardIndex {
+    pub info: AssetInfo,
+    pub index: Decimal,
+}
+
+/// Adds the amount of the reward token per unit to the index of the token
+fn add_index(indexes: &mut Vec<RewardIndex>, info: &AssetInfo, index: Decimal) {
+    match indexes.iter_mut().find(|it| it.info.equal(info)) {
+        Some(existing) => existing.index += index,
+        None => indexes.push(RewardIndex { info: info.clone(), index }),
+    }
+}
+
+/// Returns the reward tokens accrued by the units since the last indexes
+fn calc_accrued(indexes: &[RewardIndex], last_indexes: &[RewardIndex], units: Uint128) -> Vec<Asset> {
+    indexes.iter()
+        .map(|it| {
+            let last_index = last_indexes.iter()
+                .find(|own| own.info.equal(&it.info))
+                .map(|own| own.index)
+                .unwrap_or_default();
+            it.info.with_balance(units * (it.index - last_index))
+        })
+        .filter(|asset| !asset.amount.is_zero())
+        .collect()
+}
+
+/// Adds the asset to the list, merged with the asset of the same token
+pub fn add_asset(assets: &mut Vec<Asset>, asset: Asset) -> StdResult<()> {
+    match assets.iter_mut().find(|it| it.info.equal(&asset.info)) {
+        Some(existing) => existing.amount = existing.amount.checked_add(asset.amount)?,
+        None => assets.push(asset),
+    }
+    Ok(())
 }
 
 pub const STATE: Item<State> = Item::new("state");
 
 impl State {
+    /// Returns the LP token amount backing the shares, the deposit excluding the auto-stake bucket
+    pub fn calc_compound_balance(&self, lp_balance: Uint128) -> Uint128 {
+        lp_balance.checked_sub(self.total_stake_bond_amount).unwrap_or_default()
+    }
+
+    /// Shares the reward kept for the auto-stake bucket by LP token bonded in it
+    pub fn distribute_stake_reward(&mut self, reward: Asset) -> StdResult<()> {
+        if self.total_stake_bond_amount.is_zero() || reward.amount.is_zero() {
+            return Ok(());
+        }
+        let index = Decimal::from_ratio(reward.amount, self.total_stake_bond_amount);
+        add_index(&mut self.stake_reward_indexes, &reward.info, index);
+        add_asset(&mut self.stake_reward_balances, reward)
+    }
+
     pub fn calc_bond_share(
         &self,
         bond_amount: Uint128,
@@ -61,6 +174,14 @@ impl State {
         }
     }
 
+    pub fn calc_share_price(&self, lp_balance: Uint128) -> Decimal {
+        if self.total_bond_share.is_zero() {
+            Decimal::one()
+        } else {
+            Decimal::from_ratio(lp_balance, self.total_bond_share)
+        }
+    }
+
     pub fn calc_bond_amount(
         &self,
         lp_balance: Uint128,
@@ -72,6 +193,38 @@ impl State {
             lp_balance.multiply_ratio(bond_share, self.total_bond_share)
         }
     }
+
+    /// Shares the referral fee kept for the referrers by referred share
+    pub fn distribute_referral_fee(&mut self, fee: Asset) -> StdResult<()> {
+        if self.total_referred_share.is_zero() || fee.amount.is_zero() {
+            return Ok(());
+        }
+        let index = Decimal::from_ratio(fee.amount, self.total_referred_share);
+        add_index(&mut self.referral_indexes, &fee.info, index);
+        add_asset(&mut self.referral_balances, fee)
+    }
+
+    /// Starts a boost epoch, every staker holds the base working share of their bond share until
+    /// their working share is updated
+    pub fn start_boost(&mut self, base_ratio: Decimal) -> StdResult<()> {
+        self.boost_epoch += 1;
+        self.boost_start_index = self.boost_index;
+        self.boost_start_ratio = base_ratio;
+        self.total_working_share = self.total_bond_share.checked_sub(self.reserve_share)? * base_ratio;
+        Ok(())
+    }
+
+    /// Mints the shares worth the compounded LP amount to the boost index, so the yield is shared
+    /// by working share instead of raising the share price
+    pub fn distribute_boost(&mut self, amount: Uint128, lp_balance: Uint128) -> Uint128 {
+        if self.total_working_share.is_zero() || lp_balance.is_zero() {
+            return Uint128::zero();
+        }
+        let boost_share = amount.multiply_ratio(self.total_bond_share, lp_balance);
+        self.total_bond_share += boost_share;
+        self.boost_index += Decimal::from_ratio(boost_share, self.total_working_share);
+        boost_share
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
@@ -82,9 +235,29 @@ pub struct RewardInfo {
 
     #[serde(default)] pub transfer_share: Uint128,
     #[serde(default)] pub deposit_costs: Vec<Uint128>,
+    #[serde(default)] pub working_share: Uint128,
+    #[serde(default)] pub boost_index: Decimal,
+    #[serde(default)] pub boost_epoch: u64,
+    #[serde(default)] pub auto_stake_ratio: Decimal,
+    #[serde(default)] pub stake_bond_amount: Uint128,
+    #[serde(default)] pub stake_reward_indexes: Vec<RewardIndex>,
+    #[serde(default)] pub pending_stake_rewards: Vec<Asset>,
+    #[serde(default)] pub last_bond_time: u64,
+    #[serde(default)] pub referrer: Option<Addr>,
+    #[serde(default)] pub referred_share: Uint128,
+    #[serde(default)] pub referral_indexes: Vec<RewardIndex>,
 }
 
 impl RewardInfo {
+    /// Returns an error while the bond cooldown since the last own bond of the staker has not elapsed
+    pub fn assert_bond_cooldown(&self, config: &Config, now: u64) -> Result<(), ContractError> {
+        let unbond_at = self.last_bond_time + config.bond_cooldown;
+        if config.bond_cooldown != 0 && now < unbond_at {
+            return Err(ContractError::BondCooldown(unbond_at));
+        }
+        Ok(())
+    }
+
     pub fn ensure_deposit_costs(&mut self, storage: &dyn Storage) -> StdResult<()> {
         if !self.deposit_amount.is_zero() && self.deposit_costs.is_empty() {
             let pool_info = POOL_INFO.load(storage)?;
@@ -131,9 +304,100 @@ impl RewardInfo {
 
 pub const REWARD: Map<&Addr, RewardInfo> = Map::new("reward");
 
+/// Stores the referral fees credited to each referrer and not claimed
+pub const REFERRAL_FEES: Map<&Addr, Vec<Asset>> = Map::new("referral_fees");
+
+/// Stores the keepers allowed to compound in addition to the controller
+pub const KEEPERS: Map<&Addr, bool> = Map::new("keepers");
+
 const DAY: u64 = 86400;
 
 impl RewardInfo {
+    /// Returns the boosted shares minted to the staker since the last settlement. A staker not
+    /// settled since the boost was enabled earns by the base working share of their bond share
+    pub fn calc_pending_boost_share(&self, state: &State) -> Uint128 {
+        if self.boost_epoch == state.boost_epoch {
+            return self.working_share * (state.boost_index - self.boost_index);
+        }
+        self.working_share * (state.boost_start_index - self.boost_index)
+            + self.bond_share * state.boost_start_ratio * (state.boost_index - state.boost_start_index)
+    }
+
+    /// Adds the pending boosted shares to the bond share, must be called before changing the bond
+    /// share or the working share
+    pub fn settle_boost(&mut self, state: &State) {
+        let pending_boost_share = self.calc_pending_boost_share(state);
+        if self.boost_epoch != state.boost_epoch {
+            self.working_share = self.bond_share * state.boost_start_ratio;
+            self.boost_epoch = state.boost_epoch;
+        }
+        self.bond_share += pending_boost_share;
+        self.boost_index = state.boost_index;
+    }
+
+    /// Adds the rewards earned by the auto-stake bucket to the pending rewards, must be called
+    /// before changing the auto-stake bond amount
+    pub fn settle_stake_reward(&mut self, state: &State) -> StdResult<()> {
+        for reward in calc_accrued(&state.stake_reward_indexes, &self.stake_reward_indexes, self.stake_bond_amount) {
+            add_asset(&mut self.pending_stake_rewards, reward)?;
+        }
+        self.stake_reward_indexes = state.stake_reward_indexes.clone();
+        Ok(())
+    }
+
+    /// Credits the referral fees earned by the referred share since the last settlement to the
+    /// referrer, then refers the current bond share. Must be called after changing the bond share
+    pub fn settle_referral(&mut self, storage: &mut dyn Storage, state: &mut State) -> StdResult<()> {
+        let referrer = match &self.referrer {
+            Some(referrer) => referrer,
+            None => return Ok(()),
+        };
+        let fees = calc_accrued(&state.referral_indexes, &self.referral_indexes, self.referred_share);
+        if !fees.is_empty() {
+            let mut pending_fees = REFERRAL_FEES.may_load(storage, referrer)?.unwrap_or_default();
+            for fee in fees {
+                add_asset(&mut pending_fees, fee)?;
+            }
+            REFERRAL_FEES.save(storage, referrer, &pending_fees)?;
+        }
+        self.referral_indexes = state.referral_indexes.clone();
+        state.total_referred_share = state.total_referred_share
+            .checked_sub(self.referred_share)?
+            .checked_add(self.bond_share)?;
+        self.referred_share = self.bond_share;
+
+        Ok(())
+    }
+
+    /// Updates the working share of the staker from their staked SPEC weight
+    pub fn update_working_share<C: CustomQuery>(
+        &mut self,
+        querier: &QuerierWrapper<C>,
+        config: &Config,
+        state: &mut State,
+        staker_addr: &Addr,
+    ) -> StdResult<()> {
+        let working_share = match &config.boost {
+            Some(boost) if !self.bond_share.is_zero() => {
+                let res = boost.gov.query_boost_weight(querier, staker_addr)?;
+                let mut working_share = self.bond_share * boost.base_ratio;
+                if !res.total_weight.is_zero() {
+                    working_share += state.total_bond_share
+                        .multiply_ratio(res.weight, res.total_weight)
+                        * (Decimal::one() - boost.base_ratio);
+                }
+                working_share.min(self.bond_share)
+            }
+            _ => Uint128::zero(),
+        };
+        state.total_working_share = state.total_working_share
+            .checked_sub(self.working_share)?
+            .checked_add(working_share)?;
+        self.working_share = working_share;
+
+        Ok(())
+    }
+
     pub fn calc_user_balance(&self, state: &State, lp_balance: Uint128, time: u64) -> Uint128 {
         let amount = state.calc_bond_amount(lp_balance, self.bond_share);
         let deposit_time = time - self.deposit_time;
@@ -148,10 +412,34 @@ impl RewardInfo {
 /// Stores the latest proposal to change contract ownership
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
 
+/// This structure stores the reward balances of the contract before the generator claim, the
+/// claimed rewards are the balance increase measured in the reply.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CompoundSnapshot {
+    /// The balance of each reward token before the claim
+    pub balances: Vec<Asset>,
+    /// The minimum expected amount of LP token
+    pub minimum_receive: Option<Uint128>,
+    /// Slippage tolerance when providing LP
+    pub slippage_tolerance: Option<Decimal>,
+}
+
+/// Stores the snapshot of the compound in progress, removed in the claim reply
+pub const COMPOUND_SNAPSHOT: Item<CompoundSnapshot> = Item::new("compound_snapshot");
+
+/// Stores the rewards claimed by harvest and not compounded yet, added to the next compound
+pub const HARVESTED: Item<Vec<Asset>> = Item::new("harvested");
+
 pub enum ScalingOperation {
     Truncate,
     Ceil,
 }
 
+/// Stores the LP amount waiting for the unbonding period, keyed by staker and release time
+pub const CLAIMS: Map<(&Addr, u64), Uint128> = Map::new("claims");
+
+/// Stores the share price after each compound, keyed by block time
+pub const SHARE_PRICE_CHECKPOINTS: Map<u64, Decimal> = Map::new("share_price_checkpoints");
+
 pub const ALLOWANCES: Map<(&Addr, &Addr), AllowanceResponse> = Map::new("allowance");
 pub const POOL_INFO: Item<PoolResponse> = Item::new("pool_info");
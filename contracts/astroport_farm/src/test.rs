@@ -1,26 +1,33 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate as migrate_contract, query, reply};
 use crate::error::ContractError;
-use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::{Config, State};
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier, TOTAL_WEIGHT};
+use crate::state::{Config, CONFIG};
 
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{native_asset, token_asset, Asset, AssetInfo};
 use astroport::generator::{
     Cw20HookMsg as GeneratorCw20HookMsg, ExecuteMsg as GeneratorExecuteMsg,
 };
+use astroport::pair::Cw20HookMsg as PairCw20HookMsg;
 
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
-    Timestamp, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Decimal256, Env, Event, OwnedDeps, Reply, Response,
+    StdError, SubMsg, SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg,
 };
 use cw20::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo, AllowanceResponse, BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration, Logo, MarketingInfoResponse, MinterResponse, TokenInfoResponse};
 use spectrum::adapters::generator::Generator;
+use spectrum::adapters::incentives::{Cw20HookMsg as IncentivesCw20HookMsg, ExecuteMsg as IncentivesExecuteMsg};
+use spectrum::adapters::oracle::{Oracle, PriceGuardConfig};
 use spectrum::adapters::pair::Pair;
+use spectrum::adapters::staking::{Cw20HookMsg as StakingAdapterCw20HookMsg, ExecuteMsg as StakingAdapterExecuteMsg};
 use spectrum::astroport_farm::{
-    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem,
+    Boost, CallbackMsg, ClaimInfo, ClaimsResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ReferralInfoResponse, RewardInfoResponse, RewardInfoResponseItem, SharePriceCheckpoint, SharePriceHistoryResponse, StakeInfoResponse,
+    StakingInterface, StateResponse, UserInfoResponse,
 };
 use spectrum::compound_proxy::{Compounder, ExecuteMsg as CompoundProxyExecuteMsg};
+use spectrum::oracle::PriceGuard;
+use spectrum::vault::ExchangeRateResponse;
 
 const ASTRO_TOKEN: &str = "astro";
 const REWARD_TOKEN: &str = "reward";
@@ -37,6 +44,12 @@ const CONTROLLER_2: &str = "controller_2";
 const FEE_COLLECTOR_2: &str = "fee_collector_2";
 const LP_TOKEN: &str = "lp_token";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const LEGACY_FARM: &str = "legacy_farm";
+const GOV: &str = "gov";
+const ORACLE: &str = "oracle";
+const LP_DENOM: &str = "factory/pair/uLP";
+const INCENTIVES: &str = "incentives";
+const STAKING_ADAPTER: &str = "staking_adapter";
 
 #[test]
 fn test() -> Result<(), ContractError> {
@@ -49,6 +62,28 @@ fn test() -> Result<(), ContractError> {
     compound(&mut deps)?;
     callback(&mut deps)?;
     cw20(&mut deps)?;
+    emergency_unbond(&mut deps)?;
+    pause(&mut deps)?;
+    harvest(&mut deps)?;
+    dual_reward(&mut deps)?;
+    sweep_rewards(&mut deps)?;
+    deposit_cap(&mut deps)?;
+    unbonding_period(&mut deps)?;
+    min_reward_value(&mut deps)?;
+    unbond_as_asset(&mut deps)?;
+    migrate_stake(&mut deps)?;
+    boost(&mut deps)?;
+    auto_stake(&mut deps)?;
+    reserve(&mut deps)?;
+    price_guard(&mut deps)?;
+    bond_cooldown(&mut deps)?;
+    migrate(&mut deps)?;
+    token_factory()?;
+    ibc_hook()?;
+    incentives()?;
+    staking_adapter()?;
+    referral()?;
+    receipt_token()?;
 
     Ok(())
 }
@@ -61,6 +96,21 @@ fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     }
 }
 
+/// Claims the pending rewards on the generator and replies to the claim submessage of compound
+fn claim_rewards(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+) -> Result<Response, ContractError> {
+    deps.querier.claim_rewards(GENERATOR_PROXY, MOCK_CONTRACT_ADDR);
+    reply(deps.as_mut(), env, Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    })
+}
+
 fn create(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -71,6 +121,7 @@ fn create(
     let msg = InstantiateMsg {
         owner: USER_1.to_string(),
         staking_contract: GENERATOR_PROXY.to_string(),
+        staking_interface: None,
         compound_proxy: COMPOUND_PROXY.to_string(),
         controller: CONTROLLER.to_string(),
         fee: Decimal::percent(101),
@@ -80,6 +131,10 @@ fn create(
         name: "name".to_string(),
         symbol: "SYMBOL".to_string(),
         pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "fee must be 0 to 1");
@@ -88,6 +143,7 @@ fn create(
     let msg = InstantiateMsg {
         owner: USER_1.to_string(),
         staking_contract: GENERATOR_PROXY.to_string(),
+        staking_interface: None,
         compound_proxy: COMPOUND_PROXY.to_string(),
         controller: CONTROLLER.to_string(),
         fee: Decimal::percent(5),
@@ -97,6 +153,10 @@ fn create(
         name: "name".to_string(),
         symbol: "SYMBOL".to_string(),
         pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
     };
 
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
@@ -112,6 +172,7 @@ fn create(
             controller: Addr::unchecked(CONTROLLER),
             fee_collector: Addr::unchecked(FEE_COLLECTOR),
             staking_contract: Generator(Addr::unchecked(GENERATOR_PROXY)),
+            staking_interface: StakingInterface::Generator,
             compound_proxy: Compounder(Addr::unchecked(COMPOUND_PROXY)),
             fee: Decimal::percent(5),
             liquidity_token: Addr::unchecked(LP_TOKEN.to_string()),
@@ -119,6 +180,17 @@ fn create(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            pauser: None,
+            deposit_cap: Uint128::zero(),
+            unbonding_period: 0,
+            min_reward_value: Uint128::zero(),
+            legacy_farm: None,
+            boost: None,
+            reserve_bps: 0,
+            price_guard: None,
+            bond_cooldown: 0,
+            referral_bps: 0,
+            receipt_token: None,
         }
     );
 
@@ -136,6 +208,16 @@ fn config(
         controller: None,
         fee: Some(Decimal::percent(101)),
         fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -149,15 +231,38 @@ fn config(
         controller: None,
         fee: Some(Decimal::percent(3)),
         fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
     };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
-    assert!(res.is_ok());
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "update_config"), attr("fee", "0.03")]
+    );
 
     let msg = ExecuteMsg::UpdateConfig {
         compound_proxy: Some(COMPOUND_PROXY_2.to_string()),
         controller: None,
         fee: None,
         fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -167,6 +272,16 @@ fn config(
         controller: Some(CONTROLLER_2.to_string()),
         fee: None,
         fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -176,9 +291,22 @@ fn config(
         controller: None,
         fee: None,
         fee_collector: Some(FEE_COLLECTOR_2.to_string()),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
     };
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
-    assert!(res.is_ok());
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "update_config"), attr("fee_collector", FEE_COLLECTOR_2)]
+    );
 
     let msg = QueryMsg::Config {};
     let res: Config = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
@@ -189,6 +317,7 @@ fn config(
             controller: Addr::unchecked(CONTROLLER_2),
             fee_collector: Addr::unchecked(FEE_COLLECTOR_2),
             staking_contract: Generator(Addr::unchecked(GENERATOR_PROXY)),
+            staking_interface: StakingInterface::Generator,
             compound_proxy: Compounder(Addr::unchecked(COMPOUND_PROXY_2)),
             fee: Decimal::percent(3),
             liquidity_token: Addr::unchecked(LP_TOKEN.to_string()),
@@ -196,6 +325,17 @@ fn config(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            pauser: None,
+            deposit_cap: Uint128::zero(),
+            unbonding_period: 0,
+            min_reward_value: Uint128::zero(),
+            legacy_farm: None,
+            boost: None,
+            reserve_bps: 0,
+            price_guard: None,
+            bond_cooldown: 0,
+            referral_bps: 0,
+            receipt_token: None,
         }
     );
 
@@ -204,6 +344,16 @@ fn config(
         controller: Some(CONTROLLER.to_string()),
         fee: Some(Decimal::percent(5)),
         fee_collector: Some(FEE_COLLECTOR.to_string()),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -217,6 +367,7 @@ fn config(
             controller: Addr::unchecked(CONTROLLER),
             fee_collector: Addr::unchecked(FEE_COLLECTOR),
             staking_contract: Generator(Addr::unchecked(GENERATOR_PROXY)),
+            staking_interface: StakingInterface::Generator,
             compound_proxy: Compounder(Addr::unchecked(COMPOUND_PROXY)),
             fee: Decimal::percent(5),
             liquidity_token: Addr::unchecked(LP_TOKEN.to_string()),
@@ -224,6 +375,17 @@ fn config(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            pauser: None,
+            deposit_cap: Uint128::zero(),
+            unbonding_period: 0,
+            min_reward_value: Uint128::zero(),
+            legacy_farm: None,
+            boost: None,
+            reserve_bps: 0,
+            price_guard: None,
+            bond_cooldown: 0,
+            referral_bps: 0,
+            receipt_token: None,
         }
     );
 
@@ -336,17 +498,27 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_1.to_string(),
         amount: Uint128::from(100000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "Unauthorized");
 
-    // user_1 bond 100000 LP
+    // the first bond must cover the locked shares
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Initial bond must be greater than 1000");
+
+    // user_1 bond 100000 LP, 1000 shares are locked in the farm
     let info = mock_info(LP_TOKEN, &[]);
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_1.to_string(),
         amount: Uint128::from(100000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
@@ -383,14 +555,14 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(100000u128),
-                    deposit_amount: Uint128::from(100000u128),
+                    bond_share: Uint128::from(99000u128),
+                    deposit_amount: Uint128::from(99000u128),
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(100000u128),
+                    bond_amount: Uint128::from(99000u128),
                     deposit_costs: vec![
-                        Uint128::from(100000u128),
-                        Uint128::from(100000u128),
+                        Uint128::from(99000u128),
+                        Uint128::from(99000u128),
                     ],
                 }
             }
@@ -407,9 +579,19 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         amount: Uint128::from(50000u128),
         msg: to_binary(&Cw20HookMsg::Bond {
             staker_addr: Some(USER_2.to_string()),
+            referrer: None,
         })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("staker_addr", USER_2),
+            attr("amount", "50000"),
+            attr("bond_amount", "50000"),
+        ]
+    );
     assert_eq!(
         res.messages
             .into_iter()
@@ -461,11 +643,19 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
 
     // query state
     let msg = QueryMsg::State {};
-    let res: State = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    let res: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
     assert_eq!(
         res,
-        State {
+        StateResponse {
+            total_bond_amount: Uint128::from(150000u128),
             total_bond_share: Uint128::from(150000u128),
+            share_price: Decimal::one(),
+            total_stake_bond_amount: Uint128::zero(),
+            reserve_share: Uint128::zero(),
+            reserve_amount: Uint128::zero(),
+            last_compound_time: 0,
+            last_compound_height: 0,
+            total_compounded: Uint128::zero(),
         }
     );
 
@@ -476,7 +666,20 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         Uint128::from(180000u128),
     );
 
-    // query reward info for user_1, bond amount should be 100000 + 20000 = 120000
+    // the exchange rate follows the compounded LP token
+    let msg = QueryMsg::ExchangeRate {};
+    let res: ExchangeRateResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        ExchangeRateResponse {
+            asset: AssetInfo::Token { contract_addr: Addr::unchecked(LP_TOKEN) },
+            exchange_rate: Decimal::from_ratio(6u128, 5u128),
+            total_shares: Uint128::from(150000u128),
+            total_assets: Uint128::from(180000u128),
+        }
+    );
+
+    // query reward info for user_1, bond amount should be 99000 + 19800 = 118800
     let msg = QueryMsg::RewardInfo {
         staker_addr: USER_1.to_string(),
     };
@@ -487,14 +690,14 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(100000u128),
-                    deposit_amount: Uint128::from(100000u128),
+                    bond_share: Uint128::from(99000u128),
+                    deposit_amount: Uint128::from(99000u128),
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(120000u128),
+                    bond_amount: Uint128::from(118800u128),
                     deposit_costs: vec![
-                        Uint128::from(100000u128),
-                        Uint128::from(100000u128),
+                        Uint128::from(99000u128),
+                        Uint128::from(99000u128),
                     ],
                 }
             }
@@ -526,24 +729,48 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         }
     );
 
+    // query user info for user_1, each share is worth 1.2 LP
+    let msg = QueryMsg::UserInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let res: UserInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        UserInfoResponse {
+            staker_addr: USER_1.to_string(),
+            shares: Uint128::from(99000u128),
+            lp_amount: Uint128::from(118800u128),
+            share_price: Decimal::from_ratio(6u128, 5u128),
+        }
+    );
+
     // unbond error for new user
     let info = mock_info(USER_3, &[]);
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(100u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    assert_error(res, "spectrum_astroport_farm::state::RewardInfo not found");
+    assert_error(res, "IDC_astroport_farm::state::RewardInfo not found");
 
     // unbond for user_1
     let info = mock_info(USER_1, &[]);
     let msg = ExecuteMsg::Unbond {
-        amount: Uint128::from(120001u128),
+        amount: Uint128::from(118801u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "Cannot unbond more than balance");
 
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(60000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
@@ -578,7 +805,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         Uint128::from(120000u128),
     );
 
-    // query reward info for user_1, bond amount should be 120000 - 60000 = 60000
+    // query reward info for user_1, bond amount should be 118800 - 60000 = 58800
     let msg = QueryMsg::RewardInfo {
         staker_addr: USER_1.to_string(),
     };
@@ -589,14 +816,14 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(50000u128),
-                    deposit_amount: Uint128::from(50000u128),
+                    bond_share: Uint128::from(49000u128),
+                    deposit_amount: Uint128::from(49000u128),
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(60000u128),
+                    bond_amount: Uint128::from(58800u128),
                     deposit_costs: vec![
-                        Uint128::from(50000u128),
-                        Uint128::from(50000u128),
+                        Uint128::from(49000u128),
+                        Uint128::from(49000u128),
                     ],
                 }
             }
@@ -632,6 +859,9 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
     let info = mock_info(USER_2, &[]);
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(60000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
@@ -699,14 +929,14 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(50000u128),
-                    deposit_amount: Uint128::from(50000u128),
+                    bond_share: Uint128::from(49000u128),
+                    deposit_amount: Uint128::from(49000u128),
                     deposit_time: 101,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(60000u128),
+                    bond_amount: Uint128::from(58800u128),
                     deposit_costs: vec![
-                        Uint128::from(50000u128),
-                        Uint128::from(50000u128),
+                        Uint128::from(49000u128),
+                        Uint128::from(49000u128),
                     ],
                 }
             }
@@ -744,6 +974,8 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         minimum_receive: Some(Uint128::from(10000u128)),
         no_swap: None,
         slippage_tolerance: Some(Decimal::percent(2)),
+        staker_addr: None,
+        referrer: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
@@ -791,6 +1023,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     to: None,
                     no_swap: None,
                     slippage_tolerance: Some(Decimal::percent(2)),
+                    source: None,
                 })?,
                 funds: vec![Coin {
                     denom: IBC_TOKEN.to_string(),
@@ -801,22 +1034,31 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                 contract_addr: MOCK_CONTRACT_ADDR.to_string(),
                 msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::BondTo {
                     to: Addr::unchecked(USER_1),
+                    sender: Addr::unchecked(USER_1),
                     prev_balance: Uint128::from(142u128),
                     minimum_receive: Some(Uint128::from(10000u128)),
+                    referrer: None,
                 }))?,
                 funds: vec![],
             }),
         ]
     );
 
+    // bond on behalf of another staker
     let msg = ExecuteMsg::BondAssets {
         assets: assets.clone(),
         minimum_receive: Some(Uint128::from(10000u128)),
         no_swap: Some(true),
         slippage_tolerance: None,
+        staker_addr: Some(USER_2.to_string()),
+        referrer: None,
     };
 
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "bond_assets"), attr("staker_addr", USER_2)]
+    );
     assert_eq!(
         res.messages
             .into_iter()
@@ -848,24 +1090,123 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
                     to: None,
                     no_swap: Some(true),
                     slippage_tolerance: None,
+                    source: None,
                 })?,
                 funds: vec![Coin {
                     denom: IBC_TOKEN.to_string(),
                     amount: Uint128::from(40000u128),
                 }],
             }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::BondTo {
+                    to: Addr::unchecked(USER_2),
+                    sender: Addr::unchecked(USER_1),
+                    prev_balance: Uint128::from(142u128),
+                    minimum_receive: Some(Uint128::from(10000u128)),
+                    referrer: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // bond a single cw20 asset
+    let bond_asset_msg = to_binary(&Cw20HookMsg::BondAsset {
+        minimum_receive: Some(Uint128::from(10000u128)),
+        slippage_tolerance: None,
+        staker_addr: None,
+        referrer: None,
+    })?;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(20000u128),
+        msg: bond_asset_msg.clone(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg.clone());
+    assert_error(res, "Invalid message");
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(REWARD_TOKEN, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "bond_asset"), attr("staker_addr", USER_1)]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(20000u128),
+                    expires: Some(Expiration::AtHeight(601))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: Addr::unchecked(REWARD_TOKEN),
+                        },
+                        amount: Uint128::from(20000u128),
+                    }],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    source: None,
+                })?,
+                funds: vec![],
+            }),
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: MOCK_CONTRACT_ADDR.to_string(),
                 msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::BondTo {
                     to: Addr::unchecked(USER_1),
+                    sender: Addr::unchecked(USER_1),
                     prev_balance: Uint128::from(142u128),
                     minimum_receive: Some(Uint128::from(10000u128)),
+                    referrer: None,
                 }))?,
                 funds: vec![],
             }),
         ]
     );
 
+    // bond a single cw20 asset on behalf of another staker
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(20000u128),
+        msg: to_binary(&Cw20HookMsg::BondAsset {
+            minimum_receive: None,
+            slippage_tolerance: None,
+            staker_addr: Some(USER_2.to_string()),
+            referrer: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(REWARD_TOKEN, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "bond_asset"), attr("staker_addr", USER_2)]
+    );
+    assert_eq!(
+        res.messages[2].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::BondTo {
+                to: Addr::unchecked(USER_2),
+                sender: Addr::unchecked(USER_1),
+                prev_balance: Uint128::from(142u128),
+                minimum_receive: None,
+                referrer: None,
+            }))?,
+            funds: vec![],
+        })
+    );
+
     // update time
     env.block.time = Timestamp::from_seconds(200201);
 
@@ -878,8 +1219,10 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
 
     let msg = ExecuteMsg::Callback(CallbackMsg::BondTo {
         to: Addr::unchecked(USER_1),
+        sender: Addr::unchecked(USER_1),
         prev_balance: Uint128::from(142u128),
         minimum_receive: Some(Uint128::from(10000u128)),
+        referrer: None,
     });
     let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
     // received less LP token than minimum_receive, received 10141 - 142 = 9999 LP
@@ -924,7 +1267,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         Uint128::from(70000u128),
     );
 
-    // query reward info for user_1, bond amount should be 60000 + 10000 = 70000
+    // query reward info for user_1, bond amount should be 58800 + 9999 = 68799
     let msg = QueryMsg::RewardInfo {
         staker_addr: USER_1.to_string(),
     };
@@ -935,14 +1278,14 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(58333u128),
-                    deposit_amount: Uint128::from(59999u128),
-                    deposit_time: 33448,
+                    bond_share: Uint128::from(57333u128),
+                    deposit_amount: Uint128::from(58999u128),
+                    deposit_time: 34013,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(70000u128),
+                    bond_amount: Uint128::from(68799u128),
                     deposit_costs: vec![
-                        Uint128::from(59999u128),
-                        Uint128::from(59999u128),
+                        Uint128::from(58999u128),
+                        Uint128::from(58999u128),
                     ],
                 }
             }
@@ -951,11 +1294,19 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
 
     // query state
     let msg = QueryMsg::State {};
-    let res: State = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    let res: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
     assert_eq!(
         res,
-        State {
+        StateResponse {
+            total_bond_amount: Uint128::from(70000u128),
             total_bond_share: Uint128::from(58333u128),
+            share_price: Decimal::from_ratio(70000u128, 58333u128),
+            total_stake_bond_amount: Uint128::zero(),
+            reserve_share: Uint128::zero(),
+            reserve_amount: Uint128::zero(),
+            last_compound_time: 0,
+            last_compound_height: 0,
+            total_compounded: Uint128::zero(),
         }
     );
 
@@ -973,7 +1324,7 @@ fn deposit_time(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_3.to_string(),
         amount: Uint128::from(10000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
     });
     let res = execute(deps.as_mut(), env.clone(), info, msg);
     assert!(res.is_ok());
@@ -1037,7 +1388,7 @@ fn deposit_time(
         }
     );
 
-    // query reward info for user_1, should be 74375
+    // query reward info for user_1, should be 73100
     let msg = QueryMsg::RewardInfo {
         staker_addr: USER_1.to_string(),
     };
@@ -1048,14 +1399,14 @@ fn deposit_time(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(58333u128),
-                    deposit_amount: Uint128::from(59999u128),
-                    deposit_time: 33448,
+                    bond_share: Uint128::from(57333u128),
+                    deposit_amount: Uint128::from(58999u128),
+                    deposit_time: 34013,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(74375u128),
+                    bond_amount: Uint128::from(73100u128),
                     deposit_costs: vec![
-                        Uint128::from(59999u128),
-                        Uint128::from(59999u128),
+                        Uint128::from(58999u128),
+                        Uint128::from(58999u128),
                     ],
                 }
             }
@@ -1097,6 +1448,9 @@ fn deposit_time(
     let info = mock_info(USER_3, &[]);
     let msg = ExecuteMsg::Unbond {
         amount: Uint128::from(10311u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
@@ -1131,7 +1485,7 @@ fn deposit_time(
         Uint128::from(74689u128),
     );
 
-    // query reward info for user_1, should be 74375 + 312 (from user_3 penalty)= 74687
+    // query reward info for user_1, should be 73100 + 308 (from user_3 penalty)= 73408
     let msg = QueryMsg::RewardInfo {
         staker_addr: USER_1.to_string(),
     };
@@ -1142,14 +1496,14 @@ fn deposit_time(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(58333u128),
-                    deposit_amount: Uint128::from(59999u128),
-                    deposit_time: 33448,
+                    bond_share: Uint128::from(57333u128),
+                    deposit_amount: Uint128::from(58999u128),
+                    deposit_time: 34013,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(74689u128),
+                    bond_amount: Uint128::from(73408u128),
                     deposit_costs: vec![
-                        Uint128::from(59999u128),
-                        Uint128::from(59999u128),
+                        Uint128::from(58999u128),
+                        Uint128::from(58999u128),
                     ],
                 }
             }
@@ -1195,14 +1549,42 @@ fn compound(
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
 
+    // keepers can execute compound too
+    let add_keeper = ExecuteMsg::AddKeeper {
+        keeper: USER_1.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), add_keeper.clone());
+    assert_error(res, "Unauthorized");
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), add_keeper)?;
+    assert_eq!(res.attributes, vec![attr("action", "add_keeper"), attr("keeper", USER_1)]);
+    let keepers: Vec<String> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Keepers {})?)?;
+    assert_eq!(keepers, vec![USER_1.to_string()]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(res.attributes, vec![attr("action", "claim_rewards")]);
+
+    let remove_keeper = ExecuteMsg::RemoveKeeper {
+        keeper: USER_1.to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), remove_keeper)?;
+    let keepers: Vec<String> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Keepers {})?)?;
+    assert!(keepers.is_empty());
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // 1000 ASTRO held before the claim is not compounded
+    deps.querier.set_balance(
+        ASTRO_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000u128),
+    );
+
+    // rewards are claimed with a reply
     let info = mock_info(CONTROLLER, &[]);
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "claim_rewards")]);
     assert_eq!(
-        res.messages
-            .into_iter()
-            .map(|it| it.msg)
-            .collect::<Vec<CosmosMsg>>(),
-        [
+        res.messages,
+        vec![SubMsg::reply_on_success(
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: GENERATOR_PROXY.to_string(),
                 msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
@@ -1210,6 +1592,59 @@ fn compound(
                 })?,
                 funds: vec![],
             }),
+            1,
+        )]
+    );
+
+    // the reply is unknown without a compound in progress
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 99,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    });
+    assert_error(res, "Unknown reply id");
+
+    // the claimed rewards are measured from the balances
+    let res = claim_rewards(deps, env.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "9500"),
+            attr("commission_amount", "500"),
+            attr("token", REWARD_TOKEN),
+            attr("compound_amount", "47500"),
+            attr("commission_amount", "2500"),
+        ]
+    );
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new("spectrum/compound")
+                .add_attribute("stage", "harvest")
+                .add_attribute("reward_asset", ASTRO_TOKEN)
+                .add_attribute("reward_amount", "10000")
+                .add_attribute("fee_asset", ASTRO_TOKEN)
+                .add_attribute("fee_amount", "500"),
+            Event::new("spectrum/compound")
+                .add_attribute("stage", "harvest")
+                .add_attribute("reward_asset", REWARD_TOKEN)
+                .add_attribute("reward_amount", "50000")
+                .add_attribute("fee_asset", REWARD_TOKEN)
+                .add_attribute("fee_amount", "2500"),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: ASTRO_TOKEN.to_string(),
                 msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
@@ -1264,6 +1699,7 @@ fn compound(
                     to: None,
                     no_swap: None,
                     slippage_tolerance: Some(Decimal::percent(3)),
+                    source: None,
                 })?,
                 funds: vec![],
             }),
@@ -1278,6 +1714,12 @@ fn compound(
         ]
     );
 
+    // compound is tracked in state
+    let msg = QueryMsg::State {};
+    let res: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.last_compound_time, env.block.time.seconds());
+    assert_eq!(res.last_compound_height, 700);
+
     // receive 29899 LP token from compound proxy
     deps.querier.set_balance(
         LP_TOKEN.to_string(),
@@ -1304,6 +1746,12 @@ fn compound(
         Uint128::from(29901u128),
     );
     let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.events,
+        vec![Event::new("spectrum/compound")
+            .add_attribute("stage", "stake")
+            .add_attribute("lp_minted", "29900")]
+    );
     assert_eq!(
         res.messages
             .into_iter()
@@ -1320,6 +1768,31 @@ fn compound(
         }),]
     );
 
+    let msg = QueryMsg::State {};
+    let state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(state.total_compounded, Uint128::from(29900u128));
+
+    // the share price after the compound, including the LP token being staked, is recorded
+    let msg = QueryMsg::SharePriceHistory {
+        start_after: None,
+        limit: None,
+    };
+    let res: SharePriceHistoryResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res.checkpoints,
+        vec![SharePriceCheckpoint {
+            time: env.block.time.seconds(),
+            share_price: Decimal::from_ratio(state.total_bond_amount + Uint128::from(29900u128), state.total_bond_share),
+        }]
+    );
+
+    let msg = QueryMsg::SharePriceHistory {
+        start_after: Some(env.block.time.seconds()),
+        limit: None,
+    };
+    let res: SharePriceHistoryResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.checkpoints, vec![]);
+
     Ok(())
 }
 
@@ -1341,8 +1814,10 @@ fn callback(
 
     let msg = ExecuteMsg::Callback(CallbackMsg::BondTo {
         to: Addr::unchecked(USER_1),
+        sender: Addr::unchecked(USER_1),
         prev_balance: Uint128::zero(),
         minimum_receive: None,
+        referrer: None,
     });
     let info = mock_info(USER_1, &[]);
 
@@ -1372,7 +1847,7 @@ fn cw20(
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: USER_3.to_string(),
         amount: Uint128::from(200000u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
     });
     execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
 
@@ -1426,6 +1901,7 @@ fn cw20(
         contract: FEE_COLLECTOR_2.to_string(),
         msg: to_binary(&Cw20HookMsg::Bond {
             staker_addr: None,
+            referrer: None,
         })?,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
@@ -1442,6 +1918,7 @@ fn cw20(
                     amount: Uint128::from(50000u128),
                     msg: to_binary(&Cw20HookMsg::Bond {
                         staker_addr: None,
+                        referrer: None,
                     })?,
                 }))?,
                 funds: vec![],
@@ -1459,14 +1936,14 @@ fn cw20(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
-                    bond_share: Uint128::from(108333u128),
-                    deposit_amount: Uint128::from(124018u128),
-                    deposit_time: 811389522,
+                    bond_share: Uint128::from(107333u128),
+                    deposit_amount: Uint128::from(123018u128),
+                    deposit_time: 817985219,
                     staking_token: LP_TOKEN.to_string(),
-                    bond_amount: Uint128::from(138708u128),
+                    bond_amount: Uint128::from(137428u128),
                     deposit_costs: vec![
-                        Uint128::from(124018u128),
-                        Uint128::from(124018u128),
+                        Uint128::from(123018u128),
+                        Uint128::from(123018u128),
                     ],
                 }
             }
@@ -1796,6 +2273,7 @@ fn cw20(
         amount: Uint128::from(20000u128),
         msg: to_binary(&Cw20HookMsg::Bond {
             staker_addr: None,
+            referrer: None,
         })?,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
@@ -1812,6 +2290,7 @@ fn cw20(
                     amount: Uint128::from(20000u128),
                     msg: to_binary(&Cw20HookMsg::Bond {
                         staker_addr: None,
+                        referrer: None,
                     })?,
                 }))?,
                 funds: vec![],
@@ -1937,3 +2416,2580 @@ fn cw20(
 
     Ok(())
 }
+
+fn emergency_unbond(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    // unknown user
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), ExecuteMsg::EmergencyUnbond {});
+    assert_error(res, "IDC_astroport_farm::state::RewardInfo not found");
+
+    // withdraw the whole position of user_1 without compound
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    let amount = res.reward_info.bond_amount;
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::EmergencyUnbond {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_1.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.reward_info.bond_share, Uint128::zero());
+
+    // nothing left to withdraw
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::EmergencyUnbond {});
+    assert_error(res, "Invalid zero amount");
+
+    Ok(())
+}
+
+fn pause(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    // set pauser
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: Some(USER_3.to_string()),
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // only owner and pauser can pause
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), ExecuteMsg::Pause {});
+    assert_error(res, "Unauthorized");
+
+    execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::Pause {})?;
+    let paused: bool = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Paused {})?)?;
+    assert!(paused);
+
+    // bond and compound are halted
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg);
+    assert_error(res, "Contract is paused");
+
+    let msg = ExecuteMsg::BondAssets {
+        assets: vec![],
+        minimum_receive: None,
+        no_swap: None,
+        slippage_tolerance: None,
+        staker_addr: None,
+        referrer: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg);
+    assert_error(res, "Contract is paused");
+
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg.clone());
+    assert_error(res, "Contract is paused");
+
+    // unbond is still available
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_3.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    let msg = ExecuteMsg::Unbond {
+        amount: res.reward_info.bond_amount,
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), msg)?;
+
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), ExecuteMsg::Unpause {})?;
+    let paused: bool = from_binary(&query(deps.as_ref(), env, QueryMsg::Paused {})?)?;
+    assert!(!paused);
+
+    Ok(())
+}
+
+fn harvest(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.height = 700;
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(1000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::from(2000u128),
+    );
+
+    execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::Pause {})?;
+
+    // only owner, controller and pauser can harvest
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), ExecuteMsg::Harvest {});
+    assert_error(res, "Unauthorized");
+
+    // rewards are claimed while paused
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::Harvest {})?;
+    assert_eq!(res.attributes, vec![attr("action", "harvest")]);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            2,
+        )]
+    );
+
+    // the claimed rewards are buffered without compounding
+    deps.querier.claim_rewards(GENERATOR_PROXY, MOCK_CONTRACT_ADDR);
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 2,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    })?;
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "harvest_rewards"),
+            attr("token", ASTRO_TOKEN),
+            attr("harvest_amount", "1000"),
+            attr("token", REWARD_TOKEN),
+            attr("harvest_amount", "2000"),
+        ]
+    );
+
+    let harvested: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Harvested {})?)?;
+    assert_eq!(
+        harvested,
+        vec![
+            token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(1000u128)),
+            token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(2000u128)),
+        ]
+    );
+
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), ExecuteMsg::Unpause {})?;
+
+    // the buffered rewards are compounded along with the next claim
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    let res = claim_rewards(deps, env.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "1900"),
+            attr("commission_amount", "100"),
+            attr("token", REWARD_TOKEN),
+            attr("compound_amount", "3800"),
+            attr("commission_amount", "200"),
+        ]
+    );
+
+    let harvested: Vec<Asset> = from_binary(&query(deps.as_ref(), env, QueryMsg::Harvested {})?)?;
+    assert_eq!(harvested, vec![]);
+
+    Ok(())
+}
+
+fn dual_reward(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.height = 700;
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        LP_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::zero(),
+    );
+
+    // proxy rewards paid in the base reward token and in a pool asset are merged
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    deps.querier.set_proxy_rewards(Some(vec![
+        Asset {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(ASTRO_TOKEN),
+            },
+            amount: Uint128::from(2000u128),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "denom1".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+        Asset {
+            info: AssetInfo::NativeToken {
+                denom: "denom1".to_string(),
+            },
+            amount: Uint128::from(1000u128),
+        },
+    ]));
+
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    let res = claim_rewards(deps, env)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "11400"),
+            attr("commission_amount", "600"),
+            attr("token", "denom1"),
+            attr("compound_amount", "1900"),
+            attr("commission_amount", "100"),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: COMPOUND_PROXY.to_string(),
+                    amount: Uint128::from(11400u128),
+                    expires: Some(Expiration::AtHeight(701))
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: FEE_COLLECTOR.to_string(),
+                    amount: Uint128::from(600u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: FEE_COLLECTOR.to_string(),
+                amount: vec![Coin {
+                    denom: "denom1".to_string(),
+                    amount: Uint128::from(100u128),
+                }],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![
+                        Asset {
+                            info: AssetInfo::Token {
+                                contract_addr: Addr::unchecked(ASTRO_TOKEN),
+                            },
+                            amount: Uint128::from(11400u128),
+                        },
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "denom1".to_string(),
+                            },
+                            amount: Uint128::from(1900u128),
+                        },
+                    ],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    source: None,
+                })?,
+                funds: vec![Coin {
+                    denom: "denom1".to_string(),
+                    amount: Uint128::from(1900u128),
+                }],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::Stake {
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    deps.querier.set_proxy_rewards(None);
+    // the claimed denom1 was sent to the compound proxy
+    deps.querier.set_balance(
+        "denom1".to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::zero(),
+    );
+
+    Ok(())
+}
+
+fn sweep_rewards(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.height = 700;
+
+    // denom1 is a proxy reward, paid to the farm outside of the claim
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), ASTRO_TOKEN.to_string(), Uint128::zero());
+    deps.querier.set_proxy_rewards(Some(vec![native_asset("denom1".to_string(), Uint128::zero())]));
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(500u128));
+
+    let sweep = |assets: Vec<AssetInfo>| ExecuteMsg::SweepRewards { assets };
+    let denom1 = AssetInfo::NativeToken { denom: "denom1".to_string() };
+
+    // only owner and controller can sweep
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), sweep(vec![denom1.clone()]));
+    assert_error(res, "Unauthorized");
+
+    // the base reward token and tokens other than the proxy rewards cannot be swept
+    let astro = AssetInfo::Token { contract_addr: Addr::unchecked(ASTRO_TOKEN) };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), sweep(vec![astro]));
+    assert_error(res, "Not a proxy reward of the staking contract: astro");
+    let uusd = AssetInfo::NativeToken { denom: "uusd".to_string() };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), sweep(vec![uusd]));
+    assert_error(res, "Not a proxy reward of the staking contract: uusd");
+
+    // the balance is buffered once
+    let msg = sweep(vec![denom1.clone(), denom1.clone()]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep_rewards"),
+            attr("token", "denom1"),
+            attr("sweep_amount", "500"),
+        ]
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), sweep(vec![denom1]))?;
+    assert_eq!(res.attributes, vec![attr("action", "sweep_rewards")]);
+
+    let harvested: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Harvested {})?)?;
+    assert_eq!(harvested, vec![native_asset("denom1".to_string(), Uint128::from(500u128))]);
+
+    // the swept balance is compounded by the next compound
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    let res = claim_rewards(deps, env.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", "denom1"),
+            attr("compound_amount", "475"),
+            attr("commission_amount", "25"),
+        ]
+    );
+
+    let harvested: Vec<Asset> = from_binary(&query(deps.as_ref(), env, QueryMsg::Harvested {})?)?;
+    assert_eq!(harvested, vec![]);
+
+    deps.querier.set_proxy_rewards(None);
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    Ok(())
+}
+
+fn deposit_cap(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: Some(Uint128::from(150000u128)),
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // bond above the cap
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(50001u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Deposit cap exceeded; deposit cap: 150000");
+
+    // bond up to the cap
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(50000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // remove the cap
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: Some(Uint128::zero()),
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+    let res: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(res.deposit_cap, Uint128::zero());
+
+    Ok(())
+}
+
+fn unbonding_period(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    let release_at = env.block.time.seconds() + 100;
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(150000u128),
+    );
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: Some(100),
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // unbonded LP token is kept in the contract
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(1000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(1000u128)
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    let msg = QueryMsg::Claims {
+        staker_addr: USER_1.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: ClaimsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        ClaimsResponse {
+            claims: vec![ClaimInfo {
+                amount: Uint128::from(1000u128),
+                release_at,
+            }],
+        }
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Claim {});
+    assert_error(res, "Nothing to claim");
+
+    // claim after the unbonding period
+    env.block.time = env.block.time.plus_seconds(100);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(500u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Claim {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_1.to_string(),
+                    amount: Uint128::from(1000u128)
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    let msg = QueryMsg::Claims {
+        staker_addr: USER_1.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: ClaimsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        ClaimsResponse {
+            claims: vec![ClaimInfo {
+                amount: Uint128::from(500u128),
+                release_at: release_at + 100,
+            }],
+        }
+    );
+
+    let msg = QueryMsg::Claims {
+        staker_addr: USER_1.to_string(),
+        start_after: Some(release_at + 100),
+        limit: None,
+    };
+    let res: ClaimsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, ClaimsResponse { claims: vec![] });
+
+    // emergency unbond transfers the LP token without waiting for the unbonding period
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user_8".to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(160000u128));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user_8", &[]), ExecuteMsg::EmergencyUnbond {})?;
+    let amount = Uint128::from(9999u128);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "emergency_unbond"),
+            attr("staker_addr", "user_8"),
+            attr("amount", amount),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "user_8".to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(150000u128));
+
+    let msg = QueryMsg::Claims {
+        staker_addr: "user_8".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: ClaimsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, ClaimsResponse { claims: vec![] });
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: Some(0),
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env, mock_info(OWNER, &[]), msg)?;
+
+    Ok(())
+}
+
+fn min_reward_value(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: Some(Uint128::from(20000u128)),
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // rewards below the min reward value are not compounded
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(5000u128),
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        REWARD_TOKEN.to_string(),
+        Uint128::from(5000u128),
+    );
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "skip_compound"),
+            attr("reward_value", "10000"),
+            attr("min_reward_value", "20000"),
+        ]
+    );
+    assert!(res.messages.is_empty());
+
+    // compound once rewards reach the min reward value
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(15000u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    assert_eq!(res.attributes[0], attr("action", "claim_rewards"));
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GENERATOR_PROXY.to_string(),
+            msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                lp_tokens: vec![LP_TOKEN.to_string()]
+            })?,
+            funds: vec![],
+        })
+    );
+    let res = claim_rewards(deps, env.clone())?;
+    assert_eq!(res.attributes[0], attr("action", "compound"));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: Some(Uint128::zero()),
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env, mock_info(OWNER, &[]), msg)?;
+
+    Ok(())
+}
+
+fn unbond_as_asset(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+
+    // only pool assets can be received
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(1000u128),
+        as_asset: Some(AssetInfo::NativeToken {
+            denom: "denom3".to_string(),
+        }),
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid message");
+
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(1000u128),
+        as_asset: Some(AssetInfo::NativeToken {
+            denom: "denom1".to_string(),
+        }),
+        minimum_receive: Some(Uint128::from(1000u128)),
+        slippage_tolerance: Some(Decimal::percent(2)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(1000u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair".to_string(),
+                    amount: Uint128::from(1000u128),
+                    msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ZapOut {
+                    to: Addr::unchecked(USER_1),
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "denom1".to_string(),
+                    },
+                    prev_balances: vec![
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "denom1".to_string(),
+                            },
+                            amount: Uint128::zero(),
+                        },
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "denom2".to_string(),
+                            },
+                            amount: Uint128::zero(),
+                        },
+                    ],
+                    minimum_receive: Some(Uint128::from(1000u128)),
+                    slippage_tolerance: Some(Decimal::percent(2)),
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // send the pool assets withdrawn from the pair to compound proxy to swap into the asset
+    deps.querier.set_balance(
+        "denom1".to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(500u128),
+    );
+    deps.querier.set_balance(
+        "denom2".to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(500u128),
+    );
+    let msg = ExecuteMsg::Callback(CallbackMsg::ZapOut {
+        to: Addr::unchecked(USER_1),
+        ask_asset_info: AssetInfo::NativeToken {
+            denom: "denom1".to_string(),
+        },
+        prev_balances: vec![
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "denom1".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "denom2".to_string(),
+                },
+                amount: Uint128::zero(),
+            },
+        ],
+        minimum_receive: Some(Uint128::from(1000u128)),
+        slippage_tolerance: Some(Decimal::percent(2)),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: COMPOUND_PROXY.to_string(),
+            msg: to_binary(&CompoundProxyExecuteMsg::SwapToAsset {
+                rewards: vec![
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "denom1".to_string(),
+                        },
+                        amount: Uint128::from(500u128),
+                    },
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "denom2".to_string(),
+                        },
+                        amount: Uint128::from(500u128),
+                    },
+                ],
+                target: AssetInfo::NativeToken {
+                    denom: "denom1".to_string(),
+                },
+                min_receive: Some(Uint128::from(1000u128)),
+                to: Some(USER_1.to_string()),
+                slippage_tolerance: Some(Decimal::percent(2)),
+            })?,
+            funds: vec![
+                Coin {
+                    denom: "denom1".to_string(),
+                    amount: Uint128::from(500u128),
+                },
+                Coin {
+                    denom: "denom2".to_string(),
+                    amount: Uint128::from(500u128),
+                },
+            ],
+        })]
+    );
+
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+    deps.querier.set_balance("denom2".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    Ok(())
+}
+
+fn migrate_stake(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: Some(LEGACY_FARM.to_string()),
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // only the legacy farm can migrate stakes
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::MigrateStake {
+            staker_addr: USER_2.to_string(),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: LEGACY_FARM.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::MigrateStake {
+            staker_addr: USER_2.to_string(),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(ASTRO_TOKEN, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let res = execute(deps.as_mut(), env, mock_info(LP_TOKEN, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("staker_addr", USER_2),
+            attr("amount", "1000"),
+            attr("bond_amount", "1000"),
+            attr("migrate_from", LEGACY_FARM),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: LP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: GENERATOR_PROXY.to_string(),
+                amount: Uint128::from(1000u128),
+                msg: to_binary(&GeneratorCw20HookMsg::Deposit {})?,
+            })?,
+            funds: vec![],
+        })]
+    );
+
+    Ok(())
+}
+
+fn boost(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let update_boost = |base_ratio: Decimal| ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: Some(Boost {
+            gov: GOV.to_string(),
+            base_ratio,
+        }),
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_boost(Decimal::percent(101)));
+    assert_error(res, "base_ratio must be 0 to 1");
+
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_boost(Decimal::percent(40)))?;
+
+    // user_1 stakes all SPEC
+    deps.querier.set_balance(GOV.to_string(), USER_1.to_string(), Uint128::from(100u128));
+    deps.querier.set_balance(GOV.to_string(), TOTAL_WEIGHT.to_string(), Uint128::from(100u128));
+
+    let balance = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, address: &str| -> Result<Uint128, ContractError> {
+        let res: BalanceResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Balance {
+            address: address.to_string(),
+        })?)?;
+        Ok(res.balance)
+    };
+    assert_eq!(balance(deps, USER_1)?, Uint128::from(30708u128));
+    assert_eq!(balance(deps, USER_2)?, Uint128::from(644u128));
+    assert_eq!(balance(deps, FEE_COLLECTOR_2)?, Uint128::from(65000u128));
+
+    // anyone can refresh the boost of a staker
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::UpdateBoost {
+        staker_addr: USER_1.to_string(),
+    })?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_boost"),
+            attr("staker_addr", USER_1),
+            attr("working_share", "30708"),
+        ]
+    );
+
+    // user_2 has no SPEC, working share is 40% of 644
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::UpdateBoost {
+        staker_addr: USER_2.to_string(),
+    })?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_boost"),
+            attr("staker_addr", USER_2),
+            attr("working_share", "257"),
+        ]
+    );
+
+    let state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    assert_eq!(state.total_bond_amount, Uint128::from(150000u128));
+    assert_eq!(state.total_bond_share, Uint128::from(97352u128));
+
+    // compound 10000 LP
+    deps.querier.set_balance(LP_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::Stake {
+        prev_balance: Uint128::zero(),
+        minimum_receive: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg.clone())?;
+
+    // 10000 LP is worth 10000 * 97352 / 150000 = 6490 shares, shared by working share
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "stake"),
+            attr("staking_token", LP_TOKEN),
+            attr("amount", "10000"),
+            attr("boost_share", "6490"),
+        ]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        state.total_bond_amount + Uint128::from(10000u128),
+    );
+    deps.querier.set_balance(LP_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    // the share price is unchanged, user_1 gains 2.5x more shares per share than user_2, and the
+    // stakers not updated since the boost was enabled hold the base working share of 40% of the
+    // 97352 - 30708 - 644 shares, so the total working share is 30708 + 257 + 26400
+    let new_state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    assert_eq!(new_state.total_bond_share, Uint128::from(103842u128));
+    assert!(new_state.share_price - state.share_price < Decimal::permille(1));
+    assert_eq!(balance(deps, USER_1)?, Uint128::from(34182u128));
+    assert_eq!(balance(deps, USER_2)?, Uint128::from(673u128));
+    assert_eq!(balance(deps, FEE_COLLECTOR_2)?, Uint128::from(67941u128));
+
+    // bond settles the boosted shares
+    let info = mock_info(LP_TOKEN, &[]);
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_2.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, bond_msg)?;
+    let res: UserInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::UserInfo {
+        staker_addr: USER_2.to_string(),
+    })?)?;
+    assert_eq!(res.shares, Uint128::from(673u128 + 649u128));
+    assert_eq!(balance(deps, USER_2)?, res.shares);
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(161000u128),
+    );
+
+    // base ratio 1 disables the boost, compound raises the share price again
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_boost(Decimal::one()))?;
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.boost, None);
+
+    deps.querier.set_balance(LP_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "stake"),
+            attr("staking_token", LP_TOKEN),
+            attr("amount", "10000"),
+        ]
+    );
+    deps.querier.set_balance(LP_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    Ok(())
+}
+
+fn auto_stake(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::UpdateAutoStakeRatio {
+        auto_stake_ratio: Decimal::percent(150),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), msg);
+    assert_error(res, "auto_stake_ratio must be 0 to 1");
+
+    let msg = ExecuteMsg::UpdateAutoStakeRatio {
+        auto_stake_ratio: Decimal::percent(50),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_auto_stake_ratio"),
+            attr("staker_addr", USER_3),
+            attr("auto_stake_ratio", "0.5"),
+        ]
+    );
+
+    // half of the deposit is bonded in the auto-stake bucket
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(190000u128));
+    let state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_3.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond"),
+            attr("staker_addr", USER_3),
+            attr("amount", "10000"),
+            attr("bond_amount", "5000"),
+            attr("stake_amount", "5000"),
+        ]
+    );
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(200000u128));
+
+    // the auto-stake bucket is excluded from the share price
+    let new_state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    assert_eq!(new_state.total_bond_amount, Uint128::from(200000u128));
+    assert_eq!(new_state.total_stake_bond_amount, Uint128::from(5000u128));
+    assert!(new_state.share_price - state.share_price < Decimal::permille(1));
+
+    let res: StakeInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::StakeInfo {
+        staker_addr: USER_3.to_string(),
+    })?)?;
+    assert_eq!(
+        res,
+        StakeInfoResponse {
+            staker_addr: USER_3.to_string(),
+            auto_stake_ratio: Decimal::percent(50),
+            stake_bond_amount: Uint128::from(5000u128),
+            pending_rewards: vec![],
+        }
+    );
+
+    // nothing earned yet
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::ClaimStakeReward {});
+    assert_error(res, "Nothing to claim");
+
+    // 100000 ASTRO, 5000 fee, 95000 * 5000 / 200000 = 2375 is kept for the auto-stake bucket, and
+    // the proxy reward is carved alike, 20000 - 1000 fee, 19000 * 5000 / 200000 = 475
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), ASTRO_TOKEN.to_string(), Uint128::from(100000u128));
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), REWARD_TOKEN.to_string(), Uint128::from(20000u128));
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    let res = claim_rewards(deps, env.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "92625"),
+            attr("commission_amount", "5000"),
+            attr("stake_amount", "2375"),
+            attr("token", REWARD_TOKEN),
+            attr("compound_amount", "18525"),
+            attr("commission_amount", "1000"),
+            attr("stake_amount", "475"),
+        ]
+    );
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), ASTRO_TOKEN.to_string(), Uint128::zero());
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), REWARD_TOKEN.to_string(), Uint128::zero());
+
+    let res: StakeInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::StakeInfo {
+        staker_addr: USER_3.to_string(),
+    })?)?;
+    assert_eq!(
+        res.pending_rewards,
+        vec![
+            token_asset(Addr::unchecked(ASTRO_TOKEN), Uint128::from(2375u128)),
+            token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(475u128)),
+        ]
+    );
+
+    // the proxy reward kept for the auto-stake bucket is not swept, only the balance paid on top
+    deps.querier.set_balance(REWARD_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(475u128));
+    let msg = ExecuteMsg::SweepRewards {
+        assets: vec![AssetInfo::Token { contract_addr: Addr::unchecked(REWARD_TOKEN) }],
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg.clone())?;
+    assert_eq!(res.attributes, vec![attr("action", "sweep_rewards")]);
+    deps.querier.set_balance(REWARD_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(575u128));
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "sweep_rewards"),
+            attr("token", REWARD_TOKEN),
+            attr("sweep_amount", "100"),
+        ]
+    );
+    let harvested: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Harvested {})?)?;
+    assert_eq!(harvested, vec![token_asset(Addr::unchecked(REWARD_TOKEN), Uint128::from(100u128))]);
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::ClaimStakeReward {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_3.to_string(),
+                    amount: Uint128::from(2375u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_3.to_string(),
+                    amount: Uint128::from(475u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::ClaimStakeReward {});
+    assert_error(res, "Nothing to claim");
+
+    // unbond from both buckets pro rata, 4000 * 5000 / 10000 = 2000 from the auto-stake bucket
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(4000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), msg)?;
+    let res: StakeInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::StakeInfo {
+        staker_addr: USER_3.to_string(),
+    })?)?;
+    assert_eq!(res.stake_bond_amount, Uint128::from(3000u128));
+    let res: StateResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::State {})?)?;
+    assert_eq!(res.total_stake_bond_amount, Uint128::from(3000u128));
+
+    Ok(())
+}
+
+fn reserve(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let mut msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: Some(1001),
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg.clone());
+    assert_error(res, "Reserve share must be at most 1000 bps");
+
+    if let ExecuteMsg::UpdateConfig { reserve_bps, .. } = &mut msg {
+        *reserve_bps = Some(500);
+    }
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // 5% of 10000 compounded LP is kept in the reserve, the compound balance is 200000 - 3000
+    let state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    deps.querier.set_balance(LP_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::Stake {
+        prev_balance: Uint128::zero(),
+        minimum_receive: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    let reserve_share = Uint128::from(500u128).multiply_ratio(state.total_bond_share, 206500u128);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "stake"),
+            attr("staking_token", LP_TOKEN),
+            attr("amount", "10000"),
+            attr("reserve_share", reserve_share),
+        ]
+    );
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(210000u128));
+
+    let res: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    assert_eq!(res.reserve_share, reserve_share);
+    assert_eq!(res.reserve_amount, Uint128::from(498u128));
+
+    // only the owner can withdraw the reserve
+    let msg = ExecuteMsg::WithdrawReserve {
+        amount: Uint128::from(498u128),
+        recipient: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::WithdrawReserve {
+            amount: Uint128::from(600u128),
+            recipient: None,
+        },
+    );
+    assert_error(res, "Cannot unbond more than balance");
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(498u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: OWNER.to_string(),
+                    amount: Uint128::from(498u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(209502u128));
+
+    let res: StateResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::State {})?)?;
+    assert!(res.reserve_share < Uint128::from(2u128));
+    assert_eq!(res.total_bond_share, state.total_bond_share + res.reserve_share);
+
+    Ok(())
+}
+
+fn price_guard(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let mut msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: Some(PriceGuard {
+            oracle: ORACLE.to_string(),
+            window: 3600,
+            max_deviation_bps: 10001,
+        }),
+        bond_cooldown: None,
+        referral_bps: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg.clone());
+    assert_error(res, "max_deviation_bps must be 0 to 10000");
+
+    if let ExecuteMsg::UpdateConfig { price_guard: Some(price_guard), .. } = &mut msg {
+        price_guard.max_deviation_bps = 200;
+    }
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg.clone())?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config.price_guard,
+        Some(PriceGuardConfig {
+            oracle: Oracle(Addr::unchecked(ORACLE)),
+            window: 3600,
+            max_deviation_bps: 200,
+        })
+    );
+
+    // the pool is 1:1 while the TWAP is 1.03
+    deps.querier.set_oracle_price(Decimal256::percent(103));
+    let compound_msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), compound_msg.clone());
+    assert_error(res, "Spot price 1 deviates from TWAP 1.03 by more than 200 bps");
+
+    deps.querier.set_oracle_price(Decimal256::percent(101));
+    execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), compound_msg)?;
+
+    // the deposit is valued at the reserves of the 1:1 pool moved along x * y = k to the TWAP of 1.21
+    deps.querier.set_oracle_price(Decimal256::percent(121));
+    let bond_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user_5".to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), bond_msg)?;
+
+    let reward_msg = QueryMsg::RewardInfo {
+        staker_addr: "user_5".to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), reward_msg)?)?;
+    assert_eq!(res.reward_info.deposit_amount, Uint128::from(99999u128));
+    assert_eq!(
+        res.reward_info.deposit_costs,
+        vec![Uint128::from(90908u128), Uint128::from(109998u128)]
+    );
+
+    // a max deviation of 0 disables the guard
+    if let ExecuteMsg::UpdateConfig { price_guard: Some(price_guard), .. } = &mut msg {
+        price_guard.max_deviation_bps = 0;
+    }
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(config.price_guard, None);
+
+    Ok(())
+}
+
+fn bond_cooldown(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+
+    let update_cooldown = |bond_cooldown: u64| ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: Some(bond_cooldown),
+        referral_bps: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), update_cooldown(3600));
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_cooldown(3600))?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.bond_cooldown, 3600);
+
+    // user_6 bonds 10000 LP
+    let state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user_6".to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        state.total_bond_amount + Uint128::from(10000u128),
+    );
+
+    // every withdrawal is blocked until the cooldown has elapsed
+    let unbond_at = env.block.time.seconds() + 3600;
+    env.block.time = env.block.time.plus_seconds(3599);
+    let unbond_msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(5000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let expected = format!("Cannot unbond before {}, the bond cooldown has not elapsed", unbond_at);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user_6", &[]), unbond_msg.clone());
+    assert_error(res, &expected);
+    let burn_msg = ExecuteMsg::Burn {
+        amount: Uint128::from(1000u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user_6", &[]), burn_msg);
+    assert_error(res, &expected);
+
+    // shares in the cooldown cannot be transferred
+    let msg = ExecuteMsg::Transfer {
+        recipient: "user_7".to_string(),
+        amount: Uint128::from(1000u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user_6", &[]), msg);
+    assert_error(res, &expected);
+
+    // a bond on behalf of user_6 does not restart the cooldown
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user_7".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: Some("user_6".to_string()), referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        state.total_bond_amount + Uint128::from(11000u128),
+    );
+
+    env.block.time = env.block.time.plus_seconds(1);
+    execute(deps.as_mut(), env.clone(), mock_info("user_6", &[]), unbond_msg.clone())?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        state.total_bond_amount + Uint128::from(6000u128),
+    );
+
+    // emergency unbond bypasses the cooldown
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user_6".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        state.total_bond_amount + Uint128::from(7000u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info("user_6", &[]), unbond_msg);
+    assert_error(res, &format!("Cannot unbond before {}, the bond cooldown has not elapsed", unbond_at + 3600));
+    execute(deps.as_mut(), env.clone(), mock_info("user_6", &[]), ExecuteMsg::EmergencyUnbond {})?;
+
+    // a cooldown of 0 disables it
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_cooldown(0))?;
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(config.bond_cooldown, 0);
+
+    Ok(())
+}
+
+fn migrate(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let migrate_msg = || MigrateMsg {
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+    };
+
+    // a config out of its limits aborts the migration
+    let config = CONFIG.load(&deps.storage)?;
+    let mut corrupt = config.clone();
+    corrupt.fee = Decimal::percent(101);
+    CONFIG.save(&mut deps.storage, &corrupt)?;
+    let res = migrate_contract(deps.as_mut(), env.clone(), migrate_msg()).map_err(ContractError::from);
+    assert_error(res, "Invalid state: fee must be 0 to 1");
+    CONFIG.save(&mut deps.storage, &config)?;
+
+    // shares without LP token in the generator abort the migration
+    let state: StateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), state.total_stake_bond_amount);
+    let res = migrate_contract(deps.as_mut(), env.clone(), migrate_msg()).map_err(ContractError::from);
+    assert_error(res, "Invalid state: shares are not backed by the staking deposit");
+
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), state.total_bond_amount);
+    migrate_contract(deps.as_mut(), env, migrate_msg())?;
+
+    Ok(())
+}
+
+fn token_factory() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        staking_interface: None,
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: "factory/p/uLP".to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg.clone());
+    assert_error(res, "Invalid input: human address too short");
+
+    let msg = InstantiateMsg {
+        liquidity_token: LP_DENOM.to_string(),
+        ..msg
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // the LP token must be sent as funds
+    let msg = ExecuteMsg::Bond { staker_addr: None, referrer: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Invalid message");
+
+    let info = mock_info(USER_1, &[Coin::new(100000, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Invalid message");
+
+    // user_1 bond 100000 LP
+    let info = mock_info(USER_1, &[Coin::new(100000, LP_DENOM)]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GENERATOR_PROXY.to_string(),
+            msg: to_binary(&GeneratorExecuteMsg::Deposit {})?,
+            funds: vec![Coin::new(100000, LP_DENOM)],
+        }),]
+    );
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_DENOM.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    // the LP token is sent back with a bank transfer, less the locked shares
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(99000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info(USER_1, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_DENOM.to_string(),
+                    amount: Uint128::from(99000u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin::new(99000, LP_DENOM)],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+fn ibc_hook() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        staking_interface: None,
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_DENOM.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // the hook sender is the intermediate account of the ibc-hooks module
+    let hook_sender = "ibc_hook_sender";
+    let msg = ExecuteMsg::IbcHookDeposit {
+        farm: "another_farm".to_string(),
+        min_lp: None,
+        receiver: USER_1.to_string(),
+    };
+    let info = mock_info(hook_sender, &[Coin::new(40000, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "IBC hook deposit is intended for another farm: another_farm");
+
+    let msg = ExecuteMsg::IbcHookDeposit {
+        farm: MOCK_CONTRACT_ADDR.to_string(),
+        min_lp: Some(Uint128::from(10000u128)),
+        receiver: USER_1.to_string(),
+    };
+    let info = mock_info(hook_sender, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Invalid message");
+
+    let info = mock_info(hook_sender, &[Coin::new(0, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Invalid zero amount");
+
+    // transferred denom is zapped into LP token and bonded for the receiver
+    let info = mock_info(hook_sender, &[Coin::new(40000, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "bond_assets"),
+            attr("staker_addr", USER_1),
+            attr("ibc_hook_farm", MOCK_CONTRACT_ADDR),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: COMPOUND_PROXY.to_string(),
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: IBC_TOKEN.to_string(),
+                        },
+                        amount: Uint128::from(40000u128),
+                    }],
+                    to: None,
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    source: None,
+                })?,
+                funds: vec![Coin::new(40000, IBC_TOKEN)],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::BondTo {
+                    to: Addr::unchecked(USER_1),
+                    sender: Addr::unchecked(hook_sender),
+                    prev_balance: Uint128::zero(),
+                    minimum_receive: Some(Uint128::from(10000u128)),
+                    referrer: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // transferred LP token is bonded directly
+    let info = mock_info(hook_sender, &[Coin::new(5000, LP_DENOM)]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(
+        res,
+        "Assertion failed; minimum receive amount: 10000, actual amount: 5000",
+    );
+
+    let info = mock_info(hook_sender, &[Coin::new(20000, LP_DENOM)]);
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: GENERATOR_PROXY.to_string(),
+            msg: to_binary(&GeneratorExecuteMsg::Deposit {})?,
+            funds: vec![Coin::new(20000, LP_DENOM)],
+        }),]
+    );
+
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_DENOM.to_string(),
+        Uint128::from(20000u128),
+    );
+
+    let res: UserInfoResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            staker_addr: USER_1.to_string(),
+        },
+    )?)?;
+    assert_eq!(res.lp_amount, Uint128::from(19000u128));
+
+    Ok(())
+}
+
+fn incentives() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        staking_contract: INCENTIVES.to_string(),
+        staking_interface: Some(StakingInterface::Incentives),
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_TOKEN.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // LP token is deposited with the incentives hook
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: LP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: INCENTIVES.to_string(),
+                amount: Uint128::from(100000u128),
+                msg: to_binary(&IncentivesCw20HookMsg::Deposit { recipient: None })?,
+            })?,
+            funds: vec![],
+        }),]
+    );
+
+    deps.querier.set_balance(
+        INCENTIVES.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        INCENTIVES.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+
+    // pending rewards are claimed from the incentives contract
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: INCENTIVES.to_string(),
+                msg: to_binary(&IncentivesExecuteMsg::ClaimRewards {
+                    lp_tokens: vec![LP_TOKEN.to_string()]
+                })?,
+                funds: vec![],
+            }),
+            1,
+        )]
+    );
+
+    deps.querier.claim_rewards(INCENTIVES, MOCK_CONTRACT_ADDR);
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    })?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "9500"),
+            attr("commission_amount", "500"),
+        ]
+    );
+
+    // LP token is withdrawn from the incentives contract
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(99000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info(USER_1, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: INCENTIVES.to_string(),
+                msg: to_binary(&IncentivesExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(99000u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_1.to_string(),
+                    amount: Uint128::from(99000u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+fn staking_adapter() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        staking_contract: STAKING_ADAPTER.to_string(),
+        staking_interface: Some(StakingInterface::Adapter),
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_TOKEN.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // LP token is staked with the staking adapter hook
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: LP_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: STAKING_ADAPTER.to_string(),
+                amount: Uint128::from(100000u128),
+                msg: to_binary(&StakingAdapterCw20HookMsg::Stake {})?,
+            })?,
+            funds: vec![],
+        }),]
+    );
+
+    deps.querier.set_balance(
+        STAKING_ADAPTER.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    deps.querier.set_balance(
+        STAKING_ADAPTER.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+
+    // pending rewards are claimed through the staking adapter
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: STAKING_ADAPTER.to_string(),
+                msg: to_binary(&StakingAdapterExecuteMsg::ClaimRewards {})?,
+                funds: vec![],
+            }),
+            1,
+        )]
+    );
+
+    deps.querier.claim_rewards(STAKING_ADAPTER, MOCK_CONTRACT_ADDR);
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 1,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: None,
+        }),
+    })?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "9500"),
+            attr("commission_amount", "500"),
+        ]
+    );
+
+    // LP token is unstaked through the staking adapter
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(99000u128),
+        as_asset: None,
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env, mock_info(USER_1, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: STAKING_ADAPTER.to_string(),
+                msg: to_binary(&StakingAdapterExecuteMsg::Unstake {
+                    amount: Uint128::from(99000u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_1.to_string(),
+                    amount: Uint128::from(99000u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+fn referral() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        staking_interface: None,
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_TOKEN.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    let mut msg = ExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: None,
+        fee: None,
+        fee_collector: None,
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        legacy_farm: None,
+        boost: None,
+        reserve_bps: None,
+        price_guard: None,
+        bond_cooldown: None,
+        referral_bps: Some(10001),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg.clone());
+    assert_error(res, "referral_bps must be 0 to 10000");
+
+    if let ExecuteMsg::UpdateConfig { referral_bps, .. } = &mut msg {
+        *referral_bps = Some(5000);
+    }
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "update_config"), attr("referral_bps", "5000")]
+    );
+
+    let bond_msg = |amount: u128, referrer: &str| ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker_addr: None,
+            referrer: Some(referrer.to_string()),
+        }).unwrap(),
+    });
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), bond_msg(101000, USER_1));
+    assert_error(res, "Cannot refer yourself");
+
+    // only the staker can set their referrer
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_2.to_string(),
+        amount: Uint128::from(101000u128),
+        msg: to_binary(&Cw20HookMsg::Bond {
+            staker_addr: Some(USER_1.to_string()),
+            referrer: Some(USER_3.to_string()),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg);
+    assert_error(res, "Only the staker can set their referrer");
+
+    // 100000 shares of user_1 are referred by user_3, 1000 shares are locked
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), bond_msg(101000, USER_3))?;
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(101000u128));
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_2.to_string(),
+        amount: Uint128::from(101000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(202000u128));
+
+    let res: ReferralInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ReferralInfo {
+        address: USER_1.to_string(),
+    })?)?;
+    assert_eq!(
+        res,
+        ReferralInfoResponse {
+            referrer: Some(USER_3.to_string()),
+            pending_fees: vec![],
+        }
+    );
+
+    // 500 fee, 500 * 100000 / 202000 = 247 is charged on the referred shares, half of it is kept
+    // for the referrers
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), ASTRO_TOKEN.to_string(), Uint128::from(10000u128));
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), REWARD_TOKEN.to_string(), Uint128::zero());
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(CONTROLLER, &[]), msg)?;
+    let res = claim_rewards(&mut deps, env.clone())?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("fee", "0.05"),
+            attr("fee_collector", FEE_COLLECTOR),
+            attr("token", ASTRO_TOKEN),
+            attr("compound_amount", "9500"),
+            attr("commission_amount", "500"),
+            attr("referral_amount", "123"),
+        ]
+    );
+    assert!(res.messages.iter().any(|it| it.msg == CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: ASTRO_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: FEE_COLLECTOR.to_string(),
+            amount: Uint128::from(377u128),
+        }).unwrap(),
+        funds: vec![],
+    })));
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), ASTRO_TOKEN.to_string(), Uint128::zero());
+
+    // the fee is credited to the referrer once the referred staker is settled
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::ClaimReferral { stakers: None });
+    assert_error(res, "Nothing to claim");
+
+    let msg = ExecuteMsg::ClaimReferral {
+        stakers: Some(vec![USER_2.to_string()]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), msg);
+    assert_error(res, "Unauthorized");
+
+    // the claim settles the given referred stakers first
+    let msg = ExecuteMsg::ClaimReferral {
+        stakers: Some(vec![USER_1.to_string()]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_referral"),
+            attr("referrer", USER_3),
+            attr("token", ASTRO_TOKEN),
+            attr("amount", "123"),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ASTRO_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER_3.to_string(),
+                amount: Uint128::from(123u128),
+            })?,
+            funds: vec![],
+        })]
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_3, &[]), ExecuteMsg::ClaimReferral { stakers: None });
+    assert_error(res, "Nothing to claim");
+
+    // the referrer is kept by the next bond
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), bond_msg(1000, USER_2))?;
+    let res: ReferralInfoResponse = from_binary(&query(deps.as_ref(), env, QueryMsg::ReferralInfo {
+        address: USER_1.to_string(),
+    })?)?;
+    assert_eq!(res.referrer, Some(USER_3.to_string()));
+
+    Ok(())
+}
+
+fn receipt_token() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        staking_contract: GENERATOR_PROXY.to_string(),
+        staking_interface: None,
+        compound_proxy: COMPOUND_PROXY.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        liquidity_token: LP_TOKEN.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        name: "name".to_string(),
+        symbol: "SYMBOL".to_string(),
+        pair: "pair".to_string(),
+        pauser: None,
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+
+    // user_1 bonds 101000 LP, 1000 shares are locked
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(101000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(101000u128));
+
+    let mint_msg = ExecuteMsg::MintReceipt {
+        amount: Uint128::from(40000u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), mint_msg.clone());
+    assert_error(res, "Receipt token is not created");
+
+    // the owner creates the receipt token once
+    let create_msg = ExecuteMsg::CreateReceiptToken {
+        code_id: 10,
+        name: "Spectrum LP receipt".to_string(),
+        symbol: "spLP".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), create_msg.clone());
+    assert_error(res, "Unauthorized");
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), create_msg.clone())?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(OWNER.to_string()),
+                code_id: 10,
+                msg: to_binary(&astroport::token::InstantiateMsg {
+                    name: "Spectrum LP receipt".to_string(),
+                    symbol: "spLP".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![],
+                    mint: Some(MinterResponse {
+                        minter: MOCK_CONTRACT_ADDR.to_string(),
+                        cap: None,
+                    }),
+                    marketing: None,
+                })?,
+                funds: vec![],
+                label: "Spectrum farm receipt token".to_string(),
+            },
+            3,
+        )]
+    );
+
+    let mut data = vec![0x0a, "receipt_token".len() as u8];
+    data.extend_from_slice(b"receipt_token");
+    reply(deps.as_mut(), env.clone(), Reply {
+        id: 3,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(Binary(data)),
+        }),
+    })?;
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.receipt_token, Some(Addr::unchecked("receipt_token")));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), create_msg);
+    assert_error(res, "Receipt token is already created");
+
+    // 40000 shares of user_1 are escrowed in the farm for 40000 receipt token
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), mint_msg)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "receipt_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: USER_1.to_string(),
+                amount: Uint128::from(40000u128),
+            })?,
+            funds: vec![],
+        })]
+    );
+    let res: BalanceResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Balance {
+        address: USER_1.to_string(),
+    })?)?;
+    assert_eq!(res.balance, Uint128::from(60000u128));
+    let res: BalanceResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Balance {
+        address: MOCK_CONTRACT_ADDR.to_string(),
+    })?)?;
+    assert_eq!(res.balance, Uint128::from(40000u128));
+
+    // the receipt token is transferable, user_2 redeems 10000 receipt token for LP token
+    let redeem_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_2.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::RedeemReceipt {})?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), redeem_msg.clone());
+    assert_error(res, "Unauthorized");
+    let res = execute(deps.as_mut(), env.clone(), mock_info("receipt_token", &[]), redeem_msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "receipt_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: Uint128::from(10000u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(10000u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_2.to_string(),
+                    amount: Uint128::from(10000u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+    let res: BalanceResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Balance {
+        address: MOCK_CONTRACT_ADDR.to_string(),
+    })?)?;
+    assert_eq!(res.balance, Uint128::from(30000u128));
+
+    // the share price doubles, then user_2 bonds 20000 LP for 10000 shares and escrows them, the
+    // fresh deposit does not hold back the value of the shares escrowed before
+    let mut env = env;
+    env.block.time = env.block.time.plus_seconds(86400);
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(182000u128));
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_2.to_string(),
+        amount: Uint128::from(20000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, referrer: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(LP_TOKEN, &[]), msg)?;
+    deps.querier.set_balance(GENERATOR_PROXY.to_string(), LP_TOKEN.to_string(), Uint128::from(202000u128));
+    let mint_msg = ExecuteMsg::MintReceipt {
+        amount: Uint128::from(10000u128),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), mint_msg)?;
+
+    let redeem_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_3.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::RedeemReceipt {})?,
+    });
+    let res = execute(deps.as_mut(), env, mock_info("receipt_token", &[]), redeem_msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "redeem_receipt"),
+            attr("staker_addr", USER_3),
+            attr("amount", "10000"),
+            attr("lp_amount", "20000"),
+        ]
+    );
+
+    Ok(())
+}
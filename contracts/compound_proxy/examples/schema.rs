@@ -4,8 +4,9 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 use spectrum::compound_proxy::{
     ExecuteMsg, InstantiateMsg, MigrateMsg,
-    QueryMsg, CallbackMsg, CompoundSimulationResponse
+    QueryMsg, CallbackMsg, CompoundSimulationResponse, SourceStatsResponse
 };
+use IDC_compound_proxy::state::Config;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,5 +19,7 @@ fn main() {
     export_schema(&schema_for!(CallbackMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(CompoundSimulationResponse), &out_dir);
+    export_schema(&schema_for!(SourceStatsResponse), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
     export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
@@ -1,26 +1,37 @@
 use crate::error::ContractError;
 use crate::simulation::query_compound_simulation;
-use crate::state::{Config, CONFIG, PAIR_PROXY};
-use std::collections::HashMap;
+use crate::state::{CompoundStep, Config, COMPOUND_STEP, CONFIG, LEGACY_CONFIG, PAIR_PROXY, SOURCE_STATS};
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 
-use astroport::factory::PairType;
+use astroport::factory::{FeeInfoResponse, PairType, QueryMsg as FactoryQueryMsg};
+use astroport::pair_concentrated::{is_concentrated, ConcentratedPoolConfig};
+use astroport::querier::query_token_precision;
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
-    Isqrt, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
+    attr, from_binary, to_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, CustomQuery, Decimal, Decimal256, Deps, DepsMut, Env,
+    Event, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
 };
 use cw20::Expiration;
-use spectrum::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use cw_storage_plus::Bound;
+use spectrum::compound_proxy::{
+    CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SourceStats, SourceStatsResponse,
+};
 
-use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::asset::{determine_asset_info, Asset, AssetInfo, AssetInfoExt};
 use spectrum::adapters::asset::AssetEx;
-use spectrum::adapters::pair::Pair;
+use spectrum::adapters::oracle::PriceGuardConfig;
+use spectrum::adapters::pair::{Pair, PairAdapter};
+use spectrum::events::{CompoundReport, STAGE_COMPOUND};
+use spectrum::helper::{assert_bps, assert_ratio, assert_valid_addr, invalid_state};
+use spectrum::math::{optimal_swap_amount, xyk_return_amount};
+use spectrum::querier::{query_asset_balance, query_asset_balances, query_pool_reserves};
 
 /// Scaling denominator for commission
 const COMMISSION_DENOM: u64 = 10000u64;
 
-/// Maximum spread percentage when swapping
-const MAX_SPREAD: u64 = 50; // 50%
+/// Page size limits of the source stats query
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 /// ## Description
 /// Validates that commission bps must be less than or equal 10000
@@ -32,6 +43,27 @@ fn validate_commission(commission_bps: u64) -> StdResult<u64> {
     }
 }
 
+/// ## Description
+/// Returns the swap commission of the pair type, the commission tier of the pair type if one is set,
+/// otherwise the current commission queried from the factory.
+pub fn query_commission_bps<C: CustomQuery>(querier: &QuerierWrapper<C>, config: &Config) -> StdResult<u64> {
+    if let Some((_, commission_bps)) = config
+        .commission_tiers
+        .iter()
+        .find(|(pair_type, _)| pair_type == &config.pair_info.pair_type)
+    {
+        return Ok(*commission_bps);
+    }
+
+    let fee_info: FeeInfoResponse = querier.query_wasm_smart(
+        config.factory.to_string(),
+        &FactoryQueryMsg::FeeInfo {
+            pair_type: config.pair_info.pair_type.clone(),
+        },
+    )?;
+    validate_commission(fee_info.total_fee_bps as u64)
+}
+
 /// ## Description
 /// Validates that decimal value is in the range 0 to 1
 fn validate_percentage(value: Decimal, field: &str) -> StdResult<Decimal> {
@@ -42,25 +74,58 @@ fn validate_percentage(value: Decimal, field: &str) -> StdResult<Decimal> {
     }
 }
 
+/// ## Description
+/// Sums up duplicate reward assets and rejects rewards with zero amount.
+fn aggregate_rewards(rewards: Vec<Asset>) -> StdResult<Vec<Asset>> {
+    let mut aggregated: Vec<Asset> = vec![];
+    for reward in rewards {
+        if reward.amount.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "reward amount must be greater than 0: {}",
+                reward.info
+            )));
+        }
+        if let Some(existing) = aggregated.iter_mut().find(|a| a.info.equal(&reward.info)) {
+            existing.amount = existing.amount.checked_add(reward.amount)?;
+        } else {
+            aggregated.push(reward);
+        }
+    }
+    Ok(aggregated)
+}
+
+/// ## Description
+/// Returns whether the asset is one of the assets of the pair. A reward that is a pool asset is
+/// provided as is, its pair proxy is never looked up.
+pub fn is_pool_asset(config: &Config, asset_info: &AssetInfo) -> bool {
+    config.pair_info.asset_infos.iter().any(|it| it.equal(asset_info))
+}
+
 /// ## Description
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
 /// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
+pub fn instantiate<C: CustomQuery>(
+    deps: DepsMut<C>,
     _env: Env,
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    let commission_bps = validate_commission(msg.commission_bps)?;
     let slippage_tolerance = validate_percentage(msg.slippage_tolerance, "slippage_tolerance")?;
     let pair_contract = deps.api.addr_validate(&msg.pair_contract)?;
     let pair_info = Pair(pair_contract).query_pair_info(&deps.querier)?;
+    let factory = deps.api.addr_validate(&msg.factory)?;
+    let price_guard = match msg.price_guard {
+        Some(price_guard) => PriceGuardConfig::from_msg(deps.api, price_guard)?,
+        None => None,
+    };
 
     let config = Config {
         pair_info,
-        commission_bps,
+        factory,
         slippage_tolerance,
+        price_guard,
+        owner: Some(deps.api.addr_validate(&msg.owner)?),
+        commission_tiers: vec![],
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -75,9 +140,8 @@ pub fn instantiate(
 
 /// ## Description
 /// Exposes execute functions available in the contract.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
+pub fn execute<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
@@ -88,6 +152,7 @@ pub fn execute(
             to,
             no_swap,
             slippage_tolerance,
+            source,
         } => {
             let to_addr = if let Some(to_addr) = to {
                 Some(deps.api.addr_validate(&to_addr)?)
@@ -102,52 +167,284 @@ pub fn execute(
                 to_addr,
                 no_swap,
                 slippage_tolerance,
+                source,
             )
         }
+        ExecuteMsg::CompoundNative {
+            rewards,
+            to,
+            no_swap,
+            slippage_tolerance,
+            source,
+        } => {
+            let rewards = aggregate_rewards(rewards)?;
+            assert_native_funds(&rewards, &info.funds)?;
+            let to_addr = to.map(|to_addr| deps.api.addr_validate(&to_addr)).transpose()?;
+            compound(
+                deps,
+                env,
+                info,
+                rewards,
+                to_addr,
+                no_swap,
+                slippage_tolerance,
+                source,
+            )
+        }
+        ExecuteMsg::SwapToAsset {
+            rewards,
+            target,
+            min_receive,
+            to,
+            slippage_tolerance,
+        } => {
+            let to_addr = to.map(|to_addr| deps.api.addr_validate(&to_addr)).transpose()?;
+            swap_to_asset(deps, env, info, rewards, target, min_receive, to_addr, slippage_tolerance)
+        }
+        ExecuteMsg::Rescue { asset, to } => rescue(deps, env, info, asset, to),
+        ExecuteMsg::UpdateCommissionTiers { commission_tiers } => {
+            update_commission_tiers(deps, info, commission_tiers)
+        }
         ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
     }
 }
 
+/// ## Description
+/// Replaces the commission tiers. Only the owner can execute this. Returns a [`ContractError`] if a
+/// commission is out of range or a pair type is listed twice.
+fn update_commission_tiers<C: CustomQuery>(
+    deps: DepsMut<C>,
+    info: MessageInfo,
+    commission_tiers: Vec<(PairType, u64)>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if config.owner.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut uniq = HashSet::new();
+    for (pair_type, commission_bps) in commission_tiers.iter() {
+        validate_commission(*commission_bps)?;
+        if !uniq.insert(pair_type.to_string()) {
+            return Err(ContractError::DuplicatedCommissionTier(pair_type.to_string()));
+        }
+    }
+
+    config.commission_tiers = commission_tiers;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_commission_tiers"))
+}
+
+/// ## Description
+/// Sends the whole balance of an asset stranded in the contract to the recipient. Only the owner
+/// can execute this, and not while a compound is in progress so its assets are never touched.
+fn rescue<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if COMPOUND_STEP.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::CompoundInProgress {});
+    }
+
+    let to = deps.api.addr_validate(&to)?;
+    let amount = query_asset_balance(&deps.querier, &asset, &env.contract.address)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToRescue {});
+    }
+
+    Ok(Response::new()
+        .add_message(asset.with_balance(amount).transfer_msg(&to)?)
+        .add_attributes(vec![
+            attr("action", "rescue"),
+            attr("asset", asset.to_string()),
+            attr("amount", amount),
+            attr("to", to),
+        ]))
+}
+
+/// ## Description
+/// Returns the native funds sent in excess of the rewards, funds of unlisted denoms are surplus
+/// entirely. Returns an error if a native reward is not covered by the funds.
+fn surplus_funds(rewards: &[Asset], funds: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+    let reward_amount = |denom: &str| -> Uint128 {
+        rewards
+            .iter()
+            .filter(|reward| matches!(&reward.info, AssetInfo::NativeToken { denom: d } if d == denom))
+            .map(|reward| reward.amount)
+            .sum()
+    };
+
+    for reward in rewards {
+        if let AssetInfo::NativeToken { denom } = &reward.info {
+            let sent = funds
+                .iter()
+                .filter(|coin| &coin.denom == denom)
+                .map(|coin| coin.amount)
+                .sum();
+            if sent < reward.amount {
+                return Err(ContractError::FundsMismatch {
+                    denom: denom.clone(),
+                    expected: reward.amount,
+                    sent,
+                });
+            }
+        }
+    }
+
+    let mut surplus: Vec<Coin> = vec![];
+    for coin in funds {
+        let amount = coin.amount.checked_sub(reward_amount(&coin.denom))?;
+        if !amount.is_zero() {
+            surplus.push(Coin {
+                denom: coin.denom.clone(),
+                amount,
+            });
+        }
+    }
+    Ok(surplus)
+}
+
+/// ## Description
+/// Checks that every reward is a native token and the sent funds cover exactly the rewards,
+/// rejecting under-funded and over-funded rewards and funds of unlisted denoms.
+fn assert_native_funds(rewards: &[Asset], funds: &[Coin]) -> Result<(), ContractError> {
+    for reward in rewards {
+        if let AssetInfo::Token { contract_addr } = &reward.info {
+            return Err(ContractError::NonNativeReward(contract_addr.to_string()));
+        }
+    }
+
+    if let Some(coin) = surplus_funds(rewards, funds)?.into_iter().next() {
+        let sent = funds
+            .iter()
+            .filter(|it| it.denom == coin.denom)
+            .map(|it| it.amount)
+            .sum::<Uint128>();
+        return Err(ContractError::FundsMismatch {
+            denom: coin.denom,
+            expected: sent.checked_sub(coin.amount)?,
+            sent,
+        });
+    }
+
+    Ok(())
+}
+
 /// ## Description
 /// Performs rewards compounding to LP token. Sender must do token approval upon calling this function.
 #[allow(clippy::too_many_arguments)]
-pub fn compound(
-    deps: DepsMut,
+pub fn compound<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     rewards: Vec<Asset>,
     to: Option<Addr>,
     no_swap: Option<bool>,
     slippage_tolerance: Option<Decimal>,
+    source: Option<String>,
 ) -> Result<Response, ContractError> {
     let no_swap = no_swap.unwrap_or(false);
+    let rewards = aggregate_rewards(rewards)?;
+    if let Some(slippage_tolerance) = slippage_tolerance {
+        validate_percentage(slippage_tolerance, "slippage_tolerance")?;
+    }
+
+    // Abort while the pool is manipulated away from its time weighted average price
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(price_guard) = &config.price_guard {
+        price_guard.assert_price(&deps.querier, &config.pair_info)?;
+    }
+
+    // Tracks the callbacks of this compound, which also locks rescue until liquidity is provided
+    let step = if no_swap { CompoundStep::ProvideLiquidity } else { CompoundStep::OptimalSwap };
+    COMPOUND_STEP.save(deps.storage, &step)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
-    let mut native_reward_map: HashMap<AssetInfo, Uint128> = HashMap::new();
+    let mut attributes: Vec<Attribute> = vec![];
+
+    // Refund native funds sent in excess of the rewards
+    let surplus = surplus_funds(&rewards, &info.funds)?;
+    if !surplus.is_empty() {
+        attributes.push(attr(
+            "refund",
+            surplus.iter().map(|coin| coin.to_string()).collect::<Vec<String>>().join(","),
+        ));
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: surplus,
+        }));
+    }
+
+    // The sent funds are either compounded or refunded
+    let native_reward_map: HashMap<AssetInfo, Uint128> = info
+        .funds
+        .iter()
+        .map(|coin| (AssetInfo::NativeToken { denom: coin.denom.clone() }, coin.amount))
+        .collect();
+
+    let receiver = to.unwrap_or_else(|| info.sender.clone());
+    let mut events: Vec<Event> = vec![];
+
+    // Attribute the rewards to the source, the LP token is recorded once liquidity is provided
+    if let Some(source) = &source {
+        SOURCE_STATS.update(deps.storage, source, |totals| -> StdResult<_> {
+            let mut totals = totals.unwrap_or_default();
+            for reward in rewards.iter() {
+                match totals.rewards.iter_mut().find(|it| it.info.equal(&reward.info)) {
+                    Some(total) => total.amount = total.amount.checked_add(reward.amount)?,
+                    None => totals.rewards.push(reward.clone()),
+                }
+            }
+            Ok(totals)
+        })?;
+        attributes.push(attr("source", source));
+    }
+
     // Swap reward to asset in the pair
     for reward in rewards {
-        reward.deposit_asset(&info, &env.contract.address, &mut messages)?;
+        if !reward.is_native_token() {
+            reward.deposit_asset(&info, &env.contract.address, &mut messages)?;
+        }
+        attributes.push(attr("reward_asset", reward.info.to_string()));
+        attributes.push(attr("reward_amount", reward.amount));
+        events.push(CompoundReport::new(STAGE_COMPOUND).reward(&reward).receiver(&receiver).into_event());
+
+        // The balance of a pool asset is already counted by the optimal swap and the provide
+        if is_pool_asset(&config, &reward.info) {
+            continue;
+        }
 
         let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
         if let Some(pair_proxy) = pair_proxy {
-            let swap_reward =
-                pair_proxy.swap_msg(&reward, Some(Decimal::MAX), Some(Decimal::percent(MAX_SPREAD)), None)?;
+            let swap_reward = pair_proxy_swap_msg(
+                &deps.querier,
+                &config,
+                &pair_proxy,
+                &reward,
+                slippage_tolerance.unwrap_or(config.slippage_tolerance),
+            )?;
             messages.push(swap_reward);
         }
-
-        if reward.is_native_token() {
-            native_reward_map.insert(reward.info, reward.amount);
-        }
     }
 
     if !no_swap {
-        messages.push(CallbackMsg::OptimalSwap {}.into_cosmos_msg(&env.contract.address)?);
+        messages.push(CallbackMsg::OptimalSwap { slippage_tolerance }.into_cosmos_msg(&env.contract.address)?);
     }
 
-    let config = CONFIG.load(deps.storage)?;
-    let assets = config
-        .pair_info
-        .query_pools(&deps.querier, &env.contract.address)?;
+    let assets = query_asset_balances(
+        &deps.querier,
+        &config.pair_info.asset_infos,
+        &env.contract.address,
+    )?;
     let prev_balances = assets
         .iter()
         .map(|a| {
@@ -158,25 +455,146 @@ pub fn compound(
         })
         .collect::<StdResult<_>>()?;
 
-    let receiver = to.unwrap_or(info.sender);
     messages.push(
         CallbackMsg::ProvideLiquidity {
             prev_balances,
             slippage_tolerance,
             receiver: receiver.to_string(),
+            source,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "compound")
+        .add_attributes(attributes)
+        .add_attribute("receiver", receiver)
+        .add_events(events))
+}
+
+/// ## Description
+/// Swaps rewards to the target asset of the pair without providing liquidity. Each reward is routed
+/// through its pair proxy, then the other asset of the pair is swapped to the target and sent to
+/// the receiver. Sender must do token approval upon calling this function.
+#[allow(clippy::too_many_arguments)]
+pub fn swap_to_asset<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    info: MessageInfo,
+    rewards: Vec<Asset>,
+    target: AssetInfo,
+    min_receive: Option<Uint128>,
+    to: Option<Addr>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let rewards = aggregate_rewards(rewards)?;
+    if let Some(slippage_tolerance) = slippage_tolerance {
+        validate_percentage(slippage_tolerance, "slippage_tolerance")?;
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if !is_pool_asset(&config, &target) {
+        return Err(ContractError::InvalidTarget(target.to_string()));
+    }
+
+    // Abort while the pool is manipulated away from its time weighted average price
+    if let Some(price_guard) = &config.price_guard {
+        price_guard.assert_price(&deps.querier, &config.pair_info)?;
+    }
+
+    // Tracks the callbacks of this swap, which also locks rescue until the target is sent
+    COMPOUND_STEP.save(deps.storage, &CompoundStep::SwapToTarget)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes: Vec<Attribute> = vec![];
+
+    // Refund native funds sent in excess of the rewards
+    let surplus = surplus_funds(&rewards, &info.funds)?;
+    if !surplus.is_empty() {
+        attributes.push(attr(
+            "refund",
+            surplus.iter().map(|coin| coin.to_string()).collect::<Vec<String>>().join(","),
+        ));
+        messages.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: surplus,
+        }));
+    }
+
+    // The sent funds are either swapped or refunded
+    let native_reward_map: HashMap<AssetInfo, Uint128> = info
+        .funds
+        .iter()
+        .map(|coin| (AssetInfo::NativeToken { denom: coin.denom.clone() }, coin.amount))
+        .collect();
+
+    let receiver = to.unwrap_or_else(|| info.sender.clone());
+
+    // Swap reward to asset in the pair
+    for reward in rewards {
+        let pair_proxy = if is_pool_asset(&config, &reward.info) {
+            None
+        } else {
+            let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
+            Some(pair_proxy.ok_or_else(|| ContractError::NoRoute(reward.info.to_string()))?)
+        };
+
+        if !reward.is_native_token() {
+            reward.deposit_asset(&info, &env.contract.address, &mut messages)?;
+        }
+        attributes.push(attr("reward_asset", reward.info.to_string()));
+        attributes.push(attr("reward_amount", reward.amount));
+
+        if let Some(pair_proxy) = pair_proxy {
+            let swap_reward = pair_proxy_swap_msg(
+                &deps.querier,
+                &config,
+                &pair_proxy,
+                &reward,
+                slippage_tolerance.unwrap_or(config.slippage_tolerance),
+            )?;
+            messages.push(swap_reward);
+        }
+    }
+
+    let assets = query_asset_balances(
+        &deps.querier,
+        &config.pair_info.asset_infos,
+        &env.contract.address,
+    )?;
+    let prev_balances = assets
+        .iter()
+        .map(|a| {
+            let balance = a
+                .amount
+                .checked_sub(*native_reward_map.get(&a.info).unwrap_or(&Uint128::zero()))?;
+            Ok(a.info.with_balance(balance))
+        })
+        .collect::<StdResult<_>>()?;
+
+    messages.push(
+        CallbackMsg::SwapToTarget {
+            prev_balances,
+            target: target.clone(),
+            min_receive,
+            receiver: receiver.to_string(),
+            slippage_tolerance,
         }
         .into_cosmos_msg(&env.contract.address)?,
     );
 
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("action", "compound"))
+        .add_attribute("action", "swap_to_asset")
+        .add_attributes(attributes)
+        .add_attribute("target", target.to_string())
+        .add_attribute("receiver", receiver))
 }
 
 /// # Description
 /// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
-pub fn handle_callback(
-    deps: DepsMut,
+pub fn handle_callback<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     info: MessageInfo,
     msg: CallbackMsg,
@@ -185,133 +603,374 @@ pub fn handle_callback(
     if info.sender != env.contract.address {
         return Err(ContractError::Unauthorized {});
     }
+    // Callbacks must follow the order set by compound, so a stray self-call cannot use unrelated balances
+    let step = COMPOUND_STEP.may_load(deps.storage)?;
     match msg {
-        CallbackMsg::OptimalSwap {} => optimal_swap(deps, env, info),
+        CallbackMsg::OptimalSwap { slippage_tolerance } => {
+            if step != Some(CompoundStep::OptimalSwap) {
+                return Err(ContractError::UnexpectedCallback("optimal_swap".to_string()));
+            }
+            COMPOUND_STEP.save(deps.storage, &CompoundStep::ProvideLiquidity)?;
+            optimal_swap(deps, env, info, slippage_tolerance)
+        }
         CallbackMsg::ProvideLiquidity {
             prev_balances,
             slippage_tolerance,
             receiver,
-        } => provide_liquidity(deps, env, info, prev_balances, receiver, slippage_tolerance),
+            source,
+        } => {
+            if step != Some(CompoundStep::ProvideLiquidity) {
+                return Err(ContractError::UnexpectedCallback("provide_liquidity".to_string()));
+            }
+            COMPOUND_STEP.remove(deps.storage);
+            provide_liquidity(deps, env, info, prev_balances, receiver, slippage_tolerance, source)
+        }
+        CallbackMsg::RecordSource {
+            source,
+            receiver,
+            prev_lp_balance,
+        } => {
+            if step != Some(CompoundStep::RecordSource) {
+                return Err(ContractError::UnexpectedCallback("record_source".to_string()));
+            }
+            COMPOUND_STEP.remove(deps.storage);
+            record_source(deps, source, receiver, prev_lp_balance)
+        }
+        CallbackMsg::SwapToTarget {
+            prev_balances,
+            target,
+            min_receive,
+            receiver,
+            slippage_tolerance,
+        } => {
+            if step != Some(CompoundStep::SwapToTarget) {
+                return Err(ContractError::UnexpectedCallback("swap_to_target".to_string()));
+            }
+            COMPOUND_STEP.save(deps.storage, &CompoundStep::SendTarget)?;
+            swap_to_target(deps, env, prev_balances, target, min_receive, receiver, slippage_tolerance)
+        }
+        CallbackMsg::SendTarget {
+            prev_balance,
+            min_receive,
+            receiver,
+        } => {
+            if step != Some(CompoundStep::SendTarget) {
+                return Err(ContractError::UnexpectedCallback("send_target".to_string()));
+            }
+            COMPOUND_STEP.remove(deps.storage);
+            send_target(deps, env, prev_balance, min_receive, receiver)
+        }
     }
 }
 
 /// # Description
 /// Performs optimal swap of assets in the pair contract.
-fn optimal_swap(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+fn optimal_swap<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    _info: MessageInfo,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes: Vec<Attribute> = vec![];
 
     match config.pair_info.pair_type {
         PairType::Stable {} => {
             //Do nothing for stable pair
         }
         _ => {
-            let assets = config
-                .pair_info
-                .query_pools(&deps.querier, env.contract.address)?;
+            let assets = query_asset_balances(
+                &deps.querier,
+                &config.pair_info.asset_infos,
+                env.contract.address,
+            )?;
             let asset_a = assets[0].clone();
             let asset_b = assets[1].clone();
             if !asset_a.amount.is_zero() || !asset_b.amount.is_zero() {
-                calculate_optimal_swap(
-                    &deps.querier,
-                    &config,
-                    asset_a,
-                    asset_b,
-                    &mut messages,
-                )?;
+                let (swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
+                    calculate_optimal_swap(
+                        &deps.querier,
+                        &config,
+                        asset_a.clone(),
+                        asset_b.clone(),
+                        slippage_tolerance.unwrap_or(config.slippage_tolerance),
+                        &mut messages,
+                    )?;
+                // the commission is taken by the pair from the return asset
+                let commission_bps = query_commission_bps(&deps.querier, &config)?;
+                let commission_amount = |return_amount: Uint128| {
+                    return_amount.multiply_ratio(commission_bps, COMMISSION_DENOM - commission_bps)
+                };
+                if !swap_asset_a_amount.is_zero() {
+                    attributes.push(attr("swap_asset", asset_a.info.to_string()));
+                    attributes.push(attr("swap_amount", swap_asset_a_amount));
+                    attributes.push(attr("return_asset", asset_b.info.to_string()));
+                    attributes.push(attr("return_amount", return_b_amount));
+                    attributes.push(attr("commission_amount", commission_amount(return_b_amount)));
+                }
+                if !swap_asset_b_amount.is_zero() {
+                    attributes.push(attr("swap_asset", asset_b.info.to_string()));
+                    attributes.push(attr("swap_amount", swap_asset_b_amount));
+                    attributes.push(attr("return_asset", asset_a.info.to_string()));
+                    attributes.push(attr("return_amount", return_a_amount));
+                    attributes.push(attr("commission_amount", commission_amount(return_a_amount)));
+                }
             }
         }
     }
 
     Ok(Response::new()
         .add_messages(messages)
-        .add_attribute("action", "optimal_swap"))
+        .add_attribute("action", "optimal_swap")
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Returns the belief price of a swap from the offer asset to the ask asset. With the price guard,
+/// this is the TWAP of the ask asset, so the spread is measured from the oracle price instead of the
+/// spot price of the pool.
+fn twap_belief_price<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    config: &Config,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> StdResult<Option<Decimal>> {
+    let price_guard = match &config.price_guard {
+        Some(price_guard) => price_guard,
+        None => return Ok(None),
+    };
+    let twap = price_guard
+        .oracle
+        .query_price(querier, ask_asset_info, offer_asset_info, price_guard.window)?
+        .price;
+    let atomics: Uint128 = twap.atomics().try_into()?;
+    let belief_price = Decimal::from_atomics(atomics, twap.decimal_places())
+        .map_err(|_| StdError::generic_err("belief price overflow"))?;
+    Ok(Some(belief_price))
+}
+
+/// ## Description
+/// Returns the message swapping the offer asset on the pair, which enforces the slippage tolerance
+/// as its max spread from the TWAP belief price.
+fn pool_swap_msg<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    config: &Config,
+    offer_asset: &Asset,
+    slippage_tolerance: Decimal,
+) -> StdResult<CosmosMsg> {
+    let belief_price = if config.price_guard.is_some() {
+        let ask_asset_info = config
+            .pair_info
+            .asset_infos
+            .iter()
+            .find(|it| !it.equal(&offer_asset.info))
+            .ok_or_else(|| StdError::generic_err("Invalid offer asset"))?;
+        twap_belief_price(querier, config, &offer_asset.info, ask_asset_info)?
+    } else {
+        None
+    };
+
+    Pair(config.pair_info.contract_addr.clone()).swap_msg(
+        offer_asset,
+        belief_price,
+        Some(slippage_tolerance),
+        None,
+    )
+}
+
+/// ## Description
+/// Returns the message swapping a reward on its pair proxy, which enforces the slippage tolerance
+/// as its max spread from the TWAP belief price like [`pool_swap_msg`].
+fn pair_proxy_swap_msg<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    config: &Config,
+    pair_proxy: &Pair,
+    offer_asset: &Asset,
+    slippage_tolerance: Decimal,
+) -> StdResult<CosmosMsg> {
+    let belief_price = if config.price_guard.is_some() {
+        let pair_info = pair_proxy.query_pair_info(querier)?;
+        let ask_asset_info = pair_info
+            .asset_infos
+            .iter()
+            .find(|it| !it.equal(&offer_asset.info))
+            .ok_or_else(|| StdError::generic_err("Invalid pair proxy"))?;
+        twap_belief_price(querier, config, &offer_asset.info, ask_asset_info)?
+    } else {
+        None
+    };
+
+    pair_proxy.swap_msg(offer_asset, belief_price, Some(slippage_tolerance), None)
 }
 
 /// # Description
 /// Calculates the amount of asset in the pair contract that need to be swapped before providing liquidity.
-/// The swap messages will be added to **messages**.
-pub fn calculate_optimal_swap(
-    querier: &QuerierWrapper,
+/// The swap messages with the slippage tolerance will be added to **messages**.
+pub fn calculate_optimal_swap<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     config: &Config,
     asset_a: Asset,
     asset_b: Asset,
+    slippage_tolerance: Decimal,
     messages: &mut Vec<CosmosMsg>,
 ) -> StdResult<(Uint128, Uint128, Uint128, Uint128)> {
+    if is_concentrated(&config.pair_info.pair_type) {
+        return calculate_concentrated_optimal_swap(querier, config, asset_a, asset_b, slippage_tolerance, messages);
+    }
+
     let mut swap_asset_a_amount = Uint128::zero();
     let mut swap_asset_b_amount = Uint128::zero();
     let mut return_a_amount = Uint128::zero();
     let mut return_b_amount = Uint128::zero();
 
-    let pair_contract = config.pair_info.contract_addr.clone();
-    let pools = config
-        .pair_info
-        .query_pools(querier, &pair_contract)?;
-    let provide_a_amount: Uint256 = asset_a.amount.into();
-    let provide_b_amount: Uint256 = asset_b.amount.into();
-    let pool_a_amount: Uint256 = pools[0].amount.into();
-    let pool_b_amount: Uint256 = pools[1].amount.into();
-    let provide_a_area = provide_a_amount * pool_b_amount;
-    let provide_b_area = provide_b_amount * pool_a_amount;
+    let pools = query_pool_reserves(querier, &config.pair_info)?;
+    let commission_bps = query_commission_bps(querier, config)?;
+    let provide_a_area = asset_a.amount.full_mul(pools[1].amount);
+    let provide_b_area = asset_b.amount.full_mul(pools[0].amount);
 
     #[allow(clippy::comparison_chain)]
     if provide_a_area > provide_b_area {
-        let swap_amount = get_swap_amount(
-            provide_a_amount,
-            provide_b_amount,
-            pool_a_amount,
-            pool_b_amount,
-            config.commission_bps,
+        let swap_amount = optimal_swap_amount(
+            asset_a.amount,
+            asset_b.amount,
+            pools[0].amount,
+            pools[1].amount,
+            commission_bps,
         )?;
         if !swap_amount.is_zero() {
             let swap_asset = Asset {
                 info: asset_a.info,
                 amount: swap_amount,
             };
-            return_b_amount = simulate(
-                pool_a_amount,
-                pool_b_amount,
-                swap_asset.amount.into(),
-                Decimal256::from_ratio(config.commission_bps, COMMISSION_DENOM),
+            return_b_amount = xyk_return_amount(
+                pools[0].amount,
+                pools[1].amount,
+                swap_asset.amount,
+                commission_bps,
             )?;
             if !return_b_amount.is_zero() {
                 swap_asset_a_amount = swap_asset.amount;
-                messages.push(Pair(pair_contract).swap_msg(
-                    &swap_asset,
-                    Some(Decimal::MAX),
-                    Some(Decimal::percent(MAX_SPREAD)),
-                    None,
-                )?);
+                messages.push(pool_swap_msg(querier, config, &swap_asset, slippage_tolerance)?);
             }
         }
     } else if provide_a_area < provide_b_area {
-        let swap_amount = get_swap_amount(
-            provide_b_amount,
-            provide_a_amount,
-            pool_b_amount,
-            pool_a_amount,
-            config.commission_bps,
+        let swap_amount = optimal_swap_amount(
+            asset_b.amount,
+            asset_a.amount,
+            pools[1].amount,
+            pools[0].amount,
+            commission_bps,
         )?;
         if !swap_amount.is_zero() {
             let swap_asset = Asset {
                 info: asset_b.info,
                 amount: swap_amount,
             };
-            return_a_amount = simulate(
-                pool_b_amount,
-                pool_a_amount,
-                swap_asset.amount.into(),
-                Decimal256::from_ratio(config.commission_bps, COMMISSION_DENOM),
+            return_a_amount = xyk_return_amount(
+                pools[1].amount,
+                pools[0].amount,
+                swap_asset.amount,
+                commission_bps,
             )?;
             if !return_a_amount.is_zero() {
                 swap_asset_b_amount = swap_asset.amount;
-                messages.push(Pair(pair_contract).swap_msg(
-                    &swap_asset,
-                    Some(Decimal::MAX),
-                    Some(Decimal::percent(MAX_SPREAD)),
-                    None,
-                )?);
+                messages.push(pool_swap_msg(querier, config, &swap_asset, slippage_tolerance)?);
+            }
+        }
+    };
+
+    Ok((
+        swap_asset_a_amount,
+        swap_asset_b_amount,
+        return_a_amount,
+        return_b_amount,
+    ))
+}
+
+/// # Description
+/// Returns the price scale of a concentrated pair as a fraction of raw amounts,
+/// i.e. the amount of asset A (numerator) per amount of asset B (denominator).
+pub fn query_concentrated_price<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    config: &Config,
+) -> StdResult<(Uint256, Uint256)> {
+    let params = Pair(config.pair_info.contract_addr.clone())
+        .query_config(querier)?
+        .params
+        .ok_or_else(|| StdError::generic_err("params not found"))?;
+    let pool_config: ConcentratedPoolConfig = from_binary(&params)?;
+    let precision_a = query_token_precision(querier, &config.pair_info.asset_infos[0])?;
+    let precision_b = query_token_precision(querier, &config.pair_info.asset_infos[1])?;
+
+    let price_numerator = Uint256::from(pool_config.price_scale.atomics())
+        * Uint256::from(10u128.pow(precision_a as u32));
+    let price_denominator = Uint256::from(Decimal::one().atomics())
+        * Uint256::from(10u128.pow(precision_b as u32));
+    Ok((price_numerator, price_denominator))
+}
+
+/// # Description
+/// Calculates the swap needed to match the pool ratio of a concentrated pair. The pool is assumed
+/// to trade close to its price scale, so the excess value is swapped at that price.
+/// The swap messages with the slippage tolerance will be added to **messages**.
+fn calculate_concentrated_optimal_swap<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    config: &Config,
+    asset_a: Asset,
+    asset_b: Asset,
+    slippage_tolerance: Decimal,
+    messages: &mut Vec<CosmosMsg>,
+) -> StdResult<(Uint128, Uint128, Uint128, Uint128)> {
+    let mut swap_asset_a_amount = Uint128::zero();
+    let mut swap_asset_b_amount = Uint128::zero();
+    let mut return_a_amount = Uint128::zero();
+    let mut return_b_amount = Uint128::zero();
+
+    let pools = query_pool_reserves(querier, &config.pair_info)?;
+    let commission_rate =
+        Decimal256::from_ratio(query_commission_bps(querier, config)?, COMMISSION_DENOM);
+    let (price_numerator, price_denominator) = query_concentrated_price(querier, config)?;
+
+    let provide_a_amount: Uint256 = asset_a.amount.into();
+    let provide_b_amount: Uint256 = asset_b.amount.into();
+    let pool_a_amount: Uint256 = pools[0].amount.into();
+    let pool_b_amount: Uint256 = pools[1].amount.into();
+    let provide_a_area = provide_a_amount * pool_b_amount;
+    let provide_b_area = provide_b_amount * pool_a_amount;
+
+    #[allow(clippy::comparison_chain)]
+    if provide_a_area > provide_b_area {
+        // (a - s) / (b + s / p) = pool_a / pool_b
+        let swap_amount: Uint128 = (price_numerator * (provide_a_area - provide_b_area)
+            / (price_numerator * pool_b_amount + price_denominator * pool_a_amount))
+            .try_into()
+            .map_err(|_| StdError::generic_err("overflow"))?;
+        if !swap_amount.is_zero() {
+            let return_amount = Uint256::from(swap_amount) * price_denominator / price_numerator;
+            return_b_amount = (return_amount - return_amount * commission_rate)
+                .try_into()
+                .map_err(|_| StdError::generic_err("overflow"))?;
+            if !return_b_amount.is_zero() {
+                swap_asset_a_amount = swap_amount;
+                messages.push(pool_swap_msg(querier, config, &asset_a.info.with_balance(swap_amount), slippage_tolerance)?);
+            }
+        }
+    } else if provide_a_area < provide_b_area {
+        // (b - s) / (a + s * p) = pool_b / pool_a
+        let swap_amount: Uint128 = (price_denominator * (provide_b_area - provide_a_area)
+            / (price_denominator * pool_a_amount + price_numerator * pool_b_amount))
+            .try_into()
+            .map_err(|_| StdError::generic_err("overflow"))?;
+        if !swap_amount.is_zero() {
+            let return_amount = Uint256::from(swap_amount) * price_numerator / price_denominator;
+            return_a_amount = (return_amount - return_amount * commission_rate)
+                .try_into()
+                .map_err(|_| StdError::generic_err("overflow"))?;
+            if !return_a_amount.is_zero() {
+                swap_asset_b_amount = swap_amount;
+                messages.push(pool_swap_msg(querier, config, &asset_b.info.with_balance(swap_amount), slippage_tolerance)?);
             }
         }
     };
@@ -326,21 +985,24 @@ pub fn calculate_optimal_swap(
 
 /// ## Description
 /// Provides liquidity on the pair contract to get LP token.
-pub fn provide_liquidity(
-    deps: DepsMut,
+pub fn provide_liquidity<C: CustomQuery>(
+    deps: DepsMut<C>,
     env: Env,
     _info: MessageInfo,
     prev_balances: Vec<Asset>,
     receiver: String,
     slippage_tolerance: Option<Decimal>,
+    source: Option<String>,
 ) -> Result<Response, ContractError> {
     let config: Config = CONFIG.load(deps.storage)?;
 
     let pair_contract = config.pair_info.contract_addr.clone();
 
-    let assets = config
-        .pair_info
-        .query_pools(&deps.querier, env.contract.address)?;
+    let assets = query_asset_balances(
+        &deps.querier,
+        &config.pair_info.asset_infos,
+        &env.contract.address,
+    )?;
 
     let prev_balance_map: HashMap<_, _> = prev_balances
         .into_iter()
@@ -348,6 +1010,7 @@ pub fn provide_liquidity(
         .collect();
 
     let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes: Vec<Attribute> = vec![];
     let mut provide_assets: Vec<Asset> = vec![];
     let mut funds: Vec<Coin> = vec![];
     for asset in assets.iter() {
@@ -356,6 +1019,8 @@ pub fn provide_liquidity(
             .unwrap_or(&Uint128::zero());
         let amount = asset.amount.checked_sub(prev_balance)?;
         let provide_asset = asset.info.with_balance(amount);
+        attributes.push(attr("provide_asset", provide_asset.info.to_string()));
+        attributes.push(attr("provide_amount", provide_asset.amount));
 
         if !provide_asset.amount.is_zero() {
             if asset.is_native_token() {
@@ -381,75 +1046,283 @@ pub fn provide_liquidity(
     )?;
     messages.push(provide_liquidity);
 
+    // The LP token received by the receiver is reported and recorded to the source after the provide
+    let prev_lp_balance = query_asset_balance(
+        &deps.querier,
+        &determine_asset_info(&config.pair_info.liquidity_token),
+        &receiver,
+    )?;
+    COMPOUND_STEP.save(deps.storage, &CompoundStep::RecordSource)?;
+    messages.push(
+        CallbackMsg::RecordSource {
+            source,
+            receiver: receiver.clone(),
+            prev_lp_balance,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("action", "provide_liquidity")
+        .add_attributes(attributes)
         .add_attribute("receiver", receiver))
 }
 
-/// Calculate swap amount
-pub(crate) fn get_swap_amount(
-    amount_a: Uint256,
-    amount_b: Uint256,
-    pool_a: Uint256,
-    pool_b: Uint256,
-    commission_bps: u64,
-) -> StdResult<Uint128> {
-    let pool_ax = amount_a + pool_a;
-    let pool_bx = amount_b + pool_b;
-    let area_ax = pool_ax * pool_b;
-    let area_bx = pool_bx * pool_a;
-
-    let a = Uint256::from(commission_bps * commission_bps) * area_ax
-        + Uint256::from(4u64 * (COMMISSION_DENOM - commission_bps) * COMMISSION_DENOM) * area_bx;
-    let b = Uint256::from(commission_bps) * area_ax + area_ax.isqrt() * a.isqrt();
-    let result = (b / Uint256::from(2u64 * COMMISSION_DENOM) / pool_bx).saturating_sub(pool_a);
-
-    result
-        .try_into()
-        .map_err(|_| StdError::generic_err("overflow"))
+/// # Description
+/// Swaps the other asset of the pair received since the swap to asset started to the target asset.
+fn swap_to_target<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    prev_balances: Vec<Asset>,
+    target: AssetInfo,
+    min_receive: Option<Uint128>,
+    receiver: String,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes: Vec<Attribute> = vec![];
+    let mut target_prev_balance = target.with_balance(Uint128::zero());
+    for prev_balance in prev_balances {
+        if prev_balance.info.equal(&target) {
+            target_prev_balance = prev_balance;
+            continue;
+        }
+        let balance = query_asset_balance(&deps.querier, &prev_balance.info, &env.contract.address)?;
+        let swap_asset = prev_balance.info.with_balance(balance.checked_sub(prev_balance.amount)?);
+        if !swap_asset.amount.is_zero() {
+            attributes.push(attr("swap_asset", swap_asset.info.to_string()));
+            attributes.push(attr("swap_amount", swap_asset.amount));
+            messages.push(pool_swap_msg(
+                &deps.querier,
+                &config,
+                &swap_asset,
+                slippage_tolerance.unwrap_or(config.slippage_tolerance),
+            )?);
+        }
+    }
+
+    messages.push(
+        CallbackMsg::SendTarget {
+            prev_balance: target_prev_balance,
+            min_receive,
+            receiver,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "swap_to_target")
+        .add_attributes(attributes))
 }
 
-/// Simulates return amount from the swap
-fn simulate(
-    offer_pool: Uint256,
-    ask_pool: Uint256,
-    offer_amount: Uint256,
-    commission_rate: Decimal256,
-) -> StdResult<Uint128> {
-    // offer => ask
-    // ask_amount = (ask_pool - cp / (offer_pool + offer_amount)) * (1 - commission_rate)
-    let cp: Uint256 = offer_pool * ask_pool;
-    let return_amount: Uint256 = (Decimal256::from_ratio(ask_pool, 1u64)
-        - Decimal256::from_ratio(cp, offer_pool + offer_amount))
-        * Uint256::from(1u64);
-
-    // calculate commission
-    let commission_amount: Uint256 = return_amount * commission_rate;
-
-    // commission will be absorbed to pool
-    let return_amount: Uint256 = return_amount - commission_amount;
-
-    return_amount
-        .try_into()
-        .map_err(|_| StdError::generic_err("overflow"))
+/// # Description
+/// Sends the target asset received since the swap to asset started to the receiver, checking it
+/// against the minimum receive.
+fn send_target<C: CustomQuery>(
+    deps: DepsMut<C>,
+    env: Env,
+    prev_balance: Asset,
+    min_receive: Option<Uint128>,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    let balance = query_asset_balance(&deps.querier, &prev_balance.info, &env.contract.address)?;
+    let amount = balance.checked_sub(prev_balance.amount)?;
+    if let Some(min_receive) = min_receive {
+        if amount < min_receive {
+            return Err(ContractError::MinimumReceive {
+                min_receive,
+                received: amount,
+            });
+        }
+    }
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        messages.push(prev_balance.info.with_balance(amount).transfer_msg(&deps.api.addr_validate(&receiver)?)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "send_target")
+        .add_attribute("asset", prev_balance.info.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("receiver", receiver))
+}
+
+/// # Description
+/// Reports the LP token received by the receiver since the provide and adds it to the source totals.
+fn record_source<C: CustomQuery>(
+    deps: DepsMut<C>,
+    source: Option<String>,
+    receiver: String,
+    prev_lp_balance: Uint128,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    let lp_balance = query_asset_balance(
+        &deps.querier,
+        &determine_asset_info(&config.pair_info.liquidity_token),
+        &receiver,
+    )?;
+    let lp_amount = lp_balance.checked_sub(prev_lp_balance)?;
+
+    let mut attributes: Vec<Attribute> = vec![];
+    if let Some(source) = source {
+        SOURCE_STATS.update(deps.storage, &source, |totals| -> StdResult<_> {
+            let mut totals = totals.unwrap_or_default();
+            totals.lp_amount = totals.lp_amount.checked_add(lp_amount)?;
+            Ok(totals)
+        })?;
+        attributes.push(attr("source", source));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "record_source")
+        .add_attributes(attributes)
+        .add_attribute("receiver", receiver)
+        .add_attribute("lp_amount", lp_amount))
+}
+
+/// ## Description
+/// Returns the cumulative rewards and LP token of the compound sources.
+fn query_source_stats<C: CustomQuery>(
+    deps: Deps<C>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SourceStatsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let sources = SOURCE_STATS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(source, totals)| SourceStats {
+                source,
+                rewards: totals.rewards,
+                lp_amount: totals.lp_amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(SourceStatsResponse { sources })
 }
 
 /// ## Description
 /// Exposes all the queries available in the contract.
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query<C: CustomQuery>(deps: Deps<C>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
         QueryMsg::CompoundSimulation { rewards } => {
             to_binary(&query_compound_simulation(deps, rewards)?)
         }
+        QueryMsg::SourceStats { start_after, limit } => {
+            to_binary(&query_source_stats(deps, start_after, limit)?)
+        }
     }
 }
 
 /// ## Description
-/// Used for migration of contract. Returns the default object of type [`Response`].
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+/// Used for migration of contract. Returns the default object of type [`Response`], or an error
+/// if the stored state is inconsistent.
+pub fn migrate<C: CustomQuery>(deps: DepsMut<C>, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    // the legacy config stored the commission, which is now queried from the factory
+    if let Ok(legacy) = LEGACY_CONFIG.load(deps.storage) {
+        let factory = msg.factory.ok_or_else(|| StdError::generic_err("factory is required to migrate the legacy config"))?;
+        let config = Config {
+            pair_info: legacy.pair_info,
+            factory: deps.api.addr_validate(&factory)?,
+            slippage_tolerance: legacy.slippage_tolerance,
+            price_guard: None,
+            owner: None,
+            commission_tiers: vec![],
+        };
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    if let Some(owner) = msg.owner {
+        let mut config = CONFIG.load(deps.storage)?;
+        if config.owner.is_none() {
+            config.owner = Some(deps.api.addr_validate(&owner)?);
+            CONFIG.save(deps.storage, &config)?;
+        }
+    }
+
+    assert_state(deps.as_ref())?;
+
     Ok(Response::default())
 }
+
+/// ## Description
+/// Checks the stored config and that no compound is halfway through its callbacks.
+fn assert_state<C: CustomQuery>(deps: Deps<C>) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.pair_info.contract_addr, "pair")?;
+    assert_valid_addr(deps.api, &config.pair_info.liquidity_token, "liquidity_token")?;
+    assert_valid_addr(deps.api, &config.factory, "factory")?;
+    if let Some(owner) = &config.owner {
+        assert_valid_addr(deps.api, owner, "owner")?;
+    }
+    if let Some(price_guard) = &config.price_guard {
+        assert_valid_addr(deps.api, &price_guard.oracle.0, "price_guard oracle")?;
+        assert_bps(price_guard.max_deviation_bps, 10000, "max_deviation_bps")?;
+    }
+    assert_ratio(config.slippage_tolerance, "slippage_tolerance")?;
+    for (_, commission_bps) in config.commission_tiers.iter() {
+        assert_bps(*commission_bps, COMMISSION_DENOM - 1, "commission_tiers")?;
+    }
+
+    for item in PAIR_PROXY.range(deps.storage, None, None, Order::Ascending) {
+        let (_, pair) = item?;
+        assert_valid_addr(deps.api, &pair.0, "pair proxy")?;
+    }
+
+    if COMPOUND_STEP.may_load(deps.storage)?.is_some() {
+        return Err(invalid_state("compound in progress"));
+    }
+
+    Ok(())
+}
+
+/// The entry points of the contract on chains without custom queries. A chain-specific build
+/// enables the `library` feature and exports the generic entry points with its own query type.
+#[cfg(not(feature = "library"))]
+pub mod entry {
+    use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+    use spectrum::compound_proxy::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+
+    use crate::error::ContractError;
+
+    #[entry_point]
+    pub fn instantiate(
+        deps: DepsMut<Empty>,
+        env: Env,
+        info: MessageInfo,
+        msg: InstantiateMsg,
+    ) -> Result<Response, ContractError> {
+        super::instantiate(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn execute(
+        deps: DepsMut<Empty>,
+        env: Env,
+        info: MessageInfo,
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
+        super::execute(deps, env, info, msg)
+    }
+
+    #[entry_point]
+    pub fn query(deps: Deps<Empty>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        super::query(deps, env, msg)
+    }
+
+    #[entry_point]
+    pub fn migrate(deps: DepsMut<Empty>, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+        super::migrate(deps, env, msg)
+    }
+}
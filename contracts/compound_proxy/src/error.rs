@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -10,6 +10,33 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Reward must be a native token: {0}")]
+    NonNativeReward(String),
+
+    #[error("Cannot rescue while a compound is in progress")]
+    CompoundInProgress {},
+
+    #[error("Unexpected callback: {0}")]
+    UnexpectedCallback(String),
+
+    #[error("Nothing to rescue")]
+    NothingToRescue {},
+
+    #[error("Duplicated commission tier: {0}")]
+    DuplicatedCommissionTier(String),
+
+    #[error("Target must be an asset in the pair: {0}")]
+    InvalidTarget(String),
+
+    #[error("No route to swap the reward to the pair: {0}")]
+    NoRoute(String),
+
+    #[error("Received amount is below the minimum; minimum receive: {min_receive}, received: {received}")]
+    MinimumReceive { min_receive: Uint128, received: Uint128 },
+
+    #[error("Sent funds do not match the reward {denom}; reward amount: {expected}, sent amount: {sent}")]
+    FundsMismatch { denom: String, expected: Uint128, sent: Uint128 },
 }
 
 impl From<OverflowError> for ContractError {
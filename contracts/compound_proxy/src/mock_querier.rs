@@ -1,16 +1,19 @@
 use astroport::asset::{PairInfo, AssetInfo};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Addr, Coin, Empty, OwnedDeps, Querier, QuerierResult,
+    from_binary, from_slice, to_binary, Addr, Coin, Decimal, Decimal256, Empty, OwnedDeps, Querier, QuerierResult,
     QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
 use std::collections::HashMap;
 
 use astroport::factory::FeeInfoResponse;
 use astroport::factory::QueryMsg::FeeInfo;
-use astroport::pair::QueryMsg::{Pair, Simulation};
+use astroport::pair::QueryMsg::{Config, Pair, Simulation};
+use astroport::pair::ConfigResponse;
+use astroport::pair_concentrated::ConcentratedPoolConfig;
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 use astroport::pair::SimulationResponse;
+use spectrum::oracle::{PriceResponse, QueryMsg as OracleQueryMsg};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -31,6 +34,8 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     token_querier: TokenQuerier,
+    token_decimals: HashMap<String, u8>,
+    oracle_price: Decimal256,
 }
 
 #[derive(Clone, Default)]
@@ -82,7 +87,20 @@ impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
             QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
-                if contract_addr == "factory" {
+                if contract_addr == "oracle" {
+                    match from_binary(msg).unwrap() {
+                        OracleQueryMsg::Price { .. } => SystemResult::Ok(
+                            to_binary(&PriceResponse {
+                                price: self.oracle_price,
+                                start_time: 0,
+                                sources: 1,
+                                max_deviation: Decimal256::zero(),
+                            })
+                            .into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else if contract_addr == "factory" {
                     match from_binary(&msg).unwrap() {
                         FeeInfo { .. } => SystemResult::Ok(
                             to_binary(&FeeInfoResponse {
@@ -134,6 +152,44 @@ impl WasmMockQuerier {
                         ),
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if contract_addr == "pair_concentrated" {
+                    match from_binary(msg).unwrap() {
+                        Pair { .. } => SystemResult::Ok(
+                            to_binary(&PairInfo {
+                                asset_infos: vec![
+                                    {
+                                        AssetInfo::Token { contract_addr: Addr::unchecked("token") }
+                                    },
+                                    {
+                                        AssetInfo::NativeToken { denom: "uluna".to_string() }
+                                    },
+                                ],
+                                contract_addr: Addr::unchecked("pair_concentrated"),
+                                liquidity_token: Addr::unchecked("liquidity_token"),
+                                pair_type: astroport::factory::PairType::Custom("concentrated".to_string()),
+                            })
+                            .into(),
+                        ),
+                        Config { .. } => SystemResult::Ok(
+                            to_binary(&ConfigResponse {
+                                block_time_last: 0,
+                                params: Some(to_binary(&ConcentratedPoolConfig {
+                                    amp: Decimal::from_ratio(40u128, 1u128),
+                                    gamma: Decimal::from_ratio(145u128, 1000000u128),
+                                    mid_fee: Decimal::from_ratio(26u128, 10000u128),
+                                    out_fee: Decimal::from_ratio(45u128, 10000u128),
+                                    fee_gamma: Decimal::from_ratio(23u128, 100000u128),
+                                    repeg_profit_threshold: Decimal::from_ratio(2u128, 1000000u128),
+                                    min_price_scale_delta: Decimal::from_ratio(146u128, 1000000u128),
+                                    price_scale: Decimal::from_ratio(2u128, 1u128),
+                                    ma_half_time: 600,
+                                }).unwrap()),
+                                owner: None,
+                            })
+                            .into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else if contract_addr == "pair_astro_token" {
                     match from_binary(&msg).unwrap() {
                         Pair { .. } => SystemResult::Ok(
@@ -181,30 +237,28 @@ impl WasmMockQuerier {
                                 to_binary(&TokenInfoResponse {
                                     name: "mAPPL".to_string(),
                                     symbol: "mAPPL".to_string(),
-                                    decimals: 6,
+                                    decimals: self
+                                        .token_decimals
+                                        .get(contract_addr)
+                                        .cloned()
+                                        .unwrap_or(6),
                                     total_supply,
                                 })
                                 .into(),
                             )
                         }
                         Cw20QueryMsg::Balance { address } => {
-                            let balances: &HashMap<String, Uint128> =
-                                match self.token_querier.balances.get(contract_addr) {
-                                    Some(balances) => balances,
-                                    None => {
-                                        return SystemResult::Err(SystemError::Unknown {});
-                                    }
-                                };
-
-                            let balance = match balances.get(&address) {
-                                Some(v) => v,
-                                None => {
-                                    return SystemResult::Err(SystemError::Unknown {});
-                                }
-                            };
+                            // like the cw20 contract, accounts without balance hold zero
+                            let balance = self
+                                .token_querier
+                                .balances
+                                .get(contract_addr)
+                                .and_then(|balances| balances.get(&address))
+                                .cloned()
+                                .unwrap_or_default();
 
                             SystemResult::Ok(
-                                to_binary(&BalanceResponse { balance: *balance }).into(),
+                                to_binary(&BalanceResponse { balance }).into(),
                             )
                         }
                         _ => panic!("DO NOT ENTER HERE"),
@@ -221,14 +275,26 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             token_querier: TokenQuerier::default(),
+            token_decimals: HashMap::new(),
+            oracle_price: Decimal256::zero(),
         }
     }
 
+    pub fn with_oracle_price(&mut self, price: Decimal256) {
+        self.oracle_price = price;
+    }
+
     // configure the mint whitelist mock querier
     pub fn with_token_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
         self.token_querier = TokenQuerier::new(balances);
     }
 
+    pub fn with_token_decimals(&mut self, decimals: &[(&String, u8)]) {
+        for (contract_addr, decimals) in decimals {
+            self.token_decimals.insert(contract_addr.to_string(), *decimals);
+        }
+    }
+
     /*// configure the token owner mock querier
     pub fn with_tax(&mut self, rate: Decimal, caps: &[(&String, &Uint128)]) {
         self.tax_querier = TaxQuerier::new(rate, caps);
@@ -1,19 +1,21 @@
 use std::cmp::Ordering;
 
 use crate::{
-    contract::calculate_optimal_swap,
+    contract::{calculate_optimal_swap, is_pool_asset, query_concentrated_price},
     state::{Config, CONFIG, PAIR_PROXY},
 };
-use astroport::{asset::AssetInfo, pair::StablePoolConfig, querier::query_token_precision, U256};
+use astroport::{pair::StablePoolConfig, querier::query_token_precision, U256};
+use astroport::pair_concentrated::is_concentrated;
+use std::convert::TryInto;
 
-use astroport::querier::query_supply;
-use cosmwasm_std::{from_binary, CosmosMsg, Deps, StdError, StdResult, Uint128};
+use cosmwasm_std::{from_binary, CosmosMsg, CustomQuery, Deps, StdError, StdResult, Uint128, Uint256};
 
 use spectrum::compound_proxy::CompoundSimulationResponse;
 
-use astroport::asset::{Asset, AssetInfoExt};
+use astroport::asset::{determine_asset_info, Asset, AssetInfoExt};
 use astroport::factory::PairType;
-use spectrum::adapters::pair::Pair;
+use spectrum::adapters::pair::{Pair, PairAdapter};
+use spectrum::querier::{query_pair_lp_supply, query_pool_reserves};
 
 const ITERATIONS: u8 = 32;
 
@@ -22,8 +24,8 @@ const AMP_PRECISION: u64 = 100;
 
 /// ## Description
 /// Returns simulated amount of LP token from given rewards in a [`CompoundSimulationResponse`].
-pub fn query_compound_simulation(
-    deps: Deps,
+pub fn query_compound_simulation<C: CustomQuery>(
+    deps: Deps<C>,
     rewards: Vec<Asset>,
 ) -> StdResult<CompoundSimulationResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
@@ -33,7 +35,11 @@ pub fn query_compound_simulation(
     let mut asset_b_amount = Uint128::zero();
 
     for reward in rewards {
-        let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
+        let pair_proxy = if is_pool_asset(&config, &reward.info) {
+            None
+        } else {
+            PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?
+        };
         let add_asset = if let Some(pair_proxy) = pair_proxy {
             let simulation_response = pair_proxy.simulate(&deps.querier, &reward, None)?;
             let pair_proxy_info = pair_proxy.query_pair_info(&deps.querier)?;
@@ -58,11 +64,9 @@ pub fn query_compound_simulation(
     }
 
     let pair = Pair(config.pair_info.contract_addr.clone());
-    let mut pools = config
-        .pair_info
-        .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
+    let mut pools = query_pool_reserves(&deps.querier, &config.pair_info)?;
 
-    let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
+    let total_share = query_pair_lp_supply(&deps.querier, &config.pair_info)?;
 
     let (lp_amount, swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
         match config.pair_info.pair_type {
@@ -82,6 +86,7 @@ pub fn query_compound_simulation(
                         &config,
                         asset_a,
                         asset_b,
+                        config.slippage_tolerance,
                         &mut _messages,
                     )?;
 
@@ -133,9 +138,7 @@ pub fn query_compound_simulation(
                 let lp_amount = if total_share.is_zero() {
                     let liquidity_token_precision = query_token_precision(
                         &deps.querier,
-                        &AssetInfo::Token {
-                            contract_addr: config.pair_info.liquidity_token,
-                        },
+                        &determine_asset_info(&config.pair_info.liquidity_token),
                     )?;
 
                     // Initial share = collateral amount
@@ -190,6 +193,57 @@ pub fn query_compound_simulation(
                     Uint128::zero(),
                 )
             }
+            _ if is_concentrated(&config.pair_info.pair_type) => {
+                let asset_a = asset_a_info.with_balance(asset_a_amount);
+                let asset_b = asset_b_info.with_balance(asset_b_amount);
+                let mut _messages: Vec<CosmosMsg> = vec![];
+                let (swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
+                    calculate_optimal_swap(
+                        &deps.querier,
+                        &config,
+                        asset_a,
+                        asset_b,
+                        config.slippage_tolerance,
+                        &mut _messages,
+                    )?;
+
+                if !swap_asset_a_amount.is_zero() {
+                    asset_a_amount -= swap_asset_a_amount;
+                    asset_b_amount += return_b_amount;
+                    pools[0].amount += swap_asset_a_amount;
+                    pools[1].amount -= return_b_amount;
+                }
+
+                if !swap_asset_b_amount.is_zero() {
+                    asset_b_amount -= swap_asset_b_amount;
+                    asset_a_amount += return_a_amount;
+                    pools[1].amount += swap_asset_b_amount;
+                    pools[0].amount -= return_a_amount;
+                }
+
+                if total_share.is_zero() {
+                    return Err(StdError::generic_err("Pool has no liquidity"));
+                }
+
+                // LP share is estimated from the deposit value at the pool's price scale
+                let (price_numerator, price_denominator) =
+                    query_concentrated_price(&deps.querier, &config)?;
+                let deposit_value = Uint256::from(asset_a_amount) * price_denominator
+                    + Uint256::from(asset_b_amount) * price_numerator;
+                let pool_value = Uint256::from(pools[0].amount) * price_denominator
+                    + Uint256::from(pools[1].amount) * price_numerator;
+                let lp_amount = (Uint256::from(total_share) * deposit_value / pool_value)
+                    .try_into()
+                    .map_err(|_| StdError::generic_err("overflow"))?;
+
+                (
+                    lp_amount,
+                    swap_asset_a_amount,
+                    swap_asset_b_amount,
+                    return_a_amount,
+                    return_b_amount,
+                )
+            }
             PairType::Custom(_) => {
                 return Err(StdError::generic_err("Custom pair type not supported"));
             }
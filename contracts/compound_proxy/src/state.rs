@@ -1,13 +1,35 @@
-use astroport::asset::{PairInfo};
-use cosmwasm_std::{Decimal};
+use astroport::asset::{Asset, PairInfo};
+use astroport::factory::PairType;
+use cosmwasm_std::{Addr, Decimal, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use spectrum::adapters::oracle::PriceGuardConfig;
 use spectrum::adapters::pair::Pair;
 
 /// This structure describes the main control config of pair.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    /// The pair info
+    pub pair_info: PairInfo,
+    /// The factory contract address
+    pub factory: Addr,
+    /// The slippage tolerance when swapping on the pair and providing liquidity
+    pub slippage_tolerance: Decimal,
+    /// The guard that aborts compounds while the spot price of the pair deviates from its TWAP
+    #[serde(default)] pub price_guard: Option<PriceGuardConfig>,
+    /// The owner address, allowed to rescue assets stranded in the contract and set the commission tiers
+    #[serde(default)] pub owner: Option<Addr>,
+    /// The commission in bps of each pair type, overriding the live commission of the factory
+    #[serde(default)] pub commission_tiers: Vec<(PairType, u64)>,
+}
+
+/// Stores config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// This structure describes the config stored before the commission was queried from the factory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
     /// The pair info
     pub pair_info: PairInfo,
     /// The swap commission
@@ -16,8 +38,35 @@ pub struct Config {
     pub slippage_tolerance: Decimal,
 }
 
-/// Stores config at the given key
-pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores the legacy config at the same key as [`CONFIG`], replaced by [`Config`] on migration
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
 
 /// Stores pair proxy for the given reward
 pub const PAIR_PROXY: Map<String, Pair> = Map::new("pair_proxy");
+
+/// This enum describes the next callback expected by the compound in progress.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompoundStep {
+    OptimalSwap,
+    ProvideLiquidity,
+    RecordSource,
+    SwapToTarget,
+    SendTarget,
+}
+
+/// Stores the step of the compound in progress, set by compound and removed once liquidity is provided
+/// and the LP token received is recorded. A swap to asset is tracked the same way until the target is sent
+pub const COMPOUND_STEP: Item<CompoundStep> = Item::new("compound_step");
+
+/// This structure stores the cumulative rewards and LP token of a compound source.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SourceTotals {
+    /// The total rewards compounded
+    pub rewards: Vec<Asset>,
+    /// The total LP token received
+    pub lp_amount: Uint128,
+}
+
+/// Stores the totals of each compound source
+pub const SOURCE_STATS: Map<&str, SourceTotals> = Map::new("source_stats");
@@ -1,25 +1,29 @@
 use astroport::asset::{Asset, AssetInfo, PairInfo, native_asset, token_asset};
+use astroport::factory::PairType;
 use astroport::pair::{
     Cw20HookMsg as AstroportPairCw20HookMsg, ExecuteMsg as AstroportPairExecuteMsg,
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{coin, to_binary, Addr, Coin, CosmosMsg, Decimal, Order, StdResult, Uint128, WasmMsg, from_binary, Uint256};
+use cosmwasm_std::{attr, coin, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Decimal256, Event, Order, StdError, StdResult, Uint128, WasmMsg, from_binary};
 use cw20::{Cw20ExecuteMsg};
-use spectrum::adapters::pair::Pair;
-use spectrum::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use spectrum::adapters::pair::{Pair, PairAdapter};
+use spectrum::compound_proxy::{CallbackMsg, CompoundSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SourceStats, SourceStatsResponse};
+use spectrum::math::optimal_swap_amount;
+use spectrum::oracle::PriceGuard;
 
-use crate::contract::{execute, get_swap_amount, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query, query_commission_bps};
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::{Config, PAIR_PROXY};
+use crate::state::{CompoundStep, Config, LegacyConfig, COMPOUND_STEP, CONFIG, LEGACY_CONFIG, PAIR_PROXY};
 
 #[test]
 fn proper_initialization() -> StdResult<()> {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
-        commission_bps: 30,
+        factory: "factory".to_string(),
         pair_proxies: vec![
             (
                 AssetInfo::Token {
@@ -35,6 +39,7 @@ fn proper_initialization() -> StdResult<()> {
             ),
         ],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let sender = "addr0000";
@@ -66,153 +71,1432 @@ fn proper_initialization() -> StdResult<()> {
             pair_type: astroport::factory::PairType::Xyk {}
         }
     );
+    assert_eq!(config.factory, Addr::unchecked("factory"));
+    assert_eq!(config.owner, Some(Addr::unchecked("owner")));
+
+    let pair_proxies = PAIR_PROXY
+        .range(&deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Pair)>>>()?;
+    assert_eq!(
+        pair_proxies,
+        vec![
+            ("ibc/token".to_string(), Pair(Addr::unchecked("pair0002"))),
+            ("token0001".to_string(), Pair(Addr::unchecked("pair0001"))),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let sender = "addr0000";
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000000),
+        }],
+    )]);
+
+    let env = mock_env();
+    let info = mock_info(sender, &[]);
+    let res = instantiate(deps.as_mut(), env, info, msg);
+    assert!(res.is_ok());
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        }],
+        to: None,
+        no_swap: None,
+        slippage_tolerance: None,
+        source: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info(
+        "addr0000",
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::from(1000000u128),
+        }],
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback {
+                    0: CallbackMsg::OptimalSwap { slippage_tolerance: None }
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback {
+                    0: CallbackMsg::ProvideLiquidity {
+                        prev_balances: vec![
+                            token_asset(Addr::unchecked("token"), Uint128::zero()),
+                            native_asset("uluna".to_string(), Uint128::zero())
+                        ],
+                        receiver: "addr0000".to_string(),
+                        slippage_tolerance: None,
+                        source: None,
+                    }
+                })?,
+            }),
+        ]
+    );
+    assert_eq!(
+        res.events,
+        vec![Event::new("spectrum/compound")
+            .add_attribute("stage", "compound")
+            .add_attribute("reward_asset", "uluna")
+            .add_attribute("reward_amount", "1000000")
+            .add_attribute("receiver", "addr0000")]
+    );
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        }],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: Some(Decimal::percent(101)),
+        source: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(
+        res,
+        Err(ContractError::Std(StdError::generic_err(
+            "slippage_tolerance must be 0 to 1"
+        )))
+    );
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000008),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[
+            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(9)),
+        ],
+    )]);
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::from(1000000u128),
+        }],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: Some(Decimal::percent(2)),
+        source: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback {
+                    0: CallbackMsg::ProvideLiquidity {
+                        prev_balances: vec![
+                            token_asset(Addr::unchecked("token"), Uint128::from(9u128)),
+                            native_asset("uluna".to_string(), Uint128::from(8u128))
+                        ],
+                        receiver: "addr0000".to_string(),
+                        slippage_tolerance: Some(Decimal::percent(2)),
+                        source: None,
+                    }
+                })?,
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_duplicate_rewards() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![
+            token_asset(Addr::unchecked("token"), Uint128::from(100u128)),
+            token_asset(Addr::unchecked("token"), Uint128::zero()),
+        ],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(
+        res,
+        Err(ContractError::Std(StdError::generic_err(
+            "reward amount must be greater than 0: token"
+        )))
+    );
+
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(150))],
+    )]);
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![
+            token_asset(Addr::unchecked("token"), Uint128::from(100u128)),
+            token_asset(Addr::unchecked("token"), Uint128::from(50u128)),
+        ],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: Uint128::new(150),
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ProvideLiquidity {
+                    prev_balances: vec![
+                        token_asset(Addr::unchecked("token"), Uint128::new(150)),
+                        native_asset("uluna".to_string(), Uint128::zero())
+                    ],
+                    receiver: "addr0000".to_string(),
+                    slippage_tolerance: None,
+                    source: None,
+                }))?,
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_refund_surplus() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![native_asset("uluna".to_string(), Uint128::from(1000u128))],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: None,
+    };
+
+    // under-funded
+    let info = mock_info("addr0000", &[coin(999, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::FundsMismatch {
+            denom: "uluna".to_string(),
+            expected: Uint128::from(1000u128),
+            sent: Uint128::from(999u128),
+        })
+    );
+
+    // the contract holds the sent funds during execution
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(1200, "uluna"), coin(5, "uusd")],
+    )]);
+
+    // excess uluna and unlisted uusd are refunded
+    let info = mock_info("addr0000", &[coin(1200, "uluna"), coin(5, "uusd")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound"),
+            attr("refund", "200uluna,5uusd"),
+            attr("reward_asset", "uluna"),
+            attr("reward_amount", "1000"),
+            attr("receiver", "addr0000"),
+        ]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(200, "uluna"), coin(5, "uusd")],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ProvideLiquidity {
+                    prev_balances: vec![
+                        token_asset(Addr::unchecked("token"), Uint128::zero()),
+                        native_asset("uluna".to_string(), Uint128::zero())
+                    ],
+                    receiver: "addr0000".to_string(),
+                    slippage_tolerance: None,
+                    source: None,
+                }))?,
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_native() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = ExecuteMsg::CompoundNative {
+        rewards: vec![
+            native_asset("uluna".to_string(), Uint128::from(600u128)),
+            native_asset("uluna".to_string(), Uint128::from(400u128)),
+        ],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: None,
+    };
+
+    // cw20 rewards need an allowance
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::CompoundNative {
+            rewards: vec![token_asset(Addr::unchecked("token"), Uint128::from(100u128))],
+            to: None,
+            no_swap: None,
+            slippage_tolerance: None,
+            source: None,
+        },
+    );
+    assert_eq!(res, Err(ContractError::NonNativeReward("token".to_string())));
+
+    // under-funded
+    let info = mock_info("addr0000", &[coin(999, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::FundsMismatch {
+            denom: "uluna".to_string(),
+            expected: Uint128::from(1000u128),
+            sent: Uint128::from(999u128),
+        })
+    );
+
+    // over-funded
+    let info = mock_info("addr0000", &[coin(1001, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::FundsMismatch {
+            denom: "uluna".to_string(),
+            expected: Uint128::from(1000u128),
+            sent: Uint128::from(1001u128),
+        })
+    );
+
+    // unlisted funds
+    let info = mock_info("addr0000", &[coin(1000, "uluna"), coin(5, "uusd")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::FundsMismatch {
+            denom: "uusd".to_string(),
+            expected: Uint128::zero(),
+            sent: Uint128::from(5u128),
+        })
+    );
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(1000, "uluna")],
+    )]);
+
+    let info = mock_info("addr0000", &[coin(1000, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ProvideLiquidity {
+                prev_balances: vec![
+                    token_asset(Addr::unchecked("token"), Uint128::zero()),
+                    native_asset("uluna".to_string(), Uint128::zero())
+                ],
+                receiver: "addr0000".to_string(),
+                slippage_tolerance: None,
+                source: None,
+            }))?,
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_pool_asset() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    // pair proxies of the pool assets are never used
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![
+            (
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token"),
+                },
+                "pair_token".to_string(),
+            ),
+            (
+                AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                "pair_uluna".to_string(),
+            ),
+        ],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(1200, "uluna")],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[
+            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(9)),
+        ],
+    )]);
+
+    // the rewards are kept as is and counted by the provide
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![
+            token_asset(Addr::unchecked("token"), Uint128::from(100u128)),
+            native_asset("uluna".to_string(), Uint128::from(200u128)),
+        ],
+        to: None,
+        no_swap: None,
+        slippage_tolerance: None,
+        source: None,
+    };
+    let info = mock_info("addr0000", &[coin(200, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: Uint128::new(100),
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::OptimalSwap { slippage_tolerance: None }))?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::ProvideLiquidity {
+                    prev_balances: vec![
+                        token_asset(Addr::unchecked("token"), Uint128::from(9u128)),
+                        native_asset("uluna".to_string(), Uint128::from(1000u128))
+                    ],
+                    receiver: "addr0000".to_string(),
+                    slippage_tolerance: None,
+                    source: None,
+                }))?,
+            }),
+        ]
+    );
+    COMPOUND_STEP.remove(deps.as_mut().storage);
+
+    // the swap to asset only swaps the other pool asset to the target
+    let msg = ExecuteMsg::SwapToAsset {
+        rewards: vec![native_asset("uluna".to_string(), Uint128::from(200u128))],
+        target: AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        },
+        min_receive: None,
+        to: None,
+        slippage_tolerance: None,
+    };
+    let info = mock_info("addr0000", &[coin(200, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SwapToTarget {
+                prev_balances: vec![
+                    token_asset(Addr::unchecked("token"), Uint128::from(9u128)),
+                    native_asset("uluna".to_string(), Uint128::from(1000u128))
+                ],
+                target: AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token"),
+                },
+                min_receive: None,
+                receiver: "addr0000".to_string(),
+                slippage_tolerance: None,
+            }))?,
+        })]
+    );
+    COMPOUND_STEP.remove(deps.as_mut().storage);
+
+    // the simulation is the same as without the pair proxies
+    deps.querier.with_balance(&[(
+        &String::from("pair_contract"),
+        &[coin(1000000000, "uluna")],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("token"),
+            &[
+                (&String::from("pair_contract"), &Uint128::new(1000000000)),
+            ],
+        ),
+        (
+            &String::from("liquidity_token"),
+            &[
+                (&String::from("xxxx"), &Uint128::new(1000000000)),
+            ],
+        )]);
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![
+            token_asset(Addr::unchecked("token"), Uint128::from(100u128)),
+            native_asset("uluna".to_string(), Uint128::from(200u128)),
+        ],
+    };
+    let res: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env.clone(), msg.clone())?)?;
+    PAIR_PROXY.remove(deps.as_mut().storage, "token".to_string());
+    PAIR_PROXY.remove(deps.as_mut().storage, "uluna".to_string());
+    let expected: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res, expected);
+
+    Ok(())
+}
+
+#[test]
+fn compound_source() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(1000, "uluna")],
+    )]);
+
+    // the rewards are recorded to the source on compound
+    let msg = ExecuteMsg::CompoundNative {
+        rewards: vec![native_asset("uluna".to_string(), Uint128::from(1000u128))],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: Some("farm_a".to_string()),
+    };
+    let info = mock_info("addr0000", &[coin(1000, "uluna")]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert!(res.attributes.contains(&attr("source", "farm_a")));
+    let provide_liquidity = CallbackMsg::ProvideLiquidity {
+        prev_balances: vec![
+            token_asset(Addr::unchecked("token"), Uint128::zero()),
+            native_asset("uluna".to_string(), Uint128::zero()),
+        ],
+        receiver: "addr0000".to_string(),
+        slippage_tolerance: None,
+        source: Some("farm_a".to_string()),
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![provide_liquidity.into_cosmos_msg(&env.contract.address)?]
+    );
+
+    // the LP token balance of the receiver is recorded after the provide
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Callback(provide_liquidity))?;
+    let record_source = CallbackMsg::RecordSource {
+        source: Some("farm_a".to_string()),
+        receiver: "addr0000".to_string(),
+        prev_lp_balance: Uint128::zero(),
+    };
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(record_source.into_cosmos_msg(&env.contract.address)?)
+    );
+    assert_eq!(COMPOUND_STEP.may_load(deps.as_ref().storage)?, Some(CompoundStep::RecordSource));
+
+    deps.querier.with_token_balances(&[(
+        &String::from("liquidity_token"),
+        &[(&String::from("addr0000"), &Uint128::new(50))],
+    )]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Callback(record_source.clone()))?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "record_source"),
+            attr("source", "farm_a"),
+            attr("receiver", "addr0000"),
+            attr("lp_amount", "50"),
+        ]
+    );
+    assert_eq!(COMPOUND_STEP.may_load(deps.as_ref().storage)?, None);
+
+    // the record callback is only accepted once
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Callback(record_source));
+    assert_eq!(res, Err(ContractError::UnexpectedCallback("record_source".to_string())));
+
+    let res: SourceStatsResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::SourceStats { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(
+        res.sources,
+        vec![SourceStats {
+            source: "farm_a".to_string(),
+            rewards: vec![native_asset("uluna".to_string(), Uint128::from(1000u128))],
+            lp_amount: Uint128::new(50),
+        }]
+    );
+
+    let res: SourceStatsResponse = from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::SourceStats { start_after: Some("farm_a".to_string()), limit: None },
+    )?)?;
+    assert_eq!(res.sources, vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn swap_to_asset() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![(
+            AssetInfo::NativeToken {
+                denom: "ibc/token".to_string(),
+            },
+            "pair0002".to_string(),
+        )],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg)?;
+
+    // the target must be an asset in the pair
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[]),
+        ExecuteMsg::SwapToAsset {
+            rewards: vec![token_asset(Addr::unchecked("token"), Uint128::from(100u128))],
+            target: AssetInfo::NativeToken {
+                denom: "ibc/token".to_string(),
+            },
+            min_receive: None,
+            to: None,
+            slippage_tolerance: None,
+        },
+    );
+    assert_eq!(res, Err(ContractError::InvalidTarget("ibc/token".to_string())));
+
+    // rewards without a pair proxy must be an asset in the pair
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[coin(100, "uusd")]),
+        ExecuteMsg::SwapToAsset {
+            rewards: vec![native_asset("uusd".to_string(), Uint128::from(100u128))],
+            target: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            min_receive: None,
+            to: None,
+            slippage_tolerance: None,
+        },
+    );
+    assert_eq!(res, Err(ContractError::NoRoute("uusd".to_string())));
+
+    deps.querier.with_balance(&[(
+        &String::from(MOCK_CONTRACT_ADDR),
+        &[coin(55, "ibc/token"), coin(7, "uluna")],
+    )]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(9))],
+    )]);
+
+    let msg = ExecuteMsg::SwapToAsset {
+        rewards: vec![
+            token_asset(Addr::unchecked("token"), Uint128::from(100u128)),
+            native_asset("ibc/token".to_string(), Uint128::from(50u128)),
+        ],
+        target: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        min_receive: Some(Uint128::from(200u128)),
+        to: Some("receiver".to_string()),
+        slippage_tolerance: Some(Decimal::percent(2)),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr0000", &[coin(55, "ibc/token")]),
+        msg,
+    )?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![coin(5, "ibc/token")],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "addr0000".to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: Uint128::new(100),
+                })?,
+            }),
+            Pair(Addr::unchecked("pair0002")).swap_msg(
+                &native_asset("ibc/token".to_string(), Uint128::from(50u128)),
+                None,
+                Some(Decimal::percent(2)),
+                None,
+            )?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SwapToTarget {
+                    prev_balances: vec![
+                        token_asset(Addr::unchecked("token"), Uint128::new(9)),
+                        native_asset("uluna".to_string(), Uint128::new(7)),
+                    ],
+                    target: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    min_receive: Some(Uint128::from(200u128)),
+                    receiver: "receiver".to_string(),
+                    slippage_tolerance: Some(Decimal::percent(2)),
+                }))?,
+            }),
+        ]
+    );
+
+    // assets of a swap in progress cannot be rescued
+    let rescue_msg = ExecuteMsg::Rescue {
+        asset: AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        },
+        to: "recipient".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), rescue_msg.clone());
+    assert_eq!(res, Err(ContractError::CompoundInProgress {}));
+
+    // the target is sent only after the swap to target
+    let send_msg = ExecuteMsg::Callback(CallbackMsg::SendTarget {
+        prev_balance: native_asset("uluna".to_string(), Uint128::new(7)),
+        min_receive: Some(Uint128::from(200u128)),
+        receiver: "receiver".to_string(),
+    });
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), send_msg.clone());
+    assert_eq!(res, Err(ContractError::UnexpectedCallback("send_target".to_string())));
+
+    // the rewards swapped by the pair proxy and the token reward are swapped to the target
+    deps.querier.with_balance(&[(&String::from(MOCK_CONTRACT_ADDR), &[coin(157, "uluna")])]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(109))],
+    )]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Callback(CallbackMsg::SwapToTarget {
+            prev_balances: vec![
+                token_asset(Addr::unchecked("token"), Uint128::new(9)),
+                native_asset("uluna".to_string(), Uint128::new(7)),
+            ],
+            target: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            min_receive: Some(Uint128::from(200u128)),
+            receiver: "receiver".to_string(),
+            slippage_tolerance: Some(Decimal::percent(2)),
+        }),
+    )?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            Pair(Addr::unchecked("pair_contract")).swap_msg(
+                &token_asset(Addr::unchecked("token"), Uint128::from(100u128)),
+                None,
+                Some(Decimal::percent(2)),
+                None,
+            )?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&send_msg)?,
+            }),
+        ]
+    );
+
+    // the received amount is checked against the minimum receive
+    deps.querier.with_balance(&[(&String::from(MOCK_CONTRACT_ADDR), &[coin(206, "uluna")])]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), send_msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::MinimumReceive {
+            min_receive: Uint128::from(200u128),
+            received: Uint128::from(199u128),
+        })
+    );
+
+    // a failed callback reverts the step on chain
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::SendTarget)?;
+    deps.querier.with_balance(&[(&String::from(MOCK_CONTRACT_ADDR), &[coin(257, "uluna")])]);
+    let res = execute(deps.as_mut(), env.clone(), info, send_msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: "receiver".to_string(),
+            amount: vec![coin(250, "uluna")],
+        })]
+    );
+    assert_eq!(COMPOUND_STEP.may_load(deps.as_ref().storage)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn rescue() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg)?;
+
+    let msg = ExecuteMsg::Rescue {
+        asset: AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        },
+        to: "recipient".to_string(),
+    };
+
+    // only owner can rescue
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::NothingToRescue {}));
+
+    // assets of a compound in progress cannot be rescued
+    deps.querier.with_balance(&[(&String::from(MOCK_CONTRACT_ADDR), &[coin(100, "uluna")])]);
+    deps.querier.with_token_balances(&[(
+        &String::from("token"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(50))],
+    )]);
+    let compound_msg = ExecuteMsg::Compound {
+        rewards: vec![native_asset("uluna".to_string(), Uint128::from(100u128))],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[coin(100, "uluna")]), compound_msg)?;
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg.clone());
+    assert_eq!(res, Err(ContractError::CompoundInProgress {}));
+
+    // rescue is available again once liquidity is provided and recorded
+    let callback_msg = ExecuteMsg::Callback(CallbackMsg::ProvideLiquidity {
+        prev_balances: vec![
+            token_asset(Addr::unchecked("token"), Uint128::new(50)),
+            native_asset("uluna".to_string(), Uint128::zero()),
+        ],
+        receiver: "addr0000".to_string(),
+        slippage_tolerance: None,
+        source: None,
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), callback_msg)?;
+    let callback_msg = ExecuteMsg::Callback(CallbackMsg::RecordSource {
+        source: None,
+        receiver: "addr0000".to_string(),
+        prev_lp_balance: Uint128::zero(),
+    });
+    execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), callback_msg)?;
+
+    let res = execute(deps.as_mut(), env, mock_info("owner", &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "recipient".to_string(),
+                amount: Uint128::new(50),
+            })?,
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "rescue"),
+            attr("asset", "token"),
+            attr("amount", "50"),
+            attr("to", "recipient"),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compound_price_guard() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_astro_token".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: Some(PriceGuard {
+            oracle: "oracle".to_string(),
+            window: 3600,
+            max_deviation_bps: 10001,
+        }),
+    };
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_eq!(
+        res,
+        Err(ContractError::Std(StdError::generic_err(
+            "max_deviation_bps must be 0 to 10000"
+        )))
+    );
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_astro_token".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![(
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            "pair_contract_2".to_string(),
+        )],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: Some(PriceGuard {
+            oracle: "oracle".to_string(),
+            window: 3600,
+            max_deviation_bps: 300,
+        }),
+    };
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("astro"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(100))],
+        ),
+        (
+            &String::from("token"),
+            &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::zero())],
+        ),
+    ]);
+
+    // the pair returns 1 token per astro, 5% above the TWAP
+    deps.querier.with_oracle_price(Decimal256::percent(95));
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![token_asset(Addr::unchecked("astro"), Uint128::from(100u128))],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+        source: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::Std(StdError::generic_err(
+            "Spot price 1 deviates from TWAP 0.95 by more than 300 bps"
+        )))
+    );
+
+    deps.querier.with_oracle_price(Decimal256::percent(98));
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(res.attributes[0], attr("action", "compound"));
+
+    // the swaps on the pair proxies believe the TWAP as well, within the slippage tolerance of the call
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![native_asset("uluna".to_string(), Uint128::from(50u128))],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: Some(Decimal::percent(2)),
+        source: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[coin(50, "uluna")]), msg)?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair_contract_2".to_string(),
+            funds: vec![coin(50, "uluna")],
+            msg: to_binary(&AstroportPairExecuteMsg::Swap {
+                offer_asset: native_asset("uluna".to_string(), Uint128::from(50u128)),
+                ask_asset_info: None,
+                belief_price: Some(Decimal::percent(98)),
+                max_spread: Some(Decimal::percent(2)),
+                to: None,
+            })?,
+        })
+    );
+
+    // the swaps on the pair believe the TWAP within the slippage tolerance
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::SwapToTarget)?;
+    let msg = ExecuteMsg::Callback(CallbackMsg::SwapToTarget {
+        prev_balances: vec![
+            token_asset(Addr::unchecked("astro"), Uint128::zero()),
+            token_asset(Addr::unchecked("token"), Uint128::zero()),
+        ],
+        target: AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        },
+        min_receive: None,
+        receiver: "addr0000".to_string(),
+        slippage_tolerance: None,
+    });
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "astro".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_astro_token".to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::percent(98)),
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                })?
+            })?,
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn commission_tiers() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg)?;
 
-    let pair_proxies = PAIR_PROXY
-        .range(&deps.storage, None, None, Order::Ascending)
-        .collect::<StdResult<Vec<(String, Pair)>>>()?;
-    assert_eq!(
-        pair_proxies,
-        vec![
-            ("ibc/token".to_string(), Pair(Addr::unchecked("pair0002"))),
-            ("token0001".to_string(), Pair(Addr::unchecked("pair0001"))),
-        ]
-    );
+    // without a tier the commission is queried from the factory
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(query_commission_bps(&deps.as_ref().querier, &config)?, 30);
+
+    let update_tiers = |commission_tiers: Vec<(PairType, u64)>| ExecuteMsg::UpdateCommissionTiers { commission_tiers };
+
+    // only owner can update the tiers
+    let msg = update_tiers(vec![(PairType::Xyk {}, 20)]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg);
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let msg = update_tiers(vec![(PairType::Xyk {}, 10000)]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+    assert_eq!(res, Err(ContractError::Std(StdError::generic_err("commission rate must be 0 to 9999"))));
+
+    let msg = update_tiers(vec![(PairType::Xyk {}, 20), (PairType::Xyk {}, 25)]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg);
+    assert_eq!(res, Err(ContractError::DuplicatedCommissionTier("xyk".to_string())));
+
+    // the tier of the pair type overrides the factory commission
+    let msg = update_tiers(vec![
+        (PairType::Xyk {}, 20),
+        (PairType::Stable {}, 5),
+        (PairType::Custom("concentrated".to_string()), 50),
+    ]);
+    let res = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "update_commission_tiers")]);
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.commission_tiers.len(), 3);
+    assert_eq!(query_commission_bps(&deps.as_ref().querier, &config)?, 20);
+
+    // other pair types keep the factory commission
+    let msg = update_tiers(vec![(PairType::Stable {}, 5)]);
+    execute(deps.as_mut(), env, mock_info("owner", &[]), msg)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(query_commission_bps(&deps.as_ref().querier, &config)?, 30);
 
     Ok(())
 }
 
 #[test]
-fn compound() -> Result<(), ContractError> {
+fn migrate_state() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
-        commission_bps: 30,
+        factory: "factory".to_string(),
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
-    let sender = "addr0000";
+    let env = mock_env();
+    instantiate(deps.as_mut(), env.clone(), mock_info("addr0000", &[]), msg)?;
 
-    deps.querier.with_balance(&[(
-        &String::from(MOCK_CONTRACT_ADDR),
-        &[Coin {
-            denom: "uluna".to_string(),
-            amount: Uint128::new(1000000),
-        }],
-    )]);
+    // a compound halfway through its callbacks aborts the migration
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::ProvideLiquidity)?;
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None, factory: None });
+    assert_eq!(res, Err(StdError::generic_err("Invalid state: compound in progress")));
+    COMPOUND_STEP.remove(deps.as_mut().storage);
+
+    // a config out of its limits aborts the migration
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let mut corrupt = config.clone();
+    corrupt.slippage_tolerance = Decimal::percent(101);
+    CONFIG.save(deps.as_mut().storage, &corrupt)?;
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None, factory: None });
+    assert_eq!(res, Err(StdError::generic_err("Invalid state: slippage_tolerance must be 0 to 1")));
 
+    CONFIG.save(deps.as_mut().storage, &config)?;
+    migrate(deps.as_mut(), env, MigrateMsg { owner: None, factory: None })?;
+
+    Ok(())
+}
+
+#[test]
+fn migrate_legacy_config() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
     let env = mock_env();
-    let info = mock_info(sender, &[]);
-    let res = instantiate(deps.as_mut(), env, info, msg);
-    assert!(res.is_ok());
 
-    let msg = ExecuteMsg::Compound {
-        rewards: vec![Asset {
-            info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            amount: Uint128::from(1000000u128),
-        }],
-        to: None,
-        no_swap: None,
-        slippage_tolerance: None,
+    let pair_info = PairInfo {
+        asset_infos: vec![
+            AssetInfo::Token { contract_addr: Addr::unchecked("token0001") },
+            AssetInfo::NativeToken { denom: "uusd".to_string() },
+        ],
+        contract_addr: Addr::unchecked("pair_contract"),
+        liquidity_token: Addr::unchecked("liquidity_token"),
+        pair_type: PairType::Xyk {},
     };
+    LEGACY_CONFIG.save(deps.as_mut().storage, &LegacyConfig {
+        pair_info: pair_info.clone(),
+        commission_bps: 30,
+        slippage_tolerance: Decimal::percent(1),
+    })?;
 
-    let env = mock_env();
-    let info = mock_info(
-        "addr0000",
-        &[Coin {
-            denom: "uluna".to_string(),
-            amount: Uint128::from(1000000u128),
-        }],
-    );
+    // the factory is required to replace the stored commission
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None, factory: None });
+    assert_eq!(res, Err(StdError::generic_err("factory is required to migrate the legacy config")));
 
-    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
-    assert_eq!(
-        res.messages
-            .into_iter()
-            .map(|it| it.msg)
-            .collect::<Vec<CosmosMsg>>(),
-        vec![
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::Callback {
-                    0: CallbackMsg::OptimalSwap {}
-                })?,
-            }),
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::Callback {
-                    0: CallbackMsg::ProvideLiquidity {
-                        prev_balances: vec![
-                            token_asset(Addr::unchecked("token"), Uint128::zero()),
-                            native_asset("uluna".to_string(), Uint128::zero())
-                        ],
-                        receiver: "addr0000".to_string(),
-                        slippage_tolerance: None,
-                    }
-                })?,
-            }),
-        ]
-    );
+    migrate(deps.as_mut(), env, MigrateMsg {
+        owner: Some("owner".to_string()),
+        factory: Some("factory".to_string()),
+    })?;
+
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(config, Config {
+        pair_info,
+        factory: Addr::unchecked("factory"),
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+        owner: Some(Addr::unchecked("owner")),
+        commission_tiers: vec![],
+    });
+
+    Ok(())
+}
 
+#[test]
+fn optimal_swap() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
     deps.querier.with_balance(&[(
-        &String::from(MOCK_CONTRACT_ADDR),
+        &String::from("pair_contract"),
         &[Coin {
             denom: "uluna".to_string(),
-            amount: Uint128::new(1000008),
+            amount: Uint128::new(1000000000),
         }],
     )]);
     deps.querier.with_token_balances(&[(
         &String::from("token"),
         &[
-            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(9)),
+            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
+            (&String::from("pair_contract"), &Uint128::new(1000000000)),
         ],
     )]);
 
-    let msg = ExecuteMsg::Compound {
-        rewards: vec![Asset {
-            info: AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            amount: Uint128::from(1000000u128),
-        }],
-        to: None,
-        no_swap: Some(true),
-        slippage_tolerance: Some(Decimal::percent(2)),
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
-    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let info = mock_info("addr0000", &[]);
+
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = ExecuteMsg::Callback {
+        0: CallbackMsg::OptimalSwap { slippage_tolerance: None },
+    };
+
+    let res = execute(deps.as_mut(), env.clone().clone(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    // a self-call outside of a compound is rejected
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(ContractError::UnexpectedCallback("optimal_swap".to_string())));
+
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::OptimalSwap)?;
+    let res = execute(deps.as_mut(), env.clone().clone(), info, msg)?;
+
     assert_eq!(
         res.messages
             .into_iter()
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_contract".to_string(),
+                amount: Uint128::new(500626),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                })?
+            })?,
+        }),]
+    );
+    assert_eq!(
+        res.attributes,
         vec![
-            CosmosMsg::Wasm(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                funds: vec![],
-                msg: to_binary(&ExecuteMsg::Callback {
-                    0: CallbackMsg::ProvideLiquidity {
-                        prev_balances: vec![
-                            token_asset(Addr::unchecked("token"), Uint128::from(9u128)),
-                            native_asset("uluna".to_string(), Uint128::from(8u128))
-                        ],
-                        receiver: "addr0000".to_string(),
-                        slippage_tolerance: Some(Decimal::percent(2))
-                    }
-                })?,
-            }),
+            attr("action", "optimal_swap"),
+            attr("swap_asset", "token"),
+            attr("swap_amount", "500626"),
+            attr("return_asset", "uluna"),
+            attr("return_amount", "498874"),
+            attr("commission_amount", "1501"),
         ]
     );
 
+    // the slippage tolerance of the compound overrides the config
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap {
+        slippage_tolerance: Some(Decimal::permille(5)),
+    });
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::OptimalSwap)?;
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "token".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_contract".to_string(),
+                amount: Uint128::new(500626),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread: Some(Decimal::permille(5)),
+                    to: None,
+                })?
+            })?,
+        })
+    );
+
     Ok(())
 }
 
 #[test]
-fn optimal_swap() -> Result<(), ContractError> {
+fn optimal_swap_unequal_decimals() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
     deps.querier.with_balance(&[(
         &String::from("pair_contract"),
@@ -221,38 +1505,93 @@ fn optimal_swap() -> Result<(), ContractError> {
             amount: Uint128::new(1000000000),
         }],
     )]);
+    // same pool as optimal_swap with an 18 decimals token, the constant product split is scale
+    // invariant so the raw amounts need no decimals normalization
     deps.querier.with_token_balances(&[(
         &String::from("token"),
         &[
-            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
-            (&String::from("pair_contract"), &Uint128::new(1000000000)),
+            (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000000000000000)),
+            (&String::from("pair_contract"), &Uint128::new(1000000000000000000000)),
         ],
     )]);
+    deps.querier.with_token_decimals(&[(&String::from("token"), 18)]);
 
     let env = mock_env();
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
-        commission_bps: 30,
+        factory: "factory".to_string(),
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
 
-    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
-    assert!(res.is_ok());
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { slippage_tolerance: None });
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::OptimalSwap)?;
+    let res = execute(deps.as_mut(), env, info, msg)?;
 
-    let msg = ExecuteMsg::Callback {
-        0: CallbackMsg::OptimalSwap {},
-    };
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "optimal_swap"),
+            attr("swap_asset", "token"),
+            attr("swap_amount", "500626377119548651"),
+            attr("return_asset", "uluna"),
+            attr("return_amount", "498874"),
+            attr("commission_amount", "1501"),
+        ]
+    );
 
-    let res = execute(deps.as_mut(), env.clone().clone(), info, msg.clone());
-    assert_eq!(res, Err(ContractError::Unauthorized {}));
+    Ok(())
+}
+
+#[test]
+fn optimal_swap_concentrated() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[(
+        &String::from("pair_concentrated"),
+        &[Coin {
+            denom: "uluna".to_string(),
+            amount: Uint128::new(1000000000),
+        }],
+    )]);
+    deps.querier.with_token_balances(&[
+        (
+            &String::from("token"),
+            &[
+                (&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1000000)),
+                (&String::from("pair_concentrated"), &Uint128::new(2000000000)),
+            ],
+        ),
+        (
+            &String::from("liquidity_token"),
+            &[(&String::from("xxxx"), &Uint128::new(1000000000))],
+        ),
+    ]);
+
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_concentrated".to_string(),
+        factory: "factory".to_string(),
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
 
     let info = mock_info(env.contract.address.as_str(), &[]);
-    let res = execute(deps.as_mut(), env.clone().clone(), info, msg)?;
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
 
+    // pool is 2000 token : 1000 uluna at price scale 2, so half of the token is swapped
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::OptimalSwap)?;
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap { slippage_tolerance: None });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
     assert_eq!(
         res.messages
             .into_iter()
@@ -262,17 +1601,43 @@ fn optimal_swap() -> Result<(), ContractError> {
             contract_addr: "token".to_string(),
             funds: vec![],
             msg: to_binary(&Cw20ExecuteMsg::Send {
-                contract: "pair_contract".to_string(),
-                amount: Uint128::new(500626),
+                contract: "pair_concentrated".to_string(),
+                amount: Uint128::new(500000),
                 msg: to_binary(&AstroportPairCw20HookMsg::Swap {
                     ask_asset_info: None,
-                    belief_price: Some(Decimal::MAX),
-                    max_spread: Some(Decimal::percent(50)),
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
                     to: None,
                 })?
             })?,
         }),]
     );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "optimal_swap"),
+            attr("swap_asset", "token"),
+            attr("swap_amount", "500000"),
+            attr("return_asset", "uluna"),
+            attr("return_amount", "249250"),
+            attr("commission_amount", "750"),
+        ]
+    );
+
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![token_asset(Addr::unchecked("token"), Uint128::new(1000000))],
+    };
+    let res: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(
+        res,
+        CompoundSimulationResponse {
+            lp_amount: Uint128::new(249624),
+            swap_asset_a_amount: Uint128::new(500000),
+            swap_asset_b_amount: Uint128::zero(),
+            return_a_amount: Uint128::zero(),
+            return_b_amount: Uint128::new(249250),
+        }
+    );
 
     Ok(())
 }
@@ -312,10 +1677,12 @@ fn provide_liquidity() -> Result<(), ContractError> {
     let env = mock_env();
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract_2".to_string(),
-        commission_bps: 30,
+        factory: "factory".to_string(),
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -330,12 +1697,25 @@ fn provide_liquidity() -> Result<(), ContractError> {
             native_asset("uluna".to_string(), Uint128::new(1)),
         ],
         slippage_tolerance: None,
+        source: None,
     });
 
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
     assert_eq!(res, Err(ContractError::Unauthorized {}));
 
+    // liquidity is only provided after the optimal swap of a compound
     let info = mock_info(env.contract.address.as_str(), &[]);
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::OptimalSwap)?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(ContractError::UnexpectedCallback("provide_liquidity".to_string())));
+
+    // the LP token received by the receiver is recorded after the provide
+    let record_lp = CallbackMsg::RecordSource {
+        source: None,
+        receiver: "sender".to_string(),
+        prev_lp_balance: Uint128::zero(),
+    };
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::ProvideLiquidity)?;
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
     assert_eq!(
         res.messages
@@ -369,6 +1749,7 @@ fn provide_liquidity() -> Result<(), ContractError> {
                     receiver: Some("sender".to_string()),
                 })?,
             }),
+            record_lp.into_cosmos_msg(&env.contract.address)?,
         ]
     );
 
@@ -401,7 +1782,12 @@ fn provide_liquidity() -> Result<(), ContractError> {
         ),
     ]);
 
-    let res = execute(deps.as_mut(), env, info, msg)?;
+    // the step is cleared once liquidity is provided
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_eq!(res, Err(ContractError::UnexpectedCallback("provide_liquidity".to_string())));
+
+    COMPOUND_STEP.save(deps.as_mut().storage, &CompoundStep::ProvideLiquidity)?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
         res.messages
             .into_iter()
@@ -433,21 +1819,39 @@ fn provide_liquidity() -> Result<(), ContractError> {
                     receiver: Some("sender".to_string()),
                 })?,
             }),
+            record_lp.clone().into_cosmos_msg(&env.contract.address)?,
         ]
     );
+    assert_eq!(COMPOUND_STEP.may_load(deps.as_ref().storage)?, Some(CompoundStep::RecordSource));
+
+    // the LP token is reported without a source
+    deps.querier.with_token_balances(&[(
+        &String::from("liquidity_token"),
+        &[(&String::from("sender"), &Uint128::new(100))],
+    )]);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Callback(record_lp))?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "record_source"),
+            attr("receiver", "sender"),
+            attr("lp_amount", "100"),
+        ]
+    );
+    assert_eq!(COMPOUND_STEP.may_load(deps.as_ref().storage)?, None);
 
     Ok(())
 }
 
 #[test]
 fn test_get_swap_amount() -> StdResult<()> {
-    let amount_a = Uint256::from(1146135045u128);
-    let amount_b = Uint256::from(9093887u128);
-    let pool_a = Uint256::from(114613504500u128);
-    let pool_b = Uint256::from(909388700u128);
+    let amount_a = Uint128::from(1146135045u128);
+    let amount_b = Uint128::from(9093887u128);
+    let pool_a = Uint128::from(114613504500u128);
+    let pool_b = Uint128::from(909388700u128);
     let commission_bps = 30u64;
 
-    let result = get_swap_amount(
+    let result = optimal_swap_amount(
         amount_a,
         amount_b,
         pool_a,
@@ -486,8 +1890,9 @@ fn test_compound_simulation() -> StdResult<()> {
         )]);
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
-        commission_bps: 30,
+        factory: "factory".to_string(),
         pair_proxies: vec![
             (
                 AssetInfo::Token {
@@ -497,6 +1902,7 @@ fn test_compound_simulation() -> StdResult<()> {
             ),
         ],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let sender = "addr0000";
@@ -0,0 +1,627 @@
+use crate::error::ContractError;
+use crate::state::{
+    Config, FarmSchedule, ScheduledTask, COMPOUND_CURSOR, COMPOUND_REPLIES, CONFIG, FARMS,
+    FARM_SCHEDULES, OWNERSHIP_PROPOSAL, SCHEDULED_TASK,
+};
+
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Api, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order,
+    Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use spectrum::astroport_farm::ExecuteMsg as FarmExecuteMsg;
+use spectrum::adapters::scheduler::Scheduler;
+use spectrum::helper::{assert_valid_addr, invalid_state};
+use spectrum::farm_controller::{
+    ExecuteMsg, FarmScheduleInfo, FarmSchedulesResponse, FarmsResponse, InstantiateMsg, MigrateMsg,
+    QueryMsg, TaskSchedule,
+};
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// The reply ids of the scheduler messages, compound replies are keyed from 0
+const SCHEDULE_TASK_REPLY_ID: u64 = u64::MAX - 1;
+const CANCEL_TASK_REPLY_ID: u64 = u64::MAX;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        operator: deps.api.addr_validate(&msg.operator)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    for farm in validate_farms(deps.api, &msg.farms)? {
+        FARMS.save(deps.storage, &farm, &true)?;
+    }
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { operator } => update_config(deps, info, operator),
+        ExecuteMsg::AddFarms { farms } => add_farms(deps, info, farms),
+        ExecuteMsg::RemoveFarms { farms } => remove_farms(deps, info, farms),
+        ExecuteMsg::CompoundAll { limit } => compound_all(deps, env, info, limit),
+        ExecuteMsg::CompoundFarms { farms } => compound_farms(deps, env, info, farms),
+        ExecuteMsg::CompoundNext { max_farms } => compound_next(deps, env, info, max_farms),
+        ExecuteMsg::SetFarmPriority { farm, priority } => {
+            set_farm_priority(deps, info, farm, priority)
+        }
+        ExecuteMsg::ScheduleCompound { schedule, limit } => {
+            schedule_compound(deps, env, info, schedule, limit)
+        }
+        ExecuteMsg::FundTask {} => fund_task(deps, info),
+        ExecuteMsg::CancelTask {} => cancel_task(deps, info),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Validates the farm addresses.
+fn validate_farms(api: &dyn Api, farms: &[String]) -> StdResult<Vec<Addr>> {
+    farms.iter().map(|farm| api.addr_validate(farm)).collect()
+}
+
+/// ## Description
+/// Updates contract config. Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(operator) = operator {
+        config.operator = deps.api.addr_validate(&operator)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Adds farms to the registry. Only the owner can execute this.
+fn add_farms(
+    deps: DepsMut,
+    info: MessageInfo,
+    farms: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for farm in validate_farms(deps.api, &farms)? {
+        FARMS.save(deps.storage, &farm, &true)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_farms"),
+        attr("farms", farms.join(",")),
+    ]))
+}
+
+/// ## Description
+/// Removes farms from the registry. Only the owner can execute this.
+fn remove_farms(
+    deps: DepsMut,
+    info: MessageInfo,
+    farms: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for farm in validate_farms(deps.api, &farms)? {
+        FARMS.remove(deps.storage, &farm);
+        FARM_SCHEDULES.remove(deps.storage, &farm);
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_farms"),
+        attr("farms", farms.join(",")),
+    ]))
+}
+
+/// ## Description
+/// Checks that the sender is the owner, the operator or the scheduler of the compound task.
+fn assert_operator(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    let config = CONFIG.load(storage)?;
+    if *sender == config.owner || *sender == config.operator {
+        return Ok(());
+    }
+    match SCHEDULED_TASK.may_load(storage)? {
+        Some(task) if task.scheduler.executor() == sender => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// ## Description
+/// Compounds registered farms. With a limit, farms are compounded in a round robin resuming after
+/// the last farm compounded by the previous call, otherwise all farms are compounded.
+fn compound_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    assert_operator(deps.storage, &info.sender)?;
+
+    let farms = match limit {
+        None => FARMS
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?,
+        Some(limit) => {
+            let limit = limit as usize;
+            let cursor = COMPOUND_CURSOR.may_load(deps.storage)?;
+            let mut farms = FARMS
+                .keys(deps.storage, cursor.as_ref().map(Bound::exclusive), None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?;
+
+            // wrap around to the first farms
+            if farms.len() < limit && cursor.is_some() {
+                let wrapped = FARMS
+                    .keys(deps.storage, None, cursor.as_ref().map(Bound::inclusive), Order::Ascending)
+                    .take(limit - farms.len())
+                    .collect::<StdResult<Vec<_>>>()?;
+                farms.extend(wrapped);
+            }
+
+            if let Some(last) = farms.last() {
+                COMPOUND_CURSOR.save(deps.storage, last)?;
+            }
+            farms
+        }
+    };
+
+    let messages = build_compound_msgs(deps.storage, &farms, env.block.height)?;
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("action", "compound_all"),
+            attr("farm_count", farms.len().to_string()),
+        ]))
+}
+
+/// ## Description
+/// Compounds the specified registered farms.
+fn compound_farms(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    farms: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_operator(deps.storage, &info.sender)?;
+
+    let farms = validate_farms(deps.api, &farms)?;
+    for farm in farms.iter() {
+        if !FARMS.has(deps.storage, farm) {
+            return Err(ContractError::FarmNotFound { farm: farm.to_string() });
+        }
+    }
+
+    let messages = build_compound_msgs(deps.storage, &farms, env.block.height)?;
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("action", "compound_farms"),
+            attr("farm_count", farms.len().to_string()),
+        ]))
+}
+
+/// ## Description
+/// Compounds the registered farms with the most blocks since their last compound, weighted by
+/// their priority. Farms compounded in the current block are skipped.
+fn compound_next(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_farms: u32,
+) -> Result<Response, ContractError> {
+    assert_operator(deps.storage, &info.sender)?;
+
+    if max_farms == 0 {
+        return Err(StdError::generic_err("max_farms must be greater than 0").into());
+    }
+
+    let mut candidates = FARMS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|farm| {
+            let farm = farm?;
+            let schedule = load_farm_schedule(deps.storage, &farm)?;
+            let staleness = env.block.height.saturating_sub(schedule.last_compound_height) as u128
+                * schedule.priority as u128;
+            Ok((farm, staleness))
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // stalest first, the stable sort keeps ties in address order
+    candidates.retain(|(_, staleness)| *staleness > 0);
+    candidates.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let farms: Vec<Addr> = candidates
+        .into_iter()
+        .take(max_farms as usize)
+        .map(|(farm, _)| farm)
+        .collect();
+
+    let messages = build_compound_msgs(deps.storage, &farms, env.block.height)?;
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("action", "compound_next"),
+            attr("farm_count", farms.len().to_string()),
+        ]))
+}
+
+/// ## Description
+/// Sets the priority of a registered farm. Only the owner can execute this.
+fn set_farm_priority(
+    deps: DepsMut,
+    info: MessageInfo,
+    farm: String,
+    priority: u32,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if priority == 0 {
+        return Err(StdError::generic_err("priority must be greater than 0").into());
+    }
+
+    let farm_addr = deps.api.addr_validate(&farm)?;
+    if !FARMS.has(deps.storage, &farm_addr) {
+        return Err(ContractError::FarmNotFound { farm });
+    }
+
+    let mut schedule = load_farm_schedule(deps.storage, &farm_addr)?;
+    schedule.priority = priority;
+    FARM_SCHEDULES.save(deps.storage, &farm_addr, &schedule)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_farm_priority"),
+        attr("farm", farm),
+        attr("priority", priority.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Returns the compound schedule of the farm, the default if the farm has none stored.
+fn load_farm_schedule(storage: &dyn Storage, farm: &Addr) -> StdResult<FarmSchedule> {
+    Ok(FARM_SCHEDULES.may_load(storage, farm)?.unwrap_or_default())
+}
+
+/// ## Description
+/// Builds a compound submessage for each farm and records the height as its last compound. A
+/// failed compound is reported in the reply instead of reverting the other farms.
+fn build_compound_msgs(
+    storage: &mut dyn Storage,
+    farms: &[Addr],
+    height: u64,
+) -> StdResult<Vec<SubMsg>> {
+    farms
+        .iter()
+        .enumerate()
+        .map(|(id, farm)| {
+            COMPOUND_REPLIES.save(storage, id as u64, farm)?;
+            let mut schedule = load_farm_schedule(storage, farm)?;
+            schedule.last_compound_height = height;
+            FARM_SCHEDULES.save(storage, farm, &schedule)?;
+            Ok(SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: farm.to_string(),
+                    msg: to_binary(&FarmExecuteMsg::Compound {
+                        minimum_receive: None,
+                        slippage_tolerance: None,
+                    })?,
+                    funds: vec![],
+                },
+                id as u64,
+            ))
+        })
+        .collect()
+}
+
+/// ## Description
+/// Registers a recurring CompoundAll with the scheduler, funded with the sent funds. Only the
+/// owner can execute this.
+fn schedule_compound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    schedule: TaskSchedule,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if SCHEDULED_TASK.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::TaskAlreadyScheduled {});
+    }
+
+    let scheduler = Scheduler::from_schedule(deps.api, schedule)?;
+    let compound = WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_binary(&ExecuteMsg::CompoundAll { limit })?,
+        funds: vec![],
+    };
+    let create_task = scheduler.create_task_msg("spectrum-compound", compound.into(), info.funds)?;
+
+    SCHEDULED_TASK.save(
+        deps.storage,
+        &ScheduledTask {
+            scheduler,
+            limit,
+            task_id: None,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(create_task, SCHEDULE_TASK_REPLY_ID))
+        .add_attribute("action", "schedule_compound"))
+}
+
+/// ## Description
+/// Adds the sent funds to the balance of the scheduled task.
+fn fund_task(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let task = SCHEDULED_TASK
+        .may_load(deps.storage)?
+        .ok_or(ContractError::TaskNotFound {})?;
+    let task_id = task.task_id.ok_or(ContractError::TaskNotFound {})?;
+
+    if info.funds.is_empty() {
+        return Err(StdError::generic_err("no funds sent").into());
+    }
+
+    Ok(Response::new()
+        .add_message(task.scheduler.fund_task_msg(&task_id, info.funds)?)
+        .add_attribute("action", "fund_task"))
+}
+
+/// ## Description
+/// Removes the scheduled task. The scheduler refunds the task balance to this contract, which is
+/// sent to the owner in the reply. Only the owner can execute this.
+fn cancel_task(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let task = SCHEDULED_TASK
+        .may_load(deps.storage)?
+        .ok_or(ContractError::TaskNotFound {})?;
+    let task_id = task.task_id.ok_or(ContractError::TaskNotFound {})?;
+    SCHEDULED_TASK.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            task.scheduler.remove_task_msg(&task_id)?,
+            CANCEL_TASK_REPLY_ID,
+        ))
+        .add_attribute("action", "cancel_task"))
+}
+
+/// ## Description
+/// Records the id of the created task, refunds the balance of the removed task and reports a
+/// failed farm compound.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SCHEDULE_TASK_REPLY_ID => return save_task_id(deps, msg.result),
+        CANCEL_TASK_REPLY_ID => return refund_task_balance(deps, env),
+        _ => {}
+    }
+
+    match msg.result {
+        SubMsgResult::Err(err) => {
+            let farm = COMPOUND_REPLIES.load(deps.storage, msg.id)?;
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "compound_failed"),
+                attr("farm", farm),
+                attr("error", err),
+            ]))
+        }
+        SubMsgResult::Ok(_) => Ok(Response::default()),
+    }
+}
+
+/// ## Description
+/// Saves the task id from the events of the scheduler.
+fn save_task_id(deps: DepsMut, result: SubMsgResult) -> Result<Response, ContractError> {
+    let mut task = SCHEDULED_TASK.load(deps.storage)?;
+    let key = task.scheduler.task_id_key();
+    let task_id = result
+        .into_result()
+        .map_err(StdError::generic_err)?
+        .events
+        .into_iter()
+        .flat_map(|event| event.attributes)
+        .find(|attr| attr.key == key)
+        .map(|attr| attr.value)
+        .ok_or_else(|| StdError::generic_err(format!("{} not found in the scheduler events", key)))?;
+
+    task.task_id = Some(task_id.clone());
+    SCHEDULED_TASK.save(deps.storage, &task)?;
+
+    Ok(Response::new().add_attribute("task_id", task_id))
+}
+
+/// ## Description
+/// Sends the refunded task balance to the owner, this contract holds no other funds.
+fn refund_task_balance(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let balance = deps.querier.query_all_balances(env.contract.address)?;
+    if balance.is_empty() {
+        return Ok(Response::default());
+    }
+
+    Ok(Response::new().add_message(BankMsg::Send {
+        to_address: config.owner.to_string(),
+        amount: balance,
+    }))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Farms { start_after, limit } => to_binary(&query_farms(deps, start_after, limit)?),
+        QueryMsg::ScheduledTask {} => to_binary(&SCHEDULED_TASK.may_load(deps.storage)?),
+        QueryMsg::FarmSchedules { start_after, limit } => {
+            to_binary(&query_farm_schedules(deps, start_after, limit)?)
+        }
+    }
+}
+
+/// ## Description
+/// Returns the registered farms.
+fn query_farms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FarmsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    let farms = FARMS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|farm| Ok(farm?.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FarmsResponse { farms })
+}
+
+/// ## Description
+/// Returns the compound schedule of the registered farms.
+fn query_farm_schedules(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FarmSchedulesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    let farms = FARMS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|farm| {
+            let farm = farm?;
+            let schedule = load_farm_schedule(deps.storage, &farm)?;
+            Ok(FarmScheduleInfo {
+                farm: farm.to_string(),
+                priority: schedule.priority,
+                last_compound_height: schedule.last_compound_height,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FarmSchedulesResponse { farms })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored state is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored addresses and that every compound schedule belongs to a registered farm.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.operator, "operator")?;
+
+    for farm in FARMS.keys(deps.storage, None, None, Order::Ascending) {
+        assert_valid_addr(deps.api, &farm?, "farm")?;
+    }
+    for item in FARM_SCHEDULES.range(deps.storage, None, None, Order::Ascending) {
+        let (farm, schedule) = item?;
+        if !FARMS.has(deps.storage, &farm) {
+            return Err(invalid_state(&format!("schedule of unregistered farm {}", farm)));
+        }
+        if schedule.priority == 0 {
+            return Err(invalid_state(&format!("zero priority for {}", farm)));
+        }
+    }
+
+    if let Some(task) = SCHEDULED_TASK.may_load(deps.storage)? {
+        assert_valid_addr(deps.api, task.scheduler.executor(), "scheduler")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes farm controller contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Farm is not registered: {farm}")]
+    FarmNotFound { farm: String },
+
+    #[error("Compound is already scheduled")]
+    TaskAlreadyScheduled {},
+
+    #[error("Compound is not scheduled")]
+    TaskNotFound {},
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
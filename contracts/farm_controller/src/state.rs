@@ -0,0 +1,65 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::Addr;
+use spectrum::adapters::scheduler::Scheduler;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores the main parameter for the farm controller contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to update config and the farm registry
+    pub owner: Addr,
+    /// Address that's allowed to compound the farms
+    pub operator: Addr,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the registered farms
+pub const FARMS: Map<&Addr, bool> = Map::new("farms");
+
+/// This structure stores the compound schedule of a registered farm.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmSchedule {
+    /// The weight of the blocks since the last compound when CompoundNext picks farms
+    pub priority: u32,
+    /// The block height the farm was last compounded
+    pub last_compound_height: u64,
+}
+
+impl Default for FarmSchedule {
+    fn default() -> Self {
+        FarmSchedule {
+            priority: 1,
+            last_compound_height: 0,
+        }
+    }
+}
+
+/// Stores the compound schedule of the registered farms, farms without one have the default
+pub const FARM_SCHEDULES: Map<&Addr, FarmSchedule> = Map::new("farm_schedules");
+
+/// Stores the last farm compounded by CompoundAll, the next call resumes after it
+pub const COMPOUND_CURSOR: Item<Addr> = Item::new("compound_cursor");
+
+/// Stores the farm compounded by each compound submessage, keyed by reply id
+pub const COMPOUND_REPLIES: Map<u64, Addr> = Map::new("compound_replies");
+
+/// This structure stores the recurring compound registered with an on-chain scheduler.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduledTask {
+    /// The scheduler executing the task
+    pub scheduler: Scheduler,
+    /// The max number of farms compounded by each execution
+    pub limit: Option<u32>,
+    /// The task id assigned by the scheduler, set once the task is created
+    pub task_id: Option<String>,
+}
+
+/// Stores the scheduled compound task
+pub const SCHEDULED_TASK: Item<ScheduledTask> = Item::new("scheduled_task");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
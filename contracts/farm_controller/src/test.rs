@@ -0,0 +1,662 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, coins, from_binary, to_binary, Addr, BankMsg, Event, OwnedDeps, Reply, ReplyOn, Response,
+    StdError, SubMsgResponse, SubMsgResult, Timestamp, Uint128, WasmMsg,
+};
+use spectrum::adapters::scheduler::{
+    CroncatAction, CroncatExecuteMsg, CroncatInterval, CroncatTaskRequest, Scheduler, WarpCreateJobMsg,
+    WarpExecuteMsg,
+};
+use spectrum::astroport_farm::ExecuteMsg as FarmExecuteMsg;
+use spectrum::farm_controller::{
+    ExecuteMsg, FarmScheduleInfo, FarmSchedulesResponse, FarmsResponse, InstantiateMsg, MigrateMsg,
+    QueryMsg, TaskSchedule,
+};
+
+use crate::contract::{execute, instantiate, migrate as migrate_contract, query, reply};
+use crate::error::ContractError;
+use crate::state::{Config, FarmSchedule, ScheduledTask, FARM_SCHEDULES};
+
+const OWNER: &str = "owner";
+const OPERATOR_1: &str = "operator_1";
+const OPERATOR_2: &str = "operator_2";
+const USER_1: &str = "user_1";
+const USER_2: &str = "user_2";
+const FARM_1: &str = "farm_1";
+const FARM_2: &str = "farm_2";
+const FARM_3: &str = "farm_3";
+const CRONCAT: &str = "croncat";
+const WARP: &str = "warp";
+const WARP_ACCOUNT: &str = "warp_account";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    config(&mut deps)?;
+    owner(&mut deps)?;
+    farms(&mut deps)?;
+    compound(&mut deps)?;
+    compound_next(&mut deps)?;
+    schedule(&mut deps)?;
+    migrate(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn assert_compound_farms(res: &Response, farms: &[&str]) {
+    assert_eq!(res.messages.len(), farms.len());
+    for (id, (msg, farm)) in res.messages.iter().zip(farms).enumerate() {
+        assert_eq!(msg.id, id as u64);
+        assert_eq!(msg.reply_on, ReplyOn::Error);
+        assert_eq!(
+            msg.msg,
+            WasmMsg::Execute {
+                contract_addr: farm.to_string(),
+                msg: to_binary(&FarmExecuteMsg::Compound {
+                    minimum_receive: None,
+                    slippage_tolerance: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+    }
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        operator: OPERATOR_1.to_string(),
+        farms: vec![FARM_1.to_string()],
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(USER_1),
+            operator: Addr::unchecked(OPERATOR_1),
+        }
+    );
+
+    Ok(())
+}
+
+fn config(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: Some(OPERATOR_2.to_string()),
+    };
+
+    // unauthorized
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(config.operator, Addr::unchecked(OPERATOR_2));
+
+    Ok(())
+}
+
+fn owner(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(0);
+
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: OWNER.to_string(),
+        expires_in: 100,
+    };
+
+    // unauthorized check
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // propose new owner
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // unauthorized ownership claim
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {});
+    assert_error(res, "Unauthorized");
+
+    // claim ownership
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {})?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(config.owner, Addr::unchecked(OWNER));
+
+    Ok(())
+}
+
+fn farms(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::AddFarms {
+        farms: vec![FARM_2.to_string(), FARM_3.to_string()],
+    };
+
+    // unauthorized
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "add_farms"), attr("farms", "farm_2,farm_3")]
+    );
+
+    let res: FarmsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Farms { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(res.farms, vec![FARM_1, FARM_2, FARM_3]);
+
+    let res: FarmsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Farms { start_after: Some(FARM_1.to_string()), limit: Some(1) },
+    )?)?;
+    assert_eq!(res.farms, vec![FARM_2]);
+
+    // remove farm
+    let msg = ExecuteMsg::RemoveFarms {
+        farms: vec![FARM_2.to_string()],
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    let res: FarmsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Farms { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(res.farms, vec![FARM_1, FARM_3]);
+
+    // add back
+    let info = mock_info(OWNER, &[]);
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::AddFarms { farms: vec![FARM_2.to_string()] },
+    )?;
+
+    Ok(())
+}
+
+fn compound(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // only owner and operator can compound
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: None });
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CompoundFarms { farms: vec![FARM_1.to_string()] },
+    );
+    assert_error(res, "Unauthorized");
+
+    // compound all farms
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: None })?;
+    assert_compound_farms(&res, &[FARM_1, FARM_2, FARM_3]);
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "compound_all"), attr("farm_count", "3")]
+    );
+
+    // round robin with limit
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: Some(2) })?;
+    assert_compound_farms(&res, &[FARM_1, FARM_2]);
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: Some(2) })?;
+    assert_compound_farms(&res, &[FARM_3, FARM_1]);
+
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: Some(5) })?;
+    assert_compound_farms(&res, &[FARM_2, FARM_3, FARM_1]);
+
+    // compound specific farms
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CompoundFarms { farms: vec!["farm_4".to_string()] },
+    );
+    assert_error(res, "Farm is not registered: farm_4");
+
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::CompoundFarms { farms: vec![FARM_3.to_string(), FARM_2.to_string()] },
+    )?;
+    assert_compound_farms(&res, &[FARM_3, FARM_2]);
+
+    // failed compound is reported without reverting
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: 1,
+            result: SubMsgResult::Err("Unauthorized".to_string()),
+        },
+    )?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "compound_failed"),
+            attr("farm", FARM_2),
+            attr("error", "Unauthorized"),
+        ]
+    );
+
+    Ok(())
+}
+
+fn compound_next(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+
+    // only owner and operator can compound
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CompoundNext { max_farms: 2 });
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CompoundNext { max_farms: 0 });
+    assert_error(res, "max_farms must be greater than 0");
+
+    // farms compounded in this block are skipped
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CompoundNext { max_farms: 3 })?;
+    assert_compound_farms(&res, &[]);
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "compound_next"), attr("farm_count", "0")]
+    );
+
+    // set priority
+    let msg = ExecuteMsg::SetFarmPriority {
+        farm: FARM_3.to_string(),
+        priority: 3,
+    };
+
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::SetFarmPriority { farm: FARM_3.to_string(), priority: 0 },
+    );
+    assert_error(res, "priority must be greater than 0");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::SetFarmPriority { farm: "farm_4".to_string(), priority: 3 },
+    );
+    assert_error(res, "Farm is not registered: farm_4");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let res: FarmSchedulesResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::FarmSchedules { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(
+        res.farms,
+        vec![
+            FarmScheduleInfo { farm: FARM_1.to_string(), priority: 1, last_compound_height: 12345 },
+            FarmScheduleInfo { farm: FARM_2.to_string(), priority: 1, last_compound_height: 12345 },
+            FarmScheduleInfo { farm: FARM_3.to_string(), priority: 3, last_compound_height: 12345 },
+        ]
+    );
+
+    // the blocks since the last compound are weighted by priority
+    env.block.height = 12355;
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CompoundNext { max_farms: 2 })?;
+    assert_compound_farms(&res, &[FARM_3, FARM_1]);
+
+    // ties are picked in address order
+    env.block.height = 12360;
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CompoundNext { max_farms: 2 })?;
+    assert_compound_farms(&res, &[FARM_2, FARM_3]);
+
+    env.block.height = 12361;
+    let info = mock_info(OPERATOR_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::CompoundNext { max_farms: 1 })?;
+    assert_compound_farms(&res, &[FARM_1]);
+
+    let res: FarmSchedulesResponse = from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::FarmSchedules { start_after: Some(FARM_1.to_string()), limit: Some(1) },
+    )?)?;
+    assert_eq!(
+        res.farms,
+        vec![FarmScheduleInfo { farm: FARM_2.to_string(), priority: 1, last_compound_height: 12360 }]
+    );
+
+    Ok(())
+}
+
+fn schedule(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::ScheduleCompound {
+        schedule: TaskSchedule::Croncat {
+            manager: CRONCAT.to_string(),
+            interval: 100,
+            gas_limit: Some(2_000_000),
+        },
+        limit: Some(2),
+    };
+
+    // only owner can schedule
+    let info = mock_info(OPERATOR_2, &coins(1000, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // zero interval
+    let info = mock_info(OWNER, &coins(1000, "uluna"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ScheduleCompound {
+            schedule: TaskSchedule::Croncat {
+                manager: CRONCAT.to_string(),
+                interval: 0,
+                gas_limit: None,
+            },
+            limit: None,
+        },
+    );
+    assert_error(res, "interval must be greater than 0");
+
+    // cannot fund before the task is created
+    let info = mock_info(USER_1, &coins(1000, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundTask {});
+    assert_error(res, "Compound is not scheduled");
+
+    // create croncat task
+    let info = mock_info(OWNER, &coins(1000, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone())?;
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: CRONCAT.to_string(),
+            msg: to_binary(&CroncatExecuteMsg::CreateTask {
+                task: CroncatTaskRequest {
+                    interval: CroncatInterval::Block(100),
+                    boundary: None,
+                    stop_on_fail: false,
+                    actions: vec![CroncatAction {
+                        msg: WasmMsg::Execute {
+                            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                            msg: to_binary(&ExecuteMsg::CompoundAll { limit: Some(2) })?,
+                            funds: vec![],
+                        }
+                        .into(),
+                        gas_limit: Some(2_000_000),
+                    }],
+                    rules: None,
+                    cw20_coins: vec![],
+                },
+            })?,
+            funds: coins(1000, "uluna"),
+        }
+        .into()
+    );
+
+    // only one task at a time
+    let info = mock_info(OWNER, &coins(1000, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Compound is already scheduled");
+
+    // task hash from the croncat events
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: u64::MAX - 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm")
+                    .add_attribute("action", "create_task")
+                    .add_attribute("task_hash", "hash_1")],
+                data: None,
+            }),
+        },
+    )?;
+    assert_eq!(res.attributes, vec![attr("task_id", "hash_1")]);
+
+    let task: Option<ScheduledTask> =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ScheduledTask {})?)?;
+    assert_eq!(
+        task,
+        Some(ScheduledTask {
+            scheduler: Scheduler::Croncat {
+                manager: Addr::unchecked(CRONCAT),
+                interval: 100,
+                gas_limit: Some(2_000_000),
+            },
+            limit: Some(2),
+            task_id: Some("hash_1".to_string()),
+        })
+    );
+
+    // croncat can compound
+    let info = mock_info(CRONCAT, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: Some(2) })?;
+    assert_eq!(res.messages.len(), 2);
+
+    // anyone can fund the task
+    let info = mock_info(USER_1, &coins(500, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::FundTask {})?;
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: CRONCAT.to_string(),
+            msg: to_binary(&CroncatExecuteMsg::RefillTaskBalance {
+                task_hash: "hash_1".to_string(),
+            })?,
+            funds: coins(500, "uluna"),
+        }
+        .into()
+    );
+
+    // only owner can cancel
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelTask {});
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CancelTask {})?;
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: CRONCAT.to_string(),
+            msg: to_binary(&CroncatExecuteMsg::RemoveTask {
+                task_hash: "hash_1".to_string(),
+            })?,
+            funds: vec![],
+        }
+        .into()
+    );
+
+    // refunded balance is sent to the owner
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(700, "uluna"));
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: u64::MAX,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        },
+    )?;
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            to_address: OWNER.to_string(),
+            amount: coins(700, "uluna"),
+        }
+        .into()
+    );
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, vec![]);
+
+    // croncat can no longer compound
+    let info = mock_info(CRONCAT, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: None });
+    assert_error(res, "Unauthorized");
+
+    // warp job
+    let info = mock_info(OWNER, &coins(1000, "uluna"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::ScheduleCompound {
+            schedule: TaskSchedule::Warp {
+                controller: WARP.to_string(),
+                account: WARP_ACCOUNT.to_string(),
+                condition: "{\"expr\":{\"block_height\":{\"comparator\":\"0\",\"op\":\"gt\"}}}".to_string(),
+                vars: "[]".to_string(),
+                reward: Uint128::from(100u128),
+            },
+            limit: None,
+        },
+    )?;
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: WARP.to_string(),
+            msg: to_binary(&WarpExecuteMsg::CreateJob(WarpCreateJobMsg {
+                name: "spectrum-compound".to_string(),
+                description: "spectrum-compound".to_string(),
+                labels: vec![],
+                condition: "{\"expr\":{\"block_height\":{\"comparator\":\"0\",\"op\":\"gt\"}}}".to_string(),
+                msgs: format!(
+                    "[{{\"wasm\":{{\"execute\":{{\"contract_addr\":\"{}\",\"msg\":\"{}\",\"funds\":[]}}}}}}]",
+                    MOCK_CONTRACT_ADDR,
+                    to_binary(&ExecuteMsg::CompoundAll { limit: None })?,
+                ),
+                vars: "[]".to_string(),
+                recurring: true,
+                requeue_on_evict: true,
+                reward: Uint128::from(100u128),
+            }))?,
+            funds: coins(1000, "uluna"),
+        }
+        .into()
+    );
+
+    // job id is required
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: u64::MAX - 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm").add_attribute("action", "create_job")],
+                data: None,
+            }),
+        },
+    );
+    assert_error(res, "job_id not found in the scheduler events");
+
+    reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: u64::MAX - 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("wasm").add_attribute("job_id", "7")],
+                data: None,
+            }),
+        },
+    )?;
+
+    // warp account can compound
+    let info = mock_info(WARP_ACCOUNT, &[]);
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::CompoundAll { limit: None })?;
+
+    Ok(())
+}
+
+fn migrate(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    migrate_contract(deps.as_mut(), mock_env(), MigrateMsg {})?;
+
+    // a schedule left by a removed farm aborts the migration
+    let unregistered = Addr::unchecked("farm_4");
+    FARM_SCHEDULES.save(deps.as_mut().storage, &unregistered, &FarmSchedule::default())?;
+    let res = migrate_contract(deps.as_mut(), mock_env(), MigrateMsg {}).map_err(ContractError::from);
+    assert_error(res, "Invalid state: schedule of unregistered farm farm_4");
+
+    FARM_SCHEDULES.remove(deps.as_mut().storage, &unregistered);
+    migrate_contract(deps.as_mut(), mock_env(), MigrateMsg {})?;
+
+    Ok(())
+}
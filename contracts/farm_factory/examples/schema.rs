@@ -0,0 +1,22 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{remove_schemas, schema_for, export_schema};
+
+use spectrum::farm_factory::{ExecuteMsg, FarmResponse, FarmsResponse, InstantiateMsg, MigrateMsg, QueryMsg};
+use IDC_farm_factory::state::Config;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(FarmResponse), &out_dir);
+    export_schema(&schema_for!(FarmsResponse), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+}
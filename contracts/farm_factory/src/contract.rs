@@ -0,0 +1,379 @@
+use crate::error::ContractError;
+use crate::state::{
+    Config, FarmInfo, FarmTemplateConfig, PendingFarm, CONFIG, FARMS, OWNERSHIP_PROPOSAL,
+    PENDING_FARM,
+};
+
+use astroport::asset::AssetInfo;
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Api, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Reply, Response, StdError, StdResult, SubMsg, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
+use spectrum::adapters::pair::Pair;
+use spectrum::astroport_farm::InstantiateMsg as FarmInstantiateMsg;
+use spectrum::compound_proxy::InstantiateMsg as CompoundProxyInstantiateMsg;
+use spectrum::farm_factory::{
+    ExecuteMsg, FarmResponse, FarmTemplate, FarmsResponse, InstantiateMsg, MigrateMsg, QueryMsg,
+};
+use spectrum::helper::{assert_valid_addr, invalid_state};
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// The reply id of the compound proxy instantiation
+const INSTANTIATE_COMPOUND_PROXY_REPLY_ID: u64 = 1;
+/// The reply id of the farm instantiation
+const INSTANTIATE_FARM_REPLY_ID: u64 = 2;
+
+/// ## Description
+/// Validates that a decimal value is between 0 and 1.
+fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
+    if value > Decimal::one() {
+        Err(StdError::generic_err(field.to_string() + " must be 0 to 1"))
+    } else {
+        Ok(())
+    }
+}
+
+/// ## Description
+/// Validates the farm template.
+fn validate_template(api: &dyn Api, template: FarmTemplate) -> StdResult<FarmTemplateConfig> {
+    validate_percentage(template.fee, "fee")?;
+    validate_percentage(template.slippage_tolerance, "slippage_tolerance")?;
+
+    Ok(FarmTemplateConfig {
+        farm_code_id: template.farm_code_id,
+        compound_proxy_code_id: template.compound_proxy_code_id,
+        astroport_factory: api.addr_validate(&template.astroport_factory)?,
+        staking_contract: api.addr_validate(&template.staking_contract)?,
+        staking_interface: template.staking_interface.unwrap_or_default(),
+        controller: api.addr_validate(&template.controller)?,
+        fee: template.fee,
+        fee_collector: api.addr_validate(&template.fee_collector)?,
+        base_reward_token: template.base_reward_token,
+        slippage_tolerance: template.slippage_tolerance,
+        pauser: template.pauser
+            .map(|pauser| api.addr_validate(&pauser))
+            .transpose()?,
+    })
+}
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            template: validate_template(deps.api, msg.template)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateTemplate { template } => update_template(deps, info, template),
+        ExecuteMsg::CreateFarm {
+            pair_contract,
+            name,
+            symbol,
+            fee,
+            pair_proxies,
+        } => create_farm(deps, info, pair_contract, name, symbol, fee, pair_proxies),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Replaces the template of the farms created afterwards. Only the owner can execute this.
+fn update_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    template: FarmTemplate,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.template = validate_template(deps.api, template)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_template"))
+}
+
+/// ## Description
+/// Instantiates the compound proxy of the pair, the farm is instantiated once the compound proxy
+/// address is known. Only the owner can execute this.
+fn create_farm(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_contract: String,
+    name: String,
+    symbol: String,
+    fee: Option<Decimal>,
+    pair_proxies: Option<Vec<(AssetInfo, String)>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair = deps.api.addr_validate(&pair_contract)?;
+    if FARMS.has(deps.storage, &pair) {
+        return Err(ContractError::FarmAlreadyExists(pair.to_string()));
+    }
+    let fee = fee.unwrap_or(config.template.fee);
+    validate_percentage(fee, "fee")?;
+
+    let pair_info = Pair(pair.clone()).query_pair_info(&deps.querier)?;
+    PENDING_FARM.save(
+        deps.storage,
+        &PendingFarm {
+            pair: pair.clone(),
+            liquidity_token: pair_info.liquidity_token,
+            name,
+            symbol: symbol.clone(),
+            fee,
+            compound_proxy: None,
+        },
+    )?;
+
+    let instantiate_compound_proxy = WasmMsg::Instantiate {
+        admin: Some(config.owner.to_string()),
+        code_id: config.template.compound_proxy_code_id,
+        msg: to_binary(&CompoundProxyInstantiateMsg {
+            owner: config.owner.to_string(),
+            pair_contract: pair.to_string(),
+            factory: config.template.astroport_factory.to_string(),
+            pair_proxies: pair_proxies.unwrap_or_default(),
+            slippage_tolerance: config.template.slippage_tolerance,
+            price_guard: None,
+        })?,
+        funds: vec![],
+        label: format!("Spectrum {} compound proxy", symbol),
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            instantiate_compound_proxy,
+            INSTANTIATE_COMPOUND_PROXY_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            attr("action", "create_farm"),
+            attr("pair", pair),
+        ]))
+}
+
+/// ## Description
+/// Continues the farm creation with the address of each instantiated contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    let id = msg.id;
+    let res = parse_reply_instantiate_data(msg)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let contract_addr = deps.api.addr_validate(&res.contract_address)?;
+
+    match id {
+        INSTANTIATE_COMPOUND_PROXY_REPLY_ID => instantiate_farm(deps, contract_addr),
+        INSTANTIATE_FARM_REPLY_ID => register_farm(deps, contract_addr),
+        _ => Err(ContractError::InvalidReplyId(id)),
+    }
+}
+
+/// ## Description
+/// Instantiates the farm of the pending farm with the compound proxy.
+fn instantiate_farm(deps: DepsMut, compound_proxy: Addr) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut pending_farm = PENDING_FARM.load(deps.storage)?;
+    pending_farm.compound_proxy = Some(compound_proxy.clone());
+    PENDING_FARM.save(deps.storage, &pending_farm)?;
+
+    let template = config.template;
+    let farm_msg = FarmInstantiateMsg {
+        owner: config.owner.to_string(),
+        staking_contract: template.staking_contract.to_string(),
+        staking_interface: Some(template.staking_interface),
+        compound_proxy: compound_proxy.to_string(),
+        controller: template.controller.to_string(),
+        fee: pending_farm.fee,
+        fee_collector: template.fee_collector.to_string(),
+        liquidity_token: pending_farm.liquidity_token.to_string(),
+        base_reward_token: template.base_reward_token,
+        pair: pending_farm.pair.to_string(),
+        pauser: template.pauser.map(|pauser| pauser.to_string()),
+        deposit_cap: None,
+        unbonding_period: None,
+        min_reward_value: None,
+        name: pending_farm.name,
+        symbol: pending_farm.symbol.clone(),
+    };
+    farm_msg.validate()?;
+
+    let instantiate_farm = WasmMsg::Instantiate {
+        admin: Some(config.owner.to_string()),
+        code_id: template.farm_code_id,
+        msg: to_binary(&farm_msg)?,
+        funds: vec![],
+        label: format!("Spectrum {} farm", pending_farm.symbol),
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(instantiate_farm, INSTANTIATE_FARM_REPLY_ID))
+        .add_attribute("compound_proxy", compound_proxy))
+}
+
+/// ## Description
+/// Adds the pending farm to the registry.
+fn register_farm(deps: DepsMut, farm: Addr) -> Result<Response, ContractError> {
+    let pending_farm = PENDING_FARM.load(deps.storage)?;
+    PENDING_FARM.remove(deps.storage);
+
+    let compound_proxy = pending_farm
+        .compound_proxy
+        .ok_or_else(|| StdError::generic_err("Compound proxy is not instantiated"))?;
+    FARMS.save(
+        deps.storage,
+        &pending_farm.pair,
+        &FarmInfo {
+            pair: pending_farm.pair.clone(),
+            liquidity_token: pending_farm.liquidity_token,
+            compound_proxy,
+            farm: farm.clone(),
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("farm", farm))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Farm { pair_contract } => to_binary(&query_farm(deps, pair_contract)?),
+        QueryMsg::Farms { start_after, limit } => to_binary(&query_farms(deps, start_after, limit)?),
+    }
+}
+
+impl From<FarmInfo> for FarmResponse {
+    fn from(farm_info: FarmInfo) -> Self {
+        FarmResponse {
+            pair: farm_info.pair.to_string(),
+            liquidity_token: farm_info.liquidity_token.to_string(),
+            compound_proxy: farm_info.compound_proxy.to_string(),
+            farm: farm_info.farm.to_string(),
+        }
+    }
+}
+
+/// ## Description
+/// Returns the farm created for the pair.
+fn query_farm(deps: Deps, pair_contract: String) -> StdResult<FarmResponse> {
+    let pair = deps.api.addr_validate(&pair_contract)?;
+    let farm_info = FARMS
+        .may_load(deps.storage, &pair)?
+        .ok_or_else(|| StdError::generic_err(format!("No farm is created for pair {}", pair)))?;
+
+    Ok(farm_info.into())
+}
+
+/// ## Description
+/// Returns the created farms ordered by pair address.
+fn query_farms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FarmsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|pair| deps.api.addr_validate(&pair)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let farms = FARMS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, farm_info)| farm_info.into()))
+        .collect::<StdResult<_>>()?;
+
+    Ok(FarmsResponse { farms })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored addresses and that no farm is halfway through its creation.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.template.astroport_factory, "astroport_factory")?;
+    assert_valid_addr(deps.api, &config.template.staking_contract, "staking_contract")?;
+    assert_valid_addr(deps.api, &config.template.controller, "controller")?;
+    assert_valid_addr(deps.api, &config.template.fee_collector, "fee_collector")?;
+    if let Some(pauser) = &config.template.pauser {
+        assert_valid_addr(deps.api, pauser, "pauser")?;
+    }
+    if PENDING_FARM.may_load(deps.storage)?.is_some() {
+        return Err(invalid_state("a farm is being created"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Decimal};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::astroport_farm::StakingInterface;
+
+/// This structure stores the main parameter for the farm factory contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to create farms and update the template
+    pub owner: Addr,
+    /// The parameters shared by the created farms
+    pub template: FarmTemplateConfig,
+}
+
+/// This structure stores the parameters shared by the created farms.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmTemplateConfig {
+    /// The code id of the farm contract
+    pub farm_code_id: u64,
+    /// The code id of the compound proxy contract
+    pub compound_proxy_code_id: u64,
+    /// The Astroport factory contract
+    pub astroport_factory: Addr,
+    /// The LP staking contract
+    pub staking_contract: Addr,
+    /// The interface of the staking contract
+    pub staking_interface: StakingInterface,
+    /// The controller address to execute compound
+    pub controller: Addr,
+    /// The default performance fee
+    pub fee: Decimal,
+    /// The fee collector contract
+    pub fee_collector: Addr,
+    /// The base reward token contract address
+    pub base_reward_token: String,
+    /// The slippage tolerance of the compound proxy when swapping
+    pub slippage_tolerance: Decimal,
+    /// The address allowed to pause bond and compound of the farms
+    pub pauser: Option<Addr>,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// This structure stores the contracts of a created farm.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmInfo {
+    /// The pair contract
+    pub pair: Addr,
+    /// The LP token of the pair
+    pub liquidity_token: Addr,
+    /// The compound proxy contract
+    pub compound_proxy: Addr,
+    /// The farm contract
+    pub farm: Addr,
+}
+
+/// Stores the created farms by pair
+pub const FARMS: Map<&Addr, FarmInfo> = Map::new("farms");
+
+/// This structure stores the farm being created while its contracts are instantiated.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingFarm {
+    /// The pair contract
+    pub pair: Addr,
+    /// The LP token of the pair
+    pub liquidity_token: Addr,
+    /// The name of the farm share
+    pub name: String,
+    /// The symbol of the farm share
+    pub symbol: String,
+    /// The performance fee
+    pub fee: Decimal,
+    /// The compound proxy contract, set once it is instantiated
+    pub compound_proxy: Option<Addr>,
+}
+
+/// Stores the farm being created, removed once the farm is instantiated
+pub const PENDING_FARM: Item<PendingFarm> = Item::new("pending_farm");
@@ -0,0 +1,310 @@
+use astroport::asset::{AssetInfo, PairInfo};
+use astroport::factory::PairType;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, Binary, ContractResult, CosmosMsg, Decimal, OwnedDeps, Reply,
+    Response, StdError, SubMsg, SubMsgResponse, SubMsgResult, SystemResult, WasmMsg, WasmQuery,
+};
+use spectrum::astroport_farm::{InstantiateMsg as FarmInstantiateMsg, StakingInterface};
+use spectrum::compound_proxy::InstantiateMsg as CompoundProxyInstantiateMsg;
+use spectrum::farm_factory::{
+    ExecuteMsg, FarmResponse, FarmTemplate, FarmsResponse, InstantiateMsg, QueryMsg,
+};
+
+use crate::contract::{execute, instantiate, query, reply};
+use crate::error::ContractError;
+use crate::state::{Config, FarmTemplateConfig};
+
+const GOV: &str = "gov";
+const USER_1: &str = "user_1";
+const ASTROPORT_FACTORY: &str = "astroport_factory";
+const GENERATOR: &str = "generator";
+const CONTROLLER: &str = "controller";
+const FEE_COLLECTOR: &str = "fee_collector";
+const ASTRO_TOKEN: &str = "astro_token";
+const PAIR_1: &str = "pair_1";
+const PAIR_2: &str = "pair_2";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    update_template(&mut deps)?;
+    create_farm(&mut deps)?;
+    farms(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn template(fee: Decimal) -> FarmTemplate {
+    FarmTemplate {
+        farm_code_id: 10,
+        compound_proxy_code_id: 11,
+        astroport_factory: ASTROPORT_FACTORY.to_string(),
+        staking_contract: GENERATOR.to_string(),
+        staking_interface: None,
+        controller: CONTROLLER.to_string(),
+        fee,
+        fee_collector: FEE_COLLECTOR.to_string(),
+        base_reward_token: ASTRO_TOKEN.to_string(),
+        slippage_tolerance: Decimal::percent(1),
+        pauser: None,
+    }
+}
+
+/// Returns the reply of an instantiation with the protobuf encoded contract address
+fn instantiate_reply(id: u64, contract_addr: &str) -> Reply {
+    let mut data = vec![0x0a, contract_addr.len() as u8];
+    data.extend_from_slice(contract_addr.as_bytes());
+    Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(Binary(data)),
+        }),
+    }
+}
+
+fn create_farm_msg(pair: &str, symbol: &str, fee: Option<Decimal>) -> ExecuteMsg {
+    ExecuteMsg::CreateFarm {
+        pair_contract: pair.to_string(),
+        name: format!("Spectrum {} LP", symbol),
+        symbol: symbol.to_string(),
+        fee,
+        pair_proxies: None,
+    }
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(GOV, &[]);
+    let msg = InstantiateMsg {
+        owner: GOV.to_string(),
+        template: template(Decimal::percent(101)),
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_error(res, "fee must be 0 to 1");
+
+    let msg = InstantiateMsg {
+        owner: GOV.to_string(),
+        template: template(Decimal::percent(3)),
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(GOV),
+            template: FarmTemplateConfig {
+                farm_code_id: 10,
+                compound_proxy_code_id: 11,
+                astroport_factory: Addr::unchecked(ASTROPORT_FACTORY),
+                staking_contract: Addr::unchecked(GENERATOR),
+                staking_interface: StakingInterface::Generator,
+                controller: Addr::unchecked(CONTROLLER),
+                fee: Decimal::percent(3),
+                fee_collector: Addr::unchecked(FEE_COLLECTOR),
+                base_reward_token: ASTRO_TOKEN.to_string(),
+                slippage_tolerance: Decimal::percent(1),
+                pauser: None,
+            },
+        }
+    );
+
+    deps.querier.update_wasm(|request| match request {
+        WasmQuery::Smart { contract_addr, .. } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&PairInfo {
+                asset_infos: vec![
+                    AssetInfo::Token { contract_addr: Addr::unchecked(ASTRO_TOKEN) },
+                    AssetInfo::NativeToken { denom: "uluna".to_string() },
+                ],
+                contract_addr: Addr::unchecked(contract_addr),
+                liquidity_token: Addr::unchecked(format!("{}_lp", contract_addr)),
+                pair_type: PairType::Xyk {},
+            })
+            .unwrap(),
+        )),
+        _ => panic!("Unexpected query"),
+    });
+
+    Ok(())
+}
+
+fn update_template(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateTemplate { template: template(Decimal::percent(5)) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(GOV, &[]);
+    let mut new_template = template(Decimal::percent(5));
+    new_template.slippage_tolerance = Decimal::percent(150);
+    let msg = ExecuteMsg::UpdateTemplate { template: new_template };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "slippage_tolerance must be 0 to 1");
+
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateTemplate { template: template(Decimal::percent(5)) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "update_template")]);
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(config.template.fee, Decimal::percent(5));
+
+    Ok(())
+}
+
+fn create_farm(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, create_farm_msg(PAIR_1, "spLP", None));
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, create_farm_msg(PAIR_1, "spLP", Some(Decimal::percent(200))));
+    assert_error(res, "fee must be 0 to 1");
+
+    // the compound proxy is instantiated first
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, create_farm_msg(PAIR_1, "spLP", None))?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin: Some(GOV.to_string()),
+                code_id: 11,
+                msg: to_binary(&CompoundProxyInstantiateMsg {
+                    owner: GOV.to_string(),
+                    pair_contract: PAIR_1.to_string(),
+                    factory: ASTROPORT_FACTORY.to_string(),
+                    pair_proxies: vec![],
+                    slippage_tolerance: Decimal::percent(1),
+                    price_guard: None,
+                })?,
+                funds: vec![],
+                label: "Spectrum spLP compound proxy".to_string(),
+            }),
+            1
+        )]
+    );
+    assert_eq!(res.attributes, vec![attr("action", "create_farm"), attr("pair", PAIR_1)]);
+
+    // the farm is instantiated with the compound proxy
+    let res = reply(deps.as_mut(), mock_env(), instantiate_reply(1, "compound_proxy_1"))?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                admin: Some(GOV.to_string()),
+                code_id: 10,
+                msg: to_binary(&FarmInstantiateMsg {
+                    owner: GOV.to_string(),
+                    staking_contract: GENERATOR.to_string(),
+                    staking_interface: Some(StakingInterface::Generator),
+                    compound_proxy: "compound_proxy_1".to_string(),
+                    controller: CONTROLLER.to_string(),
+                    fee: Decimal::percent(5),
+                    fee_collector: FEE_COLLECTOR.to_string(),
+                    liquidity_token: "pair_1_lp".to_string(),
+                    base_reward_token: ASTRO_TOKEN.to_string(),
+                    pair: PAIR_1.to_string(),
+                    pauser: None,
+                    deposit_cap: None,
+                    unbonding_period: None,
+                    min_reward_value: None,
+                    name: "Spectrum spLP LP".to_string(),
+                    symbol: "spLP".to_string(),
+                })?,
+                funds: vec![],
+                label: "Spectrum spLP farm".to_string(),
+            }),
+            2
+        )]
+    );
+    assert_eq!(res.attributes, vec![attr("compound_proxy", "compound_proxy_1")]);
+
+    let res = reply(deps.as_mut(), mock_env(), instantiate_reply(2, "farm_1"))?;
+    assert_eq!(res.attributes, vec![attr("farm", "farm_1")]);
+
+    let res: FarmResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Farm { pair_contract: PAIR_1.to_string() },
+    )?)?;
+    assert_eq!(
+        res,
+        FarmResponse {
+            pair: PAIR_1.to_string(),
+            liquidity_token: "pair_1_lp".to_string(),
+            compound_proxy: "compound_proxy_1".to_string(),
+            farm: "farm_1".to_string(),
+        }
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Farm { pair_contract: PAIR_2.to_string() });
+    assert_eq!(res, Err(StdError::generic_err("No farm is created for pair pair_2")));
+
+    // only one farm is created per pair
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, create_farm_msg(PAIR_1, "spLP", None));
+    assert_error(res, "Farm of pair pair_1 already exists");
+
+    let res = reply(deps.as_mut(), mock_env(), instantiate_reply(3, "contract"));
+    assert_error(res, "Invalid reply id 3");
+
+    Ok(())
+}
+
+fn farms(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(GOV, &[]);
+    execute(deps.as_mut(), mock_env(), info, create_farm_msg(PAIR_2, "spLPB", Some(Decimal::percent(2))))?;
+    let res = reply(deps.as_mut(), mock_env(), instantiate_reply(1, "compound_proxy_2"))?;
+    let farm_msg: FarmInstantiateMsg = match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate { msg, .. }) => from_binary(msg)?,
+        _ => panic!("Expected farm instantiation"),
+    };
+    assert_eq!(farm_msg.fee, Decimal::percent(2));
+    reply(deps.as_mut(), mock_env(), instantiate_reply(2, "farm_2"))?;
+
+    let res: FarmsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Farms { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(
+        res.farms.iter().map(|farm| farm.farm.as_str()).collect::<Vec<_>>(),
+        vec!["farm_1", "farm_2"]
+    );
+
+    let res: FarmsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Farms { start_after: Some(PAIR_1.to_string()), limit: Some(1) },
+    )?)?;
+    assert_eq!(
+        res.farms,
+        vec![FarmResponse {
+            pair: PAIR_2.to_string(),
+            liquidity_token: "pair_2_lp".to_string(),
+            compound_proxy: "compound_proxy_2".to_string(),
+            farm: "farm_2".to_string(),
+        }]
+    );
+
+    Ok(())
+}
@@ -3,8 +3,8 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{remove_schemas, schema_for, export_schema};
 
-use spectrum::fees_collector::{ExecuteMsg, InstantiateMsg, QueryMsg, BalancesResponse, AssetWithLimit, CollectSimulationResponse};
-use spectrum_fees_collector::state::Config;
+use spectrum::fees_collector::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, BalancesResponse, AssetWithLimit, CollectSimulationResponse, SimulateCollectResponse, StakingHookMsg, Cw20HookMsg, Distribution, BeneficiaryTotal};
+use IDC_fee_collector::state::{Config, ConfigProposal};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,5 +18,12 @@ fn main() {
     export_schema(&schema_for!(BalancesResponse), &out_dir);
     export_schema(&schema_for!(AssetWithLimit), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(ConfigProposal), &out_dir);
     export_schema(&schema_for!(CollectSimulationResponse), &out_dir);
+    export_schema(&schema_for!(SimulateCollectResponse), &out_dir);
+    export_schema(&schema_for!(Distribution), &out_dir);
+    export_schema(&schema_for!(BeneficiaryTotal), &out_dir);
+    export_schema(&schema_for!(StakingHookMsg), &out_dir);
+    export_schema(&schema_for!(Cw20HookMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
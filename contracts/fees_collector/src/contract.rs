@@ -1,14 +1,25 @@
 use crate::error::ContractError;
-use crate::state::{Config, BRIDGES, CONFIG, OWNERSHIP_PROPOSAL};
+use crate::state::{Config, ConfigChange, ConfigProposal, GeneratorRewardsConfig, LpTargetConfig, SwapRouterConfig, ASSET_CONFIGS, BRIDGES, CONFIG, CONFIG_PROPOSAL, DEFAULT_IBC_TIMEOUT, LAST_DISTRIBUTE, OPERATORS, COLLECT_IN_PROGRESS, OWNERSHIP_PROPOSAL, SWAP_REPLIES, SwapReply, BENEFICIARY_TOTALS, DISTRIBUTIONS, DISTRIBUTION_COUNT, SOURCE_TOTALS, PAYOUT_ASSETS};
 
-use crate::utils::{build_swap_bridge_msg, try_build_swap_msg, validate_bridge, BRIDGES_EXECUTION_MAX_DEPTH, BRIDGES_INITIAL_DEPTH, try_swap_simulation};
+use crate::utils::{build_staking_deposit_msg, build_swap_bridge_msg, build_unwrap_msg, get_max_spread, get_unwrap, try_build_payout_swap_msg, try_build_router_swap_msg, try_build_swap_msg, validate_bridge, BRIDGES_EXECUTION_MAX_DEPTH, BRIDGES_INITIAL_DEPTH, try_swap_simulation, MAX_BURN_BPS, MAX_COLLECT_INCENTIVE_BPS, MAX_SPREAD, MAX_SWAP_PORTION_BPS};
 use astroport::asset::{native_asset_info, Asset, AssetInfo, ULUNA_DENOM, AssetInfoExt};
 
 use astroport::common::{propose_new_owner, drop_ownership_proposal, claim_ownership};
-use cosmwasm_std::{entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg, attr, Addr};
-use spectrum::fees_collector::{AssetWithLimit, BalancesResponse, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use astroport::querier::query_pair_info;
+use cosmwasm_std::{entry_point, from_binary, to_binary, Api, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, IbcMsg, IbcTimeout, MessageInfo, Order, QuerierWrapper, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint128, WasmMsg, attr, Addr, Event};
+use spectrum::fees_collector::{AssetCollectSimulation, AssetConfig, AssetWithLimit, BalancesResponse, BeneficiaryTotal, Cw20HookMsg, Distribution, CollectSimulationResponse, ExecuteMsg, GeneratorRewards, IbcTarget, InstantiateMsg, LpTarget, MigrateMsg, QueryMsg, SimulateCollectResponse, SwapRouter};
+use spectrum::pause::{self, assert_not_paused, is_paused};
+use spectrum::adapters::router::Router;
+use spectrum::compound_proxy::Compounder;
 use std::collections::{HashMap, HashSet};
 use spectrum::adapters::asset::AssetEx;
+use spectrum::adapters::generator::Generator;
+use spectrum::math::bps_amount;
+use spectrum::events::{CompoundReport, STAGE_DISTRIBUTE};
+use spectrum::helper::{assert_bps, assert_valid_addr, invalid_state};
+use spectrum::querier::query_asset_balance;
+use cw_storage_plus::Bound;
+use cw20::{Cw20ReceiveMsg, Expiration};
 
 /// ## Description
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
@@ -27,17 +38,201 @@ pub fn instantiate(
         owner: deps.api.addr_validate(&msg.owner)?,
         operator: deps.api.addr_validate(&msg.operator)?,
         factory_contract: deps.api.addr_validate(&msg.factory_contract)?,
+        target_list: validate_target_list(deps.api, msg.target_list)?,
+        collect_incentive_bps: validate_collect_incentive(msg.collect_incentive_bps.unwrap_or_default())?,
+        distribution_interval: msg.distribution_interval.unwrap_or_default(),
+        burn_bps: validate_burn_share(msg.burn_bps.unwrap_or_default())?,
+        ibc_target_list: validate_ibc_target_list(msg.ibc_target_list.unwrap_or_default(), &msg.stablecoin)?,
+        ibc_timeout: validate_ibc_timeout(msg.ibc_timeout.unwrap_or(DEFAULT_IBC_TIMEOUT))?,
+        staking_target_list: validate_target_list(deps.api, msg.staking_target_list.unwrap_or_default())?,
+        generator_rewards: msg.generator_rewards
+            .map(|g| validate_generator_rewards(deps.api, g))
+            .transpose()?,
+        swap_router: msg.swap_router
+            .map(|r| validate_swap_router(deps.api, r))
+            .transpose()?,
+        max_swap_portion_bps: validate_swap_portion(msg.max_swap_portion_bps.unwrap_or_default())?,
+        config_delay: msg.config_delay.unwrap_or_default(),
+        pauser: msg.pauser
+            .map(|pauser| deps.api.addr_validate(&pauser))
+            .transpose()?,
+        lp_target_list: validate_lp_target_list(deps.api, msg.lp_target_list.unwrap_or_default())?,
         stablecoin: msg.stablecoin,
-        target_list: msg.target_list.into_iter()
-                                .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
-                                .collect::<StdResult<_>>()?,
     };
+    validate_targets(&config)?;
+    validate_lp_target_routes(&deps.querier, &config)?;
 
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
+/// ## Description
+/// Validates that there is at least one local, remote, staking or LP beneficiary.
+fn validate_targets(config: &Config) -> Result<(), ContractError> {
+    if config.target_list.is_empty()
+        && config.ibc_target_list.is_empty()
+        && config.staking_target_list.is_empty()
+        && config.lp_target_list.is_empty()
+    {
+        return Err(ContractError::InvalidTargetList("target list is empty".to_string()));
+    }
+    Ok(())
+}
+
+/// ## Description
+/// Validates the beneficiary addresses and their weights. Returns a [`ContractError`] if the list
+/// contains a zero weight or contains the same address twice.
+fn validate_target_list(
+    api: &dyn Api,
+    target_list: Vec<(String, u64)>,
+) -> Result<Vec<(Addr, u64)>, ContractError> {
+    let mut uniq = HashSet::new();
+    target_list
+        .into_iter()
+        .map(|(addr, weight)| {
+            let addr = api.addr_validate(&addr)?;
+            if weight == 0 {
+                return Err(ContractError::InvalidTargetList(format!("zero weight for {}", addr)));
+            }
+            if !uniq.insert(addr.clone()) {
+                return Err(ContractError::InvalidTargetList(format!("duplicated address {}", addr)));
+            }
+            Ok((addr, weight))
+        })
+        .collect()
+}
+
+/// ## Description
+/// Validates the remote beneficiaries. Returns a [`ContractError`] if the stablecoin is not a native token,
+/// or if the list contains an empty channel or receiver, a zero weight or the same receiver twice.
+fn validate_ibc_target_list(
+    ibc_target_list: Vec<IbcTarget>,
+    stablecoin: &AssetInfo,
+) -> Result<Vec<IbcTarget>, ContractError> {
+    if !ibc_target_list.is_empty() && !stablecoin.is_native_token() {
+        return Err(ContractError::InvalidTargetList("IBC transfer requires a native stablecoin".to_string()));
+    }
+
+    let mut uniq = HashSet::new();
+    for target in ibc_target_list.iter() {
+        if target.channel_id.is_empty() || target.receiver.is_empty() {
+            return Err(ContractError::InvalidTargetList("empty IBC channel or receiver".to_string()));
+        }
+        if target.weight == 0 {
+            return Err(ContractError::InvalidTargetList(format!("zero weight for {}", target.receiver)));
+        }
+        if !uniq.insert((target.channel_id.clone(), target.receiver.clone())) {
+            return Err(ContractError::InvalidTargetList(format!("duplicated address {}", target.receiver)));
+        }
+    }
+
+    Ok(ibc_target_list)
+}
+
+/// ## Description
+/// Validates the LP beneficiaries. Returns a [`ContractError`] if the list contains a zero weight
+/// or the same compound proxy and receiver twice.
+fn validate_lp_target_list(
+    api: &dyn Api,
+    lp_target_list: Vec<LpTarget>,
+) -> Result<Vec<LpTargetConfig>, ContractError> {
+    let mut uniq = HashSet::new();
+    lp_target_list
+        .into_iter()
+        .map(|target| {
+            let compound_proxy = api.addr_validate(&target.compound_proxy)?;
+            let receiver = api.addr_validate(&target.receiver)?;
+            if target.weight == 0 {
+                return Err(ContractError::InvalidTargetList(format!("zero weight for {}", receiver)));
+            }
+            if !uniq.insert((compound_proxy.clone(), receiver.clone())) {
+                return Err(ContractError::InvalidTargetList(format!("duplicated address {}", receiver)));
+            }
+            Ok(LpTargetConfig {
+                compound_proxy: Compounder(compound_proxy),
+                receiver,
+                weight: target.weight,
+            })
+        })
+        .collect()
+}
+
+/// ## Description
+/// Validates that the compound proxy of each LP beneficiary can compound the stablecoin, either as
+/// an asset of its pair or through a pair proxy. Returns a [`ContractError`] otherwise.
+fn validate_lp_target_routes(querier: &QuerierWrapper, config: &Config) -> Result<(), ContractError> {
+    for target in config.lp_target_list.iter() {
+        target.compound_proxy
+            .query_compound_simulation(querier, vec![config.stablecoin.with_balance(1u128)])
+            .map_err(|_| ContractError::InvalidTargetList(
+                format!("{} cannot compound the stablecoin", target.compound_proxy.0),
+            ))?;
+    }
+    Ok(())
+}
+
+/// ## Description
+/// Validates the generator, ASTRO token and LP token addresses.
+fn validate_generator_rewards(
+    api: &dyn Api,
+    generator_rewards: GeneratorRewards,
+) -> StdResult<GeneratorRewardsConfig> {
+    Ok(GeneratorRewardsConfig {
+        generator: api.addr_validate(&generator_rewards.generator)?,
+        astro_token: api.addr_validate(&generator_rewards.astro_token)?,
+        lp_tokens: generator_rewards.lp_tokens
+            .iter()
+            .map(|lp_token| api.addr_validate(lp_token))
+            .collect::<StdResult<Vec<Addr>>>()?,
+    })
+}
+
+/// ## Description
+/// Validates the router address.
+fn validate_swap_router(api: &dyn Api, swap_router: SwapRouter) -> StdResult<SwapRouterConfig> {
+    Ok(SwapRouterConfig {
+        router: Router(api.addr_validate(&swap_router.router)?),
+        router_type: swap_router.router_type,
+    })
+}
+
+/// ## Description
+/// Validates that the collect incentive does not exceed [`MAX_COLLECT_INCENTIVE_BPS`].
+fn validate_collect_incentive(collect_incentive_bps: u64) -> Result<u64, ContractError> {
+    if collect_incentive_bps > MAX_COLLECT_INCENTIVE_BPS {
+        return Err(ContractError::InvalidCollectIncentive(MAX_COLLECT_INCENTIVE_BPS));
+    }
+    Ok(collect_incentive_bps)
+}
+
+/// ## Description
+/// Validates that the burn share does not exceed [`MAX_BURN_BPS`].
+fn validate_burn_share(burn_bps: u64) -> Result<u64, ContractError> {
+    if burn_bps > MAX_BURN_BPS {
+        return Err(ContractError::InvalidBurnShare(MAX_BURN_BPS));
+    }
+    Ok(burn_bps)
+}
+
+/// ## Description
+/// Validates that the max swap portion does not exceed [`MAX_SWAP_PORTION_BPS`].
+fn validate_swap_portion(max_swap_portion_bps: u64) -> Result<u64, ContractError> {
+    if max_swap_portion_bps > MAX_SWAP_PORTION_BPS {
+        return Err(ContractError::InvalidSwapPortion(MAX_SWAP_PORTION_BPS));
+    }
+    Ok(max_swap_portion_bps)
+}
+
+/// ## Description
+/// Validates that the IBC timeout is not zero, which would time out every transfer immediately.
+fn validate_ibc_timeout(ibc_timeout: u64) -> Result<u64, ContractError> {
+    if ibc_timeout == 0 {
+        return Err(ContractError::InvalidIbcTimeout {});
+    }
+    Ok(ibc_timeout)
+}
+
 /// ## Description
 /// Exposes execute functions available in the contract.
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -48,23 +243,65 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Collect { assets, minimum_receive } => collect(deps, env, info, assets, minimum_receive),
-        ExecuteMsg::UpdateBridges { add, remove } => update_bridges(deps, info, add, remove),
+        ExecuteMsg::UpdateBridges { add, remove } => update_bridges(deps, env, info, add, remove),
+        ExecuteMsg::UpdateAssetConfigs { add, remove } => update_asset_configs(deps, env, info, add, remove),
         ExecuteMsg::UpdateConfig {
             operator,
             factory_contract,
             target_list,
+            collect_incentive_bps,
+            distribution_interval,
+            burn_bps,
+            ibc_target_list,
+            ibc_timeout,
+            stablecoin,
+            staking_target_list,
+            generator_rewards,
+            swap_router,
+            max_swap_portion_bps,
+            config_delay,
+            pauser,
+            lp_target_list,
         } => update_config(
             deps,
+            env,
             info,
             operator,
             factory_contract,
             target_list,
+            collect_incentive_bps,
+            distribution_interval,
+            burn_bps,
+            ibc_target_list,
+            ibc_timeout,
+            stablecoin,
+            staking_target_list,
+            generator_rewards,
+            swap_router,
+            max_swap_portion_bps,
+            config_delay,
+            pauser,
+            lp_target_list,
         ),
+        ExecuteMsg::ApplyConfig {} => apply_config(deps, env, info),
+        ExecuteMsg::DropConfigProposal {} => drop_config_proposal(deps, info),
+        ExecuteMsg::UpdatePayoutAssets { add, remove } => update_payout_assets(deps, info, add, remove),
         ExecuteMsg::SwapBridgeAssets { assets, depth } => {
             swap_bridge_assets(deps, env, info, assets, depth)
         }
-        ExecuteMsg::DistributeFees { minimum_receive } => distribute_fees(deps, env, info, minimum_receive),
+        ExecuteMsg::DepositStakingPayout { staking_contract, asset, prev_balance } => {
+            deposit_staking_payout(deps, env, info, staking_contract, asset, prev_balance)
+        }
+        ExecuteMsg::DistributeFees { minimum_receive, keeper, prev_balance } => {
+            distribute_fees(deps, env, info, minimum_receive, keeper, prev_balance)
+        }
+        ExecuteMsg::AddOperator { operator } => add_operator(deps, env, info, operator),
+        ExecuteMsg::RemoveOperator { operator } => remove_operator(deps, env, info, operator),
+        ExecuteMsg::Pause {} => set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => set_paused(deps, info, false),
+        ExecuteMsg::Rescue { asset, to } => rescue(deps, env, info, asset, to),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -101,6 +338,8 @@ pub fn execute(
 
 /// ## Description
 /// Swaps fee tokens to stablecoin and distribute the resulting stablecoin to the target list.
+/// Only the owner and operators can call this, the caller is paid the collect incentive from the stablecoin
+/// received by this collect.
 /// Returns a [`ContractError`] on failure, otherwise returns a [`Response`] object if the
 /// operation was successful.
 fn collect(
@@ -113,10 +352,12 @@ fn collect(
 
     let config = CONFIG.load(deps.storage)?;
 
-    if info.sender != config.operator {
+    if !is_operator(deps.as_ref(), &config, &info.sender)? {
         return Err(ContractError::Unauthorized {});
     }
 
+    assert_not_paused(deps.storage)?;
+
     // Check for duplicate assets
     let mut uniq = HashSet::new();
     if !assets
@@ -126,8 +367,18 @@ fn collect(
         return Err(ContractError::DuplicatedAsset {});
     }
     let response = Response::default();
+
+    // Locks the balances until DistributeFees is executed at the end of the collect
+    COLLECT_IN_PROGRESS.save(deps.storage, &true)?;
+
+    // The collect incentive is paid from the stablecoin received since
+    let prev_balance = query_asset_balance(&deps.querier, &config.stablecoin, &env.contract.address)?;
+
+    // Claim pending generator rewards, they are swapped once received
+    let (claim_messages, reward_assets) = claim_generator_rewards(deps.as_ref(), &env, &config)?;
+
     // Swap all non stablecoin tokens
-    let (mut messages, bridge_assets) = swap_assets(
+    let (swaps, bridge_assets, skipped_assets) = swap_assets(
         deps.as_ref(),
         &env.contract.address,
         &config,
@@ -137,8 +388,10 @@ fn collect(
             .collect(),
     )?;
 
-    // If no swap messages - send stablecoin directly to beneficiary
-    if !messages.is_empty() && !bridge_assets.is_empty() {
+    let mut messages: Vec<CosmosMsg> = vec![];
+
+    // Assets swapped into a bridge token need a second hop to reach the stablecoin
+    if !swaps.is_empty() && !bridge_assets.is_empty() {
         messages.push(build_swap_bridge_msg(
             &env.contract.address,
             bridge_assets,
@@ -146,18 +399,160 @@ fn collect(
         )?);
     }
 
+    // Claimed rewards are swapped from the contract balance like bridge assets
+    if !reward_assets.is_empty() {
+        messages.push(build_swap_bridge_msg(
+            &env.contract.address,
+            reward_assets,
+            BRIDGES_INITIAL_DEPTH,
+        )?);
+    }
+
     let distribute_fee = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: env.contract.address.to_string(),
         msg: to_binary(&ExecuteMsg::DistributeFees {
             minimum_receive,
+            keeper: Some(info.sender.to_string()),
+            prev_balance: Some(prev_balance),
         })?,
         funds: vec![],
     });
     messages.push(distribute_fee);
 
+    for (asset, _) in swaps.iter() {
+        update_source_totals(deps.storage, asset, true)?;
+    }
+
     Ok(response
+        .add_messages(claim_messages)
+        .add_submessages(build_swap_submsgs(deps.storage, swaps, true)?)
         .add_messages(messages)
-        .add_attribute("action", "collect"))
+        .add_attribute("action", "collect")
+        .add_attributes(skipped_assets.into_iter().map(|a| attr("skipped_asset", a.to_string()))))
+}
+
+/// ## Description
+/// Receives a cw20 fee token pushed with a [`Cw20HookMsg`].
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Deposit {} => deposit(deps, env, info.sender, cw20_msg.sender, cw20_msg.amount),
+    }
+}
+
+/// ## Description
+/// Records the deposit of a cw20 fee token with the sending contract as the source. Once the balance
+/// of the fee token reaches the swap threshold of its asset config, it is swapped to stablecoin like
+/// in Collect. The deposit is kept until Collect when the swap is skipped or cannot be built.
+fn deposit(
+    deps: DepsMut,
+    env: Env,
+    token: Addr,
+    source: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let asset_info = AssetInfo::Token { contract_addr: token };
+
+    let response = Response::new().add_attributes(vec![
+        attr("action", "deposit"),
+        attr("source", source),
+        attr("asset", asset_info.to_string()),
+        attr("amount", amount),
+    ]);
+
+    let swap_threshold = match ASSET_CONFIGS
+        .may_load(deps.storage, asset_info.to_string())?
+        .and_then(|c| c.swap_threshold)
+    {
+        Some(swap_threshold) if asset_info.ne(&config.stablecoin) => swap_threshold,
+        _ => return Ok(response),
+    };
+    if is_paused(deps.storage)?
+        || COLLECT_IN_PROGRESS.may_load(deps.storage)?.unwrap_or_default()
+    {
+        return Ok(response);
+    }
+    let balance = query_asset_balance(&deps.querier, &asset_info, &env.contract.address)?;
+    if balance < swap_threshold {
+        return Ok(response);
+    }
+
+    let asset = AssetWithLimit { info: asset_info, limit: None };
+    let (swaps, bridge_assets, _) = match swap_assets(deps.as_ref(), &env.contract.address, &config, vec![asset]) {
+        Ok(parts) => parts,
+        Err(err) => return Ok(response.add_attribute("swap_error", err.to_string())),
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !swaps.is_empty() && !bridge_assets.is_empty() {
+        messages.push(build_swap_bridge_msg(
+            &env.contract.address,
+            bridge_assets,
+            BRIDGES_INITIAL_DEPTH,
+        )?);
+    }
+
+    for (asset, _) in swaps.iter() {
+        update_source_totals(deps.storage, asset, true)?;
+    }
+
+    Ok(response
+        .add_submessages(build_swap_submsgs(deps.storage, swaps, true)?)
+        .add_messages(messages))
+}
+
+/// ## Description
+/// Builds the generator claim message for the configured LP tokens with pending rewards. Returns the
+/// claim messages and the reward assets to swap after the claim.
+fn claim_generator_rewards(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+) -> StdResult<(Vec<CosmosMsg>, Vec<AssetInfo>)> {
+    let generator_rewards = match &config.generator_rewards {
+        Some(generator_rewards) => generator_rewards,
+        None => return Ok((vec![], vec![])),
+    };
+
+    let generator = Generator(generator_rewards.generator.clone());
+    let mut lp_tokens = vec![];
+    let mut reward_assets: Vec<AssetInfo> = vec![];
+    for lp_token in &generator_rewards.lp_tokens {
+        let pending = generator.query_pending_token(&deps.querier, lp_token, &env.contract.address)?;
+
+        let mut rewards = vec![];
+        if !pending.pending.is_zero() {
+            rewards.push(AssetInfo::Token {
+                contract_addr: generator_rewards.astro_token.clone(),
+            });
+        }
+        rewards.extend(pending.pending_on_proxy
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|asset| !asset.amount.is_zero())
+            .map(|asset| asset.info));
+
+        if rewards.is_empty() {
+            continue;
+        }
+        lp_tokens.push(lp_token.to_string());
+        for reward in rewards {
+            if !reward_assets.contains(&reward) {
+                reward_assets.push(reward);
+            }
+        }
+    }
+
+    if lp_tokens.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+
+    Ok((vec![generator.claim_rewards_msg(lp_tokens)?], reward_assets))
 }
 
 /// ## Description
@@ -167,21 +562,36 @@ enum SwapTarget {
     Bridge { asset: AssetInfo, msg: CosmosMsg },
 }
 
+type SwapAssetsParts = (Vec<(Asset, CosmosMsg)>, Vec<AssetInfo>, Vec<AssetInfo>);
+
 /// ## Description
-/// Swap all non stablecoin tokens to stablecoin. Returns a [`ContractError`] on failure, otherwise returns
-/// a [`Response`] object if the operation was successful.
+/// Swap all non stablecoin tokens to stablecoin. Tokens with a balance below their configured minimum
+/// amount are skipped. Returns a [`ContractError`] on failure, otherwise returns the swap messages
+/// with their fee token, the bridge assets to swap next and the skipped assets if the operation was successful.
 fn swap_assets(
     deps: Deps,
     contract_addr: &Addr,
     config: &Config,
     assets: Vec<AssetWithLimit>,
-) -> Result<(Vec<CosmosMsg>, Vec<AssetInfo>), ContractError> {
-    let mut messages: Vec<CosmosMsg> = vec![];
+) -> Result<SwapAssetsParts, ContractError> {
+    let mut swaps: Vec<(Asset, CosmosMsg)> = vec![];
     let mut bridge_assets = HashMap::new();
+    let mut skipped_assets = vec![];
 
     for a in assets {
         // Get balance
-        let mut balance = a.info.query_pool(&deps.querier, contract_addr)?;
+        let mut balance = query_asset_balance(&deps.querier, &a.info, contract_addr)?;
+
+        // Skip dust below the minimum amount
+        let min_amount = ASSET_CONFIGS
+            .may_load(deps.storage, a.info.to_string())?
+            .and_then(|c| c.min_amount)
+            .unwrap_or_default();
+        if !balance.is_zero() && balance < min_amount {
+            skipped_assets.push(a.info);
+            continue;
+        }
+
         if let Some(limit) = a.limit {
             if limit < balance {
                 balance = limit;
@@ -189,55 +599,97 @@ fn swap_assets(
         }
 
         if !balance.is_zero() {
-            let swap_msg = swap(deps, config, a.info, balance)?;
+            let (swap_msg, amount) = swap(deps, config, a.info.clone(), balance)?;
             match swap_msg {
                 SwapTarget::Stable(msg) => {
-                    messages.push(msg);
+                    swaps.push((a.info.with_balance(amount), msg));
                 }
                 SwapTarget::Bridge { asset, msg } => {
-                    messages.push(msg);
+                    swaps.push((a.info.with_balance(amount), msg));
                     bridge_assets.insert(asset.to_string(), asset);
                 }
             }
         }
     }
 
-    Ok((messages, bridge_assets.into_values().collect()))
+    Ok((swaps, bridge_assets.into_values().collect(), skipped_assets))
+}
+
+/// ## Description
+/// Wraps each swap in a submessage replying on error, so a failing pool does not revert the other swaps.
+/// The fee token of each swap is stored under the reply id to be reported by [`reply`].
+fn build_swap_submsgs(
+    storage: &mut dyn Storage,
+    swaps: Vec<(Asset, CosmosMsg)>,
+    collected: bool,
+) -> StdResult<Vec<SubMsg>> {
+    swaps
+        .into_iter()
+        .enumerate()
+        .map(|(id, (asset, msg))| {
+            SWAP_REPLIES.save(storage, id as u64, &SwapReply { asset, collected })?;
+            Ok(SubMsg::reply_on_error(msg, id as u64))
+        })
+        .collect()
+}
+
+/// ## Description
+/// Adds the swapped fee tokens to their lifetime totals, or subtracts a failed swap when `add` is false.
+fn update_source_totals(storage: &mut dyn Storage, asset: &Asset, add: bool) -> StdResult<()> {
+    SOURCE_TOTALS.update(storage, asset.info.to_string(), |total| -> StdResult<_> {
+        let total = total.map(|t| t.amount).unwrap_or_default();
+        let amount = if add { total.checked_add(asset.amount)? } else { total.checked_sub(asset.amount)? };
+        Ok(asset.info.with_balance(amount))
+    })?;
+    Ok(())
 }
 
 /// ## Description
 /// Checks if all required pools and bridges exists and performs a swap operation to stablecoin.
-/// Returns a [`ContractError`] on failure, otherwise returns a vector that contains objects
-/// of type [`SwapTarget`] if the operation was successful.
+/// Returns a [`ContractError`] on failure, otherwise returns the [`SwapTarget`] with the amount
+/// swapped after the max swap portion cap if the operation was successful.
 fn swap(
     deps: Deps,
     config: &Config,
     from_token: AssetInfo,
     amount_in: Uint128,
-) -> Result<SwapTarget, ContractError> {
+) -> Result<(SwapTarget, Uint128), ContractError> {
     let stablecoin = config.stablecoin.clone();
     let uluna = native_asset_info(ULUNA_DENOM.to_string());
+    let max_spread = get_max_spread(deps.storage, &from_token)?;
+
+    // Liquid staking derivatives are redeemed first, the underlying asset is swapped like a bridge asset
+    if let Some(unwrap) = get_unwrap(deps.storage, &from_token)? {
+        let msg = build_unwrap_msg(&unwrap, from_token.with_balance(amount_in))?;
+        return Ok((SwapTarget::Bridge { asset: unwrap.underlying, msg }, amount_in));
+    }
+
+    // The router swaps along the whole bridge route at once
+    if let Some(swap_router) = &config.swap_router {
+        let (msg, amount) = try_build_router_swap_msg(&deps.querier, deps.storage, config, swap_router, from_token, amount_in, max_spread)?;
+        return Ok((SwapTarget::Stable(msg), amount));
+    }
 
     // Check if bridge tokens exist
     let bridge_token = BRIDGES.load(deps.storage, from_token.to_string());
     if let Ok(asset) = bridge_token {
-        let msg = try_build_swap_msg(&deps.querier, config, from_token, asset.clone(), amount_in)?;
-        return Ok(SwapTarget::Bridge { asset, msg });
+        let (msg, amount) = try_build_swap_msg(&deps.querier, config, from_token, asset.clone(), amount_in, max_spread)?;
+        return Ok((SwapTarget::Bridge { asset, msg }, amount));
     }
 
     // Check for a direct pair with stablecoin
     let swap_to_stablecoin =
-        try_build_swap_msg(&deps.querier, config, from_token.clone(), stablecoin, amount_in);
-    if let Ok(msg) = swap_to_stablecoin {
-        return Ok(SwapTarget::Stable(msg));
+        try_build_swap_msg(&deps.querier, config, from_token.clone(), stablecoin, amount_in, max_spread);
+    if let Ok((msg, amount)) = swap_to_stablecoin {
+        return Ok((SwapTarget::Stable(msg), amount));
     }
 
     // Check for a pair with LUNA
     if from_token.ne(&uluna) {
         let swap_to_uluna =
-            try_build_swap_msg(&deps.querier, config, from_token.clone(), uluna.clone(), amount_in);
-        if let Ok(msg) = swap_to_uluna {
-            return Ok(SwapTarget::Bridge { asset: uluna, msg });
+            try_build_swap_msg(&deps.querier, config, from_token.clone(), uluna.clone(), amount_in, max_spread);
+        if let Ok((msg, amount)) = swap_to_uluna {
+            return Ok((SwapTarget::Bridge { asset: uluna, msg }, amount));
         }
     }
 
@@ -257,45 +709,44 @@ fn swap_bridge_assets(
         return Err(ContractError::Unauthorized {});
     }
 
-    if assets.is_empty() {
-        return Ok(Response::default());
-    }
-
-    // Check that the contract doesn't call itself endlessly
-    if depth >= BRIDGES_EXECUTION_MAX_DEPTH {
-        return Err(ContractError::MaxBridgeDepth(depth));
-    }
-
     let config = CONFIG.load(deps.storage)?;
 
-    let bridges = assets
+    // The stablecoin is already the final hop, so it is left for distribution
+    let bridges: Vec<AssetWithLimit> = assets
         .into_iter()
+        .filter(|a| a.ne(&config.stablecoin))
         .map(|a| AssetWithLimit {
             info: a,
             limit: None,
         })
         .collect();
 
-    let (mut messages, bridge_assets) = swap_assets(
+    if bridges.is_empty() {
+        return Ok(Response::default());
+    }
+
+    // Check that the contract doesn't call itself endlessly
+    if depth >= BRIDGES_EXECUTION_MAX_DEPTH {
+        return Err(ContractError::MaxBridgeDepth(depth));
+    }
+
+    let (swaps, bridge_assets, skipped_assets) = swap_assets(
         deps.as_ref(),
         &env.contract.address,
         &config,
         bridges)?;
 
-    // There should always be some messages, if there are none - something went wrong
-    if messages.is_empty() {
-        return Err(ContractError::Std(StdError::generic_err(
-            "Empty swap messages",
-        )));
-    }
-
+    // Bridge assets can be missing if their swap failed in the previous hop
+    let mut messages: Vec<CosmosMsg> = vec![];
     if !bridge_assets.is_empty() {
         messages.push(build_swap_bridge_msg(&env.contract.address, bridge_assets, depth + 1)?)
     }
 
     Ok(Response::new()
+        .add_submessages(build_swap_submsgs(deps.storage, swaps, false)?)
         .add_messages(messages)
-        .add_attribute("action", "swap_bridge_assets"))
+        .add_attribute("action", "swap_bridge_assets")
+        .add_attributes(skipped_assets.into_iter().map(|a| attr("skipped_asset", a.to_string()))))
 }
 
 /// ## Description
@@ -305,36 +756,61 @@ fn distribute_fees(
     env: Env,
     info: MessageInfo,
     minimum_receive: Option<Uint128>,
+    keeper: Option<String>,
+    prev_balance: Option<Uint128>,
 ) -> Result<Response, ContractError> {
 
-    // Only the contract itself can call this function
-    if info.sender != env.contract.address {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Only the contract itself and operators can call this function
+    if info.sender != env.contract.address && !is_operator(deps.as_ref(), &config, &info.sender)? {
         return Err(ContractError::Unauthorized {});
     }
 
-    let config = CONFIG.load(deps.storage)?;
-    let (distribute_msg, attributes) = distribute(deps, env, &config, minimum_receive)?;
+    COLLECT_IN_PROGRESS.remove(deps.storage);
+
+    // Keep the stablecoin in the contract until the distribution interval has elapsed
+    let last_distribute = LAST_DISTRIBUTE.may_load(deps.storage)?.unwrap_or_default();
+    let next_distribute = last_distribute + config.distribution_interval;
+    if last_distribute > 0 && env.block.time.seconds() < next_distribute {
+        return Ok(Response::new().add_attributes(vec![
+            attr("action", "distribute_fees"),
+            attr("next_distribute", next_distribute.to_string()),
+        ]));
+    }
+
+    // Only a collect pays its caller, from the stablecoin it received
+    let keeper = match (keeper, prev_balance) {
+        (Some(keeper), Some(prev_balance)) if info.sender == env.contract.address => {
+            Some((deps.api.addr_validate(&keeper)?, prev_balance))
+        }
+        _ => None,
+    };
+    let (distribute_msg, attributes, events) = distribute(deps, env, &config, minimum_receive, keeper)?;
 
     Ok(Response::new()
         .add_messages(distribute_msg)
-        .add_attributes(attributes))
+        .add_attributes(attributes)
+        .add_events(events))
 }
 
-type DistributeMsgParts = (Vec<CosmosMsg>, Vec<(String, String)>);
+type DistributeMsgParts = (Vec<CosmosMsg>, Vec<(String, String)>, Vec<Event>);
 
 /// ## Description
-/// Private function that performs the stablecoin token distribution to beneficiary. Returns a [`ContractError`] on failure,
-/// otherwise returns a vector that contains the objects of type [`CosmosMsg`] if the operation was successful.
+/// Private function that performs the stablecoin token distribution to beneficiary after paying the keeper
+/// its collect incentive on the stablecoin received since the previous balance and burning the burn share. Returns a [`ContractError`] on failure, otherwise returns a vector that contains
+/// the objects of type [`CosmosMsg`] if the operation was successful.
 fn distribute(
     deps: DepsMut,
     env: Env,
     config: &Config,
     minimum_receive: Option<Uint128>,
+    keeper: Option<(Addr, Uint128)>,
 ) -> Result<DistributeMsgParts, ContractError> {
     let mut messages = vec![];
     let mut attributes = vec![];
 
-    let total_amount = config.stablecoin.query_pool(&deps.querier, &env.contract.address)?;
+    let total_amount = query_asset_balance(&deps.querier, &config.stablecoin, &env.contract.address)?;
     if let Some(minimum_receive) = minimum_receive {
         if total_amount < minimum_receive {
             return Err(ContractError::AssertionMinimumReceive {
@@ -345,37 +821,243 @@ fn distribute(
     }
 
     if total_amount.is_zero() {
-        return Ok((messages, attributes));
+        return Ok((messages, attributes, vec![]));
+    }
+
+    LAST_DISTRIBUTE.save(deps.storage, &env.block.time.seconds())?;
+    let events = vec![CompoundReport::new(STAGE_DISTRIBUTE).fee(&config.stablecoin, total_amount).into_event()];
+
+    let mut transfers: Vec<(String, Uint128)> = vec![];
+    let distributed_amount = total_amount;
+    let mut total_amount = total_amount;
+    if let Some((keeper, prev_balance)) = keeper {
+        let incentive = bps_amount(total_amount.saturating_sub(prev_balance), config.collect_incentive_bps)?;
+        if !incentive.is_zero() {
+            messages.push(config.stablecoin.with_balance(incentive).transfer_msg(&keeper)?);
+            attributes.push(("keeper".to_string(), keeper.to_string()));
+            attributes.push(("keeper_amount".to_string(), incentive.to_string()));
+            transfers.push((keeper.to_string(), incentive));
+            total_amount -= incentive;
+        }
+    }
+
+    let burn_amount = bps_amount(total_amount, config.burn_bps)?;
+    if !burn_amount.is_zero() {
+        messages.push(config.stablecoin.with_balance(burn_amount).burn_msg()?);
+        attributes.push(("burn_amount".to_string(), burn_amount.to_string()));
+        transfers.push(("burn".to_string(), burn_amount));
+        total_amount -= burn_amount;
     }
 
     let total_weight = config.target_list.iter()
         .map(|(_, weight)| *weight)
+        .chain(config.ibc_target_list.iter().map(|target| target.weight))
+        .chain(config.staking_target_list.iter().map(|(_, weight)| *weight))
+        .chain(config.lp_target_list.iter().map(|target| target.weight))
         .sum::<u64>();
 
     for (to, weight) in &config.target_list {
         let amount = total_amount.multiply_ratio(*weight, total_weight);
         if !amount.is_zero() {
-            let send_msg = config.stablecoin.with_balance(amount).transfer_msg(to)?;
-            messages.push(send_msg);
+            match PAYOUT_ASSETS.may_load(deps.storage, to)? {
+                Some(payout_asset) => {
+                    messages.push(try_build_payout_swap_msg(
+                        &deps.querier,
+                        deps.storage,
+                        config,
+                        payout_asset.clone(),
+                        amount,
+                        Some(to.clone()),
+                    )?);
+                    attributes.push(("payout_asset".to_string(), payout_asset.to_string()));
+                }
+                None => messages.push(config.stablecoin.with_balance(amount).transfer_msg(to)?),
+            }
             attributes.push(("to".to_string(), to.to_string()));
             attributes.push(("amount".to_string(), amount.to_string()));
+            transfers.push((to.to_string(), amount));
+        }
+    }
+
+    for (staking_contract, weight) in &config.staking_target_list {
+        let amount = total_amount.multiply_ratio(*weight, total_weight);
+        if !amount.is_zero() {
+            // The payout asset is received by this contract, then deposited through the hook
+            match PAYOUT_ASSETS.may_load(deps.storage, staking_contract)? {
+                Some(payout_asset) => {
+                    let prev_balance = query_asset_balance(&deps.querier, &payout_asset, &env.contract.address)?;
+                    messages.push(try_build_payout_swap_msg(
+                        &deps.querier,
+                        deps.storage,
+                        config,
+                        payout_asset.clone(),
+                        amount,
+                        None,
+                    )?);
+                    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+                        contract_addr: env.contract.address.to_string(),
+                        msg: to_binary(&ExecuteMsg::DepositStakingPayout {
+                            staking_contract: staking_contract.to_string(),
+                            asset: payout_asset.clone(),
+                            prev_balance,
+                        })?,
+                        funds: vec![],
+                    }));
+                    attributes.push(("payout_asset".to_string(), payout_asset.to_string()));
+                }
+                None => messages.push(build_staking_deposit_msg(
+                    config.stablecoin.with_balance(amount),
+                    staking_contract,
+                )?),
+            }
+            attributes.push(("to".to_string(), staking_contract.to_string()));
+            attributes.push(("amount".to_string(), amount.to_string()));
+            transfers.push((staking_contract.to_string(), amount));
+        }
+    }
+
+    // The compound proxy swaps half of the share to the other pair asset and sends the LP token to the receiver
+    for target in &config.lp_target_list {
+        let amount = total_amount.multiply_ratio(target.weight, total_weight);
+        if !amount.is_zero() {
+            let share = config.stablecoin.with_balance(amount);
+            let funds = match &config.stablecoin {
+                AssetInfo::NativeToken { denom } => vec![Coin { denom: denom.clone(), amount }],
+                AssetInfo::Token { .. } => {
+                    messages.push(share.increase_allowance_msg(
+                        target.compound_proxy.0.to_string(),
+                        Some(Expiration::AtHeight(env.block.height + 1)),
+                    )?);
+                    vec![]
+                }
+            };
+            messages.push(target.compound_proxy.compound_msg(
+                vec![share],
+                funds,
+                None,
+                None,
+                Some(target.receiver.to_string()),
+            )?);
+            attributes.push(("to".to_string(), target.receiver.to_string()));
+            attributes.push(("amount".to_string(), amount.to_string()));
+            transfers.push((target.receiver.to_string(), amount));
         }
     }
 
+    // A timed out IBC transfer is refunded to this contract and distributed again next time
+    if let AssetInfo::NativeToken { denom } = &config.stablecoin {
+        for target in &config.ibc_target_list {
+            let amount = total_amount.multiply_ratio(target.weight, total_weight);
+            if !amount.is_zero() {
+                messages.push(CosmosMsg::Ibc(IbcMsg::Transfer {
+                    channel_id: target.channel_id.clone(),
+                    to_address: target.receiver.clone(),
+                    amount: Coin {
+                        denom: denom.clone(),
+                        amount,
+                    },
+                    timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(config.ibc_timeout)),
+                }));
+                attributes.push(("to".to_string(), target.receiver.clone()));
+                attributes.push(("amount".to_string(), amount.to_string()));
+                transfers.push((target.receiver.clone(), amount));
+            }
+        }
+    }
+
+    record_distribution(deps.storage, &env, &config.stablecoin, distributed_amount, transfers)?;
     attributes.push(("action".to_string(), "distribute_fees".to_string()));
 
-    Ok((messages, attributes))
+    Ok((messages, attributes, events))
+}
+
+/// ## Description
+/// Deposits the payout asset received from the payout swap into the staking contract. Only the contract
+/// itself can execute this. Returns a [`ContractError`] on failure.
+fn deposit_staking_payout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking_contract: String,
+    asset: AssetInfo,
+    prev_balance: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let staking_contract = deps.api.addr_validate(&staking_contract)?;
+    let balance = query_asset_balance(&deps.querier, &asset, &env.contract.address)?;
+    let amount = balance.checked_sub(prev_balance)?;
+
+    let mut messages = vec![];
+    if !amount.is_zero() {
+        messages.push(build_staking_deposit_msg(asset.with_balance(amount), &staking_contract)?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "deposit_staking_payout"),
+        attr("to", staking_contract),
+        attr("asset", asset.to_string()),
+        attr("amount", amount),
+    ]))
+}
+
+/// ## Description
+/// Stores the distribution in the history and adds each transfer to the lifetime total of its beneficiary.
+fn record_distribution(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &AssetInfo,
+    amount: Uint128,
+    transfers: Vec<(String, Uint128)>,
+) -> StdResult<()> {
+    for (beneficiary, amount) in transfers.iter() {
+        BENEFICIARY_TOTALS.update(storage, beneficiary, |totals| -> StdResult<_> {
+            let mut totals = totals.unwrap_or_default();
+            match totals.iter_mut().find(|total| &total.info == asset) {
+                Some(total) => total.amount = total.amount.checked_add(*amount)?,
+                None => totals.push(asset.with_balance(*amount)),
+            }
+            Ok(totals)
+        })?;
+    }
+
+    let id = DISTRIBUTION_COUNT.may_load(storage)?.unwrap_or_default() + 1;
+    DISTRIBUTION_COUNT.save(storage, &id)?;
+    DISTRIBUTIONS.save(storage, id, &Distribution {
+        id,
+        time: env.block.time.seconds(),
+        asset: asset.clone(),
+        amount,
+        transfers,
+    })
 }
 
 /// ## Description
-/// Updates contract config. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+/// Updates contract config. When a config delay is set, the new config is stored in [`CONFIG_PROPOSAL`]
+/// to be applied once the delay has elapsed. Returns a [`ContractError`] on failure.
 #[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     operator: Option<String>,
     factory_contract: Option<String>,
     target_list: Option<Vec<(String, u64)>>,
+    collect_incentive_bps: Option<u64>,
+    distribution_interval: Option<u64>,
+    burn_bps: Option<u64>,
+    ibc_target_list: Option<Vec<IbcTarget>>,
+    ibc_timeout: Option<u64>,
+    stablecoin: Option<AssetInfo>,
+    staking_target_list: Option<Vec<(String, u64)>>,
+    generator_rewards: Option<GeneratorRewards>,
+    swap_router: Option<SwapRouter>,
+    max_swap_portion_bps: Option<u64>,
+    config_delay: Option<u64>,
+    pauser: Option<String>,
+    lp_target_list: Option<Vec<LpTarget>>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
 
@@ -392,60 +1074,285 @@ pub fn update_config(
     }
 
     if let Some(target_list) = target_list {
-        config.target_list = target_list.into_iter()
-        .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
-        .collect::<StdResult<_>>()?
+        config.target_list = validate_target_list(deps.api, target_list)?;
     }
 
-    CONFIG.save(deps.storage, &config)?;
+    if let Some(collect_incentive_bps) = collect_incentive_bps {
+        config.collect_incentive_bps = validate_collect_incentive(collect_incentive_bps)?;
+    }
 
-    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
-}
+    if let Some(distribution_interval) = distribution_interval {
+        config.distribution_interval = distribution_interval;
+    }
 
-/// ## Description
-/// Adds or removes bridge tokens used to swap fee tokens to stablecoin. Returns a [`ContractError`] on failure.
-fn update_bridges(
-    deps: DepsMut,
-    info: MessageInfo,
-    add: Option<Vec<(AssetInfo, AssetInfo)>>,
-    remove: Option<Vec<AssetInfo>>,
-) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    if let Some(burn_bps) = burn_bps {
+        config.burn_bps = validate_burn_share(burn_bps)?;
+    }
 
-    // Permission check
-    if info.sender != config.operator {
-        return Err(ContractError::Unauthorized {});
+    if let Some(generator_rewards) = generator_rewards {
+        config.generator_rewards = Some(validate_generator_rewards(deps.api, generator_rewards)?);
     }
 
-    // Remove old bridges
-    if let Some(remove_bridges) = remove {
-        for asset in remove_bridges {
-            BRIDGES.remove(deps.storage, asset.to_string());
-        }
+    if let Some(max_swap_portion_bps) = max_swap_portion_bps {
+        config.max_swap_portion_bps = validate_swap_portion(max_swap_portion_bps)?;
     }
 
-    // Add new bridges
-    if let Some(add_bridges) = add {
-        for (asset, bridge) in add_bridges {
-            if asset.equal(&bridge) {
-                return Err(ContractError::InvalidBridge(asset, bridge));
-            }
-            BRIDGES.save(deps.storage, asset.to_string(), &bridge)?;
-        }
+    if let Some(swap_router) = swap_router {
+        config.swap_router = Some(validate_swap_router(deps.api, swap_router)?);
     }
 
-    let bridges = BRIDGES
-        .range(deps.storage, None, None, Order::Ascending)
-        .collect::<StdResult<Vec<(String, AssetInfo)>>>()?;
+    if let Some(staking_target_list) = staking_target_list {
+        config.staking_target_list = validate_target_list(deps.api, staking_target_list)?;
+    }
 
-    for (asset_label, bridge) in bridges {
-        let asset = match deps.api.addr_validate(&asset_label) {
-            Ok(contract_addr) => AssetInfo::Token { contract_addr },
-            Err(_) => AssetInfo::NativeToken { denom: asset_label },
-        };
-        // Check that bridge tokens can be swapped to stablecoin
-        validate_bridge(
+    let mut lp_target_changed = false;
+    if let Some(lp_target_list) = lp_target_list {
+        config.lp_target_list = validate_lp_target_list(deps.api, lp_target_list)?;
+        lp_target_changed = true;
+    }
+
+    if let Some(ibc_target_list) = ibc_target_list {
+        let new_stablecoin = stablecoin.as_ref().unwrap_or(&config.stablecoin);
+        config.ibc_target_list = validate_ibc_target_list(ibc_target_list, new_stablecoin)?;
+    }
+
+    if let Some(ibc_timeout) = ibc_timeout {
+        config.ibc_timeout = validate_ibc_timeout(ibc_timeout)?;
+    }
+
+    if let Some(config_delay) = config_delay {
+        config.config_delay = config_delay;
+    }
+
+    if let Some(pauser) = pauser {
+        config.pauser = Some(deps.api.addr_validate(&pauser)?);
+    }
+
+    if let Some(stablecoin) = stablecoin {
+        stablecoin.check(deps.api)?;
+        if stablecoin.ne(&config.stablecoin) {
+            config.stablecoin = stablecoin;
+            config.ibc_target_list = validate_ibc_target_list(config.ibc_target_list, &config.stablecoin)?;
+            lp_target_changed = true;
+            validate_bridges(deps.as_ref(), &config)?;
+        }
+    }
+
+    validate_targets(&config)?;
+    if lp_target_changed {
+        validate_lp_target_routes(&deps.querier, &config)?;
+    }
+
+    // The delay of the current config applies, including to a change of the delay itself
+    let delay = CONFIG.load(deps.storage)?.config_delay;
+    if delay == 0 {
+        return save_config(deps, &env, config);
+    }
+
+    // Pending bridge, asset config and operator changes are kept with the new config
+    let changes = CONFIG_PROPOSAL
+        .may_load(deps.storage)?
+        .map(|proposal| proposal.changes)
+        .unwrap_or_default();
+    let apply_after = env.block.time.seconds() + delay;
+    CONFIG_PROPOSAL.save(deps.storage, &ConfigProposal {
+        config,
+        changes,
+        apply_after,
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_config"),
+        attr("apply_after", apply_after.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Applies the proposed config and changes once the config delay has elapsed. Only the owner and the
+/// operator can execute this.
+fn apply_config(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner && info.sender != config.operator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let proposal = CONFIG_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoConfigProposal {})?;
+    if env.block.time.seconds() < proposal.apply_after {
+        return Err(ContractError::ConfigProposalNotReady(proposal.apply_after));
+    }
+
+    CONFIG_PROPOSAL.remove(deps.storage);
+
+    // Ownership is transferred separately and may have changed since the proposal
+    let mut new_config = proposal.config;
+    new_config.owner = config.owner;
+    let response = save_config(deps.branch(), &env, new_config.clone())?;
+
+    let mut bridges_changed = new_config.stablecoin.ne(&config.stablecoin);
+    let mut attributes = vec![];
+    for change in proposal.changes {
+        match change {
+            ConfigChange::UpdateBridges { add, remove } => {
+                save_bridges(deps.storage, add, remove)?;
+                bridges_changed = true;
+                attributes.push(attr("action", "update_bridges"));
+            }
+            ConfigChange::UpdateAssetConfigs { add, remove } => {
+                save_asset_configs(deps.storage, add, remove)?;
+                attributes.push(attr("action", "update_asset_configs"));
+            }
+            ConfigChange::AddOperator(operator) => {
+                OPERATORS.save(deps.storage, &operator, &true)?;
+                attributes.push(attr("action", "add_operator"));
+                attributes.push(attr("operator", operator));
+            }
+            ConfigChange::RemoveOperator(operator) => {
+                OPERATORS.remove(deps.storage, &operator);
+                attributes.push(attr("action", "remove_operator"));
+                attributes.push(attr("operator", operator));
+            }
+        }
+    }
+    if bridges_changed {
+        validate_bridges(deps.as_ref(), &new_config)?;
+    }
+
+    Ok(response.add_attributes(attributes))
+}
+
+/// ## Description
+/// Adds the change to the config proposal, to be applied with the proposed config once the config delay
+/// has elapsed again. Without a pending proposal, the current config is proposed unchanged.
+fn propose_change(deps: DepsMut, env: &Env, config: Config, change: ConfigChange) -> Result<Response, ContractError> {
+    let mut proposal = CONFIG_PROPOSAL.may_load(deps.storage)?.unwrap_or(ConfigProposal {
+        config: config.clone(),
+        changes: vec![],
+        apply_after: 0,
+    });
+    proposal.changes.push(change);
+    proposal.apply_after = env.block.time.seconds() + config.config_delay;
+    CONFIG_PROPOSAL.save(deps.storage, &proposal)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_config"),
+        attr("apply_after", proposal.apply_after.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Removes the proposed config. Only the owner can execute this.
+fn drop_config_proposal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    CONFIG_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_config_proposal"))
+}
+
+/// ## Description
+/// Saves the new config. When the stablecoin changes, the remaining balance of the old stablecoin
+/// is swapped into the new one.
+fn save_config(deps: DepsMut, env: &Env, config: Config) -> Result<Response, ContractError> {
+    let old_stablecoin = CONFIG.load(deps.storage)?.stablecoin;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if old_stablecoin.ne(&config.stablecoin) {
+        let (swaps, bridge_assets, _) = swap_assets(
             deps.as_ref(),
+            &env.contract.address,
+            &config,
+            vec![AssetWithLimit {
+                info: old_stablecoin,
+                limit: None,
+            }],
+        )?;
+        messages.extend(swaps.into_iter().map(|(_, msg)| msg));
+        if !bridge_assets.is_empty() {
+            messages.push(build_swap_bridge_msg(
+                &env.contract.address,
+                bridge_assets,
+                BRIDGES_INITIAL_DEPTH,
+            )?);
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![attr("action", "update_config")]))
+}
+
+/// ## Description
+/// Adds or removes bridge tokens used to swap fee tokens to stablecoin. When a config delay is set, the
+/// change is added to the config proposal. Returns a [`ContractError`] on failure.
+fn update_bridges(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, AssetInfo)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.operator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let add = add.unwrap_or_default();
+    for (asset, bridge) in add.iter() {
+        if asset.equal(bridge) {
+            return Err(ContractError::InvalidBridge(asset.clone(), bridge.clone()));
+        }
+    }
+    let remove = remove.unwrap_or_default();
+
+    if config.config_delay > 0 {
+        return propose_change(deps, &env, config, ConfigChange::UpdateBridges { add, remove });
+    }
+
+    save_bridges(deps.storage, add, remove)?;
+    validate_bridges(deps.as_ref(), &config)?;
+
+    Ok(Response::default().add_attribute("action", "update_bridges"))
+}
+
+/// ## Description
+/// Removes the old bridges, then stores the new bridges.
+fn save_bridges(
+    storage: &mut dyn Storage,
+    add: Vec<(AssetInfo, AssetInfo)>,
+    remove: Vec<AssetInfo>,
+) -> StdResult<()> {
+    for asset in remove {
+        BRIDGES.remove(storage, asset.to_string());
+    }
+    for (asset, bridge) in add {
+        BRIDGES.save(storage, asset.to_string(), &bridge)?;
+    }
+    Ok(())
+}
+
+/// ## Description
+/// Checks that all the stored bridge tokens can be swapped to the stablecoin. Returns a [`ContractError`] on failure.
+fn validate_bridges(deps: Deps, config: &Config) -> Result<(), ContractError> {
+    let bridges = BRIDGES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, AssetInfo)>>>()?;
+
+    for (asset_label, bridge) in bridges {
+        let asset = match deps.api.addr_validate(&asset_label) {
+            Ok(contract_addr) => AssetInfo::Token { contract_addr },
+            Err(_) => AssetInfo::NativeToken { denom: asset_label },
+        };
+        // Check that bridge tokens can be swapped to stablecoin
+        validate_bridge(
+            deps,
             &config.factory_contract,
             &asset,
             &bridge,
@@ -454,7 +1361,225 @@ fn update_bridges(
         )?;
     }
 
-    Ok(Response::default().add_attribute("action", "update_bridges"))
+    Ok(())
+}
+
+/// ## Description
+/// Returns whether the address is the owner, the config operator or one of the added operators.
+fn is_operator(deps: Deps, config: &Config, addr: &Addr) -> StdResult<bool> {
+    Ok(*addr == config.owner || *addr == config.operator || OPERATORS.has(deps.storage, addr))
+}
+
+/// ## Description
+/// Allows an address to call Collect and DistributeFees. When a config delay is set, the change is added
+/// to the config proposal. Returns a [`ContractError`] on failure.
+fn add_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let operator = deps.api.addr_validate(&operator)?;
+    if config.config_delay > 0 {
+        return propose_change(deps, &env, config, ConfigChange::AddOperator(operator));
+    }
+
+    OPERATORS.save(deps.storage, &operator, &true)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "add_operator"),
+        attr("operator", operator),
+    ]))
+}
+
+/// ## Description
+/// Removes an address from the operators. When a config delay is set, the change is added to the config
+/// proposal. Returns a [`ContractError`] on failure.
+fn remove_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let operator = deps.api.addr_validate(&operator)?;
+    if config.config_delay > 0 {
+        return propose_change(deps, &env, config, ConfigChange::RemoveOperator(operator));
+    }
+
+    OPERATORS.remove(deps.storage, &operator);
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_operator"),
+        attr("operator", operator),
+    ]))
+}
+
+/// ## Description
+/// Sets or removes the payout asset of beneficiaries. Only the owner can execute this.
+/// Returns a [`ContractError`] on failure.
+fn update_payout_assets(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<(String, AssetInfo)>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(remove_beneficiaries) = remove {
+        for beneficiary in remove_beneficiaries {
+            PAYOUT_ASSETS.remove(deps.storage, &deps.api.addr_validate(&beneficiary)?);
+        }
+    }
+
+    if let Some(add_beneficiaries) = add {
+        for (beneficiary, payout_asset) in add_beneficiaries {
+            let beneficiary = deps.api.addr_validate(&beneficiary)?;
+            let is_target = config.target_list.iter()
+                .chain(config.staking_target_list.iter())
+                .any(|(addr, _)| addr == &beneficiary);
+            if !is_target {
+                return Err(ContractError::UnknownBeneficiary(beneficiary.to_string()));
+            }
+            payout_asset.check(deps.api)?;
+            if payout_asset.eq(&config.stablecoin) {
+                return Err(ContractError::InvalidPayoutAsset(payout_asset));
+            }
+
+            // Check that stablecoin can be swapped to the payout asset
+            try_build_payout_swap_msg(&deps.querier, deps.storage, &config, payout_asset.clone(), Uint128::zero(), None)
+                .map_err(|_| ContractError::InvalidPayoutAsset(payout_asset.clone()))?;
+
+            PAYOUT_ASSETS.save(deps.storage, &beneficiary, &payout_asset)?;
+        }
+    }
+
+    Ok(Response::default().add_attribute("action", "update_payout_assets"))
+}
+
+/// ## Description
+/// Pauses or resumes Collect. Only the owner and pauser can execute this.
+/// Returns a [`ContractError`] on failure.
+fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(pause::set_paused(deps.storage, &info.sender, &config.owner, config.pauser.as_ref(), paused)?)
+}
+
+/// ## Description
+/// Sends the whole balance of an asset to the specified address. Only the owner can execute this,
+/// and not while a collect is in progress. Returns a [`ContractError`] on failure.
+fn rescue(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: AssetInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if COLLECT_IN_PROGRESS.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::CollectInProgress {});
+    }
+
+    let to = deps.api.addr_validate(&to)?;
+    let amount = query_asset_balance(&deps.querier, &asset, &env.contract.address)?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToRescue {});
+    }
+
+    Ok(Response::new()
+        .add_message(asset.with_balance(amount).transfer_msg(&to)?)
+        .add_attributes(vec![
+            attr("action", "rescue"),
+            attr("asset", asset.to_string()),
+            attr("amount", amount),
+            attr("to", to),
+        ]))
+}
+
+/// ## Description
+/// Adds or removes swap settings of fee tokens. When a config delay is set, the change is added to the
+/// config proposal. Returns a [`ContractError`] on failure.
+fn update_asset_configs(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<AssetConfig>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Permission check
+    if info.sender != config.operator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let add = add.unwrap_or_default();
+    for asset_config in add.iter() {
+        asset_config.info.check(deps.api)?;
+        if let Some(max_spread) = asset_config.max_spread {
+            if max_spread > Decimal::percent(MAX_SPREAD) {
+                return Err(ContractError::IncorrectMaxSpread {});
+            }
+        }
+        if let Some(unwrap) = &asset_config.unwrap {
+            deps.api.addr_validate(&unwrap.hub)?;
+            unwrap.underlying.check(deps.api)?;
+            if unwrap.underlying.eq(&asset_config.info) {
+                return Err(ContractError::InvalidUnwrap(asset_config.info.clone()));
+            }
+        }
+    }
+    let remove = remove.unwrap_or_default();
+
+    if config.config_delay > 0 {
+        return propose_change(deps, &env, config, ConfigChange::UpdateAssetConfigs { add, remove });
+    }
+
+    save_asset_configs(deps.storage, add, remove)?;
+
+    Ok(Response::default().add_attribute("action", "update_asset_configs"))
+}
+
+/// ## Description
+/// Removes the old asset configs, then stores the new asset configs.
+fn save_asset_configs(
+    storage: &mut dyn Storage,
+    add: Vec<AssetConfig>,
+    remove: Vec<AssetInfo>,
+) -> StdResult<()> {
+    for asset in remove {
+        ASSET_CONFIGS.remove(storage, asset.to_string());
+    }
+    for asset_config in add {
+        ASSET_CONFIGS.save(storage, asset_config.info.to_string(), &asset_config)?;
+    }
+    Ok(())
 }
 
 /// ## Description
@@ -463,9 +1588,22 @@ fn update_bridges(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::ConfigProposal {} => to_binary(&CONFIG_PROPOSAL.may_load(deps.storage)?),
         QueryMsg::Balances { assets } => to_binary(&query_get_balances(deps, env, assets)?),
-        QueryMsg::Bridges {} => to_binary(&query_bridges(deps, env)?),
+        QueryMsg::Bridges { start_after, limit } => to_binary(&query_bridges(deps, start_after, limit)?),
+        QueryMsg::Assets { start_after, limit } => to_binary(&query_assets(deps, start_after, limit)?),
+        QueryMsg::PayoutAssets { start_after, limit } => to_binary(&query_payout_assets(deps, start_after, limit)?),
+        QueryMsg::Operators {} => to_binary(&query_operators(deps)?),
+        QueryMsg::Paused {} => to_binary(&is_paused(deps.storage)?),
         QueryMsg::CollectSimulation { assets } => to_binary(&query_collect_simulation(deps, env, assets)?),
+        QueryMsg::SimulateCollect { assets } => to_binary(&query_simulate_collect(deps, env, assets)?),
+        QueryMsg::DistributionHistory { start_after, limit } => {
+            to_binary(&query_distribution_history(deps, start_after, limit)?)
+        }
+        QueryMsg::BeneficiaryTotals { start_after, limit } => {
+            to_binary(&query_beneficiary_totals(deps, start_after, limit)?)
+        }
+        QueryMsg::SourceTotals { start_after, limit } => to_binary(&query_source_totals(deps, start_after, limit)?),
     }
 }
 
@@ -476,7 +1614,7 @@ fn query_get_balances(deps: Deps, env: Env, assets: Vec<AssetInfo>) -> StdResult
 
     for a in assets {
         // Get balance
-        let balance = a.query_pool(&deps.querier, &env.contract.address)?;
+        let balance = query_asset_balance(&deps.querier, &a, &env.contract.address)?;
         if !balance.is_zero() {
             resp.balances.push(Asset {
                 info: a,
@@ -488,11 +1626,23 @@ fn query_get_balances(deps: Deps, env: Env, assets: Vec<AssetInfo>) -> StdResult
     Ok(resp)
 }
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 /// ## Description
 /// Returns bridge tokens used for swapping fee tokens to stablecoin.
-fn query_bridges(deps: Deps, _env: Env) -> StdResult<Vec<(String, String)>> {
+fn query_bridges(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, String)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
     BRIDGES
-        .range(deps.storage, None, None, Order::Ascending)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
         .map(|bridge| {
             let (bridge, asset) = bridge?;
             Ok((bridge, asset.to_string()))
@@ -500,6 +1650,106 @@ fn query_bridges(deps: Deps, _env: Env) -> StdResult<Vec<(String, String)>> {
         .collect()
 }
 
+/// ## Description
+/// Returns the swap settings of fee tokens.
+fn query_assets(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<AssetConfig>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    ASSET_CONFIGS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// ## Description
+/// Returns the beneficiaries with a payout asset other than stablecoin.
+fn query_payout_assets(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, AssetInfo)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    PAYOUT_ASSETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (beneficiary, payout_asset) = item?;
+            Ok((beneficiary.to_string(), payout_asset))
+        })
+        .collect()
+}
+
+/// ## Description
+/// Returns the past distributions, oldest first.
+fn query_distribution_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Distribution>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    DISTRIBUTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// ## Description
+/// Returns the lifetime amounts distributed to each beneficiary.
+fn query_beneficiary_totals(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<BeneficiaryTotal>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    BENEFICIARY_TOTALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (beneficiary, amounts) = item?;
+            Ok(BeneficiaryTotal { beneficiary, amounts })
+        })
+        .collect()
+}
+
+/// ## Description
+/// Returns the lifetime amounts of each fee token swapped by Collect.
+fn query_source_totals(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Asset>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    SOURCE_TOTALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// ## Description
+/// Returns the operators allowed to call Collect and DistributeFees.
+fn query_operators(deps: Deps) -> StdResult<Vec<String>> {
+    OPERATORS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.to_string()))
+        .collect()
+}
+
 fn query_collect_simulation(
     deps: Deps,
     env: Env,
@@ -511,7 +1761,7 @@ fn query_collect_simulation(
     for a in assets {
 
         // query balance
-        let mut balance = a.info.query_pool(&deps.querier, &env.contract.address)?;
+        let mut balance = query_asset_balance(&deps.querier, &a.info, &env.contract.address)?;
         if let Some(limit) = a.limit {
             if limit < balance {
                 balance = limit;
@@ -526,7 +1776,7 @@ fn query_collect_simulation(
 
     let config = CONFIG.load(deps.storage)?;
     if !uniq.contains_key(&config.stablecoin) {
-        let stable_amount = config.stablecoin.query_pool(&deps.querier, &env.contract.address)?;
+        let stable_amount = query_asset_balance(&deps.querier, &config.stablecoin, &env.contract.address)?;
         uniq.insert(config.stablecoin.clone(), stable_amount);
     }
 
@@ -553,6 +1803,11 @@ fn bulk_swap_simulation(
             continue;
         }
 
+        // The hub redemption rate is unknown to the contract
+        if get_unwrap(deps.storage, &from_asset_info)?.is_some() {
+            return Err(ContractError::CannotSimulateUnwrap(from_asset_info));
+        }
+
         // Check if bridge tokens exist
         let bridge_token = BRIDGES.load(deps.storage, from_asset_info.to_string());
         if let Ok(to_asset_info) = bridge_token {
@@ -598,6 +1853,90 @@ fn bulk_swap_simulation(
     bulk_swap_simulation(deps, next_assets, config, next_depth)
 }
 
+/// ## Description
+/// Simulates the swap of each fee token to stablecoin separately, following the same route as [`swap`].
+/// Bridge assets shared between fee tokens are simulated per fee token, so the outputs are estimates.
+fn query_simulate_collect(
+    deps: Deps,
+    env: Env,
+    assets: Vec<AssetWithLimit>,
+) -> Result<SimulateCollectResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // Check for duplicate assets
+    let mut uniq = HashSet::new();
+    if !assets
+        .iter()
+        .all(|a| uniq.insert(a.info.to_string()))
+    {
+        return Err(ContractError::DuplicatedAsset {});
+    }
+
+    let simulations = assets
+        .into_iter()
+        .map(|a| {
+            let mut balance = query_asset_balance(&deps.querier, &a.info, &env.contract.address)?;
+            if let Some(limit) = a.limit {
+                if limit < balance {
+                    balance = limit;
+                }
+            }
+            simulate_asset_collect(deps, &config, a.info, balance)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(SimulateCollectResponse { simulations })
+}
+
+/// ## Description
+/// Simulates the swap of a single fee token to stablecoin hop by hop. Returns a [`ContractError`] if
+/// a hop has no swap destination or the route is longer than the execution depth.
+fn simulate_asset_collect(
+    deps: Deps,
+    config: &Config,
+    info: AssetInfo,
+    amount: Uint128,
+) -> Result<AssetCollectSimulation, ContractError> {
+    let uluna = native_asset_info(ULUNA_DENOM.to_string());
+    let mut route = vec![info.clone()];
+    let mut from_asset_info = info.clone();
+    let mut return_amount = amount;
+    let mut depth = BRIDGES_INITIAL_DEPTH;
+
+    while from_asset_info.ne(&config.stablecoin) {
+        if depth > BRIDGES_EXECUTION_MAX_DEPTH {
+            return Err(ContractError::MaxBridgeDepth(depth));
+        }
+        if get_unwrap(deps.storage, &from_asset_info)?.is_some() {
+            return Err(ContractError::CannotSimulateUnwrap(from_asset_info));
+        }
+
+        let to_asset_info = if let Ok(bridge) = BRIDGES.load(deps.storage, from_asset_info.to_string()) {
+            bridge
+        } else if query_pair_info(&deps.querier, &config.factory_contract, &[from_asset_info.clone(), config.stablecoin.clone()]).is_ok() {
+            config.stablecoin.clone()
+        } else if from_asset_info.ne(&uluna) && query_pair_info(&deps.querier, &config.factory_contract, &[from_asset_info.clone(), uluna.clone()]).is_ok() {
+            uluna.clone()
+        } else {
+            return Err(ContractError::CannotSwap(from_asset_info));
+        };
+
+        if !return_amount.is_zero() {
+            return_amount = try_swap_simulation(&deps.querier, config, from_asset_info, to_asset_info.clone(), return_amount)?;
+        }
+        route.push(to_asset_info.clone());
+        from_asset_info = to_asset_info;
+        depth += 1;
+    }
+
+    Ok(AssetCollectSimulation {
+        info,
+        amount,
+        route,
+        return_amount,
+    })
+}
+
 fn add_amount(assets: &mut HashMap<AssetInfo, Uint128>, key: AssetInfo, return_amount: Uint128) {
     let prev_amount = assets.get(&key)
         .copied()
@@ -606,8 +1945,90 @@ fn add_amount(assets: &mut HashMap<AssetInfo, Uint128>, key: AssetInfo, return_a
 }
 
 /// ## Description
-/// Used for contract migration. Returns a default object of type [`Response`].
+/// Handles a failed swap submessage. The failure is reported in attributes and the fee token stays
+/// in the contract, so the remaining swaps and the distribution still go through.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.result {
+        SubMsgResult::Err(err) => {
+            let swap_reply = SWAP_REPLIES.load(deps.storage, msg.id)?;
+            // The fee token stays in the contract, so it is no longer counted as swapped
+            if swap_reply.collected {
+                update_source_totals(deps.storage, &swap_reply.asset, false)?;
+            }
+            Ok(Response::new().add_attributes(vec![
+                attr("action", "swap_failed"),
+                attr("asset", swap_reply.asset.info.to_string()),
+                attr("error", err),
+            ]))
+        }
+        SubMsgResult::Ok(_) => Ok(Response::default()),
+    }
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
     Ok(Response::default())
 }
+
+/// ## Description
+/// Checks the stored config against the limits enforced when it is set, and that no collect is
+/// in progress.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.operator, "operator")?;
+    assert_valid_addr(deps.api, &config.factory_contract, "factory_contract")?;
+    if let Some(pauser) = &config.pauser {
+        assert_valid_addr(deps.api, pauser, "pauser")?;
+    }
+    for (addr, weight) in config.target_list.iter().chain(config.staking_target_list.iter()) {
+        assert_valid_addr(deps.api, addr, "target")?;
+        if *weight == 0 {
+            return Err(invalid_state(&format!("zero weight for {}", addr)));
+        }
+    }
+    for target in config.lp_target_list.iter() {
+        assert_valid_addr(deps.api, &target.compound_proxy.0, "compound_proxy")?;
+        assert_valid_addr(deps.api, &target.receiver, "target")?;
+        if target.weight == 0 {
+            return Err(invalid_state(&format!("zero weight for {}", target.receiver)));
+        }
+    }
+    if config.target_list.is_empty() && config.ibc_target_list.is_empty() && config.staking_target_list.is_empty() && config.lp_target_list.is_empty() {
+        return Err(invalid_state("target list is empty"));
+    }
+    if let Some(generator_rewards) = &config.generator_rewards {
+        assert_valid_addr(deps.api, &generator_rewards.generator, "generator")?;
+        assert_valid_addr(deps.api, &generator_rewards.astro_token, "astro_token")?;
+        for lp_token in generator_rewards.lp_tokens.iter() {
+            assert_valid_addr(deps.api, lp_token, "lp_token")?;
+        }
+    }
+    if let Some(swap_router) = &config.swap_router {
+        assert_valid_addr(deps.api, &swap_router.router.0, "swap_router")?;
+    }
+    config.stablecoin.check(deps.api).map_err(|_| invalid_state("stablecoin is not a valid asset"))?;
+    if config.ibc_timeout == 0 {
+        return Err(invalid_state("ibc_timeout must be greater than 0"));
+    }
+    assert_bps(config.collect_incentive_bps, MAX_COLLECT_INCENTIVE_BPS, "collect_incentive_bps")?;
+    assert_bps(config.burn_bps, MAX_BURN_BPS, "burn_bps")?;
+    assert_bps(config.max_swap_portion_bps, MAX_SWAP_PORTION_BPS, "max_swap_portion_bps")?;
+    for item in PAYOUT_ASSETS.range(deps.storage, None, None, Order::Ascending) {
+        let (beneficiary, payout_asset) = item?;
+        assert_valid_addr(deps.api, &beneficiary, "payout beneficiary")?;
+        payout_asset.check(deps.api).map_err(|_| invalid_state(&format!("invalid payout asset of {}", beneficiary)))?;
+    }
+
+    if COLLECT_IN_PROGRESS.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(invalid_state("collect in progress"));
+    }
+
+    Ok(())
+}
@@ -27,9 +27,48 @@ pub enum ContractError {
     #[error("Incorrect max spread")]
     IncorrectMaxSpread {},
 
+    #[error("Invalid unwrap of {0}")]
+    InvalidUnwrap(AssetInfo),
+
+    #[error("Cannot simulate the unwrap of {0}")]
+    CannotSimulateUnwrap(AssetInfo),
+
+    #[error("Invalid target list: {0}")]
+    InvalidTargetList(String),
+
+    #[error("{0} is not in the target list or staking target list")]
+    UnknownBeneficiary(String),
+
+    #[error("Invalid payout asset {0}")]
+    InvalidPayoutAsset(AssetInfo),
+
+    #[error("Collect incentive must be at most {0} bps")]
+    InvalidCollectIncentive(u64),
+
+    #[error("Burn share must be at most {0} bps")]
+    InvalidBurnShare(u64),
+
+    #[error("Max swap portion must be at most {0} bps")]
+    InvalidSwapPortion(u64),
+
+    #[error("IBC timeout must be greater than 0")]
+    InvalidIbcTimeout {},
+
+    #[error("No config proposal")]
+    NoConfigProposal {},
+
+    #[error("Config proposal cannot be applied before {0}")]
+    ConfigProposalNotReady(u64),
+
     #[error("Cannot collect. Remove duplicate asset")]
     DuplicatedAsset {},
 
+    #[error("Cannot rescue while a collect is in progress")]
+    CollectInProgress {},
+
+    #[error("Nothing to rescue")]
+    NothingToRescue {},
+
     #[error("Assertion failed; minimum receive amount: {minimum_receive}, actual amount: {amount}")]
     AssertionMinimumReceive { minimum_receive: Uint128, amount: Uint128 },
 }
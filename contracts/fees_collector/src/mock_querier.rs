@@ -6,7 +6,8 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{token_asset, AssetInfo, PairInfo, Asset};
 use astroport::generator::{PendingTokenResponse};
-use astroport::pair::SimulationResponse;
+use astroport::pair::{PoolResponse, SimulationResponse};
+use spectrum::compound_proxy::CompoundSimulationResponse;
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -27,6 +28,7 @@ pub struct WasmMockQuerier {
     raw: HashMap<(String, Binary), Binary>,
     pairs: HashMap<Vec<u8>, PairInfo>,
     prices: HashMap<String, Decimal>,
+    compounders: HashMap<String, Vec<AssetInfo>>,
 }
 
 impl WasmMockQuerier {
@@ -36,6 +38,7 @@ impl WasmMockQuerier {
             raw: HashMap::new(),
             pairs: HashMap::new(),
             prices: HashMap::new(),
+            compounders: HashMap::new(),
         }
     }
 
@@ -56,6 +59,10 @@ impl WasmMockQuerier {
     }
 
 
+    pub fn set_compounder(&mut self, compound_proxy: String, asset_infos: Vec<AssetInfo>) {
+        self.compounders.insert(compound_proxy, asset_infos);
+    }
+
     pub fn set_price(&mut self, pair: String, price: Decimal) {
         self.prices.insert(pair, price);
     }
@@ -132,6 +139,20 @@ impl WasmMockQuerier {
                     .ok_or_else(|| StdError::generic_err("No pair info"))?;
                 to_binary(pair_info)
              },
+            MockQueryMsg::Pool {} => {
+                let pair_info = self.pairs.values()
+                    .find(|pair| pair.contract_addr.as_str() == contract_addr)
+                    .ok_or_else(|| StdError::generic_err("No pair info"))?;
+                to_binary(&PoolResponse {
+                    assets: pair_info.asset_infos.iter()
+                        .map(|info| Asset {
+                            info: info.clone(),
+                            amount: self.get_balance(info.to_string(), contract_addr.clone()),
+                        })
+                        .collect(),
+                    total_share: Uint128::zero(),
+                })
+            },
             MockQueryMsg::Simulation { offer_asset } => {
                 let price = *self.get_price(contract_addr)
                     .ok_or_else(|| StdError::generic_err("No price"))?;
@@ -141,6 +162,20 @@ impl WasmMockQuerier {
                     commission_amount: Default::default()
                 })
             },
+            MockQueryMsg::CompoundSimulation { rewards } => {
+                let asset_infos = self.compounders.get(contract_addr)
+                    .ok_or_else(|| StdError::generic_err("No compound proxy"))?;
+                if rewards.iter().any(|reward| !asset_infos.contains(&reward.info)) {
+                    return Err(StdError::generic_err("Invalid reward"));
+                }
+                to_binary(&CompoundSimulationResponse {
+                    lp_amount: Uint128::zero(),
+                    swap_asset_a_amount: Uint128::zero(),
+                    swap_asset_b_amount: Uint128::zero(),
+                    return_a_amount: Uint128::zero(),
+                    return_b_amount: Uint128::zero(),
+                })
+            },
         }
     }
 }
@@ -162,9 +197,13 @@ enum MockQueryMsg {
     Pair {
         asset_infos: [AssetInfo; 2],
     },
+    Pool {},
     Simulation {
         offer_asset: Asset,
     },
+    CompoundSimulation {
+        rewards: Vec<Asset>,
+    },
 }
 
 impl Querier for WasmMockQuerier {
@@ -1,8 +1,18 @@
-use astroport::{asset::AssetInfo, common::OwnershipProposal};
+use astroport::{asset::{Asset, AssetInfo}, common::OwnershipProposal};
 use cosmwasm_std::{Addr};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use spectrum::adapters::router::{Router, RouterType};
+use spectrum::compound_proxy::Compounder;
+use spectrum::fees_collector::{AssetConfig, Distribution, IbcTarget};
+
+/// The default number of seconds before an IBC transfer times out
+pub const DEFAULT_IBC_TIMEOUT: u64 = 3600;
+
+pub fn default_ibc_timeout() -> u64 {
+    DEFAULT_IBC_TIMEOUT
+}
 
 /// This structure stores the main parameter for the fees collector contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -17,13 +27,142 @@ pub struct Config {
     pub target_list: Vec<(Addr, u64)>,
     /// The stablecoin token address
     pub stablecoin: AssetInfo,
+    /// The share of collected stablecoin paid to the Collect caller, in basis points
+    #[serde(default)] pub collect_incentive_bps: u64,
+    /// The minimum number of seconds between two distributions
+    #[serde(default)] pub distribution_interval: u64,
+    /// The share of collected stablecoin burned on distribution, in basis points
+    #[serde(default)] pub burn_bps: u64,
+    /// The list of remote beneficiaries paid over IBC
+    #[serde(default)] pub ibc_target_list: Vec<IbcTarget>,
+    /// The number of seconds before an IBC transfer times out and is refunded
+    #[serde(default = "default_ibc_timeout")] pub ibc_timeout: u64,
+    /// The list of staking contract and weight to receive fees through their deposit hook
+    #[serde(default)] pub staking_target_list: Vec<(Addr, u64)>,
+    /// The generator positions to claim rewards from on Collect
+    #[serde(default)] pub generator_rewards: Option<GeneratorRewardsConfig>,
+    /// The router used to swap fee tokens instead of the factory pairs
+    #[serde(default)] pub swap_router: Option<SwapRouterConfig>,
+    /// The max share of the pool reserve of a fee token swapped per call, in basis points, 0 is unlimited
+    #[serde(default)] pub max_swap_portion_bps: u64,
+    /// The number of seconds a config change is proposed before it can be applied
+    #[serde(default)] pub config_delay: u64,
+    /// The address allowed to pause Collect
+    #[serde(default)] pub pauser: Option<Addr>,
+    /// The list of beneficiaries whose share is compounded to protocol-owned LP
+    #[serde(default)] pub lp_target_list: Vec<LpTargetConfig>,
+}
+
+/// This structure stores a config change waiting for the config delay.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigProposal {
+    /// The proposed config
+    pub config: Config,
+    /// The bridge, asset config and operator changes applied after the config
+    #[serde(default)]
+    pub changes: Vec<ConfigChange>,
+    /// The block time in seconds after which the config can be applied
+    pub apply_after: u64,
+}
+
+/// This enum describes a change stored outside the config, proposed with the config delay.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChange {
+    /// Adds or removes bridge tokens
+    UpdateBridges {
+        add: Vec<(AssetInfo, AssetInfo)>,
+        remove: Vec<AssetInfo>,
+    },
+    /// Adds or removes swap settings for fee tokens
+    UpdateAssetConfigs {
+        add: Vec<AssetConfig>,
+        remove: Vec<AssetInfo>,
+    },
+    /// Adds an operator
+    AddOperator(Addr),
+    /// Removes an operator
+    RemoveOperator(Addr),
+}
+
+/// This structure stores the router that executes fee swaps along their bridge route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapRouterConfig {
+    /// The router contract
+    pub router: Router,
+    /// The type of router
+    pub router_type: RouterType,
+}
+
+/// This structure stores a beneficiary whose share is compounded to LP by a compound proxy.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpTargetConfig {
+    /// The compound proxy of the pair
+    pub compound_proxy: Compounder,
+    /// The receiver of the LP token
+    pub receiver: Addr,
+    /// The weight to receive fees
+    pub weight: u64,
+}
+
+/// This structure stores the generator positions whose rewards are claimed and swapped on Collect.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GeneratorRewardsConfig {
+    /// The generator contract address
+    pub generator: Addr,
+    /// The ASTRO token address
+    pub astro_token: Addr,
+    /// The LP tokens staked in the generator by this contract
+    pub lp_tokens: Vec<Addr>,
 }
 
 /// Stores the contract configuration at the given key
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the config change waiting for the config delay
+pub const CONFIG_PROPOSAL: Item<ConfigProposal> = Item::new("config_proposal");
+
+/// Stores the block time in seconds of the last distribution
+pub const LAST_DISTRIBUTE: Item<u64> = Item::new("last_distribute");
+
 /// Stores bridge tokens used to swap fee tokens to stablecoin
 pub const BRIDGES: Map<String, AssetInfo> = Map::new("bridges");
 
+/// Stores the swap settings of fee tokens
+pub const ASSET_CONFIGS: Map<String, AssetConfig> = Map::new("asset_configs");
+
+/// Stores the asset each beneficiary is paid in, stablecoin if not set
+pub const PAYOUT_ASSETS: Map<&Addr, AssetInfo> = Map::new("payout_assets");
+
+/// This structure stores the fee token swapped by a swap submessage.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapReply {
+    /// The swapped fee token and amount
+    pub asset: Asset,
+    /// Whether the swap is of a fee token collected by Collect, not of a bridge asset
+    pub collected: bool,
+}
+
+/// Stores the fee token swapped by each swap submessage, keyed by reply id
+pub const SWAP_REPLIES: Map<u64, SwapReply> = Map::new("swap_replies");
+
+/// Stores the id of the last distribution
+pub const DISTRIBUTION_COUNT: Item<u64> = Item::new("distribution_count");
+
+/// Stores the past distributions, keyed by id
+pub const DISTRIBUTIONS: Map<u64, Distribution> = Map::new("distributions");
+
+/// Stores the lifetime amounts distributed to each beneficiary
+pub const BENEFICIARY_TOTALS: Map<&str, Vec<Asset>> = Map::new("beneficiary_totals");
+
+/// Stores the lifetime amounts of each fee token swapped by Collect
+pub const SOURCE_TOTALS: Map<String, Asset> = Map::new("source_totals");
+
+/// Stores the addresses allowed to call Collect and DistributeFees
+pub const OPERATORS: Map<&Addr, bool> = Map::new("operators");
+
+/// Stores whether a collect is waiting for its DistributeFees callback
+pub const COLLECT_IN_PROGRESS: Item<bool> = Item::new("collect_in_progress");
+
 /// Stores the latest proposal to change contract ownership
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -1,20 +1,26 @@
-use astroport::asset::{AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::pair::{
-    Cw20HookMsg as AstroportPairCw20HookMsg,
+    Cw20HookMsg as AstroportPairCw20HookMsg, ExecuteMsg as AstroportPairExecuteMsg,
 };
 use astroport::factory::PairType;
+use astroport::generator::ExecuteMsg as GeneratorExecuteMsg;
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError, Timestamp,
+    attr, from_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Event, IbcMsg, IbcTimeout, OwnedDeps, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgResult, Timestamp,
     Uint128, WasmMsg, to_binary,
 };
-use cw20::Cw20ExecuteMsg;
-use spectrum::fees_collector::{AssetWithLimit, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration};
+use spectrum::compound_proxy::ExecuteMsg as CompoundProxyExecuteMsg;
+use spectrum::adapters::router::{ExecuteMsg as RouterExecuteMsg, RouterType, SwapOperation};
+use spectrum::fees_collector::{
+    AssetCollectSimulation, AssetConfig, AssetWithLimit, BeneficiaryTotal, CollectSimulationResponse, Cw20HookMsg, Distribution, ExecuteMsg, GeneratorRewards, IbcTarget, InstantiateMsg, LpTarget, QueryMsg,
+    SimulateCollectResponse, StakingHookMsg, SwapRouter, UnwrapConfig,
+};
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, query, reply};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, ConfigChange, ConfigProposal, CONFIG};
 
 const OWNER: &str = "owner";
 const OPERATOR_1: &str = "operator_1";
@@ -27,6 +33,8 @@ const FACTORY_2: &str = "factory_2";
 const TOKEN_1: &str = "token_1";
 const TOKEN_2: &str = "token_2";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const AMP_LUNA: &str = "amp_luna";
+const HUB: &str = "hub";
 
 #[test]
 fn test() -> Result<(), ContractError> {
@@ -37,6 +45,15 @@ fn test() -> Result<(), ContractError> {
     bridges(&mut deps)?;
     collect(&mut deps)?;
     distribute_fees(&mut deps)?;
+    distribution_totals(&mut deps)?;
+    swap_router(&mut deps)?;
+    config_delay(&mut deps)?;
+    pause(&mut deps)?;
+    rescue(&mut deps)?;
+    unwrap(&mut deps)?;
+    deposit(&mut deps)?;
+    payout_assets(&mut deps)?;
+    lp_targets(&mut deps)?;
 
     Ok(())
 }
@@ -63,6 +80,18 @@ fn create(
             denom: IBC_TOKEN.to_string(),
         },
         target_list: vec![(USER_2.to_string(), 2), (USER_3.to_string(), 3)],
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg);
     assert!(res.is_ok());
@@ -75,6 +104,18 @@ fn create(
             operator: Addr::unchecked(OPERATOR_1),
             factory_contract: Addr::unchecked(FACTORY_1),
             target_list: vec![(Addr::unchecked(USER_2), 2), (Addr::unchecked(USER_3), 3)],
+            collect_incentive_bps: 0,
+            distribution_interval: 0,
+            burn_bps: 0,
+            ibc_target_list: vec![],
+            ibc_timeout: 3600,
+            staking_target_list: vec![],
+            generator_rewards: None,
+            swap_router: None,
+            max_swap_portion_bps: 0,
+            config_delay: 0,
+            pauser: None,
+            lp_target_list: vec![],
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
@@ -94,6 +135,19 @@ fn config(
         operator: Some(OPERATOR_2.to_string()),
         factory_contract: None,
         target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -106,14 +160,166 @@ fn config(
         operator: None,
         factory_contract: Some(FACTORY_2.to_string()),
         target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
 
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![]),
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid target list: target list is empty");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_1.to_string(), 1), (USER_2.to_string(), 0)]),
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid target list: zero weight for user_2");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: Some(vec![(USER_1.to_string(), 1), (USER_1.to_string(), 2)]),
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid target list: duplicated address user_1");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: Some(101),
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Collect incentive must be at most 100 bps");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: Some(0),
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "IBC timeout must be greater than 0");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: Some(10001),
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Burn share must be at most 10000 bps");
+
     let msg = ExecuteMsg::UpdateConfig {
         operator: None,
         factory_contract: None,
         target_list: Some(vec![(USER_1.to_string(), 1)]),
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -127,6 +333,18 @@ fn config(
             operator: Addr::unchecked(OPERATOR_2),
             factory_contract: Addr::unchecked(FACTORY_2),
             target_list: vec![(Addr::unchecked(USER_1), 1)],
+            collect_incentive_bps: 0,
+            distribution_interval: 0,
+            burn_bps: 0,
+            ibc_target_list: vec![],
+            ibc_timeout: 3600,
+            staking_target_list: vec![],
+            generator_rewards: None,
+            swap_router: None,
+            max_swap_portion_bps: 0,
+            config_delay: 0,
+            pauser: None,
+            lp_target_list: vec![],
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
@@ -137,6 +355,19 @@ fn config(
         operator: Some(OPERATOR_1.to_string()),
         factory_contract: Some(FACTORY_1.to_string()),
         target_list: Some(vec![(USER_2.to_string(), 2), (USER_3.to_string(), 3)]),
+        collect_incentive_bps: Some(50),
+        distribution_interval: Some(86400),
+        burn_bps: Some(1000),
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -150,6 +381,18 @@ fn config(
             operator: Addr::unchecked(OPERATOR_1),
             factory_contract: Addr::unchecked(FACTORY_1),
             target_list: vec![(Addr::unchecked(USER_2), 2), (Addr::unchecked(USER_3), 3)],
+            collect_incentive_bps: 50,
+            distribution_interval: 86400,
+            burn_bps: 1000,
+            ibc_target_list: vec![],
+            ibc_timeout: 3600,
+            staking_target_list: vec![],
+            generator_rewards: None,
+            swap_router: None,
+            max_swap_portion_bps: 0,
+            config_delay: 0,
+            pauser: None,
+            lp_target_list: vec![],
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
@@ -334,9 +577,16 @@ fn bridges(
 
     // query bridges
     let bridges: Vec<(String, String)> =
-        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Bridges {})?)?;
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Bridges { start_after: None, limit: None })?)?;
     assert_eq!(vec![(TOKEN_1.to_string(), TOKEN_2.to_string())], bridges);
 
+    let bridges: Vec<(String, String)> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Bridges { start_after: Some(TOKEN_1.to_string()), limit: None },
+    )?)?;
+    assert!(bridges.is_empty());
+
     let msg = ExecuteMsg::UpdateBridges {
         add: None,
         remove: Some(vec![AssetInfo::Token {
@@ -349,7 +599,7 @@ fn bridges(
 
     // query bridges
     let bridges: Vec<(String, String)> =
-        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Bridges {})?)?;
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Bridges { start_after: None, limit: None })?)?;
     assert!(bridges.is_empty());
 
     Ok(())
@@ -389,11 +639,21 @@ fn collect(
     let info = mock_info(USER_1, &[]);
 
     // unauthorized check
-    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
 
-    // distribute fee only if no balance
-    let info = mock_info(OPERATOR_1, &[]);
+    // add operator
+    let add_operator = ExecuteMsg::AddOperator {
+        operator: USER_1.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), add_operator.clone());
+    assert_error(res, "Unauthorized");
+
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), add_operator)?;
+    let operators: Vec<String> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Operators {})?)?;
+    assert_eq!(operators, vec![USER_1.to_string()]);
+
+    // operator collects, the caller is paid the collect incentive
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
     assert_eq!(
         res.messages
@@ -404,7 +664,7 @@ fn collect(
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 funds: vec![],
-                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None })?,
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None, keeper: Some(USER_1.to_string()), prev_balance: Some(Uint128::zero()) })?,
             }),
         ]
     );
@@ -418,6 +678,8 @@ fn collect(
 
     // collect success
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.messages[0].id, 0);
+    assert_eq!(res.messages[0].reply_on, ReplyOn::Error);
     assert_eq!(
         res.messages
             .into_iter()
@@ -432,7 +694,7 @@ fn collect(
                     amount: Uint128::new(1000000u128),
                     msg: to_binary(&AstroportPairCw20HookMsg::Swap {
                         ask_asset_info: None,
-                        belief_price: Some(Decimal::MAX),
+                        belief_price: None,
                         max_spread: Some(Decimal::percent(50)),
                         to: None,
                     })?
@@ -446,11 +708,25 @@ fn collect(
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 funds: vec![],
-                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None })?,
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None, keeper: Some(USER_1.to_string()), prev_balance: Some(Uint128::zero()) })?,
             }),
         ]
     );
 
+    // a failed swap is reported without reverting the collect
+    let res = reply(deps.as_mut(), env.clone(), Reply {
+        id: 0,
+        result: SubMsgResult::Err("pool is paused".to_string()),
+    })?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "swap_failed"),
+            attr("asset", TOKEN_1),
+            attr("error", "pool is paused"),
+        ]
+    );
+
     deps.querier.set_price("token1token2".to_string(), Decimal::percent(200u64));
     deps.querier.set_price("token2ibc".to_string(), Decimal::percent(25u64));
 
@@ -470,6 +746,39 @@ fn collect(
         }
     );
 
+    let msg = QueryMsg::SimulateCollect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            limit: None,
+        }],
+    };
+    let res: SimulateCollectResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        res,
+        SimulateCollectResponse {
+            simulations: vec![AssetCollectSimulation {
+                info: AssetInfo::Token {
+                    contract_addr: Addr::unchecked(TOKEN_1),
+                },
+                amount: Uint128::from(1000000u128),
+                route: vec![
+                    AssetInfo::Token {
+                        contract_addr: Addr::unchecked(TOKEN_1),
+                    },
+                    AssetInfo::Token {
+                        contract_addr: Addr::unchecked(TOKEN_2),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: IBC_TOKEN.to_string(),
+                    },
+                ],
+                return_amount: Uint128::from(500000u128),
+            }],
+        }
+    );
+
     // set balance
     deps.querier.set_balance(
         TOKEN_2.to_string(),
@@ -477,6 +786,72 @@ fn collect(
         Uint128::from(2000000u128),
     );
 
+    // tighter max spread for token_2
+    let asset_config = AssetConfig {
+        info: AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_2),
+        },
+        max_spread: Some(Decimal::percent(1)),
+        min_amount: Some(Uint128::from(1000u128)),
+        unwrap: None,
+        swap_threshold: None,
+    };
+    let msg = ExecuteMsg::UpdateAssetConfigs {
+        add: Some(vec![asset_config.clone()]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Unauthorized");
+
+    let msg = ExecuteMsg::UpdateAssetConfigs {
+        add: Some(vec![AssetConfig {
+            max_spread: Some(Decimal::percent(51)),
+            ..asset_config.clone()
+        }]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OPERATOR_1, &[]), msg);
+    assert_error(res, "Incorrect max spread");
+
+    let msg = ExecuteMsg::UpdateAssetConfigs {
+        add: Some(vec![asset_config.clone()]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OPERATOR_1, &[]), msg)?;
+
+    let res: Vec<AssetConfig> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Assets { start_after: None, limit: None })?)?;
+    assert_eq!(res, vec![asset_config]);
+
+    // skip dust below min amount
+    deps.querier.set_balance(
+        TOKEN_2.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(999u128),
+    );
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            limit: None,
+        }],
+        minimum_receive: None
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(res.messages.len(), 1);
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "collect"),
+            attr("skipped_asset", TOKEN_2),
+        ]
+    );
+    deps.querier.set_balance(
+        TOKEN_2.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(2000000u128),
+    );
+
     let msg = ExecuteMsg::Collect {
         assets: vec![AssetWithLimit {
             info: AssetInfo::Token {
@@ -488,7 +863,7 @@ fn collect(
     };
 
     // collect success
-    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
     assert_eq!(
         res.messages
             .into_iter()
@@ -503,8 +878,8 @@ fn collect(
                     amount: Uint128::new(1500000u128),
                     msg: to_binary(&AstroportPairCw20HookMsg::Swap {
                         ask_asset_info: None,
-                        belief_price: Some(Decimal::MAX),
-                        max_spread: Some(Decimal::percent(50)),
+                        belief_price: None,
+                        max_spread: Some(Decimal::percent(1)),
                         to: None,
                     })?
                 })?,
@@ -512,11 +887,229 @@ fn collect(
             CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: env.contract.address.to_string(),
                 funds: vec![],
-                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None })?,
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None, keeper: Some(USER_1.to_string()), prev_balance: Some(Uint128::zero()) })?,
+            }),
+        ]
+    );
+
+    // swap only a portion of the pool reserve per call
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: Some(10001),
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg);
+    assert_error(res, "Max swap portion must be at most 10000 bps");
+
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: Some(1000),
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+
+    deps.querier.set_balance(
+        TOKEN_2.to_string(),
+        "token2ibc".to_string(),
+        Uint128::from(10000000u128),
+    );
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            limit: None,
+        }],
+        minimum_receive: None
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_2.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "token2ibc".to_string(),
+                amount: Uint128::new(1000000u128),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: None,
+                })?
+            })?,
+        })
+    );
+
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: Some(0),
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+
+    // claim generator rewards and swap them after the claim
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: Some(GeneratorRewards {
+            generator: "generator".to_string(),
+            astro_token: "astro".to_string(),
+            lp_tokens: vec!["lp_token".to_string()],
+        }),
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+
+    // nothing pending, nothing to claim
+    let msg = ExecuteMsg::Collect {
+        assets: vec![],
+        minimum_receive: None
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(res.messages.len(), 1);
+
+    deps.querier.set_balance(
+        "generator".to_string(),
+        "astro".to_string(),
+        Uint128::from(1000u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "generator".to_string(),
+                funds: vec![],
+                msg: to_binary(&GeneratorExecuteMsg::ClaimRewards {
+                    lp_tokens: vec!["lp_token".to_string()],
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::SwapBridgeAssets {
+                    assets: vec![AssetInfo::Token { contract_addr: Addr::unchecked("astro") }],
+                    depth: 0,
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None, keeper: Some(USER_1.to_string()), prev_balance: Some(Uint128::zero()) })?,
+            }),
+        ]
+    );
+
+    deps.querier.set_balance(
+        "generator".to_string(),
+        "astro".to_string(),
+        Uint128::zero(),
+    );
+
+    // swap bridge assets is internal only
+    let msg = ExecuteMsg::SwapBridgeAssets {
+        assets: vec![AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) }],
+        depth: 0,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // second hop swaps the bridge token to stablecoin
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_2.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "token2ibc".to_string(),
+                    amount: Uint128::new(2000000u128),
+                    msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                        ask_asset_info: None,
+                        belief_price: None,
+                        max_spread: Some(Decimal::percent(1)),
+                        to: None,
+                    })?
+                })?,
             }),
         ]
     );
 
+    // stablecoin is never swapped again
+    let msg = ExecuteMsg::SwapBridgeAssets {
+        assets: vec![AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() }],
+        depth: 1,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert!(res.messages.is_empty());
+
+    // max depth
+    let msg = ExecuteMsg::SwapBridgeAssets {
+        assets: vec![AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) }],
+        depth: 3,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Max bridge length of 3 was reached");
 
     Ok(())
 }
@@ -533,9 +1126,13 @@ fn distribute_fees(
         Uint128::from(1000000u128),
     );
 
-    let msg = ExecuteMsg::DistributeFees { minimum_receive: Some(Uint128::from(2000000u128)) };
+    let msg = ExecuteMsg::DistributeFees {
+        minimum_receive: Some(Uint128::from(2000000u128)),
+        keeper: Some(USER_1.to_string()),
+        prev_balance: Some(Uint128::zero()),
+    };
 
-    let info = mock_info(USER_1, &[]);
+    let info = mock_info(USER_2, &[]);
 
     // unauthorized check
     let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
@@ -546,30 +1143,1194 @@ fn distribute_fees(
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Assertion failed; minimum receive amount: 2000000, actual amount: 1000000");
 
-    let msg = ExecuteMsg::DistributeFees { minimum_receive: None };
-    let res = execute(deps.as_mut(), env.clone(), info, msg.clone())?;
+    // the keeper is only paid from the stablecoin received by its collect
+    let msg = ExecuteMsg::DistributeFees {
+        minimum_receive: None,
+        keeper: Some(USER_1.to_string()),
+        prev_balance: Some(Uint128::from(600000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.events,
+        vec![Event::new("spectrum/compound")
+            .add_attribute("stage", "distribute")
+            .add_attribute("fee_asset", IBC_TOKEN)
+            .add_attribute("fee_amount", "1000000")]
+    );
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(2000u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(99800u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_2.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(359280u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_3.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(538920u128),
+                }]
+            }),
+        ]
+    );
+
+    // distribution interval has not elapsed
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None, keeper: None, prev_balance: None };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert!(res.messages.is_empty());
+    assert!(res.events.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "distribute_fees"),
+            attr("next_distribute", (env.block.time.seconds() + 86400).to_string()),
+        ]
+    );
+
+    // no incentive unless distributed by a collect
+    let mut env = env;
+    env.block.time = env.block.time.plus_seconds(86400);
+    let keeper_msg = ExecuteMsg::DistributeFees {
+        minimum_receive: None,
+        keeper: Some(USER_1.to_string()),
+        prev_balance: Some(Uint128::zero()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OPERATOR_1, &[]), keeper_msg)?;
     assert_eq!(
         res.messages
             .into_iter()
             .map(|it| it.msg)
             .collect::<Vec<CosmosMsg>>(),
         [
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(100000u128),
+                }]
+            }),
             CosmosMsg::Bank(BankMsg::Send {
                 to_address: USER_2.to_string(),
                 amount: vec![Coin {
                     denom: IBC_TOKEN.to_string(),
-                    amount: Uint128::from(400000u128),
+                    amount: Uint128::from(360000u128),
                 }]
             }),
             CosmosMsg::Bank(BankMsg::Send {
                 to_address: USER_3.to_string(),
                 amount: vec![Coin {
                     denom: IBC_TOKEN.to_string(),
-                    amount: Uint128::from(600000u128),
+                    amount: Uint128::from(540000u128),
                 }]
             }),
         ]
     );
 
+    // remote beneficiary
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: Some(vec![IbcTarget {
+            channel_id: "channel-0".to_string(),
+            receiver: "remote_treasury".to_string(),
+            weight: 0,
+        }]),
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg);
+    assert_error(res, "Invalid target list: zero weight for remote_treasury");
+
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: Some(vec![IbcTarget {
+            channel_id: "channel-0".to_string(),
+            receiver: "remote_treasury".to_string(),
+            weight: 5,
+        }]),
+        ibc_timeout: Some(600),
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+
+    env.block.time = env.block.time.plus_seconds(86400);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(100000u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_2.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(180000u128),
+                }]
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_3.to_string(),
+                amount: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(270000u128),
+                }]
+            }),
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-0".to_string(),
+                to_address: "remote_treasury".to_string(),
+                amount: Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(450000u128),
+                },
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(600)),
+            }),
+        ]
+    );
+
+    // switching to a cw20 stablecoin is not allowed with remote beneficiaries
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: Some(token_2.clone()),
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg);
+    assert_error(res, "Invalid target list: IBC transfer requires a native stablecoin");
+
+    // switch stablecoin, the old stablecoin balance is swapped into the new one
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: Some(vec![]),
+        ibc_timeout: None,
+        stablecoin: Some(token_2.clone()),
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "token2ibc".to_string(),
+                funds: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(1000000u128),
+                }],
+                msg: to_binary(&AstroportPairExecuteMsg::Swap {
+                    offer_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: IBC_TOKEN.to_string(),
+                        },
+                        amount: Uint128::from(1000000u128),
+                    },
+                    ask_asset_info: None,
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(50)),
+                    to: None,
+                })?,
+            }),
+        ]
+    );
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.stablecoin, token_2);
+
+    // fund a staking contract through its deposit hook
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: Some(vec![("staking".to_string(), 5)]),
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+
+    env.block.time = env.block.time.plus_seconds(86400);
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None, keeper: None, prev_balance: None };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.messages.last().map(|it| it.msg.clone()),
+        Some(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_2.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "staking".to_string(),
+                amount: Uint128::from(900000u128),
+                msg: to_binary(&StakingHookMsg::Deposit {})?,
+            })?,
+        }))
+    );
+
+    // remove operator
+    let msg = ExecuteMsg::RemoveOperator {
+        operator: USER_1.to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg)?;
+    let operators: Vec<String> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Operators {})?)?;
+    assert!(operators.is_empty());
+
+    let msg = ExecuteMsg::DistributeFees { minimum_receive: None, keeper: None, prev_balance: None };
+    let res = execute(deps.as_mut(), env, mock_info(USER_1, &[]), msg);
+    assert_error(res, "Unauthorized");
+
+    Ok(())
+}
+
+fn distribution_totals(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let ibc_token = AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() };
+    let token_2 = AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) };
+
+    // the four distributions of distribute_fees, the last one in token_2
+    let history: Vec<Distribution> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::DistributionHistory { start_after: Some(2), limit: Some(1) },
+    )?)?;
+    assert_eq!(
+        history,
+        vec![Distribution {
+            id: 3,
+            time: env.block.time.seconds() + 172800,
+            asset: ibc_token.clone(),
+            amount: Uint128::from(1000000u128),
+            transfers: vec![
+                ("burn".to_string(), Uint128::from(100000u128)),
+                (USER_2.to_string(), Uint128::from(180000u128)),
+                (USER_3.to_string(), Uint128::from(270000u128)),
+                ("remote_treasury".to_string(), Uint128::from(450000u128)),
+            ],
+        }]
+    );
+    let history: Vec<Distribution> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::DistributionHistory { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(history.iter().map(|it| it.id).collect::<Vec<u64>>(), vec![1, 2, 3, 4]);
+    assert_eq!(history[3].asset, token_2);
+    assert_eq!(history[3].transfers.last(), Some(&("staking".to_string(), Uint128::from(900000u128))));
+
+    // totals are kept per beneficiary and per distributed asset
+    let totals: Vec<BeneficiaryTotal> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BeneficiaryTotals { start_after: Some("staking".to_string()), limit: Some(2) },
+    )?)?;
+    assert_eq!(
+        totals,
+        vec![
+            BeneficiaryTotal {
+                beneficiary: USER_1.to_string(),
+                amounts: vec![ibc_token.with_balance(2000u128)],
+            },
+            BeneficiaryTotal {
+                beneficiary: USER_2.to_string(),
+                amounts: vec![ibc_token.with_balance(899280u128), token_2.with_balance(360000u128)],
+            },
+        ]
+    );
+    let totals: Vec<BeneficiaryTotal> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::BeneficiaryTotals { start_after: None, limit: Some(1) },
+    )?)?;
+    assert_eq!(
+        totals,
+        vec![BeneficiaryTotal {
+            beneficiary: "burn".to_string(),
+            amounts: vec![ibc_token.with_balance(299800u128), token_2.with_balance(200000u128)],
+        }]
+    );
+
+    // the failed token_1 swap of collect is not counted, the collect capped by the max swap portion
+    // counts the 1000000 swapped out of its 2000000 balance
+    let sources: Vec<Asset> = from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::SourceTotals { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(
+        sources,
+        vec![
+            AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) }.with_balance(0u128),
+            token_2.with_balance(2500000u128),
+        ]
+    );
+
+    Ok(())
+}
+
+fn swap_router(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let info = mock_info(OWNER, &[]);
+
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: Some(SwapRouter {
+            router: "router".to_string(),
+            router_type: RouterType::AstroSwap,
+        }),
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), update_msg)?;
+
+    // fee tokens are swapped along their route by the router
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000u128),
+    );
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::NativeToken {
+                denom: IBC_TOKEN.to_string(),
+            },
+            limit: None,
+        }],
+        minimum_receive: None
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "router".to_string(),
+                funds: vec![Coin {
+                    denom: IBC_TOKEN.to_string(),
+                    amount: Uint128::from(1000u128),
+                }],
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() },
+                        ask_asset_info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) },
+                    }],
+                    minimum_receive: None,
+                    to: None,
+                    max_spread: Some(Decimal::percent(50)),
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DistributeFees { minimum_receive: None, keeper: Some(OWNER.to_string()), prev_balance: Some(Uint128::new(2000000)) })?,
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+fn config_delay(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    let info = mock_info(OWNER, &[]);
+
+    // the delay is applied instantly while no delay is set
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: Some(3600),
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), update_msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "update_config")]);
+
+    // config changes are proposed
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: Some(100),
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), update_msg.clone());
+    assert_error(res, "Unauthorized");
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), update_msg)?;
+    let apply_after = env.block.time.seconds() + 3600;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_config"),
+            attr("apply_after", apply_after.to_string()),
+        ]
+    );
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.burn_bps, 1000);
+    let proposal: Option<ConfigProposal> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ConfigProposal {})?)?;
+    assert_eq!(
+        proposal,
+        Some(ConfigProposal {
+            config: Config {
+                burn_bps: 100,
+                ..config
+            },
+            changes: vec![],
+            apply_after,
+        })
+    );
+
+    // apply after the delay
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ApplyConfig {});
+    assert_error(res, &format!("Config proposal cannot be applied before {}", apply_after));
+
+    env.block.time = env.block.time.plus_seconds(3600);
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), ExecuteMsg::ApplyConfig {});
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ApplyConfig {})?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.burn_bps, 100);
+    let proposal: Option<ConfigProposal> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ConfigProposal {})?)?;
+    assert_eq!(proposal, None);
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ApplyConfig {});
+    assert_error(res, "No config proposal");
+
+    // drop proposal
+    let update_msg = ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: Some(0),
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), update_msg)?;
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::DropConfigProposal {})?;
+    let proposal: Option<ConfigProposal> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ConfigProposal {})?)?;
+    assert_eq!(proposal, None);
+
+    // operator and bridge changes are proposed with the current config
+    let msg = ExecuteMsg::AddOperator {
+        operator: USER_1.to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    let apply_after = env.block.time.seconds() + 3600;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "propose_config"),
+            attr("apply_after", apply_after.to_string()),
+        ]
+    );
+    let bridge = (
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_1),
+        },
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked(TOKEN_2),
+        },
+    );
+    let msg = ExecuteMsg::UpdateBridges {
+        add: Some(vec![bridge.clone()]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(config.operator.as_str(), &[]), msg)?;
+
+    let operators: Vec<String> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Operators {})?)?;
+    assert!(operators.is_empty());
+    let proposal: Option<ConfigProposal> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ConfigProposal {})?)?;
+    assert_eq!(
+        proposal,
+        Some(ConfigProposal {
+            config: config.clone(),
+            changes: vec![
+                ConfigChange::AddOperator(Addr::unchecked(USER_1)),
+                ConfigChange::UpdateBridges {
+                    add: vec![bridge],
+                    remove: vec![],
+                },
+            ],
+            apply_after,
+        })
+    );
+
+    env.block.time = env.block.time.plus_seconds(3600);
+    execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ApplyConfig {})?;
+    let operators: Vec<String> = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Operators {})?)?;
+    assert_eq!(operators, vec![USER_1.to_string()]);
+
+    let msg = ExecuteMsg::RemoveOperator {
+        operator: USER_1.to_string(),
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    env.block.time = env.block.time.plus_seconds(3600);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ApplyConfig {})?;
+    let operators: Vec<String> = from_binary(&query(deps.as_ref(), env, QueryMsg::Operators {})?)?;
+    assert!(operators.is_empty());
+
+    Ok(())
+}
+
+/// Applies the pending config proposal once the config delay has elapsed.
+fn apply_config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<Response, ContractError> {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(3600);
+    execute(deps.as_mut(), env, mock_info(OWNER, &[]), ExecuteMsg::ApplyConfig {})
+}
+
+fn pause(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let info = mock_info(OWNER, &[]);
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), ExecuteMsg::Pause {});
+    assert_error(res, "Unauthorized");
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::Pause {})?;
+    assert_eq!(res.attributes, vec![attr("action", "pause")]);
+    let paused: bool = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Paused {})?)?;
+    assert!(paused);
+
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            limit: None,
+        }],
+        minimum_receive: None
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Contract is paused");
+
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::Unpause {})?;
+    assert_eq!(res.attributes, vec![attr("action", "unpause")]);
+    let paused: bool = from_binary(&query(deps.as_ref(), env, QueryMsg::Paused {})?)?;
+    assert!(!paused);
+
+    Ok(())
+}
+
+fn rescue(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let info = mock_info(OWNER, &[]);
+
+    let msg = ExecuteMsg::Rescue {
+        asset: AssetInfo::NativeToken {
+            denom: "ibc/stranded".to_string(),
+        },
+        to: USER_2.to_string(),
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // the previous collect is still waiting for DistributeFees
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Cannot rescue while a collect is in progress");
+
+    let distribute = ExecuteMsg::DistributeFees {
+        minimum_receive: None,
+        keeper: None,
+        prev_balance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), distribute.clone())?;
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Nothing to rescue");
+
+    deps.querier.set_balance(
+        "ibc/stranded".to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(5000u128),
+    );
+
+    // balances are locked until the collect is distributed
+    let collect = ExecuteMsg::Collect {
+        assets: vec![],
+        minimum_receive: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), collect)?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Cannot rescue while a collect is in progress");
+
+    execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), distribute)?;
+
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_2.to_string(),
+            amount: vec![Coin {
+                denom: "ibc/stranded".to_string(),
+                amount: Uint128::from(5000u128),
+            }],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "rescue"),
+            attr("asset", "ibc/stranded"),
+            attr("amount", "5000"),
+            attr("to", USER_2),
+        ]
+    );
+
+    Ok(())
+}
+
+fn unwrap(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let env = mock_env();
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let amp_luna = AssetInfo::Token {
+        contract_addr: Addr::unchecked(AMP_LUNA),
+    };
+    let uluna = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+    let unwrap = UnwrapConfig {
+        hub: HUB.to_string(),
+        msg: Binary::from(br#"{"instant_unbond":{}}"#.to_vec()),
+        underlying: uluna.clone(),
+    };
+
+    let msg = ExecuteMsg::UpdateAssetConfigs {
+        add: Some(vec![AssetConfig {
+            info: amp_luna.clone(),
+            max_spread: None,
+            min_amount: None,
+            unwrap: Some(UnwrapConfig {
+                underlying: amp_luna.clone(),
+                ..unwrap.clone()
+            }),
+            swap_threshold: None,
+        }]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(config.operator.as_str(), &[]), msg);
+    assert_error(res, "Invalid unwrap of amp_luna");
+
+    let msg = ExecuteMsg::UpdateAssetConfigs {
+        add: Some(vec![AssetConfig {
+            info: amp_luna.clone(),
+            max_spread: None,
+            min_amount: None,
+            unwrap: Some(unwrap.clone()),
+            swap_threshold: None,
+        }]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(config.operator.as_str(), &[]), msg)?;
+    apply_config(deps)?;
+
+    // the fee token is redeemed through the hub, then the underlying is swapped as a bridge asset
+    deps.querier.set_balance(AMP_LUNA.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(1000u128));
+    let assets = vec![AssetWithLimit {
+        info: amp_luna.clone(),
+        limit: None,
+    }];
+    let msg = ExecuteMsg::Collect {
+        assets: assets.clone(),
+        minimum_receive: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(config.owner.as_str(), &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: AMP_LUNA.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: HUB.to_string(),
+                    amount: Uint128::from(1000u128),
+                    msg: unwrap.msg,
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::SwapBridgeAssets { assets: vec![uluna], depth: 0 })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DistributeFees {
+                    minimum_receive: None,
+                    keeper: Some(config.owner.to_string()),
+                    prev_balance: Some(Uint128::new(2000000)),
+                })?,
+            }),
+        ]
+    );
+
+    let res = query(deps.as_ref(), env, QueryMsg::SimulateCollect { assets });
+    assert_eq!(res, Err(StdError::generic_err("Cannot simulate the unwrap of amp_luna")));
+
+    Ok(())
+}
+
+fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let env = mock_env();
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let deposit_msg = |amount: u128| -> Result<ExecuteMsg, ContractError> {
+        Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "farm".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::Deposit {})?,
+        }))
+    };
+
+    // deposits are kept until collect without a swap threshold
+    deps.querier.set_balance(TOKEN_1.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(4000u128));
+    let res = execute(deps.as_mut(), env.clone(), mock_info(TOKEN_1, &[]), deposit_msg(4000)?)?;
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "deposit"),
+            attr("source", "farm"),
+            attr("asset", TOKEN_1),
+            attr("amount", "4000"),
+        ]
+    );
+
+    let msg = ExecuteMsg::UpdateAssetConfigs {
+        add: Some(vec![AssetConfig {
+            info: token_1.clone(),
+            max_spread: None,
+            min_amount: None,
+            unwrap: None,
+            swap_threshold: Some(Uint128::from(5000u128)),
+        }]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(config.operator.as_str(), &[]), msg)?;
+    apply_config(deps)?;
+
+    // below the threshold
+    let res = execute(deps.as_mut(), env.clone(), mock_info(TOKEN_1, &[]), deposit_msg(4000)?)?;
+    assert!(res.messages.is_empty());
+
+    // no swap while the previous collect is waiting for DistributeFees
+    deps.querier.set_balance(TOKEN_1.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(6000u128));
+    let res = execute(deps.as_mut(), env.clone(), mock_info(TOKEN_1, &[]), deposit_msg(2000)?)?;
+    assert!(res.messages.is_empty());
+
+    let distribute = ExecuteMsg::DistributeFees {
+        minimum_receive: None,
+        keeper: None,
+        prev_balance: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), distribute)?;
+
+    // the deposit reaching the threshold swaps the whole balance
+    let res = execute(deps.as_mut(), env, mock_info(TOKEN_1, &[]), deposit_msg(2000)?)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_error(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "router".to_string(),
+                    amount: Uint128::from(6000u128),
+                    msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                        operations: vec![SwapOperation::AstroSwap {
+                            offer_asset_info: token_1,
+                            ask_asset_info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) },
+                        }],
+                        minimum_receive: None,
+                        to: None,
+                        max_spread: Some(Decimal::percent(50)),
+                    })?,
+                })?,
+            }),
+            0,
+        )]
+    );
+
+    Ok(())
+}
+
+fn payout_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    let info = mock_info(OWNER, &[]);
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+
+    let msg = ExecuteMsg::UpdatePayoutAssets {
+        add: Some(vec![(USER_2.to_string(), token_1.clone())]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg);
+    assert_error(res, "Unauthorized");
+
+    let msg = ExecuteMsg::UpdatePayoutAssets {
+        add: Some(vec![(USER_1.to_string(), token_1.clone())]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "user_1 is not in the target list or staking target list");
+
+    let msg = ExecuteMsg::UpdatePayoutAssets {
+        add: Some(vec![(USER_2.to_string(), token_2.clone())]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid payout asset token_2");
+
+    let msg = ExecuteMsg::UpdatePayoutAssets {
+        add: Some(vec![
+            (USER_2.to_string(), token_1.clone()),
+            (USER_3.to_string(), token_1.clone()),
+            ("staking".to_string(), token_1.clone()),
+        ]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    let msg = ExecuteMsg::UpdatePayoutAssets {
+        add: None,
+        remove: Some(vec![USER_3.to_string()]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "update_payout_assets")]);
+
+    let payout_assets: Vec<(String, AssetInfo)> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PayoutAssets { start_after: None, limit: None },
+    )?)?;
+    assert_eq!(
+        payout_assets,
+        vec![("staking".to_string(), token_1.clone()), (USER_2.to_string(), token_1.clone())]
+    );
+
+    // shares with a payout asset are swapped from stablecoin before they are sent
+    env.block.time = env.block.time.plus_seconds(86400 * 4);
+    deps.querier.set_balance(TOKEN_2.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let distribute = ExecuteMsg::DistributeFees {
+        minimum_receive: None,
+        keeper: None,
+        prev_balance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), distribute)?;
+    let payout_swap_msg = |amount: u128, to: Option<&str>| -> Result<CosmosMsg, ContractError> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_2.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "router".to_string(),
+                amount: Uint128::from(amount),
+                msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::AstroSwap {
+                        offer_asset_info: token_2.clone(),
+                        ask_asset_info: token_1.clone(),
+                    }],
+                    minimum_receive: None,
+                    to: to.map(Addr::unchecked),
+                    max_spread: Some(Decimal::percent(50)),
+                })?,
+            })?,
+        }))
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_2.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Burn { amount: Uint128::from(100u128) })?,
+            }),
+            payout_swap_msg(1980, Some(USER_2))?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_2.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_3.to_string(),
+                    amount: Uint128::from(2970u128),
+                })?,
+            }),
+            payout_swap_msg(4950, None)?,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::DepositStakingPayout {
+                    staking_contract: "staking".to_string(),
+                    asset: token_1.clone(),
+                    prev_balance: Uint128::from(6000u128),
+                })?,
+            }),
+        ]
+    );
+
+    // the staking contract receives the payout asset through its deposit hook
+    let msg = ExecuteMsg::DepositStakingPayout {
+        staking_contract: "staking".to_string(),
+        asset: token_1,
+        prev_balance: Uint128::from(6000u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    deps.querier.set_balance(TOKEN_1.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_1.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "staking".to_string(),
+                amount: Uint128::from(4000u128),
+                msg: to_binary(&StakingHookMsg::Deposit {})?,
+            })?,
+        })]
+    );
+
+    Ok(())
+}
+
+fn lp_targets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    let info = mock_info(OWNER, &[]);
+    let update_msg = |weight: u64| ExecuteMsg::UpdateConfig {
+        operator: None,
+        factory_contract: None,
+        target_list: None,
+        collect_incentive_bps: None,
+        distribution_interval: None,
+        burn_bps: None,
+        ibc_target_list: None,
+        ibc_timeout: None,
+        stablecoin: None,
+        staking_target_list: None,
+        generator_rewards: None,
+        swap_router: None,
+        max_swap_portion_bps: None,
+        config_delay: None,
+        pauser: None,
+        lp_target_list: Some(vec![LpTarget {
+            compound_proxy: "compound_proxy".to_string(),
+            receiver: "treasury".to_string(),
+            weight,
+        }]),
+    };
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), update_msg(0));
+    assert_error(res, "Invalid target list: zero weight for treasury");
+
+    // the compound proxy must be able to compound the stablecoin
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), update_msg(10));
+    assert_error(res, "Invalid target list: compound_proxy cannot compound the stablecoin");
+
+    deps.querier.set_compounder(
+        "compound_proxy".to_string(),
+        vec![AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) }],
+    );
+    execute(deps.as_mut(), env.clone(), info.clone(), update_msg(10))?;
+    env.block.time = env.block.time.plus_seconds(86400 * 6);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ApplyConfig {})?;
+
+    // the LP share is compounded to protocol-owned LP sent to the receiver
+    deps.querier.set_balance(TOKEN_2.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(20000u128));
+    let distribute = ExecuteMsg::DistributeFees {
+        minimum_receive: None,
+        keeper: None,
+        prev_balance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), distribute)?;
+    assert_eq!(
+        res.messages[5..]
+            .iter()
+            .map(|it| it.msg.clone())
+            .collect::<Vec<CosmosMsg>>(),
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_2.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: "compound_proxy".to_string(),
+                    amount: Uint128::from(9900u128),
+                    expires: Some(Expiration::AtHeight(env.block.height + 1)),
+                })?,
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "compound_proxy".to_string(),
+                funds: vec![],
+                msg: to_binary(&CompoundProxyExecuteMsg::Compound {
+                    rewards: vec![Asset {
+                        info: AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) },
+                        amount: Uint128::from(9900u128),
+                    }],
+                    to: Some("treasury".to_string()),
+                    no_swap: None,
+                    slippage_tolerance: None,
+                    source: None,
+                })?,
+            }),
+        ]
+    );
+    assert_eq!(res.attributes[res.attributes.len() - 3..], [
+        attr("to", "treasury"),
+        attr("amount", "9900"),
+        attr("action", "distribute_fees"),
+    ]);
+
     Ok(())
 }
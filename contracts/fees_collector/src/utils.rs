@@ -1,10 +1,12 @@
 use crate::error::ContractError;
-use crate::state::{Config, BRIDGES};
-use astroport::asset::{Asset, AssetInfo, PairInfo};
-use astroport::querier::{query_pair_info, simulate};
-use cosmwasm_std::{to_binary, Deps, StdResult, Uint128, WasmMsg, CosmosMsg, Addr, QuerierWrapper, Decimal};
-use spectrum::adapters::pair::Pair;
-use spectrum::fees_collector::ExecuteMsg;
+use crate::state::{Config, SwapRouterConfig, ASSET_CONFIGS, BRIDGES};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::querier::query_pair_info;
+use cosmwasm_std::{to_binary, Coin, Deps, StdResult, Uint128, WasmMsg, CosmosMsg, Addr, QuerierWrapper, Decimal, Storage};
+use spectrum::adapters::pair::{Pair, PairAdapter};
+use cw20::Cw20ExecuteMsg;
+use spectrum::fees_collector::{ExecuteMsg, StakingHookMsg, UnwrapConfig};
+use spectrum::math::bps_amount;
 
 /// The default bridge depth for a fee token
 pub const BRIDGES_INITIAL_DEPTH: u64 = 0;
@@ -13,24 +15,167 @@ pub const BRIDGES_MAX_DEPTH: u64 = 2;
 /// Swap execution depth limit
 pub const BRIDGES_EXECUTION_MAX_DEPTH: u64 = 3;
 
+/// Maximum share of collected stablecoin paid to the Collect caller, in basis points
+pub const MAX_COLLECT_INCENTIVE_BPS: u64 = 100; // 1%
+
+/// Maximum share of collected stablecoin burned on distribution, in basis points
+pub const MAX_BURN_BPS: u64 = 10000; // 100%
+
+/// Maximum share of the pool reserve swapped per call, in basis points
+pub const MAX_SWAP_PORTION_BPS: u64 = 10000; // 100%
+
 /// Maximum spread percentage when swapping
-const MAX_SPREAD: u64 = 50; // 50%
+pub const MAX_SPREAD: u64 = 50; // 50%
+
+/// Returns the max spread configured for the fee token, or the global default
+pub fn get_max_spread(storage: &dyn Storage, asset: &AssetInfo) -> StdResult<Decimal> {
+    let asset_config = ASSET_CONFIGS.may_load(storage, asset.to_string())?;
+    Ok(asset_config
+        .and_then(|c| c.max_spread)
+        .unwrap_or_else(|| Decimal::percent(MAX_SPREAD)))
+}
+
+/// Returns the unwrap configured for the fee token
+pub fn get_unwrap(storage: &dyn Storage, asset: &AssetInfo) -> StdResult<Option<UnwrapConfig>> {
+    let asset_config = ASSET_CONFIGS.may_load(storage, asset.to_string())?;
+    Ok(asset_config.and_then(|c| c.unwrap))
+}
+
+/// Creates a message redeeming the liquid staking derivative through its hub
+pub fn build_unwrap_msg(unwrap: &UnwrapConfig, asset: Asset) -> StdResult<CosmosMsg> {
+    let wasm_msg = match &asset.info {
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: unwrap.hub.clone(),
+                amount: asset.amount,
+                msg: unwrap.msg.clone(),
+            })?,
+            funds: vec![],
+        },
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: unwrap.hub.clone(),
+            msg: unwrap.msg.clone(),
+            funds: vec![Coin {
+                denom: denom.to_string(),
+                amount: asset.amount,
+            }],
+        },
+    };
+
+    Ok(CosmosMsg::Wasm(wasm_msg))
+}
+
+/// Returns the route of the fee token to stablecoin following its bridge tokens
+pub fn get_bridge_route(
+    storage: &dyn Storage,
+    config: &Config,
+    from: AssetInfo,
+) -> Result<Vec<AssetInfo>, ContractError> {
+    let mut route = vec![from.clone()];
+    let mut asset = from;
+    while asset.ne(&config.stablecoin) {
+        if route.len() > BRIDGES_EXECUTION_MAX_DEPTH as usize {
+            return Err(ContractError::MaxBridgeDepth(BRIDGES_EXECUTION_MAX_DEPTH));
+        }
+        asset = BRIDGES
+            .may_load(storage, asset.to_string())?
+            .unwrap_or_else(|| config.stablecoin.clone());
+        route.push(asset.clone());
+    }
+
+    Ok(route)
+}
+
+/// Creates a router message swapping the fee token to stablecoin along its bridge route, returns
+/// the message with the amount swapped after the cap
+pub fn try_build_router_swap_msg(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+    swap_router: &SwapRouterConfig,
+    from: AssetInfo,
+    amount: Uint128,
+    max_spread: Decimal,
+) -> Result<(CosmosMsg, Uint128), ContractError> {
+    let route = get_bridge_route(storage, config, from.clone())?;
+
+    // The first hop may trade on another dex, the cap only applies to factory pairs
+    let first_pool = route
+        .get(1)
+        .and_then(|to| query_pair_info(querier, &config.factory_contract, &[from.clone(), to.clone()]).ok());
+    let amount = match first_pool {
+        Some(pool) => cap_swap_amount(querier, config, pool.contract_addr, &from, amount)?,
+        None => amount,
+    };
+
+    let msg = swap_router.router.execute_swap_operations_msg(
+        Asset { info: from, amount },
+        swap_router.router_type.create_swap_operations(&route)?,
+        None,
+        None,
+        Some(max_spread),
+    )?;
+    Ok((msg, amount))
+}
 
-/// Creates swap message
+/// Creates swap message, the pair checks the max spread against its spot price. Returns the message
+/// with the amount swapped after the cap
 pub fn try_build_swap_msg(
     querier: &QuerierWrapper,
     config: &Config,
     from: AssetInfo,
     to: AssetInfo,
     amount: Uint128,
-) -> Result<CosmosMsg, ContractError> {
+    max_spread: Decimal,
+) -> Result<(CosmosMsg, Uint128), ContractError> {
     let pool = query_pair_info(querier, &config.factory_contract, &[from.clone(), to])?;
+    let amount = cap_swap_amount(querier, config, pool.contract_addr.clone(), &from, amount)?;
     let msg = Pair(pool.contract_addr).swap_msg(
         &Asset { info: from, amount },
-        Some(Decimal::MAX),
-        Some(Decimal::percent(MAX_SPREAD)),
+        None,
+        Some(max_spread),
         None,
     )?;
+    Ok((msg, amount))
+}
+
+/// Creates a message swapping stablecoin to the payout asset of a beneficiary along the reversed bridge
+/// route of the payout asset, the output is sent to the receiver or kept by the contract
+pub fn try_build_payout_swap_msg(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+    to: AssetInfo,
+    amount: Uint128,
+    receiver: Option<Addr>,
+) -> Result<CosmosMsg, ContractError> {
+    let max_spread = get_max_spread(storage, &to)?;
+    let offer_asset = config.stablecoin.with_balance(amount);
+
+    // Without the router, only a direct pair with stablecoin can be used in a single message
+    let msg = match &config.swap_router {
+        Some(swap_router) => {
+            let mut route = get_bridge_route(storage, config, to)?;
+            route.reverse();
+            swap_router.router.execute_swap_operations_msg(
+                offer_asset,
+                swap_router.router_type.create_swap_operations(&route)?,
+                None,
+                receiver,
+                Some(max_spread),
+            )?
+        }
+        None => {
+            let pool = query_pair_info(querier, &config.factory_contract, &[config.stablecoin.clone(), to])?;
+            Pair(pool.contract_addr).swap_msg(
+                &offer_asset,
+                None,
+                Some(max_spread),
+                receiver.map(|r| r.to_string()),
+            )?
+        }
+    };
     Ok(msg)
 }
 
@@ -42,13 +187,35 @@ pub fn try_swap_simulation(
     amount: Uint128,
 ) -> StdResult<Uint128> {
     let pool = query_pair_info(querier, &config.factory_contract, &[from.clone(), to])?;
-    let result = simulate(
-        querier,
-        pool.contract_addr,
-        &Asset { info: from, amount })?;
+    let result = Pair(pool.contract_addr).simulate(querier, &Asset { info: from, amount }, None)?;
     Ok(result.return_amount)
 }
 
+/// Caps the swapped amount to the configured portion of the fee token reserve in the pool,
+/// the remainder is left for the next run
+pub fn cap_swap_amount(
+    querier: &QuerierWrapper,
+    config: &Config,
+    pool: Addr,
+    from: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<Uint128> {
+    if config.max_swap_portion_bps == 0 {
+        return Ok(amount);
+    }
+
+    let reserve = Pair(pool)
+        .query_pool_info(querier)?
+        .assets
+        .into_iter()
+        .find(|asset| asset.info.eq(from))
+        .map(|asset| asset.amount)
+        .unwrap_or_default();
+    let max_amount = bps_amount(reserve, config.max_swap_portion_bps)?;
+
+    Ok(amount.min(max_amount))
+}
+
 /// Creates swap message via bridge token pair
 pub fn build_swap_bridge_msg(
     contract_addr: &Addr,
@@ -69,6 +236,31 @@ pub fn build_swap_bridge_msg(
     Ok(msg)
 }
 
+/// Creates a message funding the staking contract through its deposit hook
+pub fn build_staking_deposit_msg(asset: Asset, staking_contract: &Addr) -> StdResult<CosmosMsg> {
+    let msg = match asset.info {
+        AssetInfo::Token { contract_addr } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: staking_contract.to_string(),
+                amount: asset.amount,
+                msg: to_binary(&StakingHookMsg::Deposit {})?,
+            })?,
+            funds: vec![],
+        }),
+        AssetInfo::NativeToken { denom } => CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: staking_contract.to_string(),
+            msg: to_binary(&StakingHookMsg::Deposit {})?,
+            funds: vec![Coin {
+                denom,
+                amount: asset.amount,
+            }],
+        }),
+    };
+
+    Ok(msg)
+}
+
 /// Validates bridge token
 pub fn validate_bridge(
     deps: Deps,
@@ -81,6 +273,11 @@ pub fn validate_bridge(
     // Check if the bridge pool exists
     let bridge_pool = query_pair_info(&deps.querier, factory_contract, &[from_token.clone(), bridge_token.clone()])?;
 
+    // A bridge to the stablecoin itself needs no further hop
+    if bridge_token.eq(stablecoin_token) {
+        return Ok(bridge_pool);
+    }
+
     // Check if the bridge token - stablecoin pool exists
     let stablecoin_pool = query_pair_info(&deps.querier, factory_contract, &[bridge_token.clone(), stablecoin_token.clone()]);
     if stablecoin_pool.is_err() {
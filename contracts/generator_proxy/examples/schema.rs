@@ -2,7 +2,9 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
-use spectrum_generator_proxy::model::{CallbackMsg, Config, ExecuteMsg, InstantiateMsg, QueryMsg};
+use IDC_generator_proxy::model::{
+    CallbackMsg, Config, ExecuteMsg, InstantiateMsg, QueryMsg, StakerInfoResponse, UserInfoResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,4 +17,6 @@ fn main() {
     export_schema(&schema_for!(CallbackMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(UserInfoResponse), &out_dir);
+    export_schema(&schema_for!(StakerInfoResponse), &out_dir);
 }
@@ -4,7 +4,7 @@ use cw20::Cw20ReceiveMsg;
 use astroport_governance::utils::get_period;
 use spectrum::adapters::generator::Generator;
 use crate::bond::{callback_after_bond_changed, callback_after_bond_claimed, callback_claim_rewards, callback_deposit, callback_withdraw, execute_deposit, execute_withdraw, query_deposit, query_pending_token, execute_claim_rewards};
-use crate::oper::{execute_controller_vote, execute_send_income, execute_update_config, execute_update_parameters, query_config, validate_percentage};
+use crate::oper::{execute_controller_vote, execute_extend_lock_time, execute_send_income, execute_update_config, execute_update_parameters, query_config, validate_percentage};
 use crate::error::ContractError;
 use crate::model::{CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakingState};
 use crate::query::{query_pool_info, query_reward_info, query_staker_info, query_staking_state, query_user_info};
@@ -70,6 +70,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
         } => execute_update_parameters(deps, env, info, max_quota, staker_rate),
 
         ExecuteMsg::ControllerVote { votes } => execute_controller_vote(deps, env, info, votes),
+        ExecuteMsg::ExtendLockTime { time } => execute_extend_lock_time(deps, env, info, time),
         ExecuteMsg::SendIncome {} => execute_send_income(deps, env, info),
 
         ExecuteMsg::ClaimRewards { lp_tokens } => execute_claim_rewards(deps, env, info, lp_tokens),
@@ -225,6 +225,11 @@ pub enum ExecuteMsg {
     ControllerVote {
         votes: Vec<(String, u16)>,
     },
+    /// Extends the vxASTRO lock by the number of seconds, unstaking waits until the lock ends. An
+    /// ended lock is withdrawn to release the unstaking amount, and the rest is locked for the seconds
+    ExtendLockTime {
+        time: u64,
+    },
     SendIncome {},
 
     // from generator
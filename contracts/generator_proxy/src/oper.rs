@@ -1,8 +1,10 @@
 use cosmwasm_std::{CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128};
 use astroport::asset::{token_asset};
+use astroport_governance::utils::get_period;
 use spectrum::adapters::asset::AssetEx;
 use crate::error::ContractError;
 use crate::model::{Config};
+use crate::staking::relock;
 use crate::state::{CONFIG, REWARD_INFO};
 
 pub fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
@@ -88,6 +90,34 @@ pub fn execute_controller_vote(
     )
 }
 
+pub fn execute_extend_lock_time(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    time: u64,
+) -> Result<Response, ContractError> {
+    // only controller can extend lock
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // an ended lock cannot be extended, unlock it to release the unstaking amount and lock the rest
+    let lock = config.astro_gov.query_lock(&deps.querier, env.contract.address.clone())?;
+    if lock.amount.is_zero() {
+        return Err(StdError::generic_err("No lock to extend").into());
+    }
+    if lock.end <= get_period(env.block.time.seconds())? {
+        return relock(deps, env, config, time);
+    }
+
+    let extend_msg = config.astro_gov.extend_lock_time_msg(time)?;
+
+    Ok(Response::new()
+        .add_message(extend_msg)
+    )
+}
+
 pub fn execute_send_income(
     deps: DepsMut,
     _env: Env,
@@ -101,7 +101,16 @@ pub fn execute_relock(
         return Err(ContractError::Unauthorized {});
     }
 
-    // relock
+    relock(deps, env, config, WEEK)
+}
+
+/// Withdraws the ended lock, moving the unstaking amount to unstaked, and locks the rest for the time
+pub fn relock(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    time: u64,
+) -> Result<Response, ContractError> {
     let mut astro_reward = REWARD_INFO.load(deps.storage, &config.astro_token)?;
     let mut state = STAKING_STATE.load(deps.storage)?;
     let lock = config.astro_gov.query_lock(&deps.querier, env.contract.address.clone())?;
@@ -128,9 +137,8 @@ pub fn execute_relock(
             prev_balance,
         }.to_cosmos_msg(&env.contract.address)?)
         .add_message(config.astro_gov.withdraw_msg()?)
-        .add_message(config.astro_gov.create_lock_msg(lock_amount, WEEK)?)
+        .add_message(config.astro_gov.create_lock_msg(lock_amount, time)?)
     )
-
 }
 
 fn reconcile_staking_claim_by_others(
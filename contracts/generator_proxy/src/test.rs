@@ -45,6 +45,7 @@ fn test() -> Result<(), ContractError> {
     claim_income(&mut deps)?;
 
     send_income(&mut deps)?;
+    extend_lock_time(&mut deps)?;
 
     Ok(())
 }
@@ -702,7 +703,7 @@ fn withdraw(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resu
         lp_token: Addr::unchecked(LP_TOKEN),
     });
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
-    assert_error(res, "spectrum_generator_proxy::model::UserInfo not found");
+    assert_error(res, "IDC_generator_proxy::model::UserInfo not found");
 
     let msg = ExecuteMsg::Callback(CallbackMsg::Withdraw {
         amount: Uint128::from(101u128),
@@ -1228,3 +1229,78 @@ fn send_income(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> R
 
     Ok(())
 }
+
+fn extend_lock_time(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(EPOCH_START + WEEK);
+    let info = mock_info(USER2, &[]);
+
+    let msg = ExecuteMsg::ExtendLockTime {
+        time: WEEK * 52,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(CONTROLLER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: VOTING_ESCROW.to_string(),
+                msg: to_binary(&VotingExecuteMsg::ExtendLockTime {
+                    time: WEEK * 52,
+                })?,
+                funds: vec![],
+            }),
+        ]);
+
+    // an ended lock is withdrawn to release the unstaking amount, and the rest is locked again
+    env.block.time = Timestamp::from_seconds(EPOCH_START + WEEK * 2);
+    let msg = ExecuteMsg::ExtendLockTime {
+        time: WEEK * 52,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: FEE_DISTRIBUTOR.to_string(),
+                msg: to_binary(&FeeExecuteMsg::Claim {
+                    recipient: None,
+                    max_periods: None,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::AfterStakingClaimed {
+                    prev_balance: Uint128::from(92u128),
+                }))?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: VOTING_ESCROW.to_string(),
+                msg: to_binary(&VotingExecuteMsg::Withdraw {})?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: XASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: VOTING_ESCROW.to_string(),
+                    amount: Uint128::from(200u128),
+                    msg: to_binary(&VotingCw20HookMsg::CreateLock {
+                        time: WEEK * 52,
+                    })?,
+                })?,
+                funds: vec![],
+            })
+        ]);
+
+    let msg = QueryMsg::StakingState {};
+    let res: StakingState = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.total_unstaking_amount, Uint128::zero());
+    assert_eq!(res.unstaking_period, 2);
+
+    Ok(())
+}
@@ -0,0 +1,386 @@
+use cosmwasm_std::{
+    entry_point, from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG};
+use astroport::asset::token_asset_info;
+use astroport::generator_proxy::{
+    CallbackMsg, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+};
+use spectrum::adapters::staking::StakingAdapter;
+use spectrum::helper::assert_valid_addr;
+use spectrum::querier::query_asset_balance;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the default object of type [`Response`] if the operation was successful,
+/// or a [`ContractError`] if the contract was not created.
+/// ## Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **_env** is the object of type [`Env`].
+///
+/// * **_info** is the object of type [`MessageInfo`].
+/// * **msg** is a message of type [`InstantiateMsg`] which contains the basic settings for creating a contract.
+///   The **reward_contract_addr** is the staking adapter of the third-party staking contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        generator_contract_addr: deps.api.addr_validate(&msg.generator_contract_addr)?,
+        pair_addr: deps.api.addr_validate(&msg.pair_addr)?,
+        lp_token_addr: deps.api.addr_validate(&msg.lp_token_addr)?,
+        staking_adapter: StakingAdapter(deps.api.addr_validate(&msg.reward_contract_addr)?),
+        reward_token_addr: deps.api.addr_validate(&msg.reward_token_addr)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Available the execute messages of the contract.
+/// ## Params
+/// * **deps** is the object of type [`Deps`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **info** is the object of type [`MessageInfo`].
+///
+/// * **msg** is the object of type [`ExecuteMsg`].
+///
+/// ## Queries
+/// * **ExecuteMsg::Receive(msg)** Receives a message of type [`Cw20ReceiveMsg`] and processes
+///   it depending on the received template.
+///
+/// * **ExecuteMsg::UpdateRewards {}** Claims the pending rewards into the escrow.
+///
+/// * **ExecuteMsg::SendRewards { account, amount }** Releases escrowed rewards to the recipient.
+///
+/// * **ExecuteMsg::Withdraw { account, amount }** Unstakes the lp tokens and sends them to the recipient.
+///
+/// * **ExecuteMsg::EmergencyWithdraw { account, amount }** Unstakes the lp tokens and sends them to the recipient.
+///
+/// * **ExecuteMsg::Callback(msg)** Handles the callbacks describes in the [`CallbackMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::UpdateRewards {} => update_rewards(deps),
+        ExecuteMsg::SendRewards { account, amount } => {
+            send_rewards(deps, env, info, account, amount)
+        }
+        ExecuteMsg::Withdraw { account, amount } => withdraw(deps, env, info, account, amount),
+        ExecuteMsg::EmergencyWithdraw { account, amount } => {
+            withdraw(deps, env, info, account, amount)
+        }
+        ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
+    }
+}
+
+/// ## Description
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// If the template is not found in the received message, then an [`ContractError`] is returned,
+/// otherwise returns the [`Response`] with the specified attributes if the operation was successful
+/// ## Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **_env** is the object of type [`Env`].
+///
+/// * **info** is the object of type [`MessageInfo`].
+///
+/// * **cw20_msg** is the object of type [`Cw20ReceiveMsg`].
+fn receive_cw20(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    if let Ok(Cw20HookMsg::Deposit {}) = from_binary(&cw20_msg.msg) {
+        if cw20_msg.sender != cfg.generator_contract_addr || info.sender != cfg.lp_token_addr {
+            return Err(ContractError::Unauthorized {});
+        }
+        Ok(Response::new().add_message(
+            cfg.staking_adapter
+                .stake_msg(cfg.lp_token_addr.to_string(), cw20_msg.amount)?,
+        ))
+    } else {
+        Err(ContractError::IncorrectCw20HookMessageVariant {})
+    }
+}
+
+/// ## Description
+/// Claims the pending rewards from the staking contract. The rewards stay in this contract
+/// until the generator releases them with [`ExecuteMsg::SendRewards`].
+/// Returns an [`ContractError`] on failure, otherwise returns the [`Response`] object with the specified submessages.
+///
+/// ## Params
+/// * **deps** is the object of type [`DepsMut`].
+fn update_rewards(deps: DepsMut) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    Ok(Response::new().add_message(cfg.staking_adapter.claim_rewards_msg()?))
+}
+
+/// ## Description
+/// Releases escrowed rewards to the recipient. Returns an [`ContractError`] on failure or when the
+/// escrow holds less than **amount**, otherwise returns the [`Response`] object with the specified submessages.
+///
+/// ## Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **info** is the object of type [`MessageInfo`].
+///
+/// * **account** is the object of type [`Addr`]. Sets the recipient of rewards.
+///
+/// * **amount** is the object of type [`Uint128`].
+///
+/// ## Executor
+/// Only the contract of generator can execute it
+fn send_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    deps.api.addr_validate(&account)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.generator_contract_addr {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    let escrowed = query_asset_balance(
+        &deps.querier,
+        &token_asset_info(cfg.reward_token_addr.clone()),
+        &env.contract.address,
+    )?;
+    if escrowed < amount {
+        return Err(ContractError::InsufficientRewards { escrowed, amount });
+    }
+
+    Ok(Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: cfg.reward_token_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: account,
+            amount,
+        })?,
+        funds: vec![],
+    })))
+}
+
+/// # Description
+/// Unstakes the lp tokens from the staking contract and sends them to the recipient. Returns an [`ContractError`] on
+/// failure, otherwise returns the [`Response`] object with the specified submessages if the operation was successful.
+/// # Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **info** is the object of type [`MessageInfo`].
+///
+/// * **account** is the object of type [`Addr`]. Sets the recipient for withdrawal.
+///
+/// * **amount** is the object of type [`Uint128`].
+///
+/// ## Executor
+/// Only the contract of generator can execute it
+fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    account: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let account = deps.api.addr_validate(&account)?;
+
+    let cfg = CONFIG.load(deps.storage)?;
+    if info.sender != cfg.generator_contract_addr {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    let prev_lp_balance = query_asset_balance(
+        &deps.querier,
+        &token_asset_info(cfg.lp_token_addr.clone()),
+        &env.contract.address,
+    )?;
+
+    Ok(Response::new()
+        .add_message(cfg.staking_adapter.unstake_msg(amount)?)
+        .add_submessage(SubMsg::new(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::Callback(
+                CallbackMsg::TransferLpTokensAfterWithdraw {
+                    account,
+                    prev_lp_balance,
+                },
+            ))?,
+        })))
+}
+
+/// # Description
+/// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
+/// object with the specified submessages if the operation was successful.
+/// # Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **info** is the object of type [`MessageInfo`].
+///
+/// * **msg** is the object of type [`CallbackMsg`]. Sets the callback action.
+///
+/// ## Executor
+/// Callback functions can only be called this contract itself
+pub fn handle_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CallbackMsg,
+) -> Result<Response, ContractError> {
+    // Callback functions can only be called this contract itself
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    match msg {
+        CallbackMsg::TransferLpTokensAfterWithdraw {
+            account,
+            prev_lp_balance,
+        } => transfer_lp_tokens_after_withdraw(deps, env, account, prev_lp_balance),
+    }
+}
+
+/// # Description
+/// Transfers lp tokens after withdrawal to the recipient. Returns an [`ContractError`] on failure,
+/// otherwise returns the [`Response`] object with the specified submessages if the operation was successful.
+/// # Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **account** is the object of type [`Addr`]. Sets the recipient of withdrawal.
+///
+/// * **prev_lp_balance** is the object of type [`Uint128`]. Sets the previous balance for
+///   calculating the withdrawal amount.
+pub fn transfer_lp_tokens_after_withdraw(
+    deps: DepsMut,
+    env: Env,
+    account: Addr,
+    prev_lp_balance: Uint128,
+) -> Result<Response, ContractError> {
+    let cfg = CONFIG.load(deps.storage)?;
+
+    let amount = query_asset_balance(
+        &deps.querier,
+        &token_asset_info(cfg.lp_token_addr.clone()),
+        &env.contract.address,
+    )? - prev_lp_balance;
+
+    Ok(Response::new().add_message(WasmMsg::Execute {
+        contract_addr: cfg.lp_token_addr.to_string(),
+        funds: vec![],
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: account.to_string(),
+            amount,
+        })?,
+    }))
+}
+
+/// # Description
+/// Describes all query messages.
+/// # Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **env** is the object of type [`Env`].
+///
+/// * **msg** is the object of type [`QueryMsg`].
+///
+/// ## Queries
+/// * **QueryMsg::Deposit {}** returns the lp token amount staked in the staking contract.
+///
+/// * **QueryMsg::Reward {}** returns the escrowed balance of reward token.
+///
+/// * **QueryMsg::PendingToken {}** returns the reward token amount not yet claimed into the escrow.
+///
+/// * **QueryMsg::RewardInfo {}** returns the reward token contract address.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let cfg = CONFIG.load(deps.storage)?;
+    match msg {
+        QueryMsg::Config {} => to_binary(&ConfigResponse {
+            generator_contract_addr: cfg.generator_contract_addr.to_string(),
+            pair_addr: cfg.pair_addr.to_string(),
+            lp_token_addr: cfg.lp_token_addr.to_string(),
+            reward_contract_addr: cfg.staking_adapter.0.to_string(),
+            reward_token_addr: cfg.reward_token_addr.to_string(),
+        }),
+        QueryMsg::Deposit {} => {
+            let deposit_amount = cfg
+                .staking_adapter
+                .query_staked_balance(&deps.querier, &env.contract.address)?;
+
+            to_binary(&deposit_amount)
+        }
+        QueryMsg::Reward {} => {
+            let reward_amount = query_asset_balance(
+                &deps.querier,
+                &token_asset_info(cfg.reward_token_addr),
+                env.contract.address,
+            )?;
+
+            to_binary(&reward_amount)
+        }
+        QueryMsg::PendingToken {} => {
+            let reward_token = token_asset_info(cfg.reward_token_addr);
+            let pending_reward: Uint128 = cfg
+                .staking_adapter
+                .query_pending_rewards(&deps.querier, &env.contract.address)?
+                .into_iter()
+                .filter(|asset| asset.info == reward_token)
+                .map(|asset| asset.amount)
+                .sum();
+
+            to_binary(&pending_reward)
+        }
+        QueryMsg::RewardInfo {} => to_binary(&cfg.reward_token_addr),
+    }
+}
+
+/// ## Description
+/// Used for migration of contract. Returns the default object of type [`Response`], or an error
+/// if the stored config is inconsistent.
+/// ## Params
+/// * **deps** is the object of type [`DepsMut`].
+///
+/// * **_env** is the object of type [`Env`].
+///
+/// * **_msg** is the object of type [`MigrateMsg`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.generator_contract_addr, "generator_contract_addr")?;
+    assert_valid_addr(deps.api, &config.pair_addr, "pair_addr")?;
+    assert_valid_addr(deps.api, &config.lp_token_addr, "lp_token_addr")?;
+    assert_valid_addr(deps.api, &config.staking_adapter.0, "staking_adapter")?;
+    assert_valid_addr(deps.api, &config.reward_token_addr, "reward_token_addr")?;
+
+    Ok(Response::default())
+}
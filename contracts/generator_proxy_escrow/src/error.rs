@@ -0,0 +1,19 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes errors for generator_proxy_escrow contract!
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Incorrect CW20 hook message variant!")]
+    IncorrectCw20HookMessageVariant {},
+
+    #[error("Insufficient escrowed rewards: {escrowed} < {amount}")]
+    InsufficientRewards { escrowed: Uint128, amount: Uint128 },
+}
@@ -0,0 +1,2 @@
+mod mock_querier;
+mod tests;
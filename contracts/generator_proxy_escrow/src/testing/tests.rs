@@ -0,0 +1,290 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG};
+use crate::testing::mock_querier::mock_dependencies;
+use astroport::asset::{token_asset, Asset, AssetInfo};
+use astroport::generator_proxy::{CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{from_binary, to_binary, Addr, CosmosMsg, SubMsg, Uint128, WasmMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use spectrum::adapters::staking::{
+    Cw20HookMsg as StakingCw20HookMsg, ExecuteMsg as StakingExecuteMsg,
+};
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        generator_contract_addr: "generator0000".to_string(),
+        pair_addr: "pair0000".to_string(),
+        lp_token_addr: "lptoken0000".to_string(),
+        reward_contract_addr: "adapter0000".to_string(),
+        reward_token_addr: "reward0000".to_string(),
+    }
+}
+
+#[test]
+fn test_proper_initialization() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    let config: Config = CONFIG.load(deps.as_ref().storage).unwrap();
+    assert_eq!("generator0000", config.generator_contract_addr.as_str());
+    assert_eq!("pair0000", config.pair_addr.as_str());
+    assert_eq!("lptoken0000", config.lp_token_addr.as_str());
+    assert_eq!("adapter0000", config.staking_adapter.0.as_str());
+    assert_eq!("reward0000", config.reward_token_addr.as_str());
+}
+
+#[test]
+fn test_deposit() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg()).unwrap();
+
+    // deposit fails when not sent by LP token
+    let deposit_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "generator0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), info, deposit_msg.clone()).unwrap_err();
+    match res {
+        ContractError::Unauthorized {} => {}
+        _ => panic!("Must return unauthorized error"),
+    };
+
+    // deposit fails when not sent on behalf of generator
+    let lp_info = mock_info("lptoken0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        lp_info.clone(),
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(100u128),
+            msg: to_binary(&Cw20HookMsg::Deposit {}).unwrap(),
+        }),
+    )
+    .unwrap_err();
+    match res {
+        ContractError::Unauthorized {} => {}
+        _ => panic!("Must return unauthorized error"),
+    };
+
+    // stake lp tokens in the staking contract
+    let res = execute(deps.as_mut(), mock_env(), lp_info, deposit_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "lptoken0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "adapter0000".to_string(),
+                amount: Uint128::from(100u128),
+                msg: to_binary(&StakingCw20HookMsg::Stake {}).unwrap(),
+            })
+            .unwrap(),
+        }))]
+    );
+}
+
+#[test]
+fn test_update_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg()).unwrap();
+
+    // rewards are claimed into this contract
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::UpdateRewards {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "adapter0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&StakingExecuteMsg::ClaimRewards {}).unwrap(),
+        }))]
+    );
+}
+
+#[test]
+fn test_send_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg()).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &String::from("reward0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(150))],
+    )]);
+
+    // fails when called from unauthorized
+    let send_msg = ExecuteMsg::SendRewards {
+        account: "addr0000".to_string(),
+        amount: Uint128::new(100),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, send_msg.clone()).unwrap_err();
+    match res {
+        ContractError::Unauthorized {} => {}
+        _ => panic!("Must return unauthorized error"),
+    };
+
+    // fails when the escrow holds less than the amount
+    let generator_info = mock_info("generator0000", &[]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        generator_info.clone(),
+        ExecuteMsg::SendRewards {
+            account: "addr0000".to_string(),
+            amount: Uint128::new(200),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        res.to_string(),
+        "Insufficient escrowed rewards: 150 < 200"
+    );
+
+    // releases the escrowed rewards
+    let res = execute(deps.as_mut(), mock_env(), generator_info, send_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_withdraw() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg()).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &String::from("lptoken0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1))],
+    )]);
+
+    // unstake and send lp tokens to user
+    // fails when called from unauthorized
+    let withdraw_msg = ExecuteMsg::Withdraw {
+        account: "addr0000".to_string(),
+        amount: Uint128::new(100),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, withdraw_msg.clone()).unwrap_err();
+    match res {
+        ContractError::Unauthorized {} => {}
+        _ => panic!("Must return unauthorized error"),
+    };
+
+    // succeeds when coming from generator
+    let generator_info = mock_info("generator0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), generator_info, withdraw_msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "adapter0000".to_string(),
+                funds: vec![],
+                msg: to_binary(&StakingExecuteMsg::Unstake {
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+            }),
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                funds: vec![],
+                msg: to_binary(&ExecuteMsg::Callback(
+                    CallbackMsg::TransferLpTokensAfterWithdraw {
+                        account: Addr::unchecked("addr0000"),
+                        prev_lp_balance: Uint128::new(1),
+                    }
+                ))
+                .unwrap(),
+            })
+        ]
+    );
+
+    // callback sends the unstaked lp tokens
+    deps.querier.with_token_balances(&[(
+        &String::from("lptoken0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(101))],
+    )]);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        ExecuteMsg::Callback(CallbackMsg::TransferLpTokensAfterWithdraw {
+            account: Addr::unchecked("addr0000"),
+            prev_lp_balance: Uint128::new(1),
+        }),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(WasmMsg::Execute {
+            contract_addr: "lptoken0000".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::new(100),
+            })
+            .unwrap(),
+        })]
+    );
+}
+
+#[test]
+fn test_query() {
+    let mut deps = mock_dependencies(&[]);
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, instantiate_msg()).unwrap();
+
+    deps.querier.with_token_balances(&[(
+        &String::from("reward0000"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(150))],
+    )]);
+    deps.querier.with_staking_info(
+        vec![
+            token_asset(Addr::unchecked("reward0000"), Uint128::new(30)),
+            Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::new(70),
+            },
+        ],
+        Uint128::new(1000),
+    );
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Deposit {}).unwrap();
+    let deposit: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(deposit, Uint128::new(1000));
+
+    // escrowed rewards
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Reward {}).unwrap();
+    let reward: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(reward, Uint128::new(150));
+
+    // only the reward token is pending for the generator
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingToken {}).unwrap();
+    let pending: Uint128 = from_binary(&res).unwrap();
+    assert_eq!(pending, Uint128::new(30));
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::RewardInfo {}).unwrap();
+    let reward_token: Addr = from_binary(&res).unwrap();
+    assert_eq!(reward_token, Addr::unchecked("reward0000"));
+}
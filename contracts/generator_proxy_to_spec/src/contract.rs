@@ -1,14 +1,17 @@
 use cosmwasm_std::{
     entry_point, from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
-use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
 use crate::error::ContractError;
 use crate::state::{Config, CONFIG};
+use astroport::asset::token_asset_info;
 use astroport::generator_proxy::{
     CallbackMsg, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
 };
+use spectrum::helper::assert_valid_addr;
+use spectrum::querier::query_asset_balance;
 use spectrum::lp_staking::{
     Cw20HookMsg as SpecCw20HookMsg, ExecuteMsg as SpecExecuteMsg, RewardInfoResponse as SpecRewardInfoResponse, QueryMsg as SpecQueryMsg
 };
@@ -227,15 +230,11 @@ fn withdraw(
         return Err(ContractError::Unauthorized {});
     };
 
-    let prev_lp_balance = {
-        let res: BalanceResponse = deps.querier.query_wasm_smart(
-            &cfg.lp_token_addr,
-            &Cw20QueryMsg::Balance {
-                address: env.contract.address.to_string(),
-            },
-        )?;
-        res.balance
-    };
+    let prev_lp_balance = query_asset_balance(
+        &deps.querier,
+        &token_asset_info(cfg.lp_token_addr.clone()),
+        &env.contract.address,
+    )?;
 
     // withdraw from the end reward contract
     response.messages.push(SubMsg::new(WasmMsg::Execute {
@@ -312,15 +311,11 @@ pub fn transfer_lp_tokens_after_withdraw(
 ) -> Result<Response, ContractError> {
     let cfg = CONFIG.load(deps.storage)?;
 
-    let amount = {
-        let res: BalanceResponse = deps.querier.query_wasm_smart(
-            &cfg.lp_token_addr,
-            &Cw20QueryMsg::Balance {
-                address: env.contract.address.to_string(),
-            },
-        )?;
-        res.balance - prev_lp_balance
-    };
+    let amount = query_asset_balance(
+        &deps.querier,
+        &token_asset_info(cfg.lp_token_addr.clone()),
+        &env.contract.address,
+    )? - prev_lp_balance;
 
     Ok(Response::new().add_message(WasmMsg::Execute {
         contract_addr: cfg.lp_token_addr.to_string(),
@@ -374,13 +369,11 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&deposit_amount)
         }
         QueryMsg::Reward {} => {
-            let res: Result<BalanceResponse, StdError> = deps.querier.query_wasm_smart(
-                cfg.reward_token_addr,
-                &Cw20QueryMsg::Balance {
-                    address: env.contract.address.into_string(),
-                },
-            );
-            let reward_amount = res?.balance;
+            let reward_amount = query_asset_balance(
+                &deps.querier,
+                &token_asset_info(cfg.reward_token_addr),
+                env.contract.address,
+            )?;
 
             to_binary(&reward_amount)
         }
@@ -404,14 +397,22 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 }
 
 /// ## Description
-/// Used for migration of contract. Returns the default object of type [`Response`].
+/// Used for migration of contract. Returns the default object of type [`Response`], or an error
+/// if the stored config is inconsistent.
 /// ## Params
-/// * **_deps** is the object of type [`DepsMut`].
+/// * **deps** is the object of type [`DepsMut`].
 ///
 /// * **_env** is the object of type [`Env`].
 ///
 /// * **_msg** is the object of type [`MigrateMsg`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.generator_contract_addr, "generator_contract_addr")?;
+    assert_valid_addr(deps.api, &config.pair_addr, "pair_addr")?;
+    assert_valid_addr(deps.api, &config.lp_token_addr, "lp_token_addr")?;
+    assert_valid_addr(deps.api, &config.reward_contract_addr, "reward_contract_addr")?;
+    assert_valid_addr(deps.api, &config.reward_token_addr, "reward_token_addr")?;
+
     Ok(Response::default())
 }
@@ -0,0 +1,34 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{remove_schemas, schema_for, export_schema};
+
+use spectrum::adapters::gov::BoostWeightResponse;
+use spectrum::gov::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockResponse, MigrateMsg, PollResponse, PollsResponse,
+    QueryMsg, StakerInfoResponse, StateResponse, VoterResponse,
+};
+use IDC_gov::state::Config;
+use spectrum::vault::ExchangeRateResponse;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(Cw20HookMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(ExchangeRateResponse), &out_dir);
+    export_schema(&schema_for!(StakerInfoResponse), &out_dir);
+    export_schema(&schema_for!(LockResponse), &out_dir);
+    export_schema(&schema_for!(BoostWeightResponse), &out_dir);
+    export_schema(&schema_for!(PollResponse), &out_dir);
+    export_schema(&schema_for!(PollsResponse), &out_dir);
+    export_schema(&schema_for!(VoterResponse), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+}
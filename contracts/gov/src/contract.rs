@@ -0,0 +1,619 @@
+use crate::error::ContractError;
+use crate::poll::{cast_vote, create_poll, end_poll, execute_poll, query_poll, query_polls, query_voter};
+use crate::state::{
+    week_floor, Config, Lock, StakerInfo, State, CONFIG, MAX_LOCK_TIME, MIN_LOCK_TIME, STAKERS, STATE,
+};
+
+use astroport::asset::{token_asset, AssetInfo, AssetInfoExt};
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use astroport::querier::query_token_balance;
+use spectrum::adapters::asset::AssetEx;
+use spectrum::adapters::gov::BoostWeightResponse;
+use spectrum::helper::{assert_ratio, assert_valid_addr, invalid_state};
+use spectrum::vault::ExchangeRateResponse;
+use spectrum::gov::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockResponse, MigrateMsg, QueryMsg, StakerInfoResponse,
+    StateResponse,
+};
+
+/// ## Description
+/// Validates that decimal value is in the range 0 to 1
+fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
+    if value > Decimal::one() {
+        Err(StdError::generic_err(field.to_string() + " must be 0 to 1"))
+    } else {
+        Ok(())
+    }
+}
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    msg.reward_token.check(deps.api)?;
+    validate_percentage(msg.quorum, "quorum")?;
+    validate_percentage(msg.threshold, "threshold")?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            spec_token: deps.api.addr_validate(&msg.spec_token)?,
+            reward_token: msg.reward_token,
+            quorum: msg.quorum,
+            threshold: msg.threshold,
+            voting_period: msg.voting_period,
+            effective_delay: msg.effective_delay,
+            proposal_deposit: msg.proposal_deposit,
+        },
+    )?;
+    STATE.save(deps.storage, &State::default())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Deposit {} => deposit_native(deps, info),
+        ExecuteMsg::Withdraw { amount } => withdraw(deps, env, info, amount),
+        ExecuteMsg::ClaimReward {} => claim_reward(deps, env, info),
+        ExecuteMsg::ExtendLock { index, duration } => extend_lock(deps, env, info, index, duration),
+        ExecuteMsg::MergeLocks { indexes } => merge_locks(deps, env, info, indexes),
+        ExecuteMsg::ReleaseLocks { staker_addr } => release_locks(deps, env, staker_addr),
+        ExecuteMsg::UpdateConfig {
+            quorum,
+            threshold,
+            voting_period,
+            effective_delay,
+            proposal_deposit,
+        } => update_config(
+            deps,
+            env,
+            info,
+            quorum,
+            threshold,
+            voting_period,
+            effective_delay,
+            proposal_deposit,
+        ),
+        ExecuteMsg::CastVote { poll_id, vote } => cast_vote(deps, env, info, poll_id, vote),
+        ExecuteMsg::EndPoll { poll_id } => end_poll(deps, env, poll_id),
+        ExecuteMsg::ExecutePoll { poll_id } => execute_poll(deps, env, poll_id),
+    }
+}
+
+/// ## Description
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// If the template is not found in the received message, then a [`ContractError`] is returned,
+/// otherwise returns a [`Response`] with the specified attributes if the operation was successful
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Stake { staker_addr, lock_duration } => {
+            // only SPEC token contract can execute this message
+            if info.sender != config.spec_token {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let staker_addr = deps.api.addr_validate(&staker_addr.unwrap_or(cw20_msg.sender))?;
+            stake(deps, env, staker_addr, cw20_msg.amount, lock_duration)
+        }
+        Cw20HookMsg::Deposit {} => {
+            // only the cw20 reward token contract can execute this message
+            let reward_token = AssetInfo::Token { contract_addr: info.sender };
+            if !config.reward_token.equal(&reward_token) {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            deposit(deps, cw20_msg.amount)
+        }
+        Cw20HookMsg::CreatePoll { title, description, link, execute_msgs } => {
+            // only SPEC token contract can execute this message
+            if info.sender != config.spec_token {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            let creator = deps.api.addr_validate(&cw20_msg.sender)?;
+            create_poll(deps, env, creator, cw20_msg.amount, title, description, link, execute_msgs)
+        }
+    }
+}
+
+/// ## Description
+/// Updates the poll parameters, only the contract itself can execute this through a poll.
+#[allow(clippy::too_many_arguments)]
+fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+    voting_period: Option<u64>,
+    effective_delay: Option<u64>,
+    proposal_deposit: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(quorum) = quorum {
+        validate_percentage(quorum, "quorum")?;
+        config.quorum = quorum;
+    }
+
+    if let Some(threshold) = threshold {
+        validate_percentage(threshold, "threshold")?;
+        config.threshold = threshold;
+    }
+
+    if let Some(voting_period) = voting_period {
+        config.voting_period = voting_period;
+    }
+
+    if let Some(effective_delay) = effective_delay {
+        config.effective_delay = effective_delay;
+    }
+
+    if let Some(proposal_deposit) = proposal_deposit {
+        config.proposal_deposit = proposal_deposit;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Validates the lock duration and returns the lock end.
+fn calc_lock_end(env: &Env, duration: u64) -> Result<u64, ContractError> {
+    if !(MIN_LOCK_TIME..=MAX_LOCK_TIME).contains(&duration) {
+        return Err(ContractError::InvalidLockDuration {
+            min: MIN_LOCK_TIME,
+            max: MAX_LOCK_TIME,
+        });
+    }
+    Ok(week_floor(env.block.time.seconds() + duration))
+}
+
+/// ## Description
+/// Loads the state with the voting power decayed up to the time.
+pub fn load_state(storage: &dyn Storage, time: u64) -> StdResult<State> {
+    let mut state = STATE.load(storage)?;
+    state.checkpoint(storage, time)?;
+    Ok(state)
+}
+
+/// ## Description
+/// Loads the staker info with the reward settled and the ended locks released.
+pub fn load_staker_info(
+    storage: &dyn Storage,
+    state: &mut State,
+    staker_addr: &Addr,
+    time: u64,
+) -> StdResult<StakerInfo> {
+    let mut staker_info = STAKERS.may_load(storage, staker_addr)?.unwrap_or_default();
+    staker_info.settle(state);
+    staker_info.release_locks(state, time);
+    Ok(staker_info)
+}
+
+/// ## Description
+/// Stakes SPEC on behalf of the staker, locked for the duration if set.
+fn stake(
+    deps: DepsMut,
+    env: Env,
+    staker_addr: Addr,
+    amount: Uint128,
+    lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let mut staker_info = load_staker_info(deps.storage, &mut state, &staker_addr, time)?;
+
+    let mut attributes = vec![
+        attr("action", "stake"),
+        attr("staker_addr", &staker_addr),
+        attr("amount", amount),
+    ];
+
+    match lock_duration {
+        Some(lock_duration) => {
+            let lock = Lock::new(amount, calc_lock_end(&env, lock_duration)?, time);
+            state.add_lock(deps.storage, &lock, time)?;
+            staker_info.weight += lock.weight;
+            state.total_weight += lock.weight;
+            attributes.push(attr("lock_end", lock.end.to_string()));
+            attributes.push(attr("weight", lock.weight));
+            staker_info.locks.push(lock);
+        }
+        None => {
+            staker_info.amount += amount;
+            staker_info.weight += amount;
+            state.total_weight += amount;
+        }
+    }
+    state.total_staked += amount;
+
+    // share the reward deposited while nothing was staked
+    state.distribute(Uint128::zero());
+
+    STAKERS.save(deps.storage, &staker_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// ## Description
+/// Shares the native reward token sent with the message with the stakers.
+fn deposit_native(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let amount = match &config.reward_token {
+        AssetInfo::NativeToken { denom } => info
+            .funds
+            .iter()
+            .find(|coin| &coin.denom == denom)
+            .map(|coin| coin.amount)
+            .unwrap_or_default(),
+        AssetInfo::Token { .. } => return Err(ContractError::Unauthorized {}),
+    };
+
+    deposit(deps, amount)
+}
+
+/// ## Description
+/// Shares the deposited reward token with the stakers.
+fn deposit(deps: DepsMut, amount: Uint128) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut state = STATE.load(deps.storage)?;
+    state.distribute(amount);
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deposit"),
+        attr("amount", amount),
+    ]))
+}
+
+/// ## Description
+/// Withdraws staked SPEC of the sender, locked SPEC is withdrawable once the lock has ended.
+fn withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let config = CONFIG.load(deps.storage)?;
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let mut staker_info = load_staker_info(deps.storage, &mut state, &staker_addr, time)?;
+
+    let amount = amount.unwrap_or(staker_info.amount);
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if amount > staker_info.amount {
+        return Err(ContractError::WithdrawExceedStake {});
+    }
+    if staker_info.vote_end > time {
+        return Err(ContractError::StakeLockedByPoll {});
+    }
+
+    staker_info.amount -= amount;
+    staker_info.weight = staker_info.weight.checked_sub(amount)?;
+    state.total_weight = state.total_weight.checked_sub(amount)?;
+    state.total_staked = state.total_staked.checked_sub(amount)?;
+
+    STAKERS.save(deps.storage, &staker_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(token_asset(config.spec_token, amount).transfer_msg(&staker_addr)?)
+        .add_attributes(vec![
+            attr("action", "withdraw"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Claims the reward token earned by the sender.
+fn claim_reward(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let config = CONFIG.load(deps.storage)?;
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let mut staker_info = load_staker_info(deps.storage, &mut state, &staker_addr, time)?;
+
+    let amount = staker_info.pending_reward;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    staker_info.pending_reward = Uint128::zero();
+
+    STAKERS.save(deps.storage, &staker_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(config.reward_token.with_balance(amount).transfer_msg(&staker_addr)?)
+        .add_attributes(vec![
+            attr("action", "claim_reward"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Extends a lock of the sender, the weight is reset from the new lock duration.
+fn extend_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    index: u32,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let end = calc_lock_end(&env, duration)?;
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let mut staker_info = load_staker_info(deps.storage, &mut state, &staker_addr, time)?;
+
+    let old_lock = staker_info.locks
+        .get(index as usize)
+        .cloned()
+        .ok_or(ContractError::LockNotFound { index })?;
+    if end <= old_lock.end {
+        return Err(ContractError::LockNotExtended {});
+    }
+
+    let lock = Lock::new(old_lock.amount, end, time);
+    state.remove_lock(deps.storage, &old_lock, time)?;
+    state.add_lock(deps.storage, &lock, time)?;
+    staker_info.weight = staker_info.weight - old_lock.weight + lock.weight;
+    state.total_weight = state.total_weight - old_lock.weight + lock.weight;
+
+    let attributes = vec![
+        attr("action", "extend_lock"),
+        attr("staker_addr", &staker_addr),
+        attr("lock_end", lock.end.to_string()),
+        attr("weight", lock.weight),
+    ];
+    staker_info.locks[index as usize] = lock;
+
+    STAKERS.save(deps.storage, &staker_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// ## Description
+/// Merges locks of the sender into one lock ending at the latest end, the weight is reset from
+/// the remaining lock duration.
+fn merge_locks(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut indexes: Vec<u32>,
+) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let mut staker_info = load_staker_info(deps.storage, &mut state, &staker_addr, time)?;
+
+    indexes.sort_unstable();
+    indexes.dedup();
+    if indexes.len() < 2 {
+        return Err(ContractError::InvalidMerge {});
+    }
+    if let Some(index) = indexes.iter().find(|index| **index as usize >= staker_info.locks.len()) {
+        return Err(ContractError::LockNotFound { index: *index });
+    }
+
+    let mut amount = Uint128::zero();
+    let mut end = 0u64;
+    for index in indexes.into_iter().rev() {
+        let old_lock = staker_info.locks.remove(index as usize);
+        state.remove_lock(deps.storage, &old_lock, time)?;
+        staker_info.weight -= old_lock.weight;
+        state.total_weight -= old_lock.weight;
+        amount += old_lock.amount;
+        end = end.max(old_lock.end);
+    }
+
+    let lock = Lock::new(amount, end, time);
+    state.add_lock(deps.storage, &lock, time)?;
+    staker_info.weight += lock.weight;
+    state.total_weight += lock.weight;
+
+    let attributes = vec![
+        attr("action", "merge_locks"),
+        attr("staker_addr", &staker_addr),
+        attr("amount", amount),
+        attr("lock_end", lock.end.to_string()),
+        attr("weight", lock.weight),
+    ];
+    staker_info.locks.push(lock);
+
+    STAKERS.save(deps.storage, &staker_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// ## Description
+/// Releases the ended locks of the staker, the reward earned with the lock weight is settled first.
+fn release_locks(deps: DepsMut, env: Env, staker_addr: String) -> Result<Response, ContractError> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let staker_info = STAKERS.may_load(deps.storage, &staker_addr)?.unwrap_or_default();
+    if !staker_info.locks.iter().any(|lock| lock.end <= time) {
+        return Err(ContractError::NoLockEnded {});
+    }
+    let staker_info = load_staker_info(deps.storage, &mut state, &staker_addr, time)?;
+
+    STAKERS.save(deps.storage, &staker_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "release_locks"),
+        attr("staker_addr", staker_addr),
+        attr("weight", staker_info.weight),
+    ]))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::State {} => to_binary(&query_state(deps, env)?),
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
+        QueryMsg::StakerInfo { staker_addr } => to_binary(&query_staker_info(deps, env, staker_addr)?),
+        QueryMsg::BoostWeight { address } => to_binary(&query_boost_weight(deps, env, address)?),
+        QueryMsg::Poll { poll_id } => to_binary(&query_poll(deps, poll_id)?),
+        QueryMsg::Polls { start_after, limit } => to_binary(&query_polls(deps, start_after, limit)?),
+        QueryMsg::Voter { poll_id, address } => to_binary(&query_voter(deps, poll_id, address)?),
+    }
+}
+
+/// ## Description
+/// Returns the staking state.
+fn query_state(deps: Deps, env: Env) -> StdResult<StateResponse> {
+    let state = load_state(deps.storage, env.block.time.seconds())?;
+
+    Ok(StateResponse {
+        total_staked: state.total_staked,
+        total_weight: state.total_weight,
+        voting_power: state.voting_power(),
+        reward_index: state.reward_index,
+        undistributed_reward: state.undistributed_reward,
+    })
+}
+
+/// ## Description
+/// Returns the total staked SPEC, staked SPEC is not compounded so each share is one SPEC.
+fn query_exchange_rate(deps: Deps, env: Env) -> StdResult<ExchangeRateResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = load_state(deps.storage, env.block.time.seconds())?;
+
+    Ok(ExchangeRateResponse {
+        asset: AssetInfo::Token { contract_addr: config.spec_token },
+        exchange_rate: Decimal::one(),
+        total_shares: state.total_staked,
+        total_assets: state.total_staked,
+    })
+}
+
+/// ## Description
+/// Returns the staked SPEC, the locks and the pending reward of the staker.
+fn query_staker_info(deps: Deps, env: Env, staker_addr: String) -> StdResult<StakerInfoResponse> {
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    let staker_info = load_staker_info(
+        deps.storage,
+        &mut state,
+        &deps.api.addr_validate(&staker_addr)?,
+        time,
+    )?;
+
+    Ok(StakerInfoResponse {
+        staker_addr,
+        amount: staker_info.amount,
+        locks: staker_info.locks
+            .iter()
+            .map(|lock| LockResponse {
+                amount: lock.amount,
+                end: lock.end,
+                weight: lock.weight,
+                voting_power: lock.amount + lock.decay_power(time),
+            })
+            .collect(),
+        weight: staker_info.weight,
+        voting_power: staker_info.voting_power(time),
+        pending_reward: staker_info.pending_reward,
+    })
+}
+
+/// ## Description
+/// Returns the voting power of the address and the total voting power.
+fn query_boost_weight(deps: Deps, env: Env, address: String) -> StdResult<BoostWeightResponse> {
+    let time = env.block.time.seconds();
+    let state = load_state(deps.storage, time)?;
+    let staker_info = STAKERS
+        .may_load(deps.storage, &deps.api.addr_validate(&address)?)?
+        .unwrap_or_default();
+
+    Ok(BoostWeightResponse {
+        weight: staker_info.voting_power(time),
+        total_weight: state.voting_power(),
+    })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored state is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref(), &env)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored config and that the staked SPEC is held by the contract.
+fn assert_state(deps: Deps, env: &Env) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.spec_token, "spec_token")?;
+    config.reward_token.check(deps.api).map_err(|_| invalid_state("reward_token is not a valid asset"))?;
+    assert_ratio(config.quorum, "quorum")?;
+    assert_ratio(config.threshold, "threshold")?;
+
+    let state = STATE.load(deps.storage)?;
+    let spec_balance = query_token_balance(&deps.querier, &config.spec_token, &env.contract.address)?;
+    if spec_balance < state.total_staked {
+        return Err(invalid_state("staked SPEC exceeds the SPEC balance"));
+    }
+
+    Ok(())
+}
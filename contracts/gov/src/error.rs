@@ -0,0 +1,70 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes gov contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Cannot withdraw more than staked amount")]
+    WithdrawExceedStake {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Lock duration must be between {min} and {max} seconds")]
+    InvalidLockDuration { min: u64, max: u64 },
+
+    #[error("Lock not found: {index}")]
+    LockNotFound { index: u32 },
+
+    #[error("Lock end must be later than the current end")]
+    LockNotExtended {},
+
+    #[error("No lock has ended")]
+    NoLockEnded {},
+
+    #[error("At least two distinct locks are required to merge")]
+    InvalidMerge {},
+
+    #[error("Staked SPEC is locked until the voting of the polls voted on ends")]
+    StakeLockedByPoll {},
+
+    #[error("Poll deposit must be at least {0}")]
+    InsufficientDeposit(Uint128),
+
+    #[error("Poll is not in progress")]
+    PollNotInProgress {},
+
+    #[error("Voting period has ended")]
+    VotingPeriodEnded {},
+
+    #[error("Voting period has not ended")]
+    VotingPeriodNotEnded {},
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("No voting power")]
+    NoVotingPower {},
+
+    #[error("Poll has not passed")]
+    PollNotPassed {},
+
+    #[error("Poll can be executed from {0}")]
+    PollNotEffective(u64),
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
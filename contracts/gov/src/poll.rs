@@ -0,0 +1,266 @@
+use cosmwasm_std::{
+    attr, to_binary, Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw_storage_plus::Bound;
+
+use crate::contract::{load_staker_info, load_state};
+use crate::error::ContractError;
+use crate::state::{Poll, Voter, CONFIG, POLLS, STAKERS, STATE, VOTERS};
+use spectrum::gov::{
+    PollExecuteMsg, PollResponse, PollStatus, PollsResponse, VoteOption, VoterResponse,
+};
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Creates a poll with the deposited SPEC, the voting ends after the voting period.
+#[allow(clippy::too_many_arguments)]
+pub fn create_poll(
+    deps: DepsMut,
+    env: Env,
+    creator: Addr,
+    deposit_amount: Uint128,
+    title: String,
+    description: String,
+    link: Option<String>,
+    execute_msgs: Vec<PollExecuteMsg>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if deposit_amount < config.proposal_deposit {
+        return Err(ContractError::InsufficientDeposit(config.proposal_deposit));
+    }
+    for execute_msg in execute_msgs.iter() {
+        deps.api.addr_validate(&execute_msg.contract)?;
+    }
+
+    let time = env.block.time.seconds();
+    let mut state = load_state(deps.storage, time)?;
+    state.poll_count += 1;
+    let poll_id = state.poll_count;
+
+    let poll = Poll {
+        creator,
+        status: PollStatus::InProgress,
+        title,
+        description,
+        link,
+        execute_msgs,
+        deposit_amount,
+        start_time: time,
+        end_time: time + config.voting_period,
+        yes_votes: Uint128::zero(),
+        no_votes: Uint128::zero(),
+        total_voting_power: state.voting_power(),
+    };
+    POLLS.save(deps.storage, poll_id, &poll)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "create_poll"),
+        attr("creator", poll.creator),
+        attr("poll_id", poll_id.to_string()),
+        attr("end_time", poll.end_time.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Votes on the poll with the voting power of the sender at the poll start.
+pub fn cast_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    vote: VoteOption,
+) -> Result<Response, ContractError> {
+    let voter_addr = info.sender;
+
+    let time = env.block.time.seconds();
+    let mut poll = POLLS.load(deps.storage, poll_id)?;
+    if poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+    if time >= poll.end_time {
+        return Err(ContractError::VotingPeriodEnded {});
+    }
+    if VOTERS.has(deps.storage, (poll_id, &voter_addr)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    // the stake at the end of the block the poll was created in, so stake added later cannot vote
+    let balance = STAKERS
+        .may_load_at_height(deps.storage, &voter_addr, poll.start_time + 1)?
+        .unwrap_or_default()
+        .voting_power(poll.start_time);
+    if balance.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    let mut state = load_state(deps.storage, time)?;
+    let mut staker_info = load_staker_info(deps.storage, &mut state, &voter_addr, time)?;
+    staker_info.vote_end = staker_info.vote_end.max(poll.end_time);
+
+    match vote {
+        VoteOption::Yes => poll.yes_votes += balance,
+        VoteOption::No => poll.no_votes += balance,
+    }
+
+    let attributes = vec![
+        attr("action", "cast_vote"),
+        attr("poll_id", poll_id.to_string()),
+        attr("voter", &voter_addr),
+        attr("vote", if vote == VoteOption::Yes { "yes" } else { "no" }),
+        attr("balance", balance),
+    ];
+
+    VOTERS.save(deps.storage, (poll_id, &voter_addr), &Voter { vote, balance })?;
+    POLLS.save(deps.storage, poll_id, &poll)?;
+    STAKERS.save(deps.storage, &voter_addr, &staker_info, time)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(attributes))
+}
+
+/// ## Description
+/// Tallies the votes of the poll after the end of voting. The poll passes if the quorum is
+/// reached and the share of yes votes is above the threshold. The deposit is returned to the
+/// creator if the quorum is reached, otherwise it is burned.
+pub fn end_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut poll = POLLS.load(deps.storage, poll_id)?;
+    if poll.status != PollStatus::InProgress {
+        return Err(ContractError::PollNotInProgress {});
+    }
+    if env.block.time.seconds() < poll.end_time {
+        return Err(ContractError::VotingPeriodNotEnded {});
+    }
+
+    let total_votes = poll.yes_votes + poll.no_votes;
+    let quorum_reached = !total_votes.is_zero()
+        && Decimal::from_ratio(total_votes, poll.total_voting_power.max(total_votes)) >= config.quorum;
+    poll.status = if quorum_reached
+        && Decimal::from_ratio(poll.yes_votes, total_votes) > config.threshold
+    {
+        PollStatus::Passed
+    } else {
+        PollStatus::Rejected
+    };
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !poll.deposit_amount.is_zero() {
+        let msg = if quorum_reached {
+            Cw20ExecuteMsg::Transfer {
+                recipient: poll.creator.to_string(),
+                amount: poll.deposit_amount,
+            }
+        } else {
+            Cw20ExecuteMsg::Burn { amount: poll.deposit_amount }
+        };
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.spec_token.to_string(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }));
+    }
+
+    POLLS.save(deps.storage, poll_id, &poll)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "end_poll"),
+            attr("poll_id", poll_id.to_string()),
+            attr("passed", (poll.status == PollStatus::Passed).to_string()),
+            attr("yes_votes", poll.yes_votes),
+            attr("no_votes", poll.no_votes),
+        ]))
+}
+
+/// ## Description
+/// Executes the messages of the passed poll after the effective delay.
+pub fn execute_poll(deps: DepsMut, env: Env, poll_id: u64) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut poll = POLLS.load(deps.storage, poll_id)?;
+    if poll.status != PollStatus::Passed {
+        return Err(ContractError::PollNotPassed {});
+    }
+    let effective_time = poll.end_time + config.effective_delay;
+    if env.block.time.seconds() < effective_time {
+        return Err(ContractError::PollNotEffective(effective_time));
+    }
+
+    poll.status = PollStatus::Executed;
+    POLLS.save(deps.storage, poll_id, &poll)?;
+
+    let messages: Vec<CosmosMsg> = poll.execute_msgs
+        .into_iter()
+        .map(|execute_msg| CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: execute_msg.contract,
+            msg: execute_msg.msg,
+            funds: vec![],
+        }))
+        .collect();
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "execute_poll"),
+            attr("poll_id", poll_id.to_string()),
+        ]))
+}
+
+fn to_poll_response(id: u64, poll: Poll) -> PollResponse {
+    PollResponse {
+        id,
+        creator: poll.creator.to_string(),
+        status: poll.status,
+        title: poll.title,
+        description: poll.description,
+        link: poll.link,
+        execute_msgs: poll.execute_msgs,
+        deposit_amount: poll.deposit_amount,
+        start_time: poll.start_time,
+        end_time: poll.end_time,
+        yes_votes: poll.yes_votes,
+        no_votes: poll.no_votes,
+        total_voting_power: poll.total_voting_power,
+    }
+}
+
+/// ## Description
+/// Returns the poll.
+pub fn query_poll(deps: Deps, poll_id: u64) -> StdResult<PollResponse> {
+    Ok(to_poll_response(poll_id, POLLS.load(deps.storage, poll_id)?))
+}
+
+/// ## Description
+/// Returns the polls ordered by id.
+pub fn query_polls(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PollsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let polls = POLLS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, poll)| to_poll_response(id, poll)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(PollsResponse { polls })
+}
+
+/// ## Description
+/// Returns the vote of the voter on the poll.
+pub fn query_voter(deps: Deps, poll_id: u64, address: String) -> StdResult<VoterResponse> {
+    let voter = VOTERS.load(deps.storage, (poll_id, &deps.api.addr_validate(&address)?))?;
+
+    Ok(VoterResponse {
+        vote: voter.vote,
+        balance: voter.balance,
+    })
+}
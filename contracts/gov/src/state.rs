@@ -0,0 +1,274 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::gov::{PollExecuteMsg, PollStatus, VoteOption};
+
+/// The number of seconds in a week, lock ends are rounded down to a week
+pub const WEEK: u64 = 7 * 86400;
+/// The min lock duration in seconds
+pub const MIN_LOCK_TIME: u64 = WEEK;
+/// The max lock duration in seconds, a lock of the max duration doubles the voting power
+pub const MAX_LOCK_TIME: u64 = 104 * WEEK;
+
+/// Returns the start of the week of the time
+pub fn week_floor(time: u64) -> u64 {
+    time / WEEK * WEEK
+}
+
+/// This structure stores the main parameter for the gov contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// The SPEC token contract address
+    pub spec_token: Addr,
+    /// The fee token shared by the stakers
+    pub reward_token: AssetInfo,
+    /// The min share of the total voting power voting on a poll
+    pub quorum: Decimal,
+    /// The min share of yes votes for a poll to pass
+    pub threshold: Decimal,
+    /// The number of seconds a poll is open for voting
+    pub voting_period: u64,
+    /// The number of seconds after the end of voting a passed poll can be executed
+    pub effective_delay: u64,
+    /// The SPEC deposit required to create a poll
+    pub proposal_deposit: Uint128,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// This structure stores the staking state of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct State {
+    /// The total staked SPEC, including locked SPEC
+    pub total_staked: Uint128,
+    /// The total weight sharing the reward token
+    pub total_weight: Uint128,
+    /// The cumulative reward token per weight
+    pub reward_index: Decimal,
+    /// The reward token deposited while nothing was staked
+    pub undistributed_reward: Uint128,
+    /// The decaying voting power of the active locks at the checkpoint time
+    pub lock_bias: Decimal,
+    /// The voting power decay per second of the active locks
+    pub lock_slope: Decimal,
+    /// The block time in seconds of the last voting power checkpoint
+    pub checkpoint_time: u64,
+    /// The number of polls created
+    #[serde(default)] pub poll_count: u64,
+}
+
+impl State {
+    /// Shares the deposited reward token with the stakers, the reward is kept until something is
+    /// staked
+    pub fn distribute(&mut self, amount: Uint128) {
+        let amount = amount + self.undistributed_reward;
+        if self.total_weight.is_zero() {
+            self.undistributed_reward = amount;
+        } else {
+            self.reward_index += Decimal::from_ratio(amount, self.total_weight);
+            self.undistributed_reward = Uint128::zero();
+        }
+    }
+
+    /// Decays the voting power of the active locks up to the time, the slope of the locks ending
+    /// on each week boundary is removed when it is passed
+    pub fn checkpoint(&mut self, storage: &dyn Storage, time: u64) -> StdResult<()> {
+        while self.checkpoint_time < time {
+            if self.lock_slope.is_zero() {
+                self.lock_bias = Decimal::zero();
+                self.checkpoint_time = time;
+                break;
+            }
+            let next = (week_floor(self.checkpoint_time) + WEEK).min(time);
+            let decay = Decimal::from_ratio(next - self.checkpoint_time, 1u64) * self.lock_slope;
+            self.lock_bias = self.lock_bias - decay.min(self.lock_bias);
+            if week_floor(next) == next {
+                let slope_change = SLOPE_CHANGES.may_load(storage, next)?.unwrap_or_default();
+                self.lock_slope = self.lock_slope - slope_change.min(self.lock_slope);
+            }
+            self.checkpoint_time = next;
+        }
+        Ok(())
+    }
+
+    /// Returns the total voting power, must be called after the checkpoint
+    pub fn voting_power(&self) -> Uint128 {
+        self.total_staked + Uint128::new(1) * self.lock_bias
+    }
+
+    /// Adds the decaying voting power of the lock
+    pub fn add_lock(&mut self, storage: &mut dyn Storage, lock: &Lock, time: u64) -> StdResult<()> {
+        if lock.end > time {
+            self.lock_bias += lock.decay_bias(time);
+            self.lock_slope += lock.slope();
+            SLOPE_CHANGES.update(storage, lock.end, |it| -> StdResult<_> {
+                Ok(it.unwrap_or_default() + lock.slope())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Removes the decaying voting power of the lock
+    pub fn remove_lock(&mut self, storage: &mut dyn Storage, lock: &Lock, time: u64) -> StdResult<()> {
+        if lock.end > time {
+            let decay_bias = lock.decay_bias(time);
+            self.lock_bias = self.lock_bias - decay_bias.min(self.lock_bias);
+            self.lock_slope = self.lock_slope - lock.slope().min(self.lock_slope);
+            SLOPE_CHANGES.update(storage, lock.end, |it| -> StdResult<_> {
+                let slope_change = it.unwrap_or_default();
+                Ok(slope_change - lock.slope().min(slope_change))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Stores the staking state at the given key
+pub const STATE: Item<State> = Item::new("state");
+
+/// Stores the voting power slope of the locks ending at each week boundary
+pub const SLOPE_CHANGES: Map<u64, Decimal> = Map::new("slope_changes");
+
+/// This structure stores SPEC locked until the end time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Lock {
+    /// The locked SPEC
+    pub amount: Uint128,
+    /// The block time in seconds the lock ends, at a week boundary
+    pub end: u64,
+    /// The weight sharing the reward token, set from the lock duration when the lock is created,
+    /// extended or merged
+    pub weight: Uint128,
+}
+
+impl Lock {
+    pub fn new(amount: Uint128, end: u64, time: u64) -> Lock {
+        let mut lock = Lock { amount, end, weight: Uint128::zero() };
+        lock.weight = amount + lock.decay_power(time);
+        lock
+    }
+
+    /// Returns the voting power decay per second
+    pub fn slope(&self) -> Decimal {
+        Decimal::from_ratio(self.amount, MAX_LOCK_TIME)
+    }
+
+    /// Returns the voting power added by the lock on top of the locked SPEC, decaying to zero at
+    /// the end of the lock
+    pub fn decay_power(&self, time: u64) -> Uint128 {
+        self.amount.multiply_ratio(self.end.saturating_sub(time), MAX_LOCK_TIME)
+    }
+
+    /// Returns the decaying voting power without rounding, tracked in the total voting power
+    pub fn decay_bias(&self, time: u64) -> Decimal {
+        Decimal::from_ratio(
+            self.amount.checked_mul(Uint128::from(self.end.saturating_sub(time))).unwrap_or(Uint128::MAX),
+            MAX_LOCK_TIME,
+        )
+    }
+}
+
+/// This structure stores the stake of a staker.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct StakerInfo {
+    /// The staked SPEC withdrawable at any time
+    pub amount: Uint128,
+    /// The SPEC locks of the staker
+    pub locks: Vec<Lock>,
+    /// The weight sharing the reward token
+    pub weight: Uint128,
+    /// The reward index of the last settlement
+    pub reward_index: Decimal,
+    /// The reward token earned and not claimed yet
+    pub pending_reward: Uint128,
+    /// The block time in seconds the voting of the polls voted on ends, the withdrawable SPEC is
+    /// locked until then
+    #[serde(default)] pub vote_end: u64,
+}
+
+impl StakerInfo {
+    /// Adds the reward token earned since the last settlement to the pending reward, must be
+    /// called before changing the weight
+    pub fn settle(&mut self, state: &State) {
+        self.pending_reward += self.weight * (state.reward_index - self.reward_index);
+        self.reward_index = state.reward_index;
+    }
+
+    /// Moves the SPEC of the ended locks to the withdrawable stake, must be called after the
+    /// settlement
+    pub fn release_locks(&mut self, state: &mut State, time: u64) {
+        let (ended, active): (Vec<Lock>, Vec<Lock>) = self.locks
+            .drain(..)
+            .partition(|lock| lock.end <= time);
+        self.locks = active;
+        for lock in ended {
+            self.amount += lock.amount;
+            self.weight = self.weight - lock.weight + lock.amount;
+            state.total_weight = state.total_weight - lock.weight + lock.amount;
+        }
+    }
+
+    /// Returns the voting power of the staker
+    pub fn voting_power(&self, time: u64) -> Uint128 {
+        self.locks.iter().fold(self.amount, |acc, lock| {
+            acc + lock.amount + lock.decay_power(time)
+        })
+    }
+}
+
+/// Stores the stake of each staker, checkpointed by block time to vote with the stake at the
+/// poll start
+pub const STAKERS: SnapshotMap<&Addr, StakerInfo> = SnapshotMap::new(
+    "stakers",
+    "stakers__checkpoints",
+    "stakers__changelog",
+    Strategy::EveryBlock,
+);
+
+/// This structure stores a poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Poll {
+    /// The address created the poll
+    pub creator: Addr,
+    /// The poll status
+    pub status: PollStatus,
+    /// The poll title
+    pub title: String,
+    /// The poll description
+    pub description: String,
+    /// The link to the poll details
+    pub link: Option<String>,
+    /// The messages executed by the contract when the poll passes
+    pub execute_msgs: Vec<PollExecuteMsg>,
+    /// The SPEC deposit of the poll
+    pub deposit_amount: Uint128,
+    /// The block time in seconds the poll was created, votes are weighted by the voting power
+    /// at this time
+    pub start_time: u64,
+    /// The block time in seconds the voting ends
+    pub end_time: u64,
+    /// The voting power voted yes
+    pub yes_votes: Uint128,
+    /// The voting power voted no
+    pub no_votes: Uint128,
+    /// The total voting power when the poll was created
+    pub total_voting_power: Uint128,
+}
+
+/// Stores the polls by id
+pub const POLLS: Map<u64, Poll> = Map::new("polls");
+
+/// This structure stores the vote of a voter on a poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Voter {
+    /// The vote
+    pub vote: VoteOption,
+    /// The voting power voted
+    pub balance: Uint128,
+}
+
+/// Stores the votes by poll id and voter
+pub const VOTERS: Map<(u64, &Addr), Voter> = Map::new("voters");
@@ -0,0 +1,781 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Decimal, Env, OwnedDeps, Response, StdError,
+    Timestamp, Uint128, WasmMsg,
+};
+use astroport::asset::AssetInfo;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use spectrum::adapters::gov::BoostWeightResponse;
+use spectrum::vault::ExchangeRateResponse;
+use spectrum::gov::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockResponse, PollExecuteMsg, PollResponse, PollStatus,
+    PollsResponse, QueryMsg, StakerInfoResponse, StateResponse, VoteOption, VoterResponse,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::{Config, MAX_LOCK_TIME, WEEK};
+
+const SPEC_TOKEN: &str = "spec_token";
+const STABLECOIN: &str = "ibc/stablecoin";
+const FEE_COLLECTOR: &str = "fee_collector";
+const USER_1: &str = "user_1";
+const USER_2: &str = "user_2";
+const USER_3: &str = "user_3";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    stake(&mut deps)?;
+    deposit(&mut deps)?;
+    withdraw(&mut deps)?;
+    claim_reward(&mut deps)?;
+    lock(&mut deps)?;
+    poll(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn stake_msg(sender: &str, amount: u128, staker_addr: Option<&str>) -> Result<ExecuteMsg, ContractError> {
+    Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Stake {
+            staker_addr: staker_addr.map(|it| it.to_string()),
+            lock_duration: None,
+        })?,
+    }))
+}
+
+fn lock_msg(sender: &str, amount: u128, lock_duration: u64) -> Result<ExecuteMsg, ContractError> {
+    Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Stake {
+            staker_addr: None,
+            lock_duration: Some(lock_duration),
+        })?,
+    }))
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn query_staker_info(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    staker_addr: &str,
+) -> Result<StakerInfoResponse, ContractError> {
+    query_staker_info_at(deps, mock_env(), staker_addr)
+}
+
+fn query_staker_info_at(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: Env,
+    staker_addr: &str,
+) -> Result<StakerInfoResponse, ContractError> {
+    Ok(from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::StakerInfo { staker_addr: staker_addr.to_string() },
+    )?)?)
+}
+
+fn query_state_at(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    env: Env,
+) -> Result<StateResponse, ContractError> {
+    Ok(from_binary(&query(deps.as_ref(), env, QueryMsg::State {})?)?)
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        spec_token: SPEC_TOKEN.to_string(),
+        reward_token: AssetInfo::NativeToken { denom: STABLECOIN.to_string() },
+        quorum: Decimal::percent(30),
+        threshold: Decimal::percent(50),
+        voting_period: 100000,
+        effective_delay: 10000,
+        proposal_deposit: Uint128::from(1000u128),
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            spec_token: Addr::unchecked(SPEC_TOKEN),
+            reward_token: AssetInfo::NativeToken { denom: STABLECOIN.to_string() },
+            quorum: Decimal::percent(30),
+            threshold: Decimal::percent(50),
+            voting_period: 100000,
+            effective_delay: 10000,
+            proposal_deposit: Uint128::from(1000u128),
+        }
+    );
+
+    Ok(())
+}
+
+fn stake(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // reward deposited before anything is staked is kept for the first stakers
+    let info = mock_info(FEE_COLLECTOR, &[coin(1000, STABLECOIN)]);
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {})?;
+
+    // only SPEC token can be staked
+    let info = mock_info("token", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, stake_msg(USER_1, 100, None)?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, stake_msg(USER_1, 0, None)?);
+    assert_error(res, "Invalid zero amount");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, stake_msg(USER_1, 100, None)?)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "stake"),
+            attr("staker_addr", USER_1),
+            attr("amount", "100"),
+        ]
+    );
+
+    // stake on behalf of another staker
+    let info = mock_info(SPEC_TOKEN, &[]);
+    execute(deps.as_mut(), mock_env(), info, stake_msg(USER_1, 300, Some(USER_2))?)?;
+
+    let res: StateResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::State {})?)?;
+    assert_eq!(
+        res,
+        StateResponse {
+            total_staked: Uint128::from(400u128),
+            total_weight: Uint128::from(400u128),
+            voting_power: Uint128::from(400u128),
+            reward_index: Decimal::from_ratio(10u128, 1u128),
+            undistributed_reward: Uint128::zero(),
+        }
+    );
+
+    let res: ExchangeRateResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ExchangeRate {})?)?;
+    assert_eq!(
+        res,
+        ExchangeRateResponse {
+            asset: AssetInfo::Token { contract_addr: Addr::unchecked(SPEC_TOKEN) },
+            exchange_rate: Decimal::one(),
+            total_shares: Uint128::from(400u128),
+            total_assets: Uint128::from(400u128),
+        }
+    );
+
+    assert_eq!(
+        query_staker_info(deps, USER_1)?,
+        StakerInfoResponse {
+            staker_addr: USER_1.to_string(),
+            amount: Uint128::from(100u128),
+            locks: vec![],
+            weight: Uint128::from(100u128),
+            voting_power: Uint128::from(100u128),
+            pending_reward: Uint128::from(1000u128),
+        }
+    );
+    assert_eq!(query_staker_info(deps, USER_2)?.pending_reward, Uint128::zero());
+
+    let res: BoostWeightResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::BoostWeight { address: USER_2.to_string() },
+    )?)?;
+    assert_eq!(
+        res,
+        BoostWeightResponse {
+            weight: Uint128::from(300u128),
+            total_weight: Uint128::from(400u128),
+        }
+    );
+
+    Ok(())
+}
+
+fn deposit(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // the reward token is native
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: FEE_COLLECTOR.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {})?,
+    });
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(FEE_COLLECTOR, &[coin(100, "uluna")]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {});
+    assert_error(res, "Invalid zero amount");
+
+    let info = mock_info(FEE_COLLECTOR, &[coin(2000, STABLECOIN)]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {})?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "deposit"), attr("amount", "2000")]
+    );
+
+    assert_eq!(query_staker_info(deps, USER_1)?.pending_reward, Uint128::from(1500u128));
+    assert_eq!(query_staker_info(deps, USER_2)?.pending_reward, Uint128::from(1500u128));
+
+    Ok(())
+}
+
+fn withdraw(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::Withdraw { amount: Some(Uint128::from(301u128)) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Cannot withdraw more than staked amount");
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::Withdraw { amount: Some(Uint128::from(200u128)) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: SPEC_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER_2.to_string(),
+                amount: Uint128::from(200u128),
+            })?,
+            funds: vec![],
+        })]
+    );
+
+    // 1000 reward is shared by 100 + 100 staked SPEC
+    let info = mock_info(FEE_COLLECTOR, &[coin(1000, STABLECOIN)]);
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {})?;
+    assert_eq!(query_staker_info(deps, USER_1)?.pending_reward, Uint128::from(2000u128));
+    assert_eq!(
+        query_staker_info(deps, USER_2)?,
+        StakerInfoResponse {
+            staker_addr: USER_2.to_string(),
+            amount: Uint128::from(100u128),
+            locks: vec![],
+            weight: Uint128::from(100u128),
+            voting_power: Uint128::from(100u128),
+            pending_reward: Uint128::from(2000u128),
+        }
+    );
+
+    // withdraw all
+    let info = mock_info(USER_2, &[]);
+    execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Withdraw { amount: None })?;
+    assert_eq!(query_staker_info(deps, USER_2)?.amount, Uint128::zero());
+
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Withdraw { amount: None });
+    assert_error(res, "Invalid zero amount");
+
+    Ok(())
+}
+
+fn claim_reward(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ClaimReward {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_2.to_string(),
+            amount: vec![coin(2000, STABLECOIN)],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim_reward"),
+            attr("staker_addr", USER_2),
+            attr("amount", "2000"),
+        ]
+    );
+
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, ExecuteMsg::ClaimReward {});
+    assert_error(res, "Nothing to claim");
+
+    Ok(())
+}
+
+fn lock(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // start at a week boundary, USER_1 keeps 100 flexible SPEC
+    let start = WEEK * 2600;
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(start), info, lock_msg(USER_2, 1000, WEEK - 1)?);
+    assert_error(res, "Lock duration must be between 604800 and 62899200 seconds");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(start), info, lock_msg(USER_2, 1000, MAX_LOCK_TIME + 1)?);
+    assert_error(res, "Lock duration must be between 604800 and 62899200 seconds");
+
+    // a max lock doubles the weight
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(start), info, lock_msg(USER_2, 1000, MAX_LOCK_TIME)?)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "stake"),
+            attr("staker_addr", USER_2),
+            attr("amount", "1000"),
+            attr("lock_end", (start + 104 * WEEK).to_string()),
+            attr("weight", "2000"),
+        ]
+    );
+
+    // lock end is rounded down to a week
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(start), info, lock_msg(USER_2, 500, 52 * WEEK + 100)?)?;
+    assert_eq!(res.attributes[3], attr("lock_end", (start + 52 * WEEK).to_string()));
+    assert_eq!(res.attributes[4], attr("weight", "750"));
+
+    assert_eq!(
+        query_state_at(deps, env_at(start))?,
+        StateResponse {
+            total_staked: Uint128::from(1600u128),
+            total_weight: Uint128::from(2850u128),
+            voting_power: Uint128::from(2850u128),
+            reward_index: Decimal::from_ratio(20u128, 1u128),
+            undistributed_reward: Uint128::zero(),
+        }
+    );
+
+    // locked SPEC cannot be withdrawn
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env_at(start), info, ExecuteMsg::Withdraw { amount: None });
+    assert_error(res, "Invalid zero amount");
+
+    // reward is shared by weight
+    let info = mock_info(FEE_COLLECTOR, &[coin(2850, STABLECOIN)]);
+    execute(deps.as_mut(), env_at(start), info, ExecuteMsg::Deposit {})?;
+    assert_eq!(query_staker_info_at(deps, env_at(start), USER_2)?.pending_reward, Uint128::from(2750u128));
+
+    // voting power decays after half a year
+    let time = start + 26 * WEEK;
+    assert_eq!(
+        query_staker_info_at(deps, env_at(time), USER_2)?,
+        StakerInfoResponse {
+            staker_addr: USER_2.to_string(),
+            amount: Uint128::zero(),
+            locks: vec![
+                LockResponse {
+                    amount: Uint128::from(1000u128),
+                    end: start + 104 * WEEK,
+                    weight: Uint128::from(2000u128),
+                    voting_power: Uint128::from(1750u128),
+                },
+                LockResponse {
+                    amount: Uint128::from(500u128),
+                    end: start + 52 * WEEK,
+                    weight: Uint128::from(750u128),
+                    voting_power: Uint128::from(625u128),
+                },
+            ],
+            weight: Uint128::from(2750u128),
+            voting_power: Uint128::from(2375u128),
+            pending_reward: Uint128::from(2750u128),
+        }
+    );
+    let res: BoostWeightResponse = from_binary(&query(
+        deps.as_ref(),
+        env_at(time),
+        QueryMsg::BoostWeight { address: USER_2.to_string() },
+    )?)?;
+    assert_eq!(
+        res,
+        BoostWeightResponse {
+            weight: Uint128::from(2375u128),
+            total_weight: Uint128::from(2475u128),
+        }
+    );
+
+    // extend lock
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::ExtendLock { index: 2, duration: MAX_LOCK_TIME };
+    let res = execute(deps.as_mut(), env_at(time), info, msg);
+    assert_error(res, "Lock not found: 2");
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::ExtendLock { index: 0, duration: 52 * WEEK };
+    let res = execute(deps.as_mut(), env_at(time), info, msg);
+    assert_error(res, "Lock end must be later than the current end");
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::ExtendLock { index: 1, duration: MAX_LOCK_TIME };
+    let res = execute(deps.as_mut(), env_at(time), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "extend_lock"),
+            attr("staker_addr", USER_2),
+            attr("lock_end", (start + 130 * WEEK).to_string()),
+            attr("weight", "1000"),
+        ]
+    );
+    let res = query_state_at(deps, env_at(time))?;
+    assert_eq!(res.total_weight, Uint128::from(3100u128));
+    assert_eq!(res.voting_power, Uint128::from(2850u128));
+
+    // merge locks
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::MergeLocks { indexes: vec![0, 0] };
+    let res = execute(deps.as_mut(), env_at(time), info, msg);
+    assert_error(res, "At least two distinct locks are required to merge");
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::MergeLocks { indexes: vec![0, 2] };
+    let res = execute(deps.as_mut(), env_at(time), info, msg);
+    assert_error(res, "Lock not found: 2");
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::MergeLocks { indexes: vec![1, 0] };
+    let res = execute(deps.as_mut(), env_at(time), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "merge_locks"),
+            attr("staker_addr", USER_2),
+            attr("amount", "1500"),
+            attr("lock_end", (start + 130 * WEEK).to_string()),
+            attr("weight", "3000"),
+        ]
+    );
+    let res = query_state_at(deps, env_at(time))?;
+    assert_eq!(res.total_weight, Uint128::from(3100u128));
+    assert_eq!(res.voting_power, Uint128::from(3100u128));
+
+    // ended lock is released to the withdrawable stake
+    let time = start + 130 * WEEK;
+    assert_eq!(
+        query_state_at(deps, env_at(time))?.voting_power,
+        Uint128::from(1600u128)
+    );
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::ReleaseLocks { staker_addr: USER_2.to_string() };
+    let res = execute(deps.as_mut(), env_at(time - 1), info, msg);
+    assert_error(res, "No lock has ended");
+
+    // anyone can release the ended lock so the lock bonus stops earning reward
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::ReleaseLocks { staker_addr: USER_2.to_string() };
+    let res = execute(deps.as_mut(), env_at(time), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "release_locks"),
+            attr("staker_addr", USER_2),
+            attr("weight", "1500"),
+        ]
+    );
+    assert_eq!(query_state_at(deps, env_at(time))?.total_weight, Uint128::from(1600u128));
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::Withdraw { amount: Some(Uint128::from(1500u128)) };
+    execute(deps.as_mut(), env_at(time), info, msg)?;
+    let res = query_staker_info_at(deps, env_at(time), USER_2)?;
+    assert_eq!(res.amount, Uint128::zero());
+    assert_eq!(res.locks, vec![]);
+    assert_eq!(res.weight, Uint128::zero());
+    assert_eq!(res.pending_reward, Uint128::from(2750u128));
+    assert_eq!(
+        query_state_at(deps, env_at(time))?,
+        StateResponse {
+            total_staked: Uint128::from(100u128),
+            total_weight: Uint128::from(100u128),
+            voting_power: Uint128::from(100u128),
+            reward_index: Decimal::from_ratio(21u128, 1u128),
+            undistributed_reward: Uint128::zero(),
+        }
+    );
+
+    Ok(())
+}
+
+fn poll_msg(sender: &str, amount: u128, execute_msgs: Vec<PollExecuteMsg>) -> Result<ExecuteMsg, ContractError> {
+    Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::CreatePoll {
+            title: "title".to_string(),
+            description: "description".to_string(),
+            link: None,
+            execute_msgs,
+        })?,
+    }))
+}
+
+fn poll(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // USER_1 has 100 flexible SPEC, USER_2 locks 300 SPEC for 2 years
+    let time = WEEK * 2730;
+    let info = mock_info(SPEC_TOKEN, &[]);
+    execute(deps.as_mut(), env_at(time), info, lock_msg(USER_2, 300, MAX_LOCK_TIME)?)?;
+
+    let update_config_msg = to_binary(&ExecuteMsg::UpdateConfig {
+        quorum: None,
+        threshold: None,
+        voting_period: None,
+        effective_delay: None,
+        proposal_deposit: Some(Uint128::from(2000u128)),
+    })?;
+    let execute_msgs = vec![PollExecuteMsg {
+        contract: MOCK_CONTRACT_ADDR.to_string(),
+        msg: update_config_msg.clone(),
+    }];
+
+    // create poll
+    let info = mock_info("token", &[]);
+    let res = execute(deps.as_mut(), env_at(time), info, poll_msg(USER_1, 1000, execute_msgs.clone())?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(time), info, poll_msg(USER_1, 999, execute_msgs.clone())?);
+    assert_error(res, "Poll deposit must be at least 1000");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(time), info, poll_msg(USER_1, 1000, execute_msgs.clone())?)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "create_poll"),
+            attr("creator", USER_1),
+            attr("poll_id", "1"),
+            attr("end_time", (time + 100000).to_string()),
+        ]
+    );
+
+    // vote
+    let info = mock_info(USER_3, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 1, vote: VoteOption::Yes };
+    let res = execute(deps.as_mut(), env_at(time), info, msg);
+    assert_error(res, "No voting power");
+
+    let info = mock_info(USER_2, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 1, vote: VoteOption::Yes };
+    let res = execute(deps.as_mut(), env_at(time), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "cast_vote"),
+            attr("poll_id", "1"),
+            attr("voter", USER_2),
+            attr("vote", "yes"),
+            attr("balance", "600"),
+        ]
+    );
+
+    // stake added after the poll start does not vote
+    let info = mock_info(SPEC_TOKEN, &[]);
+    execute(deps.as_mut(), env_at(time + 1), info, stake_msg(USER_3, 100, None)?)?;
+    let info = mock_info(SPEC_TOKEN, &[]);
+    execute(deps.as_mut(), env_at(time + 1), info, stake_msg(USER_1, 100, None)?)?;
+
+    let info = mock_info(USER_3, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 1, vote: VoteOption::Yes };
+    let res = execute(deps.as_mut(), env_at(time + 1), info, msg);
+    assert_error(res, "No voting power");
+
+    // voting power is not decayed after the poll start
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 1, vote: VoteOption::No };
+    execute(deps.as_mut(), env_at(time + 50000), info, msg)?;
+
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 1, vote: VoteOption::No };
+    let res = execute(deps.as_mut(), env_at(time), info, msg);
+    assert_error(res, "Already voted");
+
+    let res: VoterResponse = from_binary(&query(
+        deps.as_ref(),
+        env_at(time),
+        QueryMsg::Voter { poll_id: 1, address: USER_1.to_string() },
+    )?)?;
+    assert_eq!(
+        res,
+        VoterResponse {
+            vote: VoteOption::No,
+            balance: Uint128::from(100u128),
+        }
+    );
+
+    // voted SPEC cannot be withdrawn until the voting ends
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(time), info, ExecuteMsg::Withdraw { amount: None });
+    assert_error(res, "Staked SPEC is locked until the voting of the polls voted on ends");
+
+    // end poll
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 99999), info, ExecuteMsg::EndPoll { poll_id: 1 });
+    assert_error(res, "Voting period has not ended");
+
+    let info = mock_info(USER_3, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 1, vote: VoteOption::Yes };
+    let res = execute(deps.as_mut(), env_at(time + 100000), info, msg);
+    assert_error(res, "Voting period has ended");
+
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 100000), info, ExecuteMsg::EndPoll { poll_id: 1 })?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: SPEC_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER_1.to_string(),
+                amount: Uint128::from(1000u128),
+            })?,
+            funds: vec![],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "end_poll"),
+            attr("poll_id", "1"),
+            attr("passed", "true"),
+            attr("yes_votes", "600"),
+            attr("no_votes", "100"),
+        ]
+    );
+
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 100000), info, ExecuteMsg::EndPoll { poll_id: 1 });
+    assert_error(res, "Poll is not in progress");
+
+    // voted SPEC is withdrawable after the voting
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Withdraw { amount: Some(Uint128::from(50u128)) };
+    execute(deps.as_mut(), env_at(time + 100000), info, msg)?;
+
+    // execute poll after the effective delay
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 109999), info, ExecuteMsg::ExecutePoll { poll_id: 1 });
+    assert_error(res, &format!("Poll can be executed from {}", time + 110000));
+
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 110000), info, ExecuteMsg::ExecutePoll { poll_id: 1 })?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+            msg: update_config_msg.clone(),
+            funds: vec![],
+        })]
+    );
+
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 110000), info, ExecuteMsg::ExecutePoll { poll_id: 1 });
+    assert_error(res, "Poll has not passed");
+
+    // only the contract itself can update config
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, from_binary(&update_config_msg)?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, from_binary(&update_config_msg)?)?;
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(config.proposal_deposit, Uint128::from(2000u128));
+
+    // the deposit is burned if the quorum is not reached
+    let time = time + 110000;
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(time), info, poll_msg(USER_1, 1000, vec![])?);
+    assert_error(res, "Poll deposit must be at least 2000");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    execute(deps.as_mut(), env_at(time), info, poll_msg(USER_1, 2000, vec![])?)?;
+
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::CastVote { poll_id: 2, vote: VoteOption::Yes };
+    execute(deps.as_mut(), env_at(time), info, msg)?;
+
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(time + 100000), info, ExecuteMsg::EndPoll { poll_id: 2 })?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: SPEC_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount: Uint128::from(2000u128) })?,
+            funds: vec![],
+        })]
+    );
+
+    let res: PollsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Polls { start_after: Some(1), limit: None },
+    )?)?;
+    assert_eq!(
+        res,
+        PollsResponse {
+            polls: vec![PollResponse {
+                id: 2,
+                creator: USER_1.to_string(),
+                status: PollStatus::Rejected,
+                title: "title".to_string(),
+                description: "description".to_string(),
+                link: None,
+                execute_msgs: vec![],
+                deposit_amount: Uint128::from(2000u128),
+                start_time: time,
+                end_time: time + 100000,
+                yes_votes: Uint128::from(150u128),
+                no_votes: Uint128::zero(),
+                total_voting_power: Uint128::from(849u128),
+            }],
+        }
+    );
+
+    let res: PollResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Poll { poll_id: 1 })?)?;
+    assert_eq!(res.status, PollStatus::Executed);
+    assert_eq!(res.total_voting_power, Uint128::from(700u128));
+
+    Ok(())
+}
@@ -2,10 +2,11 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use spectrum::lp_staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    StateResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    RewardInfoResponse, StateResponse,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use spectrum::vault::ExchangeRateResponse;
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -20,4 +21,6 @@ fn main() {
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(RewardInfoResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(ExchangeRateResponse), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
 }
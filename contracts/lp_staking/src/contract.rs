@@ -9,7 +9,7 @@ use cosmwasm_std::{
 use spectrum::{lp_staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
     RewardInfoResponse, StateResponse, RewardInfoResponseItem,
-}};
+}, vault::ExchangeRateResponse};
 
 use crate::{
     state::{
@@ -18,6 +18,9 @@ use crate::{
     ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner}, error::ContractError,
 };
 
+use astroport::asset::AssetInfo;
+use astroport::querier::query_token_balance;
+use spectrum::helper::{assert_valid_addr, invalid_state};
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use std::collections::BTreeMap;
 
@@ -314,6 +317,7 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::AllRewardInfos { start_after, limit, time_seconds } => {
             to_binary(&query_all_reward_infos(deps, start_after, limit, time_seconds)?)
         },
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps)?),
     }
 }
 
@@ -343,6 +347,19 @@ pub fn query_state(deps: Deps, _env: Env, time_seconds: Option<u64>) -> StdResul
     })
 }
 
+// bonded LP token is not compounded, each share is one LP token
+pub fn query_exchange_rate(deps: Deps) -> StdResult<ExchangeRateResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STATE.load(deps.storage)?;
+
+    Ok(ExchangeRateResponse {
+        asset: AssetInfo::Token { contract_addr: config.staking_token },
+        exchange_rate: Decimal::one(),
+        total_shares: state.total_bond_amount,
+        total_assets: state.total_bond_amount,
+    })
+}
+
 pub fn query_reward_info(
     deps: Deps,
     _env: Env,
@@ -449,6 +466,30 @@ pub fn query_all_reward_infos(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref(), &env)?;
+
     Ok(Response::default())
 }
+
+/// Checks the stored config and that the bonded LP token is held by the contract, aborting the
+/// migration otherwise
+fn assert_state(deps: Deps, env: &Env) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.reward_token, "reward_token")?;
+    assert_valid_addr(deps.api, &config.staking_token, "staking_token")?;
+    for (start, end, _) in config.distribution_schedule.iter() {
+        if start >= end {
+            return Err(invalid_state("distribution schedule ends before it starts"));
+        }
+    }
+
+    let state = STATE.load(deps.storage)?;
+    let staked = query_token_balance(&deps.querier, &config.staking_token, &env.contract.address)?;
+    if staked < state.total_bond_amount {
+        return Err(invalid_state("bonded amount exceeds the staking token balance"));
+    }
+
+    Ok(())
+}
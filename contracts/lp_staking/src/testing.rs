@@ -9,9 +9,11 @@ use spectrum::lp_staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
     StateResponse, RewardInfoResponseItem,
 };
+use spectrum::vault::ExchangeRateResponse;
+use astroport::asset::AssetInfo;
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
-    from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg, Timestamp, Response,
+    from_binary, to_binary, Addr, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg, Timestamp, Response,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
@@ -152,6 +154,19 @@ fn test_bond_tokens() {
         }
     );
 
+    assert_eq!(
+        from_binary::<ExchangeRateResponse>(
+            &query(deps.as_ref(), mock_env(), QueryMsg::ExchangeRate {}).unwrap()
+        )
+        .unwrap(),
+        ExchangeRateResponse {
+            asset: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+            exchange_rate: Decimal::one(),
+            total_shares: Uint128::from(100u128),
+            total_assets: Uint128::from(100u128),
+        }
+    );
+
     // bond 100 more tokens
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
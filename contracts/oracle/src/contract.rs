@@ -0,0 +1,531 @@
+use crate::error::ContractError;
+use crate::state::{
+    price_key, Config, CumulativePrices, CONFIG, LAST_UPDATE, LEGACY_PRICE_PAIRS,
+    OWNERSHIP_PROPOSAL, PAIRS, PRICE_SOURCES, SNAPSHOTS,
+};
+
+use astroport::asset::{Asset, AssetInfo};
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::pair::{CumulativePricesResponse, QueryMsg as PairQueryMsg};
+use astroport::querier::query_token_precision;
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo,
+    Order, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128, Uint256,
+};
+use cw_storage_plus::Bound;
+use spectrum::helper::{assert_valid_addr, invalid_state};
+use spectrum::adapters::pair::Pair;
+use spectrum::oracle::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, PairsResponse, PriceResponse, QueryMsg,
+    ValueAssetsResponse,
+};
+use std::convert::TryInto;
+
+/// The max number of pairs to route the price of an asset through
+const MAX_ROUTE_HOPS: usize = 3;
+
+/// The max number of pairs providing the price of two assets
+const MAX_PRICE_SOURCES: usize = 5;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.max_window == 0 {
+        return Err(ContractError::InvalidMaxWindow {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            update_interval: msg.update_interval,
+            max_window: msg.max_window,
+        },
+    )?;
+    LAST_UPDATE.save(deps.storage, &0u64)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            update_interval,
+            max_window,
+        } => update_config(deps, info, update_interval, max_window),
+        ExecuteMsg::RegisterPair { pair_contract } => register_pair(deps, env, info, pair_contract),
+        ExecuteMsg::DeregisterPair { pair_contract } => deregister_pair(deps, info, pair_contract),
+        ExecuteMsg::Update {} => update(deps, env),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Updates contract config. Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    update_interval: Option<u64>,
+    max_window: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(update_interval) = update_interval {
+        config.update_interval = update_interval;
+    }
+
+    if let Some(max_window) = max_window {
+        if max_window == 0 {
+            return Err(ContractError::InvalidMaxWindow {});
+        }
+        config.max_window = max_window;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Returns the cumulative prices of the pair at the current block time.
+fn query_cumulative_prices(
+    querier: &QuerierWrapper,
+    pair: &Addr,
+) -> StdResult<CumulativePrices> {
+    let res: CumulativePricesResponse =
+        querier.query_wasm_smart(pair.to_string(), &PairQueryMsg::CumulativePrices {})?;
+    Ok(res.cumulative_prices)
+}
+
+/// ## Description
+/// Saves the cumulative prices of the pair and removes the snapshots no longer needed for the
+/// max window.
+fn snapshot(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    config: &Config,
+    pair: &Addr,
+    time: u64,
+) -> StdResult<()> {
+    let cumulative_prices = query_cumulative_prices(querier, pair)?;
+    SNAPSHOTS.save(storage, (pair, time), &cumulative_prices)?;
+
+    // keep the latest snapshot at the start of the max window
+    let window_start = time.saturating_sub(config.max_window);
+    let expired = SNAPSHOTS
+        .prefix(pair)
+        .keys(storage, None, Some(Bound::inclusive(window_start)), Order::Descending)
+        .skip(1)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for expired_time in expired {
+        SNAPSHOTS.remove(storage, (pair, expired_time));
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Provides the prices of all asset combinations in the pair. Only the owner can execute this.
+fn register_pair(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair_contract: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair = deps.api.addr_validate(&pair_contract)?;
+    if PAIRS.has(deps.storage, &pair) {
+        return Err(ContractError::PairAlreadyRegistered {});
+    }
+    let asset_infos = Pair(pair.clone()).query_pair_info(&deps.querier)?.asset_infos;
+    for (i, a) in asset_infos.iter().enumerate() {
+        for b in asset_infos[i + 1..].iter() {
+            let key = price_key(a, b);
+            let mut sources = PRICE_SOURCES.may_load(deps.storage, key.clone())?.unwrap_or_default();
+            if sources.len() >= MAX_PRICE_SOURCES {
+                return Err(ContractError::TooManyPriceSources(key, MAX_PRICE_SOURCES));
+            }
+            sources.push(pair.clone());
+            PRICE_SOURCES.save(deps.storage, key, &sources)?;
+        }
+    }
+    PAIRS.save(deps.storage, &pair, &asset_infos)?;
+    snapshot(deps.storage, &deps.querier, &config, &pair, env.block.time.seconds())?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_pair"),
+        attr("pair", pair),
+    ]))
+}
+
+/// ## Description
+/// Stops providing the prices of the pair and removes its snapshots. Only the owner can execute
+/// this.
+fn deregister_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_contract: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair = deps.api.addr_validate(&pair_contract)?;
+    let asset_infos = PAIRS
+        .may_load(deps.storage, &pair)?
+        .ok_or(ContractError::PairNotRegistered {})?;
+    for (i, a) in asset_infos.iter().enumerate() {
+        for b in asset_infos[i + 1..].iter() {
+            let key = price_key(a, b);
+            let mut sources = PRICE_SOURCES.may_load(deps.storage, key.clone())?.unwrap_or_default();
+            sources.retain(|source| *source != pair);
+            if sources.is_empty() {
+                PRICE_SOURCES.remove(deps.storage, key);
+            } else {
+                PRICE_SOURCES.save(deps.storage, key, &sources)?;
+            }
+        }
+    }
+    PAIRS.remove(deps.storage, &pair);
+
+    let times = SNAPSHOTS
+        .prefix(&pair)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for time in times {
+        SNAPSHOTS.remove(deps.storage, (&pair, time));
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "deregister_pair"),
+        attr("pair", pair),
+    ]))
+}
+
+/// ## Description
+/// Snapshots the cumulative prices of all registered pairs, once per update interval.
+fn update(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let time = env.block.time.seconds();
+    let next_update = LAST_UPDATE.load(deps.storage)? + config.update_interval;
+    if time < next_update {
+        return Err(ContractError::UpdateTooEarly(next_update));
+    }
+    LAST_UPDATE.save(deps.storage, &time)?;
+
+    let pairs = PAIRS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for pair in pairs.iter() {
+        snapshot(deps.storage, &deps.querier, &config, pair, time)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "update"),
+        attr("pairs", pairs.len().to_string()),
+    ]))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Pairs {} => to_binary(&query_pairs(deps)?),
+        QueryMsg::Price { base, quote, window } => {
+            to_binary(&query_price(deps, &env, base, quote, window)?)
+        }
+        QueryMsg::ValueAssets { assets, quote, window } => {
+            to_binary(&query_value_assets(deps, &env, assets, quote, window)?)
+        }
+    }
+}
+
+/// ## Description
+/// Returns the registered pairs.
+fn query_pairs(deps: Deps) -> StdResult<PairsResponse> {
+    let pairs = PAIRS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    Ok(PairsResponse { pairs })
+}
+
+/// ## Description
+/// Returns the cumulative price of the base asset in the quote asset.
+fn find_cumulative_price(
+    cumulative_prices: &[(AssetInfo, AssetInfo, Uint128)],
+    base: &AssetInfo,
+    quote: &AssetInfo,
+) -> StdResult<Uint128> {
+    cumulative_prices
+        .iter()
+        .find(|(offer, ask, _)| offer.equal(base) && ask.equal(quote))
+        .map(|(_, _, price)| *price)
+        .ok_or_else(|| StdError::generic_err(format!("No cumulative price of {} in {}", base, quote)))
+}
+
+/// ## Description
+/// Returns the average price of the base asset in the quote asset in the pair, from the latest
+/// snapshot at least the window old until the current time, and the time of the snapshot.
+/// Returns `None` when the pair has no snapshot before the window.
+fn query_pair_price(
+    deps: Deps,
+    pair: &Addr,
+    base: &AssetInfo,
+    quote: &AssetInfo,
+    time: u64,
+    window: u64,
+) -> StdResult<Option<(Decimal256, u64)>> {
+    let start = SNAPSHOTS
+        .prefix(pair)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(time.saturating_sub(window))),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?;
+    let (start_time, start_prices) = match start {
+        Some(start) => start,
+        None => return Ok(None),
+    };
+
+    let start_price = find_cumulative_price(&start_prices, base, quote)?;
+    let end_price = find_cumulative_price(&query_cumulative_prices(&deps.querier, pair)?, base, quote)?;
+
+    // cumulative prices are in the smallest denomination of the quote asset per whole base asset
+    let precision = query_token_precision(&deps.querier, base)?;
+    let price = Decimal256::from_ratio(
+        end_price.wrapping_sub(start_price),
+        Uint256::from(time - start_time) * Uint256::from(10u128.pow(precision as u32)),
+    );
+
+    Ok(Some((price, start_time)))
+}
+
+/// ## Description
+/// Returns the median of the average prices of the base asset in the quote asset in all pairs
+/// providing the price, so a single manipulated pair cannot move the price. Pairs without a
+/// snapshot before the window are left out.
+fn query_price(
+    deps: Deps,
+    env: &Env,
+    base: AssetInfo,
+    quote: AssetInfo,
+    window: u64,
+) -> StdResult<PriceResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    if window == 0 || window > config.max_window {
+        return Err(StdError::generic_err(format!(
+            "Window must be 1 to {} seconds",
+            config.max_window
+        )));
+    }
+
+    let sources = PRICE_SOURCES
+        .may_load(deps.storage, price_key(&base, &quote))?
+        .ok_or_else(|| StdError::generic_err(format!("No pair provides the price of {} in {}", base, quote)))?;
+
+    let time = env.block.time.seconds();
+    let mut prices = vec![];
+    let mut start_time = time;
+    for pair in sources.iter() {
+        if let Some((price, pair_start_time)) = query_pair_price(deps, pair, &base, &quote, time, window)? {
+            prices.push(price);
+            start_time = start_time.min(pair_start_time);
+        }
+    }
+    if prices.is_empty() {
+        return Err(StdError::generic_err("No price snapshot before the window"));
+    }
+
+    prices.sort();
+    let mid = prices.len() / 2;
+    let price = if prices.len() % 2 == 0 {
+        (prices[mid - 1] + prices[mid]) / Uint256::from(2u128)
+    } else {
+        prices[mid]
+    };
+
+    let max_deviation = prices
+        .iter()
+        .map(|source_price| {
+            let deviation = if *source_price > price { *source_price - price } else { price - *source_price };
+            if price.is_zero() {
+                Decimal256::zero()
+            } else {
+                Decimal256::from_ratio(deviation.atomics(), price.atomics())
+            }
+        })
+        .max()
+        .unwrap_or_default();
+
+    Ok(PriceResponse {
+        price,
+        start_time,
+        sources: prices.len() as u32,
+        max_deviation,
+    })
+}
+
+/// ## Description
+/// Returns the shortest route of assets from the base asset to the quote asset, where each two
+/// adjacent assets are provided by a registered pair.
+fn find_route(deps: Deps, base: &AssetInfo, quote: &AssetInfo) -> StdResult<Vec<AssetInfo>> {
+    let pairs = PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, asset_infos)| asset_infos))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut routes = vec![vec![base.clone()]];
+    let mut visited = vec![base.clone()];
+    for _ in 0..MAX_ROUTE_HOPS {
+        let mut next_routes = vec![];
+        for route in routes {
+            let last = &route[route.len() - 1];
+            for asset_infos in pairs.iter().filter(|asset_infos| asset_infos.contains(last)) {
+                for asset_info in asset_infos {
+                    if visited.contains(asset_info) {
+                        continue;
+                    }
+                    let mut next_route = route.clone();
+                    next_route.push(asset_info.clone());
+                    if asset_info.equal(quote) {
+                        return Ok(next_route);
+                    }
+                    visited.push(asset_info.clone());
+                    next_routes.push(next_route);
+                }
+            }
+        }
+        routes = next_routes;
+    }
+
+    Err(StdError::generic_err(format!("No route to price {} in {}", base, quote)))
+}
+
+/// ## Description
+/// Returns the total value of the assets in the quote asset from the average prices along the
+/// route of each asset.
+fn query_value_assets(
+    deps: Deps,
+    env: &Env,
+    assets: Vec<Asset>,
+    quote: AssetInfo,
+    window: u64,
+) -> StdResult<ValueAssetsResponse> {
+    let mut value = Uint256::zero();
+    for asset in assets {
+        if asset.info.equal(&quote) {
+            value += Uint256::from(asset.amount);
+            continue;
+        }
+
+        let route = find_route(deps, &asset.info, &quote)?;
+        let mut price = Decimal256::one();
+        for hop in route.windows(2) {
+            price *= query_price(deps, env, hop[0].clone(), hop[1].clone(), window)?.price;
+        }
+        value += Uint256::from(asset.amount) * price;
+    }
+
+    Ok(ValueAssetsResponse {
+        value: value.try_into()?,
+    })
+}
+
+/// ## Description
+/// Used for contract migration. Moves the single pair providing the price of two assets to the
+/// price sources. Returns a default object of type [`Response`], or an error if the stored config
+/// is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let legacy = LEGACY_PRICE_PAIRS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (key, pair) in legacy {
+        PRICE_SOURCES.save(deps.storage, key.clone(), &vec![pair])?;
+        LEGACY_PRICE_PAIRS.remove(deps.storage, key);
+    }
+
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored owner, price window and registered pairs.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    if config.max_window == 0 {
+        return Err(invalid_state("max_window must be greater than 0"));
+    }
+    for item in PRICE_SOURCES.range(deps.storage, None, None, Order::Ascending) {
+        let (key, sources) = item?;
+        if sources.is_empty() {
+            return Err(invalid_state(&format!("no pair provides the price of {}", key)));
+        }
+        for pair in sources.iter() {
+            assert_valid_addr(deps.api, pair, "pair")?;
+        }
+    }
+
+    Ok(())
+}
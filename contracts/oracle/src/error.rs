@@ -0,0 +1,34 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes oracle contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Max window must be greater than 0")]
+    InvalidMaxWindow {},
+
+    #[error("Pair is already registered")]
+    PairAlreadyRegistered {},
+
+    #[error("Price of {0} is already provided by {1} pairs")]
+    TooManyPriceSources(String, usize),
+
+    #[error("Pair is not registered")]
+    PairNotRegistered {},
+
+    #[error("Prices cannot be updated until {0}")]
+    UpdateTooEarly(u64),
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
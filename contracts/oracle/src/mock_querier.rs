@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use cosmwasm_std::{Binary, ContractResult, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use astroport::asset::{AssetInfo, PairInfo};
+use astroport::pair::CumulativePricesResponse;
+
+pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+        custom_query_type: Default::default()
+    }
+}
+
+pub struct WasmMockQuerier {
+    pairs: HashMap<String, PairInfo>,
+    cumulative_prices: HashMap<String, Vec<(AssetInfo, AssetInfo, Uint128)>>,
+    decimals: HashMap<String, u8>,
+}
+
+impl WasmMockQuerier {
+    pub fn new() -> Self {
+        WasmMockQuerier {
+            pairs: HashMap::new(),
+            cumulative_prices: HashMap::new(),
+            decimals: HashMap::new(),
+        }
+    }
+
+    pub fn set_pair(&mut self, pair_info: PairInfo) {
+        self.pairs.insert(pair_info.contract_addr.to_string(), pair_info);
+    }
+
+    pub fn set_cumulative_prices(&mut self, pair: &str, cumulative_prices: Vec<(AssetInfo, AssetInfo, Uint128)>) {
+        self.cumulative_prices.insert(pair.to_string(), cumulative_prices);
+    }
+
+    pub fn set_decimals(&mut self, token: &str, decimals: u8) {
+        self.decimals.insert(token.to_string(), decimals);
+    }
+
+    fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        let result = match request {
+            QueryRequest::Wasm(WasmQuery::Smart {
+                                   contract_addr,
+                                   msg,
+                               }) => self.execute_wasm_query(contract_addr, msg),
+            _ => return QuerierResult::Err(SystemError::Unknown {}),
+        };
+        QuerierResult::Ok(ContractResult::from(result))
+    }
+
+    fn execute_wasm_query(&self, contract_addr: &str, msg: &Binary) -> StdResult<Binary> {
+        match from_binary(msg)? {
+            MockQueryMsg::Pair {} => {
+                let pair_info = match self.pairs.get(contract_addr) {
+                    Some(v) => v.clone(),
+                    None => {
+                        panic!("No pair info")
+                    }
+                };
+
+                to_binary(&pair_info)
+            },
+            MockQueryMsg::CumulativePrices {} => {
+                to_binary(&CumulativePricesResponse {
+                    assets: vec![],
+                    total_share: Uint128::zero(),
+                    cumulative_prices: self.cumulative_prices.get(contract_addr).cloned().unwrap_or_default(),
+                })
+            },
+            MockQueryMsg::TokenInfo {} => {
+                to_binary(&cw20::TokenInfoResponse {
+                    name: contract_addr.to_string(),
+                    symbol: contract_addr.to_string(),
+                    decimals: *self.decimals.get(contract_addr).unwrap_or(&6),
+                    total_supply: Uint128::from(1000000000000u128),
+                })
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum MockQueryMsg {
+    Pair {},
+    CumulativePrices {},
+    TokenInfo {},
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.execute_query(&request)
+    }
+}
@@ -0,0 +1,50 @@
+use astroport::asset::AssetInfo;
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores the main parameter for the oracle contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to register pairs and update config
+    pub owner: Addr,
+    /// The min number of seconds between price snapshots
+    pub update_interval: u64,
+    /// The max number of seconds of the window of a price, older snapshots are removed
+    pub max_window: u64,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Stores the block time in seconds of the last update
+pub const LAST_UPDATE: Item<u64> = Item::new("last_update");
+
+/// Stores the asset infos of the registered pairs
+pub const PAIRS: Map<&Addr, Vec<AssetInfo>> = Map::new("pairs");
+
+/// Stores the pairs providing the price of two assets at the [`price_key`], the price is the
+/// median of their prices
+pub const PRICE_SOURCES: Map<String, Vec<Addr>> = Map::new("price_sources");
+
+/// Stores the single pair providing the price of two assets before multiple sources, moved to
+/// [`PRICE_SOURCES`] on migration
+pub const LEGACY_PRICE_PAIRS: Map<String, Addr> = Map::new("price_pairs");
+
+/// The cumulative prices of each offer and ask asset of a pair
+pub type CumulativePrices = Vec<(AssetInfo, AssetInfo, Uint128)>;
+
+/// Stores the cumulative prices of the pair at the block time in seconds
+pub const SNAPSHOTS: Map<(&Addr, u64), CumulativePrices> = Map::new("snapshots");
+
+/// Returns the key of the two assets regardless of their order
+pub fn price_key(a: &AssetInfo, b: &AssetInfo) -> String {
+    let mut keys = [a.to_string(), b.to_string()];
+    keys.sort();
+    keys.join(":")
+}
@@ -0,0 +1,381 @@
+use astroport::asset::{native_asset, token_asset, AssetInfo, PairInfo};
+use astroport::factory::PairType;
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, Addr, Decimal256, Env, OwnedDeps, Response, StdError, Timestamp, Uint128,
+};
+use spectrum::oracle::{
+    ExecuteMsg, InstantiateMsg, PairsResponse, PriceResponse, QueryMsg, ValueAssetsResponse,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use crate::state::Config;
+
+const OWNER: &str = "owner";
+const PAIR_1: &str = "pair_1";
+const PAIR_2: &str = "pair_2";
+const PAIR_3: &str = "pair_3";
+const PAIR_4: &str = "pair_4";
+const IBC_TOKEN: &str = "ibc/stablecoin";
+const TOKEN_1: &str = "token_1";
+const TOKEN_2: &str = "token_2";
+const USER_1: &str = "user_1";
+const DAY: u64 = 86400;
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    register_pair(&mut deps)?;
+    update(&mut deps)?;
+    price(&mut deps)?;
+    value_assets(&mut deps)?;
+    deregister_pair(&mut deps)?;
+    median_price(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn native() -> AssetInfo {
+    AssetInfo::NativeToken { denom: IBC_TOKEN.to_string() }
+}
+
+fn token() -> AssetInfo {
+    AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_1) }
+}
+
+fn token_2() -> AssetInfo {
+    AssetInfo::Token { contract_addr: Addr::unchecked(TOKEN_2) }
+}
+
+fn pair_info(pair: &str) -> PairInfo {
+    PairInfo {
+        contract_addr: Addr::unchecked(pair),
+        liquidity_token: Addr::unchecked(format!("{}_lp", pair)),
+        asset_infos: vec![native(), token()],
+        pair_type: PairType::Xyk {},
+    }
+}
+
+/// Sets the cumulative prices of PAIR_1 in the smallest denomination of the ask asset per whole
+/// offer asset
+fn set_cumulative_prices(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    native_price: u128,
+    token_price: u128,
+) {
+    deps.querier.set_cumulative_prices(PAIR_1, vec![
+        (native(), token(), Uint128::from(native_price)),
+        (token(), native(), Uint128::from(token_price)),
+    ]);
+}
+
+fn query_price(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    time: u64,
+    base: AssetInfo,
+    quote: AssetInfo,
+    window: u64,
+) -> Result<PriceResponse, ContractError> {
+    Ok(from_binary(&query(deps.as_ref(), env_at(time), QueryMsg::Price { base, quote, window })?)?)
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(OWNER, &[]);
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        update_interval: 600,
+        max_window: 0,
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_error(res, "Max window must be greater than 0");
+
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        update_interval: 600,
+        max_window: DAY,
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(OWNER),
+            update_interval: 600,
+            max_window: DAY,
+        }
+    );
+
+    deps.querier.set_pair(pair_info(PAIR_1));
+    deps.querier.set_pair(pair_info(PAIR_2));
+    deps.querier.set_decimals(TOKEN_1, 8);
+    set_cumulative_prices(deps, 0, 0);
+
+    Ok(())
+}
+
+fn register_pair(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::RegisterPair { pair_contract: PAIR_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(1000), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::RegisterPair { pair_contract: PAIR_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(1000), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "register_pair"), attr("pair", PAIR_1)]
+    );
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::RegisterPair { pair_contract: PAIR_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(1000), info, msg);
+    assert_error(res, "Pair is already registered");
+
+    let res: PairsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Pairs {})?)?;
+    assert_eq!(res.pairs, vec![Addr::unchecked(PAIR_1)]);
+
+    Ok(())
+}
+
+fn update(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(1000), info, ExecuteMsg::Update {})?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "update"), attr("pairs", "1")]
+    );
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(1500), info, ExecuteMsg::Update {});
+    assert_error(res, "Prices cannot be updated until 1600");
+
+    Ok(())
+}
+
+fn price(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    // 2 token_1 per ibc/stablecoin for an hour, token_1 has 8 decimals
+    set_cumulative_prices(deps, 2_00000000 * 3600, 500000 * 3600);
+
+    assert_eq!(
+        query_price(deps, 4600, native(), token(), 3600)?,
+        PriceResponse {
+            price: Decimal256::from_ratio(200u128, 1u128),
+            start_time: 1000,
+            sources: 1,
+            max_deviation: Decimal256::zero(),
+        }
+    );
+    assert_eq!(
+        query_price(deps, 4600, token(), native(), 3600)?,
+        PriceResponse {
+            price: Decimal256::permille(5),
+            start_time: 1000,
+            sources: 1,
+            max_deviation: Decimal256::zero(),
+        }
+    );
+
+    let res = query(deps.as_ref(), env_at(4600), QueryMsg::Price { base: native(), quote: token(), window: 3601 });
+    assert_eq!(res, Err(StdError::generic_err("No price snapshot before the window")));
+
+    let res = query(deps.as_ref(), env_at(4600), QueryMsg::Price { base: native(), quote: token(), window: 0 });
+    assert_eq!(res, Err(StdError::generic_err("Window must be 1 to 86400 seconds")));
+
+    let res = query(deps.as_ref(), env_at(4600), QueryMsg::Price { base: native(), quote: token(), window: DAY + 1 });
+    assert_eq!(res, Err(StdError::generic_err("Window must be 1 to 86400 seconds")));
+
+    let uluna = AssetInfo::NativeToken { denom: "uluna".to_string() };
+    let res = query(deps.as_ref(), env_at(4600), QueryMsg::Price { base: uluna, quote: token(), window: 3600 });
+    assert_eq!(res, Err(StdError::generic_err("No pair provides the price of uluna in token_1")));
+
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), env_at(4600), info, ExecuteMsg::Update {})?;
+
+    // 3 token_1 per ibc/stablecoin for the next day, the snapshot at 1000 is removed
+    set_cumulative_prices(deps, 2_00000000 * 3600 + 3_00000000 * (DAY as u128 + 400), 0);
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), env_at(DAY + 5000), info, ExecuteMsg::Update {})?;
+
+    assert_eq!(
+        query_price(deps, DAY + 5000, native(), token(), DAY)?,
+        PriceResponse {
+            price: Decimal256::from_ratio(300u128, 1u128),
+            start_time: 4600,
+            sources: 1,
+            max_deviation: Decimal256::zero(),
+        }
+    );
+    let res = query(deps.as_ref(), env_at(DAY + 4599), QueryMsg::Price { base: native(), quote: token(), window: DAY });
+    assert_eq!(res, Err(StdError::generic_err("No price snapshot before the window")));
+
+    Ok(())
+}
+
+fn value_assets(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    deps.querier.set_pair(PairInfo {
+        contract_addr: Addr::unchecked(PAIR_3),
+        liquidity_token: Addr::unchecked("pair_3_lp"),
+        asset_infos: vec![token(), token_2()],
+        pair_type: PairType::Xyk {},
+    });
+    deps.querier.set_decimals(TOKEN_2, 6);
+    deps.querier.set_cumulative_prices(PAIR_3, vec![
+        (token(), token_2(), Uint128::zero()),
+        (token_2(), token(), Uint128::zero()),
+    ]);
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::RegisterPair { pair_contract: PAIR_3.to_string() };
+    execute(deps.as_mut(), env_at(DAY + 5000), info, msg)?;
+
+    // 3 token_1 per ibc/stablecoin and 0.01 token_2 per token_1 for an hour
+    set_cumulative_prices(deps, 2_00000000 * 3600 + 3_00000000 * (DAY as u128 + 4000), 0);
+    deps.querier.set_cumulative_prices(PAIR_3, vec![
+        (token(), token_2(), Uint128::from(1_000000u128 * 3600)),
+        (token_2(), token(), Uint128::zero()),
+    ]);
+
+    let msg = QueryMsg::ValueAssets {
+        assets: vec![
+            native_asset(IBC_TOKEN.to_string(), Uint128::from(1_000000u128)),
+            token_asset(Addr::unchecked(TOKEN_2), Uint128::from(500u128)),
+        ],
+        quote: token_2(),
+        window: 3600,
+    };
+    let res: ValueAssetsResponse = from_binary(&query(deps.as_ref(), env_at(DAY + 8600), msg)?)?;
+    assert_eq!(res.value, Uint128::from(3_000500u128));
+
+    let msg = QueryMsg::ValueAssets {
+        assets: vec![native_asset(IBC_TOKEN.to_string(), Uint128::from(1_000000u128))],
+        quote: token(),
+        window: 3600,
+    };
+    let res: ValueAssetsResponse = from_binary(&query(deps.as_ref(), env_at(DAY + 8600), msg)?)?;
+    assert_eq!(res.value, Uint128::from(3_00000000u128));
+
+    let msg = QueryMsg::ValueAssets {
+        assets: vec![native_asset("uluna".to_string(), Uint128::from(1_000000u128))],
+        quote: token_2(),
+        window: 3600,
+    };
+    let res = query(deps.as_ref(), env_at(DAY + 8600), msg);
+    assert_eq!(res, Err(StdError::generic_err("No route to price uluna in token_2")));
+
+    Ok(())
+}
+
+fn deregister_pair(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::DeregisterPair { pair_contract: PAIR_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(DAY + 8600), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::DeregisterPair { pair_contract: PAIR_1.to_string() };
+    execute(deps.as_mut(), env_at(DAY + 8600), info, msg.clone())?;
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY + 8600), info, msg);
+    assert_error(res, "Pair is not registered");
+
+    let res: PairsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Pairs {})?)?;
+    assert_eq!(res.pairs, vec![Addr::unchecked(PAIR_3)]);
+
+    let res = query(deps.as_ref(), env_at(DAY + 8600), QueryMsg::Price { base: native(), quote: token(), window: DAY });
+    assert_eq!(res, Err(StdError::generic_err("No pair provides the price of ibc/stablecoin in token_1")));
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::RegisterPair { pair_contract: PAIR_2.to_string() };
+    execute(deps.as_mut(), env_at(DAY + 8600), info, msg)?;
+
+    Ok(())
+}
+
+fn median_price(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    // pair_2, pair_1 and pair_4 provide the price of ibc/stablecoin in token_1
+    let start = DAY + 9200;
+    deps.querier.set_pair(pair_info(PAIR_4));
+    for pair in [PAIR_1, PAIR_2, PAIR_4] {
+        deps.querier.set_cumulative_prices(pair, vec![
+            (native(), token(), Uint128::zero()),
+            (token(), native(), Uint128::zero()),
+        ]);
+    }
+    for pair in [PAIR_1, PAIR_4] {
+        let info = mock_info(OWNER, &[]);
+        let msg = ExecuteMsg::RegisterPair { pair_contract: pair.to_string() };
+        execute(deps.as_mut(), env_at(start), info, msg)?;
+    }
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), env_at(start), info, ExecuteMsg::Update {})?;
+
+    // 3 token_1 per ibc/stablecoin in pair_1, 2.9 in pair_2 and 30 in the manipulated pair_4
+    for (pair, price) in [(PAIR_1, 3_00000000u128), (PAIR_2, 2_90000000), (PAIR_4, 30_00000000)] {
+        deps.querier.set_cumulative_prices(pair, vec![
+            (native(), token(), Uint128::from(price * 3600)),
+            (token(), native(), Uint128::zero()),
+        ]);
+    }
+    assert_eq!(
+        query_price(deps, start + 3600, native(), token(), 3600)?,
+        PriceResponse {
+            price: Decimal256::from_ratio(300u128, 1u128),
+            start_time: start,
+            sources: 3,
+            max_deviation: Decimal256::from_ratio(9u128, 1u128),
+        }
+    );
+
+    // the median of two sources is their average, a pair without a snapshot before the window
+    // is left out
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::DeregisterPair { pair_contract: PAIR_4.to_string() };
+    execute(deps.as_mut(), env_at(start + 3600), info, msg)?;
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::RegisterPair { pair_contract: PAIR_4.to_string() };
+    execute(deps.as_mut(), env_at(start + 3600), info, msg)?;
+    assert_eq!(
+        query_price(deps, start + 3600, native(), token(), 3600)?,
+        PriceResponse {
+            price: Decimal256::from_ratio(295u128, 1u128),
+            start_time: start,
+            sources: 2,
+            max_deviation: Decimal256::from_ratio(5u128, 295u128),
+        }
+    );
+
+    Ok(())
+}
@@ -5,17 +5,20 @@ use std::collections::HashSet;
 use astroport::factory::PairType;
 use astroport::pair::SimulationResponse;
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, Fraction,
-    MessageInfo, Response, StdError, StdResult, Uint128,
+    entry_point, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    Fraction, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128,
 };
 use spectrum::pair_proxy::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, MAX_ASSETS,
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, MAX_ASSETS,
 };
 
 use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::querier::query_token_precision;
 use cw20::Cw20ReceiveMsg;
+use spectrum::adapters::oracle::PriceGuardConfig;
 use spectrum::adapters::router::Router;
+use spectrum::helper::{assert_bps, assert_valid_addr, invalid_state};
+use spectrum::querier::query_asset_balance;
 
 /// ## Description
 /// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
@@ -55,6 +58,10 @@ pub fn instantiate(
     } else {
         query_token_precision(&deps.querier, &msg.asset_infos[msg.asset_infos.len() - 1])?
     };
+    let hop_guard = match msg.hop_guard {
+        Some(hop_guard) => PriceGuardConfig::from_msg(deps.api, hop_guard)?,
+        None => None,
+    };
 
     let config = Config {
         pair_info: PairInfo {
@@ -71,6 +78,7 @@ pub fn instantiate(
         router_type: msg.router_type,
         offer_precision,
         ask_precision,
+        hop_guard,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -116,6 +124,7 @@ pub fn execute(
                 to_addr,
             )
         }
+        ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
     }
 }
 
@@ -166,7 +175,7 @@ pub fn receive_cw20(
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     offer_asset: Asset,
@@ -178,12 +187,10 @@ pub fn swap(
 
     let config = CONFIG.load(deps.storage)?;
 
-    let (operations, offer_precision, ask_precision) =
+    let (asset_infos, offer_precision, ask_precision) =
         if offer_asset.info.equal(&config.asset_infos[0]) {
             (
-                config
-                    .router_type
-                    .create_swap_operations(&config.asset_infos)?,
+                config.asset_infos.clone(),
                 config.offer_precision,
                 config.ask_precision,
             )
@@ -191,9 +198,8 @@ pub fn swap(
             .info
             .equal(&config.asset_infos[config.asset_infos.len() - 1])
         {
-            let asset_infos: Vec<AssetInfo> = config.asset_infos.into_iter().rev().collect();
             (
-                config.router_type.create_swap_operations(&asset_infos)?,
+                config.asset_infos.iter().rev().cloned().collect(),
                 config.ask_precision,
                 config.offer_precision,
             )
@@ -215,19 +221,132 @@ pub fn swap(
         }
         (_, _) => None,
     };
-    let message = config.router.execute_swap_operations_msg(
-        offer_asset,
-        operations,
-        minimum_receive,
-        Some(to),
-        max_spread,
-    )?;
+
+    // with the hop guard, a single manipulated pool on the route cannot eat the offer value
+    let messages = if let Some(hop_guard) = &config.hop_guard {
+        swap_hop_messages(
+            &deps.querier,
+            &env,
+            &config,
+            hop_guard,
+            offer_asset,
+            asset_infos,
+            minimum_receive,
+            max_spread,
+            to,
+        )?
+    } else {
+        vec![config.router.execute_swap_operations_msg(
+            offer_asset,
+            config.router_type.create_swap_operations(&asset_infos)?,
+            minimum_receive,
+            Some(to),
+            max_spread,
+        )?]
+    };
 
     Ok(Response::new()
-        .add_message(message)
+        .add_messages(messages)
         .add_attribute("action", "swap"))
 }
 
+/// ## Description
+/// Swaps the offer asset on the first hop of the route with the minimum receive of the hop guard.
+/// The rest of the route is swapped by the [`CallbackMsg::SwapHop`] callback.
+#[allow(clippy::too_many_arguments)]
+fn swap_hop_messages(
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    hop_guard: &PriceGuardConfig,
+    offer_asset: Asset,
+    asset_infos: Vec<AssetInfo>,
+    minimum_receive: Option<Uint128>,
+    max_spread: Option<Decimal>,
+    to: Addr,
+) -> StdResult<Vec<CosmosMsg>> {
+    let hop_minimum_receive = hop_guard.minimum_receive(querier, &offer_asset, &asset_infos[1])?;
+    let operations = config.router_type.create_swap_operations(&asset_infos[..2])?;
+
+    // last hop, the route minimum receive also applies
+    if asset_infos.len() == 2 {
+        let minimum_receive = hop_minimum_receive.max(minimum_receive.unwrap_or_default());
+        return Ok(vec![config.router.execute_swap_operations_msg(
+            offer_asset,
+            operations,
+            Some(minimum_receive),
+            Some(to),
+            max_spread,
+        )?]);
+    }
+
+    Ok(vec![
+        config.router.execute_swap_operations_msg(
+            offer_asset,
+            operations,
+            Some(hop_minimum_receive),
+            Some(env.contract.address.clone()),
+            max_spread,
+        )?,
+        CallbackMsg::SwapHop {
+            asset_infos: asset_infos[1..].to_vec(),
+            minimum_receive,
+            max_spread,
+            to,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    ])
+}
+
+/// ## Description
+/// Handles the callbacks sent by the contract itself.
+fn handle_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CallbackMsg,
+) -> Result<Response, ContractError> {
+    // Callback functions can only be called by this contract itself
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    match msg {
+        CallbackMsg::SwapHop {
+            asset_infos,
+            minimum_receive,
+            max_spread,
+            to,
+        } => {
+            let config = CONFIG.load(deps.storage)?;
+            let hop_guard = config
+                .hop_guard
+                .as_ref()
+                .ok_or_else(|| StdError::generic_err("hop guard not set"))?;
+            let offer_asset = Asset {
+                info: asset_infos[0].clone(),
+                amount: query_asset_balance(&deps.querier, &asset_infos[0], &env.contract.address)?,
+            };
+            let messages = swap_hop_messages(
+                &deps.querier,
+                &env,
+                &config,
+                hop_guard,
+                offer_asset.clone(),
+                asset_infos,
+                minimum_receive,
+                max_spread,
+                to,
+            )?;
+
+            Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("action", "swap_hop")
+                .add_attribute("offer_asset", offer_asset.info.to_string())
+                .add_attribute("offer_amount", offer_asset.amount))
+        }
+    }
+}
+
 /// Computes minimum return amount from belief price and max spread
 fn compute_minimum_receive(
     offer_amount: Uint128,
@@ -297,12 +416,35 @@ pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationR
 }
 
 /// ## Description
-/// Used for contract migration. Returns a default object of type [`Response`].
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
     Ok(Response::default())
 }
 
+/// ## Description
+/// Checks the stored router, pair and hop guard.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.router.0, "router")?;
+    assert_valid_addr(deps.api, &config.pair_info.contract_addr, "pair")?;
+    if config.asset_infos.is_empty() || config.asset_infos.len() > MAX_ASSETS {
+        return Err(invalid_state(&format!("asset_infos must have 1 to {} assets", MAX_ASSETS)));
+    }
+    for asset_info in config.asset_infos.iter() {
+        asset_info.check(deps.api).map_err(|_| invalid_state("asset_infos has an invalid asset"))?;
+    }
+    if let Some(hop_guard) = &config.hop_guard {
+        assert_valid_addr(deps.api, &hop_guard.oracle.0, "hop_guard oracle")?;
+        assert_bps(hop_guard.max_deviation_bps, 10000, "max_deviation_bps")?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
-use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Decimal256, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
 use cosmwasm_std::testing::{MockApi, MockStorage};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{token_asset, AssetInfo, PairInfo};
 use astroport::generator::{PendingTokenResponse};
+use spectrum::oracle::PriceResponse;
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -24,7 +25,8 @@ const REWARD_TOKEN: &str = "reward";
 pub struct WasmMockQuerier {
     balances: HashMap<(String, String), Uint128>,
     raw: HashMap<(String, Binary), Binary>,
-    pairs: HashMap<Vec<u8>, PairInfo>
+    pairs: HashMap<Vec<u8>, PairInfo>,
+    prices: HashMap<(String, String), Decimal256>,
 }
 
 impl WasmMockQuerier {
@@ -33,6 +35,7 @@ impl WasmMockQuerier {
             balances: HashMap::new(),
             raw: HashMap::new(),
             pairs: HashMap::new(),
+            prices: HashMap::new(),
         }
     }
 
@@ -52,6 +55,10 @@ impl WasmMockQuerier {
         self.pairs.get(&pair_key(&asset_infos))
     }
 
+    pub fn set_price(&mut self, base: &AssetInfo, quote: &AssetInfo, price: Decimal256) {
+        self.prices.insert((base.to_string(), quote.to_string()), price);
+    }
+
     fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         let result = match request {
             QueryRequest::Bank(BankQuery::Balance {
@@ -135,6 +142,18 @@ impl WasmMockQuerier {
                     total_supply: Uint128::from(1000000000000u128),
                 })
             },
+            MockQueryMsg::Price {
+                base,
+                quote,
+                ..
+            } => {
+                to_binary(&PriceResponse {
+                    price: self.prices[&(base.to_string(), quote.to_string())],
+                    start_time: 0,
+                    sources: 1,
+                    max_deviation: Decimal256::zero(),
+                })
+            },
         }
     }
 }
@@ -157,6 +176,11 @@ enum MockQueryMsg {
         asset_infos: [AssetInfo; 2],
     },
     TokenInfo {},
+    Price {
+        base: AssetInfo,
+        quote: AssetInfo,
+        window: u64,
+    },
 }
 
 impl Querier for WasmMockQuerier {
@@ -2,6 +2,7 @@ use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{AssetInfo, PairInfo};
+use spectrum::adapters::oracle::PriceGuardConfig;
 use spectrum::adapters::router::{Router, RouterType};
 
 /// ## Description
@@ -14,6 +15,9 @@ pub struct Config {
     pub router_type: RouterType,
     pub offer_precision: u8,
     pub ask_precision: u8,
+    /// The oracle guard setting the minimum receive of each hop
+    #[serde(default)]
+    pub hop_guard: Option<PriceGuardConfig>,
 }
 
 /// ## Description
@@ -5,11 +5,13 @@ use astroport::router::{
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError, Uint128, WasmMsg,
+    to_binary, Addr, Coin, CosmosMsg, Decimal, Decimal256, OwnedDeps, Response, StdError, Uint128,
+    WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use spectrum::adapters::router::{Router, RouterType};
-use spectrum::pair_proxy::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
+use spectrum::oracle::PriceGuard;
+use spectrum::pair_proxy::{CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg};
 
 use crate::contract::{execute, instantiate};
 use crate::error::ContractError;
@@ -22,12 +24,14 @@ const ROUTER: &str = "router";
 const TOKEN_1: &str = "token_1";
 const TOKEN_2: &str = "token_2";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const ORACLE: &str = "oracle";
 
 #[test]
 fn test() -> Result<(), ContractError> {
     let mut deps = mock_dependencies();
     create(&mut deps)?;
     swap(&mut deps)?;
+    hop_guard()?;
 
     Ok(())
 }
@@ -52,6 +56,7 @@ fn create(
         router_type: RouterType::AstroSwap,
         offer_precision: None,
         ask_precision: None,
+        hop_guard: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
     assert_error(res, "Must provide at least 2 assets!");
@@ -69,6 +74,7 @@ fn create(
         router_type: RouterType::AstroSwap,
         offer_precision: None,
         ask_precision: None,
+        hop_guard: None,
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
     assert_error(res, "Duplicated assets in asset infos");
@@ -89,6 +95,7 @@ fn create(
         router_type: RouterType::AstroSwap,
         offer_precision: None,
         ask_precision: None,
+        hop_guard: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg);
     assert!(res.is_ok());
@@ -124,7 +131,8 @@ fn create(
             router: Router(Addr::unchecked(ROUTER)),
             router_type: RouterType::AstroSwap,
             offer_precision: 6,
-            ask_precision: 6
+            ask_precision: 6,
+            hop_guard: None,
         }
     );
 
@@ -243,3 +251,112 @@ fn swap(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
 
     Ok(())
 }
+
+fn hop_guard() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let token_1 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_1),
+    };
+    let token_2 = AssetInfo::Token {
+        contract_addr: Addr::unchecked(TOKEN_2),
+    };
+    let ibc_token = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+    deps.querier.set_price(&token_1, &token_2, Decimal256::percent(200));
+    deps.querier.set_price(&token_2, &ibc_token, Decimal256::percent(60));
+
+    let instantiate_msg = InstantiateMsg {
+        asset_infos: vec![token_1.clone(), token_2.clone(), ibc_token.clone()],
+        router: ROUTER.to_string(),
+        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+        hop_guard: Some(PriceGuard {
+            oracle: ORACLE.to_string(),
+            window: 3600,
+            max_deviation_bps: 500,
+        }),
+    };
+    instantiate(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), instantiate_msg)?;
+
+    // the first hop is swapped to the contract with the oracle minimum receive
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: Some(USER_2.to_string()),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    let swap_hop = CallbackMsg::SwapHop {
+        asset_infos: vec![token_2.clone(), ibc_token.clone()],
+        minimum_receive: Some(Uint128::from(99u128)),
+        max_spread: Some(Decimal::percent(1)),
+        to: Addr::unchecked(USER_2),
+    };
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: ROUTER.to_string(),
+                    amount: Uint128::from(100u128),
+                    msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![SwapOperation::AstroSwap {
+                            offer_asset_info: token_1,
+                            ask_asset_info: token_2.clone(),
+                        }],
+                        minimum_receive: Some(Uint128::from(190u128)),
+                        to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                        max_spread: Some(Decimal::percent(1))
+                    })?,
+                })?,
+                funds: vec![],
+            }),
+            swap_hop.into_cosmos_msg(&env.contract.address)?,
+        ]
+    );
+
+    let msg = ExecuteMsg::Callback(swap_hop);
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // the last hop is swapped to the receiver, the oracle minimum receive is above the route one
+    deps.querier._set_balance(TOKEN_2.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(200u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_2.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: ROUTER.to_string(),
+                amount: Uint128::from(200u128),
+                msg: to_binary(&RouterCw20HookMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::AstroSwap {
+                        offer_asset_info: token_2,
+                        ask_asset_info: ibc_token,
+                    }],
+                    minimum_receive: Some(Uint128::from(114u128)),
+                    to: Some(USER_2.to_string()),
+                    max_spread: Some(Decimal::percent(1))
+                })?,
+            })?,
+            funds: vec![],
+        }),]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,212 @@
+use crate::error::ContractError;
+use crate::state::{Config, EntryInfo, CONFIG, ENTRIES, OWNERSHIP_PROPOSAL};
+
+use astroport::asset::validate_token;
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult,
+};
+use cw_storage_plus::Bound;
+use spectrum::adapters::pair::Pair;
+use spectrum::helper::assert_valid_addr;
+use spectrum::registry::{
+    EntriesResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, RegistryEntry,
+};
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateEntries { add, remove } => update_entries(deps, info, add, remove),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Adds or replaces the entries, then removes the entries of the LP tokens. The liquidity token
+/// of the pair of each added entry must be the LP token. Only the owner can execute this.
+fn update_entries(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<RegistryEntry>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut attrs = vec![attr("action", "update_entries")];
+    for entry in add.unwrap_or_default() {
+        let lp_token = validate_token(deps.api, &entry.lp_token)?;
+        let pair = deps.api.addr_validate(&entry.pair)?;
+        let liquidity_token = Pair(pair.clone()).query_pair_info(&deps.querier)?.liquidity_token;
+        if liquidity_token != lp_token {
+            return Err(ContractError::LpTokenMismatch {
+                pair: pair.to_string(),
+                liquidity_token: liquidity_token.to_string(),
+                lp_token: lp_token.to_string(),
+            });
+        }
+
+        ENTRIES.save(
+            deps.storage,
+            &lp_token,
+            &EntryInfo {
+                farm: deps.api.addr_validate(&entry.farm)?,
+                compound_proxy: deps.api.addr_validate(&entry.compound_proxy)?,
+                generator: deps.api.addr_validate(&entry.generator)?,
+                pair,
+            },
+        )?;
+        attrs.push(attr("add", lp_token));
+    }
+
+    for lp_token in remove.unwrap_or_default() {
+        let lp_token = validate_token(deps.api, &lp_token)?;
+        ENTRIES.remove(deps.storage, &lp_token);
+        attrs.push(attr("remove", lp_token));
+    }
+
+    Ok(Response::new().add_attributes(attrs))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Entry { lp_token } => to_binary(&query_entry(deps, lp_token)?),
+        QueryMsg::Entries { start_after, limit } => to_binary(&query_entries(deps, start_after, limit)?),
+    }
+}
+
+/// ## Description
+/// Returns the entry of the LP token in the message format.
+fn to_registry_entry(lp_token: Addr, entry: EntryInfo) -> RegistryEntry {
+    RegistryEntry {
+        lp_token: lp_token.to_string(),
+        farm: entry.farm.to_string(),
+        compound_proxy: entry.compound_proxy.to_string(),
+        generator: entry.generator.to_string(),
+        pair: entry.pair.to_string(),
+    }
+}
+
+/// ## Description
+/// Returns the entry of the LP token.
+fn query_entry(deps: Deps, lp_token: String) -> StdResult<RegistryEntry> {
+    let lp_token = validate_token(deps.api, &lp_token)?;
+    let entry = ENTRIES
+        .may_load(deps.storage, &lp_token)?
+        .ok_or_else(|| StdError::generic_err(format!("No entry of LP token {}", lp_token)))?;
+
+    Ok(to_registry_entry(lp_token, entry))
+}
+
+/// ## Description
+/// Returns the entries ordered by LP token.
+fn query_entries(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<EntriesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after.map(|lp_token| validate_token(deps.api, &lp_token)).transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let entries = ENTRIES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(lp_token, entry)| to_registry_entry(lp_token, entry)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(EntriesResponse { entries })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored owner and the addresses of the entries.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    for item in ENTRIES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, entry) = item?;
+        assert_valid_addr(deps.api, &entry.farm, "farm")?;
+        assert_valid_addr(deps.api, &entry.compound_proxy, "compound_proxy")?;
+        assert_valid_addr(deps.api, &entry.generator, "generator")?;
+        assert_valid_addr(deps.api, &entry.pair, "pair")?;
+    }
+
+    Ok(())
+}
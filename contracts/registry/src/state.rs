@@ -0,0 +1,34 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores the main parameter for the registry contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to update the entries
+    pub owner: Addr,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// This structure stores the contracts deployed for an LP token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EntryInfo {
+    /// The farm contract
+    pub farm: Addr,
+    /// The compound proxy contract
+    pub compound_proxy: Addr,
+    /// The LP staking contract of the farm
+    pub generator: Addr,
+    /// The pair contract
+    pub pair: Addr,
+}
+
+/// Stores the entries by LP token
+pub const ENTRIES: Map<&Addr, EntryInfo> = Map::new("entries");
@@ -0,0 +1,193 @@
+use astroport::asset::{AssetInfo, PairInfo};
+use astroport::factory::PairType;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, ContractResult, OwnedDeps, Response, StdError, SystemResult,
+    WasmQuery,
+};
+use spectrum::registry::{EntriesResponse, ExecuteMsg, InstantiateMsg, QueryMsg, RegistryEntry};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::Config;
+
+const GOV: &str = "gov";
+const USER_1: &str = "user_1";
+const GENERATOR: &str = "generator";
+const TOKENFACTORY_PAIR: &str = "pair_3";
+const TOKENFACTORY_LP: &str = "factory/pair_3/astroport/share";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    update_entries(&mut deps)?;
+    entries(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+/// Returns the entry of pair_{id}, its LP token is pair_{id}_lp
+fn entry(id: u32) -> RegistryEntry {
+    RegistryEntry {
+        lp_token: format!("pair_{}_lp", id),
+        farm: format!("farm_{}", id),
+        compound_proxy: format!("compound_proxy_{}", id),
+        generator: GENERATOR.to_string(),
+        pair: format!("pair_{}", id),
+    }
+}
+
+fn query_entries(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<String>, ContractError> {
+    let res: EntriesResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Entries { start_after, limit },
+    )?)?;
+    Ok(res.entries.into_iter().map(|entry| entry.lp_token).collect())
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(GOV, &[]);
+    let msg = InstantiateMsg { owner: GOV.to_string() };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(config, Config { owner: Addr::unchecked(GOV) });
+
+    deps.querier.update_wasm(|request| match request {
+        WasmQuery::Smart { contract_addr, .. } => {
+            let liquidity_token = if contract_addr == TOKENFACTORY_PAIR {
+                TOKENFACTORY_LP.to_string()
+            } else {
+                format!("{}_lp", contract_addr)
+            };
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&PairInfo {
+                    asset_infos: vec![
+                        AssetInfo::NativeToken { denom: "uluna".to_string() },
+                        AssetInfo::NativeToken { denom: "ibc/stablecoin".to_string() },
+                    ],
+                    contract_addr: Addr::unchecked(contract_addr),
+                    liquidity_token: Addr::unchecked(liquidity_token),
+                    pair_type: PairType::Xyk {},
+                })
+                .unwrap(),
+            ))
+        }
+        _ => panic!("Unexpected query"),
+    });
+
+    Ok(())
+}
+
+fn update_entries(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateEntries { add: Some(vec![entry(1)]), remove: None };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    // the LP token must be the liquidity token of the pair
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateEntries {
+        add: Some(vec![RegistryEntry { pair: "pair_2".to_string(), ..entry(1) }]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Liquidity token of pair pair_2 is pair_2_lp, not pair_1_lp");
+
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateEntries {
+        add: Some(vec![
+            entry(1),
+            entry(2),
+            RegistryEntry {
+                lp_token: TOKENFACTORY_LP.to_string(),
+                pair: TOKENFACTORY_PAIR.to_string(),
+                ..entry(3)
+            },
+        ]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_entries"),
+            attr("add", "pair_1_lp"),
+            attr("add", "pair_2_lp"),
+            attr("add", TOKENFACTORY_LP),
+        ]
+    );
+
+    let res: RegistryEntry = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Entry { lp_token: "pair_1_lp".to_string() },
+    )?)?;
+    assert_eq!(res, entry(1));
+
+    // an entry is replaced by the entry of the same LP token
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateEntries {
+        add: Some(vec![RegistryEntry { farm: "farm_1_v2".to_string(), ..entry(1) }]),
+        remove: Some(vec!["pair_2_lp".to_string()]),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_entries"),
+            attr("add", "pair_1_lp"),
+            attr("remove", "pair_2_lp"),
+        ]
+    );
+
+    let res: RegistryEntry = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Entry { lp_token: "pair_1_lp".to_string() },
+    )?)?;
+    assert_eq!(res.farm, "farm_1_v2");
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Entry { lp_token: "pair_2_lp".to_string() });
+    assert_eq!(res, Err(StdError::generic_err("No entry of LP token pair_2_lp")));
+
+    Ok(())
+}
+
+fn entries(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateEntries { add: Some(vec![entry(4), entry(5)]), remove: None };
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    assert_eq!(
+        query_entries(deps, None, None)?,
+        vec![TOKENFACTORY_LP, "pair_1_lp", "pair_4_lp", "pair_5_lp"]
+    );
+    assert_eq!(
+        query_entries(deps, Some("pair_1_lp".to_string()), Some(2))?,
+        vec!["pair_4_lp", "pair_5_lp"]
+    );
+    assert_eq!(query_entries(deps, Some("pair_5_lp".to_string()), None)?, Vec::<String>::new());
+
+    Ok(())
+}
@@ -0,0 +1,594 @@
+use crate::error::ContractError;
+use crate::pair::{query_pair, simulate, swap_msg};
+use crate::state::{split_route_key, Config, SplitRouteConfig, CONFIG, OWNERSHIP_PROPOSAL, SPLIT_ROUTES};
+
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Uint128,
+};
+use std::collections::HashSet;
+use cw20::Cw20ReceiveMsg;
+use spectrum::helper::assert_valid_addr;
+use spectrum::adapters::router::{SimulateSwapOperationsResponse, SwapOperation};
+use spectrum::querier::query_asset_balance;
+use spectrum::router::{
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SplitRoute,
+    MAX_SPLIT_PAIRS, MAX_SWAP_OPERATIONS,
+};
+
+/// The number of parts of the offer amount assigned one by one in a computed split
+const SPLIT_STEPS: u128 = 10;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            astroport_factory: deps.api.addr_validate(&msg.astroport_factory)?,
+            terraswap_factory: msg
+                .terraswap_factory
+                .map(|it| deps.api.addr_validate(&it))
+                .transpose()?,
+            whitewhale_factory: msg
+                .whitewhale_factory
+                .map(|it| deps.api.addr_validate(&it))
+                .transpose()?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            to,
+            max_spread,
+        } => {
+            let offer_asset_info = operations
+                .first()
+                .ok_or(ContractError::MustProvideOperations {})?
+                .get_offer_asset_info();
+            let amount = match &offer_asset_info {
+                AssetInfo::NativeToken { denom } => info
+                    .funds
+                    .iter()
+                    .find(|it| &it.denom == denom)
+                    .map(|it| it.amount)
+                    .ok_or(ContractError::InvalidOfferAsset {})?,
+                AssetInfo::Token { .. } => return Err(ContractError::InvalidOfferAsset {}),
+            };
+            execute_swap_operations(
+                deps,
+                env,
+                info.sender,
+                Asset { info: offer_asset_info, amount },
+                operations,
+                minimum_receive,
+                to,
+                max_spread,
+            )
+        }
+        ExecuteMsg::UpdateSplitRoutes { add, remove } => update_split_routes(deps, info, add, remove),
+        ExecuteMsg::UpdateConfig {
+            astroport_factory,
+            terraswap_factory,
+            whitewhale_factory,
+        } => update_config(deps, info, astroport_factory, terraswap_factory, whitewhale_factory),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
+    }
+}
+
+/// ## Description
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            to,
+            max_spread,
+        } => execute_swap_operations(
+            deps,
+            env,
+            Addr::unchecked(cw20_msg.sender),
+            Asset {
+                info: AssetInfo::Token { contract_addr: info.sender },
+                amount: cw20_msg.amount,
+            },
+            operations,
+            minimum_receive,
+            to,
+            max_spread,
+        ),
+    }
+}
+
+/// ## Description
+/// Updates contract config. Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    astroport_factory: Option<String>,
+    terraswap_factory: Option<String>,
+    whitewhale_factory: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(astroport_factory) = astroport_factory {
+        config.astroport_factory = deps.api.addr_validate(&astroport_factory)?;
+    }
+
+    if let Some(terraswap_factory) = terraswap_factory {
+        config.terraswap_factory = Some(deps.api.addr_validate(&terraswap_factory)?);
+    }
+
+    if let Some(whitewhale_factory) = whitewhale_factory {
+        config.whitewhale_factory = Some(deps.api.addr_validate(&whitewhale_factory)?);
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Adds or removes split routes. Only the owner can execute this.
+fn update_split_routes(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<SplitRoute>>,
+    remove: Option<Vec<[AssetInfo; 2]>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(remove) = remove {
+        for asset_infos in remove {
+            SPLIT_ROUTES.remove(deps.storage, split_route_key(&asset_infos[0], &asset_infos[1]));
+        }
+    }
+
+    if let Some(add) = add {
+        for split_route in add {
+            let split_route = validate_split_route(deps.api, split_route)?;
+            let key = split_route_key(&split_route.asset_infos[0], &split_route.asset_infos[1]);
+            SPLIT_ROUTES.save(deps.storage, key, &split_route)?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_split_routes"))
+}
+
+/// ## Description
+/// Validates the assets and pairs of the split route. Returns a [`ContractError`] if the route has
+/// the same asset twice, fewer than 2 or more than [`MAX_SPLIT_PAIRS`] pairs, the same pair twice,
+/// or a zero weight while the split is not computed.
+fn validate_split_route(api: &dyn Api, split_route: SplitRoute) -> Result<SplitRouteConfig, ContractError> {
+    let [asset_a, asset_b] = &split_route.asset_infos;
+    asset_a.check(api)?;
+    asset_b.check(api)?;
+    if asset_a.equal(asset_b) {
+        return Err(ContractError::InvalidSplitRoute(format!("same asset {}", asset_a)));
+    }
+    if split_route.pairs.len() < 2 || split_route.pairs.len() > MAX_SPLIT_PAIRS {
+        return Err(ContractError::InvalidSplitRoute(format!("must have 2 to {} pairs", MAX_SPLIT_PAIRS)));
+    }
+
+    let mut uniq = HashSet::new();
+    let pairs = split_route
+        .pairs
+        .into_iter()
+        .map(|(pair, weight)| {
+            let pair = api.addr_validate(&pair)?;
+            if weight == 0 && !split_route.computed {
+                return Err(ContractError::InvalidSplitRoute(format!("zero weight for {}", pair)));
+            }
+            if !uniq.insert(pair.clone()) {
+                return Err(ContractError::InvalidSplitRoute(format!("duplicated pair {}", pair)));
+            }
+            Ok((pair, weight))
+        })
+        .collect::<Result<_, ContractError>>()?;
+
+    Ok(SplitRouteConfig {
+        asset_infos: split_route.asset_infos,
+        pairs,
+        computed: split_route.computed,
+    })
+}
+
+/// ## Description
+/// Validates the number of operations and that each operation offers the ask asset of the
+/// previous one, and that the pairs of all operations can be found.
+fn assert_operations(config: &Config, operations: &[SwapOperation]) -> Result<(), ContractError> {
+    if operations.is_empty() {
+        return Err(ContractError::MustProvideOperations {});
+    }
+    if operations.len() > MAX_SWAP_OPERATIONS {
+        return Err(ContractError::SwapLimitExceeded {});
+    }
+
+    let mut prev_ask_asset_info: Option<AssetInfo> = None;
+    for operation in operations {
+        config.factory(operation)?;
+        if let Some(prev_ask_asset_info) = prev_ask_asset_info {
+            if !prev_ask_asset_info.equal(&operation.get_offer_asset_info()) {
+                return Err(ContractError::InvalidOperations {});
+            }
+        }
+        prev_ask_asset_info = Some(operation.get_ask_asset_info());
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Swaps the offer asset through the operations, one callback per operation. The last callback
+/// sends the ask asset to the receiver, followed by the minimum receive assertion if required.
+#[allow(clippy::too_many_arguments)]
+fn execute_swap_operations(
+    deps: DepsMut,
+    env: Env,
+    sender: Addr,
+    offer_asset: Asset,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: Option<String>,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_operations(&config, &operations)?;
+    if !operations[0].get_offer_asset_info().equal(&offer_asset.info) {
+        return Err(ContractError::InvalidOfferAsset {});
+    }
+
+    let receiver = match to {
+        Some(to) => deps.api.addr_validate(&to)?,
+        None => sender,
+    };
+    let ask_asset_info = operations[operations.len() - 1].get_ask_asset_info();
+
+    let last_index = operations.len() - 1;
+    let mut messages: Vec<CosmosMsg> = operations
+        .into_iter()
+        .enumerate()
+        .map(|(index, operation)| {
+            CallbackMsg::ExecuteSwapOperation {
+                operation,
+                max_spread,
+                to: if index == last_index { Some(receiver.to_string()) } else { None },
+            }
+            .into_cosmos_msg(&env.contract.address)
+        })
+        .collect::<StdResult<_>>()?;
+
+    if let Some(minimum_receive) = minimum_receive {
+        let prev_balance = query_asset_balance(&deps.querier, &ask_asset_info, &receiver)?;
+        messages.push(
+            CallbackMsg::AssertMinimumReceive {
+                asset_info: ask_asset_info,
+                prev_balance,
+                minimum_receive,
+                receiver: receiver.to_string(),
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "execute_swap_operations"),
+            attr("offer_asset", offer_asset.to_string()),
+            attr("receiver", receiver),
+        ]))
+}
+
+/// # Description
+/// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
+fn handle_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CallbackMsg,
+) -> Result<Response, ContractError> {
+    // Callback functions can only be called by this contract itself
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    match msg {
+        CallbackMsg::ExecuteSwapOperation {
+            operation,
+            max_spread,
+            to,
+        } => execute_swap_operation(deps, env, operation, max_spread, to),
+        CallbackMsg::AssertMinimumReceive {
+            asset_info,
+            prev_balance,
+            minimum_receive,
+            receiver,
+        } => assert_minimum_receive(deps, asset_info, prev_balance, minimum_receive, receiver),
+    }
+}
+
+/// # Description
+/// Swaps the whole contract balance of the offer asset on the pair of the operation.
+fn execute_swap_operation(
+    deps: DepsMut,
+    env: Env,
+    operation: SwapOperation,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let offer_asset_info = operation.get_offer_asset_info();
+    let amount = query_asset_balance(&deps.querier, &offer_asset_info, &env.contract.address)?;
+
+    let mut response = Response::new().add_attribute("action", "execute_swap_operation");
+    for (pair, amount) in split_operation(deps.as_ref(), &config, &operation, amount)? {
+        let offer_asset = offer_asset_info.with_balance(amount);
+        response = response
+            .add_message(swap_msg(&pair, offer_asset.clone(), max_spread, to.clone())?)
+            .add_attributes(vec![
+                attr("pair", pair),
+                attr("offer_asset", offer_asset.to_string()),
+            ]);
+    }
+
+    Ok(response)
+}
+
+/// # Description
+/// Returns the pairs the offer amount of the operation is swapped on and the amount of each. An
+/// Astroport operation with a split route is split across its pools, others swap on the factory pair.
+fn split_operation(
+    deps: Deps,
+    config: &Config,
+    operation: &SwapOperation,
+    amount: Uint128,
+) -> Result<Vec<(Addr, Uint128)>, ContractError> {
+    let factory = config.factory(operation)?;
+    let offer_asset_info = operation.get_offer_asset_info();
+    let ask_asset_info = operation.get_ask_asset_info();
+
+    let split_route = match operation {
+        SwapOperation::AstroSwap { .. } => {
+            SPLIT_ROUTES.may_load(deps.storage, split_route_key(&offer_asset_info, &ask_asset_info))?
+        }
+        _ => None,
+    };
+    let split_route = match split_route {
+        Some(split_route) => split_route,
+        None => {
+            let pair = query_pair(&deps.querier, &factory, offer_asset_info, ask_asset_info)?;
+            return Ok(vec![(pair, amount)]);
+        }
+    };
+
+    let parts = if split_route.computed {
+        split_by_simulation(&deps.querier, &split_route, &offer_asset_info, amount)?
+    } else {
+        split_by_weight(&split_route, amount)
+    };
+
+    Ok(parts.into_iter().filter(|(_, amount)| !amount.is_zero()).collect())
+}
+
+/// # Description
+/// Splits the amount by the weights of the pairs, the last pair receives the rounding remainder.
+fn split_by_weight(split_route: &SplitRouteConfig, amount: Uint128) -> Vec<(Addr, Uint128)> {
+    let total_weight: u64 = split_route.pairs.iter().map(|(_, weight)| *weight).sum();
+    let mut remaining = amount;
+    let mut parts = vec![];
+    for (index, (pair, weight)) in split_route.pairs.iter().enumerate() {
+        let part = if index == split_route.pairs.len() - 1 {
+            remaining
+        } else {
+            amount.multiply_ratio(*weight, total_weight)
+        };
+        remaining -= part;
+        parts.push((pair.clone(), part));
+    }
+    parts
+}
+
+/// # Description
+/// Splits the amount in [`SPLIT_STEPS`] parts, each part goes to the pair with the best return for it
+/// on top of the parts the pair already has.
+fn split_by_simulation(
+    querier: &QuerierWrapper,
+    split_route: &SplitRouteConfig,
+    offer_asset_info: &AssetInfo,
+    amount: Uint128,
+) -> StdResult<Vec<(Addr, Uint128)>> {
+    let step = std::cmp::max(amount / Uint128::from(SPLIT_STEPS), Uint128::from(1u128));
+    let mut amounts = vec![Uint128::zero(); split_route.pairs.len()];
+    let mut returns = vec![Uint128::zero(); split_route.pairs.len()];
+
+    let mut remaining = amount;
+    while !remaining.is_zero() {
+        let part = std::cmp::min(step, remaining);
+        let mut best: Option<(usize, Uint128, Uint128)> = None;
+        for (index, (pair, _)) in split_route.pairs.iter().enumerate() {
+            let return_amount = simulate(querier, pair, offer_asset_info.with_balance(amounts[index] + part))?;
+            let marginal = return_amount.saturating_sub(returns[index]);
+            match best {
+                Some((_, best_marginal, _)) if marginal <= best_marginal => {}
+                _ => best = Some((index, marginal, return_amount)),
+            }
+        }
+        if let Some((index, _, return_amount)) = best {
+            amounts[index] += part;
+            returns[index] = return_amount;
+        }
+        remaining -= part;
+    }
+
+    Ok(split_route.pairs.iter().map(|(pair, _)| pair.clone()).zip(amounts).collect())
+}
+
+/// # Description
+/// Checks the receiver balance of the asset increased by at least the minimum receive amount.
+fn assert_minimum_receive(
+    deps: DepsMut,
+    asset_info: AssetInfo,
+    prev_balance: Uint128,
+    minimum_receive: Uint128,
+    receiver: String,
+) -> Result<Response, ContractError> {
+    let balance = query_asset_balance(&deps.querier, &asset_info, receiver)?;
+    let amount = balance.checked_sub(prev_balance)?;
+    if amount < minimum_receive {
+        return Err(ContractError::AssertionMinimumReceive {
+            minimum_receive,
+            amount,
+        });
+    }
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::SplitRoutes {} => to_binary(&query_split_routes(deps)?),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount,
+            operations,
+        } => to_binary(
+            &query_simulate_swap_operations(deps, offer_amount, operations)
+                .map_err(|e| StdError::generic_err(e.to_string()))?,
+        ),
+    }
+}
+
+/// ## Description
+/// Returns the amount of the last ask asset received from swapping the offer amount through the
+/// operations.
+fn query_simulate_swap_operations(
+    deps: Deps,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> Result<SimulateSwapOperationsResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_operations(&config, &operations)?;
+
+    let mut amount = offer_amount;
+    for operation in operations {
+        let offer_asset_info = operation.get_offer_asset_info();
+        let mut return_amount = Uint128::zero();
+        for (pair, amount) in split_operation(deps, &config, &operation, amount)? {
+            return_amount += simulate(&deps.querier, &pair, offer_asset_info.with_balance(amount))?;
+        }
+        amount = return_amount;
+    }
+
+    Ok(SimulateSwapOperationsResponse { amount })
+}
+
+/// ## Description
+/// Returns the split routes.
+fn query_split_routes(deps: Deps) -> StdResult<Vec<SplitRouteConfig>> {
+    SPLIT_ROUTES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored owner, factory and split route pair addresses.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.astroport_factory, "astroport_factory")?;
+    if let Some(terraswap_factory) = &config.terraswap_factory {
+        assert_valid_addr(deps.api, terraswap_factory, "terraswap_factory")?;
+    }
+    if let Some(whitewhale_factory) = &config.whitewhale_factory {
+        assert_valid_addr(deps.api, whitewhale_factory, "whitewhale_factory")?;
+    }
+    for item in SPLIT_ROUTES.range(deps.storage, None, None, Order::Ascending) {
+        for (pair, _) in item?.1.pairs.iter() {
+            assert_valid_addr(deps.api, pair, "split route pair")?;
+        }
+    }
+
+    Ok(())
+}
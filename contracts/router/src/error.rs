@@ -0,0 +1,43 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes router contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Must provide swap operations to execute")]
+    MustProvideOperations {},
+
+    #[error("The limit exceeded of swap operations!")]
+    SwapLimitExceeded {},
+
+    #[error("Invalid swap operations; offer asset of each operation must be the previous ask asset")]
+    InvalidOperations {},
+
+    #[error("Offer asset does not match the first swap operation")]
+    InvalidOfferAsset {},
+
+    #[error("Swap operation is not supported")]
+    UnsupportedOperation {},
+
+    #[error("{0} factory is not set")]
+    FactoryNotSet(String),
+
+    #[error("Invalid split route: {0}")]
+    InvalidSplitRoute(String),
+
+    #[error("Assertion failed; minimum receive amount: {minimum_receive}, swap amount: {amount}")]
+    AssertionMinimumReceive { minimum_receive: Uint128, amount: Uint128 },
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
@@ -0,0 +1,10 @@
+pub mod contract;
+pub mod error;
+pub mod pair;
+pub mod state;
+
+#[cfg(test)]
+mod mock_querier;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use cosmwasm_std::{BalanceResponse, BankQuery, Binary, Coin, ContractResult, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery, Addr};
+use cosmwasm_std::testing::{MockApi, MockStorage};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use astroport::asset::{Asset, AssetInfo};
+use crate::pair::{PairInfo, SimulationResponse};
+
+pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+        custom_query_type: Default::default()
+    }
+}
+
+pub struct WasmMockQuerier {
+    balances: HashMap<(String, String), Uint128>,
+    pairs: HashMap<(String, Vec<u8>), String>,
+    reserves: HashMap<String, Uint128>,
+}
+
+impl WasmMockQuerier {
+    pub fn new() -> Self {
+        WasmMockQuerier {
+            balances: HashMap::new(),
+            pairs: HashMap::new(),
+            reserves: HashMap::new(),
+        }
+    }
+
+    pub fn set_balance(&mut self, token: String, addr: String, amount: Uint128) {
+        self.balances.insert((token, addr), amount);
+    }
+
+    fn get_balance(&self, token: String, addr: String) -> Uint128 {
+        *self.balances.get(&(token, addr)).unwrap_or(&Uint128::zero())
+    }
+
+    pub fn set_pair(&mut self, factory: &str, asset_infos: &[AssetInfo; 2], pair: &str) {
+        self.pairs.insert((factory.to_string(), pair_key(asset_infos)), pair.to_string());
+    }
+
+    pub fn set_reserve(&mut self, pair: &str, reserve: Uint128) {
+        self.reserves.insert(pair.to_string(), reserve);
+    }
+
+    fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        let result = match request {
+            QueryRequest::Bank(BankQuery::Balance {
+                                   address,
+                                   denom,
+                               }) => {
+                let amount = self.get_balance(denom.clone(), address.clone());
+                to_binary(&BalanceResponse {
+                    amount: Coin {
+                        denom: denom.clone(),
+                        amount,
+                    },
+                })
+            },
+            QueryRequest::Wasm(WasmQuery::Smart {
+                                   contract_addr,
+                                   msg,
+                               }) => self.execute_wasm_query(contract_addr, msg),
+            _ => return QuerierResult::Err(SystemError::Unknown {}),
+        };
+        QuerierResult::Ok(ContractResult::from(result))
+    }
+
+    fn execute_wasm_query(&self, contract_addr: &str, msg: &Binary) -> StdResult<Binary> {
+        match from_binary(msg)? {
+            MockQueryMsg::Balance {
+                address,
+            } => {
+                let balance = self.get_balance(contract_addr.to_string(), address);
+                to_binary(&cw20::BalanceResponse {
+                    balance,
+                })
+            },
+            MockQueryMsg::Pair {
+                asset_infos,
+            } => {
+                let pair = match self.pairs.get(&(contract_addr.to_string(), pair_key(&asset_infos))) {
+                    Some(v) => v.clone(),
+                    None => {
+                        panic!("No pair info")
+                    }
+                };
+
+                to_binary(&PairInfo {
+                    contract_addr: Addr::unchecked(pair),
+                })
+            },
+            // a pair with a reserve is an xyk pool holding the reserve of both assets, every other pair
+            // swaps at a price of 2 ask asset per offer asset
+            MockQueryMsg::Simulation {
+                offer_asset,
+            } => {
+                let return_amount = match self.reserves.get(contract_addr) {
+                    Some(reserve) => reserve.multiply_ratio(offer_asset.amount, *reserve + offer_asset.amount),
+                    None => offer_asset.amount * Uint128::from(2u128),
+                };
+                to_binary(&SimulationResponse {
+                    return_amount,
+                })
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum MockQueryMsg {
+    Balance {
+        address: String,
+    },
+    Pair {
+        asset_infos: [AssetInfo; 2],
+    },
+    Simulation {
+        offer_asset: Asset,
+    },
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.execute_query(&request)
+    }
+}
+
+fn pair_key(asset_infos: &[AssetInfo; 2]) -> Vec<u8> {
+    let mut asset_infos = asset_infos.to_vec();
+    asset_infos.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+
+    [asset_infos[0].as_bytes(), asset_infos[1].as_bytes()].concat()
+}
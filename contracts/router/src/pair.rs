@@ -0,0 +1,121 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Astroport, TerraSwap and White Whale share the TerraSwap pair interface. The messages below
+// leave out the fields not known to every dex, as some of them reject unknown fields.
+
+/// This structure describes the factory query to find a pair.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FactoryQueryMsg {
+    Pair { asset_infos: [AssetInfo; 2] },
+}
+
+/// This structure holds the pair info fields used by the router.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairInfo {
+    pub contract_addr: Addr,
+}
+
+/// This structure describes the pair query to simulate a swap.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairQueryMsg {
+    Simulation { offer_asset: Asset },
+}
+
+/// This structure holds the simulation fields used by the router.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulationResponse {
+    pub return_amount: Uint128,
+}
+
+/// This structure describes the pair message to swap a native token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairExecuteMsg {
+    Swap {
+        offer_asset: Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+/// This structure describes the pair hook message to swap a token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PairCw20HookMsg {
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+/// Returns the address of the pair of the assets in the factory
+pub fn query_pair(
+    querier: &QuerierWrapper,
+    factory: &Addr,
+    offer_asset_info: AssetInfo,
+    ask_asset_info: AssetInfo,
+) -> StdResult<Addr> {
+    let pair_info: PairInfo = querier.query_wasm_smart(
+        factory.to_string(),
+        &FactoryQueryMsg::Pair {
+            asset_infos: [offer_asset_info, ask_asset_info],
+        },
+    )?;
+    Ok(pair_info.contract_addr)
+}
+
+/// Returns the amount of the ask asset received from swapping the offer asset on the pair
+pub fn simulate(querier: &QuerierWrapper, pair: &Addr, offer_asset: Asset) -> StdResult<Uint128> {
+    let res: SimulationResponse = querier
+        .query_wasm_smart(pair.to_string(), &PairQueryMsg::Simulation { offer_asset })?;
+    Ok(res.return_amount)
+}
+
+/// Generate msg for swapping the offer asset on the pair
+pub fn swap_msg(
+    pair: &Addr,
+    offer_asset: Asset,
+    max_spread: Option<Decimal>,
+    to: Option<String>,
+) -> StdResult<CosmosMsg> {
+    let wasm_msg = match &offer_asset.info {
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount: offer_asset.amount,
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread,
+                    to,
+                })?,
+            })?,
+            funds: vec![],
+        },
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: pair.to_string(),
+            funds: vec![Coin {
+                denom: denom.clone(),
+                amount: offer_asset.amount,
+            }],
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset,
+                belief_price: None,
+                max_spread,
+                to,
+            })?,
+        },
+    };
+
+    Ok(CosmosMsg::Wasm(wasm_msg))
+}
@@ -0,0 +1,65 @@
+use crate::error::ContractError;
+use astroport::common::OwnershipProposal;
+use astroport::asset::AssetInfo;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::adapters::router::SwapOperation;
+
+/// This structure stores the main parameter for the router contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to update config
+    pub owner: Addr,
+    /// The Astroport factory contract address
+    pub astroport_factory: Addr,
+    /// The TerraSwap factory contract address
+    pub terraswap_factory: Option<Addr>,
+    /// The White Whale factory contract address
+    pub whitewhale_factory: Option<Addr>,
+}
+
+impl Config {
+    /// Returns the factory of the pairs the operation swaps on
+    pub fn factory(&self, operation: &SwapOperation) -> Result<Addr, ContractError> {
+        match operation {
+            SwapOperation::AstroSwap { .. } => Ok(self.astroport_factory.clone()),
+            SwapOperation::TerraSwap { .. } => self
+                .terraswap_factory
+                .clone()
+                .ok_or_else(|| ContractError::FactoryNotSet("TerraSwap".to_string())),
+            SwapOperation::WhiteWhale { .. } => self
+                .whitewhale_factory
+                .clone()
+                .ok_or_else(|| ContractError::FactoryNotSet("White Whale".to_string())),
+            SwapOperation::TokenSwap { .. } => Err(ContractError::UnsupportedOperation {}),
+        }
+    }
+}
+
+/// This structure stores the pools of an asset pair that one swap is split across.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SplitRouteConfig {
+    /// The assets of the pools
+    pub asset_infos: [AssetInfo; 2],
+    /// The pair contracts and their weights of the offer amount
+    pub pairs: Vec<(Addr, u64)>,
+    /// Splits the offer amount by simulating the pools instead of by weight
+    pub computed: bool,
+}
+
+/// Returns the storage key of the split route of the assets, the same in both swap directions
+pub fn split_route_key(asset_a: &AssetInfo, asset_b: &AssetInfo) -> (String, String) {
+    let (a, b) = (asset_a.to_string(), asset_b.to_string());
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the split routes, keyed by [`split_route_key`]
+pub const SPLIT_ROUTES: Map<(String, String), SplitRouteConfig> = Map::new("split_routes");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -0,0 +1,485 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use spectrum::adapters::router::{SimulateSwapOperationsResponse, SwapOperation};
+use spectrum::router::{CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, SplitRoute};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use crate::pair::{PairCw20HookMsg, PairExecuteMsg};
+use crate::state::{Config, SplitRouteConfig};
+
+const OWNER: &str = "owner";
+const ASTROPORT_FACTORY: &str = "astroport_factory";
+const TERRASWAP_FACTORY: &str = "terraswap_factory";
+const WHITEWHALE_FACTORY: &str = "whitewhale_factory";
+const ASTROPORT_PAIR: &str = "astroport_pair";
+const ASTROPORT_PCL_PAIR: &str = "astroport_pcl_pair";
+const WHITEWHALE_PAIR: &str = "whitewhale_pair";
+const IBC_TOKEN: &str = "ibc/stablecoin";
+const TOKEN_1: &str = "token_1";
+const TOKEN_2: &str = "token_2";
+const USER_1: &str = "user_1";
+const USER_2: &str = "user_2";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    config(&mut deps)?;
+    execute_swap_operations(&mut deps)?;
+    callback(&mut deps)?;
+    simulate(&mut deps)?;
+    split_routes(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn native(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken { denom: denom.to_string() }
+}
+
+fn token(contract_addr: &str) -> AssetInfo {
+    AssetInfo::Token { contract_addr: Addr::unchecked(contract_addr) }
+}
+
+/// Swaps stablecoin to TOKEN_1 on Astroport, then TOKEN_1 to TOKEN_2 on White Whale
+fn route() -> Vec<SwapOperation> {
+    vec![
+        SwapOperation::AstroSwap {
+            offer_asset_info: native(IBC_TOKEN),
+            ask_asset_info: token(TOKEN_1),
+        },
+        SwapOperation::WhiteWhale {
+            offer_asset_info: token(TOKEN_1),
+            ask_asset_info: token(TOKEN_2),
+        },
+    ]
+}
+
+fn swap_operations_msg(operations: Vec<SwapOperation>, minimum_receive: Option<u128>) -> ExecuteMsg {
+    ExecuteMsg::ExecuteSwapOperations {
+        operations,
+        minimum_receive: minimum_receive.map(Uint128::from),
+        to: None,
+        max_spread: Some(Decimal::percent(1)),
+    }
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(OWNER, &[]);
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        astroport_factory: ASTROPORT_FACTORY.to_string(),
+        terraswap_factory: Some(TERRASWAP_FACTORY.to_string()),
+        whitewhale_factory: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    deps.querier.set_pair(ASTROPORT_FACTORY, &[native(IBC_TOKEN), token(TOKEN_1)], ASTROPORT_PAIR);
+    deps.querier.set_pair(WHITEWHALE_FACTORY, &[token(TOKEN_1), token(TOKEN_2)], WHITEWHALE_PAIR);
+
+    Ok(())
+}
+
+fn config(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[coin(100, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operations_msg(route(), None));
+    assert_error(res, "White Whale factory is not set");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        astroport_factory: None,
+        terraswap_factory: None,
+        whitewhale_factory: Some(WHITEWHALE_FACTORY.to_string()),
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(OWNER),
+            astroport_factory: Addr::unchecked(ASTROPORT_FACTORY),
+            terraswap_factory: Some(Addr::unchecked(TERRASWAP_FACTORY)),
+            whitewhale_factory: Some(Addr::unchecked(WHITEWHALE_FACTORY)),
+        }
+    );
+
+    Ok(())
+}
+
+fn execute_swap_operations(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[coin(100, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operations_msg(vec![], None));
+    assert_error(res, "Must provide swap operations to execute");
+
+    let info = mock_info(USER_1, &[coin(100, IBC_TOKEN)]);
+    let operations = vec![route()[1].clone()];
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operations_msg(operations, None));
+    assert_error(res, "Offer asset does not match the first swap operation");
+
+    let info = mock_info(USER_1, &[coin(100, IBC_TOKEN)]);
+    let operations = vec![route()[0].clone(), route()[0].clone()];
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operations_msg(operations, None));
+    assert_error(res, "Invalid swap operations; offer asset of each operation must be the previous ask asset");
+
+    let info = mock_info(USER_1, &[coin(100, IBC_TOKEN)]);
+    let operations = vec![SwapOperation::TokenSwap {
+        offer_asset_info: native(IBC_TOKEN),
+        ask_asset_info: token(TOKEN_1),
+    }];
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operations_msg(operations, None));
+    assert_error(res, "Swap operation is not supported");
+
+    // hops across dexes and checks the received amount at the end
+    deps.querier.set_balance(TOKEN_2.to_string(), USER_1.to_string(), Uint128::from(50u128));
+    let info = mock_info(USER_1, &[coin(100, IBC_TOKEN)]);
+    let res = execute(deps.as_mut(), mock_env(), info, swap_operations_msg(route(), Some(300)))?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CallbackMsg::ExecuteSwapOperation {
+                operation: route()[0].clone(),
+                max_spread: Some(Decimal::percent(1)),
+                to: None,
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::ExecuteSwapOperation {
+                operation: route()[1].clone(),
+                max_spread: Some(Decimal::percent(1)),
+                to: Some(USER_1.to_string()),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::AssertMinimumReceive {
+                asset_info: token(TOKEN_2),
+                prev_balance: Uint128::from(50u128),
+                minimum_receive: Uint128::from(300u128),
+                receiver: USER_1.to_string(),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "execute_swap_operations"),
+            attr("offer_asset", format!("100{}", IBC_TOKEN)),
+            attr("receiver", USER_1),
+        ]
+    );
+
+    // tokens are sent through the cw20 hook
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: route(),
+            minimum_receive: None,
+            to: None,
+            max_spread: None,
+        })?,
+    });
+    let info = mock_info(TOKEN_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Offer asset does not match the first swap operation");
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::ExecuteSwapOperations {
+            operations: vec![route()[1].clone()],
+            minimum_receive: None,
+            to: Some(USER_2.to_string()),
+            max_spread: None,
+        })?,
+    });
+    let info = mock_info(TOKEN_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CallbackMsg::ExecuteSwapOperation {
+            operation: route()[1].clone(),
+            max_spread: None,
+            to: Some(USER_2.to_string()),
+        }
+        .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?]
+    );
+
+    Ok(())
+}
+
+fn callback(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::Callback(CallbackMsg::ExecuteSwapOperation {
+        operation: route()[0].clone(),
+        max_spread: Some(Decimal::percent(1)),
+        to: None,
+    });
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // swaps the whole balance of the offer asset
+    deps.querier.set_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(100u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: ASTROPORT_PAIR.to_string(),
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: native(IBC_TOKEN),
+                    amount: Uint128::from(100u128),
+                },
+                belief_price: None,
+                max_spread: Some(Decimal::percent(1)),
+                to: None,
+            })?,
+            funds: vec![coin(100, IBC_TOKEN)],
+        })]
+    );
+
+    deps.querier.set_balance(TOKEN_1.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(200u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let msg = ExecuteMsg::Callback(CallbackMsg::ExecuteSwapOperation {
+        operation: route()[1].clone(),
+        max_spread: Some(Decimal::percent(1)),
+        to: Some(USER_1.to_string()),
+    });
+    let res = execute(deps.as_mut(), mock_env(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_1.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: WHITEWHALE_PAIR.to_string(),
+                amount: Uint128::from(200u128),
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: Some(USER_1.to_string()),
+                })?,
+            })?,
+            funds: vec![],
+        })]
+    );
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::AssertMinimumReceive {
+        asset_info: token(TOKEN_2),
+        prev_balance: Uint128::from(50u128),
+        minimum_receive: Uint128::from(300u128),
+        receiver: USER_1.to_string(),
+    });
+
+    deps.querier.set_balance(TOKEN_2.to_string(), USER_1.to_string(), Uint128::from(349u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Assertion failed; minimum receive amount: 300, swap amount: 299");
+
+    deps.querier.set_balance(TOKEN_2.to_string(), USER_1.to_string(), Uint128::from(350u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn simulate(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let res: SimulateSwapOperationsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount: Uint128::from(100u128),
+            operations: route(),
+        },
+    )?)?;
+    assert_eq!(res.amount, Uint128::from(400u128));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount: Uint128::from(100u128),
+            operations: vec![],
+        },
+    );
+    assert_eq!(
+        res,
+        Err(StdError::generic_err("Must provide swap operations to execute"))
+    );
+
+    Ok(())
+}
+
+fn split_routes(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let split_route = |pairs: Vec<(&str, u64)>, computed: bool| ExecuteMsg::UpdateSplitRoutes {
+        add: Some(vec![SplitRoute {
+            asset_infos: [token(TOKEN_1), native(IBC_TOKEN)],
+            pairs: pairs.into_iter().map(|(pair, weight)| (pair.to_string(), weight)).collect(),
+            computed,
+        }]),
+        remove: None,
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let msg = split_route(vec![(ASTROPORT_PAIR, 1), (ASTROPORT_PCL_PAIR, 3)], false);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), split_route(vec![(ASTROPORT_PAIR, 1)], false));
+    assert_error(res, "Invalid split route: must have 2 to 5 pairs");
+
+    let msg = split_route(vec![(ASTROPORT_PAIR, 1), (ASTROPORT_PAIR, 3)], false);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_error(res, "Invalid split route: duplicated pair astroport_pair");
+
+    let msg = split_route(vec![(ASTROPORT_PAIR, 1), (ASTROPORT_PCL_PAIR, 0)], false);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_error(res, "Invalid split route: zero weight for astroport_pcl_pair");
+
+    let msg = split_route(vec![(ASTROPORT_PAIR, 1), (ASTROPORT_PCL_PAIR, 3)], false);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg)?;
+    assert_eq!(res.attributes, vec![attr("action", "update_split_routes")]);
+
+    let split_routes: Vec<SplitRouteConfig> = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::SplitRoutes {})?)?;
+    assert_eq!(
+        split_routes,
+        vec![SplitRouteConfig {
+            asset_infos: [token(TOKEN_1), native(IBC_TOKEN)],
+            pairs: vec![(Addr::unchecked(ASTROPORT_PAIR), 1), (Addr::unchecked(ASTROPORT_PCL_PAIR), 3)],
+            computed: false,
+        }]
+    );
+
+    let swap_msg = |pair: &str, amount: u128| -> Result<CosmosMsg, ContractError> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: pair.to_string(),
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: native(IBC_TOKEN),
+                    amount: Uint128::from(amount),
+                },
+                belief_price: None,
+                max_spread: None,
+                to: None,
+            })?,
+            funds: vec![coin(amount, IBC_TOKEN)],
+        }))
+    };
+    let callback = ExecuteMsg::Callback(CallbackMsg::ExecuteSwapOperation {
+        operation: route()[0].clone(),
+        max_spread: None,
+        to: None,
+    });
+
+    // the balance is split by weight
+    deps.querier.set_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(101u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, callback.clone())?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [swap_msg(ASTROPORT_PAIR, 25)?, swap_msg(ASTROPORT_PCL_PAIR, 76)?]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "execute_swap_operation"),
+            attr("pair", ASTROPORT_PAIR),
+            attr("offer_asset", format!("25{}", IBC_TOKEN)),
+            attr("pair", ASTROPORT_PCL_PAIR),
+            attr("offer_asset", format!("76{}", IBC_TOKEN)),
+        ]
+    );
+
+    // each tenth of the balance goes to the pool with the best return for it
+    deps.querier.set_reserve(ASTROPORT_PAIR, Uint128::from(1000u128));
+    deps.querier.set_reserve(ASTROPORT_PCL_PAIR, Uint128::from(3000u128));
+    let msg = split_route(vec![(ASTROPORT_PAIR, 0), (ASTROPORT_PCL_PAIR, 0)], true);
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg)?;
+
+    deps.querier.set_balance(IBC_TOKEN.to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(400u128));
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, callback)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [swap_msg(ASTROPORT_PAIR, 120)?, swap_msg(ASTROPORT_PCL_PAIR, 280)?]
+    );
+
+    let res: SimulateSwapOperationsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount: Uint128::from(400u128),
+            operations: vec![route()[0].clone()],
+        },
+    )?)?;
+    assert_eq!(res.amount, Uint128::from(363u128));
+
+    // the factory pair is used again once the split route is removed
+    let msg = ExecuteMsg::UpdateSplitRoutes {
+        add: None,
+        remove: Some(vec![[native(IBC_TOKEN), token(TOKEN_1)]]),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg)?;
+    let res: SimulateSwapOperationsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount: Uint128::from(400u128),
+            operations: vec![route()[0].clone()],
+        },
+    )?)?;
+    assert_eq!(res.amount, Uint128::from(285u128));
+
+    Ok(())
+}
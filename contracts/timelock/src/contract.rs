@@ -0,0 +1,254 @@
+use crate::error::ContractError;
+use crate::state::{Config, Operation, CONFIG, OPERATIONS, OPERATION_COUNT};
+
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use spectrum::helper::assert_valid_addr;
+use spectrum::timelock::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, OperationResponse, OperationStatus, OperationsResponse,
+    QueryMsg, TimelockMsg,
+};
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            admin: deps.api.addr_validate(&msg.admin)?,
+            min_delay: msg.min_delay,
+        },
+    )?;
+    OPERATION_COUNT.save(deps.storage, &0u64)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { admin, min_delay } => update_config(deps, env, info, admin, min_delay),
+        ExecuteMsg::Queue { msgs, eta } => queue(deps, env, info, msgs, eta),
+        ExecuteMsg::Execute { id } => execute_operation(deps, env, info, id),
+        ExecuteMsg::Cancel { id } => cancel(deps, info, id),
+    }
+}
+
+/// ## Description
+/// Updates contract config, only the contract itself can execute this through an operation.
+fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admin: Option<String>,
+    min_delay: Option<u64>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(admin) = admin {
+        config.admin = deps.api.addr_validate(&admin)?;
+    }
+
+    if let Some(min_delay) = min_delay {
+        config.min_delay = min_delay;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Queues an operation executable from the eta. Only the admin can execute this.
+fn queue(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<TimelockMsg>,
+    eta: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    if msgs.is_empty() {
+        return Err(ContractError::EmptyOperation {});
+    }
+    for msg in msgs.iter() {
+        deps.api.addr_validate(&msg.contract)?;
+    }
+
+    let min_eta = env.block.time.seconds() + config.min_delay;
+    if eta < min_eta {
+        return Err(ContractError::EtaTooEarly(min_eta));
+    }
+
+    let id = OPERATION_COUNT.load(deps.storage)? + 1;
+    OPERATION_COUNT.save(deps.storage, &id)?;
+    OPERATIONS.save(
+        deps.storage,
+        id,
+        &Operation {
+            msgs,
+            eta,
+            status: OperationStatus::Queued,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "queue"),
+        attr("id", id.to_string()),
+        attr("eta", eta.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Executes a queued operation after its eta. Only the admin can execute this.
+fn execute_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut operation = OPERATIONS.load(deps.storage, id)?;
+    if operation.status != OperationStatus::Queued {
+        return Err(ContractError::NotQueued {});
+    }
+    if env.block.time.seconds() < operation.eta {
+        return Err(ContractError::NotReady(operation.eta));
+    }
+    operation.status = OperationStatus::Executed;
+    OPERATIONS.save(deps.storage, id, &operation)?;
+
+    let messages: Vec<CosmosMsg> = operation.msgs
+        .into_iter()
+        .map(|msg| CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: msg.contract,
+            msg: msg.msg,
+            funds: vec![],
+        }))
+        .collect();
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "execute"),
+            attr("id", id.to_string()),
+        ]))
+}
+
+/// ## Description
+/// Cancels a queued operation. Only the admin can execute this.
+fn cancel(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut operation = OPERATIONS.load(deps.storage, id)?;
+    if operation.status != OperationStatus::Queued {
+        return Err(ContractError::NotQueued {});
+    }
+    operation.status = OperationStatus::Cancelled;
+    OPERATIONS.save(deps.storage, id, &operation)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "cancel"),
+        attr("id", id.to_string()),
+    ]))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Operation { id } => to_binary(&query_operation(deps, id)?),
+        QueryMsg::Operations { start_after, limit } => {
+            to_binary(&query_operations(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn to_operation_response(id: u64, operation: Operation) -> OperationResponse {
+    OperationResponse {
+        id,
+        msgs: operation.msgs,
+        eta: operation.eta,
+        status: operation.status,
+    }
+}
+
+/// ## Description
+/// Returns the operation.
+fn query_operation(deps: Deps, id: u64) -> StdResult<OperationResponse> {
+    Ok(to_operation_response(id, OPERATIONS.load(deps.storage, id)?))
+}
+
+/// ## Description
+/// Returns the operations ordered by id.
+fn query_operations(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<OperationsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let operations = OPERATIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, operation)| to_operation_response(id, operation)))
+        .collect::<StdResult<_>>()?;
+
+    Ok(OperationsResponse { operations })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored admin address.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.admin, "admin")?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes timelock contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Operation must have at least one message")]
+    EmptyOperation {},
+
+    #[error("Operation eta must be at least {0}")]
+    EtaTooEarly(u64),
+
+    #[error("Operation is not queued")]
+    NotQueued {},
+
+    #[error("Operation is not executable until {0}")]
+    NotReady(u64),
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
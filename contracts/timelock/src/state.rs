@@ -0,0 +1,34 @@
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::timelock::{OperationStatus, TimelockMsg};
+
+/// This structure stores the main parameter for the timelock contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to queue, execute and cancel operations
+    pub admin: Addr,
+    /// The min number of seconds between queueing and executing an operation
+    pub min_delay: u64,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// This structure stores an operation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Operation {
+    /// The messages executed by the contract
+    pub msgs: Vec<TimelockMsg>,
+    /// The block time in seconds the operation is executable from
+    pub eta: u64,
+    /// The operation status
+    pub status: OperationStatus,
+}
+
+/// Stores the number of operations queued, used as the id of the latest operation
+pub const OPERATION_COUNT: Item<u64> = Item::new("operation_count");
+
+/// Stores the operations by id
+pub const OPERATIONS: Map<u64, Operation> = Map::new("operations");
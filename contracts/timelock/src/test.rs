@@ -0,0 +1,237 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, CosmosMsg, Env, OwnedDeps, Response, StdError, Timestamp,
+    WasmMsg,
+};
+use spectrum::farm_controller::ExecuteMsg as ControllerExecuteMsg;
+use spectrum::timelock::{
+    ExecuteMsg, InstantiateMsg, OperationResponse, OperationStatus, OperationsResponse, QueryMsg,
+    TimelockMsg,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::Config;
+
+const ADMIN: &str = "admin";
+const CONTROLLER: &str = "farm_controller";
+const USER_1: &str = "user_1";
+const DAY: u64 = 86400;
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    queue(&mut deps)?;
+    execute_operation(&mut deps)?;
+    cancel(&mut deps)?;
+    config(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+/// Returns the message claiming the ownership of the farm controller
+fn claim_ownership_msg() -> Result<TimelockMsg, ContractError> {
+    Ok(TimelockMsg {
+        contract: CONTROLLER.to_string(),
+        msg: to_binary(&ControllerExecuteMsg::ClaimOwnership {})?,
+    })
+}
+
+fn update_operator_msg() -> Result<TimelockMsg, ContractError> {
+    Ok(TimelockMsg {
+        contract: CONTROLLER.to_string(),
+        msg: to_binary(&ControllerExecuteMsg::UpdateConfig { operator: Some(USER_1.to_string()) })?,
+    })
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(ADMIN, &[]);
+    let msg = InstantiateMsg {
+        admin: ADMIN.to_string(),
+        min_delay: DAY,
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            admin: Addr::unchecked(ADMIN),
+            min_delay: DAY,
+        }
+    );
+
+    Ok(())
+}
+
+fn queue(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::Queue { msgs: vec![claim_ownership_msg()?], eta: DAY };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, ExecuteMsg::Queue { msgs: vec![], eta: DAY });
+    assert_error(res, "Operation must have at least one message");
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(1), info, msg.clone());
+    assert_error(res, "Operation eta must be at least 86401");
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "queue"),
+            attr("id", "1"),
+            attr("eta", DAY.to_string()),
+        ]
+    );
+
+    let info = mock_info(ADMIN, &[]);
+    let msg = ExecuteMsg::Queue { msgs: vec![update_operator_msg()?], eta: 2 * DAY };
+    execute(deps.as_mut(), env_at(0), info, msg)?;
+
+    let res: OperationResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Operation { id: 1 })?)?;
+    assert_eq!(
+        res,
+        OperationResponse {
+            id: 1,
+            msgs: vec![claim_ownership_msg()?],
+            eta: DAY,
+            status: OperationStatus::Queued,
+        }
+    );
+
+    Ok(())
+}
+
+fn execute_operation(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, ExecuteMsg::Execute { id: 1 });
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY - 1), info, ExecuteMsg::Execute { id: 1 });
+    assert_error(res, "Operation is not executable until 86400");
+
+    // the timelock claims the ownership proposed by the farm controller owner
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, ExecuteMsg::Execute { id: 1 })?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: CONTROLLER.to_string(),
+            msg: to_binary(&ControllerExecuteMsg::ClaimOwnership {})?,
+            funds: vec![],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "execute"), attr("id", "1")]
+    );
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, ExecuteMsg::Execute { id: 1 });
+    assert_error(res, "Operation is not queued");
+
+    Ok(())
+}
+
+fn cancel(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, ExecuteMsg::Cancel { id: 2 });
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, ExecuteMsg::Cancel { id: 1 });
+    assert_error(res, "Operation is not queued");
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, ExecuteMsg::Cancel { id: 2 })?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "cancel"), attr("id", "2")]
+    );
+
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(2 * DAY), info, ExecuteMsg::Execute { id: 2 });
+    assert_error(res, "Operation is not queued");
+
+    let res: OperationsResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Operations { start_after: Some(1), limit: None },
+    )?)?;
+    assert_eq!(
+        res,
+        OperationsResponse {
+            operations: vec![OperationResponse {
+                id: 2,
+                msgs: vec![update_operator_msg()?],
+                eta: 2 * DAY,
+                status: OperationStatus::Cancelled,
+            }],
+        }
+    );
+
+    Ok(())
+}
+
+fn config(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::UpdateConfig {
+        admin: Some(USER_1.to_string()),
+        min_delay: Some(2 * DAY),
+    };
+
+    // the admin cannot skip the delay
+    let info = mock_info(ADMIN, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    execute(deps.as_mut(), env_at(DAY), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            admin: Addr::unchecked(USER_1),
+            min_delay: 2 * DAY,
+        }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,298 @@
+use crate::error::ContractError;
+use crate::state::{
+    Config, Spend, SpendLimit, CONFIG, OWNERSHIP_PROPOSAL, SPENDS, SPEND_COUNT, SPEND_LIMITS,
+};
+
+use astroport::asset::{Asset, AssetInfo};
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
+    StdError, StdResult, Uint128,
+};
+use cw_storage_plus::Bound;
+use spectrum::helper::{assert_valid_addr, invalid_state};
+use spectrum::adapters::asset::AssetEx;
+use spectrum::treasury::{
+    ExecuteMsg, HistoryResponse, InstantiateMsg, MigrateMsg, QueryMsg, SpendLimitResponse,
+    SpendLimitsResponse, SpendResponse,
+};
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.spend_period == 0 {
+        return Err(ContractError::InvalidSpendPeriod {});
+    }
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            spend_period: msg.spend_period,
+        },
+    )?;
+    SPEND_COUNT.save(deps.storage, &0u64)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig { spend_period } => update_config(deps, info, spend_period),
+        ExecuteMsg::SetSpendLimit { asset_info, limit } => {
+            set_spend_limit(deps, env, info, asset_info, limit)
+        }
+        ExecuteMsg::Spend { asset, recipient, memo } => spend(deps, env, info, asset, recipient, memo),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Updates contract config. Only the owner can execute this.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    spend_period: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(spend_period) = spend_period {
+        if spend_period == 0 {
+            return Err(ContractError::InvalidSpendPeriod {});
+        }
+        config.spend_period = spend_period;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// ## Description
+/// Returns the start of the spend period of the time.
+fn period_start(config: &Config, env: &Env) -> u64 {
+    let time = env.block.time.seconds();
+    time / config.spend_period * config.spend_period
+}
+
+/// ## Description
+/// Sets the max amount of the asset spent per period. Only the owner can execute this.
+fn set_spend_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    limit: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    asset_info.check(deps.api)?;
+
+    let key = asset_info.to_string();
+    match limit {
+        Some(limit) => {
+            let spend_limit = match SPEND_LIMITS.may_load(deps.storage, key.clone())? {
+                Some(spend_limit) => SpendLimit { limit, ..spend_limit },
+                None => SpendLimit {
+                    asset_info,
+                    limit,
+                    spent: Uint128::zero(),
+                    period_start: period_start(&config, &env),
+                },
+            };
+            SPEND_LIMITS.save(deps.storage, key.clone(), &spend_limit)?;
+        }
+        None => SPEND_LIMITS.remove(deps.storage, key.clone()),
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_spend_limit"),
+        attr("asset", key),
+        attr("limit", limit.map(|it| it.to_string()).unwrap_or_else(|| "none".to_string())),
+    ]))
+}
+
+/// ## Description
+/// Sends the asset to the recipient within the spend limit of the asset and records the spend in
+/// the history. Only the owner can execute this.
+fn spend(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Asset,
+    recipient: String,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if asset.amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let key = asset.info.to_string();
+    let mut spend_limit = SPEND_LIMITS
+        .may_load(deps.storage, key.clone())?
+        .ok_or(ContractError::SpendLimitNotSet { asset: key.clone() })?;
+    let period_start = period_start(&config, &env);
+    let remaining = spend_limit.remaining(period_start);
+    if asset.amount > remaining {
+        return Err(ContractError::SpendLimitExceeded { remaining });
+    }
+    spend_limit.spent = spend_limit.limit - remaining + asset.amount;
+    spend_limit.period_start = period_start;
+    SPEND_LIMITS.save(deps.storage, key, &spend_limit)?;
+
+    let id = SPEND_COUNT.load(deps.storage)? + 1;
+    SPEND_COUNT.save(deps.storage, &id)?;
+    SPENDS.save(
+        deps.storage,
+        id,
+        &Spend {
+            asset: asset.clone(),
+            recipient: recipient.clone(),
+            memo,
+            time: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_message(asset.transfer_msg(&recipient)?)
+        .add_attributes(vec![
+            attr("action", "spend"),
+            attr("id", id.to_string()),
+            attr("recipient", recipient),
+            attr("asset", asset.to_string()),
+        ]))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::SpendLimits {} => to_binary(&query_spend_limits(deps, env)?),
+        QueryMsg::History { start_after, limit } => to_binary(&query_history(deps, start_after, limit)?),
+    }
+}
+
+/// ## Description
+/// Returns the spend limits of all assets with the amount still spendable in the current period.
+fn query_spend_limits(deps: Deps, env: Env) -> StdResult<SpendLimitsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let period_start = period_start(&config, &env);
+
+    let spend_limits = SPEND_LIMITS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, spend_limit)| SpendLimitResponse {
+            remaining: spend_limit.remaining(period_start),
+            asset_info: spend_limit.asset_info,
+            limit: spend_limit.limit,
+        }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(SpendLimitsResponse { spend_limits })
+}
+
+/// ## Description
+/// Returns the spends ordered by id.
+fn query_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<HistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let spends = SPENDS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, spend)| SpendResponse {
+            id,
+            asset: spend.asset,
+            recipient: spend.recipient.to_string(),
+            memo: spend.memo,
+            time: spend.time,
+        }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(HistoryResponse { spends })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored owner and spend period.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    if config.spend_period == 0 {
+        return Err(invalid_state("spend_period must be greater than 0"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes treasury contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Spend period must be greater than 0")]
+    InvalidSpendPeriod {},
+
+    #[error("Spend limit is not set for {asset}")]
+    SpendLimitNotSet { asset: String },
+
+    #[error("Spend limit exceeded; remaining: {remaining}")]
+    SpendLimitExceeded { remaining: Uint128 },
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
@@ -0,0 +1,67 @@
+use astroport::asset::{Asset, AssetInfo};
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores the main parameter for the treasury contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to spend and update config
+    pub owner: Addr,
+    /// The number of seconds of each period the spend limits apply to
+    pub spend_period: u64,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// This structure stores the spend limit of an asset.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendLimit {
+    /// Information about the asset
+    pub asset_info: AssetInfo,
+    /// The max amount spent per period
+    pub limit: Uint128,
+    /// The amount spent in the period
+    pub spent: Uint128,
+    /// The block time in seconds the period of the spent amount starts
+    pub period_start: u64,
+}
+
+impl SpendLimit {
+    /// Returns the amount still spendable at the period start
+    pub fn remaining(&self, period_start: u64) -> Uint128 {
+        if period_start != self.period_start {
+            self.limit
+        } else {
+            self.limit.saturating_sub(self.spent)
+        }
+    }
+}
+
+/// Stores the spend limits by asset
+pub const SPEND_LIMITS: Map<String, SpendLimit> = Map::new("spend_limits");
+
+/// This structure stores a spend of the treasury.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Spend {
+    /// The asset sent
+    pub asset: Asset,
+    /// The recipient address
+    pub recipient: Addr,
+    /// The reason of the spend
+    pub memo: Option<String>,
+    /// The block time in seconds of the spend
+    pub time: u64,
+}
+
+/// Stores the number of spends, used as the id of the latest spend
+pub const SPEND_COUNT: Item<u64> = Item::new("spend_count");
+
+/// Stores the spends by id
+pub const SPENDS: Map<u64, Spend> = Map::new("spends");
@@ -0,0 +1,331 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    attr, coin, from_binary, to_binary, Addr, BankMsg, CosmosMsg, Env, OwnedDeps, Response,
+    StdError, Timestamp, Uint128, WasmMsg,
+};
+use astroport::asset::{Asset, AssetInfo};
+use cw20::Cw20ExecuteMsg;
+use spectrum::treasury::{
+    ExecuteMsg, HistoryResponse, InstantiateMsg, QueryMsg, SpendLimitResponse, SpendLimitsResponse,
+    SpendResponse,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::Config;
+
+const GOV: &str = "gov";
+const SPEC_TOKEN: &str = "spec_token";
+const STABLECOIN: &str = "ibc/stablecoin";
+const USER_1: &str = "user_1";
+const USER_2: &str = "user_2";
+const DAY: u64 = 86400;
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    config(&mut deps)?;
+    spend_limit(&mut deps)?;
+    spend(&mut deps)?;
+    history(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn stablecoin(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::NativeToken { denom: STABLECOIN.to_string() },
+        amount: Uint128::from(amount),
+    }
+}
+
+fn spec(amount: u128) -> Asset {
+    Asset {
+        info: AssetInfo::Token { contract_addr: Addr::unchecked(SPEC_TOKEN) },
+        amount: Uint128::from(amount),
+    }
+}
+
+fn spend_msg(asset: Asset, recipient: &str) -> ExecuteMsg {
+    ExecuteMsg::Spend {
+        asset,
+        recipient: recipient.to_string(),
+        memo: None,
+    }
+}
+
+fn query_spend_limits(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    time: u64,
+) -> Result<SpendLimitsResponse, ContractError> {
+    Ok(from_binary(&query(deps.as_ref(), env_at(time), QueryMsg::SpendLimits {})?)?)
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: GOV.to_string(),
+        spend_period: 0,
+    };
+    let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert_error(res, "Spend period must be greater than 0");
+
+    let msg = InstantiateMsg {
+        owner: GOV.to_string(),
+        spend_period: 7 * DAY,
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    Ok(())
+}
+
+fn config(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UpdateConfig { spend_period: Some(DAY) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateConfig { spend_period: Some(0) };
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Spend period must be greater than 0");
+
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::UpdateConfig { spend_period: Some(DAY) };
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(GOV),
+            spend_period: DAY,
+        }
+    );
+
+    Ok(())
+}
+
+fn spend_limit(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::SetSpendLimit {
+        asset_info: stablecoin(0).info,
+        limit: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env_at(0), info, msg);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::SetSpendLimit {
+        asset_info: stablecoin(0).info,
+        limit: Some(Uint128::from(1000u128)),
+    };
+    let res = execute(deps.as_mut(), env_at(0), info, msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "set_spend_limit"),
+            attr("asset", STABLECOIN),
+            attr("limit", "1000"),
+        ]
+    );
+
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::SetSpendLimit {
+        asset_info: spec(0).info,
+        limit: Some(Uint128::from(500u128)),
+    };
+    execute(deps.as_mut(), env_at(0), info, msg)?;
+
+    assert_eq!(
+        query_spend_limits(deps, 0)?,
+        SpendLimitsResponse {
+            spend_limits: vec![
+                SpendLimitResponse {
+                    asset_info: stablecoin(0).info,
+                    limit: Uint128::from(1000u128),
+                    remaining: Uint128::from(1000u128),
+                },
+                SpendLimitResponse {
+                    asset_info: spec(0).info,
+                    limit: Uint128::from(500u128),
+                    remaining: Uint128::from(500u128),
+                },
+            ],
+        }
+    );
+
+    Ok(())
+}
+
+fn spend(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    // only governance can spend
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, spend_msg(stablecoin(100), USER_1));
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(GOV, &[]);
+    let asset = Asset {
+        info: AssetInfo::NativeToken { denom: "uluna".to_string() },
+        amount: Uint128::from(100u128),
+    };
+    let msg = spend_msg(asset, USER_1);
+    let res = execute(deps.as_mut(), env_at(0), info, msg);
+    assert_error(res, "Spend limit is not set for uluna");
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, spend_msg(stablecoin(0), USER_1));
+    assert_error(res, "Invalid zero amount");
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, spend_msg(stablecoin(1001), USER_1));
+    assert_error(res, "Spend limit exceeded; remaining: 1000");
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, spend_msg(stablecoin(600), USER_1))?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_1.to_string(),
+            amount: vec![coin(600, STABLECOIN)],
+        })]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "spend"),
+            attr("id", "1"),
+            attr("recipient", USER_1),
+            attr("asset", format!("600{}", STABLECOIN)),
+        ]
+    );
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY - 1), info, spend_msg(stablecoin(401), USER_1));
+    assert_error(res, "Spend limit exceeded; remaining: 400");
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY - 1), info, spend_msg(spec(500), USER_2))?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: SPEC_TOKEN.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: USER_2.to_string(),
+                amount: Uint128::from(500u128),
+            })?,
+            funds: vec![],
+        })]
+    );
+
+    // lowering the limit keeps the spent amount of the period
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::SetSpendLimit {
+        asset_info: spec(0).info,
+        limit: Some(Uint128::from(300u128)),
+    };
+    execute(deps.as_mut(), env_at(DAY - 1), info, msg)?;
+
+    // the limit resets in the next period
+    let res = query_spend_limits(deps, DAY - 1)?;
+    assert_eq!(res.spend_limits[0].remaining, Uint128::from(400u128));
+    assert_eq!(res.spend_limits[1].remaining, Uint128::zero());
+    let res = query_spend_limits(deps, DAY)?;
+    assert_eq!(res.spend_limits[0].remaining, Uint128::from(1000u128));
+    assert_eq!(res.spend_limits[1].remaining, Uint128::from(300u128));
+
+    let info = mock_info(GOV, &[]);
+    execute(deps.as_mut(), env_at(DAY), info, spend_msg(stablecoin(1000), USER_2))?;
+
+    // removing the limit stops spending the asset
+    let info = mock_info(GOV, &[]);
+    let msg = ExecuteMsg::SetSpendLimit {
+        asset_info: spec(0).info,
+        limit: None,
+    };
+    execute(deps.as_mut(), env_at(DAY), info, msg)?;
+
+    let info = mock_info(GOV, &[]);
+    let res = execute(deps.as_mut(), env_at(DAY), info, spend_msg(spec(100), USER_2));
+    assert_error(res, "Spend limit is not set for spec_token");
+
+    Ok(())
+}
+
+fn history(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let res: HistoryResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::History { start_after: None, limit: Some(2) },
+    )?)?;
+    assert_eq!(
+        res,
+        HistoryResponse {
+            spends: vec![
+                SpendResponse {
+                    id: 1,
+                    asset: stablecoin(600),
+                    recipient: USER_1.to_string(),
+                    memo: None,
+                    time: 0,
+                },
+                SpendResponse {
+                    id: 2,
+                    asset: spec(500),
+                    recipient: USER_2.to_string(),
+                    memo: None,
+                    time: DAY - 1,
+                },
+            ],
+        }
+    );
+
+    let res: HistoryResponse = from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::History { start_after: Some(2), limit: None },
+    )?)?;
+    assert_eq!(
+        res.spends,
+        vec![SpendResponse {
+            id: 3,
+            asset: stablecoin(1000),
+            recipient: USER_2.to_string(),
+            memo: None,
+            time: DAY,
+        }]
+    );
+
+    Ok(())
+}
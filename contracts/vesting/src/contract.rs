@@ -0,0 +1,257 @@
+use crate::error::ContractError;
+use crate::state::{Config, Vesting, CONFIG, OWNERSHIP_PROPOSAL, VESTINGS};
+
+use astroport::asset::token_asset;
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, from_binary, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use spectrum::helper::assert_valid_addr;
+use spectrum::adapters::asset::AssetEx;
+use spectrum::vesting::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, VestingResponse, VestingSchedule,
+};
+
+/// ## Description
+/// Creates a new contract with the specified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the specified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+            spec_token: deps.api.addr_validate(&msg.spec_token)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::Revoke { recipient } => revoke(deps, env, info, recipient),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        },
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        },
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        },
+    }
+}
+
+/// ## Description
+/// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
+/// If the template is not found in the received message, then a [`ContractError`] is returned,
+/// otherwise returns a [`Response`] with the specified attributes if the operation was successful
+fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::RegisterVesting { recipient, schedule } => {
+            // only the owner can vest SPEC
+            if info.sender != config.spec_token || cw20_msg.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            register_vesting(deps, recipient, cw20_msg.amount, schedule)
+        }
+    }
+}
+
+/// ## Description
+/// Vests the SPEC to the recipient with the schedule.
+fn register_vesting(
+    deps: DepsMut,
+    recipient: String,
+    amount: Uint128,
+    schedule: VestingSchedule,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if schedule.start_time > schedule.cliff_time || schedule.cliff_time > schedule.end_time {
+        return Err(ContractError::InvalidSchedule {});
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    if VESTINGS.has(deps.storage, &recipient_addr) {
+        return Err(ContractError::VestingAlreadyExists { recipient });
+    }
+
+    VESTINGS.save(
+        deps.storage,
+        &recipient_addr,
+        &Vesting {
+            amount,
+            schedule,
+            claimed: Uint128::zero(),
+            revoked_time: None,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_vesting"),
+        attr("recipient", recipient),
+        attr("amount", amount),
+    ]))
+}
+
+/// ## Description
+/// Claims the vested SPEC of the sender.
+fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut vesting = VESTINGS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NothingToClaim {})?;
+
+    let amount = vesting.claimable(env.block.time.seconds());
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    vesting.claimed += amount;
+    VESTINGS.save(deps.storage, &info.sender, &vesting)?;
+
+    Ok(Response::new()
+        .add_message(token_asset(config.spec_token, amount).transfer_msg(&info.sender)?)
+        .add_attributes(vec![
+            attr("action", "claim"),
+            attr("recipient", info.sender),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Stops the vesting of the recipient and returns the unvested SPEC to the owner. Only the owner
+/// can execute this.
+fn revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let mut vesting = VESTINGS.load(deps.storage, &recipient_addr)?;
+    if vesting.revoked_time.is_some() {
+        return Err(ContractError::AlreadyRevoked {});
+    }
+
+    let time = env.block.time.seconds();
+    let amount = vesting.amount.checked_sub(vesting.vested(time))?;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToRevoke {});
+    }
+    vesting.revoked_time = Some(time);
+    VESTINGS.save(deps.storage, &recipient_addr, &vesting)?;
+
+    Ok(Response::new()
+        .add_message(token_asset(config.spec_token, amount).transfer_msg(&config.owner)?)
+        .add_attributes(vec![
+            attr("action", "revoke"),
+            attr("recipient", recipient),
+            attr("amount", amount),
+        ]))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Vesting { recipient, time } => to_binary(&query_vesting(deps, env, recipient, time)?),
+    }
+}
+
+/// ## Description
+/// Returns the vesting of the recipient with the vested and claimable SPEC at the time.
+fn query_vesting(
+    deps: Deps,
+    env: Env,
+    recipient: String,
+    time: Option<u64>,
+) -> StdResult<VestingResponse> {
+    let vesting = VESTINGS.load(deps.storage, &deps.api.addr_validate(&recipient)?)?;
+    let time = time.unwrap_or_else(|| env.block.time.seconds());
+
+    Ok(VestingResponse {
+        recipient,
+        vested: vesting.vested(time),
+        claimable: vesting.claimable(time),
+        amount: vesting.amount,
+        schedule: vesting.schedule,
+        revoked_time: vesting.revoked_time,
+        claimed: vesting.claimed,
+    })
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`], or an error if the
+/// stored config is inconsistent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    assert_state(deps.as_ref())?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Checks the stored owner and SPEC token addresses.
+fn assert_state(deps: Deps) -> StdResult<()> {
+    let config = CONFIG.load(deps.storage)?;
+    assert_valid_addr(deps.api, &config.owner, "owner")?;
+    assert_valid_addr(deps.api, &config.spec_token, "spec_token")?;
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use cosmwasm_std::{OverflowError, StdError};
+use thiserror::Error;
+
+/// ## Description
+/// This enum describes vesting contract errors!
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Vesting schedule must have start time <= cliff time <= end time")]
+    InvalidSchedule {},
+
+    #[error("Vesting already exists: {recipient}")]
+    VestingAlreadyExists { recipient: String },
+
+    #[error("Vesting is already revoked")]
+    AlreadyRevoked {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Nothing to revoke")]
+    NothingToRevoke {},
+}
+
+impl From<OverflowError> for ContractError {
+    fn from(o: OverflowError) -> Self {
+        StdError::from(o).into()
+    }
+}
@@ -0,0 +1,60 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Uint128};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::vesting::VestingSchedule;
+
+/// This structure stores the main parameter for the vesting contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to register and revoke vestings
+    pub owner: Addr,
+    /// The SPEC token contract address
+    pub spec_token: Addr,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// This structure stores the vesting of a recipient.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vesting {
+    /// The SPEC registered to vest
+    pub amount: Uint128,
+    /// The vesting schedule
+    pub schedule: VestingSchedule,
+    /// The SPEC already claimed
+    pub claimed: Uint128,
+    /// The block time in seconds the vesting was revoked, nothing vests after it
+    pub revoked_time: Option<u64>,
+}
+
+impl Vesting {
+    /// Returns the SPEC vested at the time
+    pub fn vested(&self, time: u64) -> Uint128 {
+        let time = self.revoked_time.map_or(time, |revoked_time| time.min(revoked_time));
+        let schedule = &self.schedule;
+        if time < schedule.cliff_time {
+            Uint128::zero()
+        } else if time >= schedule.end_time {
+            self.amount
+        } else {
+            self.amount.multiply_ratio(
+                time - schedule.start_time,
+                schedule.end_time - schedule.start_time,
+            )
+        }
+    }
+
+    /// Returns the SPEC vested and not claimed yet at the time
+    pub fn claimable(&self, time: u64) -> Uint128 {
+        self.vested(time).saturating_sub(self.claimed)
+    }
+}
+
+/// Stores the vesting of each recipient
+pub const VESTINGS: Map<&Addr, Vesting> = Map::new("vestings");
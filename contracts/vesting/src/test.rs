@@ -0,0 +1,306 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, CosmosMsg, Env, OwnedDeps, Response, StdError, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use spectrum::vesting::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, VestingResponse, VestingSchedule,
+};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::state::Config;
+
+const OWNER: &str = "owner";
+const SPEC_TOKEN: &str = "spec_token";
+const USER_1: &str = "user_1";
+const USER_2: &str = "user_2";
+const USER_3: &str = "user_3";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    owner(&mut deps)?;
+    register_vesting(&mut deps)?;
+    claim(&mut deps)?;
+    revoke(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn register_msg(
+    sender: &str,
+    amount: u128,
+    recipient: &str,
+    schedule: VestingSchedule,
+) -> Result<ExecuteMsg, ContractError> {
+    Ok(ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::RegisterVesting {
+            recipient: recipient.to_string(),
+            schedule,
+        })?,
+    }))
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> Result<CosmosMsg, ContractError> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: SPEC_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn query_vesting(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    recipient: &str,
+    time: u64,
+) -> Result<VestingResponse, ContractError> {
+    Ok(from_binary(&query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Vesting { recipient: recipient.to_string(), time: Some(time) },
+    )?)?)
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        spec_token: SPEC_TOKEN.to_string(),
+    };
+    instantiate(deps.as_mut(), mock_env(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(USER_1),
+            spec_token: Addr::unchecked(SPEC_TOKEN),
+        }
+    );
+
+    Ok(())
+}
+
+fn owner(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let env = env_at(0);
+
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: OWNER.to_string(),
+        expires_in: 100,
+    };
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(USER_1, &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {});
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {})?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(config.owner, Addr::unchecked(OWNER));
+
+    Ok(())
+}
+
+fn register_vesting(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let linear = VestingSchedule {
+        start_time: 1000,
+        cliff_time: 2000,
+        end_time: 11000,
+    };
+
+    // only the owner can vest SPEC
+    let info = mock_info("token", &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 1000, USER_1, linear.clone())?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(USER_1, 1000, USER_1, linear.clone())?);
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let schedule = VestingSchedule {
+        start_time: 1000,
+        cliff_time: 12000,
+        end_time: 11000,
+    };
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 1000, USER_1, schedule)?);
+    assert_error(res, "Vesting schedule must have start time <= cliff time <= end time");
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 1000, USER_1, linear.clone())?)?;
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "register_vesting"),
+            attr("recipient", USER_1),
+            attr("amount", "1000"),
+        ]
+    );
+
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let res = execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 1000, USER_1, linear.clone())?);
+    assert_error(res, "Vesting already exists: user_1");
+
+    // cliff only
+    let info = mock_info(SPEC_TOKEN, &[]);
+    let schedule = VestingSchedule {
+        start_time: 1000,
+        cliff_time: 5000,
+        end_time: 5000,
+    };
+    execute(deps.as_mut(), env_at(0), info, register_msg(OWNER, 500, USER_2, schedule)?)?;
+
+    // nothing vests before the cliff
+    assert_eq!(
+        query_vesting(deps, USER_1, 1999)?,
+        VestingResponse {
+            recipient: USER_1.to_string(),
+            amount: Uint128::from(1000u128),
+            schedule: linear,
+            revoked_time: None,
+            claimed: Uint128::zero(),
+            vested: Uint128::zero(),
+            claimable: Uint128::zero(),
+        }
+    );
+    assert_eq!(query_vesting(deps, USER_1, 2000)?.vested, Uint128::from(100u128));
+    assert_eq!(query_vesting(deps, USER_1, 11000)?.vested, Uint128::from(1000u128));
+    assert_eq!(query_vesting(deps, USER_2, 4999)?.vested, Uint128::zero());
+    assert_eq!(query_vesting(deps, USER_2, 5000)?.vested, Uint128::from(500u128));
+
+    Ok(())
+}
+
+fn claim(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_3, &[]);
+    let res = execute(deps.as_mut(), env_at(6000), info, ExecuteMsg::Claim {});
+    assert_error(res, "Nothing to claim");
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(1999), info, ExecuteMsg::Claim {});
+    assert_error(res, "Nothing to claim");
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(6000), info, ExecuteMsg::Claim {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [transfer_msg(USER_1, 500)?]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "claim"),
+            attr("recipient", USER_1),
+            attr("amount", "500"),
+        ]
+    );
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(6000), info, ExecuteMsg::Claim {});
+    assert_error(res, "Nothing to claim");
+
+    let res = query_vesting(deps, USER_1, 7000)?;
+    assert_eq!(res.claimed, Uint128::from(500u128));
+    assert_eq!(res.vested, Uint128::from(600u128));
+    assert_eq!(res.claimable, Uint128::from(100u128));
+
+    Ok(())
+}
+
+fn revoke(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Revoke { recipient: USER_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(8000), info, msg);
+    assert_error(res, "Unauthorized");
+
+    // the unvested SPEC is returned to the owner
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::Revoke { recipient: USER_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(8000), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [transfer_msg(OWNER, 300)?]
+    );
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "revoke"),
+            attr("recipient", USER_1),
+            attr("amount", "300"),
+        ]
+    );
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::Revoke { recipient: USER_1.to_string() };
+    let res = execute(deps.as_mut(), env_at(9000), info, msg);
+    assert_error(res, "Vesting is already revoked");
+
+    // the SPEC vested until the revocation stays claimable
+    let res = query_vesting(deps, USER_1, 11000)?;
+    assert_eq!(res.revoked_time, Some(8000));
+    assert_eq!(res.vested, Uint128::from(700u128));
+    assert_eq!(res.claimable, Uint128::from(200u128));
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env_at(11000), info, ExecuteMsg::Claim {})?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [transfer_msg(USER_1, 200)?]
+    );
+
+    // fully vested
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::Revoke { recipient: USER_2.to_string() };
+    let res = execute(deps.as_mut(), env_at(5000), info, msg);
+    assert_error(res, "Nothing to revoke");
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use crate::voting_escrow::QueryMsg::{
     LockInfo, TotalVotingPower, TotalVotingPowerAt, UserVotingPower, UserVotingPowerAt,
 };
-use cosmwasm_std::{Addr, Binary, Decimal, QuerierWrapper, StdResult, Uint128};
+use cosmwasm_std::{Addr, Binary, CustomQuery, Decimal, QuerierWrapper, StdResult, Uint128};
 use cw20::{Cw20ReceiveMsg, Logo};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -207,8 +207,8 @@ pub struct MigrateMsg {
 /// * **escrow_addr** is an object of type [`impl Into<String>`]. This is the vxASTRO contract address.
 ///
 /// * **user** is an object of type [`impl Into<String>`]. This is the address of the staker for which we calculate the latest vxASTRO voting power.
-pub fn get_voting_power(
-    querier: &QuerierWrapper,
+pub fn get_voting_power<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     escrow_addr: impl Into<String>,
     user: impl Into<String>,
 ) -> StdResult<Uint128> {
@@ -226,8 +226,8 @@ pub fn get_voting_power(
 /// * **user** is an object of type [`impl Into<String>`]. This is the address of the staker for which we calculate the voting power at a specific time.
 ///
 /// * **timestamp** is a variable of type [`u64`]. This is the timestamp at which we calculate the staker's voting power.
-pub fn get_voting_power_at(
-    querier: &QuerierWrapper,
+pub fn get_voting_power_at<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     escrow_addr: impl Into<String>,
     user: impl Into<String>,
     timestamp: u64,
@@ -248,8 +248,8 @@ pub fn get_voting_power_at(
 /// * **querier** is an object of type [`QuerierWrapper`].
 ///
 /// * **escrow_addr** is an object of type [`impl Into<String>`]. This is the adress of the vxASTRO contract.
-pub fn get_total_voting_power(
-    querier: &QuerierWrapper,
+pub fn get_total_voting_power<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     escrow_addr: impl Into<String>,
 ) -> StdResult<Uint128> {
     let vp: VotingPowerResponse = querier.query_wasm_smart(escrow_addr, &TotalVotingPower {})?;
@@ -264,8 +264,8 @@ pub fn get_total_voting_power(
 /// * **escrow_addr** is an object of type [`impl Into<String>`]. This is the adress of the vxASTRO contract.
 ///
 /// * **timestamp** is a variable of type [`u64`]. This is the time at which we fetch the total voting power.
-pub fn get_total_voting_power_at(
-    querier: &QuerierWrapper,
+pub fn get_total_voting_power_at<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     escrow_addr: impl Into<String>,
     timestamp: u64,
 ) -> StdResult<Uint128> {
@@ -282,8 +282,8 @@ pub fn get_total_voting_power_at(
 /// * **escrow_addr** is an object of type [`impl Into<String>`]. This is the adress of the vxASTRO contract.
 ///
 /// * **user** is an object of type [`impl Into<String>`]. This is the staker for which we return lock position information.
-pub fn get_lock_info(
-    querier: &QuerierWrapper,
+pub fn get_lock_info<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     escrow_addr: impl Into<String>,
     user: impl Into<String>,
 ) -> StdResult<LockInfoResponse> {
@@ -8,8 +8,9 @@ use crate::querier::{
     query_balance, query_token_balance, query_token_symbol, NATIVE_TOKEN_PRECISION,
 };
 use cosmwasm_std::{
-    to_binary, Addr, Api, BankMsg, Coin, ConversionOverflowError, CosmosMsg, Decimal256, Fraction,
-    MessageInfo, QuerierWrapper, StdError, StdResult, Uint128, Uint256, WasmMsg,
+    to_binary, Addr, Api, BankMsg, Coin, ConversionOverflowError, CosmosMsg, CustomQuery,
+    Decimal256, Fraction, MessageInfo, QuerierWrapper, StdError, StdResult, Uint128, Uint256,
+    WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, MinterResponse, TokenInfoResponse};
 use itertools::Itertools;
@@ -18,6 +19,8 @@ use itertools::Itertools;
 pub const UUSD_DENOM: &str = "uusd";
 /// LUNA token denomination
 pub const ULUNA_DENOM: &str = "uluna";
+/// The prefix of x/tokenfactory denominations
+pub const TOKEN_FACTORY_DENOM_PREFIX: &str = "factory/";
 
 /// ## Description
 /// This enum describes a Terra asset (native or CW20).
@@ -56,7 +59,7 @@ impl Asset {
     /// * **self** is the type of the caller object.
     ///
     /// * **querier** is an object of type [`QuerierWrapper`]
-    pub fn compute_tax(&self, _querier: &QuerierWrapper) -> StdResult<Uint128> {
+    pub fn compute_tax<C: CustomQuery>(&self, _querier: &QuerierWrapper<C>) -> StdResult<Uint128> {
         // tax rate in Terra is set to zero https://terrawiki.org/en/developers/tx-fees
         Ok(Uint128::zero())
     }
@@ -66,7 +69,7 @@ impl Asset {
     /// * **self** is the type of the caller object.
     ///
     /// * **querier** is an object of type [`QuerierWrapper`]
-    pub fn deduct_tax(&self, querier: &QuerierWrapper) -> StdResult<Coin> {
+    pub fn deduct_tax<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<Coin> {
         if let AssetInfo::NativeToken { denom } = &self.info {
             Ok(Coin {
                 denom: denom.to_string(),
@@ -89,9 +92,9 @@ impl Asset {
     /// * **querier** is an object of type [`QuerierWrapper`]
     ///
     /// * **recipient** is the address where the funds will be sent.
-    pub fn into_msg(
+    pub fn into_msg<C: CustomQuery>(
         self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         recipient: impl Into<String>,
     ) -> StdResult<CosmosMsg> {
         let recipient = recipient.into();
@@ -189,9 +192,9 @@ impl AssetInfo {
     /// * **self** is the type of the caller object.
     ///
     /// * **pool_addr** is the address of the contract whose token balance we check.
-    pub fn query_pool(
+    pub fn query_pool<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         pool_addr: impl Into<String>,
     ) -> StdResult<Uint128> {
         match self {
@@ -205,7 +208,7 @@ impl AssetInfo {
     /// Returns the number of decimals that a token has.
     /// ## Params
     /// * **querier** is an object of type [`QuerierWrapper`].
-    pub fn decimals(&self, querier: &QuerierWrapper) -> StdResult<u8> {
+    pub fn decimals<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<u8> {
         let decimals = match &self {
             AssetInfo::NativeToken { .. } => NATIVE_TOKEN_PRECISION,
             AssetInfo::Token { contract_addr } => {
@@ -263,7 +266,10 @@ impl AssetInfo {
                 addr_validate_to_lower(api, contract_addr)?;
             }
             AssetInfo::NativeToken { denom } => {
-                if !denom.starts_with("ibc/") && denom != &denom.to_lowercase() {
+                if !denom.starts_with("ibc/")
+                    && !is_token_factory_denom(denom)
+                    && denom != &denom.to_lowercase()
+                {
                     return Err(StdError::generic_err(format!(
                         "Non-IBC token denom {} should be lowercase",
                         denom
@@ -296,9 +302,9 @@ impl PairInfo {
     /// * **querier** is an object of type [`QuerierWrapper`]
     ///
     /// * **contract_addr** is pair's pool address.
-    pub fn query_pools(
+    pub fn query_pools<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         contract_addr: impl Into<String>,
     ) -> StdResult<Vec<Asset>> {
         let contract_addr = contract_addr.into();
@@ -320,9 +326,9 @@ impl PairInfo {
     /// * **querier** is an object of type [`QuerierWrapper`]
     ///
     /// * **contract_addr** is pair's pool address.
-    pub fn query_pools_decimal(
+    pub fn query_pools_decimal<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         contract_addr: impl Into<String>,
     ) -> StdResult<Vec<DecimalAsset>> {
         let contract_addr = contract_addr.into();
@@ -377,9 +383,9 @@ const TOKEN_SYMBOL_MAX_LENGTH: usize = 4;
 /// * **asset_infos** is an array with two items the type of [`AssetInfo`].
 ///
 /// * **querier** is an object of type [`QuerierWrapper`].
-pub fn format_lp_token_name(
+pub fn format_lp_token_name<C: CustomQuery>(
     asset_infos: &[AssetInfo],
-    querier: &QuerierWrapper,
+    querier: &QuerierWrapper<C>,
 ) -> StdResult<String> {
     let mut short_symbols: Vec<String> = vec![];
     for asset_info in asset_infos {
@@ -435,12 +441,56 @@ pub fn token_asset_info(contract_addr: Addr) -> AssetInfo {
     AssetInfo::Token { contract_addr }
 }
 
+/// Returns true if the denomination is created by the x/tokenfactory module, in the format
+/// `factory/{creator address}/{subdenom}`.
+pub fn is_token_factory_denom(denom: &str) -> bool {
+    denom.starts_with(TOKEN_FACTORY_DENOM_PREFIX)
+}
+
+/// Returns an [`AssetInfo`] object representing a token stored as an address, which is a native
+/// asset for x/tokenfactory denominations and a token contract otherwise.
+/// ## Params
+/// * **token** is a [`Addr`] object representing the token contract or the x/tokenfactory denomination.
+pub fn determine_asset_info(token: &Addr) -> AssetInfo {
+    if is_token_factory_denom(token.as_str()) {
+        native_asset_info(token.to_string())
+    } else {
+        token_asset_info(token.clone())
+    }
+}
+
+/// Returns a token contract address or a x/tokenfactory denomination as an [`Addr`].
+/// The creator address of a x/tokenfactory denomination is validated.
+/// ## Params
+/// * **api** is a object of type [`Api`]
+///
+/// * **token** is the token contract address or the x/tokenfactory denomination.
+pub fn validate_token(api: &dyn Api, token: &str) -> StdResult<Addr> {
+    if is_token_factory_denom(token) {
+        match token.splitn(3, '/').collect::<Vec<_>>()[..] {
+            [_, creator, subdenom] if !subdenom.is_empty() => {
+                api.addr_validate(creator)?;
+                Ok(Addr::unchecked(token))
+            }
+            _ => Err(StdError::generic_err(format!(
+                "Invalid tokenfactory denom {}",
+                token
+            ))),
+        }
+    } else {
+        api.addr_validate(token)
+    }
+}
+
 /// Returns [`PairInfo`] by specified pool address.
 /// ## Params
 /// * **deps** is an object of type [`Deps`]
 ///
 /// * **pool_addr** is a [`impl Into<String>`] object representing the address of the pool.
-pub fn pair_info_by_pool(querier: &QuerierWrapper, pool: impl Into<String>) -> StdResult<PairInfo> {
+pub fn pair_info_by_pool<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    pool: impl Into<String>,
+) -> StdResult<PairInfo> {
     let minter_info: MinterResponse = querier.query_wasm_smart(pool, &Cw20QueryMsg::Minter {})?;
 
     let pair_info: PairInfo =
@@ -1,5 +1,7 @@
 use crate::asset::addr_validate_to_lower;
-use cosmwasm_std::{attr, Addr, Api, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
+use cosmwasm_std::{
+    attr, Addr, Api, CustomQuery, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
 use cw_storage_plus::Item;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -33,8 +35,8 @@ pub struct OwnershipProposal {
 /// `owner` is the current owner.
 ///
 /// `proposal` is an object of type [`OwnershipProposal`].
-pub fn propose_new_owner(
-    deps: DepsMut,
+pub fn propose_new_owner<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     env: Env,
     new_owner: String,
@@ -87,8 +89,8 @@ pub fn propose_new_owner(
 /// `owner` is the current contract owner.
 ///
 /// `proposal` is the object of type [`OwnershipProposal`].
-pub fn drop_ownership_proposal(
-    deps: DepsMut,
+pub fn drop_ownership_proposal<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     owner: Addr,
     proposal: Item<OwnershipProposal>,
@@ -117,12 +119,12 @@ pub fn drop_ownership_proposal(
 /// `proposal` is an object of type [`OwnershipProposal`].
 ///
 /// `cb` is a callback function that takes in two parameters of type [`DepsMut`] and [`Addr`] respectively.
-pub fn claim_ownership(
-    deps: DepsMut,
+pub fn claim_ownership<C: CustomQuery>(
+    deps: DepsMut<C>,
     info: MessageInfo,
     env: Env,
     proposal: Item<OwnershipProposal>,
-    cb: fn(DepsMut, Addr) -> StdResult<()>,
+    cb: fn(DepsMut<C>, Addr) -> StdResult<()>,
 ) -> StdResult<Response> {
     let p: OwnershipProposal = proposal
         .load(deps.storage)
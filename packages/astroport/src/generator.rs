@@ -73,6 +73,8 @@ pub enum ExecuteMsg {
         /// the LP token contract address
         lp_tokens: Vec<String>,
     },
+    /// Stake the native LP token sent as funds, for LP tokens which are x/tokenfactory denominations
+    Deposit {},
     /// Withdraw LP tokens from the Generator
     Withdraw {
         /// The address of the LP token to withdraw
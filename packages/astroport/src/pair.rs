@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::asset::{Asset, AssetInfo};
 
-use cosmwasm_std::{from_slice, Addr, Binary, Decimal, QuerierWrapper, StdResult, Uint128};
+use cosmwasm_std::{
+    from_slice, Addr, Binary, CustomQuery, Decimal, QuerierWrapper, StdResult, Uint128,
+};
 use cw20::Cw20ReceiveMsg;
 
 /// The default swap slippage
@@ -52,6 +54,9 @@ pub enum ExecuteMsg {
         max_spread: Option<Decimal>,
         to: Option<String>,
     },
+    /// Withdraw liquidity from the pool with the native LP token sent as funds, for pairs whose
+    /// LP token is a x/tokenfactory denomination
+    WithdrawLiquidity { assets: Vec<Asset> },
     /// Update the pair configuration
     UpdateConfig { params: Binary },
     /// ProposeNewOwner creates a proposal to change contract ownership.
@@ -201,8 +206,8 @@ pub enum StablePoolUpdateParams {
 /// * **querier** - is the object of type [`QuerierWrapper`].
 /// * **pair** - The pair address which we need to check.
 /// * **factory** - The factory address.
-pub fn migration_check(
-    querier: QuerierWrapper,
+pub fn migration_check<C: CustomQuery>(
+    querier: QuerierWrapper<C>,
     factory: &Addr,
     pair_addr: &Addr,
 ) -> StdResult<bool> {
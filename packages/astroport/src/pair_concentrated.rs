@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Decimal;
+
+use crate::factory::PairType;
+
+/// The custom pair type name of concentrated liquidity pools
+pub const CONCENTRATED_PAIR_TYPE: &str = "concentrated";
+
+/// Returns true if the pair type is a concentrated liquidity pool.
+/// ## Params
+/// * **pair_type** is an object of type [`PairType`].
+pub fn is_concentrated(pair_type: &PairType) -> bool {
+    matches!(pair_type, PairType::Custom(name) if name == CONCENTRATED_PAIR_TYPE)
+}
+
+/// This structure stores a concentrated pool's configuration returned in the pair config params.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConcentratedPoolConfig {
+    /// Amplification coefficient affects the curvature of the invariant
+    pub amp: Decimal,
+    /// The distance between the invariant and the constant product curve
+    pub gamma: Decimal,
+    /// The fee charged when the pool is balanced
+    pub mid_fee: Decimal,
+    /// The fee charged when the pool is fully imbalanced
+    pub out_fee: Decimal,
+    /// Parameter that defines how fast the fee changes from mid_fee to out_fee
+    pub fee_gamma: Decimal,
+    /// Minimum profit before initiating a new repeg
+    pub repeg_profit_threshold: Decimal,
+    /// Minimum amount to change the price scale
+    pub min_price_scale_delta: Decimal,
+    /// The internal price of the second asset denominated in the first asset
+    pub price_scale: Decimal,
+    /// Half-time used for calculating the price oracle
+    pub ma_half_time: u64,
+}
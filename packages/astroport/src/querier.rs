@@ -6,8 +6,8 @@ use crate::factory::{
 use crate::pair::{QueryMsg as PairQueryMsg, ReverseSimulationResponse, SimulationResponse};
 
 use cosmwasm_std::{
-    Addr, AllBalanceResponse, BankQuery, Coin, Decimal, QuerierWrapper, QueryRequest, StdResult,
-    Uint128,
+    Addr, AllBalanceResponse, BankQuery, Coin, CustomQuery, Decimal, QuerierWrapper, QueryRequest,
+    StdResult, Uint128,
 };
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
@@ -22,8 +22,8 @@ pub const NATIVE_TOKEN_PRECISION: u8 = 6;
 /// * **account_addr** is an object of type [`impl Into<String>`].
 ///
 /// * **denom** is an object of type [`impl Into<String>`] used to specify the denomination used to return the balance (e.g uluna).
-pub fn query_balance(
-    querier: &QuerierWrapper,
+pub fn query_balance<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     account_addr: impl Into<String>,
     denom: impl Into<String>,
 ) -> StdResult<Uint128> {
@@ -37,7 +37,10 @@ pub fn query_balance(
 /// * **querier** is an object of type [`QuerierWrapper`].
 ///
 /// * **account_addr** is an object of type [`Addr`] which is the address for which we query balances.
-pub fn query_all_balances(querier: &QuerierWrapper, account_addr: Addr) -> StdResult<Vec<Coin>> {
+pub fn query_all_balances<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    account_addr: Addr,
+) -> StdResult<Vec<Coin>> {
     let all_balances: AllBalanceResponse =
         querier.query(&QueryRequest::Bank(BankQuery::AllBalances {
             address: String::from(account_addr),
@@ -52,8 +55,8 @@ pub fn query_all_balances(querier: &QuerierWrapper, account_addr: Addr) -> StdRe
 /// * **contract_addr** This is the token contract for which we return a balance.
 ///
 /// * **account_addr** is the account address for which we return a balance.
-pub fn query_token_balance(
-    querier: &QuerierWrapper,
+pub fn query_token_balance<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     contract_addr: impl Into<String>,
     account_addr: impl Into<String>,
 ) -> StdResult<Uint128> {
@@ -77,8 +80,8 @@ pub fn query_token_balance(
 /// * **querier** is an object of type [`QuerierWrapper`].
 ///
 /// * **contract_addr** is a object of type [`impl Into<String>`] which is the token contract address.
-pub fn query_token_symbol(
-    querier: &QuerierWrapper,
+pub fn query_token_symbol<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     contract_addr: impl Into<String>,
 ) -> StdResult<String> {
     let res: TokenInfoResponse =
@@ -92,8 +95,8 @@ pub fn query_token_symbol(
 /// * **querier** is an object of type [`QuerierWrapper`].
 ///
 /// * **contract_addr** is an object of type [`impl Into<String>`] which is the token contract address.
-pub fn query_supply(
-    querier: &QuerierWrapper,
+pub fn query_supply<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     contract_addr: impl Into<String>,
 ) -> StdResult<Uint128> {
     let res: TokenInfoResponse =
@@ -107,7 +110,10 @@ pub fn query_supply(
 /// * **querier** is an object of type [`QuerierWrapper`].
 ///
 /// * **asset_info** is a reference of type [`AssetInfo`] and contains the asset details for a specific token.
-pub fn query_token_precision(querier: &QuerierWrapper, asset_info: &AssetInfo) -> StdResult<u8> {
+pub fn query_token_precision<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    asset_info: &AssetInfo,
+) -> StdResult<u8> {
     let decimals = match asset_info {
         AssetInfo::NativeToken { .. } => NATIVE_TOKEN_PRECISION,
         AssetInfo::Token { contract_addr } => {
@@ -126,8 +132,8 @@ pub fn query_token_precision(querier: &QuerierWrapper, asset_info: &AssetInfo) -
 /// * **querier** is an object of type [`QuerierWrapper`].
 ///
 /// * **factory_contract** is an object of type [`impl Into<String>`] which is the Astroport factory contract address.
-pub fn query_factory_config(
-    querier: &QuerierWrapper,
+pub fn query_factory_config<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     factory_contract: impl Into<String>,
 ) -> StdResult<FactoryConfigResponse> {
     querier.query_wasm_smart(factory_contract, &FactoryQueryMsg::Config {})
@@ -150,8 +156,8 @@ pub struct FeeInfo {
 /// * **factory_contract** is an object of type [`impl Into<String>`].
 ///
 /// * **pair_type** is an object of type [`PairType`]. This is the pair type we return information for.
-pub fn query_fee_info(
-    querier: &QuerierWrapper,
+pub fn query_fee_info<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     factory_contract: impl Into<String>,
     pair_type: PairType,
 ) -> StdResult<FeeInfo> {
@@ -172,8 +178,8 @@ pub fn query_fee_info(
 /// * **factory_contract** is an object of type [`impl Into<String>`] and it's the Astroport factory contract address
 ///
 /// * **asset_infos** is an array that contains two items of type [`AssetInfo`].
-pub fn query_pair_info(
-    querier: &QuerierWrapper,
+pub fn query_pair_info<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     factory_contract: impl Into<String>,
     asset_infos: &[AssetInfo],
 ) -> StdResult<PairInfo> {
@@ -194,8 +200,8 @@ pub fn query_pair_info(
 /// * **start_after** is an [`Option`] field that contains an array with two items of type [`AssetInfo`].
 ///
 /// * **limit** is an [`Option`] field of type [`u32`] which is the maximum amount of pairs for which to return information.
-pub fn query_pairs_info(
-    querier: &QuerierWrapper,
+pub fn query_pairs_info<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     factory_contract: impl Into<String>,
     start_after: Option<Vec<AssetInfo>>,
     limit: Option<u32>,
@@ -213,8 +219,8 @@ pub fn query_pairs_info(
 /// * **pair_contract** is an object of type [`impl Into<String>`] and represents the address of the pair for which we return swap simulation info.
 ///
 /// * **offer_asset** is an object of type [`Asset`] and represents the asset that is being swapped.
-pub fn simulate(
-    querier: &QuerierWrapper,
+pub fn simulate<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     pair_contract: impl Into<String>,
     offer_asset: &Asset,
 ) -> StdResult<SimulationResponse> {
@@ -234,8 +240,8 @@ pub fn simulate(
 /// * **pair_contract** is an object of type [`impl Into<String>`] and represents the address of the pair for which we return swap simulation info.
 ///
 /// * **ask_asset** is an object of type [`Asset`] and represents the asset that we swap to.
-pub fn reverse_simulate(
-    querier: &QuerierWrapper,
+pub fn reverse_simulate<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
     pair_contract: impl Into<String>,
     ask_asset: &Asset,
 ) -> StdResult<ReverseSimulationResponse> {
@@ -1,4 +1,6 @@
-use crate::asset::{format_lp_token_name, Asset, AssetInfo, PairInfo};
+use crate::asset::{
+    determine_asset_info, format_lp_token_name, validate_token, Asset, AssetInfo, PairInfo,
+};
 use crate::mock_querier::mock_dependencies;
 use crate::querier::{
     query_all_balances, query_balance, query_pair_info, query_supply, query_token_balance,
@@ -157,6 +159,35 @@ fn test_asset_info() {
     );
 }
 
+#[test]
+fn test_token_factory_asset_info() {
+    let deps = mock_dependencies(&[]);
+    let denom = "factory/creator0000/uLP";
+
+    let native_token_info = AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    };
+    assert!(native_token_info.check(&deps.api).is_ok());
+    assert!(AssetInfo::NativeToken {
+        denom: "uLUNA".to_string(),
+    }
+    .check(&deps.api)
+    .is_err());
+
+    let token = validate_token(&deps.api, denom).unwrap();
+    assert_eq!(determine_asset_info(&token), native_token_info);
+    assert!(validate_token(&deps.api, "factory/creator0000").is_err());
+    assert!(validate_token(&deps.api, "factory/c/uLP").is_err());
+
+    let token = validate_token(&deps.api, "asset0000").unwrap();
+    assert_eq!(
+        determine_asset_info(&token),
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked("asset0000"),
+        }
+    );
+}
+
 #[test]
 fn test_asset() {
     let mut deps = mock_dependencies(&[Coin {
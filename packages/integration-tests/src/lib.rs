@@ -0,0 +1,4 @@
+pub mod mocks;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,68 @@
+use astroport::asset::{AssetInfo, PairInfo};
+use astroport::factory::{FeeInfoResponse, PairType};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::adapters::pair::Pair;
+
+use super::pair::COMMISSION_BPS;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {}
+
+/// This structure describes the execute messages of the factory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Registers an instantiated pair under its assets
+    RegisterPair { pair: String },
+}
+
+/// This structure describes the queries of the Astroport factory used by the Spectrum contracts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Pair { asset_infos: Vec<AssetInfo> },
+    FeeInfo { pair_type: PairType },
+}
+
+const PAIRS: Map<String, PairInfo> = Map::new("pairs");
+
+/// Returns the key of a pair, independent of the order of its assets
+fn pair_key(asset_infos: &[AssetInfo]) -> String {
+    let mut keys: Vec<String> = asset_infos.iter().map(|info| info.to_string()).collect();
+    keys.sort();
+    keys.join("-")
+}
+
+pub fn instantiate(
+    _deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+pub fn execute(deps: DepsMut, _env: Env, _info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::RegisterPair { pair } => {
+            let pair = Pair(deps.api.addr_validate(&pair)?);
+            let pair_info = pair.query_pair_info(&deps.querier)?;
+            PAIRS.save(deps.storage, pair_key(&pair_info.asset_infos), &pair_info)?;
+            Ok(Response::new().add_attribute("action", "register_pair"))
+        }
+    }
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pair { asset_infos } => to_binary(&PAIRS.load(deps.storage, pair_key(&asset_infos))?),
+        QueryMsg::FeeInfo { .. } => to_binary(&FeeInfoResponse {
+            fee_address: None,
+            total_fee_bps: COMMISSION_BPS as u16,
+            maker_fee_bps: 0,
+        }),
+    }
+}
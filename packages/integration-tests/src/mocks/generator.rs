@@ -0,0 +1,117 @@
+use astroport::asset::token_asset;
+use astroport::generator::{Cw20HookMsg, PendingTokenResponse};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::adapters::asset::AssetEx;
+
+/// This structure describes the parameters for creating a generator.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The ASTRO token paid as reward, the generator must hold enough of it
+    pub astro_token: String,
+}
+
+/// This structure describes the execute messages of the Astroport generator used by the Spectrum
+/// contracts, and the accrual of rewards driven by the test.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    Withdraw { lp_token: String, amount: Uint128 },
+    ClaimRewards { lp_tokens: Vec<String> },
+    /// Adds pending ASTRO to the deposit of the user
+    Accrue { lp_token: String, user: String, amount: Uint128 },
+}
+
+/// This structure describes the queries of the Astroport generator used by the Spectrum contracts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Deposit { lp_token: String, user: String },
+    PendingToken { lp_token: String, user: String },
+}
+
+const ASTRO_TOKEN: Item<Addr> = Item::new("astro_token");
+const DEPOSITS: Map<(&Addr, &Addr), Uint128> = Map::new("deposits");
+const PENDING: Map<(&Addr, &Addr), Uint128> = Map::new("pending");
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    ASTRO_TOKEN.save(deps.storage, &deps.api.addr_validate(&msg.astro_token)?)?;
+    Ok(Response::new())
+}
+
+pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Receive(cw20_msg) => {
+            let user = match from_binary(&cw20_msg.msg)? {
+                Cw20HookMsg::Deposit {} => deps.api.addr_validate(&cw20_msg.sender)?,
+                Cw20HookMsg::DepositFor(beneficiary) => beneficiary,
+            };
+            DEPOSITS.update(deps.storage, (&info.sender, &user), |deposit| -> StdResult<_> {
+                Ok(deposit.unwrap_or_default().checked_add(cw20_msg.amount)?)
+            })?;
+            Ok(Response::new().add_attribute("action", "deposit"))
+        }
+        ExecuteMsg::Withdraw { lp_token, amount } => {
+            let lp_token = deps.api.addr_validate(&lp_token)?;
+            DEPOSITS.update(deps.storage, (&lp_token, &info.sender), |deposit| -> StdResult<_> {
+                Ok(deposit.unwrap_or_default().checked_sub(amount)?)
+            })?;
+            Ok(Response::new()
+                .add_message(token_asset(lp_token, amount).transfer_msg(&info.sender)?)
+                .add_attribute("action", "withdraw"))
+        }
+        ExecuteMsg::ClaimRewards { lp_tokens } => {
+            let astro_token = ASTRO_TOKEN.load(deps.storage)?;
+            let mut messages: Vec<CosmosMsg> = vec![];
+            for lp_token in lp_tokens {
+                let lp_token = deps.api.addr_validate(&lp_token)?;
+                let pending = PENDING.may_load(deps.storage, (&lp_token, &info.sender))?.unwrap_or_default();
+                if !pending.is_zero() {
+                    PENDING.remove(deps.storage, (&lp_token, &info.sender));
+                    messages.push(token_asset(astro_token.clone(), pending).transfer_msg(&info.sender)?);
+                }
+            }
+            Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("action", "claim_rewards"))
+        }
+        ExecuteMsg::Accrue { lp_token, user, amount } => {
+            let lp_token = deps.api.addr_validate(&lp_token)?;
+            let user = deps.api.addr_validate(&user)?;
+            PENDING.update(deps.storage, (&lp_token, &user), |pending| -> StdResult<_> {
+                Ok(pending.unwrap_or_default().checked_add(amount)?)
+            })?;
+            Ok(Response::new().add_attribute("action", "accrue"))
+        }
+    }
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Deposit { lp_token, user } => {
+            let lp_token = deps.api.addr_validate(&lp_token)?;
+            let user = deps.api.addr_validate(&user)?;
+            to_binary(&DEPOSITS.may_load(deps.storage, (&lp_token, &user))?.unwrap_or_default())
+        }
+        QueryMsg::PendingToken { lp_token, user } => {
+            let lp_token = deps.api.addr_validate(&lp_token)?;
+            let user = deps.api.addr_validate(&user)?;
+            to_binary(&PendingTokenResponse {
+                pending: PENDING.may_load(deps.storage, (&lp_token, &user))?.unwrap_or_default(),
+                pending_on_proxy: None,
+            })
+        }
+    }
+}
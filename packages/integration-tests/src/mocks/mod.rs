@@ -0,0 +1,7 @@
+//! Minimal Astroport and cw20 contracts to wire the farm, compound proxy and fee collector
+//! together in cw-multi-test. They keep only the behavior the Spectrum contracts rely on.
+
+pub mod factory;
+pub mod generator;
+pub mod pair;
+pub mod token;
@@ -0,0 +1,305 @@
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
+use astroport::factory::PairType;
+use astroport::pair::{Cw20HookMsg, ExecuteMsg, PoolResponse, QueryMsg, SimulationResponse};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, Fraction, Isqrt,
+    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Item;
+use cw_utils::parse_reply_instantiate_data;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spectrum::adapters::asset::AssetEx;
+use spectrum::math::xyk_return_amount;
+use spectrum::querier::query_lp_supply;
+use std::convert::TryInto;
+
+use super::token;
+
+/// The commission of the pair in bps, charged from the return amount
+pub const COMMISSION_BPS: u64 = 30;
+
+/// The max spread of a swap without one
+const DEFAULT_MAX_SPREAD: u64 = 5; // 0.5%
+
+const INSTANTIATE_TOKEN_REPLY_ID: u64 = 1;
+
+/// This structure describes the parameters for creating a constant product pair.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub asset_infos: Vec<AssetInfo>,
+    /// The code of the LP token created by the pair
+    pub token_code_id: u64,
+}
+
+const PAIR_INFO: Item<PairInfo> = Item::new("pair_info");
+
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    PAIR_INFO.save(
+        deps.storage,
+        &PairInfo {
+            asset_infos: msg.asset_infos,
+            contract_addr: env.contract.address.clone(),
+            liquidity_token: Addr::unchecked(""),
+            pair_type: PairType::Xyk {},
+        },
+    )?;
+
+    let instantiate_token = WasmMsg::Instantiate {
+        admin: None,
+        code_id: msg.token_code_id,
+        msg: to_binary(&token::InstantiateMsg {
+            name: "Astroport LP token".to_string(),
+            symbol: "uLP".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            minter: Some(env.contract.address.to_string()),
+        })?,
+        funds: vec![],
+        label: "Astroport LP token".to_string(),
+    };
+
+    Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+        instantiate_token,
+        INSTANTIATE_TOKEN_REPLY_ID,
+    )))
+}
+
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    let res = parse_reply_instantiate_data(msg)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    PAIR_INFO.update(deps.storage, |mut pair_info| -> StdResult<_> {
+        pair_info.liquidity_token = Addr::unchecked(res.contract_address);
+        Ok(pair_info)
+    })?;
+    Ok(Response::new())
+}
+
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProvideLiquidity { assets, receiver, .. } => {
+            provide_liquidity(deps, env, info, assets, receiver)
+        }
+        ExecuteMsg::Swap { offer_asset, belief_price, max_spread, to, .. } => {
+            offer_asset.assert_sent_native_token_balance(&info)?;
+            let to = to.map(|to| deps.api.addr_validate(&to)).transpose()?;
+            swap(deps, env, offer_asset, belief_price, max_spread, to.unwrap_or(info.sender))
+        }
+        _ => Err(StdError::generic_err("unsupported message")),
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> StdResult<Response> {
+    let sender = deps.api.addr_validate(&cw20_msg.sender)?;
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Swap { belief_price, max_spread, to, .. } => {
+            let to = to.map(|to| deps.api.addr_validate(&to)).transpose()?;
+            let offer_asset = AssetInfo::Token { contract_addr: info.sender }.with_balance(cw20_msg.amount);
+            swap(deps, env, offer_asset, belief_price, max_spread, to.unwrap_or(sender))
+        }
+        Cw20HookMsg::WithdrawLiquidity { .. } => {
+            let pair_info = PAIR_INFO.load(deps.storage)?;
+            if info.sender != pair_info.liquidity_token {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            withdraw_liquidity(deps, env, pair_info, sender, cw20_msg.amount)
+        }
+    }
+}
+
+/// Returns the pool reserves, minus the offer amount already received by the pair
+fn query_reserves(deps: Deps, env: &Env, received: &Asset) -> StdResult<Vec<Asset>> {
+    let pair_info = PAIR_INFO.load(deps.storage)?;
+    let mut pools = pair_info.query_pools(&deps.querier, env.contract.address.clone())?;
+    for pool in pools.iter_mut() {
+        if pool.info.equal(&received.info) {
+            pool.amount = pool.amount.checked_sub(received.amount)?;
+        }
+    }
+    Ok(pools)
+}
+
+/// Simulates swapping the offer asset against the pools, returns the ask asset and the swap result
+fn simulate(pools: &[Asset], offer_asset: &Asset) -> StdResult<(AssetInfo, SimulationResponse)> {
+    let (offer_pool, ask_pool) = if pools[0].info.equal(&offer_asset.info) {
+        (&pools[0], &pools[1])
+    } else if pools[1].info.equal(&offer_asset.info) {
+        (&pools[1], &pools[0])
+    } else {
+        return Err(StdError::generic_err("asset is not in the pool"));
+    };
+
+    let return_amount =
+        xyk_return_amount(offer_pool.amount, ask_pool.amount, offer_asset.amount, COMMISSION_BPS)?;
+    let gross_amount = xyk_return_amount(offer_pool.amount, ask_pool.amount, offer_asset.amount, 0)?;
+    let ideal_amount = offer_asset.amount.multiply_ratio(ask_pool.amount, offer_pool.amount);
+
+    Ok((
+        ask_pool.info.clone(),
+        SimulationResponse {
+            return_amount,
+            spread_amount: ideal_amount.saturating_sub(gross_amount),
+            commission_amount: gross_amount - return_amount,
+        },
+    ))
+}
+
+/// Checks the spread of a swap like the Astroport pair, from the belief price if any or else from
+/// the spot price of the pool
+fn assert_max_spread(
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    offer_amount: Uint128,
+    simulation: &SimulationResponse,
+) -> StdResult<()> {
+    let max_spread = max_spread.unwrap_or_else(|| Decimal::permille(DEFAULT_MAX_SPREAD));
+    let return_amount = simulation.return_amount + simulation.commission_amount;
+    let exceeded = match belief_price {
+        Some(belief_price) => {
+            let expected_return = offer_amount * belief_price.inv().unwrap_or_default();
+            return_amount < expected_return
+                && Decimal::from_ratio(expected_return - return_amount, expected_return) > max_spread
+        }
+        None => {
+            Decimal::from_ratio(simulation.spread_amount, return_amount + simulation.spread_amount) > max_spread
+        }
+    };
+    if exceeded {
+        return Err(StdError::generic_err("Operation exceeds max spread limit"));
+    }
+    Ok(())
+}
+
+fn swap(
+    deps: DepsMut,
+    env: Env,
+    offer_asset: Asset,
+    belief_price: Option<Decimal>,
+    max_spread: Option<Decimal>,
+    to: Addr,
+) -> StdResult<Response> {
+    let pools = query_reserves(deps.as_ref(), &env, &offer_asset)?;
+    let (ask_info, simulation) = simulate(&pools, &offer_asset)?;
+    assert_max_spread(belief_price, max_spread, offer_asset.amount, &simulation)?;
+
+    Ok(Response::new()
+        .add_message(ask_info.with_balance(simulation.return_amount).transfer_msg(&to)?)
+        .add_attribute("action", "swap")
+        .add_attribute("offer_amount", offer_asset.amount)
+        .add_attribute("return_amount", simulation.return_amount))
+}
+
+fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: Vec<Asset>,
+    receiver: Option<String>,
+) -> StdResult<Response> {
+    let pair_info = PAIR_INFO.load(deps.storage)?;
+    let mut pools = pair_info.query_pools(&deps.querier, env.contract.address.clone())?;
+    let total_share = query_lp_supply(&deps.querier, pair_info.liquidity_token.to_string())?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut deposits: Vec<Uint128> = vec![];
+    for pool in pools.iter_mut() {
+        let deposit = assets
+            .iter()
+            .find(|asset| asset.info.equal(&pool.info))
+            .map(|asset| asset.amount)
+            .unwrap_or_default();
+        let asset = pool.info.with_balance(deposit);
+        if asset.is_native_token() {
+            asset.assert_sent_native_token_balance(&info)?;
+            pool.amount = pool.amount.checked_sub(deposit)?;
+        } else if !deposit.is_zero() {
+            messages.push(asset.transfer_from_msg(&info.sender, &env.contract.address)?);
+        }
+        deposits.push(deposit);
+    }
+
+    let share = if total_share.is_zero() {
+        (deposits[0].full_mul(deposits[1])).isqrt().try_into()?
+    } else {
+        std::cmp::min(
+            deposits[0].multiply_ratio(total_share, pools[0].amount),
+            deposits[1].multiply_ratio(total_share, pools[1].amount),
+        )
+    };
+    if share.is_zero() {
+        return Err(StdError::generic_err("insufficient liquidity"));
+    }
+
+    let receiver = receiver.unwrap_or_else(|| info.sender.to_string());
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pair_info.liquidity_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Mint {
+            recipient: receiver,
+            amount: share,
+        })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "provide_liquidity")
+        .add_attribute("share", share))
+}
+
+fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    pair_info: PairInfo,
+    sender: Addr,
+    amount: Uint128,
+) -> StdResult<Response> {
+    let pools = pair_info.query_pools(&deps.querier, env.contract.address)?;
+    let total_share = query_lp_supply(&deps.querier, pair_info.liquidity_token.to_string())?;
+
+    let mut messages: Vec<CosmosMsg> = pools
+        .into_iter()
+        .map(|pool| {
+            let refund = pool.info.with_balance(pool.amount.multiply_ratio(amount, total_share));
+            refund.transfer_msg(&sender)
+        })
+        .collect::<StdResult<_>>()?;
+    messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: pair_info.liquidity_token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+        funds: vec![],
+    }));
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "withdraw_liquidity")
+        .add_attribute("withdrawn_share", amount))
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let pair_info = PAIR_INFO.load(deps.storage)?;
+    match msg {
+        QueryMsg::Pair {} => to_binary(&pair_info),
+        QueryMsg::Pool {} => to_binary(&PoolResponse {
+            assets: pair_info.query_pools(&deps.querier, env.contract.address)?,
+            total_share: query_lp_supply(&deps.querier, pair_info.liquidity_token.to_string())?,
+        }),
+        QueryMsg::Simulation { offer_asset, .. } => {
+            let pools = pair_info.query_pools(&deps.querier, env.contract.address)?;
+            to_binary(&simulate(&pools, &offer_asset)?.1)
+        }
+        _ => Err(StdError::generic_err("unsupported query")),
+    }
+}
+
@@ -0,0 +1,174 @@
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    Storage, Uint128,
+};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, TokenInfoResponse};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the parameters for creating a token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<Cw20Coin>,
+    /// The address allowed to mint, minting is disabled if not set
+    pub minter: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct TokenInfo {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    total_supply: Uint128,
+    minter: Option<Addr>,
+}
+
+const TOKEN_INFO: Item<TokenInfo> = Item::new("token_info");
+const BALANCES: Map<&Addr, Uint128> = Map::new("balances");
+const ALLOWANCES: Map<(&Addr, &Addr), Uint128> = Map::new("allowances");
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let mut total_supply = Uint128::zero();
+    for coin in msg.initial_balances {
+        let address = deps.api.addr_validate(&coin.address)?;
+        BALANCES.save(deps.storage, &address, &coin.amount)?;
+        total_supply += coin.amount;
+    }
+
+    TOKEN_INFO.save(
+        deps.storage,
+        &TokenInfo {
+            name: msg.name,
+            symbol: msg.symbol,
+            decimals: msg.decimals,
+            total_supply,
+            minter: msg.minter.map(|minter| deps.api.addr_validate(&minter)).transpose()?,
+        },
+    )?;
+
+    Ok(Response::new())
+}
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: Cw20ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        Cw20ExecuteMsg::Transfer { recipient, amount } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            move_balance(deps.storage, &info.sender, &recipient, amount)?;
+            Ok(Response::new().add_attribute("action", "transfer"))
+        }
+        Cw20ExecuteMsg::TransferFrom { owner, recipient, amount } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let recipient = deps.api.addr_validate(&recipient)?;
+            spend_allowance(deps.storage, &owner, &info.sender, amount)?;
+            move_balance(deps.storage, &owner, &recipient, amount)?;
+            Ok(Response::new().add_attribute("action", "transfer_from"))
+        }
+        Cw20ExecuteMsg::Send { contract, amount, msg } => {
+            let contract = deps.api.addr_validate(&contract)?;
+            move_balance(deps.storage, &info.sender, &contract, amount)?;
+            Ok(Response::new()
+                .add_message(
+                    Cw20ReceiveMsg {
+                        sender: info.sender.to_string(),
+                        amount,
+                        msg,
+                    }
+                    .into_cosmos_msg(contract)?,
+                )
+                .add_attribute("action", "send"))
+        }
+        Cw20ExecuteMsg::IncreaseAllowance { spender, amount, .. } => {
+            let spender = deps.api.addr_validate(&spender)?;
+            ALLOWANCES.update(deps.storage, (&info.sender, &spender), |allowance| -> StdResult<_> {
+                Ok(allowance.unwrap_or_default().checked_add(amount)?)
+            })?;
+            Ok(Response::new().add_attribute("action", "increase_allowance"))
+        }
+        Cw20ExecuteMsg::Mint { recipient, amount } => {
+            let mut token_info = TOKEN_INFO.load(deps.storage)?;
+            if token_info.minter.as_ref() != Some(&info.sender) {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            let recipient = deps.api.addr_validate(&recipient)?;
+            BALANCES.update(deps.storage, &recipient, |balance| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_add(amount)?)
+            })?;
+            token_info.total_supply = token_info.total_supply.checked_add(amount)?;
+            TOKEN_INFO.save(deps.storage, &token_info)?;
+            Ok(Response::new().add_attribute("action", "mint"))
+        }
+        Cw20ExecuteMsg::Burn { amount } => {
+            let mut token_info = TOKEN_INFO.load(deps.storage)?;
+            BALANCES.update(deps.storage, &info.sender, |balance| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_sub(amount)?)
+            })?;
+            token_info.total_supply = token_info.total_supply.checked_sub(amount)?;
+            TOKEN_INFO.save(deps.storage, &token_info)?;
+            Ok(Response::new().add_attribute("action", "burn"))
+        }
+        _ => Err(StdError::generic_err("unsupported message")),
+    }
+}
+
+/// Moves the amount from the owner to the recipient balance
+fn move_balance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    BALANCES.update(storage, owner, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    BALANCES.update(storage, recipient, |balance| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(())
+}
+
+/// Deducts the amount from the allowance of the spender, expirations are not tracked
+fn spend_allowance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    ALLOWANCES.update(storage, (owner, spender), |allowance| -> StdResult<_> {
+        Ok(allowance.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    Ok(())
+}
+
+pub fn query(deps: Deps, _env: Env, msg: Cw20QueryMsg) -> StdResult<Binary> {
+    match msg {
+        Cw20QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            let balance = BALANCES.may_load(deps.storage, &address)?.unwrap_or_default();
+            to_binary(&BalanceResponse { balance })
+        }
+        Cw20QueryMsg::TokenInfo {} => {
+            let token_info = TOKEN_INFO.load(deps.storage)?;
+            to_binary(&TokenInfoResponse {
+                name: token_info.name,
+                symbol: token_info.symbol,
+                decimals: token_info.decimals,
+                total_supply: token_info.total_supply,
+            })
+        }
+        _ => Err(StdError::generic_err("unsupported query")),
+    }
+}
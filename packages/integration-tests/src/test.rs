@@ -0,0 +1,695 @@
+use astroport::asset::{native_asset, native_asset_info, token_asset, token_asset_info, PairInfo};
+use astroport::pair::ExecuteMsg as PairExecuteMsg;
+use cosmwasm_std::{coin, coins, to_binary, Addr, Decimal, Empty, Event, Uint128};
+use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+use spectrum::astroport_farm::{
+    Cw20HookMsg as FarmCw20HookMsg, ExecuteMsg as FarmExecuteMsg, InstantiateMsg as FarmInstantiateMsg,
+    QueryMsg as FarmQueryMsg, UserInfoResponse,
+};
+use spectrum::compound_proxy::InstantiateMsg as CompoundProxyInstantiateMsg;
+use spectrum::farm_factory::{
+    ExecuteMsg as FarmFactoryExecuteMsg, FarmResponse, FarmTemplate,
+    InstantiateMsg as FarmFactoryInstantiateMsg, QueryMsg as FarmFactoryQueryMsg,
+};
+use spectrum::fees_collector::{
+    AssetConfig, AssetWithLimit, ExecuteMsg as FeesCollectorExecuteMsg,
+    InstantiateMsg as FeesCollectorInstantiateMsg,
+};
+
+use crate::mocks::{factory, generator, pair, token};
+
+const OWNER: &str = "owner";
+const USER: &str = "user";
+const CONTROLLER: &str = "controller";
+const TREASURY: &str = "treasury";
+const ULUNA: &str = "uluna";
+
+const POOL_AMOUNT: u128 = 1_000_000_000_000;
+const BOND_AMOUNT: u128 = 1_000_000_000;
+/// The shares of the first bond locked in the farm
+const LOCKED_SHARES: u128 = 1000;
+const REWARD_AMOUNT: u128 = 1_000_000_000;
+/// The reserves of a pool too thin to swap the fee within the max spread
+const THIN_POOL_AMOUNT: u128 = 100_000_000;
+
+struct Contracts {
+    astro_token: Addr,
+    lp_token: Addr,
+    pair: Addr,
+    factory: Addr,
+    generator: Addr,
+    fee_collector: Addr,
+    farm: Addr,
+}
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(token::execute, token::instantiate, token::query))
+}
+
+fn factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(factory::execute, factory::instantiate, factory::query))
+}
+
+fn pair_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(pair::execute, pair::instantiate, pair::query)
+            .with_reply_empty(pair::reply),
+    )
+}
+
+fn generator_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        generator::execute,
+        generator::instantiate,
+        generator::query,
+    ))
+}
+
+fn farm_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            IDC_astroport_farm::contract::execute,
+            IDC_astroport_farm::contract::instantiate,
+            IDC_astroport_farm::contract::query,
+        )
+        .with_reply_empty(IDC_astroport_farm::contract::reply),
+    )
+}
+
+fn compound_proxy_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new_with_empty(
+        IDC_compound_proxy::contract::execute,
+        IDC_compound_proxy::contract::instantiate,
+        IDC_compound_proxy::contract::query,
+    ))
+}
+
+fn fee_collector_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            IDC_fee_collector::contract::execute,
+            IDC_fee_collector::contract::instantiate,
+            IDC_fee_collector::contract::query,
+        )
+        .with_reply_empty(IDC_fee_collector::contract::reply),
+    )
+}
+
+fn farm_factory_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(
+        ContractWrapper::new_with_empty(
+            IDC_farm_factory::contract::execute,
+            IDC_farm_factory::contract::instantiate,
+            IDC_farm_factory::contract::query,
+        )
+        .with_reply_empty(IDC_farm_factory::contract::reply),
+    )
+}
+
+fn query_token_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = app
+        .wrap()
+        .query_wasm_smart(token, &Cw20QueryMsg::Balance { address: address.to_string() })
+        .unwrap();
+    res.balance
+}
+
+#[test]
+fn test() {
+    let mut app = App::new(|router, _, storage| {
+        router
+            .bank
+            .init_balance(storage, &Addr::unchecked(OWNER), coins(POOL_AMOUNT + THIN_POOL_AMOUNT, ULUNA))
+            .unwrap();
+    });
+
+    let contracts = instantiate(&mut app);
+    deposit(&mut app, &contracts);
+    earn(&mut app, &contracts);
+    compound(&mut app, &contracts);
+    collect_fees(&mut app, &contracts);
+    collect_fees_thin_pool(&mut app, &contracts);
+    withdraw(&mut app, &contracts);
+    create_farm(&mut app, &contracts);
+}
+
+fn instantiate(app: &mut App) -> Contracts {
+    let owner = Addr::unchecked(OWNER);
+    let token_code_id = app.store_code(token_contract());
+    let factory_code_id = app.store_code(factory_contract());
+    let pair_code_id = app.store_code(pair_contract());
+    let generator_code_id = app.store_code(generator_contract());
+    let farm_code_id = app.store_code(farm_contract());
+    let compound_proxy_code_id = app.store_code(compound_proxy_contract());
+    let fee_collector_code_id = app.store_code(fee_collector_contract());
+
+    let astro_token = app
+        .instantiate_contract(
+            token_code_id,
+            owner.clone(),
+            &token::InstantiateMsg {
+                name: "Astroport".to_string(),
+                symbol: "ASTRO".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: OWNER.to_string(),
+                    amount: Uint128::new(POOL_AMOUNT + REWARD_AMOUNT),
+                }],
+                minter: None,
+            },
+            &[],
+            "astro",
+            None,
+        )
+        .unwrap();
+
+    let pair = app
+        .instantiate_contract(
+            pair_code_id,
+            owner.clone(),
+            &pair::InstantiateMsg {
+                asset_infos: vec![token_asset_info(astro_token.clone()), native_asset_info(ULUNA.to_string())],
+                token_code_id,
+            },
+            &[],
+            "pair",
+            None,
+        )
+        .unwrap();
+    let pair_info: PairInfo = app
+        .wrap()
+        .query_wasm_smart(&pair, &astroport::pair::QueryMsg::Pair {})
+        .unwrap();
+    let lp_token = pair_info.liquidity_token;
+
+    let factory = app
+        .instantiate_contract(factory_code_id, owner.clone(), &factory::InstantiateMsg {}, &[], "factory", None)
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        factory.clone(),
+        &factory::ExecuteMsg::RegisterPair { pair: pair.to_string() },
+        &[],
+    )
+    .unwrap();
+
+    // the owner provides the pool and keeps the LP token for the user to bond
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: pair.to_string(),
+            amount: Uint128::new(POOL_AMOUNT),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        pair.clone(),
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                token_asset(astro_token.clone(), Uint128::new(POOL_AMOUNT)),
+                native_asset(ULUNA.to_string(), Uint128::new(POOL_AMOUNT)),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+        &coins(POOL_AMOUNT, ULUNA),
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        lp_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: USER.to_string(),
+            amount: Uint128::new(BOND_AMOUNT),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let generator = app
+        .instantiate_contract(
+            generator_code_id,
+            owner.clone(),
+            &generator::InstantiateMsg { astro_token: astro_token.to_string() },
+            &[],
+            "generator",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        astro_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: generator.to_string(),
+            amount: Uint128::new(REWARD_AMOUNT),
+        },
+        &[],
+    )
+    .unwrap();
+
+    let compound_proxy = app
+        .instantiate_contract(
+            compound_proxy_code_id,
+            owner.clone(),
+            &CompoundProxyInstantiateMsg {
+                owner: owner.to_string(),
+                pair_contract: pair.to_string(),
+                factory: factory.to_string(),
+                pair_proxies: vec![],
+                slippage_tolerance: Decimal::percent(1),
+                price_guard: None,
+            },
+            &[],
+            "compound_proxy",
+            None,
+        )
+        .unwrap();
+
+    let fee_collector = app
+        .instantiate_contract(
+            fee_collector_code_id,
+            owner.clone(),
+            &FeesCollectorInstantiateMsg {
+                owner: OWNER.to_string(),
+                operator: OWNER.to_string(),
+                factory_contract: factory.to_string(),
+                stablecoin: native_asset_info(ULUNA.to_string()),
+                target_list: vec![(TREASURY.to_string(), 1)],
+                collect_incentive_bps: None,
+                distribution_interval: None,
+                burn_bps: None,
+                ibc_target_list: None,
+                ibc_timeout: None,
+                staking_target_list: None,
+                generator_rewards: None,
+                swap_router: None,
+                max_swap_portion_bps: None,
+                config_delay: None,
+                pauser: None,
+                lp_target_list: None,
+            },
+            &[],
+            "fee_collector",
+            None,
+        )
+        .unwrap();
+
+    let farm = app
+        .instantiate_contract(
+            farm_code_id,
+            owner,
+            &FarmInstantiateMsg {
+                owner: OWNER.to_string(),
+                staking_contract: generator.to_string(),
+                staking_interface: None,
+                compound_proxy: compound_proxy.to_string(),
+                controller: CONTROLLER.to_string(),
+                fee: Decimal::percent(5),
+                fee_collector: fee_collector.to_string(),
+                liquidity_token: lp_token.to_string(),
+                base_reward_token: astro_token.to_string(),
+                pair: pair.to_string(),
+                pauser: None,
+                deposit_cap: None,
+                unbonding_period: None,
+                min_reward_value: None,
+                name: "Spectrum ASTRO-LUNA LP".to_string(),
+                symbol: "spLP".to_string(),
+            },
+            &[],
+            "farm",
+            None,
+        )
+        .unwrap();
+
+    Contracts {
+        astro_token,
+        lp_token,
+        pair,
+        factory,
+        generator,
+        fee_collector,
+        farm,
+    }
+}
+
+fn query_user_info(app: &App, contracts: &Contracts) -> UserInfoResponse {
+    app.wrap()
+        .query_wasm_smart(
+            &contracts.farm,
+            &FarmQueryMsg::UserInfo { staker_addr: USER.to_string() },
+        )
+        .unwrap()
+}
+
+fn query_farm_deposit(app: &App, contracts: &Contracts) -> Uint128 {
+    app.wrap()
+        .query_wasm_smart(
+            &contracts.generator,
+            &generator::QueryMsg::Deposit {
+                lp_token: contracts.lp_token.to_string(),
+                user: contracts.farm.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+fn deposit(app: &mut App, contracts: &Contracts) {
+    app.execute_contract(
+        Addr::unchecked(USER),
+        contracts.lp_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: contracts.farm.to_string(),
+            amount: Uint128::new(BOND_AMOUNT),
+            msg: to_binary(&FarmCw20HookMsg::Bond { staker_addr: None, referrer: None }).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the bonded LP token is staked in the generator
+    assert_eq!(query_token_balance(app, &contracts.lp_token, USER), Uint128::zero());
+    assert_eq!(query_farm_deposit(app, contracts), Uint128::new(BOND_AMOUNT));
+    assert_eq!(query_user_info(app, contracts).lp_amount, Uint128::new(BOND_AMOUNT - LOCKED_SHARES));
+}
+
+fn earn(app: &mut App, contracts: &Contracts) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        contracts.generator.clone(),
+        &generator::ExecuteMsg::Accrue {
+            lp_token: contracts.lp_token.to_string(),
+            user: contracts.farm.to_string(),
+            amount: Uint128::new(REWARD_AMOUNT),
+        },
+        &[],
+    )
+    .unwrap();
+}
+
+fn compound(app: &mut App, contracts: &Contracts) {
+    // only the controller can compound
+    let res = app.execute_contract(
+        Addr::unchecked(USER),
+        contracts.farm.clone(),
+        &FarmExecuteMsg::Compound {
+            minimum_receive: None,
+            slippage_tolerance: None,
+        },
+        &[],
+    );
+    assert_eq!(res.unwrap_err().root_cause().to_string(), "Unauthorized");
+
+    app.execute_contract(
+        Addr::unchecked(CONTROLLER),
+        contracts.farm.clone(),
+        &FarmExecuteMsg::Compound {
+            minimum_receive: None,
+            slippage_tolerance: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the performance fee is sent to the fee collector, the rest is compounded and restaked
+    assert_eq!(
+        query_token_balance(app, &contracts.astro_token, contracts.fee_collector.as_str()),
+        Uint128::new(REWARD_AMOUNT / 20)
+    );
+    assert_eq!(
+        query_token_balance(app, &contracts.astro_token, contracts.generator.as_str()),
+        Uint128::zero()
+    );
+    let compounded = query_farm_deposit(app, contracts) - Uint128::new(BOND_AMOUNT);
+    assert_eq!(compounded, Uint128::new(474_173_501));
+    assert_eq!(
+        query_user_info(app, contracts).lp_amount,
+        (Uint128::new(BOND_AMOUNT) + compounded).multiply_ratio(BOND_AMOUNT - LOCKED_SHARES, BOND_AMOUNT)
+    );
+
+    // no dust is left in the farm
+    assert_eq!(
+        query_token_balance(app, &contracts.astro_token, contracts.farm.as_str()),
+        Uint128::zero()
+    );
+    assert_eq!(query_token_balance(app, &contracts.lp_token, contracts.farm.as_str()), Uint128::zero());
+}
+
+fn collect_fees(app: &mut App, contracts: &Contracts) {
+    app.execute_contract(
+        Addr::unchecked(OWNER),
+        contracts.fee_collector.clone(),
+        &FeesCollectorExecuteMsg::Collect {
+            assets: vec![AssetWithLimit {
+                info: token_asset_info(contracts.astro_token.clone()),
+                limit: None,
+            }],
+            minimum_receive: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the fee is swapped to the stablecoin in the pair and distributed to the treasury
+    assert_eq!(
+        query_token_balance(app, &contracts.astro_token, contracts.fee_collector.as_str()),
+        Uint128::zero()
+    );
+    let treasury_balance = app.wrap().query_balance(TREASURY, ULUNA).unwrap();
+    assert_eq!(treasury_balance, coin(49_800_199, ULUNA));
+}
+
+fn collect_fees_thin_pool(app: &mut App, contracts: &Contracts) {
+    let owner = Addr::unchecked(OWNER);
+    let token_code_id = app.store_code(token_contract());
+    let pair_code_id = app.store_code(pair_contract());
+
+    let thin_token = app
+        .instantiate_contract(
+            token_code_id,
+            owner.clone(),
+            &token::InstantiateMsg {
+                name: "Thin".to_string(),
+                symbol: "THIN".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: OWNER.to_string(),
+                    amount: Uint128::new(THIN_POOL_AMOUNT * 2),
+                }],
+                minter: None,
+            },
+            &[],
+            "thin",
+            None,
+        )
+        .unwrap();
+    let thin_pair = app
+        .instantiate_contract(
+            pair_code_id,
+            owner.clone(),
+            &pair::InstantiateMsg {
+                asset_infos: vec![token_asset_info(thin_token.clone()), native_asset_info(ULUNA.to_string())],
+                token_code_id,
+            },
+            &[],
+            "thin_pair",
+            None,
+        )
+        .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        contracts.factory.clone(),
+        &factory::ExecuteMsg::RegisterPair { pair: thin_pair.to_string() },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        thin_token.clone(),
+        &Cw20ExecuteMsg::IncreaseAllowance {
+            spender: thin_pair.to_string(),
+            amount: Uint128::new(THIN_POOL_AMOUNT),
+            expires: None,
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        thin_pair,
+        &PairExecuteMsg::ProvideLiquidity {
+            assets: vec![
+                token_asset(thin_token.clone(), Uint128::new(THIN_POOL_AMOUNT)),
+                native_asset(ULUNA.to_string(), Uint128::new(THIN_POOL_AMOUNT)),
+            ],
+            slippage_tolerance: None,
+            auto_stake: None,
+            receiver: None,
+        },
+        &coins(THIN_POOL_AMOUNT, ULUNA),
+    )
+    .unwrap();
+
+    // a fee of 10% of the pool is swapped with a spread of 9%, above the max spread of the fee token
+    app.execute_contract(
+        owner.clone(),
+        thin_token.clone(),
+        &Cw20ExecuteMsg::Transfer {
+            recipient: contracts.fee_collector.to_string(),
+            amount: Uint128::new(THIN_POOL_AMOUNT / 10),
+        },
+        &[],
+    )
+    .unwrap();
+    app.execute_contract(
+        owner.clone(),
+        contracts.fee_collector.clone(),
+        &FeesCollectorExecuteMsg::UpdateAssetConfigs {
+            add: Some(vec![AssetConfig {
+                info: token_asset_info(thin_token.clone()),
+                max_spread: Some(Decimal::percent(5)),
+                min_amount: None,
+                unwrap: None,
+                swap_threshold: None,
+            }]),
+            remove: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the pair rejects the swap and the fee token is kept for the next collect
+    let res = app
+        .execute_contract(
+            owner,
+            contracts.fee_collector.clone(),
+            &FeesCollectorExecuteMsg::Collect {
+                assets: vec![AssetWithLimit {
+                    info: token_asset_info(thin_token.clone()),
+                    limit: None,
+                }],
+                minimum_receive: None,
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(res.has_event(
+        &Event::new("wasm")
+            .add_attribute("action", "swap_failed")
+            .add_attribute("asset", thin_token.to_string())
+    ));
+    assert_eq!(
+        query_token_balance(app, &thin_token, contracts.fee_collector.as_str()),
+        Uint128::new(THIN_POOL_AMOUNT / 10)
+    );
+}
+
+fn withdraw(app: &mut App, contracts: &Contracts) {
+    // the compounded LP token of a new deposit is unbondable after a day
+    app.update_block(|block| block.time = block.time.plus_seconds(86400));
+
+    let user_info = query_user_info(app, contracts);
+    let farm_deposit = query_farm_deposit(app, contracts);
+    app.execute_contract(
+        Addr::unchecked(USER),
+        contracts.farm.clone(),
+        &FarmExecuteMsg::Unbond {
+            amount: user_info.lp_amount,
+            as_asset: None,
+            minimum_receive: None,
+            slippage_tolerance: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    // the user gets the bonded and compounded LP token back, only the locked shares are left
+    assert_eq!(query_token_balance(app, &contracts.lp_token, USER), user_info.lp_amount);
+    assert_eq!(query_farm_deposit(app, contracts), farm_deposit - user_info.lp_amount);
+
+    // the LP token is redeemable for both pool assets
+    app.execute_contract(
+        Addr::unchecked(USER),
+        contracts.lp_token.clone(),
+        &Cw20ExecuteMsg::Send {
+            contract: contracts.pair.to_string(),
+            amount: user_info.lp_amount,
+            msg: to_binary(&astroport::pair::Cw20HookMsg::WithdrawLiquidity { assets: vec![] }).unwrap(),
+        },
+        &[],
+    )
+    .unwrap();
+    assert!(!query_token_balance(app, &contracts.astro_token, USER).is_zero());
+    assert!(!app.wrap().query_balance(USER, ULUNA).unwrap().amount.is_zero());
+}
+
+fn create_farm(app: &mut App, contracts: &Contracts) {
+    let owner = Addr::unchecked(OWNER);
+    let farm_code_id = app.store_code(farm_contract());
+    let compound_proxy_code_id = app.store_code(compound_proxy_contract());
+    let farm_factory_code_id = app.store_code(farm_factory_contract());
+
+    let farm_factory = app
+        .instantiate_contract(
+            farm_factory_code_id,
+            owner.clone(),
+            &FarmFactoryInstantiateMsg {
+                owner: OWNER.to_string(),
+                template: FarmTemplate {
+                    farm_code_id,
+                    compound_proxy_code_id,
+                    astroport_factory: contracts.factory.to_string(),
+                    staking_contract: contracts.generator.to_string(),
+                    staking_interface: None,
+                    controller: CONTROLLER.to_string(),
+                    fee: Decimal::percent(5),
+                    fee_collector: contracts.fee_collector.to_string(),
+                    base_reward_token: contracts.astro_token.to_string(),
+                    slippage_tolerance: Decimal::percent(1),
+                    pauser: None,
+                },
+            },
+            &[],
+            "farm_factory",
+            None,
+        )
+        .unwrap();
+
+    // one message creates the compound proxy and the farm of the pair
+    app.execute_contract(
+        owner,
+        farm_factory.clone(),
+        &FarmFactoryExecuteMsg::CreateFarm {
+            pair_contract: contracts.pair.to_string(),
+            name: "Spectrum ASTRO-LUNA LP".to_string(),
+            symbol: "spLP".to_string(),
+            fee: None,
+            pair_proxies: None,
+        },
+        &[],
+    )
+    .unwrap();
+
+    let farm: FarmResponse = app
+        .wrap()
+        .query_wasm_smart(&farm_factory, &FarmFactoryQueryMsg::Farm { pair_contract: contracts.pair.to_string() })
+        .unwrap();
+    assert_eq!(farm.liquidity_token, contracts.lp_token.to_string());
+
+    let farm_config: IDC_astroport_farm::state::Config = app
+        .wrap()
+        .query_wasm_smart(&farm.farm, &FarmQueryMsg::Config {})
+        .unwrap();
+    assert_eq!(farm_config.compound_proxy.0.to_string(), farm.compound_proxy);
+    assert_eq!(farm_config.liquidity_token, contracts.lp_token);
+    assert_eq!(farm_config.pair.0, contracts.pair);
+
+    let contract_data = app.contract_data(&Addr::unchecked(&farm.farm)).unwrap();
+    assert_eq!(contract_data.admin, Some(Addr::unchecked(OWNER)));
+}
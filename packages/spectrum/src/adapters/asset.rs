@@ -6,6 +6,7 @@ pub trait AssetEx {
 
     fn transfer_msg(&self, to: &Addr) -> StdResult<CosmosMsg>;
     fn transfer_from_msg(&self, from: &Addr, to: &Addr) -> StdResult<CosmosMsg>;
+    fn burn_msg(&self) -> StdResult<CosmosMsg>;
     fn increase_allowance_msg(&self, spender: String, expires: Option<Expiration>) -> StdResult<CosmosMsg>;
 
     fn deposit_asset(
@@ -58,6 +59,24 @@ impl AssetEx for Asset {
         }
     }
 
+    fn burn_msg(&self) -> StdResult<CosmosMsg> {
+        match &self.info {
+            AssetInfo::Token { contract_addr } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn {
+                    amount: self.amount,
+                })?,
+                funds: vec![],
+            })),
+            AssetInfo::NativeToken { denom } => Ok(CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: denom.to_string(),
+                    amount: self.amount,
+                }],
+            })),
+        }
+    }
+
     fn increase_allowance_msg(&self, spender: String, expires: Option<Expiration>) -> StdResult<CosmosMsg> {
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: self.info.to_string(),
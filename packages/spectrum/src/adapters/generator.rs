@@ -1,16 +1,17 @@
-use cosmwasm_std::{Addr, CosmosMsg, QuerierWrapper, StdResult, to_binary, Uint128, WasmMsg};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, CustomQuery, QuerierWrapper, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use astroport::asset::is_token_factory_denom;
 use astroport::generator::{Cw20HookMsg, ExecuteMsg, PendingTokenResponse, QueryMsg};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Generator(pub Addr);
 
 impl Generator {
-    pub fn query_pending_token(
+    pub fn query_pending_token<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         lp_token: &Addr,
         staker: &Addr,
     ) -> StdResult<PendingTokenResponse> {
@@ -20,9 +21,9 @@ impl Generator {
         })
     }
 
-    pub fn query_deposit(
+    pub fn query_deposit<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         lp_token: &Addr,
         staker: &Addr,
     ) -> StdResult<Uint128> {
@@ -32,7 +33,16 @@ impl Generator {
         })
     }
 
+    /// Stakes the LP token, a x/tokenfactory LP token is sent as funds
     pub fn deposit_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        if is_token_factory_denom(&lp_token) {
+            return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.0.to_string(),
+                funds: vec![Coin { denom: lp_token, amount }],
+                msg: to_binary(&ExecuteMsg::Deposit {})?,
+            }));
+        }
+
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: lp_token,
             funds: vec![],
@@ -0,0 +1,37 @@
+use cosmwasm_std::{Addr, CustomQuery, QuerierWrapper, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the boost query of the SPEC governance staking contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the staked SPEC weight of the address and the total weight
+    BoostWeight {
+        address: String,
+    },
+}
+
+/// This structure holds the parameters that are returned from the boost weight query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BoostWeightResponse {
+    /// The staked SPEC weight of the address
+    pub weight: Uint128,
+    /// The total staked SPEC weight
+    pub total_weight: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Gov(pub Addr);
+
+impl Gov {
+    pub fn query_boost_weight<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        address: &Addr,
+    ) -> StdResult<BoostWeightResponse> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::BoostWeight {
+            address: address.to_string(),
+        })
+    }
+}
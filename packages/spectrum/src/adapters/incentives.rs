@@ -0,0 +1,101 @@
+use astroport::asset::{is_token_factory_denom, Asset};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, CustomQuery, QuerierWrapper, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the execute messages of the Astroport incentives contract used by the farm.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Stakes the LP token sent as funds
+    Deposit { recipient: Option<String> },
+    /// Withdraws the LP token and claims its rewards
+    Withdraw { lp_token: String, amount: Uint128 },
+    /// Claims the rewards of the LP tokens
+    ClaimRewards { lp_tokens: Vec<String> },
+}
+
+/// This structure describes the cw20 hook messages of the Astroport incentives contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Stakes the cw20 LP token
+    Deposit { recipient: Option<String> },
+}
+
+/// This structure describes the queries of the Astroport incentives contract used by the farm.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the LP token amount staked by the user
+    Deposit { lp_token: String, user: String },
+    /// Returns the pending rewards of the user, all reward tokens in one list
+    PendingRewards { lp_token: String, user: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Incentives(pub Addr);
+
+impl Incentives {
+    pub fn query_pending_rewards<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        lp_token: &Addr,
+        staker: &Addr,
+    ) -> StdResult<Vec<Asset>> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::PendingRewards {
+            lp_token: lp_token.to_string(),
+            user: staker.to_string(),
+        })
+    }
+
+    pub fn query_deposit<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        lp_token: &Addr,
+        staker: &Addr,
+    ) -> StdResult<Uint128> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Deposit {
+            lp_token: lp_token.to_string(),
+            user: staker.to_string(),
+        })
+    }
+
+    /// Stakes the LP token, a x/tokenfactory LP token is sent as funds
+    pub fn deposit_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        if is_token_factory_denom(&lp_token) {
+            return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.0.to_string(),
+                funds: vec![Coin { denom: lp_token, amount }],
+                msg: to_binary(&ExecuteMsg::Deposit { recipient: None })?,
+            }));
+        }
+
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lp_token,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.0.to_string(),
+                amount,
+                msg: to_binary(&Cw20HookMsg::Deposit { recipient: None })?,
+            })?,
+        }))
+    }
+
+    pub fn withdraw_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::Withdraw { lp_token, amount })?,
+        }))
+    }
+
+    pub fn claim_rewards_msg(&self, lp_tokens: Vec<String>) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ClaimRewards { lp_tokens })?,
+        }))
+    }
+}
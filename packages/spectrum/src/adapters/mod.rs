@@ -2,3 +2,8 @@ pub mod asset;
 pub mod pair;
 pub mod router;
 pub mod generator;
+pub mod incentives;
+pub mod gov;
+pub mod oracle;
+pub mod scheduler;
+pub mod staking;
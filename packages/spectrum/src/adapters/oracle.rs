@@ -0,0 +1,147 @@
+use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::factory::PairType;
+use astroport::pair::PoolResponse;
+use astroport::querier::query_token_precision;
+use cosmwasm_std::{Addr, Api, CustomQuery, Decimal256, Isqrt, QuerierWrapper, StdError, StdResult, Uint128, Uint256};
+use std::convert::TryInto;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::adapters::pair::{Pair, PairAdapter};
+use crate::oracle::{PriceGuard, PriceResponse, QueryMsg, ValueAssetsResponse};
+
+/// Scaling denominator for the price deviation
+const BPS_DENOM: u64 = 10000u64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Oracle(pub Addr);
+
+impl Oracle {
+    pub fn query_price<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        base: &AssetInfo,
+        quote: &AssetInfo,
+        window: u64,
+    ) -> StdResult<PriceResponse> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Price {
+            base: base.clone(),
+            quote: quote.clone(),
+            window,
+        })
+    }
+
+    pub fn query_value_assets<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        assets: &[Asset],
+        quote: &AssetInfo,
+        window: u64,
+    ) -> StdResult<Uint128> {
+        let res: ValueAssetsResponse = querier.query_wasm_smart(self.0.to_string(), &QueryMsg::ValueAssets {
+            assets: assets.to_vec(),
+            quote: quote.clone(),
+            window,
+        })?;
+        Ok(res.value)
+    }
+}
+
+/// This structure stores the guard that aborts compounds while the spot price of the pool
+/// deviates from its time weighted average price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceGuardConfig {
+    /// The oracle contract
+    pub oracle: Oracle,
+    /// The number of seconds of the time weighted average price
+    pub window: u64,
+    /// The max deviation of the spot price in bps
+    pub max_deviation_bps: u64,
+}
+
+impl PriceGuardConfig {
+    /// Validates the guard, returns `None` when the max deviation is 0
+    pub fn from_msg(api: &dyn Api, msg: PriceGuard) -> StdResult<Option<Self>> {
+        if msg.max_deviation_bps > BPS_DENOM {
+            return Err(StdError::generic_err("max_deviation_bps must be 0 to 10000"));
+        }
+        if msg.max_deviation_bps == 0 {
+            return Ok(None);
+        }
+        Ok(Some(PriceGuardConfig {
+            oracle: Oracle(api.addr_validate(&msg.oracle)?),
+            window: msg.window,
+            max_deviation_bps: msg.max_deviation_bps,
+        }))
+    }
+
+    /// Returns an error if the spot price of the first asset of the pair deviates from the
+    /// time weighted average price by more than the max deviation
+    pub fn assert_price<C: CustomQuery>(&self, querier: &QuerierWrapper<C>, pair_info: &PairInfo) -> StdResult<()> {
+        let base = &pair_info.asset_infos[0];
+        let quote = &pair_info.asset_infos[1];
+        let twap = self.oracle.query_price(querier, base, quote, self.window)?.price;
+
+        // spot price before spread and commission of a whole base asset
+        let precision = query_token_precision(querier, base)?;
+        let offer_asset = Asset {
+            info: base.clone(),
+            amount: Uint128::from(10u128.pow(precision.into())),
+        };
+        let res = Pair(pair_info.contract_addr.clone()).simulate(querier, &offer_asset, Some(quote.clone()))?;
+        let spot = Decimal256::from_ratio(
+            res.return_amount + res.spread_amount + res.commission_amount,
+            offer_asset.amount,
+        );
+
+        let deviation = if spot > twap { spot - twap } else { twap - spot };
+        if deviation > twap * Decimal256::from_ratio(self.max_deviation_bps, BPS_DENOM) {
+            return Err(StdError::generic_err(format!(
+                "Spot price {} deviates from TWAP {} by more than {} bps",
+                spot, twap, self.max_deviation_bps
+            )));
+        }
+
+        Ok(())
+    }
+    /// Returns the minimum amount of the ask asset received for the offer asset, valued at the
+    /// time weighted average price less the max deviation
+    pub fn minimum_receive<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        offer_asset: &Asset,
+        ask_asset_info: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        let twap = self.oracle.query_price(querier, &offer_asset.info, ask_asset_info, self.window)?.price;
+        let min_price = twap * (Decimal256::one() - Decimal256::from_ratio(self.max_deviation_bps, BPS_DENOM));
+        Ok((Uint256::from(offer_asset.amount) * min_price).try_into()?)
+    }
+
+    /// Returns the pool reserves anchored to the time weighted average price, so a value taken
+    /// from them cannot be skewed by a swap in the same block. The reserves of a constant product
+    /// pool are moved along its invariant to the TWAP, other pools must not deviate from the TWAP
+    /// by more than the max deviation.
+    pub fn anchored_pool<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        pair_info: &PairInfo,
+        mut pool: PoolResponse,
+    ) -> StdResult<PoolResponse> {
+        match pair_info.pair_type {
+            PairType::Xyk {} => {
+                let base = &pair_info.asset_infos[0];
+                let quote = &pair_info.asset_infos[1];
+                let twap = self.oracle.query_price(querier, base, quote, self.window)?.price;
+
+                // x * y = k and y / x = twap
+                let k = pool.assets[0].amount.full_mul(pool.assets[1].amount);
+                let quote_reserve = (k * twap).isqrt();
+                let base_reserve = k.checked_div(quote_reserve)?;
+                pool.assets[0].amount = base_reserve.try_into()?;
+                pool.assets[1].amount = quote_reserve.try_into()?;
+            }
+            _ => self.assert_price(querier, pair_info)?,
+        }
+        Ok(pool)
+    }
+}
@@ -1,29 +1,65 @@
-use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, to_binary, WasmMsg};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::asset::{is_token_factory_denom, Asset, AssetInfo, PairInfo};
 use astroport::pair::{Cw20HookMsg, ExecuteMsg, QueryMsg, SimulationResponse, ConfigResponse, PoolResponse};
 
+/// The messages and queries of a dex pair used to swap and to provide and withdraw liquidity.
+/// Contracts build pair messages through this trait, so a new dex only needs an implementation.
+pub trait PairAdapter {
+    /// Simulates swapping the offer asset
+    fn simulate<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        offer_asset: &Asset,
+        ask_asset_info: Option<AssetInfo>,
+    ) -> StdResult<SimulationResponse>;
+
+    /// Generate msg for swapping specified asset
+    fn swap_msg(
+        &self,
+        asset: &Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    ) -> StdResult<CosmosMsg>;
+
+    /// Generate msg for withdrawing liquidity with LP token, a x/tokenfactory LP token is sent as funds
+    fn withdraw_liquidity_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg>;
+
+    /// Generate msg for providing liquidity with the assets, native tokens are sent as funds
+    fn provide_liquidity_msg(
+        &self,
+        assets: Vec<Asset>,
+        slippage_tolerance: Option<Decimal>,
+        receiver: Option<String>,
+        funds: Vec<Coin>,
+    ) -> StdResult<CosmosMsg>;
+}
+
+/// The Astroport pair
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Pair(pub Addr);
 
 impl Pair {
-    pub fn query_pair_info(&self, querier: &QuerierWrapper) -> StdResult<PairInfo> {
+    pub fn query_pair_info<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<PairInfo> {
         querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Pair {})
     }
 
-    pub fn query_pool_info(&self, querier: &QuerierWrapper) -> StdResult<PoolResponse> {
+    pub fn query_pool_info<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<PoolResponse> {
         querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Pool {})
     }
 
-    pub fn query_config(&self, querier: &QuerierWrapper) -> StdResult<ConfigResponse> {
+    pub fn query_config<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<ConfigResponse> {
         querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Config {})
     }
+}
 
-    pub fn simulate(
+impl PairAdapter for Pair {
+    fn simulate<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         offer_asset: &Asset,
         ask_asset_info: Option<AssetInfo>
     ) -> StdResult<SimulationResponse> {
@@ -33,8 +69,7 @@ impl Pair {
         })
     }
 
-    /// Generate msg for swapping specified asset
-    pub fn swap_msg(
+    fn swap_msg(
         &self,
         asset: &Asset,
         belief_price: Option<Decimal>,
@@ -64,7 +99,7 @@ impl Pair {
                     ask_asset_info: None,
                     belief_price,
                     max_spread,
-                    to: None,
+                    to,
                 })?,
                 funds: vec![Coin {
                     denom: denom.clone(),
@@ -76,7 +111,31 @@ impl Pair {
         Ok(CosmosMsg::Wasm(wasm_msg))
     }
 
-    pub fn provide_liquidity_msg(
+    fn withdraw_liquidity_msg(
+        &self,
+        lp_token: String,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+        if is_token_factory_denom(&lp_token) {
+            return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.0.to_string(),
+                msg: to_binary(&ExecuteMsg::WithdrawLiquidity { assets: vec![] })?,
+                funds: vec![Coin { denom: lp_token, amount }],
+            }));
+        }
+
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lp_token,
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.0.to_string(),
+                amount,
+                msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+            })?,
+            funds: vec![],
+        }))
+    }
+
+    fn provide_liquidity_msg(
         &self,
         assets: Vec<Asset>,
         slippage_tolerance: Option<Decimal>,
@@ -1,6 +1,6 @@
 use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
+    to_binary, Addr, Coin, CosmosMsg, CustomQuery, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
     WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
@@ -13,6 +13,7 @@ pub enum RouterType {
     AstroSwap,
     TerraSwap,
     TokenSwap,
+    WhiteWhale,
 }
 
 impl RouterType {
@@ -39,6 +40,10 @@ impl RouterType {
                         offer_asset_info,
                         ask_asset_info,
                     },
+                    RouterType::WhiteWhale => SwapOperation::WhiteWhale {
+                        offer_asset_info,
+                        ask_asset_info,
+                    },
                 };
                 swap_operations.push(op);
                 previous = asset_info.clone();
@@ -65,6 +70,10 @@ pub enum SwapOperation {
         offer_asset_info: AssetInfo,
         ask_asset_info: AssetInfo,
     },
+    WhiteWhale {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
 }
 
 impl SwapOperation {
@@ -79,6 +88,26 @@ impl SwapOperation {
             SwapOperation::TokenSwap {
                 offer_asset_info, ..
             } => offer_asset_info.clone(),
+            SwapOperation::WhiteWhale {
+                offer_asset_info, ..
+            } => offer_asset_info.clone(),
+        }
+    }
+
+    pub fn get_ask_asset_info(&self) -> AssetInfo {
+        match self {
+            SwapOperation::AstroSwap {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
+            SwapOperation::TerraSwap {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
+            SwapOperation::TokenSwap {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
+            SwapOperation::WhiteWhale {
+                ask_asset_info, ..
+            } => ask_asset_info.clone(),
         }
     }
 }
@@ -123,9 +152,9 @@ pub struct SimulateSwapOperationsResponse {
 pub struct Router(pub Addr);
 
 impl Router {
-    pub fn simulate(
+    pub fn simulate<C: CustomQuery>(
         &self,
-        querier: &QuerierWrapper,
+        querier: &QuerierWrapper<C>,
         offer_amount: Uint128,
         operations: Vec<SwapOperation>,
     ) -> StdResult<SimulateSwapOperationsResponse> {
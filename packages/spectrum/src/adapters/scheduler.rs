@@ -0,0 +1,228 @@
+use cosmwasm_std::{
+    to_binary, to_vec, Addr, Api, Binary, Coin, CosmosMsg, StdError, StdResult, Uint128, Uint64, WasmMsg,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::farm_controller::TaskSchedule;
+
+/// This structure describes the CronCat manager messages used to manage a task.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CroncatExecuteMsg {
+    /// Creates a task funded with the attached funds
+    CreateTask { task: CroncatTaskRequest },
+    /// Adds the attached funds to the task balance
+    RefillTaskBalance { task_hash: String },
+    /// Removes the task and refunds its balance to the owner
+    RemoveTask { task_hash: String },
+}
+
+/// This structure describes a CronCat task.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CroncatTaskRequest {
+    pub interval: CroncatInterval,
+    pub boundary: Option<Binary>,
+    pub stop_on_fail: bool,
+    pub actions: Vec<CroncatAction>,
+    pub rules: Option<Vec<Binary>>,
+    pub cw20_coins: Vec<Binary>,
+}
+
+/// This enum describes how often a CronCat task is executed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CroncatInterval {
+    /// Every number of blocks
+    Block(u64),
+}
+
+/// This structure describes a message executed by a CronCat task.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CroncatAction {
+    pub msg: CosmosMsg,
+    pub gas_limit: Option<u64>,
+}
+
+/// This structure describes the Warp controller messages used to manage a job.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WarpExecuteMsg {
+    /// Creates a job, the reward is paid from the attached funds
+    CreateJob(WarpCreateJobMsg),
+    /// Adds the attached funds to the job reward
+    UpdateJob(WarpUpdateJobMsg),
+    /// Deletes the job
+    DeleteJob(WarpDeleteJobMsg),
+}
+
+/// This structure describes a Warp job, the condition, messages and variables are JSON strings.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WarpCreateJobMsg {
+    pub name: String,
+    pub description: String,
+    pub labels: Vec<String>,
+    pub condition: String,
+    pub msgs: String,
+    pub vars: String,
+    pub recurring: bool,
+    pub requeue_on_evict: bool,
+    pub reward: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WarpUpdateJobMsg {
+    pub id: Uint64,
+    pub added_reward: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WarpDeleteJobMsg {
+    pub id: Uint64,
+}
+
+/// The on-chain scheduler executing a recurring message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Scheduler {
+    /// A CronCat task executed every number of blocks
+    Croncat {
+        /// The CronCat manager contract, which executes the task
+        manager: Addr,
+        /// The number of blocks between executions
+        interval: u64,
+        /// The gas limit of the executed message
+        gas_limit: Option<u64>,
+    },
+    /// A recurring Warp job
+    Warp {
+        /// The Warp controller contract
+        controller: Addr,
+        /// The Warp account of the job owner, which executes the job
+        account: Addr,
+        /// The condition of the job, a Warp JSON expression
+        condition: String,
+        /// The variables of the condition, a Warp JSON expression
+        vars: String,
+        /// The reward paid to the keeper on each execution
+        reward: Uint128,
+    },
+}
+
+impl Scheduler {
+    /// Validates the schedule
+    pub fn from_schedule(api: &dyn Api, schedule: TaskSchedule) -> StdResult<Self> {
+        match schedule {
+            TaskSchedule::Croncat { manager, interval, gas_limit } => {
+                if interval == 0 {
+                    return Err(StdError::generic_err("interval must be greater than 0"));
+                }
+                Ok(Scheduler::Croncat { manager: api.addr_validate(&manager)?, interval, gas_limit })
+            }
+            TaskSchedule::Warp { controller, account, condition, vars, reward } => Ok(Scheduler::Warp {
+                controller: api.addr_validate(&controller)?,
+                account: api.addr_validate(&account)?,
+                condition,
+                vars,
+                reward,
+            }),
+        }
+    }
+
+    /// Returns the address executing the scheduled message
+    pub fn executor(&self) -> &Addr {
+        match self {
+            Scheduler::Croncat { manager, .. } => manager,
+            Scheduler::Warp { account, .. } => account,
+        }
+    }
+
+    /// Returns the event attribute holding the id of a created task
+    pub fn task_id_key(&self) -> &'static str {
+        match self {
+            Scheduler::Croncat { .. } => "task_hash",
+            Scheduler::Warp { .. } => "job_id",
+        }
+    }
+
+    /// Generate msg for creating a task executing the message, funded with the funds
+    pub fn create_task_msg(&self, name: &str, msg: CosmosMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        match self {
+            Scheduler::Croncat { manager, interval, gas_limit } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: manager.to_string(),
+                msg: to_binary(&CroncatExecuteMsg::CreateTask {
+                    task: CroncatTaskRequest {
+                        interval: CroncatInterval::Block(*interval),
+                        boundary: None,
+                        stop_on_fail: false,
+                        actions: vec![CroncatAction { msg, gas_limit: *gas_limit }],
+                        rules: None,
+                        cw20_coins: vec![],
+                    },
+                })?,
+                funds,
+            })),
+            Scheduler::Warp { controller, condition, vars, reward, .. } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: controller.to_string(),
+                msg: to_binary(&WarpExecuteMsg::CreateJob(WarpCreateJobMsg {
+                    name: name.to_string(),
+                    description: name.to_string(),
+                    labels: vec![],
+                    condition: condition.clone(),
+                    msgs: String::from_utf8(to_vec(&vec![msg])?)
+                        .map_err(|err| StdError::invalid_utf8(err.to_string()))?,
+                    vars: vars.clone(),
+                    recurring: true,
+                    requeue_on_evict: true,
+                    reward: *reward,
+                }))?,
+                funds,
+            })),
+        }
+    }
+
+    /// Generate msg for adding the funds to the task balance
+    pub fn fund_task_msg(&self, task_id: &str, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        match self {
+            Scheduler::Croncat { manager, .. } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: manager.to_string(),
+                msg: to_binary(&CroncatExecuteMsg::RefillTaskBalance { task_hash: task_id.to_string() })?,
+                funds,
+            })),
+            Scheduler::Warp { controller, .. } => {
+                let added_reward = funds.iter().map(|coin| coin.amount).sum();
+                Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: controller.to_string(),
+                    msg: to_binary(&WarpExecuteMsg::UpdateJob(WarpUpdateJobMsg {
+                        id: parse_job_id(task_id)?,
+                        added_reward: Some(added_reward),
+                    }))?,
+                    funds,
+                }))
+            }
+        }
+    }
+
+    /// Generate msg for removing the task
+    pub fn remove_task_msg(&self, task_id: &str) -> StdResult<CosmosMsg> {
+        match self {
+            Scheduler::Croncat { manager, .. } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: manager.to_string(),
+                msg: to_binary(&CroncatExecuteMsg::RemoveTask { task_hash: task_id.to_string() })?,
+                funds: vec![],
+            })),
+            Scheduler::Warp { controller, .. } => Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: controller.to_string(),
+                msg: to_binary(&WarpExecuteMsg::DeleteJob(WarpDeleteJobMsg { id: parse_job_id(task_id)? }))?,
+                funds: vec![],
+            })),
+        }
+    }
+}
+
+fn parse_job_id(task_id: &str) -> StdResult<Uint64> {
+    task_id
+        .parse::<u64>()
+        .map(Uint64::from)
+        .map_err(|_| StdError::generic_err(format!("Invalid Warp job id {}", task_id)))
+}
@@ -0,0 +1,99 @@
+use astroport::asset::{is_token_factory_denom, Asset};
+use cosmwasm_std::{to_binary, Addr, Coin, CosmosMsg, CustomQuery, QuerierWrapper, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the execute messages of a staking adapter. A staking adapter stakes one
+/// LP token in a third-party staking contract on behalf of each sender, and pays the rewards claimed
+/// there to the sender.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Stakes the LP token sent as funds for the sender
+    Stake {},
+    /// Unstakes the LP token of the sender and sends it to the sender
+    Unstake { amount: Uint128 },
+    /// Claims the pending rewards of the sender and sends them to the sender
+    ClaimRewards {},
+}
+
+/// This structure describes the cw20 hook messages of a staking adapter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Stakes the cw20 LP token for the sender of the token
+    Stake {},
+}
+
+/// This structure describes the queries of a staking adapter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the LP token amount staked by the staker
+    StakedBalance { staker: String },
+    /// Returns the pending rewards of the staker, all reward tokens in one list
+    PendingRewards { staker: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakingAdapter(pub Addr);
+
+impl StakingAdapter {
+    pub fn query_pending_rewards<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        staker: &Addr,
+    ) -> StdResult<Vec<Asset>> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::PendingRewards {
+            staker: staker.to_string(),
+        })
+    }
+
+    pub fn query_staked_balance<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        staker: &Addr,
+    ) -> StdResult<Uint128> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::StakedBalance {
+            staker: staker.to_string(),
+        })
+    }
+
+    /// Stakes the LP token, a x/tokenfactory LP token is sent as funds
+    pub fn stake_msg(&self, lp_token: String, amount: Uint128) -> StdResult<CosmosMsg> {
+        if is_token_factory_denom(&lp_token) {
+            return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: self.0.to_string(),
+                funds: vec![Coin { denom: lp_token, amount }],
+                msg: to_binary(&ExecuteMsg::Stake {})?,
+            }));
+        }
+
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: lp_token,
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.0.to_string(),
+                amount,
+                msg: to_binary(&Cw20HookMsg::Stake {})?,
+            })?,
+        }))
+    }
+
+    pub fn unstake_msg(&self, amount: Uint128) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::Unstake { amount })?,
+        }))
+    }
+
+    pub fn claim_rewards_msg(&self) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            funds: vec![],
+            msg: to_binary(&ExecuteMsg::ClaimRewards {})?,
+        }))
+    }
+}
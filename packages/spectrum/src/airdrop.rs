@@ -0,0 +1,110 @@
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to register stages and claw back unclaimed SPEC
+    pub owner: String,
+    /// The SPEC token contract address
+    pub spec_token: String,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Claims the SPEC airdropped to the sender in the stage
+    Claim {
+        /// The stage id
+        stage: u64,
+        /// The SPEC amount in the merkle tree leaf of the sender
+        amount: Uint128,
+        /// The hex encoded sibling hashes from the leaf to the merkle root
+        proof: Vec<String>,
+    },
+    /// Sends the SPEC not claimed before the stage expiration to the owner. Only the owner can
+    /// execute this
+    Clawback {
+        /// The stage id
+        stage: u64,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the custom hooks for the CW20.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Registers a stage airdropping the sent SPEC, only the owner can send this
+    RegisterStage {
+        /// The hex encoded sha256 merkle root. Each leaf is the sha256 of the address followed
+        /// by the amount, and each pair of hashes is sorted before hashing
+        merkle_root: String,
+        /// The block time in seconds the stage expires
+        expiration: u64,
+    },
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the stage
+    Stage {
+        /// The stage id
+        stage: u64,
+    },
+    /// Returns whether the address has claimed in the stage
+    IsClaimed {
+        /// The stage id
+        stage: u64,
+        /// The address
+        address: String,
+    },
+}
+
+/// This structure holds the parameters that are returned from the stage query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StageResponse {
+    /// The stage id
+    pub stage: u64,
+    /// The hex encoded merkle root
+    pub merkle_root: String,
+    /// The block time in seconds the stage expires
+    pub expiration: u64,
+    /// The SPEC airdropped in the stage
+    pub total_amount: Uint128,
+    /// The SPEC claimed in the stage
+    pub claimed_amount: Uint128,
+    /// Whether the unclaimed SPEC was sent back to the owner
+    pub clawed_back: bool,
+}
+
+/// This structure holds the parameters that are returned from the is claimed query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsClaimedResponse {
+    /// Whether the address has claimed in the stage
+    pub is_claimed: bool,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
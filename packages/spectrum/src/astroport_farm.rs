@@ -1,9 +1,11 @@
-use astroport::asset::Asset;
+use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg, StdError, Binary};
 use cw20::{Cw20ReceiveMsg, Expiration, Logo};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::oracle::PriceGuard;
+
 /// This structure describes the parameters for creating a contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -11,6 +13,8 @@ pub struct InstantiateMsg {
     pub owner: String,
     /// The LP staking generator contract address
     pub staking_contract: String,
+    /// The interface of the staking contract (default generator)
+    pub staking_interface: Option<StakingInterface>,
     /// The compound proxy contract address
     pub compound_proxy: String,
     /// The controller address to execute compound
@@ -25,18 +29,47 @@ pub struct InstantiateMsg {
     pub base_reward_token: String,
     /// The pair contract address
     pub pair: String,
+    /// The address allowed to pause bond and compound
+    pub pauser: Option<String>,
+    /// The max LP amount bonded in the farm (default 0, unlimited)
+    pub deposit_cap: Option<Uint128>,
+    /// The number of seconds unbonded LP token is locked before it can be claimed (default 0, instant)
+    pub unbonding_period: Option<u64>,
+    /// The min LP amount simulated from pending rewards to compound (default 0, always compound)
+    pub min_reward_value: Option<Uint128>,
 
     /// token info
     pub name: String,
     pub symbol: String,
 }
 
+/// This enum describes the interface of the LP staking contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingInterface {
+    /// The Astroport generator
+    #[default]
+    Generator,
+    /// The Astroport incentives contract, which replaces the generator
+    Incentives,
+    /// A staking adapter of a third-party staking contract, see [`crate::adapters::staking`]
+    Adapter,
+}
+
 /// This structure describes the execute messages available in the contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     /// Receives a message of type [`Cw20ReceiveMsg`]
     Receive(Cw20ReceiveMsg),
+    /// Bond the LP token sent as funds, for LP tokens which are x/tokenfactory denoms
+    Bond {
+        /// The staker to bond on behalf of, the sender if not set
+        staker_addr: Option<String>,
+        /// The referrer of the staker, only set by the first own bond of the staker with a referrer
+        referrer: Option<String>,
+    },
     /// Update contract config
     UpdateConfig {
         /// The compound proxy contract address
@@ -47,19 +80,119 @@ pub enum ExecuteMsg {
         fee: Option<Decimal>,
         /// The fee collector contract address
         fee_collector: Option<String>,
+        /// The address allowed to pause bond and compound
+        pauser: Option<String>,
+        /// The max LP amount bonded in the farm, 0 is unlimited
+        deposit_cap: Option<Uint128>,
+        /// The number of seconds unbonded LP token is locked before it can be claimed, 0 is instant
+        unbonding_period: Option<u64>,
+        /// The min LP amount simulated from pending rewards to compound, 0 always compounds
+        min_reward_value: Option<Uint128>,
+        /// The legacy farm contract allowed to migrate stakes with the MigrateStake hook
+        legacy_farm: Option<String>,
+        /// The boost of compounded yield for SPEC stakers. Stakers share the boosted yield once
+        /// their working share is set by bond, unbond, transfer or UpdateBoost
+        boost: Option<Boost>,
+        /// The share of compounded LP token in bps kept in the reserve to cover shortfalls
+        reserve_bps: Option<u64>,
+        /// The guard that aborts compounds while the spot price of the pair deviates from its
+        /// TWAP and values deposits at TWAP anchored reserves, a max deviation of 0 disables it
+        price_guard: Option<PriceGuard>,
+        /// The number of seconds after the last own bond of a staker before they can unbond or
+        /// transfer shares, 0 disables it
+        bond_cooldown: Option<u64>,
+        /// The share in bps of the performance fee on referred shares paid to the referrers
+        referral_bps: Option<u64>,
     },
+    /// Halts bond and compound, unbond stays available. Only the owner and pauser can execute this
+    Pause {},
+    /// Resumes bond and compound. Only the owner and pauser can execute this
+    Unpause {},
     /// Unbond LP token
     Unbond {
         /// The LP amount to unbond
         amount: Uint128,
+        /// The pool asset to receive instead of LP token, the LP token is withdrawn from the pair
+        /// and the other pool asset is swapped into it
+        as_asset: Option<AssetInfo>,
+        /// The minimum expected amount of the pool asset
+        minimum_receive: Option<Uint128>,
+        /// Slippage tolerance when swapping into the pool asset, overrides the default of compound proxy
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Unbond all LP token of the sender straight from the staking contract, without any swap, compound or unbonding period
+    EmergencyUnbond {},
+    /// Withdraw LP token of the sender whose unbonding period has elapsed
+    Claim {},
+    /// Sets the share of the future deposits of the sender bonded in the auto-stake bucket, which
+    /// earns the rewards instead of compounding
+    UpdateAutoStakeRatio {
+        /// The share of deposits to auto-stake, 0 to 1
+        auto_stake_ratio: Decimal,
+    },
+    /// Withdraw the rewards earned by the auto-stake bucket of the sender
+    ClaimStakeReward {},
+    /// Withdraw the referral fees credited to the sender as referrer. The fees of a referred
+    /// staker are credited on their next bond, unbond or transfer, or by UpdateBoost
+    ClaimReferral {
+        /// The stakers referred by the sender to settle before claiming
+        stakers: Option<Vec<String>>,
     },
-    /// Compound LP rewards
+    /// Withdraws LP token from the reserve. Only the owner can execute this
+    WithdrawReserve {
+        /// The LP amount to withdraw
+        amount: Uint128,
+        /// The recipient of the LP token, the owner if not set
+        recipient: Option<String>,
+    },
+    /// Refreshes the boost of the staker from their staked SPEC weight. Anyone can execute this
+    UpdateBoost {
+        /// The staker address
+        staker_addr: String,
+    },
+    /// Compound LP rewards, skipped while the rewards are worth less than the min reward value
     Compound {
         /// The minimum expected amount of LP token
         minimum_receive: Option<Uint128>,
         /// Slippage tolerance when providing LP
         slippage_tolerance: Option<Decimal>,
     },
+    /// Claims the pending rewards into the farm without compounding, the claimed rewards are added
+    /// to the next compound. Works while paused. Only the owner, controller and pauser can execute this
+    Harvest {},
+    /// Allows an address to compound in addition to the controller. Only the owner can execute this
+    AddKeeper {
+        /// The keeper address
+        keeper: String,
+    },
+    /// Removes an address from the keepers. Only the owner can execute this
+    RemoveKeeper {
+        /// The keeper address
+        keeper: String,
+    },
+    /// Adds the balances of proxy reward tokens paid directly to the farm, outside of a claim, to
+    /// the next compound. Only the owner and controller can execute this
+    SweepRewards {
+        /// The proxy reward tokens to sweep, must be reward tokens of the staking contract other
+        /// than the base reward token
+        assets: Vec<AssetInfo>,
+    },
+    /// Instantiates the cw20 receipt token of the farm shares, with the farm as minter. Only the
+    /// owner can execute this, once
+    CreateReceiptToken {
+        /// The code id of the cw20 token contract
+        code_id: u64,
+        /// The receipt token name
+        name: String,
+        /// The receipt token symbol
+        symbol: String,
+    },
+    /// Escrows the shares of the sender in the farm and mints the same amount of receipt token to
+    /// the sender. The receipt token is redeemed for LP token with the RedeemReceipt hook
+    MintReceipt {
+        /// The amount of shares to escrow
+        amount: Uint128,
+    },
     /// Bond asset with optimal swap
     BondAssets {
         /// The list of asset to bond
@@ -70,6 +203,21 @@ pub enum ExecuteMsg {
         no_swap: Option<bool>,
         /// Slippage tolerance when providing LP
         slippage_tolerance: Option<Decimal>,
+        /// The address credited with the bonded LP token, the sender if not set
+        staker_addr: Option<String>,
+        /// The referrer of the staker, only set by the first own bond of the staker with a referrer
+        referrer: Option<String>,
+    },
+    /// Bond the single coin sent with an IBC transfer, called by the ibc-hooks module with the
+    /// memo `{"wasm":{"contract":"<farm>","msg":{"ibc_hook_deposit":{..}}}}`. The LP token is
+    /// bonded directly, any other denom is converted to LP token by the compound proxy
+    IbcHookDeposit {
+        /// The farm the deposit is intended for, must be this contract
+        farm: String,
+        /// The minimum expected amount of LP token
+        min_lp: Option<Uint128>,
+        /// The address on this chain credited with the bonded LP token
+        receiver: String,
     },
     /// Creates a request to change the contract's ownership
     ProposeNewOwner {
@@ -161,10 +309,26 @@ pub enum CallbackMsg {
     BondTo {
         /// The address to bond LP
         to: Addr,
+        /// The address bonding the assets
+        sender: Addr,
         /// The previous LP balance in the contract
         prev_balance: Uint128,
         /// The minimum expected amount of LP token
         minimum_receive: Option<Uint128>,
+        /// The referrer of the staker
+        referrer: Option<Addr>,
+    },
+    ZapOut {
+        /// The address to receive the asset
+        to: Addr,
+        /// The pool asset to receive
+        ask_asset_info: AssetInfo,
+        /// The previous balances of the pool assets in the contract
+        prev_balances: Vec<Asset>,
+        /// The minimum expected amount of the pool asset
+        minimum_receive: Option<Uint128>,
+        /// Slippage tolerance when swapping into the pool asset
+        slippage_tolerance: Option<Decimal>,
     },
 }
 
@@ -184,8 +348,28 @@ impl CallbackMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    // Bond LP token
-    Bond { staker_addr: Option<String> },
+    /// Bond LP token sent with cw20 Send, on behalf of the staker address or the sender if not set
+    Bond {
+        staker_addr: Option<String>,
+        /// The referrer of the staker, only set by the first own bond of the staker with a referrer
+        referrer: Option<String>,
+    },
+    /// Bond LP token unbonded from the legacy farm on behalf of the staker, only the legacy farm can send this
+    MigrateStake { staker_addr: String },
+    /// Bond a single cw20 asset, the asset is converted to LP token by the compound proxy
+    BondAsset {
+        /// The minimum expected amount of LP token
+        minimum_receive: Option<Uint128>,
+        /// Slippage tolerance when providing LP
+        slippage_tolerance: Option<Decimal>,
+        /// The address credited with the bonded LP token, the sender if not set
+        staker_addr: Option<String>,
+        /// The referrer of the staker, only set by the first own bond of the staker with a referrer
+        referrer: Option<String>,
+    },
+    /// Burns the receipt token sent and redeems the escrowed shares for LP token to the sender,
+    /// only the receipt token can send this
+    RedeemReceipt {},
 }
 
 /// This structure describes query messages available in the contract.
@@ -196,8 +380,38 @@ pub enum QueryMsg {
     Config {},
     /// Returns the deposited balances
     RewardInfo { staker_addr: String },
-    /// Returns the global state
+    /// Returns the bonded LP token, shares, share price and compound history of the farm
     State {},
+    /// Returns the shares of the staker, their underlying LP amount and the share price
+    UserInfo { staker_addr: String },
+    /// Returns the auto-stake bucket of the staker and its pending rewards
+    StakeInfo { staker_addr: String },
+    /// Returns the referrer of the address and the referral fees claimable by the address
+    ReferralInfo { address: String },
+    /// Returns whether bond and compound are paused
+    Paused {},
+    /// Returns the keepers allowed to compound in addition to the controller
+    Keepers {},
+    /// Returns the unbonded LP token of the staker waiting for the unbonding period
+    Claims {
+        staker_addr: String,
+        /// The release time to start reading from
+        start_after: Option<u64>,
+        /// The number of claims to read
+        limit: Option<u32>,
+    },
+    /// Returns the LP token per share and the LP token managed by the farm, the vault query shared
+    /// by all vaults in [`crate::vault`]
+    ExchangeRate {},
+    /// Returns the rewards claimed by harvest waiting for the next compound
+    Harvested {},
+    /// Returns the share price recorded at each compound, oldest first
+    SharePriceHistory {
+        /// The block time in seconds to start reading from
+        start_after: Option<u64>,
+        /// The number of checkpoints to read
+        limit: Option<u32>,
+    },
 
     /// cw20
     /// Returns the current balance of the given address, 0 if unset.
@@ -267,6 +481,42 @@ pub struct RewardInfoResponseItem {
     pub deposit_costs: Vec<Uint128>,
 }
 
+/// This structure holds the parameters for state query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    /// The LP token amount bonded in the farm
+    pub total_bond_amount: Uint128,
+    /// The total shares
+    pub total_bond_share: Uint128,
+    /// The LP token amount per share
+    pub share_price: Decimal,
+    /// The LP token amount bonded in the auto-stake bucket, excluded from the share price
+    pub total_stake_bond_amount: Uint128,
+    /// The shares held by the reserve
+    pub reserve_share: Uint128,
+    /// The LP token amount backing the reserve shares
+    pub reserve_amount: Uint128,
+    /// The block time in seconds of the last compound
+    pub last_compound_time: u64,
+    /// The block height of the last compound
+    pub last_compound_height: u64,
+    /// The cumulative LP token amount bonded from compound
+    pub total_compounded: Uint128,
+}
+
+/// This structure holds the parameters for user info query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserInfoResponse {
+    /// The staker address
+    pub staker_addr: String,
+    /// The shares owned by the staker
+    pub shares: Uint128,
+    /// The LP token amount backing the shares
+    pub lp_amount: Uint128,
+    /// The LP token amount per share
+    pub share_price: Decimal,
+}
+
 /// This structure describes a migration message.
 /// We currently take no arguments for migrations
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -329,4 +579,69 @@ impl MigrateMsg {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// This structure holds the parameters for stake info query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakeInfoResponse {
+    /// The staker address
+    pub staker_addr: String,
+    /// The share of deposits bonded in the auto-stake bucket
+    pub auto_stake_ratio: Decimal,
+    /// The LP token amount bonded in the auto-stake bucket
+    pub stake_bond_amount: Uint128,
+    /// The rewards earned and not claimed
+    pub pending_rewards: Vec<Asset>,
+}
+
+/// This structure holds the parameters for referral info query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralInfoResponse {
+    /// The referrer of the address as a staker
+    pub referrer: Option<String>,
+    /// The referral fees credited to the address as referrer and not claimed
+    pub pending_fees: Vec<Asset>,
+}
+
+/// This structure holds the parameters for claims query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimsResponse {
+    /// The list of claims
+    pub claims: Vec<ClaimInfo>,
+}
+
+/// This structure holds the detail of unbonded LP token waiting for the unbonding period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimInfo {
+    /// The LP token amount
+    pub amount: Uint128,
+    /// The block time in seconds after which the LP token can be claimed
+    pub release_at: u64,
+}
+
+/// This structure holds the parameters for share price history query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharePriceHistoryResponse {
+    /// The list of checkpoints
+    pub checkpoints: Vec<SharePriceCheckpoint>,
+}
+
+/// This structure holds the share price after a compound
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SharePriceCheckpoint {
+    /// The block time in seconds of the compound
+    pub time: u64,
+    /// The LP token amount per share
+    pub share_price: Decimal,
+}
+
+/// This structure describes the boost of compounded yield for SPEC stakers. The compounded yield is
+/// shared by the working share of each staker, `min(share, base_ratio * share + (1 - base_ratio) *
+/// total_share * weight / total_weight)`, where the weight is the staked SPEC of the staker.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Boost {
+    /// The SPEC governance staking contract address
+    pub gov: String,
+    /// The share of the working share given without staked SPEC, 1 disables the boost
+    pub base_ratio: Decimal,
+}
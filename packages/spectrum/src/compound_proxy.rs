@@ -2,27 +2,34 @@ use schemars::{JsonSchema};
 use serde::{Deserialize, Serialize};
 
 use astroport::asset::{Asset, AssetInfo};
+use astroport::factory::PairType;
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg, Decimal, Uint128, Coin};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, CustomQuery, QuerierWrapper, StdResult, WasmMsg, Decimal, Uint128, Coin};
+
+use crate::oracle::PriceGuard;
 
 /// This structure describes the basic settings for creating a contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
+    /// The owner address, allowed to rescue assets stranded in the contract and set the commission tiers
+    pub owner: String,
     /// The pair contract address
     pub pair_contract: String,
-    /// The swap commission
-    pub commission_bps: u64,
+    /// The factory contract address, used to query the live pool commission
+    pub factory: String,
     /// The list of pair proxy to swap reward token to the asset in the pair
     pub pair_proxies: Vec<(AssetInfo, String)>,
     /// The slippage tolerance when swapping
     pub slippage_tolerance: Decimal,
+    /// The guard that aborts compounds while the spot price of the pair deviates from its TWAP
+    pub price_guard: Option<PriceGuard>,
 }
 
 /// This structure describes the execute messages of the contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
-    /// Compound rewards to LP token
+    /// Compound rewards to LP token, native funds sent in excess of the rewards are refunded to the sender
     Compound {
         /// List of reward asset send to compound
         rewards: Vec<Asset>,
@@ -30,9 +37,53 @@ pub enum ExecuteMsg {
         to: Option<String>,
         /// Skip optimal swap
         no_swap: Option<bool>, 
-        /// slippage tolerance when providing LP
+        /// Slippage tolerance when providing LP, overrides the configured default for this call only
+        slippage_tolerance: Option<Decimal>,
+        /// The source of the rewards, its cumulative rewards and LP token are recorded
+        source: Option<String>,
+    },
+    /// Compound native rewards to LP token without any cw20 allowance. The sent funds must match
+    /// the listed rewards exactly, so no coin is left in the contract
+    CompoundNative {
+        /// List of native reward asset send to compound
+        rewards: Vec<Asset>,
+        /// Receiver address for LP token
+        to: Option<String>,
+        /// Skip optimal swap
+        no_swap: Option<bool>,
+        /// Slippage tolerance when providing LP, overrides the configured default for this call only
+        slippage_tolerance: Option<Decimal>,
+        /// The source of the rewards, its cumulative rewards and LP token are recorded
+        source: Option<String>,
+    },
+    /// Swaps the rewards to one asset of the pair without providing liquidity. Rewards are routed
+    /// through the registered pair proxies like compound, native funds sent in excess are refunded
+    SwapToAsset {
+        /// List of reward asset send to swap
+        rewards: Vec<Asset>,
+        /// The asset to receive, must be one of the assets in the pair
+        target: AssetInfo,
+        /// The minimum amount of the target asset to receive
+        min_receive: Option<Uint128>,
+        /// Receiver address for the target asset
+        to: Option<String>,
+        /// Slippage tolerance when swapping, overrides the configured default for this call only
         slippage_tolerance: Option<Decimal>,
     },
+    /// Sends the whole balance of an asset stranded in the contract to the recipient. Only the
+    /// owner can execute this, and not while a compound is in progress
+    Rescue {
+        /// The asset to rescue
+        asset: AssetInfo,
+        /// The recipient of the asset
+        to: String,
+    },
+    /// Replaces the commission used by the optimal swap of each pair type, pair types without one
+    /// use the live commission of the factory. Only the owner can execute this
+    UpdateCommissionTiers {
+        /// The commission in bps of each pair type, must be 0 to 9999
+        commission_tiers: Vec<(PairType, u64)>,
+    },
     /// The callback of type [`CallbackMsg`]
     Callback(CallbackMsg),
 }
@@ -42,12 +93,35 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum CallbackMsg {
     /// Performs optimal swap
-    OptimalSwap {},
+    OptimalSwap {
+        slippage_tolerance: Option<Decimal>,
+    },
     /// Provides liquidity to the pair contract
     ProvideLiquidity {
         prev_balances: Vec<Asset>,
         receiver: String,
         slippage_tolerance: Option<Decimal>,
+        source: Option<String>,
+    },
+    /// Swaps the other asset of the pair received from the rewards to the target asset
+    SwapToTarget {
+        prev_balances: Vec<Asset>,
+        target: AssetInfo,
+        min_receive: Option<Uint128>,
+        receiver: String,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Sends the target asset received from the swaps to the receiver
+    SendTarget {
+        prev_balance: Asset,
+        min_receive: Option<Uint128>,
+        receiver: String,
+    },
+    /// Reports the LP token received from the compound and records it to the source, if any
+    RecordSource {
+        source: Option<String>,
+        receiver: String,
+        prev_lp_balance: Uint128,
     },
 }
 
@@ -73,6 +147,11 @@ pub enum QueryMsg {
     CompoundSimulation {
         rewards: Vec<Asset>,
     },
+    /// Returns the cumulative rewards and LP token of each compound source
+    SourceStats {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 /// This structure holds the parameters that are returned from a compound simulation response
@@ -90,26 +169,73 @@ pub struct CompoundSimulationResponse {
     pub return_b_amount: Uint128,
 }
 
+/// This structure holds the cumulative rewards and LP token of a compound source
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourceStats {
+    /// The source tag sent with compound
+    pub source: String,
+    /// The total rewards compounded
+    pub rewards: Vec<Asset>,
+    /// The total LP token received
+    pub lp_amount: Uint128,
+}
+
+/// This structure holds the parameters that are returned from a source stats query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SourceStatsResponse {
+    pub sources: Vec<SourceStats>,
+}
+
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The owner address, sets the owner of contracts instantiated without one
+    pub owner: Option<String>,
+    /// The factory contract address, required to migrate contracts storing the legacy config
+    pub factory: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Compounder(pub Addr);
 
 impl Compounder {
-    pub fn compound_msg(&self, rewards: Vec<Asset>, mut funds: Vec<Coin>, no_swap: Option<bool>, slippage_tolerance: Option<Decimal>) -> StdResult<CosmosMsg> {
+    pub fn compound_msg(&self, rewards: Vec<Asset>, mut funds: Vec<Coin>, no_swap: Option<bool>, slippage_tolerance: Option<Decimal>, to: Option<String>) -> StdResult<CosmosMsg> {
         funds.sort_by(|a, b| a.denom.cmp(&b.denom));
         Ok(CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: self.0.to_string(),
             msg: to_binary(&ExecuteMsg::Compound {
                 rewards,
                 no_swap,
-                to: None,
+                to,
+                slippage_tolerance,
+                source: None,
+            })?,
+            funds,
+        }))
+    }
+
+    pub fn swap_to_asset_msg(&self, rewards: Vec<Asset>, mut funds: Vec<Coin>, target: AssetInfo, min_receive: Option<Uint128>, slippage_tolerance: Option<Decimal>, to: Option<String>) -> StdResult<CosmosMsg> {
+        funds.sort_by(|a, b| a.denom.cmp(&b.denom));
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: self.0.to_string(),
+            msg: to_binary(&ExecuteMsg::SwapToAsset {
+                rewards,
+                target,
+                min_receive,
+                to,
                 slippage_tolerance,
             })?,
             funds,
         }))
     }
+
+    pub fn query_compound_simulation<C: CustomQuery>(
+        &self,
+        querier: &QuerierWrapper<C>,
+        rewards: Vec<Asset>,
+    ) -> StdResult<CompoundSimulationResponse> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::CompoundSimulation {
+            rewards,
+        })
+    }
 }
@@ -0,0 +1,60 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Event, Uint128};
+
+/// The event type of the compound report, emitted by the farm, compound proxy and fee collector
+pub const COMPOUND_EVENT: &str = "spectrum/compound";
+
+/// The step of the compound flow reporting the event
+pub const STAGE: &str = "stage";
+/// The reward token
+pub const REWARD_ASSET: &str = "reward_asset";
+/// The reward token amount
+pub const REWARD_AMOUNT: &str = "reward_amount";
+/// The token charged as fee
+pub const FEE_ASSET: &str = "fee_asset";
+/// The amount charged as fee
+pub const FEE_AMOUNT: &str = "fee_amount";
+/// The LP token amount received from compound
+pub const LP_MINTED: &str = "lp_minted";
+/// The receiver of the compounded LP token
+pub const RECEIVER: &str = "receiver";
+
+/// The farm claimed a reward token from the staking contract and charged the performance fee
+pub const STAGE_HARVEST: &str = "harvest";
+/// The compound proxy received a reward token to compound
+pub const STAGE_COMPOUND: &str = "compound";
+/// The farm staked the LP token received from compound
+pub const STAGE_STAKE: &str = "stake";
+/// The fee collector distributed the collected fees
+pub const STAGE_DISTRIBUTE: &str = "distribute";
+
+/// This structure builds the compound report event, so every contract emits the same schema.
+pub struct CompoundReport(Event);
+
+impl CompoundReport {
+    pub fn new(stage: &str) -> Self {
+        CompoundReport(Event::new(COMPOUND_EVENT).add_attribute(STAGE, stage))
+    }
+
+    pub fn reward(self, reward: &Asset) -> Self {
+        CompoundReport(
+            self.0.add_attribute(REWARD_ASSET, reward.info.to_string()).add_attribute(REWARD_AMOUNT, reward.amount),
+        )
+    }
+
+    pub fn fee(self, info: &AssetInfo, amount: Uint128) -> Self {
+        CompoundReport(self.0.add_attribute(FEE_ASSET, info.to_string()).add_attribute(FEE_AMOUNT, amount))
+    }
+
+    pub fn lp_minted(self, amount: Uint128) -> Self {
+        CompoundReport(self.0.add_attribute(LP_MINTED, amount))
+    }
+
+    pub fn receiver(self, receiver: impl Into<String>) -> Self {
+        CompoundReport(self.0.add_attribute(RECEIVER, receiver))
+    }
+
+    pub fn into_event(self) -> Event {
+        self.0
+    }
+}
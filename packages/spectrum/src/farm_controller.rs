@@ -0,0 +1,166 @@
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to update config and the farm registry
+    pub owner: String,
+    /// Address that's allowed to compound the farms, in addition to the owner
+    pub operator: String,
+    /// The farm contract addresses to register
+    pub farms: Vec<String>,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Updates contract config
+    UpdateConfig {
+        /// The operator address
+        operator: Option<String>,
+    },
+    /// Adds farms to the registry. This contract must be set as the controller of each farm
+    AddFarms {
+        /// The farm contract addresses
+        farms: Vec<String>,
+    },
+    /// Removes farms from the registry
+    RemoveFarms {
+        /// The farm contract addresses
+        farms: Vec<String>,
+    },
+    /// Compounds registered farms in a round robin, resuming after the last farm compounded by the
+    /// previous call. Only the owner and operator can call this
+    CompoundAll {
+        /// The max number of farms to compound, all farms are compounded if not set
+        limit: Option<u32>,
+    },
+    /// Compounds the specified registered farms. Only the owner and operator can call this
+    CompoundFarms {
+        /// The farm contract addresses
+        farms: Vec<String>,
+    },
+    /// Compounds the registered farms that waited the longest since their last compound, weighted by
+    /// their priority, so a fixed gas budget still covers all farms over time. Farms compounded in
+    /// the current block are skipped. Only the owner and operator can call this
+    CompoundNext {
+        /// The max number of farms to compound
+        max_farms: u32,
+    },
+    /// Sets the priority of a registered farm, its blocks since the last compound are multiplied by
+    /// the priority when CompoundNext picks farms. Only the owner can call this
+    SetFarmPriority {
+        /// The farm contract address
+        farm: String,
+        /// The priority, farms are registered with priority 1
+        priority: u32,
+    },
+    /// Registers a recurring CompoundAll with an on-chain scheduler, funded with the sent funds.
+    /// The scheduler is allowed to compound. Only the owner can call this
+    ScheduleCompound {
+        /// The scheduler and the schedule of the task
+        schedule: TaskSchedule,
+        /// The max number of farms compounded by each execution, all farms if not set
+        limit: Option<u32>,
+    },
+    /// Adds the sent funds to the balance of the scheduled task. Anyone can call this
+    FundTask {},
+    /// Removes the scheduled task, the remaining balance is refunded to the owner. Only the owner
+    /// can call this
+    CancelTask {},
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This enum describes the on-chain scheduler executing the recurring compound.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSchedule {
+    /// A CronCat task executed every number of blocks
+    Croncat {
+        /// The CronCat manager contract
+        manager: String,
+        /// The number of blocks between executions
+        interval: u64,
+        /// The gas limit of each execution
+        gas_limit: Option<u64>,
+    },
+    /// A recurring Warp job
+    Warp {
+        /// The Warp controller contract
+        controller: String,
+        /// The Warp account of this contract, which executes the job
+        account: String,
+        /// The condition of the job, a Warp JSON expression
+        condition: String,
+        /// The variables of the condition, a Warp JSON expression
+        vars: String,
+        /// The reward paid to the keeper on each execution
+        reward: Uint128,
+    },
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the list of registered farms
+    Farms {
+        /// The farm address to start reading from
+        start_after: Option<String>,
+        /// The number of farms to read
+        limit: Option<u32>,
+    },
+    /// Returns the scheduled compound task, if any
+    ScheduledTask {},
+    /// Returns the priority and last compound height of the registered farms
+    FarmSchedules {
+        /// The farm address to start reading from
+        start_after: Option<String>,
+        /// The number of farms to read
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the parameters that are returned from the farms query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmsResponse {
+    /// The registered farm contract addresses
+    pub farms: Vec<String>,
+}
+
+/// This structure holds the compound schedule of a registered farm
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmScheduleInfo {
+    /// The farm contract address
+    pub farm: String,
+    /// The priority of the farm
+    pub priority: u32,
+    /// The block height the farm was last compounded, 0 if never compounded
+    pub last_compound_height: u64,
+}
+
+/// This structure holds the parameters that are returned from the farm schedules query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmSchedulesResponse {
+    pub farms: Vec<FarmScheduleInfo>,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
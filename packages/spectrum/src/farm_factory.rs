@@ -0,0 +1,124 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::Decimal;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::astroport_farm::StakingInterface;
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to create farms and update the template, normally the gov contract.
+    /// It is also the owner and admin of the created contracts
+    pub owner: String,
+    /// The parameters shared by the created farms
+    pub template: FarmTemplate,
+}
+
+/// This structure describes the parameters shared by the farms created by the factory.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmTemplate {
+    /// The code id of the farm contract
+    pub farm_code_id: u64,
+    /// The code id of the compound proxy contract
+    pub compound_proxy_code_id: u64,
+    /// The Astroport factory contract address, used by the compound proxy to query the pool commission
+    pub astroport_factory: String,
+    /// The LP staking contract address
+    pub staking_contract: String,
+    /// The interface of the staking contract (default generator)
+    pub staking_interface: Option<StakingInterface>,
+    /// The controller address to execute compound
+    pub controller: String,
+    /// The default performance fee
+    pub fee: Decimal,
+    /// The fee collector contract address
+    pub fee_collector: String,
+    /// The base reward token contract address
+    pub base_reward_token: String,
+    /// The slippage tolerance of the compound proxy when swapping
+    pub slippage_tolerance: Decimal,
+    /// The address allowed to pause bond and compound of the farms
+    pub pauser: Option<String>,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Replaces the template of the farms created afterwards. Only the owner can execute this
+    UpdateTemplate {
+        /// The parameters shared by the created farms
+        template: FarmTemplate,
+    },
+    /// Instantiates a compound proxy and a farm of the Astroport pair from the template, and adds
+    /// them to the registry. Only the owner can execute this
+    CreateFarm {
+        /// The pair contract address, only one farm is created per pair
+        pair_contract: String,
+        /// The name of the farm share
+        name: String,
+        /// The symbol of the farm share
+        symbol: String,
+        /// The performance fee, the template fee if not set
+        fee: Option<Decimal>,
+        /// The pair proxies of the compound proxy to swap reward tokens to the assets in the pair
+        pair_proxies: Option<Vec<(AssetInfo, String)>>,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the farm created for the pair
+    Farm {
+        /// The pair contract address
+        pair_contract: String,
+    },
+    /// Returns the created farms ordered by pair address
+    Farms {
+        /// The pair contract address to start after
+        start_after: Option<String>,
+        /// The max number of farms to return
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the contracts of a farm created by the factory
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmResponse {
+    /// The pair contract address
+    pub pair: String,
+    /// The LP token of the pair
+    pub liquidity_token: String,
+    /// The compound proxy contract address
+    pub compound_proxy: String,
+    /// The farm contract address
+    pub farm: String,
+}
+
+/// This structure holds the parameters that are returned from the farms query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FarmsResponse {
+    /// The created farms
+    pub farms: Vec<FarmResponse>,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -1,5 +1,7 @@
 use astroport::asset::{Asset, AssetInfo};
-use cosmwasm_std::{Uint128};
+use crate::adapters::router::RouterType;
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -14,30 +16,170 @@ pub struct InstantiateMsg {
     pub factory_contract: String,
     /// The stablecoin asset info
     pub stablecoin: AssetInfo,
-    /// The beneficiary addresses to received fees in stablecoin
+    /// The beneficiary addresses and their weights to receive a pro-rata share of fees in stablecoin
     pub target_list: Vec<(String, u64)>,
+    /// The share of collected stablecoin paid to the Collect caller, in basis points (default 0)
+    pub collect_incentive_bps: Option<u64>,
+    /// The minimum number of seconds between two distributions (default 0)
+    pub distribution_interval: Option<u64>,
+    /// The share of collected stablecoin burned on distribution, in basis points (default 0)
+    pub burn_bps: Option<u64>,
+    /// The remote beneficiaries paid over IBC, they share the fees with the target list by weight
+    pub ibc_target_list: Option<Vec<IbcTarget>>,
+    /// The number of seconds before an IBC transfer times out and is refunded (default 3600)
+    pub ibc_timeout: Option<u64>,
+    /// The staking contracts funded through their deposit hook and their weights to receive fees
+    pub staking_target_list: Option<Vec<(String, u64)>>,
+    /// The generator positions to claim rewards from on Collect
+    pub generator_rewards: Option<GeneratorRewards>,
+    /// The router used to swap fee tokens instead of the factory pairs
+    pub swap_router: Option<SwapRouter>,
+    /// The max share of the pool reserve of a fee token swapped per call, in basis points (default 0, unlimited)
+    pub max_swap_portion_bps: Option<u64>,
+    /// The number of seconds a config change is proposed before it can be applied (default 0, applied instantly)
+    pub config_delay: Option<u64>,
+    /// The address allowed to pause Collect
+    pub pauser: Option<String>,
+    /// The beneficiaries whose share is compounded to protocol-owned LP
+    pub lp_target_list: Option<Vec<LpTarget>>,
+}
+
+/// This structure describes the router that executes fee swaps along their bridge route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapRouter {
+    /// The router contract address
+    pub router: String,
+    /// The type of router
+    pub router_type: RouterType,
+}
+
+/// This structure describes the generator positions whose rewards are claimed and swapped on Collect.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GeneratorRewards {
+    /// The generator contract address
+    pub generator: String,
+    /// The ASTRO token address
+    pub astro_token: String,
+    /// The LP tokens staked in the generator by this contract
+    pub lp_tokens: Vec<String>,
+}
+
+/// This structure describes the deposit hook of a staking contract funded with fees. It is sent as
+/// the [`cw20::Cw20ReceiveMsg`] payload for cw20 stablecoin, or executed with funds for native stablecoin.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StakingHookMsg {
+    /// Distributes the deposited tokens to the stakers
+    Deposit {},
+}
+
+/// This structure describes the swap settings of a fee token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetConfig {
+    /// Information about the fee token
+    pub info: AssetInfo,
+    /// The max spread when swapping the fee token, the global default is used if not set
+    pub max_spread: Option<Decimal>,
+    /// The minimum contract balance to swap the fee token, smaller balances are skipped on Collect
+    pub min_amount: Option<Uint128>,
+    /// Redeems the fee token through its liquid staking hub before swapping
+    pub unwrap: Option<UnwrapConfig>,
+    /// The contract balance from which a cw20 deposit swaps the fee token to stablecoin right away,
+    /// deposits are kept until Collect if not set
+    #[serde(default)]
+    pub swap_threshold: Option<Uint128>,
+}
+
+/// This structure describes the redemption of a liquid staking derivative fee token (e.g. ampLUNA,
+/// stLUNA). The underlying asset returned by the hub is swapped next like a bridge asset.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnwrapConfig {
+    /// The liquid staking hub contract
+    pub hub: String,
+    /// The hub message redeeming the token instantly, sent as the cw20 `Send` hook of a cw20 token
+    /// or executed with the token as funds for a native token
+    pub msg: Binary,
+    /// The underlying asset returned by the hub
+    pub underlying: AssetInfo,
+}
+
+/// This structure describes a beneficiary on a remote chain paid with an IBC transfer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcTarget {
+    /// The IBC transfer channel on this chain
+    pub channel_id: String,
+    /// The receiver address on the remote chain
+    pub receiver: String,
+    /// The weight to receive fees
+    pub weight: u64,
+}
+
+/// This structure describes a beneficiary whose share is compounded to LP by a compound proxy, half of
+/// it buying back the other asset of the pair. The LP token is sent to the receiver, e.g. the treasury.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpTarget {
+    /// The compound proxy of the pair, the stablecoin must be one of the pair assets
+    pub compound_proxy: String,
+    /// The receiver of the LP token
+    pub receiver: String,
+    /// The weight to receive fees
+    pub weight: u64,
 }
 
 /// This structure describes the functions that can be executed in this contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
-    /// Collects and swaps fee tokens to stablecoin
+    /// Receives a cw20 fee token pushed with a hook message of type [`Cw20HookMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Claims generator rewards, collects and swaps fee tokens to stablecoin. Only the owner and operators
+    /// can call this, the caller receives the collect incentive
     Collect {
         /// The assets to swap to stablecoin
         assets: Vec<AssetWithLimit>,
         /// The minimum expected amount of stablecoine
         minimum_receive: Option<Uint128>,
     },
-    /// Updates contract config
+    /// Updates contract config, or proposes the change when a config delay is set
     UpdateConfig {
         /// The operator address
         operator: Option<String>,
         /// The factory contract address
         factory_contract: Option<String>,
-        /// The list of target address to receive fees in stablecoin
+        /// The list of target address and weight to receive fees in stablecoin
         target_list: Option<Vec<(String, u64)>>,
+        /// The share of collected stablecoin paid to the Collect caller, in basis points
+        collect_incentive_bps: Option<u64>,
+        /// The minimum number of seconds between two distributions
+        distribution_interval: Option<u64>,
+        /// The share of collected stablecoin burned on distribution, in basis points
+        burn_bps: Option<u64>,
+        /// The remote beneficiaries paid over IBC
+        ibc_target_list: Option<Vec<IbcTarget>>,
+        /// The number of seconds before an IBC transfer times out and is refunded
+        ibc_timeout: Option<u64>,
+        /// The new stablecoin, the remaining balance of the old stablecoin is swapped into it
+        stablecoin: Option<AssetInfo>,
+        /// The list of staking contract and weight to receive fees through their deposit hook
+        staking_target_list: Option<Vec<(String, u64)>>,
+        /// The generator positions to claim rewards from on Collect
+        generator_rewards: Option<GeneratorRewards>,
+        /// The router used to swap fee tokens instead of the factory pairs
+        swap_router: Option<SwapRouter>,
+        /// The max share of the pool reserve of a fee token swapped per call, in basis points, 0 is unlimited
+        max_swap_portion_bps: Option<u64>,
+        /// The number of seconds a config change is proposed before it can be applied, 0 applies instantly
+        config_delay: Option<u64>,
+        /// The address allowed to pause Collect
+        pauser: Option<String>,
+        /// The beneficiaries whose share is compounded to protocol-owned LP
+        lp_target_list: Option<Vec<LpTarget>>,
     },
+    /// Applies the proposed config and changes once the config delay has elapsed
+    ApplyConfig {},
+    /// Removes the proposed config
+    DropConfigProposal {},
     /// Add bridge tokens used to swap specific fee tokens to stablecoin (effectively declaring a swap route)
     UpdateBridges {
         /// List of bridge assets to be added
@@ -45,12 +187,64 @@ pub enum ExecuteMsg {
         /// List of asset to be removed
         remove: Option<Vec<AssetInfo>>,
     },
+    /// Adds or removes swap settings for specific fee tokens
+    UpdateAssetConfigs {
+        /// List of asset configs to be added or replaced
+        add: Option<Vec<AssetConfig>>,
+        /// List of asset to be removed
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Sets or removes the asset a beneficiary of the target list or staking target list is paid in.
+    /// Its share is swapped from stablecoin along the reversed bridge route on distribution. Only the owner
+    /// can execute this
+    UpdatePayoutAssets {
+        /// List of beneficiary and payout asset to be added or replaced
+        add: Option<Vec<(String, AssetInfo)>>,
+        /// List of beneficiary to be paid in stablecoin again
+        remove: Option<Vec<String>>,
+    },
     /// Swap fee tokens via bridge assets
     SwapBridgeAssets { assets: Vec<AssetInfo>, depth: u64 },
-    /// Distribute stablecoin to beneficiary
+    /// Funds the staking contract with the payout asset received above the previous balance, callable by
+    /// the contract itself after the payout swap
+    DepositStakingPayout {
+        /// The staking contract to fund
+        staking_contract: String,
+        /// The payout asset of the staking contract
+        asset: AssetInfo,
+        /// The balance of the payout asset before the swap
+        prev_balance: Uint128,
+    },
+    /// Distribute stablecoin to beneficiary, callable by the contract itself, the owner and operators. Does nothing until the distribution interval has elapsed
     DistributeFees {
         /// The minimum expected amount of stablecoine
         minimum_receive: Option<Uint128>,
+        /// The Collect caller to receive the collect incentive
+        keeper: Option<String>,
+        /// The stablecoin balance before the collect, the collect incentive is only paid from the stablecoin received since
+        prev_balance: Option<Uint128>,
+    },
+    /// Allows an address to call Collect and DistributeFees
+    AddOperator {
+        /// The operator address
+        operator: String,
+    },
+    /// Removes an address from the operators
+    RemoveOperator {
+        /// The operator address
+        operator: String,
+    },
+    /// Halts Collect. Only the owner and pauser can execute this
+    Pause {},
+    /// Resumes Collect. Only the owner and pauser can execute this
+    Unpause {},
+    /// Sends the whole balance of an asset to an address. Only the owner can execute this,
+    /// and not while a collect is in progress
+    Rescue {
+        /// The asset to send
+        asset: AssetInfo,
+        /// The recipient
+        to: String,
     },
     /// Creates a request to change the contract's ownership
     ProposeNewOwner {
@@ -65,23 +259,83 @@ pub enum ExecuteMsg {
     ClaimOwnership {},
 }
 
+/// This structure describes the cw20 hook messages of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Deposits the sent fee token, the sending contract is reported as the source. The fee token is
+    /// swapped to stablecoin once its balance reaches the swap threshold of its asset config
+    Deposit {},
+}
+
 /// This structure describes the query functions available in the contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     /// Returns information about the maker configs that contains in the [`ConfigResponse`]
     Config {},
+    /// Returns the proposed config and the time it can be applied, if any
+    ConfigProposal {},
     /// Returns the balance for each asset in the specified input parameters
     Balances {
         assets: Vec<AssetInfo>,
     },
     /// Returns list of bridge assets
-    Bridges {},
+    Bridges {
+        /// The asset to start reading from
+        start_after: Option<String>,
+        /// The number of bridges to read
+        limit: Option<u32>,
+    },
+    /// Returns list of asset configs
+    Assets {
+        /// The asset to start reading from
+        start_after: Option<String>,
+        /// The number of asset configs to read
+        limit: Option<u32>,
+    },
+    /// Returns list of beneficiaries with a payout asset other than stablecoin
+    PayoutAssets {
+        /// The beneficiary to start reading from
+        start_after: Option<String>,
+        /// The number of beneficiaries to read
+        limit: Option<u32>,
+    },
+    /// Returns list of operators allowed to call Collect and DistributeFees, in addition to the config operator
+    Operators {},
+    /// Returns whether Collect is paused
+    Paused {},
     /// Simulate collects and swaps fee tokens to stablecoin
     CollectSimulation {
         /// The assets to swap to stablecoin
         assets: Vec<AssetWithLimit>,
-    }
+    },
+    /// Simulates the swap of each fee token to stablecoin separately, returns the route and output per asset
+    SimulateCollect {
+        /// The assets to swap to stablecoin
+        assets: Vec<AssetWithLimit>,
+    },
+    /// Returns the past distributions, oldest first
+    DistributionHistory {
+        /// The distribution id to start reading from
+        start_after: Option<u64>,
+        /// The number of distributions to read
+        limit: Option<u32>,
+    },
+    /// Returns the lifetime amounts distributed to each beneficiary
+    BeneficiaryTotals {
+        /// The beneficiary to start reading from
+        start_after: Option<String>,
+        /// The number of beneficiaries to read
+        limit: Option<u32>,
+    },
+    /// Returns the lifetime amounts of each fee token swapped by Collect
+    SourceTotals {
+        /// The asset to start reading from
+        start_after: Option<String>,
+        /// The number of assets to read
+        limit: Option<u32>,
+    },
 }
 
 /// A custom struct used to return multiple asset balances.
@@ -98,6 +352,50 @@ pub struct CollectSimulationResponse {
     pub return_amount: Uint128,
 }
 
+/// This structure holds the parameters that are returned from a per asset collect simulation
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateCollectResponse {
+    /// The simulation of each asset, in the requested order
+    pub simulations: Vec<AssetCollectSimulation>,
+}
+
+/// This structure describes the expected swap of a single fee token to stablecoin
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetCollectSimulation {
+    /// Information about the fee token
+    pub info: AssetInfo,
+    /// The amount of fee token to swap
+    pub amount: Uint128,
+    /// The assets the fee token is swapped through, starting with the fee token and ending with stablecoin
+    pub route: Vec<AssetInfo>,
+    /// The amount of stablecoin returned from swap
+    pub return_amount: Uint128,
+}
+
+/// This structure describes a distribution of collected fees
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Distribution {
+    /// The distribution id, increasing from 1
+    pub id: u64,
+    /// The block time in seconds of the distribution
+    pub time: u64,
+    /// The distributed asset
+    pub asset: AssetInfo,
+    /// The total amount distributed
+    pub amount: Uint128,
+    /// The amount paid to each beneficiary, including the keeper and `burn` for the burned amount
+    pub transfers: Vec<(String, Uint128)>,
+}
+
+/// This structure describes the lifetime amounts distributed to a beneficiary
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BeneficiaryTotal {
+    /// The beneficiary address, the IBC receiver or `burn`
+    pub beneficiary: String,
+    /// The total amount of each distributed asset
+    pub amounts: Vec<Asset>,
+}
+
 /// This structure describes a migration message.
 /// We currently take no arguments for migrations.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
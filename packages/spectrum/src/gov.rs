@@ -0,0 +1,285 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The SPEC token contract address
+    pub spec_token: String,
+    /// The fee token forwarded by the fees collector and shared by the stakers
+    pub reward_token: AssetInfo,
+    /// The min share of the total voting power voting on a poll
+    pub quorum: Decimal,
+    /// The min share of yes votes for a poll to pass
+    pub threshold: Decimal,
+    /// The number of seconds a poll is open for voting
+    pub voting_period: u64,
+    /// The number of seconds after the end of voting a passed poll can be executed
+    pub effective_delay: u64,
+    /// The SPEC deposit required to create a poll
+    pub proposal_deposit: Uint128,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Shares the native reward token sent with the message with the stakers
+    Deposit {},
+    /// Withdraws staked SPEC
+    Withdraw {
+        /// The SPEC amount to withdraw, all staked SPEC if not set
+        amount: Option<Uint128>,
+    },
+    /// Claims the reward token earned by the sender
+    ClaimReward {},
+    /// Extends a lock of the sender
+    ExtendLock {
+        /// The index of the lock in the staker info
+        index: u32,
+        /// The number of seconds from now the lock ends, rounded down to a week
+        duration: u64,
+    },
+    /// Merges locks of the sender into one lock ending at the latest end
+    MergeLocks {
+        /// The indexes of the locks in the staker info
+        indexes: Vec<u32>,
+    },
+    /// Releases the ended locks of a staker so they stop earning the lock bonus weight, anyone can
+    /// execute this
+    ReleaseLocks {
+        /// The staker address
+        staker_addr: String,
+    },
+    /// Updates the poll parameters, only the contract itself can execute this through a poll
+    UpdateConfig {
+        /// The min share of the total voting power voting on a poll
+        quorum: Option<Decimal>,
+        /// The min share of yes votes for a poll to pass
+        threshold: Option<Decimal>,
+        /// The number of seconds a poll is open for voting
+        voting_period: Option<u64>,
+        /// The number of seconds after the end of voting a passed poll can be executed
+        effective_delay: Option<u64>,
+        /// The SPEC deposit required to create a poll
+        proposal_deposit: Option<Uint128>,
+    },
+    /// Votes on a poll with the voting power of the sender at the poll start, the withdrawable SPEC of the sender
+    /// is locked until the end of the voting
+    CastVote {
+        /// The poll id
+        poll_id: u64,
+        /// The vote
+        vote: VoteOption,
+    },
+    /// Tallies the votes of a poll after the end of voting, the deposit is returned if the
+    /// quorum is reached, otherwise it is burned
+    EndPoll {
+        /// The poll id
+        poll_id: u64,
+    },
+    /// Executes the messages of a passed poll after the effective delay
+    ExecutePoll {
+        /// The poll id
+        poll_id: u64,
+    },
+}
+
+/// This structure describes the custom hooks for the CW20.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Stakes SPEC, only the SPEC token can send this
+    Stake {
+        /// The address credited with the staked SPEC, the sender if not set
+        staker_addr: Option<String>,
+        /// The number of seconds the SPEC is locked, rounded down to a week. Locking 1 week to 2
+        /// years scales the voting power and the fee share up to 2x. Not locked if not set
+        lock_duration: Option<u64>,
+    },
+    /// Shares the cw20 reward token with the stakers, only the reward token can send this
+    Deposit {},
+    /// Creates a poll with the sent SPEC as deposit, only the SPEC token can send this
+    CreatePoll {
+        /// The poll title
+        title: String,
+        /// The poll description
+        description: String,
+        /// The link to the poll details
+        link: Option<String>,
+        /// The messages executed by the contract when the poll passes
+        execute_msgs: Vec<PollExecuteMsg>,
+    },
+}
+
+/// This structure describes a message executed by a passed poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollExecuteMsg {
+    /// The contract to execute
+    pub contract: String,
+    /// The message to execute
+    pub msg: Binary,
+}
+
+/// This enum describes the vote options of a poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+}
+
+/// This enum describes the status of a poll.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PollStatus {
+    InProgress,
+    Passed,
+    Rejected,
+    Executed,
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the total staked SPEC and the reward index
+    State {},
+    /// Returns the SPEC per staked SPEC and the total staked SPEC, the vault query shared by all
+    /// vaults in [`crate::vault`]
+    ExchangeRate {},
+    /// Returns the staked SPEC and the pending reward of the staker
+    StakerInfo {
+        /// The staker address
+        staker_addr: String,
+    },
+    /// Returns the voting power of the address and the total voting power, used to boost farms
+    BoostWeight {
+        /// The staker address
+        address: String,
+    },
+    /// Returns the poll
+    Poll {
+        /// The poll id
+        poll_id: u64,
+    },
+    /// Returns the polls ordered by id
+    Polls {
+        /// The poll id to start after
+        start_after: Option<u64>,
+        /// The max number of polls to return
+        limit: Option<u32>,
+    },
+    /// Returns the vote of the voter on the poll
+    Voter {
+        /// The poll id
+        poll_id: u64,
+        /// The voter address
+        address: String,
+    },
+}
+
+/// This structure holds the parameters that are returned from the state query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    /// The total staked SPEC, including locked SPEC
+    pub total_staked: Uint128,
+    /// The total weight sharing the reward token
+    pub total_weight: Uint128,
+    /// The total voting power
+    pub voting_power: Uint128,
+    /// The cumulative reward token per weight
+    pub reward_index: Decimal,
+    /// The reward token deposited while nothing was staked, shared once SPEC is staked
+    pub undistributed_reward: Uint128,
+}
+
+/// This structure holds the parameters that are returned from the staker info query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakerInfoResponse {
+    /// The staker address
+    pub staker_addr: String,
+    /// The staked SPEC withdrawable at any time
+    pub amount: Uint128,
+    /// The SPEC locks of the staker
+    pub locks: Vec<LockResponse>,
+    /// The weight sharing the reward token
+    pub weight: Uint128,
+    /// The voting power
+    pub voting_power: Uint128,
+    /// The reward token earned and not claimed yet
+    pub pending_reward: Uint128,
+}
+
+/// This structure holds the parameters of a lock returned from the staker info query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockResponse {
+    /// The locked SPEC
+    pub amount: Uint128,
+    /// The block time in seconds the lock ends
+    pub end: u64,
+    /// The weight sharing the reward token
+    pub weight: Uint128,
+    /// The voting power, decaying to the locked SPEC at the end of the lock
+    pub voting_power: Uint128,
+}
+
+/// This structure holds the parameters that are returned from the poll query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollResponse {
+    /// The poll id
+    pub id: u64,
+    /// The address created the poll
+    pub creator: String,
+    /// The poll status
+    pub status: PollStatus,
+    /// The poll title
+    pub title: String,
+    /// The poll description
+    pub description: String,
+    /// The link to the poll details
+    pub link: Option<String>,
+    /// The messages executed by the contract when the poll passes
+    pub execute_msgs: Vec<PollExecuteMsg>,
+    /// The SPEC deposit of the poll
+    pub deposit_amount: Uint128,
+    /// The block time in seconds the poll was created, votes are weighted by the voting power
+    /// at this time
+    pub start_time: u64,
+    /// The block time in seconds the voting ends
+    pub end_time: u64,
+    /// The voting power voted yes
+    pub yes_votes: Uint128,
+    /// The voting power voted no
+    pub no_votes: Uint128,
+    /// The total voting power when the poll was created, used for the quorum
+    pub total_voting_power: Uint128,
+}
+
+/// This structure holds the parameters that are returned from the polls query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollsResponse {
+    /// The polls
+    pub polls: Vec<PollResponse>,
+}
+
+/// This structure holds the parameters that are returned from the voter query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoterResponse {
+    /// The vote
+    pub vote: VoteOption,
+    /// The voting power voted
+    pub balance: Uint128,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
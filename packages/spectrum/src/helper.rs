@@ -1,4 +1,4 @@
-use cosmwasm_std::{StdError, StdResult, Uint128, Uint256};
+use cosmwasm_std::{Addr, Api, Decimal, StdError, StdResult, Uint128, Uint256};
 use std::convert::TryFrom;
 
 pub fn compute_deposit_time(
@@ -35,6 +35,35 @@ impl ScalingUint128 for Uint128 {
     }
 }
 
+/// Returns the error of a stored state breaking an invariant, which aborts a migration.
+pub fn invalid_state(reason: &str) -> StdError {
+    StdError::generic_err(format!("Invalid state: {}", reason))
+}
+
+/// Checks that a stored address is valid and in its normalized form.
+pub fn assert_valid_addr(api: &dyn Api, addr: &Addr, field: &str) -> StdResult<()> {
+    match api.addr_validate(addr.as_str()) {
+        Ok(validated) if &validated == addr => Ok(()),
+        _ => Err(invalid_state(&format!("{} is not a valid address", field))),
+    }
+}
+
+/// Checks that a stored ratio is in the range 0 to 1.
+pub fn assert_ratio(value: Decimal, field: &str) -> StdResult<()> {
+    if value > Decimal::one() {
+        return Err(invalid_state(&format!("{} must be 0 to 1", field)));
+    }
+    Ok(())
+}
+
+/// Checks that a stored basis points value does not exceed the max.
+pub fn assert_bps(value: u64, max: u64, field: &str) -> StdResult<()> {
+    if value > max {
+        return Err(invalid_state(&format!("{} must be 0 to {}", field, max)));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,24 @@
 pub mod adapters;
+pub mod airdrop;
 pub mod astroport_farm;
 pub mod compound_proxy;
+pub mod events;
+pub mod farm_controller;
+pub mod farm_factory;
 pub mod fees_collector;
+pub mod gov;
 pub mod helper;
 pub mod pair_proxy;
+pub mod pause;
+pub mod querier;
+pub mod registry;
+pub mod router;
+#[cfg(all(any(test, feature = "test-utils"), not(target_arch = "wasm32")))]
+pub mod testing;
 pub mod lp_staking;
+pub mod math;
+pub mod oracle;
+pub mod timelock;
+pub mod treasury;
+pub mod vault;
+pub mod vesting;
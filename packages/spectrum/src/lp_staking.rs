@@ -59,6 +59,7 @@ pub enum QueryMsg {
         limit: Option<u32>,
         time_seconds: Option<u64>,
     },
+    ExchangeRate {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -0,0 +1,248 @@
+use cosmwasm_std::{Isqrt, StdError, StdResult, Uint128, Uint256};
+use std::convert::TryInto;
+
+/// Scaling denominator for bps
+pub const BPS_DENOM: u64 = 10000u64;
+
+/// Returns an error if the bps is more than 100%
+fn validate_bps(bps: u64) -> StdResult<()> {
+    if bps > BPS_DENOM {
+        Err(StdError::generic_err("bps must be 0 to 10000"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Returns the bps portion of the amount, rounded down
+pub fn bps_amount(amount: Uint128, bps: u64) -> StdResult<Uint128> {
+    validate_bps(bps)?;
+    Ok((amount.full_mul(bps) / Uint256::from(BPS_DENOM)).try_into()?)
+}
+
+/// Returns the shares for the amount added to the total amount backing the total shares, rounded
+/// down. The first amount gets 1 share per unit
+pub fn amount_to_share(
+    amount: Uint128,
+    total_amount: Uint128,
+    total_share: Uint128,
+) -> StdResult<Uint128> {
+    if total_share.is_zero() || total_amount.is_zero() {
+        return Ok(amount);
+    }
+    Ok((amount.full_mul(total_share) / Uint256::from(total_amount)).try_into()?)
+}
+
+/// Returns the amount backing the shares out of the total shares, rounded down
+pub fn share_to_amount(
+    share: Uint128,
+    total_share: Uint128,
+    total_amount: Uint128,
+) -> StdResult<Uint128> {
+    if total_share.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    Ok((share.full_mul(total_amount) / Uint256::from(total_share)).try_into()?)
+}
+
+/// Returns the amount of the ask asset from swapping the offer amount in a constant product pool,
+/// after the commission is taken from the return amount
+pub fn xyk_return_amount(
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    commission_bps: u64,
+) -> StdResult<Uint128> {
+    validate_bps(commission_bps)?;
+    let offer_pool_after = Uint256::from(offer_pool) + Uint256::from(offer_amount);
+    if offer_pool_after.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    // ask_pool - offer_pool * ask_pool / (offer_pool + offer_amount)
+    let return_amount = ask_pool.full_mul(offer_amount) / offer_pool_after;
+    let commission_amount = return_amount * Uint256::from(commission_bps) / Uint256::from(BPS_DENOM);
+
+    Ok((return_amount - commission_amount).try_into()?)
+}
+
+/// Returns the amount of asset A to swap in a constant product pool, so the rest of asset A and
+/// the asset B plus the swap return are provided in the pool ratio after the swap.
+///
+/// With the commission rate `f`, the swap amount is
+/// `(f * Ax + sqrt(Ax * (f^2 * Ax + 4 * (1 - f) * Bx))) / (2 * (pool_b + amount_b)) - pool_a`,
+/// where `Ax = (pool_a + amount_a) * pool_b` and `Bx = (pool_b + amount_b) * pool_a`. Returns an
+/// error if the areas overflow.
+pub fn optimal_swap_amount(
+    amount_a: Uint128,
+    amount_b: Uint128,
+    pool_a: Uint128,
+    pool_b: Uint128,
+    commission_bps: u64,
+) -> StdResult<Uint128> {
+    validate_bps(commission_bps)?;
+    if pool_a.is_zero() || pool_b.is_zero() {
+        return Ok(Uint128::zero());
+    }
+
+    let amount_a = Uint256::from(amount_a);
+    let amount_b = Uint256::from(amount_b);
+    let pool_a = Uint256::from(pool_a);
+    let pool_b = Uint256::from(pool_b);
+    let commission = Uint256::from(commission_bps);
+    let denom = Uint256::from(BPS_DENOM);
+
+    let pool_ax = amount_a + pool_a;
+    let pool_bx = amount_b + pool_b;
+    let area_ax = pool_ax.checked_mul(pool_b)?;
+    let area_bx = pool_bx.checked_mul(pool_a)?;
+
+    let a = (commission * commission)
+        .checked_mul(area_ax)?
+        .checked_add(
+            (Uint256::from(4u64) * (denom - commission) * denom).checked_mul(area_bx)?,
+        )?;
+    let b = commission
+        .checked_mul(area_ax)?
+        .checked_add(area_ax.isqrt().checked_mul(a.isqrt())?)?;
+    let result = (b / (Uint256::from(2u64) * denom) / pool_bx).saturating_sub(pool_a);
+
+    Ok(result.try_into()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn bps() {
+        assert_eq!(bps_amount(Uint128::new(12345), 500), Ok(Uint128::new(617)));
+        assert_eq!(bps_amount(Uint128::MAX, 10000), Ok(Uint128::MAX));
+        assert_eq!(
+            bps_amount(Uint128::new(1), 10001),
+            Err(StdError::generic_err("bps must be 0 to 10000"))
+        );
+    }
+
+    #[test]
+    fn optimal_swap() {
+        // already in the pool ratio
+        let result = optimal_swap_amount(
+            Uint128::new(1146135045),
+            Uint128::new(9093887),
+            Uint128::new(114613504500),
+            Uint128::new(909388700),
+            30,
+        );
+        assert_eq!(result, Ok(Uint128::zero()));
+
+        let result = optimal_swap_amount(
+            Uint128::new(1000000),
+            Uint128::zero(),
+            Uint128::new(1000000000),
+            Uint128::new(1000000000),
+            30,
+        );
+        assert_eq!(result, Ok(Uint128::new(500626)));
+
+        // the areas of the largest pools do not fit in 256 bits
+        let result = optimal_swap_amount(Uint128::MAX, Uint128::MAX, Uint128::MAX, Uint128::MAX, 30);
+        assert!(result.is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn bps_amount_is_at_most_amount(amount in any::<u128>(), bps in 0..=BPS_DENOM) {
+            let result = bps_amount(Uint128::new(amount), bps).unwrap();
+            prop_assert!(result <= Uint128::new(amount));
+        }
+
+        #[test]
+        fn share_round_trip_never_adds_value(
+            amount in any::<u128>(),
+            total_amount in 1..u128::MAX,
+            total_share in 1..u128::MAX,
+        ) {
+            let share = match amount_to_share(
+                Uint128::new(amount),
+                Uint128::new(total_amount),
+                Uint128::new(total_share),
+            ) {
+                Ok(share) => share,
+                // the shares of the amount overflow
+                Err(_) => return Ok(()),
+            };
+            let total_share = Uint128::new(total_share).checked_add(share);
+            let total_amount = Uint128::new(total_amount).checked_add(Uint128::new(amount));
+            if let (Ok(total_share), Ok(total_amount)) = (total_share, total_amount) {
+                let result = share_to_amount(share, total_share, total_amount).unwrap();
+                prop_assert!(result <= Uint128::new(amount));
+            }
+        }
+
+        #[test]
+        fn xyk_return_keeps_constant_product(
+            offer_pool in 1..u128::MAX,
+            ask_pool in 1..u128::MAX,
+            offer_amount in any::<u128>(),
+            commission_bps in 0..=BPS_DENOM,
+        ) {
+            let result = xyk_return_amount(
+                Uint128::new(offer_pool),
+                Uint128::new(ask_pool),
+                Uint128::new(offer_amount),
+                commission_bps,
+            ).unwrap();
+            prop_assert!(result < Uint128::new(ask_pool));
+
+            let k = Uint256::from(offer_pool) * Uint256::from(ask_pool);
+            let k_after = (Uint256::from(offer_pool) + Uint256::from(offer_amount))
+                .checked_mul(Uint256::from(ask_pool - result.u128()));
+            if let Ok(k_after) = k_after {
+                prop_assert!(k_after >= k);
+            }
+        }
+
+        #[test]
+        fn optimal_swap_provides_in_pool_ratio(
+            amount_a in 1_000_000u128..1_000_000_000_000_000_000_000_000u128,
+            amount_b in 0u128..1_000_000_000_000_000_000_000_000u128,
+            pool_a in 1_000_000u128..1_000_000_000_000_000_000_000_000_000_000u128,
+            pool_b in 1_000_000u128..1_000_000_000_000_000_000_000_000_000_000u128,
+            commission_bps in 0..1000u64,
+        ) {
+            // only asset A is in excess of the pool ratio
+            prop_assume!(
+                Uint128::new(amount_a).full_mul(pool_b) > Uint128::new(amount_b).full_mul(pool_a)
+            );
+
+            let swap_amount = optimal_swap_amount(
+                Uint128::new(amount_a),
+                Uint128::new(amount_b),
+                Uint128::new(pool_a),
+                Uint128::new(pool_b),
+                commission_bps,
+            ).unwrap();
+            prop_assert!(swap_amount.u128() <= amount_a);
+
+            let return_amount = xyk_return_amount(
+                Uint128::new(pool_a),
+                Uint128::new(pool_b),
+                swap_amount,
+                commission_bps,
+            ).unwrap();
+
+            // the provided ratio is within 0.1% of the pool ratio after the swap
+            let provide_area = Uint256::from(amount_a - swap_amount.u128())
+                * Uint256::from(pool_b - return_amount.u128());
+            let pool_area = Uint256::from(amount_b + return_amount.u128())
+                * Uint256::from(pool_a + swap_amount.u128());
+            let diff = if provide_area > pool_area {
+                provide_area - pool_area
+            } else {
+                pool_area - provide_area
+            };
+            prop_assert!(diff * Uint256::from(1000u64) <= provide_area.max(pool_area));
+        }
+    }
+}
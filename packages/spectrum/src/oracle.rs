@@ -0,0 +1,131 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Decimal256, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to register pairs and update config
+    pub owner: String,
+    /// The min number of seconds between price snapshots
+    pub update_interval: u64,
+    /// The max number of seconds of the window of a price
+    pub max_window: u64,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Updates contract config
+    UpdateConfig {
+        /// The min number of seconds between price snapshots
+        update_interval: Option<u64>,
+        /// The max number of seconds of the window of a price
+        max_window: Option<u64>,
+    },
+    /// Provides the prices of the assets in the Astroport pair. When several pairs provide the
+    /// price of the same assets, the median price is used. Only the owner can execute this
+    RegisterPair {
+        /// The pair contract address
+        pair_contract: String,
+    },
+    /// Stops providing the prices of the assets in the pair. Only the owner can execute this
+    DeregisterPair {
+        /// The pair contract address
+        pair_contract: String,
+    },
+    /// Snapshots the cumulative prices of all registered pairs, anyone can execute this once per
+    /// update interval
+    Update {},
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the registered pairs
+    Pairs {},
+    /// Returns the median of the time weighted average prices of the base asset in the quote
+    /// asset provided by the registered pairs
+    Price {
+        /// The asset to price
+        base: AssetInfo,
+        /// The asset the price is denominated in
+        quote: AssetInfo,
+        /// The number of seconds to average the price over
+        window: u64,
+    },
+    /// Returns the total value of the assets in the quote asset. Each asset is priced along the
+    /// shortest route of registered pairs to the quote asset
+    ValueAssets {
+        /// The assets to value
+        assets: Vec<Asset>,
+        /// The asset the value is denominated in
+        quote: AssetInfo,
+        /// The number of seconds to average the prices over
+        window: u64,
+    },
+}
+
+/// This structure holds the registered pairs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairsResponse {
+    pub pairs: Vec<Addr>,
+}
+
+/// This structure holds the time weighted average price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceResponse {
+    /// The amount of the quote asset per unit of the base asset, in the smallest denomination,
+    /// the median of the prices of all sources
+    pub price: Decimal256,
+    /// The start time of the window the price is averaged over, at least the requested window
+    /// before the current time. The earliest start time of all sources
+    pub start_time: u64,
+    /// The number of pairs the median price is taken from
+    #[serde(default)]
+    pub sources: u32,
+    /// The max deviation of the price of a source from the median price, as a ratio of the
+    /// median price
+    #[serde(default)]
+    pub max_deviation: Decimal256,
+}
+
+/// This structure holds the total value of assets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValueAssetsResponse {
+    /// The value in the smallest denomination of the quote asset
+    pub value: Uint128,
+}
+
+/// This structure describes the guard that aborts compounds while the spot price of the pool
+/// deviates from its time weighted average price in the oracle.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceGuard {
+    /// The oracle contract address
+    pub oracle: String,
+    /// The number of seconds of the time weighted average price
+    pub window: u64,
+    /// The max deviation of the spot price in bps, 0 disables the guard
+    pub max_deviation_bps: u64,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -3,9 +3,10 @@ use serde::{Deserialize, Serialize};
 
 use astroport::asset::{Asset, AssetInfo};
 
-use cosmwasm_std::{Decimal};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
 use cw20::Cw20ReceiveMsg;
 use crate::adapters::router::RouterType;
+use crate::oracle::PriceGuard;
 
 /// Maximum assets in the swap route
 pub const MAX_ASSETS: usize = 50;
@@ -23,6 +24,9 @@ pub struct InstantiateMsg {
     pub offer_precision: Option<u8>,
     /// The decimal precision of the ask asset
     pub ask_precision: Option<u8>,
+    /// The oracle guard setting the minimum receive of each hop from the time weighted average
+    /// price less the max deviation, multi-hop routes are then swapped one hop at a time
+    pub hop_guard: Option<PriceGuard>,
 }
 
 /// This structure describes the execute messages of the contract.
@@ -42,6 +46,35 @@ pub enum ExecuteMsg {
         /// Receiver address
         to: Option<String>,
     },
+    /// The callback of type [`CallbackMsg`]
+    Callback(CallbackMsg),
+}
+
+/// This structure describes the callback messages of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackMsg {
+    /// Swaps the balance received from the previous hop along the rest of the route
+    SwapHop {
+        /// The rest of the route, starting with the asset received from the previous hop
+        asset_infos: Vec<AssetInfo>,
+        /// The minimum receive of the whole route
+        minimum_receive: Option<Uint128>,
+        /// Maximum spread of each hop
+        max_spread: Option<Decimal>,
+        /// Receiver address
+        to: Addr,
+    },
+}
+
+impl CallbackMsg {
+    pub fn into_cosmos_msg(&self, contract_addr: &Addr) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(contract_addr),
+            msg: to_binary(&ExecuteMsg::Callback(self.clone()))?,
+            funds: vec![],
+        }))
+    }
 }
 
 /// ## Description
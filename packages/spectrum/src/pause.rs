@@ -0,0 +1,38 @@
+use cosmwasm_std::{Addr, Response, StdError, StdResult, Storage};
+use cw_storage_plus::Item;
+
+/// Stores whether the pausable actions of a contract are halted
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+/// ## Description
+/// Returns whether the pausable actions are halted.
+pub fn is_paused(storage: &dyn Storage) -> StdResult<bool> {
+    Ok(PAUSED.may_load(storage)?.unwrap_or_default())
+}
+
+/// ## Description
+/// Returns an error while the pausable actions are halted.
+pub fn assert_not_paused(storage: &dyn Storage) -> StdResult<()> {
+    if is_paused(storage)? {
+        return Err(StdError::generic_err("Contract is paused"));
+    }
+    Ok(())
+}
+
+/// ## Description
+/// Pauses or unpauses the pausable actions. Only the owner and the pauser can execute this.
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    owner: &Addr,
+    pauser: Option<&Addr>,
+    paused: bool,
+) -> StdResult<Response> {
+    if sender != owner && Some(sender) != pauser {
+        return Err(StdError::generic_err("Unauthorized"));
+    }
+
+    PAUSED.save(storage, &paused)?;
+
+    Ok(Response::new().add_attribute("action", if paused { "pause" } else { "unpause" }))
+}
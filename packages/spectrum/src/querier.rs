@@ -0,0 +1,65 @@
+use astroport::asset::{is_token_factory_denom, Asset, AssetInfo, PairInfo};
+use astroport::pair::{PoolResponse, QueryMsg as PairQueryMsg};
+use cosmwasm_std::{CustomQuery, QuerierWrapper, StdResult, Uint128};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+
+/// Returns the balance of the native or cw20 asset held by the address
+pub fn query_asset_balance<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    asset_info: &AssetInfo,
+    address: impl Into<String>,
+) -> StdResult<Uint128> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => Ok(querier.query_balance(address, denom)?.amount),
+        AssetInfo::Token { contract_addr } => {
+            let res: BalanceResponse = querier.query_wasm_smart(
+                contract_addr,
+                &Cw20QueryMsg::Balance {
+                    address: address.into(),
+                },
+            )?;
+            Ok(res.balance)
+        }
+    }
+}
+
+/// Returns the balances of the assets held by the address
+pub fn query_asset_balances<C: CustomQuery>(
+    querier: &QuerierWrapper<C>,
+    asset_infos: &[AssetInfo],
+    address: impl Into<String>,
+) -> StdResult<Vec<Asset>> {
+    let address = address.into();
+    asset_infos
+        .iter()
+        .map(|asset_info| {
+            Ok(Asset {
+                info: asset_info.clone(),
+                amount: query_asset_balance(querier, asset_info, &address)?,
+            })
+        })
+        .collect()
+}
+
+/// Returns the total supply of the LP token
+pub fn query_lp_supply<C: CustomQuery>(querier: &QuerierWrapper<C>, lp_token: impl Into<String>) -> StdResult<Uint128> {
+    let res: TokenInfoResponse = querier.query_wasm_smart(lp_token, &Cw20QueryMsg::TokenInfo {})?;
+    Ok(res.total_supply)
+}
+
+/// Returns the total supply of the LP token of the pair. The supply of a x/tokenfactory LP token
+/// is read from the pool of the pair
+pub fn query_pair_lp_supply<C: CustomQuery>(querier: &QuerierWrapper<C>, pair_info: &PairInfo) -> StdResult<Uint128> {
+    if is_token_factory_denom(pair_info.liquidity_token.as_str()) {
+        let res: PoolResponse =
+            querier.query_wasm_smart(&pair_info.contract_addr, &PairQueryMsg::Pool {})?;
+        Ok(res.total_share)
+    } else {
+        query_lp_supply(querier, &pair_info.liquidity_token)
+    }
+}
+
+/// Returns the reserves of the pool assets held by the pair
+pub fn query_pool_reserves<C: CustomQuery>(querier: &QuerierWrapper<C>, pair_info: &PairInfo) -> StdResult<Vec<Asset>> {
+    query_asset_balances(querier, &pair_info.asset_infos, &pair_info.contract_addr)
+}
@@ -0,0 +1,80 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to update the entries, normally the gov contract
+    pub owner: String,
+}
+
+/// This structure describes the contracts deployed for an LP token.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RegistryEntry {
+    /// The LP token contract address or x/tokenfactory denom
+    pub lp_token: String,
+    /// The farm contract address
+    pub farm: String,
+    /// The compound proxy contract address
+    pub compound_proxy: String,
+    /// The LP staking contract address of the farm
+    pub generator: String,
+    /// The pair contract address, its liquidity token must be the LP token
+    pub pair: String,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Adds or replaces entries, then removes entries by LP token. Only the owner can execute this
+    UpdateEntries {
+        /// The entries to add, an existing entry of the LP token is replaced
+        add: Option<Vec<RegistryEntry>>,
+        /// The LP tokens of the entries to remove
+        remove: Option<Vec<String>>,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the entry of the LP token
+    Entry {
+        /// The LP token contract address or x/tokenfactory denom
+        lp_token: String,
+    },
+    /// Returns the entries ordered by LP token
+    Entries {
+        /// The LP token to start after
+        start_after: Option<String>,
+        /// The max number of entries to return
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the parameters that are returned from the entries query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EntriesResponse {
+    /// The entries
+    pub entries: Vec<RegistryEntry>,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
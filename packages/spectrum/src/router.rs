@@ -0,0 +1,156 @@
+use crate::adapters::router::SwapOperation;
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The max number of swap operations in one route
+pub const MAX_SWAP_OPERATIONS: usize = 50;
+
+/// The max number of pools one swap is split across
+pub const MAX_SPLIT_PAIRS: usize = 5;
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to update config
+    pub owner: String,
+    /// The Astroport factory contract address, used to find the pairs of astro swap operations
+    pub astroport_factory: String,
+    /// The TerraSwap factory contract address, used to find the pairs of terra swap operations
+    pub terraswap_factory: Option<String>,
+    /// The White Whale factory contract address, used to find the pairs of white whale operations
+    pub whitewhale_factory: Option<String>,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Swaps the sent native token through the operations
+    ExecuteSwapOperations {
+        /// The swap operations, the ask asset of each operation is offered to the next one
+        operations: Vec<SwapOperation>,
+        /// The min amount of the last ask asset received, the swap fails otherwise
+        minimum_receive: Option<Uint128>,
+        /// The recipient of the last ask asset, default to the sender
+        to: Option<String>,
+        /// The max spread of each swap operation
+        max_spread: Option<Decimal>,
+    },
+    /// Adds or removes the pools Astroport swap operations are split across. Only the owner can execute this
+    UpdateSplitRoutes {
+        /// List of split routes to be added or replaced
+        add: Option<Vec<SplitRoute>>,
+        /// List of asset pairs to be swapped on the factory pair again
+        remove: Option<Vec<[AssetInfo; 2]>>,
+    },
+    /// Updates contract config
+    UpdateConfig {
+        /// The Astroport factory contract address
+        astroport_factory: Option<String>,
+        /// The TerraSwap factory contract address
+        terraswap_factory: Option<String>,
+        /// The White Whale factory contract address
+        whitewhale_factory: Option<String>,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+    /// The callback of type [`CallbackMsg`]
+    Callback(CallbackMsg),
+}
+
+/// This structure describes the pools of an asset pair, e.g. an xyk and a PCL pool, that one Astroport
+/// swap operation is split across in either direction to reduce the price impact.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SplitRoute {
+    /// The assets of the pools
+    pub asset_infos: [AssetInfo; 2],
+    /// The pair contracts and their weights of the offer amount
+    pub pairs: Vec<(String, u64)>,
+    /// Splits the offer amount by simulating the pools instead of by weight, each part goes to the pool
+    /// with the best return for it
+    pub computed: bool,
+}
+
+/// This structure describes a CW20 hook message.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Swaps the sent token through the operations
+    ExecuteSwapOperations {
+        /// The swap operations, the ask asset of each operation is offered to the next one
+        operations: Vec<SwapOperation>,
+        /// The min amount of the last ask asset received, the swap fails otherwise
+        minimum_receive: Option<Uint128>,
+        /// The recipient of the last ask asset, default to the sender
+        to: Option<String>,
+        /// The max spread of each swap operation
+        max_spread: Option<Decimal>,
+    },
+}
+
+/// This structure describes the callback messages of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackMsg {
+    /// Swaps the whole contract balance of the offer asset of the operation
+    ExecuteSwapOperation {
+        operation: SwapOperation,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    /// Checks the receiver balance increased by at least the minimum receive amount
+    AssertMinimumReceive {
+        asset_info: AssetInfo,
+        prev_balance: Uint128,
+        minimum_receive: Uint128,
+        receiver: String,
+    },
+}
+
+// Modified from
+// https://github.com/CosmWasm/cw-plus/blob/v0.8.0/packages/cw20/src/receiver.rs#L23
+impl CallbackMsg {
+    pub fn into_cosmos_msg(&self, contract_addr: &Addr) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(contract_addr),
+            msg: to_binary(&ExecuteMsg::Callback(self.clone()))?,
+            funds: vec![],
+        }))
+    }
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the list of split routes
+    SplitRoutes {},
+    /// Returns the amount of the last ask asset received from the operations
+    SimulateSwapOperations {
+        /// The amount of the first offer asset
+        offer_amount: Uint128,
+        /// The swap operations
+        operations: Vec<SwapOperation>,
+    },
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
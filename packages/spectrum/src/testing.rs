@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use astroport::asset::{AssetInfo, PairInfo};
+use astroport::factory::{ConfigResponse as FactoryConfigResponse, QueryMsg as FactoryQueryMsg};
+use astroport::generator::{
+    PendingTokenResponse, QueryMsg as GeneratorQueryMsg, RewardInfoResponse, UserInfoV2,
+};
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Binary, Coin, ContractResult, Empty, OwnedDeps,
+    Querier, QuerierResult, QueryRequest, StdError, StdResult, SystemError, SystemResult, Uint128,
+    WasmQuery,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use cw_storage_plus::Map;
+use serde::Serialize;
+
+/// The generator storage of staked LP token per LP token and user
+const GENERATOR_USER_INFO: Map<(&Addr, &Addr), UserInfoV2> = Map::new("user_info");
+/// The generator storage of the reward asset per reward proxy
+const GENERATOR_PROXY_REWARD_ASSET: Map<&Addr, AssetInfo> = Map::new("proxy_reward_asset");
+
+/// Returns the dependencies with a [`WasmMockQuerier`], the contract holds the native balances
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)])),
+        custom_query_type: Default::default(),
+    }
+}
+
+/// A querier answering cw20, Astroport factory and generator queries, and raw queries of
+/// contract storage. Contracts not registered as a factory or generator are queried as cw20 tokens.
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    token_balances: HashMap<String, HashMap<String, Uint128>>,
+    raw: HashMap<(String, Binary), Binary>,
+    factory_configs: HashMap<String, Binary>,
+    factory_pairs: HashMap<(String, String), Binary>,
+    generators: HashMap<String, HashMap<String, Binary>>,
+}
+
+/// Returns the key of a pair, independent of the order of its assets
+fn pair_key(asset_infos: &[AssetInfo]) -> String {
+    let mut keys: Vec<String> = asset_infos.iter().map(|info| info.to_string()).collect();
+    keys.sort();
+    keys.join("-")
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            token_balances: HashMap::new(),
+            raw: HashMap::new(),
+            factory_configs: HashMap::new(),
+            factory_pairs: HashMap::new(),
+            generators: HashMap::new(),
+        }
+    }
+
+    /// Sets the native balances of the address
+    pub fn set_native_balance(&mut self, addr: &str, balance: Vec<Coin>) {
+        self.base.update_balance(addr, balance);
+    }
+
+    /// Sets the cw20 balance of the address, the total supply is the sum of the balances
+    pub fn set_token_balance(&mut self, token: &str, addr: &str, amount: Uint128) {
+        self.token_balances
+            .entry(token.to_string())
+            .or_default()
+            .insert(addr.to_string(), amount);
+    }
+
+    /// Sets the value stored under the key of the contract storage
+    pub fn set_raw<T: Serialize>(
+        &mut self,
+        contract: &str,
+        key: &[u8],
+        value: &T,
+    ) -> StdResult<()> {
+        self.raw
+            .insert((contract.to_string(), Binary::from(key)), to_binary(value)?);
+        Ok(())
+    }
+
+    /// Sets the config returned by the factory, registering the address as a factory
+    pub fn set_factory_config(
+        &mut self,
+        factory: &str,
+        config: &FactoryConfigResponse,
+    ) -> StdResult<()> {
+        self.factory_configs
+            .insert(factory.to_string(), to_binary(config)?);
+        Ok(())
+    }
+
+    /// Adds a pair returned by the factory for its assets in any order
+    pub fn set_factory_pair(&mut self, factory: &str, pair_info: &PairInfo) -> StdResult<()> {
+        self.factory_pairs.insert(
+            (factory.to_string(), pair_key(&pair_info.asset_infos)),
+            to_binary(pair_info)?,
+        );
+        Ok(())
+    }
+
+    /// Sets the stake of the user in the generator storage, the deposit query returns its amount
+    pub fn set_generator_user_info(
+        &mut self,
+        generator: &str,
+        lp_token: &Addr,
+        user: &Addr,
+        user_info: &UserInfoV2,
+    ) -> StdResult<()> {
+        self.generators.entry(generator.to_string()).or_default();
+        let key = GENERATOR_USER_INFO.key((lp_token, user));
+        self.set_raw(generator, key.deref(), user_info)
+    }
+
+    /// Sets the reward asset of a reward proxy in the generator storage
+    pub fn set_generator_proxy_reward_asset(
+        &mut self,
+        generator: &str,
+        reward_proxy: &Addr,
+        asset_info: &AssetInfo,
+    ) -> StdResult<()> {
+        self.generators.entry(generator.to_string()).or_default();
+        let key = GENERATOR_PROXY_REWARD_ASSET.key(reward_proxy);
+        self.set_raw(generator, key.deref(), asset_info)
+    }
+
+    /// Sets the reward tokens of the LP token returned by the generator
+    pub fn set_generator_reward_info(
+        &mut self,
+        generator: &str,
+        lp_token: &str,
+        reward_info: &RewardInfoResponse,
+    ) -> StdResult<()> {
+        self.generators
+            .entry(generator.to_string())
+            .or_default()
+            .insert(format!("reward_info:{}", lp_token), to_binary(reward_info)?);
+        Ok(())
+    }
+
+    /// Sets the pending rewards of the user returned by the generator
+    pub fn set_generator_pending_token(
+        &mut self,
+        generator: &str,
+        lp_token: &str,
+        user: &str,
+        pending_token: &PendingTokenResponse,
+    ) -> StdResult<()> {
+        self.generators
+            .entry(generator.to_string())
+            .or_default()
+            .insert(
+                format!("pending_token:{}:{}", lp_token, user),
+                to_binary(pending_token)?,
+            );
+        Ok(())
+    }
+
+    fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                let result = if self.factory_configs.contains_key(contract_addr)
+                    || self
+                        .factory_pairs
+                        .keys()
+                        .any(|(factory, _)| factory == contract_addr)
+                {
+                    self.execute_factory_query(contract_addr, msg)
+                } else if self.generators.contains_key(contract_addr) {
+                    self.execute_generator_query(contract_addr, msg)
+                } else {
+                    self.execute_token_query(contract_addr, msg)
+                };
+                SystemResult::Ok(result.into())
+            }
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                let value = self.raw.get(&(contract_addr.clone(), key.clone()));
+                SystemResult::Ok(ContractResult::Ok(value.cloned().unwrap_or_default()))
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+
+    fn execute_factory_query(&self, factory: &str, msg: &Binary) -> StdResult<Binary> {
+        match from_binary(msg)? {
+            FactoryQueryMsg::Config {} => self
+                .factory_configs
+                .get(factory)
+                .cloned()
+                .ok_or_else(|| StdError::not_found("factory config")),
+            FactoryQueryMsg::Pair { asset_infos } => self
+                .factory_pairs
+                .get(&(factory.to_string(), pair_key(&asset_infos)))
+                .cloned()
+                .ok_or_else(|| StdError::generic_err("No pair info exists")),
+            _ => Err(StdError::generic_err("unsupported factory query")),
+        }
+    }
+
+    fn execute_generator_query(&self, generator: &str, msg: &Binary) -> StdResult<Binary> {
+        let responses = &self.generators[generator];
+        match from_binary(msg)? {
+            GeneratorQueryMsg::Deposit { lp_token, user } => {
+                let key =
+                    GENERATOR_USER_INFO.key((&Addr::unchecked(lp_token), &Addr::unchecked(user)));
+                let amount = match self
+                    .raw
+                    .get(&(generator.to_string(), Binary::from(key.deref())))
+                {
+                    Some(value) => from_binary::<UserInfoV2>(value)?.amount,
+                    None => Uint128::zero(),
+                };
+                to_binary(&amount)
+            }
+            GeneratorQueryMsg::RewardInfo { lp_token } => responses
+                .get(&format!("reward_info:{}", lp_token))
+                .cloned()
+                .ok_or_else(|| StdError::not_found("reward info")),
+            GeneratorQueryMsg::PendingToken { lp_token, user } => {
+                match responses.get(&format!("pending_token:{}:{}", lp_token, user)) {
+                    Some(value) => Ok(value.clone()),
+                    None => to_binary(&PendingTokenResponse {
+                        pending: Uint128::zero(),
+                        pending_on_proxy: None,
+                    }),
+                }
+            }
+            _ => Err(StdError::generic_err("unsupported generator query")),
+        }
+    }
+
+    fn execute_token_query(&self, token: &str, msg: &Binary) -> StdResult<Binary> {
+        let balances = self.token_balances.get(token);
+        match from_binary(msg)? {
+            Cw20QueryMsg::Balance { address } => to_binary(&BalanceResponse {
+                balance: balances
+                    .and_then(|balances| balances.get(&address))
+                    .cloned()
+                    .unwrap_or_default(),
+            }),
+            Cw20QueryMsg::TokenInfo {} => to_binary(&TokenInfoResponse {
+                name: token.to_string(),
+                symbol: "TOKEN".to_string(),
+                decimals: 6,
+                total_supply: balances
+                    .map(|balances| balances.values().sum())
+                    .unwrap_or_default(),
+            }),
+            _ => Err(StdError::generic_err("unsupported token query")),
+        }
+    }
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.execute_query(&request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astroport::asset::native_asset_info;
+    use astroport::factory::PairType;
+    use astroport::querier::{query_pair_info, query_supply, query_token_balance};
+    use cosmwasm_std::{coin, Decimal, QuerierWrapper};
+
+    use crate::adapters::generator::Generator;
+
+    #[test]
+    fn factory_queries() -> StdResult<()> {
+        let mut deps = mock_dependencies(&[]);
+        let pair_info = PairInfo {
+            asset_infos: vec![
+                native_asset_info("uluna".to_string()),
+                native_asset_info("uusd".to_string()),
+            ],
+            contract_addr: Addr::unchecked("pair"),
+            liquidity_token: Addr::unchecked("lp_token"),
+            pair_type: PairType::Xyk {},
+        };
+        deps.querier.set_factory_pair("factory", &pair_info)?;
+
+        // the pair is found for its assets in any order
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+        let res = query_pair_info(
+            &querier,
+            Addr::unchecked("factory"),
+            &[
+                native_asset_info("uusd".to_string()),
+                native_asset_info("uluna".to_string()),
+            ],
+        )?;
+        assert_eq!(res, pair_info);
+
+        let res = query_pair_info(
+            &querier,
+            Addr::unchecked("factory"),
+            &[
+                native_asset_info("uluna".to_string()),
+                native_asset_info("ukrw".to_string()),
+            ],
+        );
+        assert!(res.is_err());
+
+        let config = FactoryConfigResponse {
+            owner: Addr::unchecked("owner"),
+            pair_configs: vec![],
+            token_code_id: 1,
+            fee_address: None,
+            generator_address: Some(Addr::unchecked("generator")),
+            whitelist_code_id: 2,
+        };
+        deps.querier.set_factory_config("factory", &config)?;
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+        let res: FactoryConfigResponse =
+            querier.query_wasm_smart("factory", &FactoryQueryMsg::Config {})?;
+        assert_eq!(res, config);
+
+        Ok(())
+    }
+
+    #[test]
+    fn generator_queries() -> StdResult<()> {
+        let mut deps = mock_dependencies(&[coin(100, "uluna")]);
+        let lp_token = Addr::unchecked("lp_token");
+        let farm = Addr::unchecked("farm");
+        deps.querier.set_generator_user_info(
+            "generator",
+            &lp_token,
+            &farm,
+            &UserInfoV2 {
+                amount: Uint128::new(1000),
+                reward_user_index: Decimal::zero(),
+                reward_debt_proxy: Default::default(),
+                virtual_amount: Uint128::new(1000),
+            },
+        )?;
+        deps.querier.set_generator_proxy_reward_asset(
+            "generator",
+            &Addr::unchecked("reward_proxy"),
+            &native_asset_info("ureward".to_string()),
+        )?;
+        deps.querier.set_generator_pending_token(
+            "generator",
+            "lp_token",
+            "farm",
+            &PendingTokenResponse {
+                pending: Uint128::new(10),
+                pending_on_proxy: None,
+            },
+        )?;
+        deps.querier
+            .set_token_balance("lp_token", "generator", Uint128::new(1000));
+        deps.querier
+            .set_token_balance("lp_token", "user", Uint128::new(500));
+
+        // the deposit is read from the raw user info
+        let querier = QuerierWrapper::<Empty>::new(&deps.querier);
+        let generator = Generator(Addr::unchecked("generator"));
+        assert_eq!(
+            generator.query_deposit(&querier, &lp_token, &farm)?,
+            Uint128::new(1000)
+        );
+        assert_eq!(
+            generator.query_deposit(&querier, &lp_token, &Addr::unchecked("user"))?,
+            Uint128::zero()
+        );
+        assert_eq!(
+            generator
+                .query_pending_token(&querier, &lp_token, &farm)?
+                .pending,
+            Uint128::new(10)
+        );
+
+        let key = GENERATOR_PROXY_REWARD_ASSET.key(&Addr::unchecked("reward_proxy"));
+        let res: Option<AssetInfo> = querier
+            .query_wasm_raw("generator", key.deref())?
+            .map(|value| from_slice(&value))
+            .transpose()?;
+        assert_eq!(res, Some(native_asset_info("ureward".to_string())));
+
+        // other contracts are cw20 tokens
+        assert_eq!(
+            query_token_balance(
+                &querier,
+                Addr::unchecked("lp_token"),
+                Addr::unchecked("user")
+            )?,
+            Uint128::new(500)
+        );
+        assert_eq!(
+            query_supply(&querier, Addr::unchecked("lp_token"))?,
+            Uint128::new(1500)
+        );
+        assert_eq!(
+            querier.query_balance(MOCK_CONTRACT_ADDR, "uluna")?,
+            coin(100, "uluna")
+        );
+
+        Ok(())
+    }
+}
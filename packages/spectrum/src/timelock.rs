@@ -0,0 +1,110 @@
+use cosmwasm_std::Binary;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to queue, execute and cancel operations
+    pub admin: String,
+    /// The min number of seconds between queueing and executing an operation
+    pub min_delay: u64,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+///
+/// To put the admin actions of a contract behind the timelock, the owner of the contract proposes
+/// the timelock as the new owner, then the timelock claims the ownership through an operation.
+/// The ownership proposal must not expire before the operation is executable.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Updates contract config, only the contract itself can execute this through an operation
+    UpdateConfig {
+        /// The admin address
+        admin: Option<String>,
+        /// The min number of seconds between queueing and executing an operation
+        min_delay: Option<u64>,
+    },
+    /// Queues an operation executable from the eta. Only the admin can execute this
+    Queue {
+        /// The messages executed by the contract
+        msgs: Vec<TimelockMsg>,
+        /// The block time in seconds the operation is executable from, at least the min delay
+        /// from now
+        eta: u64,
+    },
+    /// Executes a queued operation after its eta. Only the admin can execute this
+    Execute {
+        /// The operation id
+        id: u64,
+    },
+    /// Cancels a queued operation. Only the admin can execute this
+    Cancel {
+        /// The operation id
+        id: u64,
+    },
+}
+
+/// This structure describes a message executed by an operation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockMsg {
+    /// The contract to execute
+    pub contract: String,
+    /// The message to execute
+    pub msg: Binary,
+}
+
+/// This enum describes the status of an operation.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the operation
+    Operation {
+        /// The operation id
+        id: u64,
+    },
+    /// Returns the operations ordered by id
+    Operations {
+        /// The operation id to start after
+        start_after: Option<u64>,
+        /// The max number of operations to return
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the parameters that are returned from the operation query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperationResponse {
+    /// The operation id
+    pub id: u64,
+    /// The messages executed by the contract
+    pub msgs: Vec<TimelockMsg>,
+    /// The block time in seconds the operation is executable from
+    pub eta: u64,
+    /// The operation status
+    pub status: OperationStatus,
+}
+
+/// This structure holds the parameters that are returned from the operations query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperationsResponse {
+    /// The operations
+    pub operations: Vec<OperationResponse>,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
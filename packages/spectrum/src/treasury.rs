@@ -0,0 +1,115 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::Uint128;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to spend and update config, normally the gov contract
+    pub owner: String,
+    /// The number of seconds of each period the spend limits apply to
+    pub spend_period: u64,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Updates contract config
+    UpdateConfig {
+        /// The number of seconds of each period the spend limits apply to
+        spend_period: Option<u64>,
+    },
+    /// Sets the max amount of the asset spent per period, the asset cannot be spent if no limit
+    /// is set. Only the owner can execute this
+    SetSpendLimit {
+        /// Information about the asset
+        asset_info: AssetInfo,
+        /// The max amount spent per period, removes the limit if not set
+        limit: Option<Uint128>,
+    },
+    /// Sends the asset to the recipient within the spend limit of the asset. Only the owner can
+    /// execute this
+    Spend {
+        /// The asset to send
+        asset: Asset,
+        /// The recipient address
+        recipient: String,
+        /// The reason of the spend, recorded in the history
+        memo: Option<String>,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the spend limits of all assets
+    SpendLimits {},
+    /// Returns the spends ordered by id
+    History {
+        /// The spend id to start after
+        start_after: Option<u64>,
+        /// The max number of spends to return
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the spend limit of an asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendLimitResponse {
+    /// Information about the asset
+    pub asset_info: AssetInfo,
+    /// The max amount spent per period
+    pub limit: Uint128,
+    /// The amount still spendable in the current period
+    pub remaining: Uint128,
+}
+
+/// This structure holds the parameters that are returned from the spend limits query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendLimitsResponse {
+    /// The spend limits
+    pub spend_limits: Vec<SpendLimitResponse>,
+}
+
+/// This structure holds a spend of the treasury
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendResponse {
+    /// The spend id
+    pub id: u64,
+    /// The asset sent
+    pub asset: Asset,
+    /// The recipient address
+    pub recipient: String,
+    /// The reason of the spend
+    pub memo: Option<String>,
+    /// The block time in seconds of the spend
+    pub time: u64,
+}
+
+/// This structure holds the parameters that are returned from the history query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HistoryResponse {
+    /// The spends
+    pub spends: Vec<SpendResponse>,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -0,0 +1,35 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Addr, CustomQuery, Decimal, QuerierWrapper, StdResult, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the query shared by the vault and farm contracts, so an integration
+/// reads the exchange rate of every vault the same way.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the underlying asset per share and the total managed assets
+    ExchangeRate {},
+}
+
+/// This structure holds the parameters that are returned from the exchange rate query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExchangeRateResponse {
+    /// The underlying asset managed by the vault
+    pub asset: AssetInfo,
+    /// The underlying asset amount per share
+    pub exchange_rate: Decimal,
+    /// The total shares issued by the vault
+    pub total_shares: Uint128,
+    /// The total underlying asset managed by the vault
+    pub total_assets: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vault(pub Addr);
+
+impl Vault {
+    pub fn query_exchange_rate<C: CustomQuery>(&self, querier: &QuerierWrapper<C>) -> StdResult<ExchangeRateResponse> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::ExchangeRate {})
+    }
+}
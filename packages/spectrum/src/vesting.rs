@@ -0,0 +1,106 @@
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure stores general parameters for the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Address that's allowed to register and revoke vestings
+    pub owner: String,
+    /// The SPEC token contract address
+    pub spec_token: String,
+}
+
+/// This structure describes the functions that can be executed in this contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Receives a message of type [`Cw20ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Claims the vested SPEC of the sender
+    Claim {},
+    /// Stops the vesting of the recipient and returns the unvested SPEC to the owner. The SPEC
+    /// vested until now stays claimable. Only the owner can execute this
+    Revoke {
+        /// The recipient address
+        recipient: String,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the custom hooks for the CW20.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Vests the sent SPEC to the recipient, only the owner can send this
+    RegisterVesting {
+        /// The recipient address
+        recipient: String,
+        /// The vesting schedule
+        schedule: VestingSchedule,
+    },
+}
+
+/// This structure describes a vesting schedule. The SPEC vests linearly from the start time to
+/// the end time, nothing vests before the cliff time. A cliff only schedule has the same cliff
+/// time and end time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingSchedule {
+    /// The block time in seconds the vesting starts
+    pub start_time: u64,
+    /// The block time in seconds the SPEC vested since the start time is released
+    pub cliff_time: u64,
+    /// The block time in seconds all SPEC is vested
+    pub end_time: u64,
+}
+
+/// This structure describes the query functions available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the vesting of the recipient
+    Vesting {
+        /// The recipient address
+        recipient: String,
+        /// The block time in seconds to calculate the vested SPEC at, the current block time if
+        /// not set
+        time: Option<u64>,
+    },
+}
+
+/// This structure holds the parameters that are returned from the vesting query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingResponse {
+    /// The recipient address
+    pub recipient: String,
+    /// The SPEC registered to vest
+    pub amount: Uint128,
+    /// The vesting schedule
+    pub schedule: VestingSchedule,
+    /// The block time in seconds the vesting was revoked
+    pub revoked_time: Option<u64>,
+    /// The SPEC already claimed
+    pub claimed: Uint128,
+    /// The SPEC vested at the time
+    pub vested: Uint128,
+    /// The SPEC vested and not claimed yet at the time
+    pub claimable: Uint128,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}